@@ -20,6 +20,16 @@ pub struct Command {
     /// Whether to compile to bytecode (false) or to print out the IR (true).
     #[structopt(long)]
     pub print_ir: bool,
+    /// Whether to print how many bytes the peephole optimizations saved.
+    #[structopt(long)]
+    pub print_peephole_savings: bool,
+    /// Disables the peephole optimizations applied to the generated ASM.
+    #[structopt(long)]
+    pub disable_asm_optimizations: bool,
+    /// The IR optimization level: 0 disables the IR optimization passes, 1 runs each of them once
+    /// and 2 runs them until they make no further changes.  Only used by the IR pipeline.
+    #[structopt(short = "O", default_value = "1", possible_values = &["0", "1", "2"])]
+    pub opt_level: u8,
     /// If set, outputs a binary file representing the script bytes.
     #[structopt(short = "o")]
     pub binary_outfile: Option<String>,
@@ -10,6 +10,16 @@ pub struct Command {
     /// If set, outputs a json file representing the output json abi.
     #[structopt(short = "o")]
     pub json_outfile: Option<String>,
+    /// If set, outputs a json file listing the revert codes each entry point of the program may
+    /// revert with: the ABI methods of a contract or the `main` function of a script or predicate.
+    #[structopt(long = "revert-codes")]
+    pub revert_codes_outfile: Option<String>,
+    /// If set, outputs a json file listing the types of the values the program may log, along
+    /// with the log id which each log receipt carries to identify the type of its value. These
+    /// aren't a section of the json abi itself, which is an array of functions that existing
+    /// tools expect, as adding one would make it an object.
+    #[structopt(long = "logged-types")]
+    pub logged_types_outfile: Option<String>,
     /// Offline mode, prevents Forc from using the network when managing dependencies.
     /// Meaning it will only try to use previously downloaded dependencies.
     #[structopt(long = "offline")]
@@ -8,7 +8,7 @@ use crate::{
     },
 };
 
-use sway_types::{Function, JsonABI};
+use sway_types::{Function, JsonABI, LoggedTypes, RevertManifest};
 use sway_utils::find_manifest_dir;
 
 use anyhow::Result;
@@ -19,7 +19,7 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use sway_core::{
     create_module, BuildConfig, CompileAstResult, NamespaceRef, NamespaceWrapper, TreeType,
-    TypedParseTree,
+    TypedFunctionDeclaration, TypedParseTree,
 };
 
 pub fn build(command: JsonAbiCommand) -> Result<Value, String> {
@@ -32,6 +32,8 @@ pub fn build(command: JsonAbiCommand) -> Result<Value, String> {
 
     let JsonAbiCommand {
         json_outfile,
+        revert_codes_outfile,
+        logged_types_outfile,
         offline_mode,
         silent_mode,
         ..
@@ -109,7 +111,7 @@ pub fn build(command: JsonAbiCommand) -> Result<Value, String> {
     // now, compile this program with all of its dependencies
     let main_file = get_main_file(&manifest, &manifest_dir)?;
 
-    let mut res = compile(
+    let (mut res, revert_manifest, logged_types) = compile(
         main_file,
         &manifest.project.name,
         namespace,
@@ -128,6 +130,16 @@ pub fn build(command: JsonAbiCommand) -> Result<Value, String> {
         println!("{}", output_json);
     }
 
+    if let Some(outfile) = revert_codes_outfile {
+        let file = File::create(outfile).map_err(|e| e.to_string())?;
+        serde_json::to_writer(&file, &json!(revert_manifest)).map_err(|e| e.to_string())?;
+    }
+
+    if let Some(outfile) = logged_types_outfile {
+        let file = File::create(outfile).map_err(|e| e.to_string())?;
+        serde_json::to_writer(&file, &json!(logged_types)).map_err(|e| e.to_string())?;
+    }
+
     Ok(output_json)
 }
 
@@ -294,7 +306,7 @@ fn compile(
     build_config: BuildConfig,
     dependency_graph: &mut HashMap<String, HashSet<String>>,
     silent_mode: bool,
-) -> Result<Vec<Function>, String> {
+) -> Result<(Vec<Function>, RevertManifest, LoggedTypes), String> {
     let res = sway_core::compile_to_ast(source, namespace, &build_config, dependency_graph);
     match res {
         CompileAstResult::Success {
@@ -310,8 +322,11 @@ fn compile(
                 }
                 typ => {
                     print_on_success(silent_mode, proj_name, warnings, typ);
-                    let json_abi = generate_json_abi(&Some(*parse_tree));
-                    Ok(json_abi)
+                    let parse_tree = Some(*parse_tree);
+                    let json_abi = generate_json_abi(&parse_tree);
+                    let revert_manifest = generate_revert_manifest(&parse_tree);
+                    let logged_types = generate_logged_types(&parse_tree);
+                    Ok((json_abi, revert_manifest, logged_types))
                 }
             }
         }
@@ -330,3 +345,36 @@ fn generate_json_abi(ast: &Option<TypedParseTree>) -> JsonABI {
         _ => vec![],
     }
 }
+
+/// The functions through which the program is entered: the ABI methods of a contract or the
+/// `main` function of a script or predicate.
+fn entry_points(ast: &Option<TypedParseTree>) -> Vec<&TypedFunctionDeclaration> {
+    match ast {
+        Some(TypedParseTree::Contract { abi_entries, .. }) => abi_entries.iter().collect(),
+        Some(TypedParseTree::Script { main_function, .. })
+        | Some(TypedParseTree::Predicate { main_function, .. }) => vec![main_function],
+        _ => vec![],
+    }
+}
+
+/// The types logged by every entry point of the program, each listed once.
+fn generate_logged_types(ast: &Option<TypedParseTree>) -> LoggedTypes {
+    let mut logged_types: LoggedTypes = vec![];
+    for logged_type in entry_points(ast)
+        .into_iter()
+        .flat_map(|x| x.generate_logged_types())
+    {
+        if !logged_types.iter().any(|x| x.log_id == logged_type.log_id) {
+            logged_types.push(logged_type);
+        }
+    }
+    logged_types
+}
+
+/// The revert codes of every entry point of the program.
+fn generate_revert_manifest(ast: &Option<TypedParseTree>) -> RevertManifest {
+    entry_points(ast)
+        .into_iter()
+        .map(|x| x.generate_revert_codes())
+        .collect()
+}
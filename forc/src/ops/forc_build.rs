@@ -37,6 +37,9 @@ pub fn build(command: BuildCommand) -> Result<Vec<u8>, String> {
         print_finalized_asm,
         print_intermediate_asm,
         print_ir,
+        print_peephole_savings,
+        disable_asm_optimizations,
+        opt_level,
         offline_mode,
         silent_mode,
         ..
@@ -73,7 +76,10 @@ pub fn build(command: BuildCommand) -> Result<Vec<u8>, String> {
     .use_ir(use_ir || print_ir) // --print-ir implies --use-ir.
     .print_finalized_asm(print_finalized_asm)
     .print_intermediate_asm(print_intermediate_asm)
-    .print_ir(print_ir);
+    .print_ir(print_ir)
+    .print_peephole_savings(print_peephole_savings)
+    .optimize_asm(!disable_asm_optimizations)
+    .ir_opt_level(opt_level);
 
     let mut dependency_graph = HashMap::new();
     let namespace = create_module();
@@ -25,6 +25,9 @@ pub async fn deploy(command: DeployCommand) -> Result<fuel_tx::ContractId, CliEr
         print_finalized_asm,
         print_intermediate_asm,
         print_ir,
+        print_peephole_savings,
+        disable_asm_optimizations,
+        opt_level,
         binary_outfile,
         debug_outfile,
         offline_mode,
@@ -48,6 +51,9 @@ pub async fn deploy(command: DeployCommand) -> Result<fuel_tx::ContractId, CliEr
                             print_finalized_asm,
                             print_intermediate_asm,
                             print_ir,
+                            print_peephole_savings,
+                            disable_asm_optimizations,
+                            opt_level,
                             binary_outfile,
                             offline_mode,
                             debug_outfile,
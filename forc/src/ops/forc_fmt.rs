@@ -15,6 +15,9 @@ pub fn format(command: FormatCommand) -> Result<(), FormatError> {
         print_finalized_asm: false,
         print_intermediate_asm: false,
         print_ir: false,
+        print_peephole_savings: false,
+        disable_asm_optimizations: false,
+        opt_level: 1,
         binary_outfile: None,
         debug_outfile: None,
         offline_mode: false,
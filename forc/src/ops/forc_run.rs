@@ -44,6 +44,9 @@ pub async fn run(command: RunCommand) -> Result<(), CliError> {
                             print_finalized_asm: command.print_finalized_asm,
                             print_intermediate_asm: command.print_intermediate_asm,
                             print_ir: command.print_ir,
+                            print_peephole_savings: command.print_peephole_savings,
+                            disable_asm_optimizations: command.disable_asm_optimizations,
+                            opt_level: command.opt_level,
                             binary_outfile: command.binary_outfile,
                             debug_outfile: command.debug_outfile,
                             offline_mode: false,
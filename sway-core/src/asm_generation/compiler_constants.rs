@@ -1,6 +1,8 @@
 /// The number of registers available for the compiler to use. Registers reserved by the
 /// compiler are contained within these.
 const NUM_FREE_REGISTERS: u8 = 48;
+/// The first register available to the compiler. Those below it are reserved by the VM.
+const FIRST_FREE_REGISTER: u8 = 16;
 pub(crate) const TWENTY_FOUR_BITS: u64 = 0b1111_1111_1111_1111_1111_1111;
 pub(crate) const EIGHTEEN_BITS: u64 = 0b11_1111_1111_1111_1111;
 pub(crate) const TWELVE_BITS: u64 = 0b1111_1111_1111;
@@ -10,7 +12,15 @@ pub(crate) const SIX_BITS: u64 = 0b11_1111;
 /// reservation must be made.
 /// So far, the compiler-reserved registers are:
 /// 1. DATA_SECTION_BEGIN
-const NUM_COMPILER_RESERVED_REGISTERS: u8 = 1;
-pub(crate) const DATA_SECTION_REGISTER: u8 = NUM_FREE_REGISTERS - 2;
+/// 2. CALL_FRAME_POINTER
+const NUM_COMPILER_RESERVED_REGISTERS: u8 = 2;
+pub(crate) const DATA_SECTION_REGISTER: u8 = FIRST_FREE_REGISTER + NUM_FREE_REGISTERS - 2;
+/// Points to the most recent frame of registers saved by a caller across a function call.  This
+/// is the very last register, which is above those handed out by the allocator.
+pub(crate) const CALL_FRAME_REGISTER: u8 = FIRST_FREE_REGISTER + NUM_FREE_REGISTERS - 1;
 pub(crate) const NUM_ALLOCATABLE_REGISTERS: u8 =
     NUM_FREE_REGISTERS - NUM_COMPILER_RESERVED_REGISTERS;
+/// Points to the stack memory holding the spilled registers of the function being executed.  This
+/// is not reserved up front; the register allocator only gives up the last allocatable register
+/// for it when a program cannot be coloured without spilling.
+pub(crate) const SPILL_REGISTER: u8 = FIRST_FREE_REGISTER + NUM_ALLOCATABLE_REGISTERS - 1;
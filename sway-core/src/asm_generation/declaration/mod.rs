@@ -1,5 +1,11 @@
 use super::{AsmNamespace, RegisterSequencer};
-use crate::{asm_lang::Op, error::*, TypedDeclaration};
+use crate::{
+    asm_lang::{virtual_register::VirtualRegister, Op, VirtualImmediate12, VirtualOp},
+    error::*,
+    type_engine::{look_up_type_id, TypeId, TypeInfo},
+    TypedDeclaration,
+};
+use either::Either;
 
 mod const_decl;
 mod fn_decl;
@@ -8,7 +14,7 @@ mod var_decl;
 
 pub(crate) use const_decl::convert_constant_decl_to_asm;
 pub(crate) use fn_decl::convert_fn_decl_to_asm;
-pub(crate) use reassignment::convert_reassignment_to_asm;
+pub(crate) use reassignment::{convert_reassignment_to_asm, convert_storage_reassignment_to_asm};
 pub(crate) use var_decl::convert_variable_decl_to_asm;
 
 pub(crate) fn convert_decl_to_asm(
@@ -29,6 +35,7 @@ pub(crate) fn convert_decl_to_asm(
         TypedDeclaration::ImplTrait { .. } => ok(vec![], vec![], vec![]),
         // once again the declaration of a type has no inherent asm, only instantiations
         TypedDeclaration::StructDeclaration(_) => ok(vec![], vec![], vec![]),
+        TypedDeclaration::TypeAliasDeclaration(_) => ok(vec![], vec![], vec![]),
         TypedDeclaration::VariableDeclaration(var_decl) => {
             convert_variable_decl_to_asm(var_decl, namespace, register_sequencer)
         }
@@ -38,6 +45,12 @@ pub(crate) fn convert_decl_to_asm(
         TypedDeclaration::Reassignment(reassignment) => {
             convert_reassignment_to_asm(reassignment, namespace, register_sequencer)
         }
+        TypedDeclaration::StorageReassignment(reassignment) => {
+            convert_storage_reassignment_to_asm(reassignment, namespace, register_sequencer)
+        }
+        // Storage slots are assigned when the contract is compiled, accesses refer to them
+        // directly.
+        TypedDeclaration::StorageDeclaration(_) => ok(vec![], vec![], vec![]),
         _ => err(
             vec![],
             vec![CompileError::Unimplemented(
@@ -47,3 +60,27 @@ pub(crate) fn convert_decl_to_asm(
         ),
     }
 }
+
+/// The size in words of a value of `type_id` if it is a wide integer.
+///
+/// A wide integer variable owns the memory its value is copied into, rather than pointing to the
+/// memory of the value it was given.  That memory may be reused for the result of an operation
+/// which runs again while the variable is live, e.g. in the next iteration of a loop.
+fn wide_int_size_in_words(type_id: TypeId) -> Option<u64> {
+    match look_up_type_id(type_id) {
+        TypeInfo::UnsignedInteger(bits) if bits.size_in_words() > 1 => Some(bits.size_in_words()),
+        _ => None,
+    }
+}
+
+fn copy_wide_int(dst: VirtualRegister, src: VirtualRegister, nwords: u64) -> Op {
+    Op {
+        opcode: Either::Left(VirtualOp::MCPI(
+            dst,
+            src,
+            VirtualImmediate12::new_unchecked(nwords * 8, "wide integers are at most 256 bits"),
+        )),
+        comment: "copy wide integer".into(),
+        owning_span: None,
+    }
+}
@@ -1,12 +1,15 @@
 use super::*;
 use crate::{
     asm_generation::{
-        convert_expression_to_asm, expression::get_contiguous_memory_layout, AsmNamespace,
-        RegisterSequencer,
+        convert_expression_to_asm,
+        expression::{convert_storage_write_to_asm, get_contiguous_memory_layout},
+        AsmNamespace, RegisterSequencer,
     },
     asm_lang::{VirtualImmediate12, VirtualOp},
     constants::VM_WORD_SIZE,
-    semantic_analysis::ast_node::{OwnedTypedStructField, ReassignmentLhs, TypedReassignment},
+    semantic_analysis::ast_node::{
+        OwnedTypedStructField, ReassignmentLhs, TypedReassignment, TypedStorageReassignment,
+    },
     type_engine::*,
     type_engine::{resolve_type, TypeInfo},
 };
@@ -52,25 +55,30 @@ pub(crate) fn convert_reassignment_to_asm(
             );
 
             // step 2
-            buf.push(Op::register_move_comment(
-                var_register.clone(),
-                return_register,
-                reassignment
-                    .lhs
-                    .iter()
-                    .fold(reassignment.lhs[0].span(), |acc, this| {
-                        sway_types::span::join_spans(acc, this.span())
-                    }),
-                format!(
-                    "variable {} reassignment",
+            // a wide integer is copied into the memory owned by the variable instead
+            if let Some(nwords) = wide_int_size_in_words(reassignment.rhs.return_type) {
+                buf.push(copy_wide_int(var_register.clone(), return_register, nwords));
+            } else {
+                buf.push(Op::register_move_comment(
+                    var_register.clone(),
+                    return_register,
                     reassignment
                         .lhs
                         .iter()
-                        .map(|x| x.name.as_str())
-                        .collect::<Vec<_>>()
-                        .join(".")
-                ),
-            ));
+                        .fold(reassignment.lhs[0].span(), |acc, this| {
+                            sway_types::span::join_spans(acc, this.span())
+                        }),
+                    format!(
+                        "variable {} reassignment",
+                        reassignment
+                            .lhs
+                            .iter()
+                            .map(|x| x.name.as_str())
+                            .collect::<Vec<_>>()
+                            .join(".")
+                    ),
+                ));
+            }
         }
         _ => {
             // 0. get the field layout
@@ -234,3 +242,39 @@ pub(crate) fn convert_reassignment_to_asm(
 
     ok(buf, warnings, errors)
 }
+
+pub(crate) fn convert_storage_reassignment_to_asm(
+    reassignment: &TypedStorageReassignment,
+    namespace: &mut AsmNamespace,
+    register_sequencer: &mut RegisterSequencer,
+) -> CompileResult<Vec<Op>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let return_register = register_sequencer.next();
+    let mut buf = check!(
+        convert_expression_to_asm(
+            &reassignment.rhs,
+            namespace,
+            &return_register,
+            register_sequencer
+        ),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    let mut write = check!(
+        convert_storage_write_to_asm(
+            reassignment.ix,
+            reassignment.rhs.return_type,
+            &return_register,
+            &reassignment.span(),
+            namespace,
+            register_sequencer
+        ),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    buf.append(&mut write);
+    ok(buf, warnings, errors)
+}
@@ -1,8 +1,9 @@
+use super::{copy_wide_int, wide_int_size_in_words};
 use crate::{
     asm_generation::{convert_expression_to_asm, AsmNamespace, RegisterSequencer},
     asm_lang::Op,
     error::*,
-    semantic_analysis::ast_node::TypedVariableDeclaration,
+    semantic_analysis::ast_node::{TypedExpressionVariant, TypedVariableDeclaration},
 };
 
 /// Provisions a register to put a variable in, and then adds the assembly used to initialize the
@@ -12,9 +13,45 @@ pub(crate) fn convert_variable_decl_to_asm(
     namespace: &mut AsmNamespace,
     register_sequencer: &mut RegisterSequencer,
 ) -> CompileResult<Vec<Op>> {
+    // a variable bound to a function value is only another name for it
+    if let TypedExpressionVariant::FunctionValue(_) = var_decl.body.expression {
+        return ok(vec![], vec![], vec![]);
+    }
     let var_register = register_sequencer.next();
-    let initialization =
-        convert_expression_to_asm(&var_decl.body, namespace, &var_register, register_sequencer);
+    let initialization = match wide_int_size_in_words(var_decl.body.return_type) {
+        Some(nwords) => {
+            let mut warnings = vec![];
+            let mut errors = vec![];
+            let value_register = register_sequencer.next();
+            let mut buf = check!(
+                convert_expression_to_asm(
+                    &var_decl.body,
+                    namespace,
+                    &value_register,
+                    register_sequencer
+                ),
+                return err(warnings, errors),
+                warnings,
+                errors
+            );
+            buf.push(check!(
+                namespace.reserve_wide_int_memory(
+                    &var_register,
+                    nwords,
+                    register_sequencer,
+                    var_decl.name.span().clone()
+                ),
+                return err(warnings, errors),
+                warnings,
+                errors
+            ));
+            buf.push(copy_wide_int(var_register.clone(), value_register, nwords));
+            ok(buf, warnings, errors)
+        }
+        None => {
+            convert_expression_to_asm(&var_decl.body, namespace, &var_register, register_sequencer)
+        }
+    };
     namespace.insert_variable(var_decl.name.clone(), var_register);
     initialization
 }
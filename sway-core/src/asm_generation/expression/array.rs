@@ -279,9 +279,40 @@ pub(super) fn convert_array_index_to_asm(
         register_sequencer,
     );
 
+    bytecode.append(&mut check!(
+        convert_array_element_to_asm(
+            &prefix_reg,
+            &index_reg,
+            &elem_type,
+            span,
+            return_register,
+            register_sequencer,
+        ),
+        return err(warnings, errors),
+        warnings,
+        errors
+    ));
+
+    ok(bytecode, warnings, errors)
+}
+
+/// Puts the element at `index_reg` of the array which `prefix_reg` refers to into
+/// `return_register`, without checking whether the index is within bounds.  Elements which fit
+/// in a register are loaded and for others a pointer to the element is returned.
+pub(crate) fn convert_array_element_to_asm(
+    prefix_reg: &VirtualRegister,
+    index_reg: &VirtualRegister,
+    elem_type: &TypeInfo,
+    span: &Span,
+    return_register: &VirtualRegister,
+    register_sequencer: &mut RegisterSequencer,
+) -> CompileResult<Vec<Op>> {
+    let warnings = Vec::new();
+    let mut errors = Vec::new();
+    let mut bytecode = Vec::new();
+
     // Get the element size in words first.
-    let elem_size_in_words =
-        check_std_result!(elem_type.size_in_words(&prefix.span), warnings, errors);
+    let elem_size_in_words = check_std_result!(elem_type.size_in_words(span), warnings, errors);
 
     // The element offset can be calculated as a byte offset.  We need to multiply the index by the
     // element size.
@@ -301,7 +332,7 @@ pub(super) fn convert_array_index_to_asm(
     bytecode.push(Op {
         opcode: either::Either::Left(VirtualOp::ADD(
             elem_offs_reg.clone(),
-            prefix_reg,
+            prefix_reg.clone(),
             elem_offs_reg.clone(),
         )),
         owning_span: Some(span.clone()),
@@ -344,7 +375,7 @@ pub(super) fn convert_array_index_to_asm(
 // We want the first (and usually probably the only) operation to OR with Zero, so we recurse for
 // each set of 12 bits until we hit a zero value, and then return the Zero register to be used
 // next.  Thereafter we OR the destination register.
-fn set_large_register_value<'a>(
+pub(crate) fn set_large_register_value<'a>(
     value: u64,
     dst_reg: &'a VirtualRegister,
     bytecode: &mut Vec<Op>,
@@ -0,0 +1,132 @@
+use crate::{
+    asm_generation::{convert_expression_to_asm, int_ops, AsmNamespace, RegisterSequencer},
+    asm_lang::{Op, VirtualRegister},
+    error::*,
+    parse_tree::OpVariant,
+    semantic_analysis::TypedExpression,
+    type_engine::{look_up_type_id, TypeInfo},
+    CompileResult,
+};
+
+use sway_ir::{BinaryOpKind, Predicate};
+use sway_types::span::Span;
+
+pub(crate) fn convert_builtin_operator_to_asm(
+    op: &OpVariant,
+    lhs: &TypedExpression,
+    rhs: &TypedExpression,
+    return_register: &VirtualRegister,
+    namespace: &mut AsmNamespace,
+    register_sequencer: &mut RegisterSequencer,
+    span: Span,
+) -> CompileResult<Vec<Op>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let mut asm_ops = vec![];
+
+    // Both operands are evaluated, into their own registers.
+    let lhs_register = register_sequencer.next();
+    let mut lhs_asm_ops = check!(
+        convert_expression_to_asm(lhs, namespace, &lhs_register, register_sequencer),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    asm_ops.append(&mut lhs_asm_ops);
+    let rhs_register = register_sequencer.next();
+    let mut rhs_asm_ops = check!(
+        convert_expression_to_asm(rhs, namespace, &rhs_register, register_sequencer),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    asm_ops.append(&mut rhs_asm_ops);
+
+    let (nwords, is_signed) = match look_up_type_id(lhs.return_type) {
+        TypeInfo::UnsignedInteger(bits) => (bits.size_in_words(), false),
+        TypeInfo::SignedInteger(_) => (1, true),
+        _ => (1, false),
+    };
+    let binary_op_kind = match (op, is_signed) {
+        (OpVariant::Add, _) => Some(BinaryOpKind::Add),
+        (OpVariant::Subtract, _) => Some(BinaryOpKind::Sub),
+        (OpVariant::Multiply, _) => Some(BinaryOpKind::Mul),
+        (OpVariant::Divide, false) => Some(BinaryOpKind::Div),
+        (OpVariant::Divide, true) => Some(BinaryOpKind::SDiv),
+        (OpVariant::Modulo, false) => Some(BinaryOpKind::Mod),
+        (OpVariant::Modulo, true) => Some(BinaryOpKind::SMod),
+        (OpVariant::BinaryAnd, _) => Some(BinaryOpKind::And),
+        (OpVariant::BinaryOr, _) => Some(BinaryOpKind::Or),
+        (OpVariant::Xor, _) => Some(BinaryOpKind::Xor),
+        (OpVariant::ShiftLeft, _) => Some(BinaryOpKind::Shl),
+        (OpVariant::ShiftRight, false) => Some(BinaryOpKind::Shr),
+        (OpVariant::ShiftRight, true) => Some(BinaryOpKind::Sar),
+        _ => None,
+    };
+    let (mut op_asm_ops, result_register) = if let Some(kind) = binary_op_kind {
+        // a wide result goes in memory reserved for this op, which is reused each time it runs
+        let result_size_in_words = int_ops::result_size_in_words(kind, nwords);
+        let result_ptr = if result_size_in_words > 0 {
+            let ptr = register_sequencer.next();
+            asm_ops.push(check!(
+                namespace.reserve_wide_int_memory(
+                    &ptr,
+                    result_size_in_words,
+                    register_sequencer,
+                    span.clone()
+                ),
+                return err(warnings, errors),
+                warnings,
+                errors
+            ));
+            Some(ptr)
+        } else {
+            None
+        };
+        int_ops::compile_binary_op(
+            register_sequencer,
+            kind,
+            nwords,
+            lhs_register,
+            rhs_register,
+            result_ptr,
+            Some(span),
+        )
+    } else {
+        let pred = match (op, is_signed) {
+            (OpVariant::Equals, _) => Predicate::Equal,
+            (OpVariant::NotEquals, _) => Predicate::NotEqual,
+            (OpVariant::LessThan, false) => Predicate::LessThan,
+            (OpVariant::LessThan, true) => Predicate::SignedLessThan,
+            (OpVariant::GreaterThan, false) => Predicate::GreaterThan,
+            (OpVariant::GreaterThan, true) => Predicate::SignedGreaterThan,
+            (OpVariant::LessThanOrEqualTo, false) => Predicate::LessThanEqual,
+            (OpVariant::LessThanOrEqualTo, true) => Predicate::SignedLessThanEqual,
+            (OpVariant::GreaterThanOrEqualTo, false) => Predicate::GreaterThanEqual,
+            (OpVariant::GreaterThanOrEqualTo, true) => Predicate::SignedGreaterThanEqual,
+            _ => {
+                errors.push(CompileError::Internal(
+                    "Lazy operators are never builtin operators.",
+                    span,
+                ));
+                return err(warnings, errors);
+            }
+        };
+        int_ops::compile_cmp(
+            register_sequencer,
+            pred,
+            nwords,
+            lhs_register,
+            rhs_register,
+            Some(span),
+        )
+    };
+    asm_ops.append(&mut op_asm_ops);
+    asm_ops.push(Op::unowned_register_move_comment(
+        return_register.clone(),
+        result_register,
+        "builtin operator result",
+    ));
+
+    ok(asm_ops, warnings, errors)
+}
@@ -6,24 +6,32 @@ use crate::{
         ast_node::{TypedAsmRegisterDeclaration, TypedCodeBlock, TypedExpressionVariant},
         TypedExpression,
     },
-    type_engine::look_up_type_id,
+    type_engine::{look_up_type_id, resolve_type, ToJsonAbi, TypeInfo},
 };
 use sway_types::span::Span;
 
 mod array;
+mod builtin_op;
 mod contract_call;
 mod enums;
 mod if_exp;
 mod lazy_op;
+mod storage;
 mod structs;
 mod subfield;
+mod vec;
+pub(crate) use array::{convert_array_element_to_asm, set_large_register_value};
+use builtin_op::convert_builtin_operator_to_asm;
 use contract_call::convert_contract_call_to_asm;
 use enums::convert_enum_instantiation_to_asm;
 use if_exp::convert_if_exp_to_asm;
+use storage::convert_storage_access_to_asm;
+pub(crate) use storage::{convert_storage_initializers_to_asm, convert_storage_write_to_asm};
 pub(crate) use structs::{
     convert_struct_expression_to_asm, convert_tuple_expression_to_asm, get_contiguous_memory_layout,
 };
 use subfield::convert_subfield_expression_to_asm;
+use vec::convert_vec_op_to_asm;
 
 /// Given a [TypedExpression], convert it to assembly and put its return value, if any, in the
 /// `return_register`.
@@ -38,7 +46,7 @@ pub(crate) fn convert_expression_to_asm(
     match &exp.expression {
         TypedExpressionVariant::Literal(ref lit) => ok(
             convert_literal_to_asm(
-                lit,
+                &lit.widen_to(&look_up_type_id(exp.return_type)),
                 namespace,
                 return_register,
                 register_sequencer,
@@ -52,6 +60,8 @@ pub(crate) fn convert_expression_to_asm(
             arguments,
             function_body,
             selector,
+            reference_parameters,
+            ..
         } => {
             if let Some(metadata) = selector {
                 assert_eq!(
@@ -79,6 +89,7 @@ pub(crate) fn convert_expression_to_asm(
                     name,
                     arguments,
                     function_body,
+                    reference_parameters,
                     namespace,
                     return_register,
                     register_sequencer,
@@ -95,6 +106,17 @@ pub(crate) fn convert_expression_to_asm(
                 register_sequencer,
             )
         }
+        TypedExpressionVariant::BuiltinOperator { op, lhs, rhs } => {
+            convert_builtin_operator_to_asm(
+                op,
+                lhs,
+                rhs,
+                return_register,
+                namespace,
+                register_sequencer,
+                exp.span.clone(),
+            )
+        }
         TypedExpressionVariant::VariableExpression { name } => {
             let var = check!(
                 namespace.look_up_variable(name),
@@ -361,6 +383,57 @@ pub(crate) fn convert_expression_to_asm(
         }
         // ABI casts are purely compile-time constructs and generate no corresponding bytecode
         TypedExpressionVariant::AbiCast { .. } => ok(vec![], warnings, errors),
+        TypedExpressionVariant::StorageAccess { ix, .. } => convert_storage_access_to_asm(
+            *ix,
+            exp.return_type,
+            &exp.span,
+            namespace,
+            return_register,
+            register_sequencer,
+        ),
+        // an enum is a pointer to its tag, followed by the value of its variant
+        TypedExpressionVariant::EnumTag { prefix } => {
+            let prefix_register = register_sequencer.next();
+            let mut asm_buf = check!(
+                convert_expression_to_asm(prefix, namespace, &prefix_register, register_sequencer),
+                return err(warnings, errors),
+                warnings,
+                errors
+            );
+            asm_buf.push(Op::new_with_comment(
+                VirtualOp::LW(
+                    return_register.clone(),
+                    prefix_register,
+                    VirtualImmediate12::new_unchecked(0, "constant num; infallible"),
+                ),
+                exp.span.clone(),
+                "load enum tag",
+            ));
+            ok(asm_buf, warnings, errors)
+        }
+        TypedExpressionVariant::Revert { code } => {
+            let code_register = register_sequencer.next();
+            let mut asm_buf = check!(
+                convert_expression_to_asm(code, namespace, &code_register, register_sequencer),
+                return err(warnings, errors),
+                warnings,
+                errors
+            );
+            asm_buf.push(Op::new(VirtualOp::RVRT(code_register), exp.span.clone()));
+            ok(asm_buf, warnings, errors)
+        }
+        TypedExpressionVariant::Log { value } => {
+            convert_log_to_asm(value, namespace, register_sequencer, exp.span.clone())
+        }
+        TypedExpressionVariant::VecOperation { op, arguments } => convert_vec_op_to_asm(
+            *op,
+            arguments,
+            exp.return_type,
+            &exp.span,
+            namespace,
+            return_register,
+            register_sequencer,
+        ),
         a => {
             println!("unimplemented: {:?}", a);
             errors.push(CompileError::Unimplemented(
@@ -372,6 +445,92 @@ pub(crate) fn convert_expression_to_asm(
     }
 }
 
+/// Logs a word sized value with `LOG`, and anything else, which is laid out in memory, with
+/// `LOGD`. Either way, the log carries the id of the value's type.
+fn convert_log_to_asm(
+    value: &TypedExpression,
+    namespace: &mut AsmNamespace,
+    register_sequencer: &mut RegisterSequencer,
+    span: Span,
+) -> CompileResult<Vec<Op>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let ty = match resolve_type(value.return_type, &value.span) {
+        Ok(ty) => ty,
+        Err(e) => {
+            errors.push(e.into());
+            return err(warnings, errors);
+        }
+    };
+    let value_register = register_sequencer.next();
+    let mut asm_buf = check!(
+        convert_expression_to_asm(value, namespace, &value_register, register_sequencer),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    let log_id_register = register_sequencer.next();
+    asm_buf.append(&mut convert_literal_to_asm(
+        &Literal::U64(value.return_type.generate_logged_type().log_id),
+        namespace,
+        &log_id_register,
+        register_sequencer,
+        span.clone(),
+    ));
+    let zero = VirtualRegister::Constant(ConstantRegister::Zero);
+    if ty.is_copy_type() {
+        asm_buf.push(Op::new(
+            VirtualOp::LOG(value_register, log_id_register, zero.clone(), zero),
+            span,
+        ));
+        return ok(asm_buf, warnings, errors);
+    }
+    let size_in_bytes = match ty.size_in_words(&value.span) {
+        Ok(size) => size * 8,
+        Err(e) => {
+            errors.push(e);
+            return err(warnings, errors);
+        }
+    };
+    // A string which fits in a word is loaded from the data section into its register by value,
+    // so it's written to the stack to be logged from memory.
+    let value_register = if matches!(ty, TypeInfo::Str(_)) && size_in_bytes == 8 {
+        let pointer_register = register_sequencer.next();
+        asm_buf.push(Op::register_move(
+            pointer_register.clone(),
+            VirtualRegister::Constant(ConstantRegister::StackPointer),
+            span.clone(),
+        ));
+        asm_buf.push(Op::unowned_stack_allocate_memory(
+            VirtualImmediate24::new_unchecked(8, "infallible constant 8"),
+        ));
+        asm_buf.push(Op::new(
+            VirtualOp::SW(
+                pointer_register.clone(),
+                value_register,
+                VirtualImmediate12::new_unchecked(0, "infallible constant 0"),
+            ),
+            span.clone(),
+        ));
+        pointer_register
+    } else {
+        value_register
+    };
+    let size_register = register_sequencer.next();
+    asm_buf.append(&mut convert_literal_to_asm(
+        &Literal::U64(size_in_bytes),
+        namespace,
+        &size_register,
+        register_sequencer,
+        span.clone(),
+    ));
+    asm_buf.push(Op::new(
+        VirtualOp::LOGD(zero, log_id_register, value_register, size_register),
+        span,
+    ));
+    ok(asm_buf, warnings, errors)
+}
+
 /// Takes a virtual register ID and either locates it in the register mapping, finds it is a reserved register,
 /// or finds nothing and returns `None`.
 fn realize_register(
@@ -442,6 +601,7 @@ fn convert_fn_app_to_asm(
     name: &CallPath,
     arguments: &[(Ident, TypedExpression)],
     function_body: &TypedCodeBlock,
+    reference_parameters: &[Ident],
     parent_namespace: &mut AsmNamespace,
     return_register: &VirtualRegister,
     register_sequencer: &mut RegisterSequencer,
@@ -455,6 +615,26 @@ fn convert_fn_app_to_asm(
     let mut args_and_registers: HashMap<Ident, VirtualRegister> = Default::default();
     // evaluate every expression being passed into the function
     for (name, arg) in arguments {
+        // every call is inlined, so a `ref mut` parameter is passed the register of the caller's
+        // variable itself, which for a value laid out in memory is its address, and the function
+        // updates it in place
+        match &arg.expression {
+            TypedExpressionVariant::VariableExpression { name: var_name }
+                if reference_parameters.contains(name) =>
+            {
+                let var_register = check!(
+                    namespace.look_up_variable(var_name),
+                    continue,
+                    warnings,
+                    errors
+                );
+                args_and_registers.insert(name.clone(), var_register.clone());
+                continue;
+            }
+            // a function value has been bound into the function body already
+            TypedExpressionVariant::FunctionValue(_) => continue,
+            _ => (),
+        }
         let return_register = register_sequencer.next();
         let mut ops = check!(
             convert_expression_to_asm(arg, &mut namespace, &return_register, register_sequencer),
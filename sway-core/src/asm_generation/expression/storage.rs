@@ -0,0 +1,280 @@
+//! This module contains the logic for reading and writing contract storage fields.
+use super::*;
+use crate::{
+    semantic_analysis::ast_node::{
+        get_storage_chunk_keys, get_storage_initialized_key, get_storage_key, TypedStorageField,
+    },
+    type_engine::{resolve_type, TypeId, TypeInfo},
+};
+
+/// Reads the storage field at index `ix` of type `field_type` into `return_register`.
+///
+/// Single word values are read directly into the register with `SRW`. Anything larger is read a
+/// quad word at a time with `SRWQ` into newly allocated stack memory, and `return_register` is
+/// left pointing to that memory.
+pub(super) fn convert_storage_access_to_asm(
+    ix: usize,
+    field_type: TypeId,
+    span: &Span,
+    namespace: &mut AsmNamespace,
+    return_register: &VirtualRegister,
+    register_sequencer: &mut RegisterSequencer,
+) -> CompileResult<Vec<Op>> {
+    let warnings = vec![];
+    let mut errors = vec![];
+    let field_type = check_std_result!(resolve_type(field_type, span), warnings, errors);
+    let size_in_words = check_std_result!(field_type.size_in_words(span), warnings, errors);
+
+    let mut asm_buf = vec![Op::new_comment(format!("storage field {} access", ix))];
+    let key_registers = load_storage_key_registers(
+        ix,
+        size_in_words,
+        namespace,
+        register_sequencer,
+        &mut asm_buf,
+    );
+    if is_storage_word(&field_type) {
+        asm_buf.push(Op::new(
+            VirtualOp::SRW(return_register.clone(), key_registers[0].clone()),
+            span.clone(),
+        ));
+    } else {
+        asm_buf.push(Op::register_move(
+            return_register.clone(),
+            VirtualRegister::Constant(ConstantRegister::StackPointer),
+            span.clone(),
+        ));
+        asm_buf.push(Op::unowned_stack_allocate_memory(
+            VirtualImmediate24::new(key_registers.len() as u64 * 32, span.clone()).unwrap(),
+        ));
+        for (chunk_ix, key_register) in key_registers.into_iter().enumerate() {
+            let dest_register = offset_register(
+                return_register,
+                chunk_ix as u64 * 32,
+                span,
+                register_sequencer,
+                &mut asm_buf,
+            );
+            asm_buf.push(Op::new(
+                VirtualOp::SRWQ(dest_register, key_register),
+                span.clone(),
+            ));
+        }
+    }
+    ok(asm_buf, warnings, errors)
+}
+
+/// Writes the value in `value_register` to the storage field at index `ix` of type
+/// `field_type`.  See [convert_storage_access_to_asm] for the layout.
+pub(crate) fn convert_storage_write_to_asm(
+    ix: usize,
+    field_type: TypeId,
+    value_register: &VirtualRegister,
+    span: &Span,
+    namespace: &mut AsmNamespace,
+    register_sequencer: &mut RegisterSequencer,
+) -> CompileResult<Vec<Op>> {
+    let warnings = vec![];
+    let mut errors = vec![];
+    let field_type = check_std_result!(resolve_type(field_type, span), warnings, errors);
+    let size_in_words = check_std_result!(field_type.size_in_words(span), warnings, errors);
+
+    let mut asm_buf = vec![Op::new_comment(format!("storage field {} write", ix))];
+    let key_registers = load_storage_key_registers(
+        ix,
+        size_in_words,
+        namespace,
+        register_sequencer,
+        &mut asm_buf,
+    );
+    if is_storage_word(&field_type) {
+        asm_buf.push(Op::new(
+            VirtualOp::SWW(key_registers[0].clone(), value_register.clone()),
+            span.clone(),
+        ));
+    } else {
+        let size_in_bytes = size_in_words * 8;
+        for (chunk_ix, key_register) in key_registers.into_iter().enumerate() {
+            let chunk_offset = chunk_ix as u64 * 32;
+            let src_register = offset_register(
+                value_register,
+                chunk_offset,
+                span,
+                register_sequencer,
+                &mut asm_buf,
+            );
+            if size_in_bytes - chunk_offset >= 32 {
+                asm_buf.push(Op::new(
+                    VirtualOp::SWWQ(key_register, src_register),
+                    span.clone(),
+                ));
+            } else {
+                // The last chunk is shorter than a quad word, so it's copied into a zeroed quad
+                // word on the stack first, rather than `SWWQ` reading past the end of the value.
+                let pad_register = register_sequencer.next();
+                asm_buf.push(Op::register_move(
+                    pad_register.clone(),
+                    VirtualRegister::Constant(ConstantRegister::StackPointer),
+                    span.clone(),
+                ));
+                asm_buf.push(Op::unowned_stack_allocate_memory(
+                    VirtualImmediate24::new(32, span.clone()).unwrap(),
+                ));
+                asm_buf.push(Op::new(
+                    VirtualOp::MCLI(
+                        pad_register.clone(),
+                        VirtualImmediate18::new(32, span.clone()).unwrap(),
+                    ),
+                    span.clone(),
+                ));
+                asm_buf.push(Op::new(
+                    VirtualOp::MCPI(
+                        pad_register.clone(),
+                        src_register,
+                        VirtualImmediate12::new(size_in_bytes - chunk_offset, span.clone())
+                            .unwrap(),
+                    ),
+                    span.clone(),
+                ));
+                asm_buf.push(Op::new(
+                    VirtualOp::SWWQ(key_register, pad_register),
+                    span.clone(),
+                ));
+                asm_buf.push(Op::new(
+                    VirtualOp::CFSI(VirtualImmediate24::new(32, span.clone()).unwrap()),
+                    span.clone(),
+                ));
+            }
+        }
+    }
+    ok(asm_buf, warnings, errors)
+}
+
+/// Gives each storage field the value of its initializer, unless the flag at
+/// `get_storage_initialized_key()` says this has already been done, in which case nothing
+/// happens.  This is run on entry to the contract, before the selector switch, so the first call
+/// to the contract does the initialization.
+pub(crate) fn convert_storage_initializers_to_asm(
+    fields: &[TypedStorageField],
+    namespace: &mut AsmNamespace,
+    register_sequencer: &mut RegisterSequencer,
+) -> CompileResult<Vec<Op>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let mut asm_buf = vec![Op::new_comment("storage initialization")];
+    let flag_data_id = namespace.insert_data_value(&Literal::B256(get_storage_initialized_key()));
+    let flag_key_register = register_sequencer.next();
+    asm_buf.push(Op::unowned_load_data_comment(
+        flag_key_register.clone(),
+        flag_data_id,
+        "storage initialized flag key",
+    ));
+    let flag_register = register_sequencer.next();
+    asm_buf.push(Op::unowned_new_with_comment(
+        VirtualOp::SRW(flag_register.clone(), flag_key_register.clone()),
+        "read storage initialized flag",
+    ));
+    let done_label = register_sequencer.get_label();
+    asm_buf.push(Op::jump_if_not_equal(
+        VirtualRegister::Constant(ConstantRegister::Zero),
+        flag_register,
+        done_label.clone(),
+    ));
+
+    for (ix, field) in fields.iter().enumerate() {
+        let value_register = register_sequencer.next();
+        let mut value_asm = check!(
+            convert_expression_to_asm(
+                &field.initializer,
+                namespace,
+                &value_register,
+                register_sequencer
+            ),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        asm_buf.append(&mut value_asm);
+        let mut write_asm = check!(
+            convert_storage_write_to_asm(
+                ix,
+                field.r#type,
+                &value_register,
+                &field.initializer.span,
+                namespace,
+                register_sequencer
+            ),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        asm_buf.append(&mut write_asm);
+    }
+
+    asm_buf.push(Op::unowned_new_with_comment(
+        VirtualOp::SWW(
+            flag_key_register,
+            VirtualRegister::Constant(ConstantRegister::One),
+        ),
+        "set storage initialized flag",
+    ));
+    asm_buf.push(Op::unowned_jump_label(done_label));
+    ok(asm_buf, warnings, errors)
+}
+
+/// Values of these types live in a single register rather than in memory.
+fn is_storage_word(ty: &TypeInfo) -> bool {
+    matches!(
+        ty,
+        TypeInfo::SignedInteger(_) | TypeInfo::Boolean | TypeInfo::Byte
+    ) || matches!(ty, TypeInfo::UnsignedInteger(bits) if !bits.is_wide())
+        || matches!(ty, TypeInfo::Tuple(fields) if fields.is_empty())
+}
+
+/// Puts each storage key backing the field at index `ix` into the data section and loads a pointer
+/// to it into a new register.
+fn load_storage_key_registers(
+    ix: usize,
+    size_in_words: u64,
+    namespace: &mut AsmNamespace,
+    register_sequencer: &mut RegisterSequencer,
+    asm_buf: &mut Vec<Op>,
+) -> Vec<VirtualRegister> {
+    get_storage_chunk_keys(&get_storage_key(ix), size_in_words)
+        .into_iter()
+        .map(|key| {
+            let data_id = namespace.insert_data_value(&Literal::B256(key));
+            let key_register = register_sequencer.next();
+            asm_buf.push(Op {
+                opcode: either::Either::Left(VirtualOp::LWDataId(key_register.clone(), data_id)),
+                comment: "storage key".into(),
+                owning_span: None,
+            });
+            key_register
+        })
+        .collect()
+}
+
+/// Returns a register holding `base_register + offset_in_bytes`, or `base_register` itself if the
+/// offset is zero.
+fn offset_register(
+    base_register: &VirtualRegister,
+    offset_in_bytes: u64,
+    span: &Span,
+    register_sequencer: &mut RegisterSequencer,
+    asm_buf: &mut Vec<Op>,
+) -> VirtualRegister {
+    if offset_in_bytes == 0 {
+        return base_register.clone();
+    }
+    let offset_register = register_sequencer.next();
+    asm_buf.push(Op::new(
+        VirtualOp::ADDI(
+            offset_register.clone(),
+            base_register.clone(),
+            VirtualImmediate12::new(offset_in_bytes, span.clone()).unwrap(),
+        ),
+        span.clone(),
+    ));
+    offset_register
+}
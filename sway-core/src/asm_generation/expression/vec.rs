@@ -0,0 +1,456 @@
+use super::*;
+use crate::semantic_analysis::ast_node::VecOp;
+use crate::type_engine::TypeId;
+
+// A vector is a pointer to a header on the heap, of the address of its elements, its capacity and
+// its length. These are the offsets of each, in words.
+const ELEMS: u64 = 0;
+const CAPACITY: u64 = 1;
+const LENGTH: u64 = 2;
+const HEADER_SIZE_IN_BYTES: u64 = 24;
+
+pub(super) fn convert_vec_op_to_asm(
+    op: VecOp,
+    arguments: &[TypedExpression],
+    return_type: TypeId,
+    span: &Span,
+    namespace: &mut AsmNamespace,
+    return_register: &VirtualRegister,
+    register_sequencer: &mut RegisterSequencer,
+) -> CompileResult<Vec<Op>> {
+    let mut warnings = Vec::new();
+    let mut errors = Vec::new();
+    let vec_type = match op {
+        VecOp::New | VecOp::WithCapacity => return_type,
+        _ => arguments[0].return_type,
+    };
+    let elem_type = match look_up_type_id(vec_type) {
+        TypeInfo::Vector(elem_type) => look_up_type_id(elem_type),
+        _ => {
+            errors.push(CompileError::Internal(
+                "vec operation on a non-vec",
+                span.clone(),
+            ));
+            return err(warnings, errors);
+        }
+    };
+    // The element type of an empty vector may never be known, which is fine as long as nothing
+    // needs its size.
+    let elem_size_in_bytes = match op {
+        VecOp::New | VecOp::Len | VecOp::Capacity => 0,
+        _ => match elem_type.size_in_words(span) {
+            Ok(size) => size * 8,
+            Err(e) => {
+                errors.push(e);
+                return err(warnings, errors);
+            }
+        },
+    };
+
+    let mut bytecode = Vec::new();
+    let mut arg_registers = Vec::with_capacity(arguments.len());
+    for arg in arguments {
+        let arg_register = register_sequencer.next();
+        bytecode.append(&mut check!(
+            convert_expression_to_asm(arg, namespace, &arg_register, register_sequencer),
+            return err(warnings, errors),
+            warnings,
+            errors
+        ));
+        arg_registers.push(arg_register);
+    }
+    let elem_size_reg = register_sequencer.next();
+    let elem_size_reg =
+        set_large_register_value(elem_size_in_bytes, &elem_size_reg, &mut bytecode, span).clone();
+    let zero = VirtualRegister::Constant(ConstantRegister::Zero);
+
+    match op {
+        VecOp::New => {
+            compile_header_allocation(&mut bytecode, return_register, span, register_sequencer);
+            bytecode.push(store_word(return_register, &zero, ELEMS, span));
+            bytecode.push(store_word(return_register, &zero, CAPACITY, span));
+        }
+        VecOp::WithCapacity => {
+            let capacity_reg = &arg_registers[0];
+            compile_header_allocation(&mut bytecode, return_register, span, register_sequencer);
+            let size_reg = register_sequencer.next();
+            bytecode.push(Op::new(
+                VirtualOp::MUL(size_reg.clone(), capacity_reg.clone(), elem_size_reg),
+                span.clone(),
+            ));
+            let elems_reg = register_sequencer.next();
+            compile_allocation(&mut bytecode, size_reg, &elems_reg, span);
+            bytecode.push(store_word(return_register, &elems_reg, ELEMS, span));
+            bytecode.push(store_word(return_register, capacity_reg, CAPACITY, span));
+        }
+        VecOp::Len => bytecode.push(load_word(return_register, &arg_registers[0], LENGTH, span)),
+        VecOp::Capacity => bytecode.push(load_word(
+            return_register,
+            &arg_registers[0],
+            CAPACITY,
+            span,
+        )),
+        VecOp::Push => compile_push(
+            &mut bytecode,
+            &arg_registers[0],
+            &arg_registers[1],
+            &elem_type,
+            elem_size_in_bytes,
+            &elem_size_reg,
+            span,
+            register_sequencer,
+        ),
+        VecOp::Get => {
+            let elem_addr_reg = compile_checked_element_address(
+                &mut bytecode,
+                &arg_registers[0],
+                &arg_registers[1],
+                &elem_size_reg,
+                span,
+                register_sequencer,
+            );
+            if elem_size_in_bytes == 0 {
+                // There's nothing to read.
+            } else if elem_type.is_held_in_register() {
+                bytecode.push(load_word(return_register, &elem_addr_reg, 0, span));
+            } else {
+                // The element is copied to the stack, so that setting an element later doesn't
+                // change the value returned here.
+                let stack_size = check_std_result!(
+                    VirtualImmediate24::new(elem_size_in_bytes, span.clone()),
+                    warnings,
+                    errors
+                );
+                bytecode.push(Op::register_move(
+                    return_register.clone(),
+                    VirtualRegister::Constant(ConstantRegister::StackPointer),
+                    span.clone(),
+                ));
+                bytecode.push(Op::unowned_stack_allocate_memory(stack_size));
+                bytecode.push(Op::new(
+                    VirtualOp::MCP(return_register.clone(), elem_addr_reg, elem_size_reg),
+                    span.clone(),
+                ));
+            }
+        }
+        VecOp::Set => {
+            let elem_addr_reg = compile_checked_element_address(
+                &mut bytecode,
+                &arg_registers[0],
+                &arg_registers[1],
+                &elem_size_reg,
+                span,
+                register_sequencer,
+            );
+            compile_element_store(
+                &mut bytecode,
+                &elem_addr_reg,
+                &arg_registers[2],
+                &elem_type,
+                elem_size_in_bytes,
+                &elem_size_reg,
+                span,
+            );
+        }
+    }
+    ok(bytecode, warnings, errors)
+}
+
+/// Appends `elem_reg` to the vector `vec_reg`. If the vector is at capacity then its elements are
+/// first copied to a new allocation of twice the capacity, or of four elements if it has none.
+/// The old allocation is never reclaimed.
+#[allow(clippy::too_many_arguments)]
+fn compile_push(
+    bytecode: &mut Vec<Op>,
+    vec_reg: &VirtualRegister,
+    elem_reg: &VirtualRegister,
+    elem_type: &TypeInfo,
+    elem_size_in_bytes: u64,
+    elem_size_reg: &VirtualRegister,
+    span: &Span,
+    register_sequencer: &mut RegisterSequencer,
+) {
+    let zero = VirtualRegister::Constant(ConstantRegister::Zero);
+    let one = VirtualRegister::Constant(ConstantRegister::One);
+    let len_reg = register_sequencer.next();
+    let capacity_reg = register_sequencer.next();
+    bytecode.push(load_word(&len_reg, vec_reg, LENGTH, span));
+    bytecode.push(load_word(&capacity_reg, vec_reg, CAPACITY, span));
+
+    // Skip to the store if there's room for the element.
+    let store_label = register_sequencer.get_label();
+    let full_reg = register_sequencer.next();
+    bytecode.push(Op::new(
+        VirtualOp::EQ(full_reg.clone(), len_reg.clone(), capacity_reg.clone()),
+        span.clone(),
+    ));
+    bytecode.push(Op::jump_if_not_equal(
+        full_reg,
+        one.clone(),
+        store_label.clone(),
+    ));
+
+    // new_capacity = capacity * 2 + (capacity == 0) * 4
+    let new_capacity_reg = register_sequencer.next();
+    let empty_reg = register_sequencer.next();
+    bytecode.push(Op::new(
+        VirtualOp::MULI(
+            new_capacity_reg.clone(),
+            capacity_reg.clone(),
+            VirtualImmediate12::new_unchecked(2, "infallible constant 2"),
+        ),
+        span.clone(),
+    ));
+    bytecode.push(Op::new(
+        VirtualOp::EQ(empty_reg.clone(), capacity_reg, zero.clone()),
+        span.clone(),
+    ));
+    bytecode.push(Op::new(
+        VirtualOp::MULI(
+            empty_reg.clone(),
+            empty_reg.clone(),
+            VirtualImmediate12::new_unchecked(4, "infallible constant 4"),
+        ),
+        span.clone(),
+    ));
+    bytecode.push(Op::new(
+        VirtualOp::ADD(
+            new_capacity_reg.clone(),
+            new_capacity_reg.clone(),
+            empty_reg,
+        ),
+        span.clone(),
+    ));
+    let size_reg = register_sequencer.next();
+    bytecode.push(Op::new(
+        VirtualOp::MUL(
+            size_reg.clone(),
+            new_capacity_reg.clone(),
+            elem_size_reg.clone(),
+        ),
+        span.clone(),
+    ));
+    let new_elems_reg = register_sequencer.next();
+    compile_allocation(bytecode, size_reg, &new_elems_reg, span);
+
+    // Copy the elements across, unless there are no bytes to copy, which MCP doesn't allow.
+    let copied_label = register_sequencer.get_label();
+    let used_size_reg = register_sequencer.next();
+    let nothing_to_copy_reg = register_sequencer.next();
+    bytecode.push(Op::new(
+        VirtualOp::MUL(
+            used_size_reg.clone(),
+            len_reg.clone(),
+            elem_size_reg.clone(),
+        ),
+        span.clone(),
+    ));
+    bytecode.push(Op::new(
+        VirtualOp::EQ(
+            nothing_to_copy_reg.clone(),
+            used_size_reg.clone(),
+            zero.clone(),
+        ),
+        span.clone(),
+    ));
+    bytecode.push(Op::jump_if_not_equal(
+        nothing_to_copy_reg,
+        zero,
+        copied_label.clone(),
+    ));
+    let old_elems_reg = register_sequencer.next();
+    bytecode.push(load_word(&old_elems_reg, vec_reg, ELEMS, span));
+    bytecode.push(Op::new_with_comment(
+        VirtualOp::MCP(new_elems_reg.clone(), old_elems_reg, used_size_reg),
+        span.clone(),
+        "copy vec elements to new allocation",
+    ));
+    bytecode.push(Op::jump_label(copied_label, span.clone()));
+    bytecode.push(store_word(vec_reg, &new_elems_reg, ELEMS, span));
+    bytecode.push(store_word(vec_reg, &new_capacity_reg, CAPACITY, span));
+
+    bytecode.push(Op::jump_label(store_label, span.clone()));
+    let elem_addr_reg = compile_element_address(
+        bytecode,
+        vec_reg,
+        &len_reg,
+        elem_size_reg,
+        span,
+        register_sequencer,
+    );
+    compile_element_store(
+        bytecode,
+        &elem_addr_reg,
+        elem_reg,
+        elem_type,
+        elem_size_in_bytes,
+        elem_size_reg,
+        span,
+    );
+    let new_len_reg = register_sequencer.next();
+    bytecode.push(Op::new(
+        VirtualOp::ADDI(
+            new_len_reg.clone(),
+            len_reg,
+            VirtualImmediate12::new_unchecked(1, "infallible constant 1"),
+        ),
+        span.clone(),
+    ));
+    bytecode.push(store_word(vec_reg, &new_len_reg, LENGTH, span));
+}
+
+/// Allocates a header on the heap, for an empty vector, and puts its address in `vec_reg`.
+fn compile_header_allocation(
+    bytecode: &mut Vec<Op>,
+    vec_reg: &VirtualRegister,
+    span: &Span,
+    register_sequencer: &mut RegisterSequencer,
+) {
+    let size_reg = register_sequencer.next();
+    set_large_register_value(HEADER_SIZE_IN_BYTES, &size_reg, bytecode, span);
+    compile_allocation(bytecode, size_reg, vec_reg, span);
+    bytecode.push(store_word(
+        vec_reg,
+        &VirtualRegister::Constant(ConstantRegister::Zero),
+        LENGTH,
+        span,
+    ));
+}
+
+/// Allocates `size_reg` bytes on the heap and puts their address in `addr_reg`.
+fn compile_allocation(
+    bytecode: &mut Vec<Op>,
+    size_reg: VirtualRegister,
+    addr_reg: &VirtualRegister,
+    span: &Span,
+) {
+    bytecode.push(Op::new(VirtualOp::ALOC(size_reg), span.clone()));
+    // The heap grows downwards, so the allocation starts just above the new $hp.
+    bytecode.push(Op::new(
+        VirtualOp::ADDI(
+            addr_reg.clone(),
+            VirtualRegister::Constant(ConstantRegister::HeapPointer),
+            VirtualImmediate12::new_unchecked(1, "infallible constant 1"),
+        ),
+        span.clone(),
+    ));
+}
+
+/// Reverts, with the same code as an out of bounds array index, unless `index_reg` is less than
+/// the length of the vector, and otherwise returns the address of the element at `index_reg`.
+fn compile_checked_element_address(
+    bytecode: &mut Vec<Op>,
+    vec_reg: &VirtualRegister,
+    index_reg: &VirtualRegister,
+    elem_size_reg: &VirtualRegister,
+    span: &Span,
+    register_sequencer: &mut RegisterSequencer,
+) -> VirtualRegister {
+    let len_reg = register_sequencer.next();
+    bytecode.push(load_word(&len_reg, vec_reg, LENGTH, span));
+    let in_bounds_reg = register_sequencer.next();
+    bytecode.push(Op::new_with_comment(
+        VirtualOp::LT(in_bounds_reg.clone(), index_reg.clone(), len_reg),
+        span.clone(),
+        "compare vec index for out of bounds",
+    ));
+    let skip_label = register_sequencer.get_label();
+    bytecode.push(Op::jump_if_not_equal(
+        in_bounds_reg,
+        VirtualRegister::Constant(ConstantRegister::Zero),
+        skip_label.clone(),
+    ));
+    bytecode.push(Op::new_with_comment(
+        VirtualOp::RVRT(VirtualRegister::Constant(ConstantRegister::One)),
+        span.clone(),
+        "aborting due to out of bounds access",
+    ));
+    bytecode.push(Op::jump_label_comment(
+        skip_label,
+        span.clone(),
+        "after bounds check",
+    ));
+    compile_element_address(
+        bytecode,
+        vec_reg,
+        index_reg,
+        elem_size_reg,
+        span,
+        register_sequencer,
+    )
+}
+
+fn compile_element_address(
+    bytecode: &mut Vec<Op>,
+    vec_reg: &VirtualRegister,
+    index_reg: &VirtualRegister,
+    elem_size_reg: &VirtualRegister,
+    span: &Span,
+    register_sequencer: &mut RegisterSequencer,
+) -> VirtualRegister {
+    let elems_reg = register_sequencer.next();
+    bytecode.push(load_word(&elems_reg, vec_reg, ELEMS, span));
+    let elem_addr_reg = register_sequencer.next();
+    bytecode.push(Op::new_with_comment(
+        VirtualOp::MUL(
+            elem_addr_reg.clone(),
+            index_reg.clone(),
+            elem_size_reg.clone(),
+        ),
+        span.clone(),
+        "convert index into byte offset",
+    ));
+    bytecode.push(Op::new(
+        VirtualOp::ADD(elem_addr_reg.clone(), elems_reg, elem_addr_reg.clone()),
+        span.clone(),
+    ));
+    elem_addr_reg
+}
+
+/// Writes `elem_reg` to `elem_addr_reg`, either as a word or by copying the memory it points to.
+fn compile_element_store(
+    bytecode: &mut Vec<Op>,
+    elem_addr_reg: &VirtualRegister,
+    elem_reg: &VirtualRegister,
+    elem_type: &TypeInfo,
+    elem_size_in_bytes: u64,
+    elem_size_reg: &VirtualRegister,
+    span: &Span,
+) {
+    if elem_size_in_bytes == 0 {
+        // There's nothing to write.
+    } else if elem_type.is_held_in_register() {
+        bytecode.push(store_word(elem_addr_reg, elem_reg, 0, span));
+    } else {
+        bytecode.push(Op::new(
+            VirtualOp::MCP(
+                elem_addr_reg.clone(),
+                elem_reg.clone(),
+                elem_size_reg.clone(),
+            ),
+            span.clone(),
+        ));
+    }
+}
+
+fn load_word(dst: &VirtualRegister, addr: &VirtualRegister, offset: u64, span: &Span) -> Op {
+    Op::new(
+        VirtualOp::LW(
+            dst.clone(),
+            addr.clone(),
+            VirtualImmediate12::new_unchecked(offset, "offset within a vec header or element"),
+        ),
+        span.clone(),
+    )
+}
+
+fn store_word(addr: &VirtualRegister, value: &VirtualRegister, offset: u64, span: &Span) -> Op {
+    Op::new(
+        VirtualOp::SW(
+            addr.clone(),
+            value.clone(),
+            VirtualImmediate12::new_unchecked(offset, "offset within a vec header or element"),
+        ),
+        span.clone(),
+    )
+}
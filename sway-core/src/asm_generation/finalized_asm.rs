@@ -28,6 +28,23 @@ pub enum FinalizedAsm {
     Library,
 }
 impl FinalizedAsm {
+    /// The number of bytes removed from the program by the peephole optimizations.
+    pub(crate) fn peephole_bytes_saved(&self) -> u64 {
+        use FinalizedAsm::*;
+        match self {
+            ContractAbi {
+                program_section, ..
+            }
+            | ScriptMain {
+                program_section, ..
+            }
+            | PredicateMain {
+                program_section, ..
+            } => program_section.peephole_bytes_saved,
+            Library => 0,
+        }
+    }
+
     pub(crate) fn to_bytecode_mut(&mut self, source_map: &mut SourceMap) -> CompileResult<Vec<u8>> {
         use FinalizedAsm::*;
         match self {
@@ -7,17 +7,21 @@
 // But this is not ideal and needs to be refactored:
 // - AsmNamespace is tied to data structures from other stages like Ident and Literal.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
     asm_generation::{
-        build_contract_abi_switch, build_preamble, finalized_asm::FinalizedAsm,
-        register_sequencer::RegisterSequencer, AbstractInstructionSet, DataId, DataSection,
-        SwayAsmSet,
+        build_contract_abi_switch, build_preamble, finalized_asm::FinalizedAsm, int_ops,
+        liveness::liveness_analysis, register_sequencer::RegisterSequencer, AbstractInstructionSet,
+        DataId, DataSection, SwayAsmSet,
+    },
+    asm_lang::{
+        virtual_register::*, Label, Op, OrganizationalOp, VirtualImmediate12, VirtualImmediate18,
+        VirtualImmediate24, VirtualOp,
     },
-    asm_lang::{virtual_register::*, Label, Op, VirtualImmediate12, VirtualImmediate24, VirtualOp},
     error::*,
     parse_tree::Literal,
+    semantic_analysis::ast_node::get_storage_chunk_keys,
     BuildConfig,
 };
 
@@ -29,46 +33,73 @@ pub fn compile_ir_to_asm(ir: &Context, build_config: &BuildConfig) -> CompileRes
     let mut warnings: Vec<CompileWarning> = Vec::new();
     let mut errors: Vec<CompileError> = Vec::new();
 
-    let mut reg_seqr = RegisterSequencer::new();
-    let mut bytecode: Vec<Op> = build_preamble(&mut reg_seqr).to_vec();
-
     // Eventually when we get this 'correct' with no hacks we'll want to compile all the modules
     // separately and then use a linker to connect them.  This way we could also keep binary caches
     // of libraries and link against them, rather than recompile everything each time.
     assert!(ir.module_iter().count() == 1);
     let module = ir.module_iter().next().unwrap();
-    let (data_section, mut ops, _) = check!(
-        compile_module_to_asm(reg_seqr, ir, module),
-        return err(warnings, errors),
-        warnings,
-        errors
-    );
-    bytecode.append(&mut ops);
 
+    let mut reg_seqr = RegisterSequencer::new();
     let asm = match module.get_kind(ir) {
-        Kind::Script => SwayAsmSet::ScriptMain {
-            program_section: AbstractInstructionSet { ops: bytecode },
-            data_section,
-        },
-        Kind::Contract => SwayAsmSet::ContractAbi {
-            program_section: AbstractInstructionSet { ops: bytecode },
-            data_section,
-        },
-        Kind::Library | Kind::Predicate => todo!("libraries and predicates coming soon!"),
+        // Libraries have no entry point and so no bytecode of their own.  Their functions are
+        // compiled into the programs which call them.
+        Kind::Library => SwayAsmSet::Library,
+        kind => {
+            let mut bytecode: Vec<Op> = build_preamble(&mut reg_seqr).to_vec();
+            let (data_section, mut ops, new_reg_seqr) = check!(
+                compile_module_to_asm(reg_seqr, ir, module),
+                return err(warnings, errors),
+                warnings,
+                errors
+            );
+            reg_seqr = new_reg_seqr;
+            bytecode.append(&mut ops);
+            let program_section = AbstractInstructionSet { ops: bytecode };
+            match kind {
+                Kind::Script => SwayAsmSet::ScriptMain {
+                    program_section,
+                    data_section,
+                },
+                Kind::Predicate => SwayAsmSet::PredicateMain {
+                    program_section,
+                    data_section,
+                },
+                Kind::Contract => SwayAsmSet::ContractAbi {
+                    program_section,
+                    data_section,
+                },
+                Kind::Library => unreachable!(),
+            }
+        }
     };
 
     if build_config.print_intermediate_asm {
         println!("{}", asm);
     }
 
-    let finalized_asm = asm
-        .remove_unnecessary_jumps()
-        .allocate_registers()
-        .optimize();
+    let register_allocated_asm = check!(
+        asm.remove_unnecessary_jumps()
+            .allocate_registers(&mut reg_seqr),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    let finalized_asm = check!(
+        register_allocated_asm.optimize(build_config),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
 
     if build_config.print_finalized_asm {
         println!("{}", finalized_asm);
     }
+    if build_config.print_peephole_savings {
+        println!(
+            ";; Peephole optimizations saved {} bytes.",
+            finalized_asm.peephole_bytes_saved()
+        );
+    }
 
     check!(
         crate::checks::check_invalid_opcodes(&finalized_asm),
@@ -87,14 +118,15 @@ fn compile_module_to_asm(
 ) -> CompileResult<(DataSection, Vec<Op>, RegisterSequencer)> {
     let mut builder = AsmBuilder::new(DataSection::default(), reg_seqr, context);
     match module.get_kind(context) {
-        Kind::Script => {
-            // We can't do function calls yet, so we expect everything to be inlined into `main`.
+        Kind::Script | Kind::Predicate => {
+            // Compile `main` followed by every function it calls, directly or indirectly.
             let function = module
                 .function_iter(context)
                 .find(|func| &context.functions[func.0].name == "main")
                 .expect("Can't find main function!");
             builder
                 .compile_function(function)
+                .flat_map(|_| builder.compile_called_functions())
                 .flat_map(|_| builder.finalize())
         }
         Kind::Contract => {
@@ -117,6 +149,12 @@ fn compile_module_to_asm(
                     selectors_and_labels.push((selector, label));
                 }
             }
+            check!(
+                builder.compile_called_functions(),
+                return err(warnings, errors),
+                warnings,
+                errors
+            );
             let (mut data_section, mut funcs_bytecode, mut reg_seqr) = check!(
                 builder.finalize(),
                 return err(warnings, errors),
@@ -133,7 +171,7 @@ fn compile_module_to_asm(
                 errors,
             )
         }
-        Kind::Library | Kind::Predicate => todo!("libraries and predicates coming soon!"),
+        Kind::Library => builder.finalize(),
     }
 }
 
@@ -145,13 +183,6 @@ macro_rules! size_bytes_in_words {
     };
 }
 
-// This is a mouthful...
-macro_rules! size_bytes_round_up_to_word_alignment {
-    ($bytes_expr: expr) => {
-        ($bytes_expr + 7) - (($bytes_expr + 7) % 8)
-    };
-}
-
 struct AsmBuilder<'ir> {
     // Data section is used by the rest of code gen to layout const memory.
     data_section: DataSection,
@@ -167,6 +198,17 @@ struct AsmBuilder<'ir> {
     reg_map: HashMap<Value, VirtualRegister>,
     ptr_map: HashMap<Pointer, Storage>,
 
+    // The stack offsets in words of the memory reserved for values which don't fit in a register
+    // and would otherwise be allocated each time they're computed.
+    result_memory_map: HashMap<Value, u64>,
+
+    // The size in words of the stack memory reserved for the function being compiled.
+    stack_size_in_words: u64,
+
+    // Single word constants are loaded into a register once per block which uses them, as the
+    // first block to load one doesn't necessarily dominate the others.
+    block_const_reg_map: HashMap<Value, VirtualRegister>,
+
     // Stack base register, copied from $SP at the start, but only if we have stack storage.
     stack_base_reg: Option<VirtualRegister>,
 
@@ -176,8 +218,71 @@ struct AsmBuilder<'ir> {
     // IR context we're compiling.
     context: &'ir Context,
 
-    // Final resulting VM bytecode ops.
+    // The calling convention registers and labels for each function which is called.
+    call_infos: HashMap<Function, FnCallInfo>,
+
+    // Functions which are called but are yet to be compiled, in the order they were first called.
+    // The index is to the next function to compile.
+    called_functions: (Vec<Function>, usize),
+
+    // The function currently being compiled, if it is called rather than an entry point.
+    current_callee: Option<Function>,
+
+    // The call sites within the function currently being compiled.
+    call_sites: Vec<CallSite>,
+
+    // Each function once it is compiled, to be finished off and put together by finalize().
+    compiled_functions: Vec<CompiledFunction>,
+
+    // Resulting VM bytecode ops for the function currently being compiled.
+    bytecode: Vec<Op>,
+}
+
+// Functions which aren't entry points are compiled just once and called via a simple convention:
+//
+// - Each function has its own registers for its args, return value and 'return address'.  The
+//   caller copies the args into the arg registers, sets the return address to the index of the
+//   call site and then jumps to the function label.
+// - To return, the function copies its result into the return value register and jumps to its
+//   epilogue, which searches the call site indices for the return address, jumping back to the
+//   label following the matching call.  There may be at most 4096 call sites per function, as
+//   the index is set with an immediate.
+// - Because the callee may clobber any register (including, for recursive calls, the caller's own
+//   arg registers) the caller saves any registers which are live across the call to a new frame
+//   on the stack, pointed to by $$cfp, and restores them afterwards.  The previous $$cfp is saved
+//   in the first word of each frame.
+//
+// - Each function which spills registers does so to its own area of the stack, reserved on entry
+//   and freed on exit, see register_allocator.rs.  Likewise the memory for its locals and any other
+//   values which don't fit in a register is reserved on entry and freed on exit.  A return value
+//   which doesn't fit in a register is a pointer into this memory, so the caller copies it into
+//   its own memory as soon as it has freed the call frame.
+
+struct FnCallInfo {
+    label: Label,
+    epilogue_label: Label,
+    arg_regs: Vec<VirtualRegister>,
+    ret_addr_reg: VirtualRegister,
+    ret_val_reg: VirtualRegister,
+
+    // The label to return to for each call site, indexed by the return address.
+    return_labels: Vec<Label>,
+}
+
+struct CallSite {
+    // Indices into the function bytecode: the start of the call sequence, where registers are
+    // saved, the call itself, and the end of the call sequence where registers are restored.
+    save_idx: usize,
+    call_idx: usize,
+    restore_idx: usize,
+}
+
+struct CompiledFunction {
+    function: Function,
+    is_entry: bool,
+    stack_size_in_words: u64,
     bytecode: Vec<Op>,
+    call_sites: Vec<CallSite>,
 }
 
 struct FieldLayout {
@@ -204,6 +309,13 @@ impl<'ir> AsmBuilder<'ir> {
         }
     }
 
+    // The source span which an instruction was compiled from, if it has one in its metadata.
+    fn instr_span(&self, instr_val: &Value) -> Option<crate::span::Span> {
+        instr_val
+            .get_metadata(self.context)
+            .and_then(|md_idx| md_idx.to_span(self.context))
+    }
+
     fn new(data_section: DataSection, reg_seqr: RegisterSequencer, context: &'ir Context) -> Self {
         AsmBuilder {
             data_section,
@@ -211,9 +323,17 @@ impl<'ir> AsmBuilder<'ir> {
             label_map: HashMap::new(),
             reg_map: HashMap::new(),
             ptr_map: HashMap::new(),
+            result_memory_map: HashMap::new(),
+            stack_size_in_words: 0,
+            block_const_reg_map: HashMap::new(),
             stack_base_reg: None,
             aggregate_layouts: HashMap::new(),
             context,
+            call_infos: HashMap::new(),
+            called_functions: (Vec::new(), 0),
+            current_callee: None,
+            call_sites: Vec::new(),
+            compiled_functions: Vec::new(),
             bytecode: Vec::new(),
         }
     }
@@ -223,16 +343,54 @@ impl<'ir> AsmBuilder<'ir> {
         // Otherwise they go in runtime allocated space, either a register or on the stack.
         //
         // Stack offsets are in words to both enforce alignment and simplify use with LW/SW.
+        //
+        // Constant structs and arrays can't be held in the data section as a single value, so they
+        // are copied onto the stack once it has been reserved.
         let mut stack_base = 0_u64;
+        let mut stack_initialisers = Vec::new();
+        let referenced_ptrs = function
+            .instruction_iter(self.context)
+            .filter_map(
+                |(_block, instr_val)| match &self.context.values[instr_val.0] {
+                    ValueContent::Instruction(Instruction::GetPointer(ptr)) => Some(*ptr),
+                    _ => None,
+                },
+            )
+            .collect::<HashSet<_>>();
         for (_name, ptr) in function.locals_iter(self.context) {
             let ptr_content = &self.context.pointers[ptr.0];
-            if !ptr_content.is_mutable && ptr_content.initializer.is_some() {
-                let constant = ptr_content.initializer.as_ref().unwrap();
+            let scalar_initializer = ptr_content.initializer.as_ref().filter(|constant| {
+                !matches!(
+                    constant.value,
+                    ConstantValue::Struct(_) | ConstantValue::Array(_)
+                )
+            });
+            if !ptr_content.is_mutable && scalar_initializer.is_some() {
+                let constant = scalar_initializer.unwrap();
                 let lit = ir_constant_to_ast_literal(constant);
                 let data_id = self.data_section.insert_data_value(&lit);
                 self.ptr_map.insert(*ptr, Storage::Data(data_id));
             } else {
+                if let Some(constant) = ptr_content.initializer.as_ref().filter(|constant| {
+                    matches!(
+                        constant.value,
+                        ConstantValue::Struct(_) | ConstantValue::Array(_)
+                    )
+                }) {
+                    stack_initialisers.push((constant, stack_base));
+                }
                 match ptr_content.ty {
+                    Type::Uint(nbits) if ptr_content.ty.is_wide_uint() => {
+                        // Wide integers live in memory, like a B256.
+                        self.ptr_map.insert(*ptr, Storage::Stack(stack_base));
+                        stack_base += nbits as u64 / 64;
+                    }
+                    Type::Unit | Type::Bool | Type::Uint(_) if referenced_ptrs.contains(ptr) => {
+                        // A scalar whose address is taken, e.g. to pass it by `ref mut`, needs
+                        // to be in memory rather than a register.
+                        self.ptr_map.insert(*ptr, Storage::Stack(stack_base));
+                        stack_base += 1;
+                    }
                     Type::Unit | Type::Bool | Type::Uint(_) => {
                         let reg = self.reg_seqr.next();
                         self.ptr_map.insert(*ptr, Storage::Register(reg));
@@ -283,6 +441,58 @@ impl<'ir> AsmBuilder<'ir> {
             }
         }
 
+        // Values which don't fit in a register are kept on the stack too, so an instruction in a
+        // loop reuses the same memory each time around rather than reserving more, and the
+        // function's stack memory is a fixed size which can be freed when it returns.  These are
+        // the results of wide integer ops, the results of calls, which are copied out of the
+        // callee's freed frame, the results of storage loads and constant structs and arrays.
+        let context = self.context;
+        for (_block, instr_val) in function.instruction_iter(context) {
+            let instruction = match &context.values[instr_val.0] {
+                ValueContent::Instruction(instruction) => instruction,
+                _otherwise => continue,
+            };
+            let result_size_in_words = match instruction {
+                Instruction::BinaryOp { op, arg1, .. } => {
+                    int_ops::result_size_in_words(*op, self.int_size_in_words(arg1))
+                }
+                Instruction::Call(callee, _) => match &context.functions[callee.0].return_type {
+                    Type::Unit | Type::Bool => 0,
+                    ty @ Type::Uint(_) if !ty.is_wide_uint() => 0,
+                    ty => size_bytes_in_words!(self.ir_type_size_in_bytes(ty)),
+                },
+                Instruction::StateLoad { ty, .. } => match ty {
+                    Type::Unit | Type::Bool | Type::Uint(_) if !ty.is_wide_uint() => 0,
+                    // Storage is read a whole quad word at a time.
+                    _otherwise => {
+                        let size_in_words = size_bytes_in_words!(self.ir_type_size_in_bytes(ty));
+                        std::cmp::max(1, (size_in_words + 3) / 4) * 4
+                    }
+                },
+                _otherwise => 0,
+            };
+            if result_size_in_words > 0 {
+                self.result_memory_map.insert(instr_val, stack_base);
+                stack_base += result_size_in_words;
+            }
+            for operand in instruction.get_operands() {
+                if let ValueContent::Constant(
+                    constant @ Constant {
+                        value: ConstantValue::Struct(_) | ConstantValue::Array(_),
+                        ..
+                    },
+                ) = &context.values[operand.0]
+                {
+                    let size_in_words = size_bytes_in_words!(self.constant_size_in_bytes(constant));
+                    if size_in_words > 0 && !self.result_memory_map.contains_key(&operand) {
+                        self.result_memory_map.insert(operand, stack_base);
+                        stack_base += size_in_words;
+                    }
+                }
+            }
+        }
+        self.stack_size_in_words = stack_base;
+
         // Reserve space on the stack for ALL our locals which require it.
         if stack_base > 0 {
             let base_reg = self.reg_seqr.next();
@@ -294,6 +504,9 @@ impl<'ir> AsmBuilder<'ir> {
             self.bytecode.push(Op::unowned_stack_allocate_memory(
                 VirtualImmediate24::new(stack_base * 8, Self::empty_span()).unwrap(),
             ));
+            for (constant, offs_in_words) in stack_initialisers {
+                self.initialise_constant_memory(constant, &base_reg, offs_in_words);
+            }
             self.stack_base_reg = Some(base_reg);
         }
     }
@@ -313,33 +526,355 @@ impl<'ir> AsmBuilder<'ir> {
         label
     }
 
-    fn finalize(self) -> CompileResult<(DataSection, Vec<Op>, RegisterSequencer)> {
+    fn finalize(mut self) -> CompileResult<(DataSection, Vec<Op>, RegisterSequencer)> {
+        let mut bytecode = Vec::new();
+        for compiled_function in std::mem::take(&mut self.compiled_functions) {
+            let CompiledFunction {
+                function,
+                is_entry,
+                stack_size_in_words,
+                bytecode: mut function_bytecode,
+                call_sites,
+            } = compiled_function;
+            if !is_entry {
+                self.add_epilogue(function, stack_size_in_words, &mut function_bytecode);
+            }
+            self.add_call_frames(&mut function_bytecode, call_sites);
+            bytecode.append(&mut function_bytecode);
+        }
         ok(
-            (self.data_section, self.bytecode, self.reg_seqr),
+            (self.data_section, bytecode, self.reg_seqr),
             Vec::new(),
             Vec::new(),
         )
     }
 
     fn compile_function(&mut self, function: Function) -> CompileResult<()> {
+        self.compile_function_body(function, true)
+    }
+
+    // Compile every function which has been called and not yet compiled, including those which
+    // are called by them in turn.
+    fn compile_called_functions(&mut self) -> CompileResult<()> {
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+        while self.called_functions.1 < self.called_functions.0.len() {
+            let function = self.called_functions.0[self.called_functions.1];
+            self.called_functions.1 += 1;
+            check!(
+                self.compile_function_body(function, false),
+                return err(warnings, errors),
+                warnings,
+                errors
+            );
+        }
+        ok((), warnings, errors)
+    }
+
+    fn compile_function_body(&mut self, function: Function, is_entry: bool) -> CompileResult<()> {
         // Compile instructions.
         let mut warnings = Vec::new();
         let mut errors = Vec::new();
+        self.stack_base_reg = None;
+        self.current_callee = if is_entry {
+            None
+        } else {
+            let call_info = &self.call_infos[&function];
+            self.bytecode.push(Op::unowned_jump_label_comment(
+                call_info.label.clone(),
+                format!("fn {}", function.get_name(self.context)),
+            ));
+            for ((_, arg_val), arg_reg) in function
+                .args_iter(self.context)
+                .zip(call_info.arg_regs.iter())
+            {
+                self.reg_map.insert(*arg_val, arg_reg.clone());
+            }
+            self.bytecode.push(Op {
+                opcode: Either::Right(OrganizationalOp::SpillAreaStart(self.reg_seqr.next())),
+                comment: String::new(),
+                owning_span: None,
+            });
+            Some(function)
+        };
         self.add_locals(function);
         for block in function.block_iter(self.context) {
             self.add_block_label(block);
+            self.block_const_reg_map.clear();
             for instr_val in block.instruction_iter(self.context) {
+                let first_op_idx = self.bytecode.len();
                 check!(
                     self.compile_instruction(&block, &instr_val),
                     return err(warnings, errors),
                     warnings,
                     errors
                 );
+
+                // The ops for an instruction are owned by its source span, unless they've been
+                // given one already.  Spans without a path can't appear in the source map, so
+                // they're replaced too.
+                if let Some(span) = self.instr_span(&instr_val) {
+                    for op in &mut self.bytecode[first_op_idx..] {
+                        if op
+                            .owning_span
+                            .as_ref()
+                            .and_then(|op_span| op_span.path.as_ref())
+                            .is_none()
+                        {
+                            op.owning_span = Some(span.clone());
+                        }
+                    }
+                }
             }
         }
+        self.compiled_functions.push(CompiledFunction {
+            function,
+            is_entry,
+            stack_size_in_words: self.stack_size_in_words,
+            bytecode: std::mem::take(&mut self.bytecode),
+            call_sites: std::mem::take(&mut self.call_sites),
+        });
         ok((), warnings, errors)
     }
 
+    // Get the calling convention info for a function, creating it the first time it is called.
+    fn get_call_info(&mut self, function: &Function) -> &mut FnCallInfo {
+        if !self.call_infos.contains_key(function) {
+            let call_info = FnCallInfo {
+                label: self.reg_seqr.get_label(),
+                epilogue_label: self.reg_seqr.get_label(),
+                arg_regs: function
+                    .args_iter(self.context)
+                    .map(|_| self.reg_seqr.next())
+                    .collect(),
+                ret_addr_reg: self.reg_seqr.next(),
+                ret_val_reg: self.reg_seqr.next(),
+                return_labels: Vec::new(),
+            };
+            self.call_infos.insert(*function, call_info);
+            self.called_functions.0.push(*function);
+        }
+        self.call_infos.get_mut(function).unwrap()
+    }
+
+    // The epilogue of a called function jumps back to the call site which matches the return
+    // address.  The VM has no jump to an address held in a register, so the return address is
+    // the index of the call site and the epilogue finds it with a binary search.
+    //
+    // The function's stack memory and spill area are freed first, in the reverse order to which
+    // they were reserved.  The caller copies a return value which points into them straight away.
+    fn add_epilogue(
+        &mut self,
+        function: Function,
+        stack_size_in_words: u64,
+        bytecode: &mut Vec<Op>,
+    ) {
+        let call_info = &self.call_infos[&function];
+        bytecode.push(Op::unowned_jump_label_comment(
+            call_info.epilogue_label.clone(),
+            format!("fn {} epilogue", function.get_name(self.context)),
+        ));
+        let ret_addr_reg = call_info.ret_addr_reg.clone();
+        if stack_size_in_words > 0 {
+            bytecode.push(Op::unowned_new_with_comment(
+                VirtualOp::CFSI(
+                    VirtualImmediate24::new(stack_size_in_words * 8, Self::empty_span()).unwrap(),
+                ),
+                "free locals",
+            ));
+        }
+        bytecode.push(Op {
+            opcode: Either::Right(OrganizationalOp::SpillAreaEnd),
+            comment: String::new(),
+            owning_span: None,
+        });
+        let return_labels = call_info.return_labels.clone();
+        self.add_return_search(bytecode, &ret_addr_reg, &return_labels, 0);
+    }
+
+    // Jump to the label for the call site at index `ret_addr_reg`, which is known to be one of
+    // `return_labels`, the first of which is for the call site at index `first_idx`.
+    fn add_return_search(
+        &mut self,
+        bytecode: &mut Vec<Op>,
+        ret_addr_reg: &VirtualRegister,
+        return_labels: &[Label],
+        first_idx: usize,
+    ) {
+        if return_labels.len() == 1 {
+            bytecode.push(Op::jump_to_label(return_labels[0].clone()));
+            return;
+        }
+
+        // The call site indices have been checked to fit in 12 bits by compile_call().
+        let mid = return_labels.len() / 2;
+        let mid_reg = self.reg_seqr.next();
+        bytecode.push(Op {
+            opcode: Either::Left(VirtualOp::ADDI(
+                mid_reg.clone(),
+                VirtualRegister::Constant(ConstantRegister::Zero),
+                VirtualImmediate12::new_unchecked(
+                    (first_idx + mid) as u64,
+                    "call site index was checked",
+                ),
+            )),
+            comment: format!("call site {}", first_idx + mid),
+            owning_span: None,
+        });
+        let is_lower_reg = self.reg_seqr.next();
+        bytecode.push(Op::unowned_new_with_comment(
+            VirtualOp::LT(is_lower_reg.clone(), ret_addr_reg.clone(), mid_reg),
+            "",
+        ));
+        let lower_label = self.reg_seqr.get_label();
+        bytecode.push(Op::jump_if_not_equal(
+            is_lower_reg,
+            VirtualRegister::Constant(ConstantRegister::Zero),
+            lower_label.clone(),
+        ));
+        self.add_return_search(
+            bytecode,
+            ret_addr_reg,
+            &return_labels[mid..],
+            first_idx + mid,
+        );
+        bytecode.push(Op::unowned_jump_label(lower_label));
+        self.add_return_search(bytecode, ret_addr_reg, &return_labels[..mid], first_idx);
+    }
+
+    // Save the registers which are live across each call in a new call frame, and restore them
+    // afterwards.
+    // The registers to save at each call site are those which are live after the call, other than
+    // its return value.  But saving a register reads it before the call sequence, which may in
+    // turn require it to be saved at an earlier call site.  So the saves are modelled as reads
+    // and the liveness is recalculated until nothing changes.
+    fn get_saved_registers(bytecode: &[Op], call_sites: &[CallSite]) -> Vec<Vec<VirtualRegister>> {
+        let mut saved_regs = vec![Vec::new(); call_sites.len()];
+        loop {
+            let mut ops = Vec::with_capacity(bytecode.len());
+            let mut call_idcs = Vec::with_capacity(call_sites.len());
+            let mut prev_save_idx = 0;
+            for (call_site, regs) in call_sites.iter().zip(saved_regs.iter()) {
+                ops.extend_from_slice(&bytecode[prev_save_idx..call_site.save_idx]);
+                ops.extend(regs.iter().map(|reg: &VirtualRegister| {
+                    Op::unowned_new_with_comment(
+                        VirtualOp::SW(
+                            VirtualRegister::Constant(ConstantRegister::CallFramePointer),
+                            reg.clone(),
+                            VirtualImmediate12 { value: 0 },
+                        ),
+                        "",
+                    )
+                }));
+                call_idcs.push(ops.len() + call_site.call_idx - call_site.save_idx);
+                prev_save_idx = call_site.save_idx;
+            }
+            ops.extend_from_slice(&bytecode[prev_save_idx..]);
+
+            let live_out = liveness_analysis(&ops);
+            let new_saved_regs = call_idcs
+                .into_iter()
+                .map(|call_idx| {
+                    let call_defs = ops[call_idx].def_registers();
+                    let mut regs = live_out[call_idx]
+                        .iter()
+                        .filter(|reg| {
+                            matches!(reg, VirtualRegister::Virtual(_)) && !call_defs.contains(reg)
+                        })
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    regs.sort_by_key(|reg| reg.to_string());
+                    regs
+                })
+                .collect::<Vec<_>>();
+            if new_saved_regs == saved_regs {
+                return saved_regs;
+            }
+            saved_regs = new_saved_regs;
+        }
+    }
+
+    fn add_call_frames(&mut self, bytecode: &mut Vec<Op>, call_sites: Vec<CallSite>) {
+        let saved_regs = Self::get_saved_registers(bytecode, &call_sites);
+
+        // Go backwards so the indices of the earlier call sites remain valid.
+        for (call_site, saved_regs) in call_sites.into_iter().zip(saved_regs).rev() {
+            if saved_regs.is_empty() {
+                continue;
+            }
+
+            // The frame is the size of the saved registers plus the previous frame pointer.
+            let frame_size =
+                VirtualImmediate24::new((saved_regs.len() as u64 + 1) * 8, Self::empty_span())
+                    .unwrap();
+            let frame_ptr_reg = VirtualRegister::Constant(ConstantRegister::CallFramePointer);
+            let restore_ops = saved_regs
+                .iter()
+                .enumerate()
+                .map(|(idx, reg)| Op {
+                    opcode: Either::Left(VirtualOp::LW(
+                        reg.clone(),
+                        frame_ptr_reg.clone(),
+                        VirtualImmediate12 {
+                            value: idx as u16 + 1,
+                        },
+                    )),
+                    comment: "restore register from call frame".into(),
+                    owning_span: None,
+                })
+                .chain(std::iter::once(Op {
+                    opcode: Either::Left(VirtualOp::LW(
+                        frame_ptr_reg.clone(),
+                        frame_ptr_reg.clone(),
+                        VirtualImmediate12 { value: 0 },
+                    )),
+                    comment: "restore previous call frame".into(),
+                    owning_span: None,
+                }))
+                .chain(std::iter::once(Op {
+                    opcode: Either::Left(VirtualOp::CFSI(frame_size.clone())),
+                    comment: "free call frame".into(),
+                    owning_span: None,
+                }))
+                .collect::<Vec<_>>();
+            bytecode.splice(call_site.restore_idx..call_site.restore_idx, restore_ops);
+
+            let new_frame_reg = self.reg_seqr.next();
+            let save_ops = vec![
+                Op::register_move(
+                    new_frame_reg.clone(),
+                    VirtualRegister::Constant(ConstantRegister::StackPointer),
+                    Self::empty_span(),
+                ),
+                Op::unowned_stack_allocate_memory(frame_size),
+                Op {
+                    opcode: Either::Left(VirtualOp::SW(
+                        new_frame_reg.clone(),
+                        frame_ptr_reg.clone(),
+                        VirtualImmediate12 { value: 0 },
+                    )),
+                    comment: "save previous call frame".into(),
+                    owning_span: None,
+                },
+                Op::register_move(frame_ptr_reg.clone(), new_frame_reg, Self::empty_span()),
+            ]
+            .into_iter()
+            .chain(saved_regs.iter().enumerate().map(|(idx, reg)| Op {
+                opcode: Either::Left(VirtualOp::SW(
+                    frame_ptr_reg.clone(),
+                    reg.clone(),
+                    VirtualImmediate12 {
+                        value: idx as u16 + 1,
+                    },
+                )),
+                comment: "save register to call frame".into(),
+                owning_span: None,
+            }))
+            .collect::<Vec<_>>();
+            bytecode.splice(call_site.save_idx..call_site.save_idx, save_ops);
+        }
+    }
+
     fn compile_instruction(&mut self, block: &Block, instr_val: &Value) -> CompileResult<()> {
         let mut warnings = Vec::new();
         let mut errors = Vec::new();
@@ -353,14 +888,17 @@ impl<'ir> AsmBuilder<'ir> {
                         errors
                     )
                 }
-                Instruction::Branch(to_block) => self.compile_branch(block, to_block),
-                Instruction::Call(..) => {
-                    errors.push(CompileError::Internal(
-                        "Calls are not yet supported.",
-                        Self::empty_span(),
-                    ));
-                    return err(warnings, errors);
+                Instruction::BinaryOp { op, arg1, arg2 } => {
+                    self.compile_binary_op(instr_val, op, arg1, arg2)
                 }
+                Instruction::Branch(to_block) => self.compile_branch(block, to_block),
+                Instruction::Call(function, args) => check!(
+                    self.compile_call(instr_val, function, args),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                ),
+                Instruction::Cmp(pred, lhs, rhs) => self.compile_cmp(instr_val, pred, lhs, rhs),
                 Instruction::ConditionalBranch {
                     cond_value,
                     true_block,
@@ -392,6 +930,18 @@ impl<'ir> AsmBuilder<'ir> {
                 Instruction::Load(ptr) => self.compile_load(instr_val, ptr),
                 Instruction::Phi(_) => (), // Managing the phi value is done in br and cbr compilation.
                 Instruction::Ret(ret_val, ty) => self.compile_ret(ret_val, ty),
+                Instruction::StateLoad { key, ty } => check!(
+                    self.compile_state_load(instr_val, key, ty),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                ),
+                Instruction::StateStore { key, stored_val } => check!(
+                    self.compile_state_store(key, stored_val),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                ),
                 Instruction::Store { ptr, stored_val } => self.compile_store(ptr, stored_val),
             }
         } else {
@@ -418,11 +968,12 @@ impl<'ir> AsmBuilder<'ir> {
         let mut errors: Vec<CompileError> = Vec::new();
         let mut inline_reg_map = HashMap::new();
         let mut inline_ops = Vec::new();
+        let asm_span = self.instr_span(instr_val).unwrap_or_else(Self::empty_span);
         for AsmArg { name, initializer } in asm_args {
             assert_or_warn!(
                 ConstantRegister::parse_register_name(name.as_str()).is_none(),
                 warnings,
-                Self::empty_span(),
+                name.span().clone(),
                 Warning::ShadowingReservedRegister {
                     reg_name: name.clone()
                 }
@@ -449,7 +1000,7 @@ impl<'ir> AsmBuilder<'ir> {
                 .map(|reg_name| -> Result<_, CompileError> {
                     realize_register(reg_name.as_str()).ok_or_else(|| {
                         CompileError::UnknownRegister {
-                            span: Self::empty_span(),
+                            span: reg_name.span().clone(),
                             initialized_registers: inline_reg_map
                                 .iter()
                                 .map(|(name, _)| *name)
@@ -473,7 +1024,7 @@ impl<'ir> AsmBuilder<'ir> {
                     &op.name,
                     &replaced_registers,
                     &op.immediate,
-                    Self::empty_span(), // Whole op span.
+                    asm_span.clone(), // Whole op span.
                 ),
                 return err(warnings, errors),
                 warnings,
@@ -495,7 +1046,7 @@ impl<'ir> AsmBuilder<'ir> {
                 Some(reg) => reg,
                 None => {
                     errors.push(CompileError::UnknownRegister {
-                        span: Self::empty_span(),
+                        span: asm_span,
                         initialized_registers: inline_reg_map
                             .iter()
                             .map(|(name, _)| name.to_string())
@@ -519,6 +1070,31 @@ impl<'ir> AsmBuilder<'ir> {
         ok((), warnings, errors)
     }
 
+    fn compile_binary_op(
+        &mut self,
+        instr_val: &Value,
+        op: &BinaryOpKind,
+        arg1: &Value,
+        arg2: &Value,
+    ) {
+        let lhs_reg = self.value_to_register(arg1);
+        let rhs_reg = self.value_to_register(arg2);
+        let nwords = self.int_size_in_words(arg1);
+        let span = self.instr_span(instr_val);
+        let result_ptr = self.result_memory(instr_val);
+        let (mut ops, instr_reg) = int_ops::compile_binary_op(
+            &mut self.reg_seqr,
+            *op,
+            nwords,
+            lhs_reg,
+            rhs_reg,
+            result_ptr,
+            span,
+        );
+        self.bytecode.append(&mut ops);
+        self.reg_map.insert(*instr_val, instr_reg);
+    }
+
     fn compile_branch(&mut self, from_block: &Block, to_block: &Block) {
         self.compile_branch_to_phi_value(from_block, to_block);
 
@@ -526,6 +1102,127 @@ impl<'ir> AsmBuilder<'ir> {
         self.bytecode.push(Op::jump_to_label(label));
     }
 
+    fn compile_call(
+        &mut self,
+        instr_val: &Value,
+        function: &Function,
+        args: &[Value],
+    ) -> CompileResult<()> {
+        // Get the args into registers first, as this may load constants.
+        let arg_regs = args
+            .iter()
+            .map(|arg| self.value_to_register(arg))
+            .collect::<Vec<_>>();
+
+        let save_idx = self.bytecode.len();
+        let return_label = self.reg_seqr.get_label();
+        let call_info = self.get_call_info(function);
+        let call_site_idx = call_info.return_labels.len();
+        call_info.return_labels.push(return_label.clone());
+        let span = self.instr_span(instr_val).unwrap_or_else(Self::empty_span);
+        let call_site_imm = match VirtualImmediate12::new(call_site_idx as u64, span.clone()) {
+            Ok(imm) => imm,
+            Err(error) => return err(Vec::new(), vec![error]),
+        };
+        let call_info = &self.call_infos[function];
+        let callee_label = call_info.label.clone();
+        let callee_arg_regs = call_info.arg_regs.clone();
+        let ret_addr_reg = call_info.ret_addr_reg.clone();
+        let ret_val_reg = call_info.ret_val_reg.clone();
+
+        // A recursive call may pass the callee's own args in a different order, in which case
+        // they're copied via temporaries so that none is overwritten before it is read.
+        let arg_regs = if arg_regs.iter().any(|reg| callee_arg_regs.contains(reg)) {
+            arg_regs
+                .into_iter()
+                .map(|arg_reg| {
+                    let tmp_reg = self.reg_seqr.next();
+                    self.bytecode
+                        .push(Op::unowned_register_move(tmp_reg.clone(), arg_reg));
+                    tmp_reg
+                })
+                .collect()
+        } else {
+            arg_regs
+        };
+        for (callee_arg_reg, arg_reg) in callee_arg_regs.iter().zip(arg_regs) {
+            self.bytecode
+                .push(Op::unowned_register_move(callee_arg_reg.clone(), arg_reg));
+        }
+        self.bytecode.push(Op {
+            opcode: Either::Left(VirtualOp::ADDI(
+                ret_addr_reg.clone(),
+                VirtualRegister::Constant(ConstantRegister::Zero),
+                call_site_imm,
+            )),
+            comment: "set return address".into(),
+            owning_span: None,
+        });
+
+        let call_idx = self.bytecode.len();
+        let mut call_regs = callee_arg_regs;
+        call_regs.push(ret_addr_reg);
+        self.bytecode.push(Op::call_comment(
+            callee_label,
+            call_regs,
+            ret_val_reg.clone(),
+            format!("call {}", function.get_name(self.context)),
+        ));
+        self.bytecode.push(Op::unowned_jump_label(return_label));
+
+        let ret_reg = self.reg_seqr.next();
+        self.bytecode
+            .push(Op::unowned_register_move(ret_reg.clone(), ret_val_reg));
+
+        self.call_sites.push(CallSite {
+            save_idx,
+            call_idx,
+            restore_idx: self.bytecode.len(),
+        });
+
+        // A value larger than a word is returned as a pointer into the callee's frame, which has
+        // been freed.  Nothing has been written to the stack since, so it's copied into our own
+        // memory for it straight after the call sequence.
+        let instr_reg = match self.result_memory(instr_val) {
+            Some(instr_reg) => {
+                let ret_type = self.context.functions[function.0].return_type;
+                let size_in_bytes = self.ir_type_size_in_bytes(&ret_type);
+                let size_imm = match VirtualImmediate12::new(size_in_bytes, span) {
+                    Ok(imm) => imm,
+                    Err(error) => return err(Vec::new(), vec![error]),
+                };
+                self.bytecode.push(Op {
+                    opcode: Either::Left(VirtualOp::MCPI(instr_reg.clone(), ret_reg, size_imm)),
+                    comment: "copy return value".into(),
+                    owning_span: None,
+                });
+                instr_reg
+            }
+            None => ret_reg,
+        };
+        self.reg_map.insert(*instr_val, instr_reg);
+        ok((), Vec::new(), Vec::new())
+    }
+
+    fn compile_cmp(&mut self, instr_val: &Value, pred: &Predicate, lhs: &Value, rhs: &Value) {
+        let lhs_reg = self.value_to_register(lhs);
+        let rhs_reg = self.value_to_register(rhs);
+        let nwords = self.int_size_in_words(lhs);
+        let span = self.instr_span(instr_val);
+        let (mut ops, instr_reg) =
+            int_ops::compile_cmp(&mut self.reg_seqr, *pred, nwords, lhs_reg, rhs_reg, span);
+        self.bytecode.append(&mut ops);
+        self.reg_map.insert(*instr_val, instr_reg);
+    }
+
+    // Integers wider than a word are in memory, and their values are pointers.
+    fn int_size_in_words(&mut self, value: &Value) -> u64 {
+        value
+            .get_type(self.context)
+            .map(|ty| size_bytes_in_words!(self.ir_type_size_in_bytes(&ty)))
+            .unwrap_or(1)
+    }
+
     fn compile_conditional_branch(
         &mut self,
         cond_value: &Value,
@@ -533,26 +1230,88 @@ impl<'ir> AsmBuilder<'ir> {
         true_block: &Block,
         false_block: &Block,
     ) {
-        self.compile_branch_to_phi_value(from_block, true_block);
-        self.compile_branch_to_phi_value(from_block, false_block);
-
         let cond_reg = self.value_to_register(cond_value);
-
+        let true_label = self.block_to_label(true_block);
         let false_label = self.block_to_label(false_block);
-        self.bytecode.push(Op::jump_if_not_equal(
-            cond_reg,
-            VirtualRegister::Constant(ConstantRegister::One),
-            false_label,
-        ));
 
-        let true_label = self.block_to_label(true_block);
-        self.bytecode.push(Op::jump_to_label(true_label));
+        // The phi values for each destination are only set once we know it's the one taken, else
+        // they could clobber a value which is still needed along the other edge.  If both need
+        // them then the false edge gets its own label to set them at.
+        let has_phi_vals = |block: &Block| {
+            !block
+                .get_phi_vals_coming_from(self.context, from_block)
+                .is_empty()
+        };
+        match (has_phi_vals(true_block), has_phi_vals(false_block)) {
+            (false, true) => {
+                self.bytecode.push(Op::jump_if_not_equal(
+                    cond_reg,
+                    VirtualRegister::Constant(ConstantRegister::Zero),
+                    true_label,
+                ));
+                self.compile_branch_to_phi_value(from_block, false_block);
+                self.bytecode.push(Op::jump_to_label(false_label));
+            }
+            (true, true) => {
+                let false_edge_label = self.reg_seqr.get_label();
+                self.bytecode.push(Op::jump_if_not_equal(
+                    cond_reg,
+                    VirtualRegister::Constant(ConstantRegister::One),
+                    false_edge_label.clone(),
+                ));
+                // Constants loaded for the true edge aren't loaded along the false edge.
+                let const_regs = self.block_const_reg_map.clone();
+                self.compile_branch_to_phi_value(from_block, true_block);
+                self.bytecode.push(Op::jump_to_label(true_label));
+                self.bytecode.push(Op::unowned_jump_label(false_edge_label));
+                self.block_const_reg_map = const_regs;
+                self.compile_branch_to_phi_value(from_block, false_block);
+                self.bytecode.push(Op::jump_to_label(false_label));
+            }
+            (_, false) => {
+                self.bytecode.push(Op::jump_if_not_equal(
+                    cond_reg,
+                    VirtualRegister::Constant(ConstantRegister::One),
+                    false_label,
+                ));
+                self.compile_branch_to_phi_value(from_block, true_block);
+                self.bytecode.push(Op::jump_to_label(true_label));
+            }
+        }
     }
 
     fn compile_branch_to_phi_value(&mut self, from_block: &Block, to_block: &Block) {
-        if let Some(local_val) = to_block.get_phi_val_coming_from(self.context, from_block) {
-            let local_reg = self.value_to_register(&local_val);
-            let phi_reg = self.value_to_register(&to_block.get_phi(self.context));
+        let moves = to_block
+            .get_phi_vals_coming_from(self.context, from_block)
+            .into_iter()
+            .map(|(phi_val, local_val)| {
+                (
+                    self.value_to_register(&phi_val),
+                    self.value_to_register(&local_val),
+                )
+            })
+            .filter(|(phi_reg, local_reg)| phi_reg != local_reg)
+            .collect::<Vec<_>>();
+
+        // The phis are all set at once, so if one phi is set to the value of another it's copied
+        // via a temporary so that it isn't overwritten before it is read.
+        let moves = if moves
+            .iter()
+            .any(|(_, local_reg)| moves.iter().any(|(phi_reg, _)| phi_reg == local_reg))
+        {
+            moves
+                .into_iter()
+                .map(|(phi_reg, local_reg)| {
+                    let tmp_reg = self.reg_seqr.next();
+                    self.bytecode
+                        .push(Op::unowned_register_move(tmp_reg.clone(), local_reg));
+                    (phi_reg, tmp_reg)
+                })
+                .collect()
+        } else {
+            moves
+        };
+        for (phi_reg, local_reg) in moves {
             self.bytecode
                 .push(Op::register_move(phi_reg, local_reg, Self::empty_span()));
         }
@@ -577,18 +1336,23 @@ impl<'ir> AsmBuilder<'ir> {
         let instr_reg = self.reg_seqr.next();
         let elem_size = self.ir_type_size_in_bytes(&ty.get_elem_type(self.context).unwrap());
         if elem_size <= 8 {
+            // The offset is calculated in a new register, as the index may still be needed.
+            let elem_offs_reg = self.reg_seqr.next();
             self.bytecode.push(Op {
                 opcode: Either::Left(VirtualOp::MULI(
-                    index_reg.clone(),
-                    index_reg.clone(),
+                    elem_offs_reg.clone(),
+                    index_reg,
                     VirtualImmediate12 { value: 8 },
                 )),
                 comment: "extract_element relative offset".into(),
                 owning_span: None,
             });
-            let elem_offs_reg = self.reg_seqr.next();
             self.bytecode.push(Op {
-                opcode: Either::Left(VirtualOp::ADD(elem_offs_reg.clone(), base_reg, index_reg)),
+                opcode: Either::Left(VirtualOp::ADD(
+                    elem_offs_reg.clone(),
+                    base_reg,
+                    elem_offs_reg.clone(),
+                )),
                 comment: "extract_element absolute offset".into(),
                 owning_span: None,
             });
@@ -717,21 +1481,22 @@ impl<'ir> AsmBuilder<'ir> {
 
         let elem_size = self.ir_type_size_in_bytes(&ty.get_elem_type(self.context).unwrap());
         if elem_size <= 8 {
+            // The offset is calculated in a new register, as the index may still be needed.
+            let elem_offs_reg = self.reg_seqr.next();
             self.bytecode.push(Op {
                 opcode: Either::Left(VirtualOp::MULI(
-                    index_reg.clone(),
-                    index_reg.clone(),
+                    elem_offs_reg.clone(),
+                    index_reg,
                     VirtualImmediate12 { value: 8 },
                 )),
                 comment: "insert_element relative offset".into(),
                 owning_span: None,
             });
-            let elem_offs_reg = self.reg_seqr.next();
             self.bytecode.push(Op {
                 opcode: Either::Left(VirtualOp::ADD(
                     elem_offs_reg.clone(),
                     base_reg.clone(),
-                    index_reg,
+                    elem_offs_reg.clone(),
                 )),
                 comment: "insert_element absolute offset".into(),
                 owning_span: None,
@@ -895,7 +1660,23 @@ impl<'ir> AsmBuilder<'ir> {
 
     // XXX This is copied from ret_or_retd_value() above, streamlined for IR types.
     fn compile_ret(&mut self, ret_val: &Value, ret_type: &Type) {
-        if ret_type == &Type::Unit {
+        if let Some(function) = self.current_callee {
+            // Called functions return their value in a register and jump to their epilogue.
+            let call_info = &self.call_infos[&function];
+            let ret_val_reg = call_info.ret_val_reg.clone();
+            let epilogue_label = call_info.epilogue_label.clone();
+            let ret_reg = if ret_type == &Type::Unit {
+                VirtualRegister::Constant(ConstantRegister::Zero)
+            } else {
+                self.value_to_register(ret_val)
+            };
+            self.bytecode.push(Op::unowned_register_move_comment(
+                ret_val_reg,
+                ret_reg,
+                "set return value",
+            ));
+            self.bytecode.push(Op::jump_to_label(epilogue_label));
+        } else if ret_type == &Type::Unit {
             // Unit returns should always be zero, although because they can be omitted from
             // functions, the register is sometimes uninitialized. Manually return zero in this
             // case.
@@ -939,6 +1720,250 @@ impl<'ir> AsmBuilder<'ir> {
         }
     }
 
+    fn compile_state_load(
+        &mut self,
+        instr_val: &Value,
+        key: &Value,
+        ty: &Type,
+    ) -> CompileResult<()> {
+        let instr_reg = match ty {
+            Type::Unit | Type::Bool | Type::Uint(_) if !ty.is_wide_uint() => {
+                // A single word value can be read straight into a register with SRW.
+                let instr_reg = self.reg_seqr.next();
+                let key_reg = self.value_to_register(key);
+                self.bytecode.push(Op {
+                    opcode: Either::Left(VirtualOp::SRW(instr_reg.clone(), key_reg)),
+                    comment: "load word from storage".into(),
+                    owning_span: None,
+                });
+                instr_reg
+            }
+            _otherwise => {
+                // Anything else is read into the stack memory reserved for it a quad word at a
+                // time with SRWQ, and the value is a pointer to that memory.
+                let size_in_words = size_bytes_in_words!(self.ir_type_size_in_bytes(ty));
+                let key_regs = match self.storage_chunk_key_registers(key, size_in_words) {
+                    Some(key_regs) => key_regs,
+                    None => {
+                        return err(
+                            Vec::new(),
+                            vec![CompileError::Internal(
+                                "Storage keys for values larger than a word must be constant.",
+                                Self::empty_span(),
+                            )],
+                        )
+                    }
+                };
+                let instr_reg = self.result_memory(instr_val).unwrap();
+                for (chunk_idx, key_reg) in key_regs.into_iter().enumerate() {
+                    let dest_reg = self.offset_register(&instr_reg, chunk_idx as u64 * 32);
+                    self.bytecode.push(Op {
+                        opcode: Either::Left(VirtualOp::SRWQ(dest_reg, key_reg)),
+                        comment: "load quad word from storage".into(),
+                        owning_span: None,
+                    });
+                }
+                instr_reg
+            }
+        };
+        self.reg_map.insert(*instr_val, instr_reg);
+        ok((), Vec::new(), Vec::new())
+    }
+
+    fn compile_state_store(&mut self, key: &Value, stored_val: &Value) -> CompileResult<()> {
+        let stored_reg = self.value_to_register(stored_val);
+        // Instructions which build or point to an aggregate, e.g., a struct expression, have no
+        // type of their own, so it's taken from their aggregate.
+        let stored_ty = stored_val.get_type(self.context).or_else(|| {
+            match &self.context.values[stored_val.0] {
+                ValueContent::Instruction(instruction) => instruction
+                    .get_aggregate(self.context)
+                    .map(|aggregate| match &self.context.aggregates[aggregate.0] {
+                        AggregateContent::FieldTypes(_) => Type::Struct(aggregate),
+                        AggregateContent::ArrayType(..) => Type::Array(aggregate),
+                    }),
+                _otherwise => None,
+            }
+        });
+        match stored_ty {
+            Some(ty @ (Type::Unit | Type::Bool | Type::Uint(_))) if !ty.is_wide_uint() => {
+                // A single word value is written straight from its register with SWW.
+                let key_reg = self.value_to_register(key);
+                self.bytecode.push(Op {
+                    opcode: Either::Left(VirtualOp::SWW(key_reg, stored_reg)),
+                    comment: "store word to storage".into(),
+                    owning_span: None,
+                });
+            }
+            Some(ty) => {
+                // Anything else is in memory, pointed to by the stored value, and is written a
+                // quad word at a time with SWWQ.
+                let size_in_bytes = self.ir_type_size_in_bytes(&ty);
+                let size_in_words = size_bytes_in_words!(size_in_bytes);
+                let key_regs = match self.storage_chunk_key_registers(key, size_in_words) {
+                    Some(key_regs) => key_regs,
+                    None => {
+                        return err(
+                            Vec::new(),
+                            vec![CompileError::Internal(
+                                "Storage keys for values larger than a word must be constant.",
+                                Self::empty_span(),
+                            )],
+                        )
+                    }
+                };
+                for (chunk_idx, key_reg) in key_regs.into_iter().enumerate() {
+                    let chunk_offset = chunk_idx as u64 * 32;
+                    let src_reg = self.offset_register(&stored_reg, chunk_offset);
+                    if size_in_bytes - chunk_offset >= 32 {
+                        self.bytecode.push(Op {
+                            opcode: Either::Left(VirtualOp::SWWQ(key_reg, src_reg)),
+                            comment: "store quad word to storage".into(),
+                            owning_span: None,
+                        });
+                    } else {
+                        self.compile_padded_state_store(
+                            key_reg,
+                            src_reg,
+                            size_in_bytes - chunk_offset,
+                        );
+                    }
+                }
+            }
+            None => {
+                return err(
+                    Vec::new(),
+                    vec![CompileError::Internal(
+                        "Value stored to storage has no type.",
+                        Self::empty_span(),
+                    )],
+                )
+            }
+        }
+        ok((), Vec::new(), Vec::new())
+    }
+
+    // The last chunk of a value may be shorter than a quad word, so it's copied into a zeroed quad
+    // word on the stack and stored from there, rather than SWWQ reading past the end of the value.
+    fn compile_padded_state_store(
+        &mut self,
+        key_reg: VirtualRegister,
+        src_reg: VirtualRegister,
+        len_in_bytes: u64,
+    ) {
+        let pad_reg = self.reg_seqr.next();
+        self.bytecode.push(Op::register_move(
+            pad_reg.clone(),
+            VirtualRegister::Constant(ConstantRegister::StackPointer),
+            Self::empty_span(),
+        ));
+        self.bytecode.push(Op::unowned_stack_allocate_memory(
+            VirtualImmediate24::new(32, Self::empty_span()).unwrap(),
+        ));
+        self.bytecode.push(Op {
+            opcode: Either::Left(VirtualOp::MCLI(
+                pad_reg.clone(),
+                VirtualImmediate18::new(32, Self::empty_span()).unwrap(),
+            )),
+            comment: "zero quad word for padding".into(),
+            owning_span: None,
+        });
+        self.bytecode.push(Op {
+            opcode: Either::Left(VirtualOp::MCPI(
+                pad_reg.clone(),
+                src_reg,
+                VirtualImmediate12::new(len_in_bytes, Self::empty_span()).unwrap(),
+            )),
+            comment: "copy last chunk into padding".into(),
+            owning_span: None,
+        });
+        self.bytecode.push(Op {
+            opcode: Either::Left(VirtualOp::SWWQ(key_reg, pad_reg)),
+            comment: "store padded quad word to storage".into(),
+            owning_span: None,
+        });
+        self.bytecode.push(Op {
+            opcode: Either::Left(VirtualOp::CFSI(
+                VirtualImmediate24::new(32, Self::empty_span()).unwrap(),
+            )),
+            comment: "free padding".into(),
+            owning_span: None,
+        });
+    }
+
+    // Each 32 byte chunk of a value in storage has its own key, derived from the key of the first
+    // chunk.  We can only derive them at compile time, so the base key must be a constant.
+    fn storage_chunk_key_registers(
+        &mut self,
+        key: &Value,
+        size_in_words: u64,
+    ) -> Option<Vec<VirtualRegister>> {
+        let base_key = match &self.context.values[key.0] {
+            ValueContent::Constant(Constant {
+                value: ConstantValue::B256(bytes),
+                ..
+            }) => *bytes,
+            _otherwise => return None,
+        };
+        Some(
+            get_storage_chunk_keys(&base_key, size_in_words)
+                .into_iter()
+                .map(|chunk_key| {
+                    let data_id = self
+                        .data_section
+                        .insert_data_value(&Literal::B256(chunk_key));
+                    let key_reg = self.reg_seqr.next();
+                    self.bytecode.push(Op {
+                        opcode: Either::Left(VirtualOp::LWDataId(key_reg.clone(), data_id)),
+                        comment: "storage key".into(),
+                        owning_span: None,
+                    });
+                    key_reg
+                })
+                .collect(),
+        )
+    }
+
+    // Return a register holding `base_reg + offset_in_bytes`, or `base_reg` itself if the offset
+    // is zero.
+    fn offset_register(
+        &mut self,
+        base_reg: &VirtualRegister,
+        offset_in_bytes: u64,
+    ) -> VirtualRegister {
+        if offset_in_bytes == 0 {
+            base_reg.clone()
+        } else {
+            let offset_reg = self.reg_seqr.next();
+            self.bytecode.push(Op {
+                opcode: Either::Left(VirtualOp::ADDI(
+                    offset_reg.clone(),
+                    base_reg.clone(),
+                    VirtualImmediate12::new(offset_in_bytes, Self::empty_span()).unwrap(),
+                )),
+                comment: "get offset".into(),
+                owning_span: None,
+            });
+            offset_reg
+        }
+    }
+
+    // A pointer to the stack memory reserved by add_locals() for `value`, if any.
+    fn result_memory(&mut self, value: &Value) -> Option<VirtualRegister> {
+        let word_offs = *self.result_memory_map.get(value)?;
+        let ptr_reg = self.reg_seqr.next();
+        self.bytecode.push(Op {
+            opcode: Either::Left(VirtualOp::ADDI(
+                ptr_reg.clone(),
+                self.stack_base_reg.as_ref().unwrap().clone(),
+                VirtualImmediate12::new(word_offs * 8, Self::empty_span()).unwrap(),
+            )),
+            comment: "get result memory".into(),
+            owning_span: None,
+        });
+        Some(ptr_reg)
+    }
+
     fn compile_store(&mut self, ptr: &Pointer, stored_val: &Value) {
         let stored_reg = self.value_to_register(stored_val);
         let is_struct_ptr = ptr.is_struct_ptr(self.context);
@@ -1025,7 +2050,11 @@ impl<'ir> AsmBuilder<'ir> {
     }
 
     fn value_to_register(&mut self, value: &Value) -> VirtualRegister {
-        match self.reg_map.get(value) {
+        match self
+            .reg_map
+            .get(value)
+            .or_else(|| self.block_const_reg_map.get(value))
+        {
             Some(reg) => reg.clone(),
             None => {
                 match &self.context.values[value.0] {
@@ -1033,43 +2062,27 @@ impl<'ir> AsmBuilder<'ir> {
                     ValueContent::Constant(constant) => {
                         match &constant.value {
                             ConstantValue::Struct(_) | ConstantValue::Array(_) => {
-                                // A constant struct or array.  We still allocate space for it on
-                                // the stack, but create the field or element initialisers
-                                // recursively.
-
-                                // Get the total size.
-                                let total_size = size_bytes_round_up_to_word_alignment!(
-                                    self.constant_size_in_bytes(constant)
-                                );
-
-                                let start_reg = self.reg_seqr.next();
-
-                                // We can have zero sized structs and maybe arrays?
-                                if total_size > 0 {
-                                    // Save the stack pointer.
-                                    self.bytecode.push(Op::register_move(
-                                        start_reg.clone(),
-                                        VirtualRegister::Constant(ConstantRegister::StackPointer),
-                                        Self::empty_span(),
-                                    ));
-
-                                    self.bytecode.push(Op::unowned_stack_allocate_memory(
-                                        VirtualImmediate24::new(total_size, Self::empty_span())
-                                            .unwrap(),
-                                    ));
-
-                                    // Fill in the fields.
-                                    self.initialise_constant_memory(constant, &start_reg, 0);
+                                // A constant struct or array.  It is in the stack memory reserved
+                                // for it by add_locals(), but create the field or element
+                                // initialisers recursively.
+                                match self.result_memory(value) {
+                                    Some(start_reg) => {
+                                        // Fill in the fields.
+                                        self.initialise_constant_memory(constant, &start_reg, 0);
+
+                                        // Return the start ptr.
+                                        start_reg
+                                    }
+                                    // We can have zero sized structs and maybe arrays?
+                                    None => self.reg_seqr.next(),
                                 }
-
-                                // Return the start ptr.
-                                start_reg
                             }
 
                             ConstantValue::Undef
                             | ConstantValue::Unit
                             | ConstantValue::Bool(_)
                             | ConstantValue::Uint(_)
+                            | ConstantValue::WideUint(_)
                             | ConstantValue::B256(_)
                             | ConstantValue::String(_) => {
                                 // Get the constant into the namespace.
@@ -1087,8 +2100,8 @@ impl<'ir> AsmBuilder<'ir> {
                                     owning_span: None,
                                 });
 
-                                // Insert the value into the map.
-                                self.reg_map.insert(*value, reg.clone());
+                                // Insert the value into the map for this block.
+                                self.block_const_reg_map.insert(*value, reg.clone());
 
                                 // Return register.
                                 reg
@@ -1113,6 +2126,7 @@ impl<'ir> AsmBuilder<'ir> {
             ConstantValue::Unit => 8,
             ConstantValue::Bool(_) => 8,
             ConstantValue::Uint(_) => 8,
+            ConstantValue::WideUint(bytes) => bytes.len() as u64,
             ConstantValue::B256(_) => 32,
             ConstantValue::String(s) => s.len() as u64, // String::len() returns the byte size, not char count.
             ConstantValue::Array(elems) => {
@@ -1140,6 +2154,31 @@ impl<'ir> AsmBuilder<'ir> {
                 // field size in words.
                 size_bytes_in_words!(self.ir_type_size_in_bytes(&constant.ty))
             }
+            ConstantValue::WideUint(bytes) => {
+                // The data section gives us a pointer to a wide integer, which we copy in.
+                let lit = ir_constant_to_ast_literal(constant);
+                let data_id = self.data_section.insert_data_value(&lit);
+                let init_reg = self.reg_seqr.next();
+                self.bytecode.push(Op {
+                    opcode: either::Either::Left(VirtualOp::LWDataId(init_reg.clone(), data_id)),
+                    comment: "literal instantiation for aggregate field".into(),
+                    owning_span: None,
+                });
+                let dest_reg = self.offset_register(start_reg, offs_in_words * 8);
+                self.bytecode.push(Op {
+                    opcode: Either::Left(VirtualOp::MCPI(
+                        dest_reg,
+                        init_reg,
+                        VirtualImmediate12::new(bytes.len() as u64, Self::empty_span()).unwrap(),
+                    )),
+                    comment: format!(
+                        "initialise aggregate field at stack offset {}",
+                        offs_in_words
+                    ),
+                    owning_span: None,
+                });
+                bytes.len() as u64 / 8
+            }
             ConstantValue::Unit
             | ConstantValue::Bool(_)
             | ConstantValue::Uint(_)
@@ -1185,7 +2224,7 @@ impl<'ir> AsmBuilder<'ir> {
                     let item_size = self.initialise_constant_memory(item, start_reg, cur_offs);
                     cur_offs += item_size;
                 }
-                cur_offs
+                cur_offs - offs_in_words
             }
         }
     }
@@ -1289,6 +2328,7 @@ impl<'ir> AsmBuilder<'ir> {
 
     fn ir_type_size_in_bytes(&mut self, ty: &Type) -> u64 {
         match ty {
+            Type::Uint(nbits) if ty.is_wide_uint() => *nbits as u64 / 8,
             Type::Unit | Type::Bool | Type::Uint(_) => 8,
             Type::B256 => 32,
             Type::String(n) => *n,
@@ -1317,6 +2357,18 @@ fn ir_constant_to_ast_literal(constant: &Constant) -> Literal {
         ConstantValue::Unit => Literal::U64(0), // No unit.
         ConstantValue::Bool(b) => Literal::Boolean(*b),
         ConstantValue::Uint(n) => Literal::U64(*n),
+        ConstantValue::WideUint(bs) => match bs.len() {
+            16 => {
+                let mut be_bytes = [0; 16];
+                be_bytes.copy_from_slice(bs);
+                Literal::U128(u128::from_be_bytes(be_bytes))
+            }
+            _otherwise => {
+                let mut be_bytes = [0; 32];
+                be_bytes.copy_from_slice(bs);
+                Literal::U256(be_bytes)
+            }
+        },
         ConstantValue::B256(bs) => Literal::B256(*bs),
         ConstantValue::String(_) => Literal::String(crate::span::Span {
             span: pest::Span::new(
@@ -1385,6 +2437,9 @@ mod tests {
                 print_intermediate_asm: false,
                 print_finalized_asm: false,
                 print_ir: false,
+                print_peephole_savings: false,
+                optimize_asm: true,
+                ir_opt_level: 1,
             },
         );
 
@@ -0,0 +1,696 @@
+//! Integer arithmetic and comparisons for the builtin operators, shared by both code generation
+//! paths.
+//!
+//! An integer which fits in a word is in a register and needs just the one op.  Like a `b256`, a
+//! wide integer is kept in memory as big-endian words and a value of its type is a pointer to that
+//! memory.  The result of an operation on wide integers is written to memory given by the caller,
+//! which is reserved once in each function's frame by the IR backend and once for the whole program
+//! by the legacy one, so an op which runs repeatedly doesn't grow the stack.  Any scratch memory
+//! needed by an operation is freed again once it is done.
+//!
+//! The VM's arithmetic ops wrap on overflow, so the carries and borrows between words are found by
+//! comparing the result of each word operation with its operands.
+//!
+//! Signed integers are at most a word and are sign extended to fill their register, so most
+//! operations on them are the same as for unsigned integers.  The VM has no signed division or
+//! comparison though, so these work on the magnitudes of their operands or flip their sign bits.
+
+use crate::asm_generation::RegisterSequencer;
+use crate::asm_lang::{
+    virtual_register::*, Op, OrganizationalOp, VirtualImmediate12, VirtualImmediate18,
+    VirtualImmediate24, VirtualOp,
+};
+
+use sway_ir::{BinaryOpKind, Predicate};
+use sway_types::span::Span;
+
+use either::Either;
+
+/// The size of the memory needed for the result of `op` on integers of `nwords` words.
+pub(crate) fn result_size_in_words(op: BinaryOpKind, nwords: u64) -> u64 {
+    match op {
+        _ if nwords == 1 => 0,
+        // The quotient and remainder are computed together.
+        BinaryOpKind::Div | BinaryOpKind::Mod => nwords * 2,
+        _ => nwords,
+    }
+}
+
+/// Compiles `lhs op rhs` for integers of `nwords` words, returning the ops and the register
+/// which will hold the result, or a pointer to it if it is wider than a word.
+///
+/// For a wide result `result_ptr` must point to memory of at least `result_size_in_words()` words.
+pub(crate) fn compile_binary_op(
+    reg_seqr: &mut RegisterSequencer,
+    op: BinaryOpKind,
+    nwords: u64,
+    lhs: VirtualRegister,
+    rhs: VirtualRegister,
+    result_ptr: Option<VirtualRegister>,
+    span: Option<Span>,
+) -> (Vec<Op>, VirtualRegister) {
+    let mut builder = IntOpBuilder::new(reg_seqr, nwords, span);
+    builder.result_ptr = result_ptr;
+    let result = if nwords == 1 {
+        match op {
+            BinaryOpKind::Add => builder.binary(VirtualOp::ADD, &lhs, &rhs),
+            BinaryOpKind::Sub => builder.binary(VirtualOp::SUB, &lhs, &rhs),
+            BinaryOpKind::Mul => builder.binary(VirtualOp::MUL, &lhs, &rhs),
+            BinaryOpKind::Div => builder.binary(VirtualOp::DIV, &lhs, &rhs),
+            BinaryOpKind::Mod => builder.binary(VirtualOp::MOD, &lhs, &rhs),
+            BinaryOpKind::And => builder.binary(VirtualOp::AND, &lhs, &rhs),
+            BinaryOpKind::Or => builder.binary(VirtualOp::OR, &lhs, &rhs),
+            BinaryOpKind::Xor => builder.binary(VirtualOp::XOR, &lhs, &rhs),
+            BinaryOpKind::SDiv => builder.signed_div_mod(&lhs, &rhs, true),
+            BinaryOpKind::SMod => builder.signed_div_mod(&lhs, &rhs, false),
+            BinaryOpKind::Shl => builder.binary(VirtualOp::SLL, &lhs, &rhs),
+            BinaryOpKind::Shr => builder.binary(VirtualOp::SRL, &lhs, &rhs),
+            BinaryOpKind::Sar => builder.sar(&lhs, &rhs),
+        }
+    } else {
+        match op {
+            BinaryOpKind::Add => builder.add(&lhs, &rhs),
+            BinaryOpKind::Sub => builder.sub(&lhs, &rhs),
+            BinaryOpKind::Mul => builder.mul(&lhs, &rhs),
+            BinaryOpKind::Div => builder.div_mod(&lhs, &rhs, true),
+            BinaryOpKind::Mod => builder.div_mod(&lhs, &rhs, false),
+            BinaryOpKind::And => builder.bitwise(&lhs, &rhs, VirtualOp::AND),
+            BinaryOpKind::Or => builder.bitwise(&lhs, &rhs, VirtualOp::OR),
+            BinaryOpKind::Xor => builder.bitwise(&lhs, &rhs, VirtualOp::XOR),
+            BinaryOpKind::Shl => builder.shift(&lhs, &rhs, true),
+            BinaryOpKind::Shr => builder.shift(&lhs, &rhs, false),
+            BinaryOpKind::SDiv | BinaryOpKind::SMod | BinaryOpKind::Sar => {
+                unreachable!("Signed integers are never wider than a word.")
+            }
+        }
+    };
+    builder.comment_last(&format!("builtin {}", op.as_str()));
+    (builder.ops, result)
+}
+
+/// Compiles the comparison `lhs pred rhs` for integers of `nwords` words, returning the ops and
+/// the register which will hold the resulting `bool`.
+pub(crate) fn compile_cmp(
+    reg_seqr: &mut RegisterSequencer,
+    pred: Predicate,
+    nwords: u64,
+    lhs: VirtualRegister,
+    rhs: VirtualRegister,
+    span: Option<Span>,
+) -> (Vec<Op>, VirtualRegister) {
+    let mut builder = IntOpBuilder::new(reg_seqr, nwords, span);
+    let result = match pred {
+        Predicate::Equal => builder.eq(&lhs, &rhs),
+        Predicate::NotEqual => {
+            let eq = builder.eq(&lhs, &rhs);
+            builder.not(eq)
+        }
+        Predicate::LessThan => builder.lt(&lhs, &rhs),
+        Predicate::GreaterThan => builder.lt(&rhs, &lhs),
+        Predicate::LessThanEqual => {
+            let gt = builder.lt(&rhs, &lhs);
+            builder.not(gt)
+        }
+        Predicate::GreaterThanEqual => {
+            let lt = builder.lt(&lhs, &rhs);
+            builder.not(lt)
+        }
+        Predicate::SignedLessThan
+        | Predicate::SignedGreaterThan
+        | Predicate::SignedLessThanEqual
+        | Predicate::SignedGreaterThanEqual => {
+            // Flipping the sign bits orders two's complement integers as unsigned integers.
+            let sign_bit = builder.sign_bit();
+            let lhs = builder.binary(VirtualOp::XOR, &lhs, &sign_bit);
+            let rhs = builder.binary(VirtualOp::XOR, &rhs, &sign_bit);
+            match pred {
+                Predicate::SignedLessThan => builder.lt(&lhs, &rhs),
+                Predicate::SignedGreaterThan => builder.lt(&rhs, &lhs),
+                Predicate::SignedLessThanEqual => {
+                    let gt = builder.lt(&rhs, &lhs);
+                    builder.not(gt)
+                }
+                _ => {
+                    let lt = builder.lt(&lhs, &rhs);
+                    builder.not(lt)
+                }
+            }
+        }
+    };
+    builder.comment_last(&format!("builtin cmp {}", pred.as_str()));
+    (builder.ops, result)
+}
+
+struct IntOpBuilder<'a> {
+    reg_seqr: &'a mut RegisterSequencer,
+    nwords: u64,
+    span: Option<Span>,
+    result_ptr: Option<VirtualRegister>,
+    ops: Vec<Op>,
+}
+
+impl<'a> IntOpBuilder<'a> {
+    fn new(reg_seqr: &'a mut RegisterSequencer, nwords: u64, span: Option<Span>) -> Self {
+        IntOpBuilder {
+            reg_seqr,
+            nwords,
+            span,
+            result_ptr: None,
+            ops: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, opcode: VirtualOp) {
+        self.push_organizational(Either::Left(opcode));
+    }
+
+    fn push_organizational(&mut self, opcode: Either<VirtualOp, OrganizationalOp>) {
+        self.ops.push(Op {
+            opcode,
+            comment: String::new(),
+            owning_span: self.span.clone(),
+        });
+    }
+
+    fn comment_last(&mut self, comment: &str) {
+        if let Some(op) = self.ops.last_mut() {
+            op.comment = comment.into();
+        }
+    }
+
+    fn imm12(value: u64) -> VirtualImmediate12 {
+        VirtualImmediate12::new_unchecked(value, "wide integers are at most 256 bits")
+    }
+
+    fn zero() -> VirtualRegister {
+        VirtualRegister::Constant(ConstantRegister::Zero)
+    }
+
+    fn one() -> VirtualRegister {
+        VirtualRegister::Constant(ConstantRegister::One)
+    }
+
+    // The index of the `k`th least significant word.
+    fn word_idx(&self, k: u64) -> u64 {
+        self.nwords - 1 - k
+    }
+
+    fn size_in_bytes(&self) -> u64 {
+        self.nwords * 8
+    }
+
+    fn binary(
+        &mut self,
+        op: fn(VirtualRegister, VirtualRegister, VirtualRegister) -> VirtualOp,
+        lhs: &VirtualRegister,
+        rhs: &VirtualRegister,
+    ) -> VirtualRegister {
+        let result = self.reg_seqr.next();
+        self.push(op(result.clone(), lhs.clone(), rhs.clone()));
+        result
+    }
+
+    fn binary_imm(
+        &mut self,
+        op: fn(VirtualRegister, VirtualRegister, VirtualImmediate12) -> VirtualOp,
+        lhs: &VirtualRegister,
+        imm: u64,
+    ) -> VirtualRegister {
+        let result = self.reg_seqr.next();
+        self.push(op(result.clone(), lhs.clone(), Self::imm12(imm)));
+        result
+    }
+
+    fn not(&mut self, bool_reg: VirtualRegister) -> VirtualRegister {
+        self.binary_imm(VirtualOp::XORI, &bool_reg, 1)
+    }
+
+    fn sign_bit(&mut self) -> VirtualRegister {
+        self.binary_imm(VirtualOp::SLLI, &Self::one(), 63)
+    }
+
+    // A word with every bit set if `value` is negative, or zero otherwise.
+    fn sign_mask(&mut self, value: &VirtualRegister) -> VirtualRegister {
+        let sign = self.binary_imm(VirtualOp::SRLI, value, 63);
+        self.binary(VirtualOp::SUB, &Self::zero(), &sign)
+    }
+
+    // Negates `value` if `mask` has every bit set, leaving it as is if `mask` is zero.
+    fn negate_if(&mut self, value: &VirtualRegister, mask: &VirtualRegister) -> VirtualRegister {
+        let flipped = self.binary(VirtualOp::XOR, value, mask);
+        self.binary(VirtualOp::SUB, &flipped, mask)
+    }
+
+    fn alloc(&mut self, nwords: u64) -> VirtualRegister {
+        let ptr = self.reg_seqr.next();
+        self.push(VirtualOp::MOVE(
+            ptr.clone(),
+            VirtualRegister::Constant(ConstantRegister::StackPointer),
+        ));
+        self.push(VirtualOp::CFEI(VirtualImmediate24::new_unchecked(
+            nwords * 8,
+            "wide integers are at most 256 bits",
+        )));
+        ptr
+    }
+
+    fn free(&mut self, nwords: u64) {
+        self.push(VirtualOp::CFSI(VirtualImmediate24::new_unchecked(
+            nwords * 8,
+            "wide integers are at most 256 bits",
+        )));
+    }
+
+    // The memory for the result, which is given by the caller.
+    fn result_memory(&mut self) -> VirtualRegister {
+        self.result_ptr
+            .take()
+            .expect("memory for wide integer results is reserved by the caller")
+    }
+
+    fn load_word(&mut self, ptr: &VirtualRegister, idx: u64) -> VirtualRegister {
+        let word = self.reg_seqr.next();
+        self.push(VirtualOp::LW(word.clone(), ptr.clone(), Self::imm12(idx)));
+        word
+    }
+
+    fn store_word(&mut self, ptr: &VirtualRegister, idx: u64, word: &VirtualRegister) {
+        self.push(VirtualOp::SW(ptr.clone(), word.clone(), Self::imm12(idx)));
+    }
+
+    // The words of the integer at `ptr`, least significant first.
+    fn load_words(&mut self, ptr: &VirtualRegister) -> Vec<VirtualRegister> {
+        (0..self.nwords)
+            .map(|k| {
+                let idx = self.word_idx(k);
+                self.load_word(ptr, idx)
+            })
+            .collect()
+    }
+
+    // Returns `a + b + carry_in` and, if wanted, the carry out.
+    fn add_with_carry(
+        &mut self,
+        a: &VirtualRegister,
+        b: &VirtualRegister,
+        carry_in: Option<&VirtualRegister>,
+        want_carry: bool,
+    ) -> (VirtualRegister, Option<VirtualRegister>) {
+        let sum = self.binary(VirtualOp::ADD, a, b);
+        let carry = want_carry.then(|| self.binary(VirtualOp::LT, &sum, a));
+        match carry_in {
+            None => (sum, carry),
+            Some(carry_in) => {
+                let sum_with_carry = self.binary(VirtualOp::ADD, &sum, carry_in);
+                let carry = carry.map(|carry| {
+                    let carry_from_carry = self.binary(VirtualOp::LT, &sum_with_carry, &sum);
+                    self.binary(VirtualOp::OR, &carry, &carry_from_carry)
+                });
+                (sum_with_carry, carry)
+            }
+        }
+    }
+
+    // Returns `a - b - borrow_in` and, if wanted, the borrow out.
+    fn sub_with_borrow(
+        &mut self,
+        a: &VirtualRegister,
+        b: &VirtualRegister,
+        borrow_in: Option<&VirtualRegister>,
+        want_borrow: bool,
+    ) -> (VirtualRegister, Option<VirtualRegister>) {
+        let diff = self.binary(VirtualOp::SUB, a, b);
+        let borrow = want_borrow.then(|| self.binary(VirtualOp::LT, a, b));
+        match borrow_in {
+            None => (diff, borrow),
+            Some(borrow_in) => {
+                let diff_with_borrow = self.binary(VirtualOp::SUB, &diff, borrow_in);
+                let borrow = borrow.map(|borrow| {
+                    let borrow_from_borrow = self.binary(VirtualOp::LT, &diff, borrow_in);
+                    self.binary(VirtualOp::OR, &borrow, &borrow_from_borrow)
+                });
+                (diff_with_borrow, borrow)
+            }
+        }
+    }
+
+    fn add(&mut self, lhs: &VirtualRegister, rhs: &VirtualRegister) -> VirtualRegister {
+        let result = self.result_memory();
+        let mut carry = None;
+        for k in 0..self.nwords {
+            let idx = self.word_idx(k);
+            let a = self.load_word(lhs, idx);
+            let b = self.load_word(rhs, idx);
+            let want_carry = k + 1 < self.nwords;
+            let (sum, carry_out) = self.add_with_carry(&a, &b, carry.as_ref(), want_carry);
+            self.store_word(&result, idx, &sum);
+            carry = carry_out;
+        }
+        result
+    }
+
+    fn sub(&mut self, lhs: &VirtualRegister, rhs: &VirtualRegister) -> VirtualRegister {
+        let result = self.result_memory();
+        let mut borrow = None;
+        for k in 0..self.nwords {
+            let idx = self.word_idx(k);
+            let a = self.load_word(lhs, idx);
+            let b = self.load_word(rhs, idx);
+            let want_borrow = k + 1 < self.nwords;
+            let (diff, borrow_out) = self.sub_with_borrow(&a, &b, borrow.as_ref(), want_borrow);
+            self.store_word(&result, idx, &diff);
+            borrow = borrow_out;
+        }
+        result
+    }
+
+    // `lhs < rhs` is the borrow out of `lhs - rhs`.
+    fn lt(&mut self, lhs: &VirtualRegister, rhs: &VirtualRegister) -> VirtualRegister {
+        if self.nwords == 1 {
+            return self.binary(VirtualOp::LT, lhs, rhs);
+        }
+        let mut borrow = None;
+        for k in 0..self.nwords {
+            let idx = self.word_idx(k);
+            let a = self.load_word(lhs, idx);
+            let b = self.load_word(rhs, idx);
+            let (_, borrow_out) = self.sub_with_borrow(&a, &b, borrow.as_ref(), true);
+            borrow = borrow_out;
+        }
+        borrow.expect("Wide integers have at least one word.")
+    }
+
+    fn eq(&mut self, lhs: &VirtualRegister, rhs: &VirtualRegister) -> VirtualRegister {
+        if self.nwords == 1 {
+            return self.binary(VirtualOp::EQ, lhs, rhs);
+        }
+        let size = self.binary_imm(VirtualOp::ADDI, &Self::zero(), self.size_in_bytes());
+        let result = self.reg_seqr.next();
+        self.push(VirtualOp::MEQ(
+            result.clone(),
+            lhs.clone(),
+            rhs.clone(),
+            size,
+        ));
+        result
+    }
+
+    fn bitwise(
+        &mut self,
+        lhs: &VirtualRegister,
+        rhs: &VirtualRegister,
+        op: fn(VirtualRegister, VirtualRegister, VirtualRegister) -> VirtualOp,
+    ) -> VirtualRegister {
+        let result = self.result_memory();
+        for idx in 0..self.nwords {
+            let a = self.load_word(lhs, idx);
+            let b = self.load_word(rhs, idx);
+            let word = self.binary(op, &a, &b);
+            self.store_word(&result, idx, &word);
+        }
+        result
+    }
+
+    // Returns the low and high words of the 128 bit product of `a` and `b`, by multiplying their
+    // 32 bit halves so that none of the partial products overflow.
+    fn mul_words(
+        &mut self,
+        a: &VirtualRegister,
+        b: &VirtualRegister,
+    ) -> (VirtualRegister, VirtualRegister) {
+        let a_lo_shifted = self.binary_imm(VirtualOp::SLLI, a, 32);
+        let a_lo = self.binary_imm(VirtualOp::SRLI, &a_lo_shifted, 32);
+        let a_hi = self.binary_imm(VirtualOp::SRLI, a, 32);
+        let b_lo_shifted = self.binary_imm(VirtualOp::SLLI, b, 32);
+        let b_lo = self.binary_imm(VirtualOp::SRLI, &b_lo_shifted, 32);
+        let b_hi = self.binary_imm(VirtualOp::SRLI, b, 32);
+
+        let lo_lo = self.binary(VirtualOp::MUL, &a_lo, &b_lo);
+        let lo_hi = self.binary(VirtualOp::MUL, &a_lo, &b_hi);
+        let hi_lo = self.binary(VirtualOp::MUL, &a_hi, &b_lo);
+        let hi_hi = self.binary(VirtualOp::MUL, &a_hi, &b_hi);
+
+        // The middle partial products are shifted up 32 bits, straddling the two result words.
+        let (mid, mid_carry) = self.add_with_carry(&lo_hi, &hi_lo, None, true);
+        let mid_carry = mid_carry.expect("Carry was requested.");
+
+        let mid_lo = self.binary_imm(VirtualOp::SLLI, &mid, 32);
+        let (lo, lo_carry) = self.add_with_carry(&lo_lo, &mid_lo, None, true);
+
+        let mid_hi = self.binary_imm(VirtualOp::SRLI, &mid, 32);
+        let mid_carry_hi = self.binary_imm(VirtualOp::SLLI, &mid_carry, 32);
+        let hi = self.binary(VirtualOp::ADD, &hi_hi, &mid_hi);
+        let hi = self.binary(VirtualOp::ADD, &hi, &mid_carry_hi);
+        let hi = self.binary(
+            VirtualOp::ADD,
+            &hi,
+            &lo_carry.expect("Carry was requested."),
+        );
+        (lo, hi)
+    }
+
+    // Schoolbook multiplication, truncated to `nwords`.  Each result word is the sum of a column
+    // of partial products, accumulated in three words which are shifted down for the next column.
+    fn mul(&mut self, lhs: &VirtualRegister, rhs: &VirtualRegister) -> VirtualRegister {
+        let result = self.result_memory();
+        let a_words = self.load_words(lhs);
+        let b_words = self.load_words(rhs);
+
+        let mut acc = [Self::zero(), Self::zero(), Self::zero()];
+        for k in 0..self.nwords {
+            let is_last_column = k + 1 == self.nwords;
+            for i in 0..=k {
+                let (a, b) = (&a_words[i as usize], &b_words[(k - i) as usize]);
+                if is_last_column {
+                    // Anything carried out of the last column is truncated.
+                    let lo = self.binary(VirtualOp::MUL, a, b);
+                    acc[0] = self.binary(VirtualOp::ADD, &acc[0], &lo);
+                } else {
+                    let (lo, hi) = self.mul_words(a, b);
+                    let (acc0, carry) = self.add_with_carry(&acc[0], &lo, None, true);
+                    let want_carry = k + 2 < self.nwords;
+                    let (acc1, carry) =
+                        self.add_with_carry(&acc[1], &hi, carry.as_ref(), want_carry);
+                    acc[0] = acc0;
+                    acc[1] = acc1;
+                    if let Some(carry) = carry {
+                        acc[2] = self.binary(VirtualOp::ADD, &acc[2], &carry);
+                    }
+                }
+            }
+            let idx = self.word_idx(k);
+            self.store_word(&result, idx, &acc[0]);
+            acc.rotate_left(1);
+            acc[2] = Self::zero();
+        }
+        result
+    }
+
+    // Shift-subtract long division, one bit at a time.  The result memory holds the remainder and
+    // the quotient, which are adjacent so they're shifted left together, moving the next bit of
+    // the dividend from the quotient into the remainder.  Whenever the remainder is then at least
+    // the divisor it's reduced, via a temporary, and a quotient bit set.
+    //
+    // Dividing by zero gives a quotient with every bit set and the dividend as the remainder.
+    fn div_mod(
+        &mut self,
+        lhs: &VirtualRegister,
+        rhs: &VirtualRegister,
+        want_quotient: bool,
+    ) -> VirtualRegister {
+        let nwords = self.nwords;
+        let size_in_bytes = self.size_in_bytes();
+
+        let rem_ptr = self.result_memory();
+        self.push(VirtualOp::MCLI(
+            rem_ptr.clone(),
+            VirtualImmediate18::new_unchecked(size_in_bytes, "wide integers are at most 256 bits"),
+        ));
+        let quot_ptr = self.binary_imm(VirtualOp::ADDI, &rem_ptr, size_in_bytes);
+        self.push(VirtualOp::MCPI(
+            quot_ptr.clone(),
+            lhs.clone(),
+            Self::imm12(size_in_bytes),
+        ));
+        let temp_ptr = self.alloc(nwords);
+        self.comment_last("allocate wide integer division scratch");
+        let divisor_words = self.load_words(rhs);
+        let counter = self.binary_imm(VirtualOp::ADDI, &Self::zero(), nwords * 64);
+
+        let loop_label = self.reg_seqr.get_label();
+        let skip_label = self.reg_seqr.get_label();
+        self.push_organizational(Either::Right(OrganizationalOp::Label(loop_label.clone())));
+
+        // Shift the remainder and quotient left by one, keeping the bit shifted out of the top.
+        let words = (0..nwords * 2)
+            .map(|idx| self.load_word(&rem_ptr, idx))
+            .collect::<Vec<_>>();
+        let top_bit = self.binary_imm(VirtualOp::SRLI, &words[0], 63);
+        let mut shifted_words = Vec::new();
+        for idx in 0..words.len() {
+            let mut shifted = self.binary_imm(VirtualOp::SLLI, &words[idx], 1);
+            if let Some(next_word) = words.get(idx + 1) {
+                let next_bit = self.binary_imm(VirtualOp::SRLI, next_word, 63);
+                shifted = self.binary(VirtualOp::OR, &shifted, &next_bit);
+            }
+            self.store_word(&rem_ptr, idx as u64, &shifted);
+            shifted_words.push(shifted);
+        }
+
+        // Subtract the divisor into the temporary, which replaces the remainder if there was no
+        // borrow or if the remainder had overflowed.
+        let mut borrow = None;
+        for k in 0..nwords {
+            let idx = self.word_idx(k);
+            let (diff, borrow_out) = self.sub_with_borrow(
+                &shifted_words[idx as usize],
+                &divisor_words[k as usize],
+                borrow.as_ref(),
+                true,
+            );
+            self.store_word(&temp_ptr, idx, &diff);
+            borrow = borrow_out;
+        }
+        let no_borrow = self.not(borrow.expect("Wide integers have at least one word."));
+        let reduce = self.binary(VirtualOp::OR, &top_bit, &no_borrow);
+        self.push_organizational(Either::Right(OrganizationalOp::JumpIfNotEq(
+            reduce,
+            Self::one(),
+            skip_label.clone(),
+        )));
+        self.push(VirtualOp::MCPI(
+            rem_ptr.clone(),
+            temp_ptr,
+            Self::imm12(size_in_bytes),
+        ));
+        let quot_lo = self.binary_imm(VirtualOp::ORI, &shifted_words[(nwords * 2 - 1) as usize], 1);
+        self.store_word(&rem_ptr, nwords * 2 - 1, &quot_lo);
+
+        self.push_organizational(Either::Right(OrganizationalOp::Label(skip_label)));
+        self.push(VirtualOp::SUBI(
+            counter.clone(),
+            counter.clone(),
+            Self::imm12(1),
+        ));
+        self.push_organizational(Either::Right(OrganizationalOp::JumpIfNotEq(
+            counter,
+            Self::zero(),
+            loop_label,
+        )));
+        self.free(nwords);
+        self.comment_last("free wide integer division scratch");
+
+        if want_quotient {
+            quot_ptr
+        } else {
+            rem_ptr
+        }
+    }
+
+    // Divides the magnitudes of the operands.  The quotient is negative if exactly one of them is,
+    // while the remainder takes the sign of the dividend.
+    fn signed_div_mod(
+        &mut self,
+        lhs: &VirtualRegister,
+        rhs: &VirtualRegister,
+        want_quotient: bool,
+    ) -> VirtualRegister {
+        let lhs_mask = self.sign_mask(lhs);
+        let rhs_mask = self.sign_mask(rhs);
+        let lhs_abs = self.negate_if(lhs, &lhs_mask);
+        let rhs_abs = self.negate_if(rhs, &rhs_mask);
+        if want_quotient {
+            let quotient = self.binary(VirtualOp::DIV, &lhs_abs, &rhs_abs);
+            let quotient_mask = self.binary(VirtualOp::XOR, &lhs_mask, &rhs_mask);
+            self.negate_if(&quotient, &quotient_mask)
+        } else {
+            let remainder = self.binary(VirtualOp::MOD, &lhs_abs, &rhs_abs);
+            self.negate_if(&remainder, &lhs_mask)
+        }
+    }
+
+    // An arithmetic shift right is a logical shift of the complement of a negative value, which
+    // is then complemented again.
+    fn sar(&mut self, lhs: &VirtualRegister, rhs: &VirtualRegister) -> VirtualRegister {
+        let mask = self.sign_mask(lhs);
+        let flipped = self.binary(VirtualOp::XOR, lhs, &mask);
+        let shifted = self.binary(VirtualOp::SRL, &flipped, rhs);
+        self.binary(VirtualOp::XOR, &shifted, &mask)
+    }
+
+    // Shifts a wide integer by a word sized amount, one bit at a time.  The amount is first
+    // limited to the width of the integer, which shifts every bit out.
+    fn shift(
+        &mut self,
+        lhs: &VirtualRegister,
+        rhs: &VirtualRegister,
+        is_left: bool,
+    ) -> VirtualRegister {
+        let nwords = self.nwords;
+        let size_in_bytes = self.size_in_bytes();
+
+        let result = self.result_memory();
+        self.push(VirtualOp::MCPI(
+            result.clone(),
+            lhs.clone(),
+            Self::imm12(size_in_bytes),
+        ));
+        let counter = self.binary(VirtualOp::ADD, rhs, &Self::zero());
+        let limit = self.binary_imm(VirtualOp::ADDI, &Self::zero(), nwords * 64);
+        let in_range = self.binary(VirtualOp::LT, &counter, &limit);
+        let loop_label = self.reg_seqr.get_label();
+        let check_label = self.reg_seqr.get_label();
+        self.push_organizational(Either::Right(OrganizationalOp::JumpIfNotEq(
+            in_range,
+            Self::zero(),
+            check_label.clone(),
+        )));
+        self.push(VirtualOp::MOVE(counter.clone(), limit));
+        self.push_organizational(Either::Right(OrganizationalOp::Jump(check_label.clone())));
+
+        self.push_organizational(Either::Right(OrganizationalOp::Label(loop_label.clone())));
+        let words = (0..nwords)
+            .map(|idx| self.load_word(&result, idx))
+            .collect::<Vec<_>>();
+        for idx in 0..words.len() {
+            // The bit moving into this word comes from the next less significant word when
+            // shifting left, or the next more significant word when shifting right.
+            let (shifted, neighbour) = if is_left {
+                (
+                    self.binary_imm(VirtualOp::SLLI, &words[idx], 1),
+                    words.get(idx + 1),
+                )
+            } else {
+                (
+                    self.binary_imm(VirtualOp::SRLI, &words[idx], 1),
+                    idx.checked_sub(1).map(|prev| &words[prev]),
+                )
+            };
+            let word = match neighbour {
+                Some(neighbour) => {
+                    let carried = if is_left {
+                        self.binary_imm(VirtualOp::SRLI, neighbour, 63)
+                    } else {
+                        self.binary_imm(VirtualOp::SLLI, neighbour, 63)
+                    };
+                    self.binary(VirtualOp::OR, &shifted, &carried)
+                }
+                None => shifted,
+            };
+            self.store_word(&result, idx as u64, &word);
+        }
+        self.push(VirtualOp::SUBI(
+            counter.clone(),
+            counter.clone(),
+            Self::imm12(1),
+        ));
+
+        self.push_organizational(Either::Right(OrganizationalOp::Label(check_label)));
+        self.push_organizational(Either::Right(OrganizationalOp::JumpIfNotEq(
+            counter,
+            Self::zero(),
+            loop_label,
+        )));
+        result
+    }
+}
@@ -0,0 +1,93 @@
+//! Register liveness analysis over abstract (not yet allocated) ops.
+
+use std::collections::{HashMap, HashSet};
+
+use either::Either;
+
+use crate::asm_lang::{virtual_register::VirtualRegister, Label, Op, OrganizationalOp, VirtualOp};
+
+/// Given a sequence of ops, returns the set of registers which are live immediately after each op,
+/// i.e., those which may be read by a later op before being written to.
+///
+/// The ops are expected to be a self contained unit of control flow such as a single function.
+/// Jumps to labels outside of the ops, and `RET`, `RETD` and `RVRT`, are treated as exits.  A
+/// [OrganizationalOp::Call] continues to the next op, having read its arguments and written its
+/// return value.
+pub(crate) fn liveness_analysis(ops: &[Op]) -> Vec<HashSet<VirtualRegister>> {
+    let label_to_index: HashMap<&Label, usize> = ops
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, op)| match &op.opcode {
+            Either::Right(OrganizationalOp::Label(label)) => Some((label, idx)),
+            _otherwise => None,
+        })
+        .collect();
+
+    let successors: Vec<Vec<usize>> = ops
+        .iter()
+        .enumerate()
+        .map(|(idx, op)| op_successors(op, idx, ops.len(), &label_to_index))
+        .collect();
+
+    let mut live_in: Vec<HashSet<VirtualRegister>> = vec![HashSet::new(); ops.len()];
+    let mut live_out: Vec<HashSet<VirtualRegister>> = vec![HashSet::new(); ops.len()];
+
+    // Iterate backwards until we reach a fixed point.
+    let mut modified = true;
+    while modified {
+        modified = false;
+        for (idx, op) in ops.iter().enumerate().rev() {
+            let new_live_out: HashSet<VirtualRegister> = successors[idx]
+                .iter()
+                .flat_map(|succ_idx| live_in[*succ_idx].iter().cloned())
+                .collect();
+
+            let defs = op.def_registers();
+            let new_live_in: HashSet<VirtualRegister> = op
+                .use_registers()
+                .into_iter()
+                .cloned()
+                .chain(
+                    new_live_out
+                        .iter()
+                        .filter(|reg| !defs.contains(reg))
+                        .cloned(),
+                )
+                .collect();
+
+            if new_live_in != live_in[idx] || new_live_out != live_out[idx] {
+                live_in[idx] = new_live_in;
+                live_out[idx] = new_live_out;
+                modified = true;
+            }
+        }
+    }
+
+    live_out
+}
+
+fn op_successors(
+    op: &Op,
+    idx: usize,
+    ops_count: usize,
+    label_to_index: &HashMap<&Label, usize>,
+) -> Vec<usize> {
+    let next = if idx + 1 < ops_count {
+        vec![idx + 1]
+    } else {
+        Vec::new()
+    };
+    match &op.opcode {
+        Either::Left(VirtualOp::RET(_))
+        | Either::Left(VirtualOp::RETD(..))
+        | Either::Left(VirtualOp::RVRT(_)) => Vec::new(),
+        Either::Right(OrganizationalOp::Jump(label)) => {
+            label_to_index.get(label).into_iter().cloned().collect()
+        }
+        Either::Right(OrganizationalOp::JumpIfNotEq(_, _, label)) => next
+            .into_iter()
+            .chain(label_to_index.get(label).cloned())
+            .collect(),
+        _otherwise => next,
+    }
+}
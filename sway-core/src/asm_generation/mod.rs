@@ -1,9 +1,9 @@
 use std::{collections::HashMap, fmt};
 
-use crate::semantic_analysis::ast_node::{TypedVariableDeclaration, VariableMutability};
+use crate::semantic_analysis::ast_node::TypedConstantDeclaration;
 use crate::type_engine::resolve_type;
 use crate::{
-    asm_generation::expression::convert_abi_fn_to_asm,
+    asm_generation::expression::{convert_abi_fn_to_asm, convert_storage_initializers_to_asm},
     asm_lang::{
         allocated_ops::{AllocatedOp, AllocatedRegister},
         virtual_register::*,
@@ -26,6 +26,10 @@ mod declaration;
 mod expression;
 mod finalized_asm;
 pub(crate) mod from_ir;
+mod int_ops;
+mod liveness;
+mod peephole;
+mod register_allocator;
 mod register_sequencer;
 mod while_loop;
 
@@ -34,7 +38,7 @@ pub(crate) use expression::*;
 pub use finalized_asm::FinalizedAsm;
 pub(crate) use register_sequencer::*;
 
-use while_loop::convert_while_loop_to_asm;
+use while_loop::{convert_for_loop_to_asm, convert_while_loop_to_asm};
 
 // Initially, the bytecode will have a lot of individual registers being used. Each register will
 // have a new unique identifier. For example, two separate invocations of `+` will result in 4
@@ -100,36 +104,22 @@ pub struct RealizedAbstractInstructionSet {
 }
 
 impl RealizedAbstractInstructionSet {
-    fn allocate_registers(self) -> InstructionSet {
-        // Eventually, we will use a cool graph-coloring algorithm.
-        // For now, just keep a pool of registers and return
-        // registers when they are not read anymore
-
-        // construct a mapping from every op to the registers it uses
-        let op_register_mapping = self
-            .ops
-            .into_iter()
-            .map(|op| {
-                (
-                    op.clone(),
-                    op.opcode.registers().into_iter().cloned().collect(),
-                )
-            })
-            .collect::<Vec<_>>();
-
-        // get registers from the pool.
-        let mut pool = RegisterPool::init();
-        let mut buf = vec![];
-        for (ix, (op, _)) in op_register_mapping.iter().enumerate() {
-            buf.push(AllocatedOp {
-                opcode: op
-                    .opcode
-                    .allocate_registers(&mut pool, &op_register_mapping, ix),
-                comment: op.comment.clone(),
-                owning_span: op.owning_span.clone(),
-            })
+    fn allocate_registers(
+        self,
+        register_mapping: &HashMap<VirtualRegister, AllocatedRegister>,
+    ) -> InstructionSet {
+        InstructionSet {
+            ops: self
+                .ops
+                .into_iter()
+                .map(|op| AllocatedOp {
+                    opcode: op.opcode.allocate_registers(register_mapping),
+                    comment: op.comment,
+                    owning_span: op.owning_span,
+                })
+                .collect(),
+            peephole_bytes_saved: 0,
         }
-        InstructionSet { ops: buf }
     }
 }
 
@@ -137,6 +127,8 @@ impl RealizedAbstractInstructionSet {
 #[derive(Clone)]
 pub struct InstructionSet {
     ops: Vec<AllocatedOp>,
+    // The number of bytes removed from the program by the peephole optimizations.
+    peephole_bytes_saved: u64,
 }
 
 type Data = Literal;
@@ -188,6 +180,22 @@ impl AbstractInstructionSet {
         AbstractInstructionSet { ops: buf2 }
     }
 
+    /// Allocates registers by colouring, spilling registers to memory where necessary, and then
+    /// realizes the labels.
+    fn allocate_registers(
+        self,
+        data_section: &DataSection,
+        register_sequencer: &mut RegisterSequencer,
+    ) -> CompileResult<InstructionSet> {
+        register_allocator::allocate_registers(self.ops, register_sequencer).map(
+            |(ops, register_mapping)| {
+                AbstractInstructionSet { ops }
+                    .realize_labels(data_section)
+                    .allocate_registers(&register_mapping)
+            },
+        )
+    }
+
     /// Runs two passes -- one to get the instruction offsets of the labels
     /// and one to replace the labels in the organizational ops
     fn realize_labels(self, data_section: &DataSection) -> RealizedAbstractInstructionSet {
@@ -212,10 +220,14 @@ impl AbstractInstructionSet {
                 // these ops will end up being exactly one op, so the counter goes up one
                 Either::Right(OrganizationalOp::Jump(..))
                 | Either::Right(OrganizationalOp::JumpIfNotEq(..))
+                | Either::Right(OrganizationalOp::Call(..))
                 | Either::Left(_) => {
                     counter += 1;
                 }
                 Either::Right(OrganizationalOp::Comment) => (),
+                Either::Right(
+                    OrganizationalOp::SpillAreaStart(_) | OrganizationalOp::SpillAreaEnd,
+                ) => unreachable!("spill area markers are replaced by the register allocator"),
                 Either::Right(OrganizationalOp::DataSectionOffsetPlaceholder) => {
                     // If the placeholder is 32 bits, this is 1. if 64, this should be 2. We use LW
                     // to load the data, which loads a whole word, so for now this is 2.
@@ -238,7 +250,7 @@ impl AbstractInstructionSet {
                     comment,
                 }),
                 Either::Right(org_op) => match org_op {
-                    OrganizationalOp::Jump(ref lab) => {
+                    OrganizationalOp::Jump(ref lab) | OrganizationalOp::Call(ref lab, ..) => {
                         let offset = label_namespace.get(lab).unwrap();
                         let imm = VirtualImmediate24::new_unchecked(
                             *offset,
@@ -271,6 +283,9 @@ impl AbstractInstructionSet {
                     }
                     OrganizationalOp::Comment => continue,
                     OrganizationalOp::Label(..) => continue,
+                    OrganizationalOp::SpillAreaStart(_) | OrganizationalOp::SpillAreaEnd => {
+                        unreachable!("spill area markers are replaced by the register allocator")
+                    }
                 },
             };
         }
@@ -278,86 +293,11 @@ impl AbstractInstructionSet {
     }
 }
 
-#[derive(Debug)]
-struct RegisterAllocationStatus {
-    reg: AllocatedRegister,
-    in_use: Option<VirtualRegister>,
-}
-#[derive(Debug)]
-pub(crate) struct RegisterPool {
-    registers: Vec<RegisterAllocationStatus>,
-}
-
-impl RegisterPool {
-    fn init() -> Self {
-        let register_pool: Vec<RegisterAllocationStatus> = (0
-            // - 1 because we reserve the final register for the data_section begin
-            ..compiler_constants::NUM_ALLOCATABLE_REGISTERS)
-            .map(|x| RegisterAllocationStatus {
-                reg: AllocatedRegister::Allocated(x),
-                in_use: None,
-            })
-            .collect();
-        Self {
-            registers: register_pool,
-        }
-    }
-
-    /// Checks if any currently used registers are no longer in use, updates the pool,
-    /// and grabs an available register.
-    pub(crate) fn get_register(
-        &mut self,
-        virtual_register: &VirtualRegister,
-        op_register_mapping: &[(RealizedOp, std::collections::HashSet<VirtualRegister>)],
-    ) -> Option<AllocatedRegister> {
-        // check if this register has already been allocated for
-        if let a @ Some(_) = self.registers.iter().find_map(
-            |RegisterAllocationStatus { reg, in_use }| match in_use {
-                Some(x) if x == virtual_register => Some(reg),
-                _ => None,
-            },
-        ) {
-            return a.cloned();
-        }
-
-        // scan to see if any of the old ones are no longer in use
-        for RegisterAllocationStatus { in_use, .. } in
-            self.registers.iter_mut().filter(|r| r.in_use.is_some())
-        {
-            if virtual_register_is_never_accessed_again(
-                in_use.as_ref().unwrap(),
-                op_register_mapping,
-            ) {
-                *in_use = None;
-            }
-        }
-        // find the next unused register, return it, assign it
-        let next_available = self
-            .registers
-            .iter_mut()
-            .find(|RegisterAllocationStatus { in_use, .. }| in_use.is_none());
-
-        match next_available {
-            Some(RegisterAllocationStatus { in_use, reg }) => {
-                *in_use = Some(virtual_register.clone());
-                Some(reg.clone())
-            }
-            None => None,
-        }
-    }
-}
-
-fn virtual_register_is_never_accessed_again(
-    reg: &VirtualRegister,
-    ops: &[(RealizedOp, std::collections::HashSet<VirtualRegister>)],
-) -> bool {
-    !ops.iter().any(|(_, regs)| regs.contains(reg))
-}
-
 /// helper function to check if a label is used in a given buffer of ops
 fn label_is_used(buf: &[Op], label: &Label) -> bool {
     buf.iter().any(|Op { ref opcode, .. }| match opcode {
         Either::Right(OrganizationalOp::Jump(ref l)) if label == l => true,
+        Either::Right(OrganizationalOp::Call(ref l, ..)) if label == l => true,
         Either::Right(OrganizationalOp::JumpIfNotEq(_reg0, _reg1, ref l)) if label == l => true,
         _ => false,
     })
@@ -430,6 +370,18 @@ impl fmt::Display for DataSection {
                 Literal::U16(num) => format!(".u16 {:#04x}", num),
                 Literal::U32(num) => format!(".u32 {:#04x}", num),
                 Literal::U64(num) => format!(".u64 {:#04x}", num),
+                Literal::U128(num) => format!(".u128 {:#034x}", num),
+                Literal::U256(b) => format!(
+                    ".u256 0x{}",
+                    b.iter()
+                        .map(|x| format!("{:02x}", x))
+                        .collect::<Vec<_>>()
+                        .join("")
+                ),
+                Literal::I8(num) => format!(".i8 {}", num),
+                Literal::I16(num) => format!(".i16 {}", num),
+                Literal::I32(num) => format!(".i32 {}", num),
+                Literal::I64(num) => format!(".i64 {}", num),
                 Literal::Numeric(num) => format!(".u64 {:#04x}", num),
                 Literal::Boolean(b) => format!(".bool {}", if *b { "0x01" } else { "0x00" }),
                 Literal::String(st) => format!(".str \"{}\"", st.as_str()),
@@ -572,6 +524,12 @@ impl fmt::Display for InstructionSet {
 pub(crate) struct AsmNamespace {
     data_section: DataSection,
     variables: HashMap<Ident, VirtualRegister>,
+    // The labels which `continue` and `break` jump to for each loop enclosing the code being
+    // generated, innermost last.
+    loop_labels: Vec<(Label, Label)>,
+    // The register pointing to the memory reserved once at the start of the program for wide
+    // integers, and the size of that memory in words so far.
+    wide_int_memory: Option<(VirtualRegister, u64)>,
 }
 
 /// An address which refers to a value in the data section of the asm.
@@ -588,9 +546,67 @@ impl AsmNamespace {
     pub(crate) fn insert_variable(&mut self, var_name: Ident, register_location: VirtualRegister) {
         self.variables.insert(var_name, register_location);
     }
+    /// Enters the body of a loop, where `continue` jumps to `continue_label` and `break` jumps to
+    /// `break_label`.
+    pub(crate) fn push_loop_labels(&mut self, continue_label: Label, break_label: Label) {
+        self.loop_labels.push((continue_label, break_label));
+    }
+    pub(crate) fn pop_loop_labels(&mut self) {
+        self.loop_labels.pop();
+    }
+    /// The labels which `continue` and `break` jump to, respectively, in the innermost loop.
+    pub(crate) fn loop_labels(&self) -> Option<&(Label, Label)> {
+        self.loop_labels.last()
+    }
     pub(crate) fn insert_data_value(&mut self, data: &Data) -> DataId {
         self.data_section.insert_data_value(data)
     }
+    /// Reserves `nwords` words of the memory for wide integers and points `ptr` at them.  As this
+    /// memory is allocated once for the whole program, code which runs more than once, such as a
+    /// loop body, reuses the same words each time rather than growing the stack.
+    pub(crate) fn reserve_wide_int_memory(
+        &mut self,
+        ptr: &VirtualRegister,
+        nwords: u64,
+        register_sequencer: &mut RegisterSequencer,
+        span: sway_types::span::Span,
+    ) -> CompileResult<Op> {
+        let (base, size) = self
+            .wide_int_memory
+            .get_or_insert_with(|| (register_sequencer.next(), 0));
+        let offset = match VirtualImmediate12::new(*size * 8, span.clone()) {
+            Ok(o) => o,
+            Err(e) => return err(vec![], vec![e]),
+        };
+        *size += nwords;
+        ok(
+            Op {
+                opcode: Either::Left(VirtualOp::ADDI(ptr.clone(), base.clone(), offset)),
+                comment: "get wide integer memory offset".into(),
+                owning_span: Some(span),
+            },
+            vec![],
+            vec![],
+        )
+    }
+    /// The ops which allocate the memory for wide integers, if any was reserved.  They must run
+    /// before any of the code which uses it.
+    fn allocate_wide_int_memory(&self) -> Vec<Op> {
+        match &self.wide_int_memory {
+            Some((base, size)) => vec![
+                Op::unowned_register_move_comment(
+                    base.clone(),
+                    VirtualRegister::Constant(ConstantRegister::StackPointer),
+                    "wide integer memory",
+                ),
+                Op::unowned_stack_allocate_memory(VirtualImmediate24::new_unchecked(
+                    size * 8,
+                    "wide integer memory is limited by its 12 bit offsets",
+                )),
+            ],
+            None => vec![],
+        }
+    }
     /// Finds the register which contains variable `var_name`
     /// The `get` is unwrapped, because invalid variable expressions are
     /// checked for in the type checking stage.
@@ -625,24 +641,23 @@ pub(crate) fn compile_ast_to_asm(
         } => {
             let mut namespace: AsmNamespace = Default::default();
             let mut asm_buf = build_preamble(&mut register_sequencer).to_vec();
+            let preamble_len = asm_buf.len();
             // generate any const decls
             read_module(
                 |ns| -> CompileResult<()> {
                     let mut warnings = vec![];
                     let mut errors = vec![];
-                    let const_decls = ns.get_all_declared_symbols().filter_map(|x| {
-                        if let TypedDeclaration::VariableDeclaration(TypedVariableDeclaration {
-                            body,
-                            is_mutable: VariableMutability::ExportedConst,
-                            name,
-                            ..
-                        }) = x
-                        {
-                            Some((body, name))
-                        } else {
-                            None
-                        }
-                    });
+                    let const_decls =
+                        ns.get_all_declared_symbols().filter_map(|x| {
+                            if let TypedDeclaration::ConstantDeclaration(
+                                TypedConstantDeclaration { value, name, .. },
+                            ) = x
+                            {
+                                Some((value, name))
+                            } else {
+                                None
+                            }
+                        });
                     for (body, name) in const_decls {
                         let return_register = register_sequencer.next();
                         let mut buf = check!(
@@ -690,6 +705,12 @@ pub(crate) fn compile_ast_to_asm(
                 errors
             ));
 
+            // the size of the wide integer memory is known once the whole program is generated
+            asm_buf.splice(
+                preamble_len..preamble_len,
+                namespace.allocate_wide_int_memory(),
+            );
+
             (
                 SwayAsmSet::ScriptMain {
                     program_section: AbstractInstructionSet { ops: asm_buf },
@@ -706,23 +727,22 @@ pub(crate) fn compile_ast_to_asm(
         } => {
             let mut namespace: AsmNamespace = Default::default();
             let mut asm_buf = build_preamble(&mut register_sequencer).to_vec();
+            let preamble_len = asm_buf.len();
             read_module(
                 |ns| -> CompileResult<()> {
                     let mut warnings = vec![];
                     let mut errors = vec![];
-                    let const_decls = ns.get_all_declared_symbols().filter_map(|x| {
-                        if let TypedDeclaration::VariableDeclaration(TypedVariableDeclaration {
-                            body,
-                            is_mutable: VariableMutability::ExportedConst,
-                            name,
-                            ..
-                        }) = x
-                        {
-                            Some((body, name))
-                        } else {
-                            None
-                        }
-                    });
+                    let const_decls =
+                        ns.get_all_declared_symbols().filter_map(|x| {
+                            if let TypedDeclaration::ConstantDeclaration(
+                                TypedConstantDeclaration { value, name, .. },
+                            ) = x
+                            {
+                                Some((value, name))
+                            } else {
+                                None
+                            }
+                        });
                     for (body, name) in const_decls {
                         let return_register = register_sequencer.next();
                         let mut buf = check!(
@@ -757,6 +777,12 @@ pub(crate) fn compile_ast_to_asm(
             );
             asm_buf.append(&mut body);
 
+            // the size of the wide integer memory is known once the whole program is generated
+            asm_buf.splice(
+                preamble_len..preamble_len,
+                namespace.allocate_wide_int_memory(),
+            );
+
             (
                 SwayAsmSet::PredicateMain {
                     program_section: AbstractInstructionSet { ops: asm_buf },
@@ -768,28 +794,27 @@ pub(crate) fn compile_ast_to_asm(
         TypedParseTree::Contract {
             abi_entries,
             namespace: ast_namespace,
-            declarations: _,
+            declarations,
             ..
         } => {
             let mut namespace: AsmNamespace = Default::default();
             let mut asm_buf = build_preamble(&mut register_sequencer).to_vec();
+            let preamble_len = asm_buf.len();
             read_module(
                 |ns| -> CompileResult<()> {
                     let mut warnings = vec![];
                     let mut errors = vec![];
-                    let const_decls = ns.get_all_declared_symbols().filter_map(|x| {
-                        if let TypedDeclaration::VariableDeclaration(TypedVariableDeclaration {
-                            body,
-                            is_mutable: VariableMutability::ExportedConst,
-                            name,
-                            ..
-                        }) = x
-                        {
-                            Some((body, name))
-                        } else {
-                            None
-                        }
-                    });
+                    let const_decls =
+                        ns.get_all_declared_symbols().filter_map(|x| {
+                            if let TypedDeclaration::ConstantDeclaration(
+                                TypedConstantDeclaration { value, name, .. },
+                            ) = x
+                            {
+                                Some((value, name))
+                            } else {
+                                None
+                            }
+                        });
                     for (body, name) in const_decls {
                         let return_register = register_sequencer.next();
                         let mut buf = check!(
@@ -810,6 +835,23 @@ pub(crate) fn compile_ast_to_asm(
                 },
                 ast_namespace,
             );
+            if let Some(storage) = declarations.iter().find_map(|decl| match decl {
+                TypedDeclaration::StorageDeclaration(storage) if !storage.fields.is_empty() => {
+                    Some(storage)
+                }
+                _ => None,
+            }) {
+                asm_buf.append(&mut check!(
+                    convert_storage_initializers_to_asm(
+                        &storage.fields,
+                        &mut namespace,
+                        &mut register_sequencer
+                    ),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                ));
+            }
             let (selectors_and_labels, mut contract_asm) = check!(
                 compile_contract_to_selectors(abi_entries, &mut namespace, &mut register_sequencer),
                 return err(warnings, errors),
@@ -823,6 +865,12 @@ pub(crate) fn compile_ast_to_asm(
             ));
             asm_buf.append(&mut contract_asm);
 
+            // the size of the wide integer memory is known once the whole program is generated
+            asm_buf.splice(
+                preamble_len..preamble_len,
+                namespace.allocate_wide_int_memory(),
+            );
+
             (
                 SwayAsmSet::ContractAbi {
                     program_section: AbstractInstructionSet { ops: asm_buf },
@@ -838,14 +886,29 @@ pub(crate) fn compile_ast_to_asm(
         println!("{}", asm);
     }
 
-    let finalized_asm = asm
-        .remove_unnecessary_jumps()
-        .allocate_registers()
-        .optimize();
+    let register_allocated_asm = check!(
+        asm.remove_unnecessary_jumps()
+            .allocate_registers(&mut register_sequencer),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    let finalized_asm = check!(
+        register_allocated_asm.optimize(build_config),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
 
     if build_config.print_finalized_asm {
         println!("{}", finalized_asm);
     }
+    if build_config.print_peephole_savings {
+        println!(
+            ";; Peephole optimizations saved {} bytes.",
+            finalized_asm.peephole_bytes_saved()
+        );
+    }
 
     check!(
         crate::checks::check_invalid_opcodes(&finalized_asm),
@@ -887,42 +950,39 @@ impl SwayAsmSet {
 }
 
 impl JumpOptimizedAsmSet {
-    fn allocate_registers(self) -> RegisterAllocatedAsmSet {
+    fn allocate_registers(
+        self,
+        register_sequencer: &mut RegisterSequencer,
+    ) -> CompileResult<RegisterAllocatedAsmSet> {
         match self {
-            JumpOptimizedAsmSet::Library => RegisterAllocatedAsmSet::Library,
+            JumpOptimizedAsmSet::Library => ok(RegisterAllocatedAsmSet::Library, vec![], vec![]),
             JumpOptimizedAsmSet::ScriptMain {
                 data_section,
                 program_section,
-            } => {
-                let program_section = program_section
-                    .realize_labels(&data_section)
-                    .allocate_registers();
-                RegisterAllocatedAsmSet::ScriptMain {
+            } => program_section
+                .allocate_registers(&data_section, register_sequencer)
+                .map(|program_section| RegisterAllocatedAsmSet::ScriptMain {
                     data_section,
                     program_section,
-                }
-            }
+                }),
             JumpOptimizedAsmSet::PredicateMain {
                 data_section,
                 program_section,
-            } => {
-                let program_section = program_section
-                    .realize_labels(&data_section)
-                    .allocate_registers();
-                RegisterAllocatedAsmSet::PredicateMain {
+            } => program_section
+                .allocate_registers(&data_section, register_sequencer)
+                .map(|program_section| RegisterAllocatedAsmSet::PredicateMain {
                     data_section,
                     program_section,
-                }
-            }
+                }),
             JumpOptimizedAsmSet::ContractAbi {
                 program_section,
                 data_section,
-            } => RegisterAllocatedAsmSet::ContractAbi {
-                program_section: program_section
-                    .realize_labels(&data_section)
-                    .allocate_registers(),
-                data_section,
-            },
+            } => program_section
+                .allocate_registers(&data_section, register_sequencer)
+                .map(|program_section| RegisterAllocatedAsmSet::ContractAbi {
+                    program_section,
+                    data_section,
+                }),
         }
     }
 }
@@ -963,64 +1023,67 @@ pub enum RegisterAllocatedAsmSet {
 }
 
 impl RegisterAllocatedAsmSet {
-    fn optimize(self) -> FinalizedAsm {
-        // TODO implement this -- noop for now
+    fn optimize(self, build_config: &BuildConfig) -> CompileResult<FinalizedAsm> {
+        let optimize_and_pad = |program_section: InstructionSet, data_section: &DataSection| {
+            let mut warnings = vec![];
+            let mut errors = vec![];
+            let mut program_section = if build_config.optimize_asm {
+                let size_before =
+                    peephole::program_size_in_bytes(&program_section.ops, data_section);
+                let ops = check!(
+                    peephole::optimize(program_section.ops, data_section),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                );
+                let size_after = peephole::program_size_in_bytes(&ops, data_section);
+                InstructionSet {
+                    ops,
+                    peephole_bytes_saved: size_before - size_after,
+                }
+            } else {
+                program_section
+            };
+            // ensure there's an even number of ops so the
+            // data section offset is valid
+            if program_section.ops.len() & 1 != 0 {
+                program_section.ops.push(AllocatedOp {
+                    opcode: crate::asm_lang::allocated_ops::AllocatedOpcode::NOOP,
+                    comment: "word-alignment of data section".into(),
+                    owning_span: None,
+                });
+            }
+            ok(program_section, warnings, errors)
+        };
         match self {
-            RegisterAllocatedAsmSet::Library => FinalizedAsm::Library,
+            RegisterAllocatedAsmSet::Library => ok(FinalizedAsm::Library, vec![], vec![]),
             RegisterAllocatedAsmSet::ScriptMain {
-                mut program_section,
+                program_section,
                 data_section,
-            } => {
-                // ensure there's an even number of ops so the
-                // data section offset is valid
-                if program_section.ops.len() & 1 != 0 {
-                    program_section.ops.push(AllocatedOp {
-                        opcode: crate::asm_lang::allocated_ops::AllocatedOpcode::NOOP,
-                        comment: "word-alignment of data section".into(),
-                        owning_span: None,
-                    });
-                }
+            } => optimize_and_pad(program_section, &data_section).map(|program_section| {
                 FinalizedAsm::ScriptMain {
                     program_section,
                     data_section,
                 }
-            }
+            }),
             RegisterAllocatedAsmSet::PredicateMain {
-                mut program_section,
+                program_section,
                 data_section,
-            } => {
-                // ensure there's an even number of ops so the
-                // data section offset is valid
-                if program_section.ops.len() & 1 != 0 {
-                    program_section.ops.push(AllocatedOp {
-                        opcode: crate::asm_lang::allocated_ops::AllocatedOpcode::NOOP,
-                        comment: "word-alignment of data section".into(),
-                        owning_span: None,
-                    });
-                }
+            } => optimize_and_pad(program_section, &data_section).map(|program_section| {
                 FinalizedAsm::PredicateMain {
                     program_section,
                     data_section,
                 }
-            }
+            }),
             RegisterAllocatedAsmSet::ContractAbi {
-                mut program_section,
+                program_section,
                 data_section,
-            } => {
-                // ensure there's an even number of ops so the
-                // data section offset is valid
-                if program_section.ops.len() & 1 != 0 {
-                    program_section.ops.push(AllocatedOp {
-                        opcode: crate::asm_lang::allocated_ops::AllocatedOpcode::NOOP,
-                        comment: "word-alignment of data section".into(),
-                        owning_span: None,
-                    });
-                }
+            } => optimize_and_pad(program_section, &data_section).map(|program_section| {
                 FinalizedAsm::ContractAbi {
                     program_section,
                     data_section,
                 }
-            }
+            }),
         }
     }
 }
@@ -1051,6 +1114,40 @@ fn convert_node_to_asm(
             );
             ok(NodeAsmResult::JustAsm(res), warnings, errors)
         }
+        TypedAstNodeContent::ForLoop(r#loop) => {
+            let res = check!(
+                convert_for_loop_to_asm(r#loop, namespace, register_sequencer),
+                return err(warnings, errors),
+                warnings,
+                errors
+            );
+            ok(NodeAsmResult::JustAsm(res), warnings, errors)
+        }
+        TypedAstNodeContent::Break | TypedAstNodeContent::Continue => {
+            let is_break = matches!(node.content, TypedAstNodeContent::Break);
+            match namespace.loop_labels() {
+                Some((continue_label, break_label)) => {
+                    let (label, comment) = if is_break {
+                        (break_label.clone(), "break")
+                    } else {
+                        (continue_label.clone(), "continue")
+                    };
+                    ok(
+                        NodeAsmResult::JustAsm(vec![Op::jump_to_label_comment(label, comment)]),
+                        warnings,
+                        errors,
+                    )
+                }
+                None => {
+                    errors.push(CompileError::Internal(
+                        "Loop control flow outside of a loop in assembly generation. This should \
+                         have been an error during type checking.",
+                        node.span.clone(),
+                    ));
+                    err(warnings, errors)
+                }
+            }
+        }
         TypedAstNodeContent::Declaration(typed_decl) => {
             let res = check!(
                 convert_decl_to_asm(typed_decl, namespace, register_sequencer),
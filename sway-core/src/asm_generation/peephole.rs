@@ -0,0 +1,260 @@
+//! Peephole optimizations over register allocated ops.
+//!
+//! Each rewrite looks at a short window of consecutive ops and may replace them with fewer ops
+//! which have the same effect.  The rewrites are applied repeatedly until none of them apply.
+//!
+//! The jumps have already been realized to instruction offsets at this point, so they are
+//! adjusted to account for any removed ops.  A window never extends past the first op which is
+//! the target of a jump, as control may arrive there from somewhere else.
+//!
+//! Compiler generated `MOVE`s are not relied upon to clear `$of` and `$err`.
+
+use std::collections::{HashMap, HashSet};
+
+use super::{compiler_constants::TWELVE_BITS, DataSection};
+use crate::{
+    asm_lang::{
+        allocated_ops::{AllocatedOp, AllocatedOpcode, AllocatedRegister},
+        VirtualImmediate12, VirtualImmediate24,
+    },
+    error::{err, ok, CompileError, CompileResult},
+};
+use sway_types::span::Span;
+
+/// Given a window of ops, a rewrite returns how many ops at the start of the window it replaces
+/// and what they are replaced with, or `None` if it does not apply.
+type Rewrite = fn(&[AllocatedOp]) -> Option<(usize, Vec<AllocatedOp>)>;
+
+const REWRITES: &[Rewrite] = &[
+    remove_self_move,
+    remove_move_back,
+    remove_load_of_stored_word,
+    remove_store_of_loaded_word,
+    remove_dead_write,
+];
+
+/// Applies the peephole rewrites to `ops`, returning the optimized ops.
+pub(crate) fn optimize(
+    mut ops: Vec<AllocatedOp>,
+    data_section: &DataSection,
+) -> CompileResult<Vec<AllocatedOp>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    loop {
+        let jump_targets = get_jump_target_indices(&ops, data_section);
+        let mut new_ops = Vec::with_capacity(ops.len());
+        // For each old op, the index of the new op which takes its place.
+        let mut new_indices = Vec::with_capacity(ops.len() + 1);
+        let mut idx = 0;
+        while idx < ops.len() {
+            let window_end = (idx + 1..ops.len())
+                .find(|window_idx| jump_targets.contains(window_idx))
+                .unwrap_or(ops.len());
+            let window = &ops[idx..window_end];
+            match REWRITES.iter().find_map(|rewrite| rewrite(window)) {
+                Some((num_replaced, mut replacements)) => {
+                    new_indices.resize(new_indices.len() + num_replaced, new_ops.len());
+                    new_ops.append(&mut replacements);
+                    idx += num_replaced;
+                }
+                None => {
+                    new_indices.push(new_ops.len());
+                    new_ops.push(ops[idx].clone());
+                    idx += 1;
+                }
+            }
+        }
+        new_indices.push(new_ops.len());
+
+        if new_ops.len() == ops.len() {
+            return ok(ops, warnings, errors);
+        }
+        ops = check!(
+            retarget_jumps(&ops, new_ops, &new_indices, data_section),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+    }
+}
+
+/// The size of the program section in bytes, after padding it to a whole number of words.
+pub(crate) fn program_size_in_bytes(ops: &[AllocatedOp], data_section: &DataSection) -> u64 {
+    let num_instructions: u64 = ops
+        .iter()
+        .map(|op| op_size_in_instructions(&op.opcode, data_section))
+        .sum();
+    (num_instructions + (num_instructions & 1)) * 4
+}
+
+fn op_size_in_instructions(opcode: &AllocatedOpcode, data_section: &DataSection) -> u64 {
+    match opcode {
+        AllocatedOpcode::LWDataId(_, data_id) => {
+            let type_of_data = data_section.type_of_data(data_id).expect(
+                "Internal miscalculation in data section -- data id did not match up to any actual data",
+            );
+            if type_of_data.stack_size_of() > 1 {
+                2
+            } else {
+                1
+            }
+        }
+        AllocatedOpcode::DataSectionOffsetPlaceholder => 2,
+        _otherwise => 1,
+    }
+}
+
+fn get_instruction_offsets(ops: &[AllocatedOp], data_section: &DataSection) -> Vec<u64> {
+    let mut offset = 0;
+    let mut offsets = Vec::with_capacity(ops.len() + 1);
+    for op in ops {
+        offsets.push(offset);
+        offset += op_size_in_instructions(&op.opcode, data_section);
+    }
+    offsets.push(offset);
+    offsets
+}
+
+fn get_jump_target_indices(ops: &[AllocatedOp], data_section: &DataSection) -> HashSet<usize> {
+    let offset_to_idx = get_instruction_offsets(ops, data_section)
+        .into_iter()
+        .enumerate()
+        .map(|(idx, offset)| (offset, idx))
+        .collect::<HashMap<_, _>>();
+    ops.iter()
+        .filter_map(|op| match &op.opcode {
+            AllocatedOpcode::JI(imm) => offset_to_idx.get(&imm.value.into()),
+            AllocatedOpcode::JNEI(_, _, imm) => offset_to_idx.get(&imm.value.into()),
+            _otherwise => None,
+        })
+        .cloned()
+        .collect()
+}
+
+/// Updates the jumps in `new_ops` so that any which targeted an op in `old_ops` now target the op
+/// which took its place.  A jump to an offset which isn't the start of an op is an error.
+fn retarget_jumps(
+    old_ops: &[AllocatedOp],
+    mut new_ops: Vec<AllocatedOp>,
+    new_indices: &[usize],
+    data_section: &DataSection,
+) -> CompileResult<Vec<AllocatedOp>> {
+    let new_offsets = get_instruction_offsets(&new_ops, data_section);
+    let offset_map = get_instruction_offsets(old_ops, data_section)
+        .into_iter()
+        .zip(new_indices.iter().map(|new_idx| new_offsets[*new_idx]))
+        .collect::<HashMap<_, _>>();
+    for op in &mut new_ops {
+        let old_offset: u64 = match &op.opcode {
+            AllocatedOpcode::JI(imm) => imm.value.into(),
+            AllocatedOpcode::JNEI(_, _, imm) => imm.value.into(),
+            _otherwise => continue,
+        };
+        let offset = match offset_map.get(&old_offset) {
+            Some(offset) => *offset,
+            None => {
+                return err(
+                    vec![],
+                    vec![CompileError::Internal(
+                        "Jump target is not the start of an op during peephole optimization.",
+                        op.owning_span.clone().unwrap_or_else(|| Span {
+                            span: pest::Span::new(" ".into(), 0, 0).unwrap(),
+                            path: None,
+                        }),
+                    )],
+                )
+            }
+        };
+        match &mut op.opcode {
+            AllocatedOpcode::JI(imm) => {
+                *imm = VirtualImmediate24::new_unchecked(
+                    offset,
+                    "Jump offsets only decrease during optimization",
+                );
+            }
+            AllocatedOpcode::JNEI(_, _, imm) => {
+                debug_assert!(offset <= TWELVE_BITS);
+                *imm = VirtualImmediate12::new_unchecked(
+                    offset,
+                    "Jump offsets only decrease during optimization",
+                );
+            }
+            _otherwise => (),
+        }
+    }
+    ok(new_ops, vec![], vec![])
+}
+
+/// `move $rA $rA` does nothing.
+fn remove_self_move(window: &[AllocatedOp]) -> Option<(usize, Vec<AllocatedOp>)> {
+    match &window.first()?.opcode {
+        AllocatedOpcode::MOVE(dst, src) if dst == src => Some((1, vec![])),
+        _otherwise => None,
+    }
+}
+
+/// In `move $rA $rB; move $rB $rA` the second move does nothing.
+fn remove_move_back(window: &[AllocatedOp]) -> Option<(usize, Vec<AllocatedOp>)> {
+    match (&window.first()?.opcode, &window.get(1)?.opcode) {
+        (AllocatedOpcode::MOVE(dst0, src0), AllocatedOpcode::MOVE(dst1, src1))
+            if dst0 == src1 && src0 == dst1 =>
+        {
+            Some((2, vec![window[0].clone()]))
+        }
+        _otherwise => None,
+    }
+}
+
+/// In `sw $rB $rA i; lw $rA $rB i` the load reads back the value which was just stored.
+fn remove_load_of_stored_word(window: &[AllocatedOp]) -> Option<(usize, Vec<AllocatedOp>)> {
+    match (&window.first()?.opcode, &window.get(1)?.opcode) {
+        (AllocatedOpcode::SW(addr0, val0, off0), AllocatedOpcode::LW(val1, addr1, off1))
+            if addr0 == addr1 && val0 == val1 && off0.value == off1.value =>
+        {
+            Some((2, vec![window[0].clone()]))
+        }
+        _otherwise => None,
+    }
+}
+
+/// In `lw $rA $rB i; sw $rB $rA i` the store writes back the value which was just loaded, as long
+/// as the load did not change the address.
+fn remove_store_of_loaded_word(window: &[AllocatedOp]) -> Option<(usize, Vec<AllocatedOp>)> {
+    match (&window.first()?.opcode, &window.get(1)?.opcode) {
+        (AllocatedOpcode::LW(val0, addr0, off0), AllocatedOpcode::SW(addr1, val1, off1))
+            if addr0 == addr1 && val0 == val1 && val0 != addr0 && off0.value == off1.value =>
+        {
+            Some((2, vec![window[0].clone()]))
+        }
+        _otherwise => None,
+    }
+}
+
+/// An op with no side effects whose result is overwritten by the following op, without being
+/// read by it, may be removed.
+fn remove_dead_write(window: &[AllocatedOp]) -> Option<(usize, Vec<AllocatedOp>)> {
+    let (first, second) = (&window.first()?.opcode, &window.get(1)?.opcode);
+    let dst = match first {
+        AllocatedOpcode::MOVE(dst, _)
+        | AllocatedOpcode::LWDataId(dst, _)
+        | AllocatedOpcode::AND(dst, ..)
+        | AllocatedOpcode::ANDI(dst, ..)
+        | AllocatedOpcode::OR(dst, ..)
+        | AllocatedOpcode::ORI(dst, ..)
+        | AllocatedOpcode::XOR(dst, ..)
+        | AllocatedOpcode::XORI(dst, ..)
+        | AllocatedOpcode::NOT(dst, _)
+        | AllocatedOpcode::EQ(dst, ..)
+        | AllocatedOpcode::LT(dst, ..)
+        | AllocatedOpcode::GT(dst, ..) => dst,
+        _otherwise => return None,
+    };
+    if matches!(dst, AllocatedRegister::Constant(_)) {
+        return None;
+    }
+    if second.def_registers().contains(dst) && !second.use_registers().contains(dst) {
+        Some((1, vec![]))
+    } else {
+        None
+    }
+}
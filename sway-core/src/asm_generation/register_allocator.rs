@@ -0,0 +1,449 @@
+//! Register allocation by graph colouring.
+//!
+//! Two virtual registers interfere if one is written to while the other is live, in which case
+//! they may not share a physical register.  The interference graph is coloured with the
+//! allocatable registers using the usual simplify/select approach: registers with fewer
+//! neighbours than there are colours are removed from the graph first, as they can always be
+//! coloured, and the rest are coloured optimistically.
+//!
+//! Registers which cannot be coloured are spilled to stack memory and are reloaded into short
+//! lived temporary registers around each use and definition.  The allocation is then attempted
+//! again.
+//!
+//! Each called function spills to its own area of the stack, reserved on entry to the function
+//! between the [OrganizationalOp::SpillAreaStart] and [OrganizationalOp::SpillAreaEnd] markers,
+//! so that a recursive call doesn't overwrite its caller's spilled registers.  The first slot of
+//! the area holds the caller's spill pointer.  Everything before the first function, i.e., the
+//! entry point, spills to an area reserved at the start of the program.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use either::Either;
+
+use super::{compiler_constants, liveness::liveness_analysis, RegisterSequencer};
+use crate::{
+    asm_lang::{
+        allocated_ops::AllocatedRegister, virtual_register::*, Op, OrganizationalOp,
+        VirtualImmediate12, VirtualImmediate24, VirtualOp,
+    },
+    error::*,
+};
+
+/// Allocates a register for every virtual register in `ops`, spilling registers to memory where
+/// necessary.  Returns the ops, which will include any spill code, and the mapping from virtual
+/// to allocated registers.
+pub(crate) fn allocate_registers(
+    mut ops: Vec<Op>,
+    register_sequencer: &mut RegisterSequencer,
+) -> CompileResult<(Vec<Op>, HashMap<VirtualRegister, AllocatedRegister>)> {
+    // The offset of each spilled register within its spill area, and the number of slots used in
+    // each area.
+    let mut spill_slots: HashMap<VirtualRegister, u64> = HashMap::new();
+    let mut area_sizes: HashMap<usize, u64> = HashMap::new();
+    let mut spill_temps: HashSet<VirtualRegister> = HashSet::new();
+    let mapping = loop {
+        // Once anything is spilled the last allocatable register is needed for the spill pointer.
+        let num_colours = if spill_slots.is_empty() {
+            compiler_constants::NUM_ALLOCATABLE_REGISTERS
+        } else {
+            compiler_constants::NUM_ALLOCATABLE_REGISTERS - 1
+        };
+
+        // Registers used in more than one spill area can't be spilled, as there's no one slot to
+        // spill them to.
+        let reg_areas = register_spill_areas(&ops);
+        let unspillable = reg_areas
+            .iter()
+            .filter(|(_, area)| area.is_none())
+            .map(|(reg, _)| reg.clone())
+            .chain(spill_temps.iter().cloned())
+            .collect::<HashSet<_>>();
+
+        let graph = InterferenceGraph::build(&ops);
+        match graph.colour(num_colours, &unspillable) {
+            Ok(mapping) => break mapping,
+            Err(spills) => {
+                for reg in spills {
+                    let area = match reg_areas.get(&reg).copied().flatten() {
+                        Some(area) => area,
+                        None => {
+                            return err(
+                                Vec::new(),
+                                vec![CompileError::Internal(
+                                    "Unable to allocate a register which is shared between \
+                                    functions.",
+                                    register_span(&ops, &reg),
+                                )],
+                            )
+                        }
+                    };
+                    // The first slot of a called function's area holds the caller's spill pointer.
+                    let slot = area_sizes
+                        .entry(area)
+                        .or_insert(if area == 0 { 0 } else { 1 });
+                    if *slot > compiler_constants::TWELVE_BITS {
+                        return err(
+                            Vec::new(),
+                            vec![CompileError::Internal(
+                                "Functions which spill more than 4095 registers are unsupported.",
+                                register_span(&ops, &reg),
+                            )],
+                        );
+                    }
+                    spill_slots.insert(reg, *slot);
+                    *slot += 1;
+                }
+                ops = insert_spill_code(ops, &spill_slots, &mut spill_temps, register_sequencer);
+            }
+        }
+    };
+
+    let ops = realize_spill_areas(ops, &area_sizes);
+    ok((ops, mapping), Vec::new(), Vec::new())
+}
+
+/// Finds the spill area which each virtual register is used in.  Area 0 is for the ops before
+/// the first [OrganizationalOp::SpillAreaStart] and each start marker begins a new area which is
+/// ended by the next [OrganizationalOp::SpillAreaEnd].  Registers used in more than one area, or
+/// between the end of one area and the start of the next, have no area.
+fn register_spill_areas(ops: &[Op]) -> HashMap<VirtualRegister, Option<usize>> {
+    let mut reg_areas = HashMap::new();
+    let mut area = Some(0);
+    let mut num_areas = 1;
+    for op in ops {
+        if let Either::Right(OrganizationalOp::SpillAreaStart(_)) = op.opcode {
+            area = Some(num_areas);
+            num_areas += 1;
+        }
+        for reg in op
+            .use_registers()
+            .into_iter()
+            .chain(op.def_registers())
+            .filter(|reg| matches!(reg, VirtualRegister::Virtual(_)))
+        {
+            reg_areas
+                .entry(reg.clone())
+                .and_modify(|reg_area: &mut Option<usize>| {
+                    if *reg_area != area {
+                        *reg_area = None
+                    }
+                })
+                .or_insert(area);
+        }
+        if let Either::Right(OrganizationalOp::SpillAreaEnd) = op.opcode {
+            area = None;
+        }
+    }
+    reg_areas
+}
+
+// The span of the first op which uses `reg` and has one, for reporting errors.
+fn register_span(ops: &[Op], reg: &VirtualRegister) -> crate::span::Span {
+    ops.iter()
+        .filter(|op| op.use_registers().contains(reg) || op.def_registers().contains(reg))
+        .find_map(|op| op.owning_span.clone())
+        .unwrap_or_else(|| crate::span::Span {
+            span: pest::Span::new(" ".into(), 0, 0).unwrap(),
+            path: None,
+        })
+}
+
+struct InterferenceGraph {
+    // The registers in order of first appearance, so that allocation is deterministic.
+    registers: Vec<VirtualRegister>,
+    edges: Vec<BTreeSet<usize>>,
+}
+
+impl InterferenceGraph {
+    fn build(ops: &[Op]) -> Self {
+        let mut registers = Vec::new();
+        let mut reg_to_idx: HashMap<VirtualRegister, usize> = HashMap::new();
+        for op in ops {
+            let mut op_regs = op
+                .use_registers()
+                .into_iter()
+                .chain(op.def_registers())
+                .filter(|reg| matches!(reg, VirtualRegister::Virtual(_)))
+                .collect::<Vec<_>>();
+            op_regs.sort_by_key(|reg| reg.to_string());
+            for reg in op_regs {
+                if !reg_to_idx.contains_key(reg) {
+                    reg_to_idx.insert(reg.clone(), registers.len());
+                    registers.push(reg.clone());
+                }
+            }
+        }
+
+        let mut edges = vec![BTreeSet::new(); registers.len()];
+        let mut add_edge = |a: usize, b: usize| {
+            if a != b {
+                edges[a].insert(b);
+                edges[b].insert(a);
+            }
+        };
+
+        let live_out = liveness_analysis(ops);
+        for (op, live) in ops.iter().zip(live_out.iter()) {
+            let defs = op
+                .def_registers()
+                .into_iter()
+                .filter_map(|reg| reg_to_idx.get(reg))
+                .collect::<Vec<_>>();
+
+            // The source of a move holds the same value as its destination, so the two may share
+            // a register unless they interfere elsewhere.
+            let move_src = match &op.opcode {
+                Either::Left(VirtualOp::MOVE(_, src)) => reg_to_idx.get(src),
+                _otherwise => None,
+            };
+
+            for def in &defs {
+                for live_reg in live.iter().filter_map(|reg| reg_to_idx.get(reg)) {
+                    if Some(live_reg) != move_src {
+                        add_edge(**def, *live_reg);
+                    }
+                }
+                for other_def in &defs {
+                    add_edge(**def, **other_def);
+                }
+            }
+        }
+
+        InterferenceGraph { registers, edges }
+    }
+
+    /// Colours the graph with `num_colours` colours, returning the registers which need to be
+    /// spilled if that is not possible.  Registers in `unspillable` are never chosen as spill
+    /// candidates.
+    fn colour(
+        &self,
+        num_colours: u8,
+        unspillable: &HashSet<VirtualRegister>,
+    ) -> Result<HashMap<VirtualRegister, AllocatedRegister>, Vec<VirtualRegister>> {
+        let num_colours = num_colours as usize;
+        let num_regs = self.registers.len();
+        let mut degrees = self.edges.iter().map(BTreeSet::len).collect::<Vec<_>>();
+        let mut removed = vec![false; num_regs];
+        let mut stack = Vec::with_capacity(num_regs);
+
+        // Simplify: repeatedly remove a register which is sure to be colourable, or if there are
+        // none then the register with the most neighbours as a potential spill.
+        let mut worklist = (0..num_regs)
+            .rev()
+            .filter(|idx| degrees[*idx] < num_colours)
+            .collect::<Vec<_>>();
+        while stack.len() < num_regs {
+            let next = match worklist.pop() {
+                Some(idx) if removed[idx] => continue,
+                Some(idx) => idx,
+                None => (0..num_regs)
+                    .filter(|idx| !removed[*idx])
+                    .max_by_key(|idx| {
+                        (
+                            !unspillable.contains(&self.registers[*idx]),
+                            degrees[*idx],
+                            std::cmp::Reverse(*idx),
+                        )
+                    })
+                    .unwrap(),
+            };
+            removed[next] = true;
+            stack.push(next);
+            for neighbour in &self.edges[next] {
+                if !removed[*neighbour] {
+                    degrees[*neighbour] -= 1;
+                    if degrees[*neighbour] == num_colours - 1 {
+                        worklist.push(*neighbour);
+                    }
+                }
+            }
+        }
+
+        // Select: colour the registers in reverse order of removal, each with the lowest colour not
+        // used by a neighbour.
+        let mut colours: Vec<Option<u8>> = vec![None; num_regs];
+        let mut spills = Vec::new();
+        while let Some(idx) = stack.pop() {
+            let neighbour_colours = self.edges[idx]
+                .iter()
+                .filter_map(|neighbour| colours[*neighbour])
+                .collect::<HashSet<_>>();
+            match (0..num_colours as u8).find(|colour| !neighbour_colours.contains(colour)) {
+                Some(colour) => colours[idx] = Some(colour),
+                None => spills.push(self.registers[idx].clone()),
+            }
+        }
+
+        if spills.is_empty() {
+            Ok(self
+                .registers
+                .iter()
+                .cloned()
+                .zip(colours.into_iter().map(|colour| {
+                    AllocatedRegister::Allocated(colour.expect("all registers are coloured"))
+                }))
+                .collect())
+        } else {
+            Err(spills)
+        }
+    }
+}
+
+/// Rewrites `ops` so that every register in `spill_slots` lives in memory.  Each use of one is
+/// preceded by a reload into a new temporary register and each definition is followed by a store
+/// from one.  The new temporaries are added to `spill_temps`.
+fn insert_spill_code(
+    ops: Vec<Op>,
+    spill_slots: &HashMap<VirtualRegister, u64>,
+    spill_temps: &mut HashSet<VirtualRegister>,
+    register_sequencer: &mut RegisterSequencer,
+) -> Vec<Op> {
+    let spill_ptr = VirtualRegister::Constant(ConstantRegister::SpillPointer);
+    let slot_offset = |reg: &VirtualRegister| {
+        VirtualImmediate12::new_unchecked(spill_slots[reg], "spill slot offsets were checked")
+    };
+
+    let mut new_ops = Vec::with_capacity(ops.len());
+    for op in ops {
+        // A call only marks where its arguments and return value are live.  They are written and
+        // read elsewhere, so a spilled register can just be dropped from it.
+        if let Either::Right(OrganizationalOp::Call(label, args, ret)) = &op.opcode {
+            let args = args
+                .iter()
+                .filter(|arg| !spill_slots.contains_key(arg))
+                .cloned()
+                .collect();
+            let ret = if spill_slots.contains_key(ret) {
+                let temp = register_sequencer.next();
+                spill_temps.insert(temp.clone());
+                temp
+            } else {
+                ret.clone()
+            };
+            new_ops.push(Op {
+                opcode: Either::Right(OrganizationalOp::Call(label.clone(), args, ret)),
+                comment: op.comment,
+                owning_span: op.owning_span,
+            });
+            continue;
+        }
+
+        let mut uses = op
+            .use_registers()
+            .into_iter()
+            .filter(|reg| spill_slots.contains_key(reg))
+            .collect::<Vec<_>>();
+        let mut defs = op
+            .def_registers()
+            .into_iter()
+            .filter(|reg| spill_slots.contains_key(reg))
+            .collect::<Vec<_>>();
+        if uses.is_empty() && defs.is_empty() {
+            new_ops.push(op);
+            continue;
+        }
+        uses.sort_by_key(|reg| reg.to_string());
+        defs.sort_by_key(|reg| reg.to_string());
+
+        let mut temps = HashMap::new();
+        for reg in uses.iter().chain(defs.iter()) {
+            temps
+                .entry(*reg)
+                .or_insert_with(|| register_sequencer.next());
+        }
+        spill_temps.extend(temps.values().cloned());
+
+        for reg in &uses {
+            new_ops.push(Op::unowned_new_with_comment(
+                VirtualOp::LW(temps[reg].clone(), spill_ptr.clone(), slot_offset(reg)),
+                "reload spilled register",
+            ));
+        }
+        new_ops.push(op.update_register(&temps.iter().map(|(reg, temp)| (*reg, temp)).collect()));
+        for reg in &defs {
+            new_ops.push(Op::unowned_new_with_comment(
+                VirtualOp::SW(spill_ptr.clone(), temps[reg].clone(), slot_offset(reg)),
+                "spill register",
+            ));
+        }
+    }
+    new_ops
+}
+
+/// Replaces the spill area markers.  Functions which spill reserve their area on entry, pointed
+/// to by the spill pointer, and save the caller's spill pointer in its first slot.  On exit they
+/// restore the caller's spill pointer and free the area.  The markers of functions which don't
+/// spill are just removed.
+///
+/// If the entry point spills then its area is reserved right after the program's preamble jumps
+/// over its metadata to the first label.
+fn realize_spill_areas(ops: Vec<Op>, area_sizes: &HashMap<usize, u64>) -> Vec<Op> {
+    let spill_ptr = VirtualRegister::Constant(ConstantRegister::SpillPointer);
+    let stack_ptr = VirtualRegister::Constant(ConstantRegister::StackPointer);
+    // The number of slots is at most 2^12, which has been checked, so the size fits in 24 bits.
+    let area_size = |num_slots: u64| {
+        VirtualImmediate24::new_unchecked(num_slots * 8, "spill area sizes were checked")
+    };
+
+    let mut new_ops = Vec::with_capacity(ops.len());
+    let mut area = 0;
+    let mut entry_area_idx = None;
+    for op in ops {
+        match &op.opcode {
+            Either::Right(OrganizationalOp::Label(_)) if entry_area_idx.is_none() => {
+                new_ops.push(op);
+                entry_area_idx = Some(new_ops.len());
+            }
+            Either::Right(OrganizationalOp::SpillAreaStart(scratch_reg)) => {
+                area += 1;
+                if let Some(num_slots) = area_sizes.get(&area) {
+                    new_ops.push(Op::unowned_register_move_comment(
+                        scratch_reg.clone(),
+                        stack_ptr.clone(),
+                        "spill area",
+                    ));
+                    new_ops.push(Op::unowned_stack_allocate_memory(area_size(*num_slots)));
+                    new_ops.push(Op::unowned_new_with_comment(
+                        VirtualOp::SW(
+                            scratch_reg.clone(),
+                            spill_ptr.clone(),
+                            VirtualImmediate12 { value: 0 },
+                        ),
+                        "save caller's spill pointer",
+                    ));
+                    new_ops.push(Op::unowned_register_move(
+                        spill_ptr.clone(),
+                        scratch_reg.clone(),
+                    ));
+                }
+            }
+            Either::Right(OrganizationalOp::SpillAreaEnd) => {
+                if let Some(num_slots) = area_sizes.get(&area) {
+                    new_ops.push(Op::unowned_new_with_comment(
+                        VirtualOp::LW(
+                            spill_ptr.clone(),
+                            spill_ptr.clone(),
+                            VirtualImmediate12 { value: 0 },
+                        ),
+                        "restore caller's spill pointer",
+                    ));
+                    new_ops.push(Op::unowned_new_with_comment(
+                        VirtualOp::CFSI(area_size(*num_slots)),
+                        "free spill area",
+                    ));
+                }
+            }
+            _otherwise => new_ops.push(op),
+        }
+    }
+
+    if let Some(num_slots) = area_sizes.get(&0) {
+        let insert_idx = entry_area_idx.unwrap_or(0);
+        let spill_area_ops = [
+            Op::unowned_register_move_comment(spill_ptr, stack_ptr, "spill area"),
+            Op::unowned_stack_allocate_memory(area_size(*num_slots)),
+        ];
+        new_ops.splice(insert_idx..insert_idx, spill_area_ops);
+    }
+    new_ops
+}
@@ -1,6 +1,6 @@
 use super::*;
-use crate::asm_lang::{ConstantRegister, VirtualRegister};
-use crate::semantic_analysis::ast_node::TypedWhileLoop;
+use crate::asm_lang::{ConstantRegister, VirtualImmediate12, VirtualOp, VirtualRegister};
+use crate::semantic_analysis::ast_node::{TypedForLoop, TypedForLoopIterable, TypedWhileLoop};
 pub(super) fn convert_while_loop_to_asm(
     r#loop: &TypedWhileLoop,
     namespace: &mut AsmNamespace,
@@ -53,13 +53,16 @@ pub(super) fn convert_while_loop_to_asm(
 
     // the implicit return value of a while loop block, if any, should be ignored,
     // so we pass None into the final argument of code block conversion
-    // step 3: run the loop body
+    // step 3: run the loop body, in which `continue` re-evaluates the condition and `break`
+    // jumps to the exit label
+    namespace.push_loop_labels(label.clone(), exit_label.clone());
     let mut body = check!(
         convert_code_block_to_asm(&r#loop.body, namespace, register_sequencer, None),
         vec![],
         warnings,
         errors
     );
+    namespace.pop_loop_labels();
     buf.append(&mut body);
 
     // step 4: jump back to beginning to re-evaluate the condition
@@ -74,3 +77,181 @@ pub(super) fn convert_while_loop_to_asm(
 
     ok(buf, warnings, errors)
 }
+
+pub(super) fn convert_for_loop_to_asm(
+    r#loop: &TypedForLoop,
+    namespace: &mut AsmNamespace,
+    register_sequencer: &mut RegisterSequencer,
+) -> CompileResult<Vec<Op>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let mut buf: Vec<Op> = vec![];
+    // A for loop counts an index up from the start of its range, or from zero for an array, and
+    // consists of (in order of asm):
+    // 0. Evaluate the iterable, putting the first index and the end of the range in registers
+    // 1. A label to jump to
+    // 2. Branch based on whether the index has reached the end
+    // 3. Put the index, or the array element at the index, into the loop variable
+    // 4. Loop Body
+    // 5. A label for `continue` to jump to, then increment the index
+    // 6. Jump to beginning label
+    // 7. Exit label
+
+    // step 0
+    let index_register = register_sequencer.next();
+    let end_register = register_sequencer.next();
+    let array_register = match &r#loop.iterable {
+        TypedForLoopIterable::Array(array) => {
+            let array_register = register_sequencer.next();
+            buf.append(&mut check!(
+                convert_expression_to_asm(array, namespace, &array_register, register_sequencer),
+                return err(warnings, errors),
+                warnings,
+                errors
+            ));
+            let count = match check_std_result!(
+                resolve_type(array.return_type, &array.span),
+                warnings,
+                errors
+            ) {
+                TypeInfo::Array(_, count) => count as u64,
+                _otherwise => {
+                    errors.push(CompileError::Internal(
+                        "attempt to iterate over a non-array",
+                        array.span.clone(),
+                    ));
+                    return err(warnings, errors);
+                }
+            };
+            buf.push(Op::register_move_comment(
+                index_register.clone(),
+                VirtualRegister::Constant(ConstantRegister::Zero),
+                array.span.clone(),
+                "start at the first array element",
+            ));
+            let count_register =
+                set_large_register_value(count, &end_register, &mut buf, &array.span);
+            if *count_register != end_register {
+                buf.push(Op::unowned_register_move(
+                    end_register.clone(),
+                    count_register.clone(),
+                ));
+            }
+            Some((array_register, array))
+        }
+        TypedForLoopIterable::Range { start, end } => {
+            buf.append(&mut check!(
+                convert_expression_to_asm(start, namespace, &index_register, register_sequencer),
+                return err(warnings, errors),
+                warnings,
+                errors
+            ));
+            buf.append(&mut check!(
+                convert_expression_to_asm(end, namespace, &end_register, register_sequencer),
+                return err(warnings, errors),
+                warnings,
+                errors
+            ));
+            None
+        }
+    };
+
+    // step 1
+    let label = register_sequencer.get_label();
+    let continue_label = register_sequencer.get_label();
+    let exit_label = register_sequencer.get_label();
+    buf.push(Op::jump_label_comment(
+        label.clone(),
+        r#loop.variable.span().clone(),
+        "begin for loop",
+    ));
+
+    // step 2
+    // if the index isn't less than the end then jump to the end of the block.
+    let condition_result_register = register_sequencer.next();
+    buf.push(Op {
+        opcode: Either::Left(VirtualOp::LT(
+            condition_result_register.clone(),
+            index_register.clone(),
+            end_register,
+        )),
+        owning_span: Some(r#loop.variable.span().clone()),
+        comment: "compare for loop index to its end".into(),
+    });
+    buf.push(Op::jump_if_not_equal(
+        condition_result_register,
+        VirtualRegister::Constant(ConstantRegister::One),
+        exit_label.clone(),
+    ));
+
+    // step 3
+    let variable_register = register_sequencer.next();
+    match array_register {
+        Some((array_register, array)) => {
+            let elem_type = check_std_result!(
+                resolve_type(r#loop.variable_type, r#loop.variable.span()),
+                warnings,
+                errors
+            );
+            buf.append(&mut check!(
+                convert_array_element_to_asm(
+                    &array_register,
+                    &index_register,
+                    &elem_type,
+                    &array.span,
+                    &variable_register,
+                    register_sequencer,
+                ),
+                return err(warnings, errors),
+                warnings,
+                errors
+            ));
+        }
+        None => buf.push(Op::register_move_comment(
+            variable_register.clone(),
+            index_register.clone(),
+            r#loop.variable.span().clone(),
+            "for loop variable",
+        )),
+    }
+    namespace.insert_variable(r#loop.variable.clone(), variable_register);
+
+    // step 4
+    // as with a while loop, the implicit return value of the body is ignored.
+    namespace.push_loop_labels(continue_label.clone(), exit_label.clone());
+    let mut body = check!(
+        convert_code_block_to_asm(&r#loop.body, namespace, register_sequencer, None),
+        vec![],
+        warnings,
+        errors
+    );
+    namespace.pop_loop_labels();
+    buf.append(&mut body);
+
+    // step 5
+    buf.push(Op::unowned_jump_label_comment(
+        continue_label,
+        "continue for loop",
+    ));
+    buf.push(Op {
+        opcode: Either::Left(VirtualOp::ADDI(
+            index_register.clone(),
+            index_register,
+            VirtualImmediate12 { value: 1 },
+        )),
+        owning_span: Some(r#loop.variable.span().clone()),
+        comment: "increment for loop index".into(),
+    });
+
+    // step 6: jump back to beginning to compare the index again
+    buf.push(Op::jump_to_label(label));
+
+    // step 7
+    buf.push(Op::jump_label_comment(
+        exit_label,
+        r#loop.body.whole_block_span.clone(),
+        "exit for loop",
+    ));
+
+    ok(buf, warnings, errors)
+}
@@ -14,7 +14,7 @@ use super::*;
 use crate::asm_generation::DataSection;
 use either::Either;
 use fuel_asm::Opcode as VmOp;
-use std::fmt;
+use std::{collections::HashSet, fmt};
 use sway_types::span::Span;
 
 const COMMENT_START_COLUMN: usize = 30;
@@ -134,6 +134,12 @@ pub(crate) enum AllocatedOpcode {
         AllocatedRegister,
         AllocatedRegister,
     ),
+    LOGD(
+        AllocatedRegister,
+        AllocatedRegister,
+        AllocatedRegister,
+        AllocatedRegister,
+    ),
     MINT(AllocatedRegister),
     RVRT(AllocatedRegister),
     SLDC(AllocatedRegister, AllocatedRegister, AllocatedRegister),
@@ -160,6 +166,188 @@ pub(crate) enum AllocatedOpcode {
     DataSectionRegisterLoadPlaceholder,
 }
 
+impl AllocatedOpcode {
+    /// Returns the registers which are read by this op.
+    pub(crate) fn use_registers(&self) -> HashSet<&AllocatedRegister> {
+        use AllocatedOpcode::*;
+        (match self {
+            ADD(_r1, r2, r3) => vec![r2, r3],
+            ADDI(_r1, r2, _i) => vec![r2],
+            AND(_r1, r2, r3) => vec![r2, r3],
+            ANDI(_r1, r2, _i) => vec![r2],
+            DIV(_r1, r2, r3) => vec![r2, r3],
+            DIVI(_r1, r2, _i) => vec![r2],
+            EQ(_r1, r2, r3) => vec![r2, r3],
+            EXP(_r1, r2, r3) => vec![r2, r3],
+            EXPI(_r1, r2, _i) => vec![r2],
+            GT(_r1, r2, r3) => vec![r2, r3],
+            LT(_r1, r2, r3) => vec![r2, r3],
+            MLOG(_r1, r2, r3) => vec![r2, r3],
+            MROO(_r1, r2, r3) => vec![r2, r3],
+            MOD(_r1, r2, r3) => vec![r2, r3],
+            MODI(_r1, r2, _i) => vec![r2],
+            MOVE(_r1, r2) => vec![r2],
+            MUL(_r1, r2, r3) => vec![r2, r3],
+            MULI(_r1, r2, _i) => vec![r2],
+            NOT(_r1, r2) => vec![r2],
+            OR(_r1, r2, r3) => vec![r2, r3],
+            ORI(_r1, r2, _i) => vec![r2],
+            SLL(_r1, r2, r3) => vec![r2, r3],
+            SLLI(_r1, r2, _i) => vec![r2],
+            SRL(_r1, r2, r3) => vec![r2, r3],
+            SRLI(_r1, r2, _i) => vec![r2],
+            SUB(_r1, r2, r3) => vec![r2, r3],
+            SUBI(_r1, r2, _i) => vec![r2],
+            XOR(_r1, r2, r3) => vec![r2, r3],
+            XORI(_r1, r2, _i) => vec![r2],
+            CIMV(_r1, r2, r3) => vec![r2, r3],
+            CTMV(_r1, r2) => vec![r2],
+            JI(_im) => vec![],
+            JNEI(r1, r2, _i) => vec![r1, r2],
+            RET(r1) => vec![r1],
+            RETD(r1, r2) => vec![r1, r2],
+            CFEI(_imm) => vec![],
+            CFSI(_imm) => vec![],
+            LB(_r1, r2, _i) => vec![r2],
+            LWDataId(_r1, _i) => vec![],
+            LW(_r1, r2, _i) => vec![r2],
+            ALOC(r1) => vec![r1],
+            MCL(r1, r2) => vec![r1, r2],
+            MCLI(r1, _imm) => vec![r1],
+            MCP(r1, r2, r3) => vec![r1, r2, r3],
+            MEQ(_r1, r2, r3, r4) => vec![r2, r3, r4],
+            MCPI(r1, r2, _imm) => vec![r1, r2],
+            SB(r1, r2, _i) => vec![r1, r2],
+            SW(r1, r2, _i) => vec![r1, r2],
+            BAL(_r1, r2, r3) => vec![r2, r3],
+            BHSH(r1, r2) => vec![r1, r2],
+            BHEI(_r1) => vec![],
+            BURN(r1) => vec![r1],
+            CALL(r1, r2, r3, r4) => vec![r1, r2, r3, r4],
+            CCP(r1, r2, r3, r4) => vec![r1, r2, r3, r4],
+            CROO(r1, r2) => vec![r1, r2],
+            CSIZ(_r1, r2) => vec![r2],
+            CB(r1) => vec![r1],
+            LDC(r1, r2, r3) => vec![r1, r2, r3],
+            LOG(r1, r2, r3, r4) => vec![r1, r2, r3, r4],
+            LOGD(r1, r2, r3, r4) => vec![r1, r2, r3, r4],
+            MINT(r1) => vec![r1],
+            RVRT(r1) => vec![r1],
+            SLDC(r1, r2, r3) => vec![r1, r2, r3],
+            SRW(_r1, r2) => vec![r2],
+            SRWQ(r1, r2) => vec![r1, r2],
+            SWW(r1, r2) => vec![r1, r2],
+            SWWQ(r1, r2) => vec![r1, r2],
+            TR(r1, r2, r3) => vec![r1, r2, r3],
+            TRO(r1, r2, r3, r4) => vec![r1, r2, r3, r4],
+            ECR(r1, r2, r3) => vec![r1, r2, r3],
+            K256(r1, r2, r3) => vec![r1, r2, r3],
+            S256(r1, r2, r3) => vec![r1, r2, r3],
+            XOS(_r1, r2) => vec![r2],
+            NOOP => vec![],
+            FLAG(r1) => vec![r1],
+            GM(_r1, _imm) => vec![],
+            Undefined | DataSectionOffsetPlaceholder => vec![],
+            DataSectionRegisterLoadPlaceholder => vec![&AllocatedRegister::Constant(
+                ConstantRegister::InstructionStart,
+            )],
+        })
+        .into_iter()
+        .collect()
+    }
+
+    /// Returns the registers which are written to by this op.  Ops which only write to memory
+    /// via a register, such as `SW` or `MCP`, do not define that register.
+    pub(crate) fn def_registers(&self) -> HashSet<&AllocatedRegister> {
+        use AllocatedOpcode::*;
+        (match self {
+            ADD(r1, _r2, _r3) => vec![r1],
+            ADDI(r1, _r2, _i) => vec![r1],
+            AND(r1, _r2, _r3) => vec![r1],
+            ANDI(r1, _r2, _i) => vec![r1],
+            DIV(r1, _r2, _r3) => vec![r1],
+            DIVI(r1, _r2, _i) => vec![r1],
+            EQ(r1, _r2, _r3) => vec![r1],
+            EXP(r1, _r2, _r3) => vec![r1],
+            EXPI(r1, _r2, _i) => vec![r1],
+            GT(r1, _r2, _r3) => vec![r1],
+            LT(r1, _r2, _r3) => vec![r1],
+            MLOG(r1, _r2, _r3) => vec![r1],
+            MROO(r1, _r2, _r3) => vec![r1],
+            MOD(r1, _r2, _r3) => vec![r1],
+            MODI(r1, _r2, _i) => vec![r1],
+            MOVE(r1, _r2) => vec![r1],
+            MUL(r1, _r2, _r3) => vec![r1],
+            MULI(r1, _r2, _i) => vec![r1],
+            NOT(r1, _r2) => vec![r1],
+            OR(r1, _r2, _r3) => vec![r1],
+            ORI(r1, _r2, _i) => vec![r1],
+            SLL(r1, _r2, _r3) => vec![r1],
+            SLLI(r1, _r2, _i) => vec![r1],
+            SRL(r1, _r2, _r3) => vec![r1],
+            SRLI(r1, _r2, _i) => vec![r1],
+            SUB(r1, _r2, _r3) => vec![r1],
+            SUBI(r1, _r2, _i) => vec![r1],
+            XOR(r1, _r2, _r3) => vec![r1],
+            XORI(r1, _r2, _i) => vec![r1],
+            CIMV(r1, _r2, _r3) => vec![r1],
+            CTMV(r1, _r2) => vec![r1],
+            LB(r1, _r2, _i) => vec![r1],
+            LWDataId(r1, _i) => vec![r1],
+            LW(r1, _r2, _i) => vec![r1],
+            MEQ(r1, _r2, _r3, _r4) => vec![r1],
+            BAL(r1, _r2, _r3) => vec![r1],
+            BHEI(r1) => vec![r1],
+            CSIZ(r1, _r2) => vec![r1],
+            SRW(r1, _r2) => vec![r1],
+            XOS(r1, _r2) => vec![r1],
+            GM(r1, _imm) => vec![r1],
+            DataSectionRegisterLoadPlaceholder => vec![&AllocatedRegister::Constant(
+                ConstantRegister::DataSectionStart,
+            )],
+            JI(_)
+            | JNEI(..)
+            | RET(_)
+            | RETD(..)
+            | CFEI(_)
+            | CFSI(_)
+            | ALOC(_)
+            | MCL(..)
+            | MCLI(..)
+            | MCP(..)
+            | MCPI(..)
+            | SB(..)
+            | SW(..)
+            | BHSH(..)
+            | BURN(_)
+            | CALL(..)
+            | CCP(..)
+            | CROO(..)
+            | CB(_)
+            | LDC(..)
+            | LOG(..)
+            | LOGD(..)
+            | MINT(_)
+            | RVRT(_)
+            | SLDC(..)
+            | SRWQ(..)
+            | SWW(..)
+            | SWWQ(..)
+            | TR(..)
+            | TRO(..)
+            | ECR(..)
+            | K256(..)
+            | S256(..)
+            | NOOP
+            | FLAG(_)
+            | Undefined
+            | DataSectionOffsetPlaceholder => vec![],
+        })
+        .into_iter()
+        .collect()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct AllocatedOp {
     pub(crate) opcode: AllocatedOpcode,
@@ -232,6 +420,7 @@ impl fmt::Display for AllocatedOp {
             CB(a)           => format!("cb   {}", a),
             LDC(a, b, c)    => format!("ldc  {} {} {}", a, b, c),
             LOG(a, b, c, d) => format!("log  {} {} {} {}", a, b, c, d),
+            LOGD(a, b, c, d) => format!("logd {} {} {} {}", a, b, c, d),
             MINT(a)         => format!("mint {}", a),
             RVRT(a)         => format!("rvrt {}", a),
             SLDC(a, b, c)   => format!("sldc {} {} {}", a, b, c),
@@ -336,6 +525,7 @@ impl AllocatedOp {
             CB  (a)         => VmOp::CB  (a.to_register_id()),
             LDC (a, b, c)   => VmOp::LDC (a.to_register_id(), b.to_register_id(), c.to_register_id()),
             LOG (a, b, c, d)=> VmOp::LOG (a.to_register_id(), b.to_register_id(), c.to_register_id(), d.to_register_id()),
+            LOGD(a, b, c, d)=> VmOp::LOGD(a.to_register_id(), b.to_register_id(), c.to_register_id(), d.to_register_id()),
             MINT(a)         => VmOp::MINT(a.to_register_id()),
             RVRT(a)         => VmOp::RVRT(a.to_register_id()),
             SLDC(a, b, c)   => VmOp::SLDC(a.to_register_id(), b.to_register_id(), c.to_register_id()),
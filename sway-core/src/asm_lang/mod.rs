@@ -18,7 +18,10 @@ use crate::{asm_generation::DataId, error::*, parse_tree::AsmRegister, Ident};
 use sway_types::span::Span;
 
 use either::Either;
-use std::{collections::HashSet, fmt};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
 
 /// The column where the ; for comments starts
 const COMMENT_START_COLUMN: usize = 40;
@@ -238,6 +241,21 @@ impl Op {
         }
     }
 
+    /// Calls the function at [Label] `label`, passing it `args` and receiving its return value in
+    /// `ret`.
+    pub(crate) fn call_comment(
+        label: Label,
+        args: Vec<VirtualRegister>,
+        ret: VirtualRegister,
+        comment: impl Into<String>,
+    ) -> Self {
+        Op {
+            opcode: Either::Right(OrganizationalOp::Call(label, args, ret)),
+            comment: comment.into(),
+            owning_span: None,
+        }
+    }
+
     /// Jumps to [Label] `label`  if the given [VirtualRegister] `reg1` is not equal to `reg0`.
     pub(crate) fn jump_if_not_equal(
         reg0: VirtualRegister,
@@ -251,6 +269,38 @@ impl Op {
         }
     }
 
+    /// Returns the registers read by this op.
+    pub(crate) fn use_registers(&self) -> HashSet<&VirtualRegister> {
+        match &self.opcode {
+            Either::Left(virt_op) => virt_op.use_registers(),
+            Either::Right(org_op) => org_op.use_registers(),
+        }
+    }
+
+    /// Returns the registers written to by this op.
+    pub(crate) fn def_registers(&self) -> HashSet<&VirtualRegister> {
+        match &self.opcode {
+            Either::Left(virt_op) => virt_op.def_registers(),
+            Either::Right(org_op) => org_op.def_registers(),
+        }
+    }
+
+    /// Returns a copy of this op with each register found in `reg_to_reg_map` replaced by the
+    /// register it maps to.
+    pub(crate) fn update_register(
+        &self,
+        reg_to_reg_map: &HashMap<&VirtualRegister, &VirtualRegister>,
+    ) -> Self {
+        Op {
+            opcode: match &self.opcode {
+                Either::Left(virt_op) => Either::Left(virt_op.update_register(reg_to_reg_map)),
+                Either::Right(org_op) => Either::Right(org_op.update_register(reg_to_reg_map)),
+            },
+            comment: self.comment.clone(),
+            owning_span: self.owning_span.clone(),
+        }
+    }
+
     pub(crate) fn parse_opcode(
         name: &Ident,
         args: &[VirtualRegister],
@@ -783,6 +833,15 @@ impl Op {
                     );
                     VirtualOp::LOG(r1, r2, r3, r4)
                 }
+                "logd" => {
+                    let (r1, r2, r3, r4) = check!(
+                        four_regs(args, immediate, whole_op_span),
+                        return err(warnings, errors),
+                        warnings,
+                        errors
+                    );
+                    VirtualOp::LOGD(r1, r2, r3, r4)
+                }
                 "mint" => {
                     let r1 = check!(
                         single_reg(args, immediate, whole_op_span),
@@ -1334,6 +1393,7 @@ impl fmt::Display for Op {
                 CB(a) => format!("cb {}", a),
                 LDC(a, b, c) => format!("ldc {} {} {}", a, b, c),
                 LOG(a, b, c, d) => format!("log {} {} {} {}", a, b, c, d),
+                LOGD(a, b, c, d) => format!("logd {} {} {} {}", a, b, c, d),
                 MINT(a) => format!("mint {}", a),
                 RVRT(a) => format!("rvrt {}", a),
                 SLDC(a, b, c) => format!("sldc {} {} {}", a, b, c),
@@ -1361,6 +1421,9 @@ impl fmt::Display for Op {
                 Comment => "".into(),
                 Jump(label) => format!("jump {}", label),
                 JumpIfNotEq(reg0, reg1, label) => format!("jnei {} {} {}", reg0, reg1, label),
+                Call(label, ..) => format!("call {}", label),
+                SpillAreaStart(reg) => format!("spill area start {}", reg),
+                SpillAreaEnd => "spill area end".into(),
                 OrganizationalOp::DataSectionOffsetPlaceholder => {
                     "data section offset placeholder".into()
                 }
@@ -1392,6 +1455,16 @@ pub(crate) enum OrganizationalOp {
     Jump(Label),
     // Jumps to a label
     JumpIfNotEq(VirtualRegister, VirtualRegister, Label),
+    // Jumps to the label of a function which will eventually jump back to the following op.  The
+    // registers are the arguments passed to the function and the register it returns a value in.
+    Call(Label, Vec<VirtualRegister>, VirtualRegister),
+    // Marks the start of the body of a called function, which spills registers to its own area
+    // of the stack.  The register is a scratch register for reserving the area.  Replaced by the
+    // register allocator.
+    SpillAreaStart(VirtualRegister),
+    // Marks the end of the body of a called function, where the caller's spill area is restored
+    // and the function's own area is freed.  Replaced by the register allocator.
+    SpillAreaEnd,
     // placeholder for the DataSection offset
     DataSectionOffsetPlaceholder,
 }
@@ -1406,6 +1479,9 @@ impl fmt::Display for OrganizationalOp {
                 Jump(lab) => format!("ji  {}", lab),
                 Comment => "".into(),
                 JumpIfNotEq(r1, r2, lab) => format!("jnei {} {} {}", r1, r2, lab),
+                Call(lab, ..) => format!("call {}", lab),
+                SpillAreaStart(reg) => format!("spill area start {}", reg),
+                SpillAreaEnd => "spill area end".into(),
                 DataSectionOffsetPlaceholder =>
                     "DATA SECTION OFFSET[0..32]\nDATA SECTION OFFSET[32..64]".into(),
             }
@@ -1417,10 +1493,66 @@ impl OrganizationalOp {
     pub(crate) fn registers(&self) -> HashSet<&VirtualRegister> {
         use OrganizationalOp::*;
         (match self {
-            Label(_) | Comment | Jump(_) | DataSectionOffsetPlaceholder => vec![],
+            Label(_) | Comment | Jump(_) | SpillAreaEnd | DataSectionOffsetPlaceholder => {
+                vec![]
+            }
             JumpIfNotEq(r1, r2, _) => vec![r1, r2],
+            Call(_, args, ret) => args.iter().chain(std::iter::once(ret)).collect(),
+            SpillAreaStart(reg) => vec![reg],
         })
         .into_iter()
         .collect()
     }
+
+    pub(crate) fn use_registers(&self) -> HashSet<&VirtualRegister> {
+        use OrganizationalOp::*;
+        (match self {
+            Label(_)
+            | Comment
+            | Jump(_)
+            | SpillAreaStart(_)
+            | SpillAreaEnd
+            | DataSectionOffsetPlaceholder => vec![],
+            JumpIfNotEq(r1, r2, _) => vec![r1, r2],
+            Call(_, args, _) => args.iter().collect(),
+        })
+        .into_iter()
+        .collect()
+    }
+
+    pub(crate) fn def_registers(&self) -> HashSet<&VirtualRegister> {
+        use OrganizationalOp::*;
+        (match self {
+            Label(_)
+            | Comment
+            | Jump(_)
+            | JumpIfNotEq(..)
+            | SpillAreaEnd
+            | DataSectionOffsetPlaceholder => vec![],
+            Call(_, _, ret) => vec![ret],
+            SpillAreaStart(reg) => vec![reg],
+        })
+        .into_iter()
+        .collect()
+    }
+
+    pub(crate) fn update_register(
+        &self,
+        reg_to_reg_map: &HashMap<&VirtualRegister, &VirtualRegister>,
+    ) -> Self {
+        let update_reg = |reg: &VirtualRegister| (*reg_to_reg_map.get(reg).unwrap_or(&reg)).clone();
+        use OrganizationalOp::*;
+        match self {
+            JumpIfNotEq(r1, r2, label) => {
+                JumpIfNotEq(update_reg(r1), update_reg(r2), label.clone())
+            }
+            Call(label, args, ret) => Call(
+                label.clone(),
+                args.iter().map(update_reg).collect(),
+                update_reg(ret),
+            ),
+            SpillAreaStart(reg) => SpillAreaStart(update_reg(reg)),
+            _otherwise => self.clone(),
+        }
+    }
 }
@@ -8,9 +8,8 @@ use super::{
     allocated_ops::{AllocatedOpcode, AllocatedRegister},
     virtual_immediate::*,
     virtual_register::*,
-    DataId, RealizedOp,
+    DataId,
 };
-use crate::asm_generation::RegisterPool;
 
 use std::collections::{HashMap, HashSet};
 
@@ -108,6 +107,12 @@ pub(crate) enum VirtualOp {
         VirtualRegister,
         VirtualRegister,
     ),
+    LOGD(
+        VirtualRegister,
+        VirtualRegister,
+        VirtualRegister,
+        VirtualRegister,
+    ),
     MINT(VirtualRegister),
     RVRT(VirtualRegister),
     SLDC(VirtualRegister, VirtualRegister, VirtualRegister),
@@ -197,6 +202,7 @@ impl VirtualOp {
             CB(r1) => vec![r1],
             LDC(r1, r2, r3) => vec![r1, r2, r3],
             LOG(r1, r2, r3, r4) => vec![r1, r2, r3, r4],
+            LOGD(r1, r2, r3, r4) => vec![r1, r2, r3, r4],
             MINT(r1) => vec![r1],
             RVRT(r1) => vec![r1],
             SLDC(r1, r2, r3) => vec![r1, r2, r3],
@@ -223,336 +229,780 @@ impl VirtualOp {
         .collect()
     }
 
-    pub(crate) fn allocate_registers(
-        &self,
-        pool: &mut RegisterPool,
-        op_register_mapping: &[(RealizedOp, HashSet<VirtualRegister>)],
-        ix: usize,
-    ) -> AllocatedOpcode {
-        let virtual_registers = self.registers();
-        let register_allocation_result = virtual_registers
-            .clone()
-            .into_iter()
-            .map(|x| match x {
-                VirtualRegister::Constant(c) => (x, Some(AllocatedRegister::Constant(c.clone()))),
-                VirtualRegister::Virtual(_) => {
-                    (x, pool.get_register(x, &op_register_mapping[ix..]))
-                }
-            })
-            .map(|(x, register_opt)| register_opt.map(|register| (x, register)))
-            .collect::<Option<Vec<_>>>();
+    /// Returns the registers which are read by this op.
+    pub(crate) fn use_registers(&self) -> HashSet<&VirtualRegister> {
+        use VirtualOp::*;
+        (match self {
+            ADD(_r1, r2, r3) => vec![r2, r3],
+            ADDI(_r1, r2, _i) => vec![r2],
+            AND(_r1, r2, r3) => vec![r2, r3],
+            ANDI(_r1, r2, _i) => vec![r2],
+            DIV(_r1, r2, r3) => vec![r2, r3],
+            DIVI(_r1, r2, _i) => vec![r2],
+            EQ(_r1, r2, r3) => vec![r2, r3],
+            EXP(_r1, r2, r3) => vec![r2, r3],
+            EXPI(_r1, r2, _i) => vec![r2],
+            GT(_r1, r2, r3) => vec![r2, r3],
+            LT(_r1, r2, r3) => vec![r2, r3],
+            MLOG(_r1, r2, r3) => vec![r2, r3],
+            MROO(_r1, r2, r3) => vec![r2, r3],
+            MOD(_r1, r2, r3) => vec![r2, r3],
+            MODI(_r1, r2, _i) => vec![r2],
+            MOVE(_r1, r2) => vec![r2],
+            MUL(_r1, r2, r3) => vec![r2, r3],
+            MULI(_r1, r2, _i) => vec![r2],
+            NOT(_r1, r2) => vec![r2],
+            OR(_r1, r2, r3) => vec![r2, r3],
+            ORI(_r1, r2, _i) => vec![r2],
+            SLL(_r1, r2, r3) => vec![r2, r3],
+            SLLI(_r1, r2, _i) => vec![r2],
+            SRL(_r1, r2, r3) => vec![r2, r3],
+            SRLI(_r1, r2, _i) => vec![r2],
+            SUB(_r1, r2, r3) => vec![r2, r3],
+            SUBI(_r1, r2, _i) => vec![r2],
+            XOR(_r1, r2, r3) => vec![r2, r3],
+            XORI(_r1, r2, _i) => vec![r2],
+            CIMV(_r1, r2, r3) => vec![r2, r3],
+            CTMV(_r1, r2) => vec![r2],
+            JI(_im) => vec![],
+            JNEI(r1, r2, _i) => vec![r1, r2],
+            RET(r1) => vec![r1],
+            RETD(r1, r2) => vec![r1, r2],
+            CFEI(_imm) => vec![],
+            CFSI(_imm) => vec![],
+            LB(_r1, r2, _i) => vec![r2],
+            LWDataId(_r1, _i) => vec![],
+            LW(_r1, r2, _i) => vec![r2],
+            ALOC(r1) => vec![r1],
+            MCL(r1, r2) => vec![r1, r2],
+            MCLI(r1, _imm) => vec![r1],
+            MCP(r1, r2, r3) => vec![r1, r2, r3],
+            MEQ(_r1, r2, r3, r4) => vec![r2, r3, r4],
+            MCPI(r1, r2, _imm) => vec![r1, r2],
+            SB(r1, r2, _i) => vec![r1, r2],
+            SW(r1, r2, _i) => vec![r1, r2],
+            BAL(_r1, r2, r3) => vec![r2, r3],
+            BHSH(r1, r2) => vec![r1, r2],
+            BHEI(_r1) => vec![],
+            BURN(r1) => vec![r1],
+            CALL(r1, r2, r3, r4) => vec![r1, r2, r3, r4],
+            CCP(r1, r2, r3, r4) => vec![r1, r2, r3, r4],
+            CROO(r1, r2) => vec![r1, r2],
+            CSIZ(_r1, r2) => vec![r2],
+            CB(r1) => vec![r1],
+            LDC(r1, r2, r3) => vec![r1, r2, r3],
+            LOG(r1, r2, r3, r4) => vec![r1, r2, r3, r4],
+            LOGD(r1, r2, r3, r4) => vec![r1, r2, r3, r4],
+            MINT(r1) => vec![r1],
+            RVRT(r1) => vec![r1],
+            SLDC(r1, r2, r3) => vec![r1, r2, r3],
+            SRW(_r1, r2) => vec![r2],
+            SRWQ(r1, r2) => vec![r1, r2],
+            SWW(r1, r2) => vec![r1, r2],
+            SWWQ(r1, r2) => vec![r1, r2],
+            TR(r1, r2, r3) => vec![r1, r2, r3],
+            TRO(r1, r2, r3, r4) => vec![r1, r2, r3, r4],
+            ECR(r1, r2, r3) => vec![r1, r2, r3],
+            K256(r1, r2, r3) => vec![r1, r2, r3],
+            S256(r1, r2, r3) => vec![r1, r2, r3],
+            XOS(_r1, r2) => vec![r2],
+            NOOP => vec![],
+            FLAG(r1) => vec![r1],
+            GM(_r1, _imm) => vec![],
+            Undefined | DataSectionOffsetPlaceholder => vec![],
+            DataSectionRegisterLoadPlaceholder => vec![&VirtualRegister::Constant(
+                ConstantRegister::InstructionStart,
+            )],
+        })
+        .into_iter()
+        .collect()
+    }
 
-        // Maps virtual registers to their allocated equivalent
-        let mut mapping = HashMap::default();
-        match register_allocation_result {
-            Some(o) => {
-                for (key, val) in o {
-                    mapping.insert(key, val);
-                }
-            }
-            None => {
-                unimplemented!("The allocator cannot resolve a register mapping for this program. This is a temporary artifact of the extremely early stage version of this language. Try to lower the number of variables you use.")
-            }
-        };
+    /// Returns the registers which are written to by this op.  Ops which only write to memory
+    /// via a register, such as `SW` or `MCP`, do not define that register.
+    pub(crate) fn def_registers(&self) -> HashSet<&VirtualRegister> {
+        use VirtualOp::*;
+        (match self {
+            ADD(r1, _r2, _r3) => vec![r1],
+            ADDI(r1, _r2, _i) => vec![r1],
+            AND(r1, _r2, _r3) => vec![r1],
+            ANDI(r1, _r2, _i) => vec![r1],
+            DIV(r1, _r2, _r3) => vec![r1],
+            DIVI(r1, _r2, _i) => vec![r1],
+            EQ(r1, _r2, _r3) => vec![r1],
+            EXP(r1, _r2, _r3) => vec![r1],
+            EXPI(r1, _r2, _i) => vec![r1],
+            GT(r1, _r2, _r3) => vec![r1],
+            LT(r1, _r2, _r3) => vec![r1],
+            MLOG(r1, _r2, _r3) => vec![r1],
+            MROO(r1, _r2, _r3) => vec![r1],
+            MOD(r1, _r2, _r3) => vec![r1],
+            MODI(r1, _r2, _i) => vec![r1],
+            MOVE(r1, _r2) => vec![r1],
+            MUL(r1, _r2, _r3) => vec![r1],
+            MULI(r1, _r2, _i) => vec![r1],
+            NOT(r1, _r2) => vec![r1],
+            OR(r1, _r2, _r3) => vec![r1],
+            ORI(r1, _r2, _i) => vec![r1],
+            SLL(r1, _r2, _r3) => vec![r1],
+            SLLI(r1, _r2, _i) => vec![r1],
+            SRL(r1, _r2, _r3) => vec![r1],
+            SRLI(r1, _r2, _i) => vec![r1],
+            SUB(r1, _r2, _r3) => vec![r1],
+            SUBI(r1, _r2, _i) => vec![r1],
+            XOR(r1, _r2, _r3) => vec![r1],
+            XORI(r1, _r2, _i) => vec![r1],
+            CIMV(r1, _r2, _r3) => vec![r1],
+            CTMV(r1, _r2) => vec![r1],
+            LB(r1, _r2, _i) => vec![r1],
+            LWDataId(r1, _i) => vec![r1],
+            LW(r1, _r2, _i) => vec![r1],
+            MEQ(r1, _r2, _r3, _r4) => vec![r1],
+            BAL(r1, _r2, _r3) => vec![r1],
+            BHEI(r1) => vec![r1],
+            CSIZ(r1, _r2) => vec![r1],
+            SRW(r1, _r2) => vec![r1],
+            XOS(r1, _r2) => vec![r1],
+            GM(r1, _imm) => vec![r1],
+            DataSectionRegisterLoadPlaceholder => vec![&VirtualRegister::Constant(
+                ConstantRegister::DataSectionStart,
+            )],
+            JI(_)
+            | JNEI(..)
+            | RET(_)
+            | RETD(..)
+            | CFEI(_)
+            | CFSI(_)
+            | ALOC(_)
+            | MCL(..)
+            | MCLI(..)
+            | MCP(..)
+            | MCPI(..)
+            | SB(..)
+            | SW(..)
+            | BHSH(..)
+            | BURN(_)
+            | CALL(..)
+            | CCP(..)
+            | CROO(..)
+            | CB(_)
+            | LDC(..)
+            | LOG(..)
+            | LOGD(..)
+            | MINT(_)
+            | RVRT(_)
+            | SLDC(..)
+            | SRWQ(..)
+            | SWW(..)
+            | SWWQ(..)
+            | TR(..)
+            | TRO(..)
+            | ECR(..)
+            | K256(..)
+            | S256(..)
+            | NOOP
+            | FLAG(_)
+            | Undefined
+            | DataSectionOffsetPlaceholder => vec![],
+        })
+        .into_iter()
+        .collect()
+    }
 
+    /// Returns a copy of this op with each register found in `reg_to_reg_map` replaced by the
+    /// register it maps to.
+    pub(crate) fn update_register(
+        &self,
+        reg_to_reg_map: &HashMap<&VirtualRegister, &VirtualRegister>,
+    ) -> Self {
         use VirtualOp::*;
         match self {
-            ADD(reg1, reg2, reg3) => AllocatedOpcode::ADD(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+            ADD(reg1, reg2, reg3) => Self::ADD(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
             ),
-            ADDI(reg1, reg2, imm) => AllocatedOpcode::ADDI(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
+            ADDI(reg1, reg2, imm) => Self::ADDI(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
                 imm.clone(),
             ),
-            AND(reg1, reg2, reg3) => AllocatedOpcode::AND(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+            AND(reg1, reg2, reg3) => Self::AND(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
             ),
-            ANDI(reg1, reg2, imm) => AllocatedOpcode::ANDI(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
+            ANDI(reg1, reg2, imm) => Self::ANDI(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
                 imm.clone(),
             ),
-            DIV(reg1, reg2, reg3) => AllocatedOpcode::DIV(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+            DIV(reg1, reg2, reg3) => Self::DIV(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
             ),
-            DIVI(reg1, reg2, imm) => AllocatedOpcode::DIVI(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
+            DIVI(reg1, reg2, imm) => Self::DIVI(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
                 imm.clone(),
             ),
-            EQ(reg1, reg2, reg3) => AllocatedOpcode::EQ(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+            EQ(reg1, reg2, reg3) => Self::EQ(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
             ),
-            EXP(reg1, reg2, reg3) => AllocatedOpcode::EXP(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+            EXP(reg1, reg2, reg3) => Self::EXP(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
             ),
-            EXPI(reg1, reg2, imm) => AllocatedOpcode::EXPI(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
+            EXPI(reg1, reg2, imm) => Self::EXPI(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
                 imm.clone(),
             ),
-            GT(reg1, reg2, reg3) => AllocatedOpcode::GT(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+            GT(reg1, reg2, reg3) => Self::GT(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
             ),
-            LT(reg1, reg2, reg3) => AllocatedOpcode::LT(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+            LT(reg1, reg2, reg3) => Self::LT(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
             ),
-            MLOG(reg1, reg2, reg3) => AllocatedOpcode::MLOG(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+            MLOG(reg1, reg2, reg3) => Self::MLOG(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
             ),
-            MROO(reg1, reg2, reg3) => AllocatedOpcode::MROO(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+            MROO(reg1, reg2, reg3) => Self::MROO(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
             ),
-            MOD(reg1, reg2, reg3) => AllocatedOpcode::MOD(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+            MOD(reg1, reg2, reg3) => Self::MOD(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
             ),
-            MODI(reg1, reg2, imm) => AllocatedOpcode::MODI(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
+            MODI(reg1, reg2, imm) => Self::MODI(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
                 imm.clone(),
             ),
-            MOVE(reg1, reg2) => {
-                AllocatedOpcode::MOVE(map_reg(&mapping, reg1), map_reg(&mapping, reg2))
-            }
-            MUL(reg1, reg2, reg3) => AllocatedOpcode::MUL(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+            MOVE(reg1, reg2) => Self::MOVE(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+            ),
+            MUL(reg1, reg2, reg3) => Self::MUL(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
             ),
-            MULI(reg1, reg2, imm) => AllocatedOpcode::MULI(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
+            MULI(reg1, reg2, imm) => Self::MULI(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
                 imm.clone(),
             ),
-            NOT(reg1, reg2) => {
-                AllocatedOpcode::NOT(map_reg(&mapping, reg1), map_reg(&mapping, reg2))
-            }
-            OR(reg1, reg2, reg3) => AllocatedOpcode::OR(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+            NOT(reg1, reg2) => Self::NOT(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
             ),
-            ORI(reg1, reg2, imm) => AllocatedOpcode::ORI(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
+            OR(reg1, reg2, reg3) => Self::OR(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
+            ),
+            ORI(reg1, reg2, imm) => Self::ORI(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
                 imm.clone(),
             ),
-            SLL(reg1, reg2, reg3) => AllocatedOpcode::SLL(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+            SLL(reg1, reg2, reg3) => Self::SLL(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
             ),
-            SLLI(reg1, reg2, imm) => AllocatedOpcode::SLLI(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
+            SLLI(reg1, reg2, imm) => Self::SLLI(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
                 imm.clone(),
             ),
-            SRL(reg1, reg2, reg3) => AllocatedOpcode::SRL(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+            SRL(reg1, reg2, reg3) => Self::SRL(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
             ),
-            SRLI(reg1, reg2, imm) => AllocatedOpcode::SRLI(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
+            SRLI(reg1, reg2, imm) => Self::SRLI(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
                 imm.clone(),
             ),
-            SUB(reg1, reg2, reg3) => AllocatedOpcode::SUB(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+            SUB(reg1, reg2, reg3) => Self::SUB(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
             ),
-            SUBI(reg1, reg2, imm) => AllocatedOpcode::SUBI(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
+            SUBI(reg1, reg2, imm) => Self::SUBI(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
                 imm.clone(),
             ),
-            XOR(reg1, reg2, reg3) => AllocatedOpcode::XOR(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+            XOR(reg1, reg2, reg3) => Self::XOR(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
             ),
-            XORI(reg1, reg2, imm) => AllocatedOpcode::XORI(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
+            XORI(reg1, reg2, imm) => Self::XORI(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
                 imm.clone(),
             ),
-            CIMV(reg1, reg2, reg3) => AllocatedOpcode::CIMV(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+            CIMV(reg1, reg2, reg3) => Self::CIMV(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
             ),
-            CTMV(reg1, reg2) => {
-                AllocatedOpcode::CTMV(map_reg(&mapping, reg1), map_reg(&mapping, reg2))
-            }
-            JI(imm) => AllocatedOpcode::JI(imm.clone()),
-            JNEI(reg1, reg2, imm) => AllocatedOpcode::JNEI(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
+            CTMV(reg1, reg2) => Self::CTMV(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+            ),
+            JI(imm) => Self::JI(imm.clone()),
+            JNEI(reg1, reg2, imm) => Self::JNEI(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
                 imm.clone(),
             ),
-            RET(reg) => AllocatedOpcode::RET(map_reg(&mapping, reg)),
-            RETD(reg1, reg2) => {
-                AllocatedOpcode::RETD(map_reg(&mapping, reg1), map_reg(&mapping, reg2))
-            }
-            CFEI(imm) => AllocatedOpcode::CFEI(imm.clone()),
-            CFSI(imm) => AllocatedOpcode::CFSI(imm.clone()),
-            LB(reg1, reg2, imm) => AllocatedOpcode::LB(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
+            RET(reg) => Self::RET(update_reg(reg_to_reg_map, reg)),
+            RETD(reg1, reg2) => Self::RETD(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+            ),
+            CFEI(imm) => Self::CFEI(imm.clone()),
+            CFSI(imm) => Self::CFSI(imm.clone()),
+            LB(reg1, reg2, imm) => Self::LB(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
                 imm.clone(),
             ),
             LWDataId(reg1, label) => {
-                AllocatedOpcode::LWDataId(map_reg(&mapping, reg1), label.clone())
+                Self::LWDataId(update_reg(reg_to_reg_map, reg1), label.clone())
             }
-            LW(reg1, reg2, imm) => AllocatedOpcode::LW(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
+            LW(reg1, reg2, imm) => Self::LW(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
                 imm.clone(),
             ),
-            ALOC(reg) => AllocatedOpcode::ALOC(map_reg(&mapping, reg)),
-            MCL(reg1, reg2) => {
-                AllocatedOpcode::MCL(map_reg(&mapping, reg1), map_reg(&mapping, reg2))
-            }
-            MCLI(reg1, imm) => AllocatedOpcode::MCLI(map_reg(&mapping, reg1), imm.clone()),
-            MCP(reg1, reg2, reg3) => AllocatedOpcode::MCP(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+            ALOC(reg) => Self::ALOC(update_reg(reg_to_reg_map, reg)),
+            MCL(reg1, reg2) => Self::MCL(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
             ),
-            MEQ(reg1, reg2, reg3, reg4) => AllocatedOpcode::MEQ(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
-                map_reg(&mapping, reg4),
-            ),
-            MCPI(reg1, reg2, imm) => AllocatedOpcode::MCPI(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
+            MCLI(reg1, imm) => Self::MCLI(update_reg(reg_to_reg_map, reg1), imm.clone()),
+            MCP(reg1, reg2, reg3) => Self::MCP(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
+            ),
+            MEQ(reg1, reg2, reg3, reg4) => Self::MEQ(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
+                update_reg(reg_to_reg_map, reg4),
+            ),
+            MCPI(reg1, reg2, imm) => Self::MCPI(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
                 imm.clone(),
             ),
-            SB(reg1, reg2, imm) => AllocatedOpcode::SB(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
+            SB(reg1, reg2, imm) => Self::SB(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
                 imm.clone(),
             ),
-            SW(reg1, reg2, imm) => AllocatedOpcode::SW(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
+            SW(reg1, reg2, imm) => Self::SW(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
                 imm.clone(),
             ),
+            BAL(reg1, reg2, reg3) => Self::BAL(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
+            ),
+            BHSH(reg1, reg2) => Self::BHSH(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+            ),
+            BHEI(reg1) => Self::BHEI(update_reg(reg_to_reg_map, reg1)),
+            BURN(reg1) => Self::BURN(update_reg(reg_to_reg_map, reg1)),
+            CALL(reg1, reg2, reg3, reg4) => Self::CALL(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
+                update_reg(reg_to_reg_map, reg4),
+            ),
+            CCP(reg1, reg2, reg3, reg4) => Self::CCP(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
+                update_reg(reg_to_reg_map, reg4),
+            ),
+            CROO(reg1, reg2) => Self::CROO(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+            ),
+            CSIZ(reg1, reg2) => Self::CSIZ(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+            ),
+            CB(reg1) => Self::CB(update_reg(reg_to_reg_map, reg1)),
+            LDC(reg1, reg2, reg3) => Self::LDC(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
+            ),
+            LOG(reg1, reg2, reg3, reg4) => Self::LOG(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
+                update_reg(reg_to_reg_map, reg4),
+            ),
+            LOGD(reg1, reg2, reg3, reg4) => Self::LOGD(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
+                update_reg(reg_to_reg_map, reg4),
+            ),
+            MINT(reg1) => Self::MINT(update_reg(reg_to_reg_map, reg1)),
+            RVRT(reg1) => Self::RVRT(update_reg(reg_to_reg_map, reg1)),
+            SLDC(reg1, reg2, reg3) => Self::SLDC(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
+            ),
+            SRW(reg1, reg2) => Self::SRW(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+            ),
+            SRWQ(reg1, reg2) => Self::SRWQ(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+            ),
+            SWW(reg1, reg2) => Self::SWW(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+            ),
+            SWWQ(reg1, reg2) => Self::SWWQ(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+            ),
+            TR(reg1, reg2, reg3) => Self::TR(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
+            ),
+            TRO(reg1, reg2, reg3, reg4) => Self::TRO(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
+                update_reg(reg_to_reg_map, reg4),
+            ),
+            ECR(reg1, reg2, reg3) => Self::ECR(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
+            ),
+            K256(reg1, reg2, reg3) => Self::K256(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
+            ),
+            S256(reg1, reg2, reg3) => Self::S256(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+                update_reg(reg_to_reg_map, reg3),
+            ),
+            XOS(reg1, reg2) => Self::XOS(
+                update_reg(reg_to_reg_map, reg1),
+                update_reg(reg_to_reg_map, reg2),
+            ),
+            NOOP => Self::NOOP,
+            FLAG(reg) => Self::FLAG(update_reg(reg_to_reg_map, reg)),
+            GM(reg, imm) => Self::GM(update_reg(reg_to_reg_map, reg), imm.clone()),
+            Undefined => Self::Undefined,
+            DataSectionOffsetPlaceholder => Self::DataSectionOffsetPlaceholder,
+            DataSectionRegisterLoadPlaceholder => Self::DataSectionRegisterLoadPlaceholder,
+        }
+    }
+
+    /// Replaces each virtual register in this op with the register it was allocated in `mapping`.
+    pub(crate) fn allocate_registers(
+        &self,
+        mapping: &HashMap<VirtualRegister, AllocatedRegister>,
+    ) -> AllocatedOpcode {
+        use VirtualOp::*;
+        match self {
+            ADD(reg1, reg2, reg3) => AllocatedOpcode::ADD(
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+            ),
+            ADDI(reg1, reg2, imm) => {
+                AllocatedOpcode::ADDI(map_reg(mapping, reg1), map_reg(mapping, reg2), imm.clone())
+            }
+            AND(reg1, reg2, reg3) => AllocatedOpcode::AND(
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+            ),
+            ANDI(reg1, reg2, imm) => {
+                AllocatedOpcode::ANDI(map_reg(mapping, reg1), map_reg(mapping, reg2), imm.clone())
+            }
+            DIV(reg1, reg2, reg3) => AllocatedOpcode::DIV(
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+            ),
+            DIVI(reg1, reg2, imm) => {
+                AllocatedOpcode::DIVI(map_reg(mapping, reg1), map_reg(mapping, reg2), imm.clone())
+            }
+            EQ(reg1, reg2, reg3) => AllocatedOpcode::EQ(
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+            ),
+            EXP(reg1, reg2, reg3) => AllocatedOpcode::EXP(
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+            ),
+            EXPI(reg1, reg2, imm) => {
+                AllocatedOpcode::EXPI(map_reg(mapping, reg1), map_reg(mapping, reg2), imm.clone())
+            }
+            GT(reg1, reg2, reg3) => AllocatedOpcode::GT(
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+            ),
+            LT(reg1, reg2, reg3) => AllocatedOpcode::LT(
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+            ),
+            MLOG(reg1, reg2, reg3) => AllocatedOpcode::MLOG(
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+            ),
+            MROO(reg1, reg2, reg3) => AllocatedOpcode::MROO(
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+            ),
+            MOD(reg1, reg2, reg3) => AllocatedOpcode::MOD(
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+            ),
+            MODI(reg1, reg2, imm) => {
+                AllocatedOpcode::MODI(map_reg(mapping, reg1), map_reg(mapping, reg2), imm.clone())
+            }
+            MOVE(reg1, reg2) => {
+                AllocatedOpcode::MOVE(map_reg(mapping, reg1), map_reg(mapping, reg2))
+            }
+            MUL(reg1, reg2, reg3) => AllocatedOpcode::MUL(
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+            ),
+            MULI(reg1, reg2, imm) => {
+                AllocatedOpcode::MULI(map_reg(mapping, reg1), map_reg(mapping, reg2), imm.clone())
+            }
+            NOT(reg1, reg2) => AllocatedOpcode::NOT(map_reg(mapping, reg1), map_reg(mapping, reg2)),
+            OR(reg1, reg2, reg3) => AllocatedOpcode::OR(
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+            ),
+            ORI(reg1, reg2, imm) => {
+                AllocatedOpcode::ORI(map_reg(mapping, reg1), map_reg(mapping, reg2), imm.clone())
+            }
+            SLL(reg1, reg2, reg3) => AllocatedOpcode::SLL(
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+            ),
+            SLLI(reg1, reg2, imm) => {
+                AllocatedOpcode::SLLI(map_reg(mapping, reg1), map_reg(mapping, reg2), imm.clone())
+            }
+            SRL(reg1, reg2, reg3) => AllocatedOpcode::SRL(
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+            ),
+            SRLI(reg1, reg2, imm) => {
+                AllocatedOpcode::SRLI(map_reg(mapping, reg1), map_reg(mapping, reg2), imm.clone())
+            }
+            SUB(reg1, reg2, reg3) => AllocatedOpcode::SUB(
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+            ),
+            SUBI(reg1, reg2, imm) => {
+                AllocatedOpcode::SUBI(map_reg(mapping, reg1), map_reg(mapping, reg2), imm.clone())
+            }
+            XOR(reg1, reg2, reg3) => AllocatedOpcode::XOR(
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+            ),
+            XORI(reg1, reg2, imm) => {
+                AllocatedOpcode::XORI(map_reg(mapping, reg1), map_reg(mapping, reg2), imm.clone())
+            }
+            CIMV(reg1, reg2, reg3) => AllocatedOpcode::CIMV(
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+            ),
+            CTMV(reg1, reg2) => {
+                AllocatedOpcode::CTMV(map_reg(mapping, reg1), map_reg(mapping, reg2))
+            }
+            JI(imm) => AllocatedOpcode::JI(imm.clone()),
+            JNEI(reg1, reg2, imm) => {
+                AllocatedOpcode::JNEI(map_reg(mapping, reg1), map_reg(mapping, reg2), imm.clone())
+            }
+            RET(reg) => AllocatedOpcode::RET(map_reg(mapping, reg)),
+            RETD(reg1, reg2) => {
+                AllocatedOpcode::RETD(map_reg(mapping, reg1), map_reg(mapping, reg2))
+            }
+            CFEI(imm) => AllocatedOpcode::CFEI(imm.clone()),
+            CFSI(imm) => AllocatedOpcode::CFSI(imm.clone()),
+            LB(reg1, reg2, imm) => {
+                AllocatedOpcode::LB(map_reg(mapping, reg1), map_reg(mapping, reg2), imm.clone())
+            }
+            LWDataId(reg1, label) => {
+                AllocatedOpcode::LWDataId(map_reg(mapping, reg1), label.clone())
+            }
+            LW(reg1, reg2, imm) => {
+                AllocatedOpcode::LW(map_reg(mapping, reg1), map_reg(mapping, reg2), imm.clone())
+            }
+            ALOC(reg) => AllocatedOpcode::ALOC(map_reg(mapping, reg)),
+            MCL(reg1, reg2) => AllocatedOpcode::MCL(map_reg(mapping, reg1), map_reg(mapping, reg2)),
+            MCLI(reg1, imm) => AllocatedOpcode::MCLI(map_reg(mapping, reg1), imm.clone()),
+            MCP(reg1, reg2, reg3) => AllocatedOpcode::MCP(
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+            ),
+            MEQ(reg1, reg2, reg3, reg4) => AllocatedOpcode::MEQ(
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+                map_reg(mapping, reg4),
+            ),
+            MCPI(reg1, reg2, imm) => {
+                AllocatedOpcode::MCPI(map_reg(mapping, reg1), map_reg(mapping, reg2), imm.clone())
+            }
+            SB(reg1, reg2, imm) => {
+                AllocatedOpcode::SB(map_reg(mapping, reg1), map_reg(mapping, reg2), imm.clone())
+            }
+            SW(reg1, reg2, imm) => {
+                AllocatedOpcode::SW(map_reg(mapping, reg1), map_reg(mapping, reg2), imm.clone())
+            }
             BAL(reg1, reg2, reg3) => AllocatedOpcode::BAL(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
             ),
             BHSH(reg1, reg2) => {
-                AllocatedOpcode::BHSH(map_reg(&mapping, reg1), map_reg(&mapping, reg2))
+                AllocatedOpcode::BHSH(map_reg(mapping, reg1), map_reg(mapping, reg2))
             }
-            BHEI(reg1) => AllocatedOpcode::BHEI(map_reg(&mapping, reg1)),
-            BURN(reg1) => AllocatedOpcode::BURN(map_reg(&mapping, reg1)),
+            BHEI(reg1) => AllocatedOpcode::BHEI(map_reg(mapping, reg1)),
+            BURN(reg1) => AllocatedOpcode::BURN(map_reg(mapping, reg1)),
             CALL(reg1, reg2, reg3, reg4) => AllocatedOpcode::CALL(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
-                map_reg(&mapping, reg4),
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+                map_reg(mapping, reg4),
             ),
             CCP(reg1, reg2, reg3, reg4) => AllocatedOpcode::CCP(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
-                map_reg(&mapping, reg4),
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+                map_reg(mapping, reg4),
             ),
             CROO(reg1, reg2) => {
-                AllocatedOpcode::CROO(map_reg(&mapping, reg1), map_reg(&mapping, reg2))
+                AllocatedOpcode::CROO(map_reg(mapping, reg1), map_reg(mapping, reg2))
             }
             CSIZ(reg1, reg2) => {
-                AllocatedOpcode::CSIZ(map_reg(&mapping, reg1), map_reg(&mapping, reg2))
+                AllocatedOpcode::CSIZ(map_reg(mapping, reg1), map_reg(mapping, reg2))
             }
-            CB(reg1) => AllocatedOpcode::CB(map_reg(&mapping, reg1)),
+            CB(reg1) => AllocatedOpcode::CB(map_reg(mapping, reg1)),
             LDC(reg1, reg2, reg3) => AllocatedOpcode::LDC(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
             ),
             LOG(reg1, reg2, reg3, reg4) => AllocatedOpcode::LOG(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
-                map_reg(&mapping, reg4),
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+                map_reg(mapping, reg4),
+            ),
+            LOGD(reg1, reg2, reg3, reg4) => AllocatedOpcode::LOGD(
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+                map_reg(mapping, reg4),
             ),
-            MINT(reg1) => AllocatedOpcode::MINT(map_reg(&mapping, reg1)),
-            RVRT(reg1) => AllocatedOpcode::RVRT(map_reg(&mapping, reg1)),
+            MINT(reg1) => AllocatedOpcode::MINT(map_reg(mapping, reg1)),
+            RVRT(reg1) => AllocatedOpcode::RVRT(map_reg(mapping, reg1)),
             SLDC(reg1, reg2, reg3) => AllocatedOpcode::SLDC(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
             ),
-            SRW(reg1, reg2) => {
-                AllocatedOpcode::SRW(map_reg(&mapping, reg1), map_reg(&mapping, reg2))
-            }
+            SRW(reg1, reg2) => AllocatedOpcode::SRW(map_reg(mapping, reg1), map_reg(mapping, reg2)),
             SRWQ(reg1, reg2) => {
-                AllocatedOpcode::SRWQ(map_reg(&mapping, reg1), map_reg(&mapping, reg2))
-            }
-            SWW(reg1, reg2) => {
-                AllocatedOpcode::SWW(map_reg(&mapping, reg1), map_reg(&mapping, reg2))
+                AllocatedOpcode::SRWQ(map_reg(mapping, reg1), map_reg(mapping, reg2))
             }
+            SWW(reg1, reg2) => AllocatedOpcode::SWW(map_reg(mapping, reg1), map_reg(mapping, reg2)),
             SWWQ(reg1, reg2) => {
-                AllocatedOpcode::SWWQ(map_reg(&mapping, reg1), map_reg(&mapping, reg2))
+                AllocatedOpcode::SWWQ(map_reg(mapping, reg1), map_reg(mapping, reg2))
             }
             TR(reg1, reg2, reg3) => AllocatedOpcode::TR(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
             ),
             TRO(reg1, reg2, reg3, reg4) => AllocatedOpcode::TRO(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
-                map_reg(&mapping, reg4),
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
+                map_reg(mapping, reg4),
             ),
             ECR(reg1, reg2, reg3) => AllocatedOpcode::ECR(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
             ),
             K256(reg1, reg2, reg3) => AllocatedOpcode::K256(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
             ),
             S256(reg1, reg2, reg3) => AllocatedOpcode::S256(
-                map_reg(&mapping, reg1),
-                map_reg(&mapping, reg2),
-                map_reg(&mapping, reg3),
+                map_reg(mapping, reg1),
+                map_reg(mapping, reg2),
+                map_reg(mapping, reg3),
             ),
-            XOS(reg1, reg2) => {
-                AllocatedOpcode::XOS(map_reg(&mapping, reg1), map_reg(&mapping, reg2))
-            }
+            XOS(reg1, reg2) => AllocatedOpcode::XOS(map_reg(mapping, reg1), map_reg(mapping, reg2)),
             NOOP => AllocatedOpcode::NOOP,
-            FLAG(reg) => AllocatedOpcode::FLAG(map_reg(&mapping, reg)),
-            GM(reg, imm) => AllocatedOpcode::GM(map_reg(&mapping, reg), imm.clone()),
+            FLAG(reg) => AllocatedOpcode::FLAG(map_reg(mapping, reg)),
+            GM(reg, imm) => AllocatedOpcode::GM(map_reg(mapping, reg), imm.clone()),
             Undefined => AllocatedOpcode::Undefined,
             DataSectionOffsetPlaceholder => AllocatedOpcode::DataSectionOffsetPlaceholder,
             DataSectionRegisterLoadPlaceholder => {
@@ -564,13 +1014,23 @@ impl VirtualOp {
 
 /// An unchecked function which serves as a convenience for looking up register mappings
 fn map_reg(
-    mapping: &HashMap<&VirtualRegister, AllocatedRegister>,
+    mapping: &HashMap<VirtualRegister, AllocatedRegister>,
     reg: &VirtualRegister,
 ) -> AllocatedRegister {
-    mapping.get(reg).unwrap().clone()
+    match reg {
+        VirtualRegister::Constant(c) => AllocatedRegister::Constant(c.clone()),
+        VirtualRegister::Virtual(_) => mapping.get(reg).unwrap().clone(),
+    }
+}
+
+fn update_reg(
+    reg_to_reg_map: &HashMap<&VirtualRegister, &VirtualRegister>,
+    reg: &VirtualRegister,
+) -> VirtualRegister {
+    (*reg_to_reg_map.get(reg).unwrap_or(&reg)).clone()
 }
 
-#[derive(Clone, Eq, PartialEq, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 /// A label for a spot in the bytecode, to be later compiled to an offset.
 pub(crate) struct Label(pub(crate) usize);
 impl fmt::Display for Label {
@@ -48,6 +48,8 @@ pub enum ConstantRegister {
     Flags,
     // Below are compiler-reserved registers
     DataSectionStart,
+    CallFramePointer,
+    SpillPointer,
 }
 
 impl ConstantRegister {
@@ -75,6 +77,13 @@ impl ConstantRegister {
                 (crate::asm_generation::compiler_constants::DATA_SECTION_REGISTER)
                     as fuel_asm::RegisterId
             }
+            CallFramePointer => {
+                (crate::asm_generation::compiler_constants::CALL_FRAME_REGISTER)
+                    as fuel_asm::RegisterId
+            }
+            SpillPointer => {
+                (crate::asm_generation::compiler_constants::SPILL_REGISTER) as fuel_asm::RegisterId
+            }
         }
     }
 }
@@ -102,6 +111,8 @@ impl fmt::Display for ConstantRegister {
             // two `$` signs denotes this is a compiler-reserved register and not a
             // VM-reserved register
             DataSectionStart => "$$ds",
+            CallFramePointer => "$$cfp",
+            SpillPointer => "$$spill",
         };
         write!(f, "{}", text)
     }
@@ -10,6 +10,9 @@ pub struct BuildConfig {
     pub(crate) print_intermediate_asm: bool,
     pub(crate) print_finalized_asm: bool,
     pub(crate) print_ir: bool,
+    pub(crate) print_peephole_savings: bool,
+    pub(crate) optimize_asm: bool,
+    pub(crate) ir_opt_level: u8,
 }
 
 impl BuildConfig {
@@ -29,6 +32,9 @@ impl BuildConfig {
             print_intermediate_asm: false,
             print_finalized_asm: false,
             print_ir: false,
+            print_peephole_savings: false,
+            optimize_asm: true,
+            ir_opt_level: 1,
         }
     }
 
@@ -57,6 +63,29 @@ impl BuildConfig {
         }
     }
 
+    pub fn print_peephole_savings(self, a: bool) -> Self {
+        Self {
+            print_peephole_savings: a,
+            ..self
+        }
+    }
+
+    pub fn optimize_asm(self, a: bool) -> Self {
+        Self {
+            optimize_asm: a,
+            ..self
+        }
+    }
+
+    /// Selects the IR optimization passes to run, from none at level 0 to all of them run to a
+    /// fixpoint at level 2.  See [sway_ir::PassPipeline::for_opt_level].
+    pub fn ir_opt_level(self, a: u8) -> Self {
+        Self {
+            ir_opt_level: a,
+            ..self
+        }
+    }
+
     pub fn path(&self) -> Arc<PathBuf> {
         self.file_name.clone()
     }
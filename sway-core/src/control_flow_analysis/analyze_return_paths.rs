@@ -7,7 +7,7 @@ use crate::{
     parse_tree::CallPath,
     semantic_analysis::{
         ast_node::{
-            TypedCodeBlock, TypedDeclaration, TypedExpression, TypedFunctionDeclaration,
+            TypedCodeBlock, TypedDeclaration, TypedForLoop, TypedFunctionDeclaration,
             TypedReassignment, TypedWhileLoop,
         },
         TypedAstNode, TypedAstNodeContent, TypedParseTree,
@@ -24,6 +24,7 @@ impl ControlFlowGraph {
             graph: Graph::new(),
             entry_points: vec![],
             namespace: Default::default(),
+            loops: vec![],
         };
         // do a depth first traversal and cover individual inner ast nodes
         let mut leaves = vec![];
@@ -141,24 +142,40 @@ fn connect_node(
             }
             NodeConnection::Return(this_index)
         }
-        TypedAstNodeContent::WhileLoop(TypedWhileLoop { .. }) => {
-            // An abridged version of the dead code analysis for a while loop
+        TypedAstNodeContent::WhileLoop(TypedWhileLoop { .. })
+        | TypedAstNodeContent::ForLoop(TypedForLoop { .. }) => {
+            // An abridged version of the dead code analysis for a loop
             // since we don't really care about what the loop body contains when detecting
             // divergent paths
             let node = graph.add_node(node.into());
             for leaf in leaves {
-                graph.add_edge(*leaf, node, "while loop entry".into());
+                graph.add_edge(*leaf, node, "loop entry".into());
             }
             NodeConnection::NextStep(vec![node])
         }
-        TypedAstNodeContent::Expression(TypedExpression { .. }) => {
+        TypedAstNodeContent::Break | TypedAstNodeContent::Continue => {
+            // These only appear within loop bodies, which aren't traversed here.  Control
+            // continues after the loop, which is the loop's next step, so nothing follows them.
+            let this_index = graph.add_node(node.into());
+            for leaf in leaves {
+                graph.add_edge(*leaf, this_index, "".into());
+            }
+            NodeConnection::NextStep(vec![])
+        }
+        TypedAstNodeContent::Expression(expr) => {
             let entry = graph.add_node(node.into());
             // insert organizational dominator node
             // connected to all current leaves
             for leaf in leaves {
                 graph.add_edge(*leaf, entry, "".into());
             }
-            NodeConnection::NextStep(vec![entry])
+            // An expression which always reverts ends its path just as a return does, since
+            // nothing needs to be returned after it.
+            if expr.diverges() {
+                NodeConnection::Return(entry)
+            } else {
+                NodeConnection::NextStep(vec![entry])
+            }
         }
         TypedAstNodeContent::SideEffect => NodeConnection::NextStep(leaves.to_vec()),
         TypedAstNodeContent::Declaration(decl) => {
@@ -180,6 +197,8 @@ fn connect_declaration(
         | AbiDeclaration(_)
         | StructDeclaration(_)
         | EnumDeclaration(_)
+        | StorageDeclaration(_)
+        | TypeAliasDeclaration(_)
         | GenericTypeForFunctionScope { .. } => leaves.to_vec(),
         VariableDeclaration(_) | ConstantDeclaration(_) => {
             let entry_node = graph.add_node(node.into());
@@ -196,7 +215,7 @@ fn connect_declaration(
             connect_typed_fn_decl(fn_decl, graph, entry_node, span);
             leaves.to_vec()
         }
-        Reassignment(TypedReassignment { .. }) => {
+        Reassignment(TypedReassignment { .. }) | StorageReassignment(_) => {
             let entry_node = graph.add_node(node.into());
             for leaf in leaves {
                 graph.add_edge(*leaf, entry_node, "".into());
@@ -5,10 +5,11 @@ use crate::{
     semantic_analysis::{
         ast_node::{
             TypedAbiDeclaration, TypedCodeBlock, TypedConstantDeclaration, TypedDeclaration,
-            TypedEnumDeclaration, TypedExpression, TypedExpressionVariant,
-            TypedFunctionDeclaration, TypedReassignment, TypedReturnStatement,
-            TypedStructDeclaration, TypedStructExpressionField, TypedTraitDeclaration,
-            TypedVariableDeclaration, TypedWhileLoop,
+            TypedEnumDeclaration, TypedExpression, TypedExpressionVariant, TypedForLoop,
+            TypedForLoopIterable, TypedFunctionDeclaration, TypedReassignment,
+            TypedReturnStatement, TypedStorageDeclaration, TypedStorageField,
+            TypedStorageReassignment, TypedStructDeclaration, TypedStructExpressionField,
+            TypedTraitDeclaration, TypedVariableDeclaration, TypedWhileLoop,
         },
         TypedAstNode, TypedAstNodeContent, TypedParseTree,
     },
@@ -173,10 +174,30 @@ impl ControlFlowGraph {
                             TypedAstNodeContent::Declaration(TypedDeclaration::ImplTrait { .. }),
                         ..
                     }) => true,
+                    ControlFlowGraphNode::ProgramNode(TypedAstNode {
+                        content: TypedAstNodeContent::Declaration(ref decl),
+                        ..
+                    }) if matches!(decl, TypedDeclaration::StorageDeclaration(..)) => true,
                     _ => false,
                 })
                 .collect(),
         };
+        // test functions are run on their own, so they are entry points too
+        let test_functions = graph
+            .graph
+            .node_indices()
+            .filter(|i| match graph.graph[*i] {
+                ControlFlowGraphNode::ProgramNode(TypedAstNode {
+                    content:
+                        TypedAstNodeContent::Declaration(TypedDeclaration::FunctionDeclaration(
+                            ref decl,
+                        )),
+                    ..
+                }) => decl.is_test(),
+                _ => false,
+            })
+            .collect::<Vec<_>>();
+        graph.entry_points.extend(test_functions);
         Ok(())
     }
 }
@@ -253,26 +274,69 @@ fn connect_node(
             for leaf in leaves {
                 graph.add_edge(*leaf, entry, "".into());
             }
-            // it is possible for a whole while loop to be skipped so add edge from
-            // beginning of while loop straight to exit
-            graph.add_edge(
-                entry,
-                while_loop_exit,
-                "condition is initially false".into(),
-            );
-            let mut leaves = vec![entry];
-            let (l_leaves, _l_exit_node) =
-                depth_first_insertion_code_block(body, graph, &leaves, exit_node, tree_type)?;
-            // insert edges from end of block back to beginning of it
-            for leaf in &l_leaves {
-                graph.add_edge(*leaf, entry, "loop repeats".into());
+            connect_loop_body(body, graph, entry, while_loop_exit, exit_node, tree_type)?;
+            (vec![while_loop_exit], exit_node)
+        }
+        TypedAstNodeContent::ForLoop(TypedForLoop { iterable, body, .. }) => {
+            // a for loop is like a while loop, except its iterable is evaluated once before the
+            // loop begins, so each iteration begins after it.
+            let entry = graph.add_node(node.into());
+            for leaf in leaves {
+                graph.add_edge(*leaf, entry, "".into());
             }
-
-            leaves = l_leaves;
+            let iterable_exprs = match iterable {
+                TypedForLoopIterable::Array(array) => vec![&**array],
+                TypedForLoopIterable::Range { start, end } => vec![&**start, &**end],
+            };
+            let mut iterable_leaves = vec![entry];
+            for expr in iterable_exprs {
+                iterable_leaves = connect_expression(
+                    &expr.expression,
+                    graph,
+                    &iterable_leaves,
+                    exit_node,
+                    "",
+                    tree_type,
+                    expr.span.clone(),
+                )?;
+            }
+            let for_loop_head = graph.add_node("for loop head".to_string().into());
+            for leaf in iterable_leaves {
+                graph.add_edge(leaf, for_loop_head, "".into());
+            }
+            let for_loop_exit = graph.add_node("for loop exit".to_string().into());
+            connect_loop_body(
+                body,
+                graph,
+                for_loop_head,
+                for_loop_exit,
+                exit_node,
+                tree_type,
+            )?;
+            (vec![for_loop_exit], exit_node)
+        }
+        TypedAstNodeContent::Break => {
+            // a break leads out of the innermost loop, so nothing after it is reachable.
+            let this_index = graph.add_node(node.into());
             for leaf in leaves {
-                graph.add_edge(leaf, while_loop_exit, "".into());
+                graph.add_edge(*leaf, this_index, "".into());
             }
-            (vec![while_loop_exit], exit_node)
+            if let Some((_, loop_exit)) = graph.loops.last().cloned() {
+                graph.add_edge(this_index, loop_exit, "break".into());
+            }
+            (vec![], exit_node)
+        }
+        TypedAstNodeContent::Continue => {
+            // a continue leads back to the start of the innermost loop, so nothing after it is
+            // reachable.
+            let this_index = graph.add_node(node.into());
+            for leaf in leaves {
+                graph.add_edge(*leaf, this_index, "".into());
+            }
+            if let Some((loop_entry, _)) = graph.loops.last().cloned() {
+                graph.add_edge(this_index, loop_entry, "continue".into());
+            }
+            (vec![], exit_node)
         }
         TypedAstNodeContent::Expression(TypedExpression {
             expression: expr_variant,
@@ -367,6 +431,31 @@ fn connect_declaration(
             tree_type,
             rhs.clone().span,
         ),
+        StorageReassignment(TypedStorageReassignment { rhs, .. }) => connect_expression(
+            &rhs.expression,
+            graph,
+            &[entry_node],
+            exit_node,
+            "storage reassignment",
+            tree_type,
+            rhs.clone().span,
+        ),
+        // Storage is persistent contract state which is accessible externally, so it is never
+        // considered dead.  Anything its initializers refer to is alive too.
+        StorageDeclaration(TypedStorageDeclaration { fields, .. }) => {
+            for TypedStorageField { initializer, .. } in fields {
+                connect_expression(
+                    &initializer.expression,
+                    graph,
+                    &[entry_node],
+                    exit_node,
+                    "storage field initializer",
+                    tree_type,
+                    initializer.span.clone(),
+                )?;
+            }
+            Ok(leaves.to_vec())
+        }
         ImplTrait {
             trait_name,
             methods,
@@ -375,7 +464,9 @@ fn connect_declaration(
             connect_impl_trait(trait_name, graph, methods, entry_node, tree_type)?;
             Ok(leaves.to_vec())
         }
-        ErrorRecovery | GenericTypeForFunctionScope { .. } => Ok(leaves.to_vec()),
+        ErrorRecovery | GenericTypeForFunctionScope { .. } | TypeAliasDeclaration(_) => {
+            Ok(leaves.to_vec())
+        }
     }
 }
 
@@ -574,6 +665,35 @@ fn connect_typed_fn_decl(
     Ok(())
 }
 
+/// Connects the `body` of a loop in which each iteration begins at `loop_entry` and which leads
+/// out to `loop_exit`.  The loop may be skipped entirely, and the end of the body leads both back
+/// to the entry and out to the exit.
+fn connect_loop_body(
+    body: &TypedCodeBlock,
+    graph: &mut ControlFlowGraph,
+    loop_entry: NodeIndex,
+    loop_exit: NodeIndex,
+    exit_node: Option<NodeIndex>,
+    tree_type: &TreeType,
+) -> Result<(), CompileError> {
+    // it is possible for a whole loop to be skipped so add edge from
+    // beginning of the loop straight to exit
+    graph.add_edge(loop_entry, loop_exit, "condition is initially false".into());
+    graph.loops.push((loop_entry, loop_exit));
+    let body_result =
+        depth_first_insertion_code_block(body, graph, &[loop_entry], exit_node, tree_type);
+    graph.loops.pop();
+    let (l_leaves, _l_exit_node) = body_result?;
+    // insert edges from end of block back to beginning of it
+    for leaf in &l_leaves {
+        graph.add_edge(*leaf, loop_entry, "loop repeats".into());
+    }
+    for leaf in l_leaves {
+        graph.add_edge(leaf, loop_exit, "".into());
+    }
+    Ok(())
+}
+
 fn depth_first_insertion_code_block(
     node_content: &TypedCodeBlock,
     graph: &mut ControlFlowGraph,
@@ -661,7 +781,7 @@ fn connect_expression(
                 Ok(vec![fn_entrypoint])
             }
         }
-        LazyOperator { lhs, rhs, .. } => {
+        LazyOperator { lhs, rhs, .. } | BuiltinOperator { lhs, rhs, .. } => {
             let lhs_expr = connect_expression(
                 &lhs.expression,
                 graph,
@@ -929,7 +1049,7 @@ fn connect_expression(
             )?;
             Ok([prefix_idx, index_idx].concat())
         }
-        EnumArgAccess { prefix, .. } => {
+        EnumArgAccess { prefix, .. } | EnumTag { prefix } => {
             let prefix_idx = connect_expression(
                 &prefix.expression,
                 graph,
@@ -953,6 +1073,85 @@ fn connect_expression(
             )?;
             Ok(prefix_idx)
         }
+        StorageAccess { field_name, .. } => {
+            let node =
+                graph.add_node(format!("storage field {} access", field_name.as_str()).into());
+            for leaf in leaves {
+                graph.add_edge(*leaf, node, "".into());
+            }
+            Ok(vec![node])
+        }
+        Revert { code } => {
+            connect_expression(
+                &code.expression,
+                graph,
+                leaves,
+                exit_node,
+                "",
+                tree_type,
+                code.span.clone(),
+            )?;
+            // nothing after a revert is ever reached
+            Ok(vec![])
+        }
+        Log { value } => connect_expression(
+            &value.expression,
+            graph,
+            leaves,
+            exit_node,
+            "",
+            tree_type,
+            value.span.clone(),
+        ),
+        VecOperation { arguments, .. } | FunctionValueApplication { arguments, .. } => {
+            let mut current_leaf = leaves.to_vec();
+            for arg in arguments {
+                current_leaf = connect_expression(
+                    &arg.expression,
+                    graph,
+                    &current_leaf,
+                    exit_node,
+                    "",
+                    tree_type,
+                    arg.span.clone(),
+                )?;
+            }
+            Ok(current_leaf)
+        }
+        // a function passed by name is only called where it's bound, but it is used here
+        FunctionValue(value) if !value.is_closure() => {
+            if let Some(FunctionNamespaceEntry { entry_point, .. }) =
+                graph.namespace.get_function(&value.name.suffix).cloned()
+            {
+                for leaf in leaves {
+                    graph.add_edge(*leaf, entry_point, label.into());
+                }
+            }
+            Ok(leaves.to_vec())
+        }
+        FunctionValue(value) => {
+            let mut current_leaf = leaves.to_vec();
+            for (_name, capture) in &value.captures {
+                current_leaf = connect_expression(
+                    &capture.expression,
+                    graph,
+                    &current_leaf,
+                    exit_node,
+                    "",
+                    tree_type,
+                    capture.span.clone(),
+                )?;
+            }
+            connect_expression(
+                &CodeBlock(value.body.clone()),
+                graph,
+                &current_leaf,
+                exit_node,
+                "closure body",
+                tree_type,
+                expression_span,
+            )
+        }
         a => {
             println!("Unimplemented: {:?}", a);
             Err(CompileError::Unimplemented(
@@ -1045,6 +1244,11 @@ fn construct_dead_code_warning_from_node(node: &TypedAstNode) -> Option<CompileW
             content: TypedAstNodeContent::Declaration(TypedDeclaration::AbiDeclaration { .. }),
             ..
         } => return None,
+        // the uses of a type alias aren't tracked, as types aren't nodes in the graph
+        TypedAstNode {
+            content: TypedAstNodeContent::Declaration(TypedDeclaration::TypeAliasDeclaration(..)),
+            ..
+        } => return None,
         TypedAstNode {
             content: TypedAstNodeContent::Declaration(..),
             span,
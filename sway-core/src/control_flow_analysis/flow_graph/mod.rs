@@ -25,6 +25,9 @@ pub struct ControlFlowGraph {
     pub(crate) graph: Graph,
     pub(crate) entry_points: Vec<NodeIndex>,
     pub(crate) namespace: ControlFlowNamespace,
+    /// The entry and exit nodes of the loops enclosing the node being connected, innermost last.
+    /// These are where `continue` and `break` lead to.
+    pub(crate) loops: Vec<(NodeIndex, NodeIndex)>,
 }
 
 pub type Graph = petgraph::Graph<ControlFlowGraphNode, ControlFlowGraphEdge>;
@@ -199,6 +199,9 @@ pub enum Warning {
     NonClassCaseEnumVariantName {
         variant_name: Ident,
     },
+    NonClassCaseTypeAliasName {
+        name: Ident,
+    },
     NonSnakeCaseStructFieldName {
         field_name: Ident,
     },
@@ -237,6 +240,14 @@ pub enum Warning {
     ShadowingReservedRegister {
         reg_name: Ident,
     },
+    MatchExpressionUnreachableArm,
+    UnknownAttribute {
+        name: String,
+    },
+    UsingDeprecated {
+        name: String,
+    },
+    InlineHintIgnored,
 }
 
 impl fmt::Display for Warning {
@@ -281,6 +292,13 @@ impl fmt::Display for Warning {
                 variant_name,
                 to_upper_camel_case(variant_name.as_str())
             ),
+            NonClassCaseTypeAliasName { name } => write!(
+                f,
+                "Type alias name \"{}\" is not idiomatic. Type aliases should have a ClassCase \
+                 name, like \"{}\".",
+                name,
+                to_upper_camel_case(name.as_str())
+            ),
             NonSnakeCaseFunctionName { name } => {
                 write!(f,
                 "Function name \"{}\" is not idiomatic. Function names should be snake_case, like \
@@ -342,6 +360,22 @@ impl fmt::Display for Warning {
                 "This register declaration shadows the reserved register, \"{}\".",
                 reg_name
             ),
+            MatchExpressionUnreachableArm => write!(
+                f,
+                "This match arm is unreachable, as every value it matches is matched by an \
+                 earlier arm."
+            ),
+            UnknownAttribute { name } => write!(
+                f,
+                "Unknown attribute \"{}\". It will be ignored.",
+                name
+            ),
+            UsingDeprecated { name } => write!(f, "\"{}\" is deprecated.", name),
+            InlineHintIgnored => write!(
+                f,
+                "Inline hints are only used when compiling with `--use-ir`. Without it every call \
+                 is inlined, so this will be ignored."
+            ),
         }
     }
 }
@@ -472,6 +506,23 @@ pub enum CompileError {
     },
     #[error("Assignment to immutable variable. Variable {0} is not declared as mutable.")]
     AssignmentToNonMutable(String, Span),
+    #[error("Mutable reference to immutable variable. Variable {0} is not declared as mutable.")]
+    MutableReferenceToNonMutable(String, Span),
+    #[error("Only a mutable variable can be passed by mutable reference.")]
+    MutableReferenceToNonVariable(Span),
+    #[error("Function \"{name}\" cannot be used as a value, because it {reason}.")]
+    InvalidFunctionValue {
+        name: Ident,
+        reason: &'static str,
+        span: Span,
+    },
+    #[error(
+        "A function value can only be passed as an argument to a function, or bound to an \
+         immutable variable if it doesn't capture any variables."
+    )]
+    MisplacedFunctionValue(Span),
+    #[error("Cannot infer the type of closure parameter \"{name}\". Consider annotating it.")]
+    UnknownClosureParameterType { name: Ident, span: Span },
     #[error(
         "Generic type \"{name}\" is not in scope. Perhaps you meant to specify type parameters in \
          the function signature? For example: \n`fn \
@@ -513,6 +564,14 @@ pub enum CompileError {
         missing_functions: String,
         span: Span,
     },
+    #[error("Type \"{name}\" is not an associated type of trait \"{trait_name}\".")]
+    TypeNotAnAssociatedTypeOfTrait {
+        name: Ident,
+        trait_name: Ident,
+        span: Span,
+    },
+    #[error("Associated types are missing from this trait implementation: {missing_types}")]
+    MissingAssociatedTypes { missing_types: String, span: Span },
     #[error("Expected {expected} type arguments, but instead found {given}.")]
     IncorrectNumberOfTypeArguments {
         given: usize,
@@ -565,6 +624,25 @@ pub enum CompileError {
         method_name: String,
         type_name: String,
     },
+    #[error(
+        "No method named \"{method_name}\" for type \"{type_name}\" accepts arguments of types \
+         ({argument_types})."
+    )]
+    NoMethodAcceptsArguments {
+        span: Span,
+        method_name: String,
+        type_name: String,
+        argument_types: String,
+    },
+    #[error(
+        "Multiple methods named \"{method_name}\" for type \"{type_name}\" accept these \
+         arguments."
+    )]
+    AmbiguousMethod {
+        span: Span,
+        method_name: String,
+        type_name: String,
+    },
     #[error("Module \"{name}\" could not be found.")]
     ModuleNotFound { span: Span, name: String },
     #[error("\"{name}\" is a {actually}, not a struct. Fields can only be accessed on structs.")]
@@ -595,6 +673,16 @@ pub enum CompileError {
         struct_name: String,
         span: Span,
     },
+    #[error(
+        "Tuple index {index} is out of bounds. The tuple \"{tuple_name}\" has {count} \
+         element(s)."
+    )]
+    TupleIndexOutOfBounds {
+        index: usize,
+        count: usize,
+        tuple_name: String,
+        span: Span,
+    },
     #[error("Could not find symbol \"{name}\" in this scope.")]
     SymbolNotFound { span: Span, name: String },
     #[error("Symbol \"{name}\" is private.")]
@@ -797,16 +885,78 @@ pub enum CompileError {
          "
     )]
     MatchWrongType { expected: TypeId, span: Span },
+    #[error(
+        "Match expression is not exhaustive. These patterns are not matched: {missing_patterns}."
+    )]
+    MatchExpressionNonExhaustive {
+        missing_patterns: String,
+        span: Span,
+    },
     #[error("Impure function called inside of pure function. Pure functions can only call other pure functions. Try making the surrounding function impure by prepending \"impure\" to the function declaration.")]
     PureCalledImpure { span: Span },
     #[error("Impure function inside of non-contract. Contract storage is only accessible from contracts.")]
     ImpureInNonContract { span: Span },
+    #[error("Storage accessed inside of pure function. Pure functions cannot read or write contract storage. Try making the surrounding function impure by prepending \"impure\" to the function declaration.")]
+    PureAccessedStorage { span: Span },
+    #[error("Storage declaration inside of non-contract. Contract storage is only accessible from contracts.")]
+    StorageDeclarationInNonContract { span: Span },
+    #[error("Multiple storage declarations were found. A contract may only declare storage once.")]
+    MultipleStorageDeclarations { span: Span },
+    #[error("Storage attempted to be accessed, but no storage has been declared.")]
+    NoDeclaredStorage { span: Span },
+    #[error("Storage field \"{field_name}\" does not exist.")]
+    StorageFieldDoesNotExist { field_name: String, span: Span },
     #[error("Literal value is too large for type {ty}.")]
     IntegerTooLarge { span: Span, ty: String },
     #[error("Literal value underflows type {ty}.")]
     IntegerTooSmall { span: Span, ty: String },
     #[error("Literal value contains digits which are not valid for type {ty}.")]
     IntegerContainsInvalidDigit { span: Span, ty: String },
+    #[error("\"break\" used outside of a loop. It may only be used within the body of a \"while\" or \"for\" loop.")]
+    BreakOutsideLoop { span: Span },
+    #[error("\"continue\" used outside of a loop. It may only be used within the body of a \"while\" or \"for\" loop.")]
+    ContinueOutsideLoop { span: Span },
+    #[error("A for loop can only iterate over an array or a range of unsigned integers, but this is of type \"{ty}\".")]
+    NotIterable { ty: String, span: Span },
+    #[error("This pattern might not match the value it destructures. Only patterns which always match, made up of variables, tuples and structs, can be used in a \"let\". Try using a match expression instead.")]
+    RefutablePatternInLet { span: Span },
+    #[error(
+        "The type \"{ty}\" does not implement the trait \"{trait_name}\", which is required of \
+         the type parameter \"{param}\". Try adding \"impl {trait_name} for {ty}\"."
+    )]
+    TraitConstraintNotSatisfied {
+        ty: String,
+        trait_name: Ident,
+        param: Ident,
+        span: Span,
+    },
+    #[error(
+        "This expression can't be evaluated at compile time, so it can't be the value of a \
+         constant. A constant may only be made of literals, other constants, operators on \
+         integers and booleans, and struct, tuple and array expressions."
+    )]
+    NonConstantExpression { span: Span },
+    #[error("This constant expression overflows its type, {ty}.")]
+    ConstantOverflow { span: Span, ty: String },
+    #[error("This constant expression divides by zero.")]
+    ConstantDivisionByZero { span: Span },
+    #[error("Malformed attribute \"{name}\". Expected {expected}.")]
+    MalformedAttribute {
+        name: String,
+        expected: &'static str,
+        span: Span,
+    },
+    #[error("The attribute \"{name}\" can't be used on {target}.")]
+    MisplacedAttribute {
+        name: String,
+        target: &'static str,
+        span: Span,
+    },
+    #[error(
+        "Test function \"{name}\" can't take parameters or type parameters. Test functions are \
+         run on their own and have nothing to be called with."
+    )]
+    InvalidTestFunction { name: Ident, span: Span },
 }
 
 impl std::convert::From<TypeError> for CompileError {
@@ -821,7 +971,7 @@ pub enum TypeError {
         "Mismatched types.\n\
          expected: {expected}\n\
          found:    {received}.\n\
-         {help}", expected=look_up_type_id(*expected).friendly_type_str(), received=look_up_type_id(*received).friendly_type_str(), help=if !help_text.is_empty() { format!("help: {}", help_text) } else { String::new() }
+         {help}", expected=expected.friendly_type_str(), received=received.friendly_type_str(), help=if !help_text.is_empty() { format!("help: {}", help_text) } else { String::new() }
     )]
     MismatchedType {
         expected: TypeId,
@@ -923,6 +1073,11 @@ impl CompileError {
             MultipleScriptMainFunctions(span) => span,
             ReassignmentToNonVariable { span, .. } => span,
             AssignmentToNonMutable(_, span) => span,
+            MutableReferenceToNonMutable(_, span) => span,
+            MutableReferenceToNonVariable(span) => span,
+            InvalidFunctionValue { span, .. } => span,
+            MisplacedFunctionValue(span) => span,
+            UnknownClosureParameterType { span, .. } => span,
             TypeParameterNotInTypeScope { span, .. } => span,
             MultipleImmediates(span) => span,
             MismatchedTypeInTrait { span, .. } => span,
@@ -930,6 +1085,8 @@ impl CompileError {
             UnknownTrait { span, .. } => span,
             FunctionNotAPartOfInterfaceSurface { span, .. } => span,
             MissingInterfaceSurfaceMethods { span, .. } => span,
+            TypeNotAnAssociatedTypeOfTrait { span, .. } => span,
+            MissingAssociatedTypes { span, .. } => span,
             IncorrectNumberOfTypeArguments { span, .. } => span,
             StructNotFound { span, .. } => span,
             DeclaredNonStructAsStruct { span, .. } => span,
@@ -938,10 +1095,13 @@ impl CompileError {
             StructMissingField { span, .. } => span,
             StructDoesNotHaveField { span, .. } => span,
             MethodNotFound { span, .. } => span,
+            NoMethodAcceptsArguments { span, .. } => span,
+            AmbiguousMethod { span, .. } => span,
             ModuleNotFound { span, .. } => span,
             NotATuple { span, .. } => span,
             NotAStruct { span, .. } => span,
             FieldNotFound { span, .. } => span,
+            TupleIndexOutOfBounds { span, .. } => span,
             SymbolNotFound { span, .. } => span,
             ImportPrivateSymbol { span, .. } => span,
             NoElseBranch { span, .. } => span,
@@ -998,13 +1158,30 @@ impl CompileError {
             ArrayOutOfBounds { span, .. } => span,
             ShadowsOtherSymbol { span, .. } => span,
             MatchWrongType { span, .. } => span,
+            MatchExpressionNonExhaustive { span, .. } => span,
             NotAnEnum { span, .. } => span,
             PatternMatchingAlgorithmFailure(_, span) => span,
             PureCalledImpure { span, .. } => span,
             ImpureInNonContract { span, .. } => span,
+            PureAccessedStorage { span, .. } => span,
+            StorageDeclarationInNonContract { span, .. } => span,
+            MultipleStorageDeclarations { span, .. } => span,
+            NoDeclaredStorage { span, .. } => span,
+            StorageFieldDoesNotExist { span, .. } => span,
             IntegerTooLarge { span, .. } => span,
             IntegerTooSmall { span, .. } => span,
             IntegerContainsInvalidDigit { span, .. } => span,
+            BreakOutsideLoop { span } => span,
+            ContinueOutsideLoop { span } => span,
+            NotIterable { span, .. } => span,
+            RefutablePatternInLet { span } => span,
+            TraitConstraintNotSatisfied { span, .. } => span,
+            NonConstantExpression { span } => span,
+            ConstantOverflow { span, .. } => span,
+            ConstantDivisionByZero { span } => span,
+            MalformedAttribute { span, .. } => span,
+            MisplacedAttribute { span, .. } => span,
+            InvalidTestFunction { span, .. } => span,
         }
     }
 
@@ -94,6 +94,14 @@ pub enum AstNodeContent {
     /// A control flow element which loops continually until some boolean expression evaluates as
     /// `false`.
     WhileLoop(WhileLoop),
+    /// A control flow element which runs its body once for each element of an array, or each
+    /// integer in a range.
+    ForLoop(ForLoop),
+    /// A statement of the form `break;`, which exits the innermost enclosing loop.
+    Break,
+    /// A statement of the form `continue;`, which skips to the next iteration of the innermost
+    /// enclosing loop.
+    Continue,
     /// A statement of the form `dep foo::bar;` which imports/includes another source file.
     IncludeStatement(IncludeStatement),
 }
@@ -323,6 +331,7 @@ pub fn compile_to_ast(
         graph: Graph::new(),
         entry_points: vec![],
         namespace: Default::default(),
+        loops: vec![],
     };
 
     let typed_parse_tree = check!(
@@ -383,7 +392,7 @@ pub fn compile_to_asm(
                 TreeType::Contract | TreeType::Script | TreeType::Predicate => {
                     let asm = check!(
                         if build_config.use_ir {
-                            compile_ast_to_ir_to_asm(*parse_tree, tree_type, &build_config)
+                            compile_ast_to_ir_to_asm(*parse_tree, &build_config)
                         } else {
                             compile_ast_to_asm(*parse_tree, &build_config)
                         },
@@ -396,23 +405,44 @@ pub fn compile_to_asm(
                     }
                     CompilationResult::Success { asm, warnings }
                 }
-                TreeType::Library { name } => CompilationResult::Library {
-                    warnings,
-                    name,
-                    namespace: parse_tree.get_namespace_ref(),
-                },
+                TreeType::Library { name } => {
+                    let namespace = parse_tree.get_namespace_ref();
+                    // Libraries produce no bytecode of their own, but lowering them to IR checks
+                    // that everything they declare can be compiled by their dependents.
+                    if build_config.use_ir {
+                        check!(
+                            compile_ast_to_ir(*parse_tree, &build_config),
+                            return CompilationResult::Failure { errors, warnings },
+                            warnings,
+                            errors
+                        );
+                        if !errors.is_empty() {
+                            return CompilationResult::Failure { errors, warnings };
+                        }
+                    }
+                    CompilationResult::Library {
+                        warnings,
+                        name,
+                        namespace,
+                    }
+                }
             }
         }
     }
 }
 
-use sway_ir::{context::Context, function::Function};
+use sway_ir::{context::Context, PassManager, PassPipeline};
 
 pub(crate) fn compile_ast_to_ir_to_asm(
     ast: TypedParseTree,
-    tree_type: TreeType,
     build_config: &BuildConfig,
 ) -> CompileResult<FinalizedAsm> {
+    compile_ast_to_ir(ast, build_config)
+        .flat_map(|ir| crate::asm_generation::from_ir::compile_ir_to_asm(&ir, build_config))
+}
+
+/// Lowers `ast` to IR and optimizes it.
+fn compile_ast_to_ir(ast: TypedParseTree, build_config: &BuildConfig) -> CompileResult<Context> {
     let mut warnings = Vec::new();
     let mut errors = Vec::new();
 
@@ -430,29 +460,10 @@ pub(crate) fn compile_ast_to_ir_to_asm(
         }
     };
 
-    // Inline function calls since we don't support them yet.  For scripts and predicates we inline
-    // into main(), and for contracts we inline into ABI impls, which are found due to them having
-    // a selector.
-    let mut functions_to_inline_to = Vec::new();
-    for (idx, fc) in &ir.functions {
-        if (matches!(tree_type, TreeType::Script | TreeType::Predicate) && fc.name == "main")
-            || (tree_type == TreeType::Contract && fc.selector.is_some())
-        {
-            functions_to_inline_to.push(::sway_ir::function::Function(idx));
-        }
-    }
-    check!(
-        inline_function_calls(&mut ir, &functions_to_inline_to),
-        return err(warnings, errors),
-        warnings,
-        errors
-    );
-
-    // The only other optimisation we have at the moment is constant combining.  In lieu of a
-    // forthcoming pass manager we can just call it here now.  We can re-use the inline functions
-    // list.
+    // Run the optimization passes selected by the optimization level.  The function calls which
+    // aren't inlined are compiled to real calls, with each called function compiled just once.
     check!(
-        combine_constants(&mut ir, &functions_to_inline_to),
+        optimize_ir(&mut ir, build_config),
         return err(warnings, errors),
         warnings,
         errors
@@ -462,41 +473,23 @@ pub(crate) fn compile_ast_to_ir_to_asm(
         println!("{}", ir);
     }
 
-    crate::asm_generation::from_ir::compile_ir_to_asm(&ir, build_config)
+    ok(ir, warnings, errors)
 }
 
-fn inline_function_calls(ir: &mut Context, functions: &[Function]) -> CompileResult<()> {
-    for function in functions {
-        if let Err(msg) = sway_ir::optimize::inline_all_function_calls(ir, function) {
-            return err(
-                Vec::new(),
-                vec![CompileError::InternalOwned(
-                    msg,
-                    span::Span {
-                        span: pest::Span::new("".into(), 0, 0).unwrap(),
-                        path: None,
-                    },
-                )],
-            );
-        }
-    }
-    ok((), Vec::new(), Vec::new())
-}
-
-fn combine_constants(ir: &mut Context, functions: &[Function]) -> CompileResult<()> {
-    for function in functions {
-        if let Err(msg) = sway_ir::optimize::combine_constants(ir, function) {
-            return err(
-                Vec::new(),
-                vec![CompileError::InternalOwned(
-                    msg,
-                    span::Span {
-                        span: pest::Span::new("".into(), 0, 0).unwrap(),
-                        path: None,
-                    },
-                )],
-            );
-        }
+fn optimize_ir(ir: &mut Context, build_config: &BuildConfig) -> CompileResult<()> {
+    let pipeline =
+        PassPipeline::for_opt_level(build_config.ir_opt_level).verify_each(cfg!(debug_assertions));
+    if let Err(msg) = PassManager::with_standard_passes().run(ir, &pipeline) {
+        return err(
+            Vec::new(),
+            vec![CompileError::InternalOwned(
+                msg,
+                span::Span {
+                    span: pest::Span::new("".into(), 0, 0).unwrap(),
+                    path: None,
+                },
+            )],
+        );
     }
     ok((), Vec::new(), Vec::new())
 }
@@ -2,7 +2,8 @@ use std::collections::HashMap;
 use std::iter::FromIterator;
 
 use crate::{
-    parse_tree::{AsmOp, AsmRegister, LazyOp, Literal, Visibility},
+    asm_lang::ConstantRegister,
+    parse_tree::{AsmOp, AsmRegister, LazyOp, Literal, OpVariant, Visibility},
     semantic_analysis::{ast_node::TypedCodeBlock, ast_node::*, *},
     type_engine::*,
 };
@@ -24,11 +25,11 @@ pub(crate) fn compile_ast(ast: TypedParseTree) -> Result<Context, String> {
             all_nodes: _,
         } => compile_script(&mut ctx, main_function, namespace, declarations),
         TypedParseTree::Predicate {
-            namespace: _,
-            main_function: _,
-            declarations: _,
+            namespace,
+            main_function,
+            declarations,
             all_nodes: _,
-        } => unimplemented!("compile predicate to ir"),
+        } => compile_predicate(&mut ctx, main_function, namespace, declarations),
         TypedParseTree::Contract {
             abi_entries,
             namespace: _,
@@ -36,9 +37,9 @@ pub(crate) fn compile_ast(ast: TypedParseTree) -> Result<Context, String> {
             all_nodes: _,
         } => compile_contract(&mut ctx, abi_entries, declarations),
         TypedParseTree::Library {
-            namespace: _,
-            all_nodes: _,
-        } => unimplemented!("compile library to ir"),
+            namespace,
+            all_nodes,
+        } => compile_library(&mut ctx, namespace, all_nodes),
     }?;
     ctx.verify()?;
     Ok(ctx)
@@ -61,6 +62,44 @@ fn compile_script(
     Ok(module)
 }
 
+fn compile_predicate(
+    context: &mut Context,
+    main_function: TypedFunctionDeclaration,
+    namespace: NamespaceRef,
+    declarations: Vec<TypedDeclaration>,
+) -> Result<Module, String> {
+    let module = Module::new(context, Kind::Predicate, "predicate");
+
+    compile_constants(context, module, namespace, false)?;
+    compile_declarations(context, module, declarations)?;
+    compile_function(context, module, main_function)?;
+
+    Ok(module)
+}
+
+// A library has no entry point, all its (non-generic) declarations are compiled so they're
+// available to dependents.
+fn compile_library(
+    context: &mut Context,
+    namespace: NamespaceRef,
+    all_nodes: Vec<TypedAstNode>,
+) -> Result<Module, String> {
+    let module = Module::new(context, Kind::Library, "library");
+
+    let declarations = all_nodes
+        .into_iter()
+        .filter_map(|node| match node.content {
+            TypedAstNodeContent::Declaration(decl) => Some(decl),
+            _otherwise => None,
+        })
+        .collect();
+
+    compile_constants(context, module, namespace, false)?;
+    compile_declarations(context, module, declarations)?;
+
+    Ok(module)
+}
+
 fn compile_contract(
     context: &mut Context,
     abi_entries: Vec<TypedFunctionDeclaration>,
@@ -68,14 +107,80 @@ fn compile_contract(
 ) -> Result<Module, String> {
     let module = Module::new(context, Kind::Contract, "contract");
 
+    let storage_initializer = compile_storage_initializer(context, module, &declarations)?;
     compile_declarations(context, module, declarations)?;
     for decl in abi_entries {
-        compile_abi_method(context, module, decl)?;
+        compile_abi_method(context, module, decl, storage_initializer)?;
     }
 
     Ok(module)
 }
 
+// The storage fields are given the values of their initializers by the first call to the
+// contract, which finds the flag at `get_storage_initialized_key()` unset.  This is a function
+// which each ABI method calls on entry.
+fn compile_storage_initializer(
+    context: &mut Context,
+    module: Module,
+    declarations: &[TypedDeclaration],
+) -> Result<Option<Function>, String> {
+    let fields = match declarations.iter().find_map(|decl| match decl {
+        TypedDeclaration::StorageDeclaration(storage) => Some(&storage.fields),
+        _otherwise => None,
+    }) {
+        Some(fields) if !fields.is_empty() => fields,
+        _otherwise => return Ok(None),
+    };
+
+    let func = Function::new(
+        context,
+        module,
+        "__init_storage".to_owned(),
+        Vec::new(),
+        Type::Unit,
+        None,
+        false,
+    );
+    let mut compiler = FnCompiler::new(context, module, func);
+    let init_block = func.create_block(context, None);
+    let done_block = func.create_block(context, None);
+
+    let flag_key = Constant::get_b256(context, get_storage_initialized_key());
+    let is_initialized = compiler
+        .current_block
+        .ins(context)
+        .state_load(flag_key, Type::Bool);
+    compiler.current_block.ins(context).conditional_branch(
+        is_initialized,
+        done_block,
+        init_block,
+        None,
+    );
+
+    compiler.current_block = init_block;
+    for (ix, field) in fields.iter().enumerate() {
+        let span_md_idx = MetadataIndex::from_span(context, &field.initializer.span);
+        let init_val = compiler.compile_expression(context, field.initializer.clone())?;
+        let key_val = Constant::get_b256(context, get_storage_key(ix));
+        compiler
+            .current_block
+            .ins(context)
+            .state_store(key_val, init_val)
+            .add_metadatum(context, span_md_idx);
+    }
+    let flag_key = Constant::get_b256(context, get_storage_initialized_key());
+    let flag_val = Constant::get_bool(context, true);
+    compiler
+        .current_block
+        .ins(context)
+        .state_store(flag_key, flag_val);
+    compiler.current_block.ins(context).branch(done_block, None);
+
+    let unit_val = Constant::get_unit(context);
+    done_block.ins(context).ret(unit_val, Type::Unit);
+    Ok(Some(func))
+}
+
 // -------------------------------------------------------------------------------------------------
 
 fn compile_constants(
@@ -115,10 +220,36 @@ fn compile_constant_expression(
     context: &mut Context,
     const_expr: &TypedExpression,
 ) -> Result<Value, String> {
-    if let TypedExpressionVariant::Literal(literal) = &const_expr.expression {
-        Ok(convert_literal_to_value(context, literal))
-    } else {
-        Err("Unsupported constant declaration type.".into())
+    let constant = compile_constant(context, const_expr)?;
+    Ok(Value::new_constant(context, constant))
+}
+
+// The value of a constant has been evaluated during type checking, so is a literal or a struct,
+// tuple or array expression made of them.
+fn compile_constant(
+    context: &mut Context,
+    const_expr: &TypedExpression,
+) -> Result<Constant, String> {
+    let elems = match &const_expr.expression {
+        TypedExpressionVariant::Literal(literal) => {
+            return Ok(convert_literal_to_constant(literal));
+        }
+        TypedExpressionVariant::Tuple { fields } => fields.iter().collect::<Vec<_>>(),
+        TypedExpressionVariant::Array { contents } => contents.iter().collect(),
+        TypedExpressionVariant::StructExpression { fields, .. } => {
+            fields.iter().map(|field| &field.value).collect()
+        }
+        _ => return Err("Unsupported constant declaration type.".into()),
+    };
+    let elems = elems
+        .into_iter()
+        .map(|elem| compile_constant(context, elem))
+        .collect::<Result<Vec<_>, String>>()?;
+    match convert_resolved_typeid(context, &const_expr.return_type, &const_expr.span)? {
+        Type::Unit => Ok(Constant::new_unit()),
+        Type::Struct(aggregate) => Ok(Constant::new_struct(&aggregate, elems)),
+        Type::Array(aggregate) => Ok(Constant::new_array(&aggregate, elems)),
+        _ => Err("Unsupported constant declaration type.".into()),
     }
 }
 
@@ -146,7 +277,18 @@ fn compile_declarations(
                 module.add_global_constant(context, decl.name.as_str().to_owned(), const_val);
             }
 
-            TypedDeclaration::FunctionDeclaration(decl) => compile_function(context, module, decl)?,
+            // Test functions are left out of the program.
+            TypedDeclaration::FunctionDeclaration(decl) if decl.is_test() => (),
+            TypedDeclaration::FunctionDeclaration(decl) => {
+                compile_function(context, module, decl)?;
+            }
+            // Implementations of a generic trait for one type may have methods of the same name,
+            // e.g. `impl From<u64> for X` and `impl From<b256> for X`, so these are instantiated
+            // at their call sites instead.
+            TypedDeclaration::ImplTrait {
+                trait_type_arguments,
+                ..
+            } if !trait_type_arguments.is_empty() => (),
             TypedDeclaration::ImplTrait {
                 methods,
                 type_implementing_for,
@@ -160,6 +302,9 @@ fn compile_declarations(
             | TypedDeclaration::Reassignment(_)
             | TypedDeclaration::AbiDeclaration(_)
             | TypedDeclaration::GenericTypeForFunctionScope { .. }
+            | TypedDeclaration::StorageDeclaration(_)
+            | TypedDeclaration::StorageReassignment(_)
+            | TypedDeclaration::TypeAliasDeclaration(_)
             | TypedDeclaration::ErrorRecovery => (),
         }
     }
@@ -274,15 +419,17 @@ fn create_tuple_aggregate(context: &mut Context, fields: Vec<TypeId>) -> Result<
 
 // -------------------------------------------------------------------------------------------------
 
+// Returns the new function, unless the declaration is only compiled at its call sites.
 fn compile_function(
     context: &mut Context,
     module: Module,
     ast_fn_decl: TypedFunctionDeclaration,
-) -> Result<(), String> {
+) -> Result<Option<Function>, String> {
     // Currently monomorphisation of generics is inlined into main() and the functions with generic
-    // args are still present in the AST declarations, but they can be ignored.
-    if !ast_fn_decl.type_parameters.is_empty() {
-        Ok(())
+    // args are still present in the AST declarations, but they can be ignored.  Likewise for the
+    // functions which are bound to the function values they're passed at each call.
+    if !ast_fn_decl.type_parameters.is_empty() || ast_fn_decl.has_function_parameters() {
+        Ok(None)
     } else {
         let args = ast_fn_decl
             .parameters
@@ -293,7 +440,7 @@ fn compile_function(
             })
             .collect::<Result<Vec<(String, Type)>, String>>()?;
 
-        compile_fn_with_args(context, module, ast_fn_decl, args, None)
+        compile_fn_with_args(context, module, ast_fn_decl, args, None, None).map(Some)
     }
 }
 
@@ -305,16 +452,39 @@ fn compile_fn_with_args(
     ast_fn_decl: TypedFunctionDeclaration,
     args: Vec<(String, Type)>,
     selector: Option<[u8; 4]>,
-) -> Result<(), String> {
+    storage_initializer: Option<Function>,
+) -> Result<Function, String> {
+    let inline = convert_inline(ast_fn_decl.inline());
     let TypedFunctionDeclaration {
         name,
         body,
+        parameters,
         return_type,
         return_type_span,
         visibility,
+        span,
         ..
     } = ast_fn_decl;
 
+    // A `ref mut` parameter is passed the address of the caller's variable.  A value laid out in
+    // memory is its address already, but one held in a register is loaded and stored through it.
+    let references = parameters
+        .iter()
+        .filter(|param| param.is_reference)
+        .map(|param| {
+            resolve_type(param.r#type, &param.type_span)
+                .map(|ty| (param.name.as_str().to_owned(), ty))
+                .map_err(|ty_err| format!("{:?}", ty_err))
+        })
+        .collect::<Result<HashMap<_, _>, String>>()?;
+    let args = args
+        .into_iter()
+        .map(|(name, ty)| match references.get(&name) {
+            Some(pointee) if pointee.is_held_in_register() => (name, Type::Uint(64)),
+            _otherwise => (name, ty),
+        })
+        .collect();
+
     let ret_type = convert_resolved_typeid(context, &return_type, &return_type_span)?;
     let func = Function::new(
         context,
@@ -325,12 +495,32 @@ fn compile_fn_with_args(
         selector,
         visibility == Visibility::Public,
     );
+    func.set_inline(context, inline);
 
     let mut compiler = FnCompiler::new(context, module, func);
+    compiler.references = references;
 
+    if let Some(storage_initializer) = storage_initializer {
+        compiler
+            .current_block
+            .ins(context)
+            .call(storage_initializer, &[]);
+    }
     let ret_val = compiler.compile_code_block(context, body)?;
-    compiler.current_block.ins(context).ret(ret_val, ret_type);
-    Ok(())
+    let span_md_idx = MetadataIndex::from_span(context, &span);
+    compiler
+        .current_block
+        .ins(context)
+        .ret(ret_val, ret_type)
+        .add_metadatum(context, span_md_idx);
+    Ok(func)
+}
+
+fn convert_inline(inline: Option<crate::parse_tree::Inline>) -> Option<Inline> {
+    inline.map(|inline| match inline {
+        crate::parse_tree::Inline::Always => Inline::Always,
+        crate::parse_tree::Inline::Never => Inline::Never,
+    })
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -341,7 +531,11 @@ fn compile_impl(
     self_type: TypeInfo,
     ast_methods: Vec<TypedFunctionDeclaration>,
 ) -> Result<(), String> {
-    for method in ast_methods {
+    // Methods of generic impls are monomorphised at their call sites, as with generic functions.
+    for method in ast_methods
+        .into_iter()
+        .filter(|method| method.type_parameters.is_empty() && !method.has_function_parameters())
+    {
         let args = method
             .parameters
             .iter()
@@ -355,17 +549,116 @@ fn compile_impl(
             })
             .collect::<Result<Vec<(String, Type)>, String>>()?;
 
-        compile_fn_with_args(context, module, method, args, None)?;
+        // Impls for different types, or of different traits, may have methods of the same name, so
+        // the type and where the method was declared are a part of the name which calls find the
+        // method by.
+        let ir_name = method_ir_name(&self_type, method.name.as_str(), &method.body);
+        let ir_name_len = ir_name.len();
+        let method = TypedFunctionDeclaration {
+            name: Ident::new(crate::span::Span {
+                span: pest::Span::new(std::sync::Arc::from(ir_name), 0, ir_name_len).unwrap(),
+                path: None,
+            }),
+            ..method
+        };
+
+        compile_fn_with_args(context, module, method, args, None, None)?;
     }
     Ok(())
 }
 
+fn method_ir_name(self_type: &TypeInfo, method_name: &str, body: &TypedCodeBlock) -> String {
+    let type_name = match self_type {
+        TypeInfo::Struct { name, .. } | TypeInfo::Enum { name, .. } => name.clone(),
+        ty => ty
+            .friendly_type_str()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect(),
+    };
+    // A Sway identifier can't contain `::` so the name can't clash with a free function.
+    format!(
+        "{}::{}_{}_{}",
+        type_name,
+        method_name,
+        body.whole_block_span.start(),
+        body.whole_block_span.end()
+    )
+}
+
+// The IR name of the instantiation of the function `fn_name`, whose body is `body`, which is
+// called with `args` and `function_values` and returns `return_type`.  The function is identified
+// by its name and where its body was declared, and the instantiation by the names and types of its
+// args and its return type, which differ for each monomorphisation of a generic function, and by
+// the function values, which are bound into the body.  The name must be a valid IR identifier so
+// different instantiations may still end up with the same one, which is caught by
+// instance_signature_matches().
+fn instance_ir_name(
+    fn_name: &str,
+    body: &TypedCodeBlock,
+    args: &[(Ident, TypedExpression)],
+    function_values: &[(Ident, TypedExpression)],
+    return_type: TypeId,
+) -> String {
+    fn decl_location(span: &Span) -> String {
+        format!("{}_{}", span.start(), span.end())
+    }
+
+    let mut name = format!("{}_{}", fn_name, decl_location(&body.whole_block_span));
+    for (arg_name, expr) in args.iter().chain(function_values) {
+        let arg = match &expr.expression {
+            TypedExpressionVariant::FunctionValue(value) => {
+                format!("fn_{}", decl_location(&value.body.whole_block_span))
+            }
+            _otherwise => look_up_type_id(expr.return_type).friendly_type_str(),
+        };
+        name.push_str(&format!("_{}_{}", arg_name.as_str(), arg));
+    }
+    name.push_str(&format!(
+        "_{}",
+        look_up_type_id(return_type).friendly_type_str()
+    ));
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+// Whether `function` has the signature which compile_fn_with_args() would give the instantiation
+// called with `args`, some of which are `reference_parameters`, and returning `return_type`.
+fn instance_signature_matches(
+    context: &mut Context,
+    function: Function,
+    args: &[(Ident, TypedExpression)],
+    reference_parameters: &[Ident],
+    return_type: TypeId,
+) -> Result<bool, String> {
+    let mut arg_types = Vec::with_capacity(args.len());
+    for (name, expr) in args {
+        let held_in_register = reference_parameters.contains(name)
+            && resolve_type(expr.return_type, &expr.span)
+                .map_err(|ty_err| format!("{:?}", ty_err))?
+                .is_held_in_register();
+        arg_types.push(if held_in_register {
+            Type::Uint(64)
+        } else {
+            convert_resolved_typeid(context, &expr.return_type, &expr.span)?
+        });
+    }
+    let return_type = convert_resolved_typeid_no_span(context, &return_type)?;
+    Ok(context.functions[function.0].return_type == return_type
+        && function
+            .args_iter(context)
+            .map(|(_, arg_val)| arg_val.get_type(context))
+            .eq(arg_types.into_iter().map(Some)))
+}
+
 // -------------------------------------------------------------------------------------------------
 
 fn compile_abi_method(
     context: &mut Context,
     module: Module,
     ast_fn_decl: TypedFunctionDeclaration,
+    storage_initializer: Option<Function>,
 ) -> Result<(), String> {
     let selector = ast_fn_decl.to_fn_selector_value().value.ok_or(format!(
         "Cannot generate selector for ABI method: {}",
@@ -381,7 +674,15 @@ fn compile_abi_method(
         })
         .collect::<Result<Vec<(String, Type)>, String>>()?;
 
-    compile_fn_with_args(context, module, ast_fn_decl, args, Some(selector))
+    compile_fn_with_args(
+        context,
+        module,
+        ast_fn_decl,
+        args,
+        Some(selector),
+        storage_initializer,
+    )?;
+    Ok(())
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -391,6 +692,34 @@ struct FnCompiler {
     function: Function,
     current_block: Block,
     symbol_map: HashMap<String, String>,
+    loops: Vec<LoopExits>,
+    // The `ref mut` args, each the address of a caller's variable of this type.
+    references: HashMap<String, TypeInfo>,
+}
+
+// The blocks which end with a `break` or `continue` in the body of a loop.  They can't be
+// terminated until the whole loop is compiled and the blocks they branch to exist.
+#[derive(Default)]
+struct LoopExits {
+    break_blocks: Vec<(Block, Option<MetadataIndex>)>,
+    continue_blocks: Vec<(Block, Option<MetadataIndex>)>,
+}
+
+impl LoopExits {
+    fn terminate(self, context: &mut Context, break_target: Block, continue_target: Block) {
+        for (block, span_md_idx) in self.break_blocks {
+            block
+                .ins(context)
+                .branch(break_target, None)
+                .add_metadatum(context, span_md_idx);
+        }
+        for (block, span_md_idx) in self.continue_blocks {
+            block
+                .ins(context)
+                .branch(continue_target, None)
+                .add_metadatum(context, span_md_idx);
+        }
+    }
 }
 
 impl FnCompiler {
@@ -405,6 +734,8 @@ impl FnCompiler {
             function,
             current_block: function.get_entry_block(context),
             symbol_map,
+            loops: Vec::new(),
+            references: HashMap::new(),
         }
     }
 
@@ -419,13 +750,14 @@ impl FnCompiler {
             .contents
             .into_iter()
             .map(|ast_node| {
+                let span_md_idx = MetadataIndex::from_span(context, &ast_node.span);
                 match ast_node.content {
                     TypedAstNodeContent::ReturnStatement(trs) => {
-                        self.compile_return_statement(context, trs.expr)
+                        self.compile_return_statement(context, trs.expr, span_md_idx)
                     }
                     TypedAstNodeContent::Declaration(td) => match td {
                         TypedDeclaration::VariableDeclaration(tvd) => {
-                            self.compile_var_decl(context, tvd)
+                            self.compile_var_decl(context, tvd, span_md_idx)
                         }
                         TypedDeclaration::ConstantDeclaration(tcd) => {
                             self.compile_const_decl(context, tcd)
@@ -438,7 +770,7 @@ impl FnCompiler {
                             Ok(Constant::get_unit(context))
                         }
                         TypedDeclaration::Reassignment(tr) => {
-                            self.compile_reassignment(context, tr)
+                            self.compile_reassignment(context, tr, span_md_idx)
                         }
                         TypedDeclaration::ImplTrait { .. } => {
                             // XXX What if I ignore the trait implementation???  Potentially since
@@ -452,6 +784,13 @@ impl FnCompiler {
                         TypedDeclaration::GenericTypeForFunctionScope { .. } => {
                             Err("gen ty for fn scope".into())
                         }
+                        TypedDeclaration::StorageDeclaration(_) => Err("storage decl".into()),
+                        TypedDeclaration::TypeAliasDeclaration(_) => {
+                            Ok(Constant::get_unit(context))
+                        }
+                        TypedDeclaration::StorageReassignment(tsr) => {
+                            self.compile_storage_reassignment(context, tsr, span_md_idx)
+                        }
                         TypedDeclaration::ErrorRecovery { .. } => Err("error recovery".into()),
                     },
                     TypedAstNodeContent::Expression(te) => {
@@ -461,7 +800,18 @@ impl FnCompiler {
                     TypedAstNodeContent::ImplicitReturnExpression(te) => {
                         self.compile_expression(context, te)
                     }
-                    TypedAstNodeContent::WhileLoop(twl) => self.compile_while_loop(context, twl),
+                    TypedAstNodeContent::WhileLoop(twl) => {
+                        self.compile_while_loop(context, twl, span_md_idx)
+                    }
+                    TypedAstNodeContent::ForLoop(tfl) => {
+                        self.compile_for_loop(context, tfl, span_md_idx)
+                    }
+                    TypedAstNodeContent::Break => {
+                        self.compile_loop_exit(context, true, span_md_idx)
+                    }
+                    TypedAstNodeContent::Continue => {
+                        self.compile_loop_exit(context, false, span_md_idx)
+                    }
                     TypedAstNodeContent::SideEffect => Err("code block side effect".into()),
                 }
             })
@@ -478,48 +828,71 @@ impl FnCompiler {
         context: &mut Context,
         ast_expr: TypedExpression,
     ) -> Result<Value, String> {
+        // Every instruction compiled directly for this expression is tagged with its span.
+        let span_md_idx = MetadataIndex::from_span(context, &ast_expr.span);
         match ast_expr.expression {
-            TypedExpressionVariant::Literal(l) => Ok(convert_literal_to_value(context, &l)),
+            TypedExpressionVariant::Literal(l) => {
+                let l = l.widen_to(&look_up_type_id(ast_expr.return_type));
+                Ok(convert_literal_to_value(context, &l))
+            }
             TypedExpressionVariant::FunctionApplication {
                 name,
                 arguments,
                 function_body,
+                inline,
+                reference_parameters,
                 ..
             } => self.compile_fn_call(
                 context,
                 name.suffix.as_str(),
                 arguments,
-                Some(function_body),
+                Some((function_body, inline)),
+                &reference_parameters,
+                span_md_idx,
             ),
             TypedExpressionVariant::LazyOperator { op, lhs, rhs, .. } => {
-                self.compile_lazy_op(context, op, *lhs, *rhs)
+                self.compile_lazy_op(context, op, *lhs, *rhs, span_md_idx)
+            }
+            TypedExpressionVariant::BuiltinOperator { op, lhs, rhs } => {
+                self.compile_builtin_op(context, op, *lhs, *rhs, span_md_idx)
             }
             TypedExpressionVariant::VariableExpression { name } => {
-                self.compile_var_expr(context, name.as_str())
+                self.compile_var_expr(context, name.as_str(), span_md_idx)
             }
             TypedExpressionVariant::Array { contents } => {
-                self.compile_array_expr(context, contents)
+                self.compile_array_expr(context, contents, span_md_idx)
             }
             TypedExpressionVariant::ArrayIndex { prefix, index } => {
-                self.compile_array_index(context, *prefix, *index)
+                self.compile_array_index(context, *prefix, *index, span_md_idx)
             }
             TypedExpressionVariant::StructExpression {
                 struct_name,
                 fields,
-            } => self.compile_struct_expr(context, struct_name.as_str(), fields),
+            } => self.compile_struct_expr(
+                context,
+                struct_name.as_str(),
+                ast_expr.return_type,
+                fields,
+                span_md_idx,
+            ),
             TypedExpressionVariant::CodeBlock(cb) => self.compile_code_block(context, cb),
             TypedExpressionVariant::FunctionParameter => Err("expr func param".into()),
+            // function values are bound into the bodies of the functions they're passed to
+            TypedExpressionVariant::FunctionValue(_)
+            | TypedExpressionVariant::FunctionValueApplication { .. } => {
+                Err("unbound function value".into())
+            }
             TypedExpressionVariant::IfExp {
                 condition,
                 then,
                 r#else,
-            } => self.compile_if(context, *condition, *then, r#else),
+            } => self.compile_if(context, *condition, *then, r#else, span_md_idx),
             TypedExpressionVariant::AsmExpression {
                 registers,
                 body,
                 returns,
                 ..
-            } => self.compile_asm_expr(context, registers, body, returns),
+            } => self.compile_asm_expr(context, registers, body, returns, span_md_idx),
             TypedExpressionVariant::StructFieldAccess {
                 prefix,
                 field_to_access,
@@ -530,30 +903,59 @@ impl FnCompiler {
                 *prefix,
                 field_to_access,
                 resolved_type_of_parent,
+                span_md_idx,
             ),
             TypedExpressionVariant::EnumInstantiation {
                 enum_decl,
                 tag,
                 contents,
                 ..
-            } => self.compile_enum_expr(context, enum_decl, tag, contents),
+            } => self.compile_enum_expr(context, enum_decl, tag, contents, span_md_idx),
             TypedExpressionVariant::EnumArgAccess {
                 //Prefix: Box<TypedExpression>,
                 //Arg_num_to_access: usize,
                 //Resolved_type_of_parent: TypeId,
                 ..
             } => Err("enum arg access".into()),
+            TypedExpressionVariant::EnumTag { prefix } => {
+                self.compile_enum_tag(context, *prefix, span_md_idx)
+            }
             TypedExpressionVariant::Tuple {
                fields
-            } => self.compile_tuple_expr(context, fields),
+            } => self.compile_tuple_expr(context, fields, span_md_idx),
             TypedExpressionVariant::TupleElemAccess {
                 prefix,
                 elem_to_access_num: idx,
                 elem_to_access_span: span,
                 resolved_type_of_parent: tuple_type,
-            } => self.compile_tuple_elem_expr( context, *prefix, tuple_type, idx, span),
+            } => self.compile_tuple_elem_expr( context, *prefix, tuple_type, idx, span, span_md_idx),
             // XXX IGNORE FOR NOW?
             TypedExpressionVariant::AbiCast { .. } => Ok(Constant::get_unit(context)),
+            TypedExpressionVariant::StorageAccess { ix, .. } => {
+                self.compile_storage_access(
+                    context,
+                    ix,
+                    &ast_expr.return_type,
+                    &ast_expr.span,
+                    span_md_idx,
+                )
+            }
+            TypedExpressionVariant::Revert { code } => {
+                self.compile_revert(context, *code, span_md_idx)
+            }
+            TypedExpressionVariant::Log { value } => {
+                self.compile_log(context, *value, span_md_idx)
+            }
+            TypedExpressionVariant::VecOperation { op, arguments } => {
+                self.compile_vec_op(
+                    context,
+                    op,
+                    arguments,
+                    ast_expr.return_type,
+                    &ast_expr.span,
+                    span_md_idx,
+                )
+            }
         }
     }
 
@@ -563,12 +965,16 @@ impl FnCompiler {
         &mut self,
         context: &mut Context,
         ast_expr: TypedExpression,
+        span_md_idx: Option<MetadataIndex>,
     ) -> Result<Value, String> {
         let ret_value = self.compile_expression(context, ast_expr)?;
         match ret_value.get_type(context) {
             None => Err("Unable to determine type for return statement expression.".into()),
             Some(ret_ty) => {
-                self.current_block.ins(context).ret(ret_value, ret_ty);
+                self.current_block
+                    .ins(context)
+                    .ret(ret_value, ret_ty)
+                    .add_metadatum(context, span_md_idx);
                 // RET is a terminator so we must create a new block here.  If anything is added to
                 // it then it'll almost certainly be dead code.
                 self.current_block = self.function.create_block(context, None);
@@ -579,12 +985,546 @@ impl FnCompiler {
 
     // ---------------------------------------------------------------------------------------------
 
+    fn compile_revert(
+        &mut self,
+        context: &mut Context,
+        ast_code: TypedExpression,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Result<Value, String> {
+        let code_val = self.compile_expression(context, ast_code)?;
+        let ident = |name: &str| {
+            Ident::new(Span {
+                span: pest::Span::new(name.into(), 0, name.len()).unwrap(),
+                path: None,
+            })
+        };
+        self.current_block
+            .ins(context)
+            .asm_block(
+                vec![AsmArg {
+                    name: ident("code"),
+                    initializer: Some(code_val),
+                }],
+                vec![AsmInstruction {
+                    name: ident("rvrt"),
+                    args: vec![ident("code")],
+                    immediate: None,
+                }],
+                None,
+            )
+            .add_metadatum(context, span_md_idx);
+        // Nothing follows a revert, but the block still needs a terminator.  As with a return,
+        // anything which is compiled after it is dead code and goes in a new block.
+        let dead_block = self.function.create_block(context, None);
+        self.current_block
+            .ins(context)
+            .branch(dead_block, None)
+            .add_metadatum(context, span_md_idx);
+        self.current_block = dead_block;
+        Ok(Constant::get_unit(context))
+    }
+
+    // ---------------------------------------------------------------------------------------------
+
+    fn compile_log(
+        &mut self,
+        context: &mut Context,
+        ast_value: TypedExpression,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Result<Value, String> {
+        let ty = resolve_type(ast_value.return_type, &ast_value.span)
+            .map_err(|ty_err| format!("{:?}", ty_err))?;
+        let log_id = ast_value.return_type.generate_logged_type().log_id;
+        let size_in_bytes = ty
+            .size_in_words(&ast_value.span)
+            .map_err(|err| format!("{:?}", err))?
+            * 8;
+        let value = self.compile_expression(context, ast_value)?;
+        let ident = |name: &str| {
+            Ident::new(Span {
+                span: pest::Span::new(name.into(), 0, name.len()).unwrap(),
+                path: None,
+            })
+        };
+        let mut args = vec![
+            AsmArg {
+                name: ident("value"),
+                initializer: Some(value),
+            },
+            AsmArg {
+                name: ident("log_id"),
+                initializer: Some(Constant::get_uint(context, 64, log_id)),
+            },
+        ];
+        // As in the other backend, a word sized value is logged itself and anything else is logged
+        // as the memory it's laid out in.
+        let instructions = if ty.is_copy_type() {
+            vec![AsmInstruction {
+                name: ident("log"),
+                args: vec![
+                    ident("value"),
+                    ident("log_id"),
+                    ident("zero"),
+                    ident("zero"),
+                ],
+                immediate: None,
+            }]
+        } else {
+            args.push(AsmArg {
+                name: ident("size"),
+                initializer: Some(Constant::get_uint(context, 64, size_in_bytes)),
+            });
+            vec![AsmInstruction {
+                name: ident("logd"),
+                args: vec![
+                    ident("zero"),
+                    ident("log_id"),
+                    ident("value"),
+                    ident("size"),
+                ],
+                immediate: None,
+            }]
+        };
+        self.current_block
+            .ins(context)
+            .asm_block(args, instructions, None)
+            .add_metadatum(context, span_md_idx);
+        Ok(Constant::get_unit(context))
+    }
+
+    // ---------------------------------------------------------------------------------------------
+
+    // A vector is a pointer to a header on the heap of three words: the address of its elements,
+    // its capacity and its length.  Elements are laid out as in the other backend.
+    fn compile_vec_op(
+        &mut self,
+        context: &mut Context,
+        op: VecOp,
+        ast_args: Vec<TypedExpression>,
+        return_type: TypeId,
+        span: &Span,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Result<Value, String> {
+        let vec_type = match op {
+            VecOp::New | VecOp::WithCapacity => return_type,
+            _ => ast_args[0].return_type,
+        };
+        let elem_type_id = match look_up_type_id(vec_type) {
+            TypeInfo::Vector(elem_type_id) => elem_type_id,
+            _ => return Err("vec operation on a non-vec".into()),
+        };
+        let elem_type = look_up_type_id(elem_type_id);
+        // The element type of an empty vector may never be known, which is fine as long as
+        // nothing needs its size.
+        let elem_size_in_bytes = match op {
+            VecOp::New | VecOp::Len | VecOp::Capacity => 0,
+            _ => {
+                elem_type
+                    .size_in_words(span)
+                    .map_err(|err| format!("{:?}", err))?
+                    * 8
+            }
+        };
+        let mut args = Vec::with_capacity(ast_args.len());
+        for ast_arg in ast_args {
+            args.push(self.compile_expression(context, ast_arg)?);
+        }
+        let elem_size = Constant::get_uint(context, 64, elem_size_in_bytes);
+        let header_size = Constant::get_uint(context, 64, 24);
+        let value = match op {
+            VecOp::New => self.compile_asm_body(
+                context,
+                vec![("size", header_size)],
+                &[
+                    "aloc size",
+                    "addi vec hp i1",
+                    "sw vec zero i0",
+                    "sw vec zero i1",
+                    "sw vec zero i2",
+                ],
+                Some("vec"),
+                span_md_idx,
+            ),
+            VecOp::WithCapacity => self.compile_asm_body(
+                context,
+                vec![
+                    ("size", header_size),
+                    ("cap", args[0]),
+                    ("elem_size", elem_size),
+                ],
+                &[
+                    "aloc size",
+                    "addi vec hp i1",
+                    "mul bytes cap elem_size",
+                    "aloc bytes",
+                    "addi elems hp i1",
+                    "sw vec elems i0",
+                    "sw vec cap i1",
+                    "sw vec zero i2",
+                ],
+                Some("vec"),
+                span_md_idx,
+            ),
+            VecOp::Len => self.compile_asm_body(
+                context,
+                vec![("vec", args[0])],
+                &["lw len vec i2"],
+                Some("len"),
+                span_md_idx,
+            ),
+            VecOp::Capacity => self.compile_asm_body(
+                context,
+                vec![("vec", args[0])],
+                &["lw cap vec i1"],
+                Some("cap"),
+                span_md_idx,
+            ),
+            VecOp::Push => {
+                self.compile_vec_push(
+                    context,
+                    args[0],
+                    args[1],
+                    &elem_type,
+                    elem_size,
+                    elem_size_in_bytes,
+                    span_md_idx,
+                );
+                Constant::get_unit(context)
+            }
+            VecOp::Get => {
+                let elem_addr =
+                    self.compile_vec_elem_addr(context, args[0], args[1], elem_size, span_md_idx);
+                if elem_type.is_held_in_register() {
+                    if elem_size_in_bytes == 0 {
+                        Constant::get_unit(context)
+                    } else {
+                        self.compile_asm_body(
+                            context,
+                            vec![("addr", elem_addr)],
+                            &["lw elem addr i0"],
+                            Some("elem"),
+                            span_md_idx,
+                        )
+                    }
+                } else {
+                    // The element is copied to a local, so that setting an element later doesn't
+                    // change the value returned here.
+                    let ir_type = convert_resolved_typeid(context, &elem_type_id, span)?;
+                    let elem_ptr = self.function.new_unique_local_ptr(
+                        context,
+                        "vec_elem".into(),
+                        ir_type,
+                        false,
+                        None,
+                    );
+                    if elem_size_in_bytes != 0 {
+                        let dst = self
+                            .current_block
+                            .ins(context)
+                            .get_ptr(elem_ptr)
+                            .add_metadatum(context, span_md_idx);
+                        self.compile_asm_body(
+                            context,
+                            vec![("dst", dst), ("addr", elem_addr), ("elem_size", elem_size)],
+                            &["mcp dst addr elem_size"],
+                            None,
+                            span_md_idx,
+                        );
+                    }
+                    if elem_ptr.is_struct_ptr(context) {
+                        self.current_block.ins(context).get_ptr(elem_ptr)
+                    } else {
+                        self.current_block.ins(context).load(elem_ptr)
+                    }
+                    .add_metadatum(context, span_md_idx)
+                }
+            }
+            VecOp::Set => {
+                let elem_addr =
+                    self.compile_vec_elem_addr(context, args[0], args[1], elem_size, span_md_idx);
+                self.compile_vec_elem_store(
+                    context,
+                    elem_addr,
+                    args[2],
+                    &elem_type,
+                    elem_size,
+                    elem_size_in_bytes,
+                    span_md_idx,
+                );
+                Constant::get_unit(context)
+            }
+        };
+        Ok(value)
+    }
+
+    // If the vector is at capacity then its elements are first copied to a new allocation of twice
+    // the capacity, or of four elements if it has none.  The old allocation is never reclaimed.
+    #[allow(clippy::too_many_arguments)]
+    fn compile_vec_push(
+        &mut self,
+        context: &mut Context,
+        vec: Value,
+        elem: Value,
+        elem_type: &TypeInfo,
+        elem_size: Value,
+        elem_size_in_bytes: u64,
+        span_md_idx: Option<MetadataIndex>,
+    ) {
+        let grow_block = self.function.create_block(context, None);
+        let copy_block = self.function.create_block(context, None);
+        let store_block = self.function.create_block(context, None);
+
+        let full = self.compile_asm_body(
+            context,
+            vec![("vec", vec)],
+            &["lw len vec i2", "lw cap vec i1", "eq full len cap"],
+            Some("full"),
+            span_md_idx,
+        );
+        self.current_block
+            .ins(context)
+            .conditional_branch(full, grow_block, store_block, None)
+            .add_metadatum(context, span_md_idx);
+
+        self.current_block = grow_block;
+        let old_elems = self.compile_asm_body(
+            context,
+            vec![("vec", vec), ("elem_size", elem_size)],
+            &[
+                "lw old vec i0",
+                "lw cap vec i1",
+                "muli new_cap cap i2",
+                "eq empty cap zero",
+                "muli pad empty i4",
+                "add new_cap new_cap pad",
+                "mul bytes new_cap elem_size",
+                "aloc bytes",
+                "addi elems hp i1",
+                "sw vec elems i0",
+                "sw vec new_cap i1",
+            ],
+            Some("old"),
+            span_md_idx,
+        );
+        // MCP doesn't allow copying nothing.
+        let any_to_copy = self.compile_asm_body(
+            context,
+            vec![("vec", vec), ("elem_size", elem_size)],
+            &[
+                "lw len vec i2",
+                "mul used len elem_size",
+                "gt any used zero",
+            ],
+            Some("any"),
+            span_md_idx,
+        );
+        self.current_block
+            .ins(context)
+            .conditional_branch(any_to_copy, copy_block, store_block, None)
+            .add_metadatum(context, span_md_idx);
+
+        self.current_block = copy_block;
+        self.compile_asm_body(
+            context,
+            vec![("vec", vec), ("old", old_elems), ("elem_size", elem_size)],
+            &[
+                "lw len vec i2",
+                "mul used len elem_size",
+                "lw elems vec i0",
+                "mcp elems old used",
+            ],
+            None,
+            span_md_idx,
+        );
+        self.current_block
+            .ins(context)
+            .branch(store_block, None)
+            .add_metadatum(context, span_md_idx);
+
+        self.current_block = store_block;
+        let len = self.compile_asm_body(
+            context,
+            vec![("vec", vec)],
+            &["lw len vec i2"],
+            Some("len"),
+            span_md_idx,
+        );
+        let elem_addr =
+            self.compile_vec_elem_addr_unchecked(context, vec, len, elem_size, span_md_idx);
+        self.compile_vec_elem_store(
+            context,
+            elem_addr,
+            elem,
+            elem_type,
+            elem_size,
+            elem_size_in_bytes,
+            span_md_idx,
+        );
+        self.compile_asm_body(
+            context,
+            vec![("vec", vec), ("len", len)],
+            &["addi new_len len i1", "sw vec new_len i2"],
+            None,
+            span_md_idx,
+        );
+    }
+
+    // Reverts, with the same code as an out of bounds array index in the other backend, unless
+    // the index is less than the length of the vector.
+    fn compile_vec_elem_addr(
+        &mut self,
+        context: &mut Context,
+        vec: Value,
+        index: Value,
+        elem_size: Value,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Value {
+        let out_of_bounds_block = self.function.create_block(context, None);
+        let in_bounds_block = self.function.create_block(context, None);
+        let in_bounds = self.compile_asm_body(
+            context,
+            vec![("vec", vec), ("index", index)],
+            &["lw len vec i2", "lt in_bounds index len"],
+            Some("in_bounds"),
+            span_md_idx,
+        );
+        self.current_block
+            .ins(context)
+            .conditional_branch(in_bounds, in_bounds_block, out_of_bounds_block, None)
+            .add_metadatum(context, span_md_idx);
+
+        self.current_block = out_of_bounds_block;
+        self.compile_asm_body(context, vec![], &["rvrt one"], None, span_md_idx);
+        self.current_block
+            .ins(context)
+            .branch(in_bounds_block, None)
+            .add_metadatum(context, span_md_idx);
+
+        self.current_block = in_bounds_block;
+        self.compile_vec_elem_addr_unchecked(context, vec, index, elem_size, span_md_idx)
+    }
+
+    fn compile_vec_elem_addr_unchecked(
+        &mut self,
+        context: &mut Context,
+        vec: Value,
+        index: Value,
+        elem_size: Value,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Value {
+        self.compile_asm_body(
+            context,
+            vec![("vec", vec), ("index", index), ("elem_size", elem_size)],
+            &[
+                "lw elems vec i0",
+                "mul offs index elem_size",
+                "add addr elems offs",
+            ],
+            Some("addr"),
+            span_md_idx,
+        )
+    }
+
+    // An element held in a register is stored as a word, and otherwise its value is the address of
+    // the memory it's laid out in, which is copied.
+    #[allow(clippy::too_many_arguments)]
+    fn compile_vec_elem_store(
+        &mut self,
+        context: &mut Context,
+        elem_addr: Value,
+        elem: Value,
+        elem_type: &TypeInfo,
+        elem_size: Value,
+        elem_size_in_bytes: u64,
+        span_md_idx: Option<MetadataIndex>,
+    ) {
+        if elem_size_in_bytes == 0 {
+            return;
+        }
+        let body = if elem_type.is_held_in_register() {
+            "sw addr elem i0"
+        } else {
+            "mcp addr elem elem_size"
+        };
+        self.compile_asm_body(
+            context,
+            vec![
+                ("addr", elem_addr),
+                ("elem", elem),
+                ("elem_size", elem_size),
+            ],
+            &[body],
+            None,
+            span_md_idx,
+        );
+    }
+
+    // An asm block with a body written as in Sway, e.g., `lw len vec i2`.  Each of `args` is
+    // initialised with its value and any other register in the body is declared without one.
+    // The body mustn't write to `args`, as their registers are those of the values themselves.
+    fn compile_asm_body(
+        &mut self,
+        context: &mut Context,
+        args: Vec<(&str, Value)>,
+        body: &[&str],
+        returns: Option<&str>,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Value {
+        let ident = |name: &str| {
+            Ident::new(Span {
+                span: pest::Span::new(name.into(), 0, name.len()).unwrap(),
+                path: None,
+            })
+        };
+        let mut asm_args = args
+            .into_iter()
+            .map(|(name, value)| AsmArg {
+                name: ident(name),
+                initializer: Some(value),
+            })
+            .collect::<Vec<_>>();
+        let mut instructions = Vec::with_capacity(body.len());
+        for op in body {
+            let mut words = op.split_whitespace();
+            let name = ident(words.next().expect("asm op has a name"));
+            let mut op_args = Vec::new();
+            let mut immediate = None;
+            for word in words {
+                if word.starts_with('i') && word[1..].parse::<u64>().is_ok() {
+                    immediate = Some(ident(word));
+                    continue;
+                }
+                if ConstantRegister::parse_register_name(word).is_none()
+                    && !asm_args.iter().any(|arg| arg.name.as_str() == word)
+                {
+                    asm_args.push(AsmArg {
+                        name: ident(word),
+                        initializer: None,
+                    });
+                }
+                op_args.push(ident(word));
+            }
+            instructions.push(AsmInstruction {
+                name,
+                args: op_args,
+                immediate,
+            });
+        }
+        self.current_block
+            .ins(context)
+            .asm_block(asm_args, instructions, returns.map(ident))
+            .add_metadatum(context, span_md_idx)
+    }
+
+    // ---------------------------------------------------------------------------------------------
+
     fn compile_lazy_op(
         &mut self,
         context: &mut Context,
         ast_op: LazyOp,
         ast_lhs: TypedExpression,
         ast_rhs: TypedExpression,
+        span_md_idx: Option<MetadataIndex>,
     ) -> Result<Value, String> {
         // Short-circuit: if LHS is true for AND we still must eval the RHS block; for OR we can
         // skip the RHS block, and vice-versa.
@@ -599,13 +1539,15 @@ impl FnCompiler {
             LazyOp::Or => {
                 cond_builder.conditional_branch(lhs_val, final_block, rhs_block, Some(lhs_val))
             }
-        };
+        }
+        .add_metadatum(context, span_md_idx);
 
         self.current_block = rhs_block;
         let rhs_val = self.compile_expression(context, ast_rhs)?;
         self.current_block
             .ins(context)
-            .branch(final_block, Some(rhs_val));
+            .branch(final_block, Some(rhs_val))
+            .add_metadatum(context, span_md_idx);
 
         self.current_block = final_block;
         Ok(final_block.get_phi(context))
@@ -613,12 +1555,76 @@ impl FnCompiler {
 
     // ---------------------------------------------------------------------------------------------
 
+    fn compile_builtin_op(
+        &mut self,
+        context: &mut Context,
+        ast_op: OpVariant,
+        ast_lhs: TypedExpression,
+        ast_rhs: TypedExpression,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Result<Value, String> {
+        let is_signed = matches!(
+            look_up_type_id(ast_lhs.return_type),
+            TypeInfo::SignedInteger(_)
+        );
+        let lhs_val = self.compile_expression(context, ast_lhs)?;
+        let rhs_val = self.compile_expression(context, ast_rhs)?;
+        let builder = self.current_block.ins(context);
+        let val = match (ast_op, is_signed) {
+            (OpVariant::Add, _) => builder.binary_op(BinaryOpKind::Add, lhs_val, rhs_val),
+            (OpVariant::Subtract, _) => builder.binary_op(BinaryOpKind::Sub, lhs_val, rhs_val),
+            (OpVariant::Multiply, _) => builder.binary_op(BinaryOpKind::Mul, lhs_val, rhs_val),
+            (OpVariant::Divide, false) => builder.binary_op(BinaryOpKind::Div, lhs_val, rhs_val),
+            (OpVariant::Divide, true) => builder.binary_op(BinaryOpKind::SDiv, lhs_val, rhs_val),
+            (OpVariant::Modulo, false) => builder.binary_op(BinaryOpKind::Mod, lhs_val, rhs_val),
+            (OpVariant::Modulo, true) => builder.binary_op(BinaryOpKind::SMod, lhs_val, rhs_val),
+            (OpVariant::BinaryAnd, _) => builder.binary_op(BinaryOpKind::And, lhs_val, rhs_val),
+            (OpVariant::BinaryOr, _) => builder.binary_op(BinaryOpKind::Or, lhs_val, rhs_val),
+            (OpVariant::Xor, _) => builder.binary_op(BinaryOpKind::Xor, lhs_val, rhs_val),
+            (OpVariant::ShiftLeft, _) => builder.binary_op(BinaryOpKind::Shl, lhs_val, rhs_val),
+            (OpVariant::ShiftRight, false) => {
+                builder.binary_op(BinaryOpKind::Shr, lhs_val, rhs_val)
+            }
+            (OpVariant::ShiftRight, true) => builder.binary_op(BinaryOpKind::Sar, lhs_val, rhs_val),
+            (OpVariant::Equals, _) => builder.cmp(Predicate::Equal, lhs_val, rhs_val),
+            (OpVariant::NotEquals, _) => builder.cmp(Predicate::NotEqual, lhs_val, rhs_val),
+            (OpVariant::LessThan, false) => builder.cmp(Predicate::LessThan, lhs_val, rhs_val),
+            (OpVariant::LessThan, true) => builder.cmp(Predicate::SignedLessThan, lhs_val, rhs_val),
+            (OpVariant::GreaterThan, false) => {
+                builder.cmp(Predicate::GreaterThan, lhs_val, rhs_val)
+            }
+            (OpVariant::GreaterThan, true) => {
+                builder.cmp(Predicate::SignedGreaterThan, lhs_val, rhs_val)
+            }
+            (OpVariant::LessThanOrEqualTo, false) => {
+                builder.cmp(Predicate::LessThanEqual, lhs_val, rhs_val)
+            }
+            (OpVariant::LessThanOrEqualTo, true) => {
+                builder.cmp(Predicate::SignedLessThanEqual, lhs_val, rhs_val)
+            }
+            (OpVariant::GreaterThanOrEqualTo, false) => {
+                builder.cmp(Predicate::GreaterThanEqual, lhs_val, rhs_val)
+            }
+            (OpVariant::GreaterThanOrEqualTo, true) => {
+                builder.cmp(Predicate::SignedGreaterThanEqual, lhs_val, rhs_val)
+            }
+            (OpVariant::And | OpVariant::Or, _) => {
+                return Err("Lazy operators are never builtin operators.".into())
+            }
+        };
+        Ok(val.add_metadatum(context, span_md_idx))
+    }
+
+    // ---------------------------------------------------------------------------------------------
+
     fn compile_fn_call(
         &mut self,
         context: &mut Context,
         ast_name: &str,
         ast_args: Vec<(Ident, TypedExpression)>,
-        callee_body: Option<TypedCodeBlock>,
+        callee_body: Option<(TypedCodeBlock, Option<crate::parse_tree::Inline>)>,
+        reference_parameters: &[Ident],
+        span_md_idx: Option<MetadataIndex>,
     ) -> Result<Value, String> {
         // XXX To do: Calling into other modules, managing namespaces.
         //
@@ -628,31 +1634,85 @@ impl FnCompiler {
         // from Forc and when the parser builds the AST (or is it during type checking?) these
         // function bodies are embedded.
         //
-        // We're going to build little single-use instantiations of the callee and then call them.
-        // For now if they're called in multiple places they'll be redundantly recreated, but also
-        // at present we are still inlining everything so it actually makes little difference.
+        // We're going to build instantiations of the callee and then call them.  Each is named
+        // after the callee's declaration and the types and function values it is called with, see
+        // instance_ir_name(), so calls which share them share the instantiation.  Small ones will
+        // be inlined anyway.
         //
-        // Eventually we need to Do It Properly and inline only when necessary, and compile the
-        // standard library to an actual module.
-
+        // Eventually we need to Do It Properly and compile the standard library to an actual
+        // module.
+
+        // Function values have been bound into the callee body, and aren't passed at run time.
+        let (function_values, ast_args): (Vec<_>, Vec<_>) =
+            ast_args.into_iter().partition(|(_, expr)| {
+                matches!(expr.expression, TypedExpressionVariant::FunctionValue(_))
+            });
+
+        // A method compiled from an impl has the type of `self` in its name, see compile_impl().
+        // Without a body this is a call to a function by its IR name.
+        let ir_name = match (ast_args.first(), &callee_body) {
+            (Some((param_name, self_expr)), Some((body, _))) if param_name.as_str() == "self" => {
+                method_ir_name(&look_up_type_id(self_expr.return_type), ast_name, body)
+            }
+            _ => ast_name.to_owned(),
+        };
         match context
             .module_iter()
             .flat_map(|module| module.function_iter(context))
-            .find(|function| function.get_name(context) == ast_name)
+            .find(|function| function.get_name(context) == ir_name)
         {
             Some(callee) => {
-                let args = ast_args
-                    .into_iter()
-                    .map(|(_, expr)| self.compile_expression(context, expr))
-                    .collect::<Result<Vec<Value>, String>>()?;
-                Ok(self.current_block.ins(context).call(callee, &args))
+                self.compile_call(context, callee, ast_args, reference_parameters, span_md_idx)
             }
 
             None if callee_body.is_none() => Err(format!("function not found: {}", ast_name)),
 
             None => {
-                // Firstly create the single-use callee by fudging an AST declaration.
-                let callee_name = context.get_unique_name();
+                let (callee_body, inline) = callee_body.unwrap();
+
+                // We're going to have to reverse engineer the return type.
+                let return_type =
+                    Self::get_codeblock_return_type(&callee_body).unwrap_or_else(||
+                    // This code block is missing a return or implicit return.  The only time I've
+                    // seen it happen (whether it's 'valid' or not) is in std::storage::store(),
+                    // which has a single asm block which also returns nothing.  In this case, it
+                    // actually is Unit.
+                    insert_type(TypeInfo::Tuple(Vec::new())));
+
+                let callee_name = instance_ir_name(
+                    ast_name,
+                    &callee_body,
+                    &ast_args,
+                    &function_values,
+                    return_type,
+                );
+                if let Some(instance) = context
+                    .module_iter()
+                    .flat_map(|module| module.function_iter(context))
+                    .find(|function| function.get_name(context) == callee_name)
+                {
+                    if !instance_signature_matches(
+                        context,
+                        instance,
+                        &ast_args,
+                        reference_parameters,
+                        return_type,
+                    )? {
+                        return Err(format!(
+                            "Function instance {} was created for a different signature.",
+                            callee_name
+                        ));
+                    }
+                    return self.compile_call(
+                        context,
+                        instance,
+                        ast_args,
+                        reference_parameters,
+                        span_md_idx,
+                    );
+                }
+
+                // Otherwise create the callee by fudging an AST declaration.
                 let callee_name_len = callee_name.len();
                 let callee_ident = Ident::new(crate::span::Span {
                     span: pest::Span::new(
@@ -668,24 +1728,14 @@ impl FnCompiler {
                     .iter()
                     .map(|(name, expr)| TypedFunctionParameter {
                         name: name.clone(),
-                        r#type: expr.return_type,
-                        type_span: crate::span::Span {
-                            span: pest::Span::new(" ".into(), 0, 0).unwrap(),
-                            path: None,
-                        },
-                    })
-                    .collect();
-
-                let callee_body = callee_body.unwrap();
-
-                // We're going to have to reverse engineer the return type.
-                let return_type =
-                    Self::get_codeblock_return_type(&callee_body).unwrap_or_else(||
-                    // This code block is missing a return or implicit return.  The only time I've
-                    // seen it happen (whether it's 'valid' or not) is in std::storage::store(),
-                    // which has a single asm block which also returns nothing.  In this case, it
-                    // actually is Unit.
-                    insert_type(TypeInfo::Tuple(Vec::new())));
+                        r#type: expr.return_type,
+                        type_span: crate::span::Span {
+                            span: pest::Span::new(" ".into(), 0, 0).unwrap(),
+                            path: None,
+                        },
+                        is_reference: reference_parameters.contains(name),
+                    })
+                    .collect();
 
                 let callee_fn_decl = TypedFunctionDeclaration {
                     name: callee_ident,
@@ -704,16 +1754,44 @@ impl FnCompiler {
                     visibility: Visibility::Private,
                     is_contract_call: false,
                     purity: Default::default(),
+                    attributes: Vec::new(),
                 };
 
-                compile_function(context, self.module, callee_fn_decl)?;
+                let callee = compile_function(context, self.module, callee_fn_decl)?
+                    .ok_or_else(|| format!("Function instance {} wasn't created.", callee_name))?;
+                callee.set_inline(context, convert_inline(inline));
 
-                // Then recursively create a call to it.
-                self.compile_fn_call(context, &callee_name, ast_args, None)
+                // Then create a call to it.
+                self.compile_call(context, callee, ast_args, reference_parameters, span_md_idx)
             }
         }
     }
 
+    fn compile_call(
+        &mut self,
+        context: &mut Context,
+        callee: Function,
+        ast_args: Vec<(Ident, TypedExpression)>,
+        reference_parameters: &[Ident],
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Result<Value, String> {
+        let args = ast_args
+            .into_iter()
+            .map(|(name, expr)| {
+                if reference_parameters.contains(&name) {
+                    self.compile_reference(context, expr, span_md_idx)
+                } else {
+                    self.compile_expression(context, expr)
+                }
+            })
+            .collect::<Result<Vec<Value>, String>>()?;
+        Ok(self
+            .current_block
+            .ins(context)
+            .call(callee, &args)
+            .add_metadatum(context, span_md_idx))
+    }
+
     fn get_codeblock_return_type(codeblock: &TypedCodeBlock) -> Option<TypeId> {
         if codeblock.contents.is_empty() {
             Some(insert_type(TypeInfo::Tuple(Vec::new())))
@@ -737,6 +1815,7 @@ impl FnCompiler {
         ast_condition: TypedExpression,
         ast_then: TypedExpression,
         ast_else: Option<Box<TypedExpression>>,
+        span_md_idx: Option<MetadataIndex>,
     ) -> Result<Value, String> {
         // Compile the condition expression in the entry block.  Then save the current block so we
         // can jump to the true and false blocks after we've created them.
@@ -769,20 +1848,20 @@ impl FnCompiler {
         };
         let false_block_end = self.current_block;
 
-        entry_block.ins(context).conditional_branch(
-            cond_value,
-            true_block_begin,
-            false_block_begin,
-            None,
-        );
+        entry_block
+            .ins(context)
+            .conditional_branch(cond_value, true_block_begin, false_block_begin, None)
+            .add_metadatum(context, span_md_idx);
 
         let merge_block = self.function.create_block(context, None);
         true_block_end
             .ins(context)
-            .branch(merge_block, Some(true_value));
+            .branch(merge_block, Some(true_value))
+            .add_metadatum(context, span_md_idx);
         false_block_end
             .ins(context)
-            .branch(merge_block, Some(false_value));
+            .branch(merge_block, Some(false_value))
+            .add_metadatum(context, span_md_idx);
 
         self.current_block = merge_block;
         Ok(merge_block.get_phi(context))
@@ -794,6 +1873,7 @@ impl FnCompiler {
         &mut self,
         context: &mut Context,
         ast_while_loop: TypedWhileLoop,
+        span_md_idx: Option<MetadataIndex>,
     ) -> Result<Value, String> {
         // We're dancing around a bit here to make the blocks sit in the right order.  Ideally we
         // have the cond block, followed by the body block which may contain other blocks, and the
@@ -801,15 +1881,27 @@ impl FnCompiler {
 
         // Jump to the while cond block.
         let cond_block = self.function.create_block(context, Some("while".into()));
-        self.current_block.ins(context).branch(cond_block, None);
+        self.current_block
+            .ins(context)
+            .branch(cond_block, None)
+            .add_metadatum(context, span_md_idx);
+
+        // Compile the condition before the body, which may shadow the variables it refers to.
+        // The condition may itself create blocks, so the branch on it goes in whichever is last.
+        self.current_block = cond_block;
+        let cond_value = self.compile_expression(context, ast_while_loop.condition)?;
+        let cond_end_block = self.current_block;
 
         // Fill in the body block now, jump unconditionally to the cond block at its end.
         let body_block = self
             .function
             .create_block(context, Some("while_body".into()));
         self.current_block = body_block;
-        self.compile_code_block(context, ast_while_loop.body)?;
-        self.current_block.ins(context).branch(cond_block, None);
+        let loop_exits = self.compile_loop_body(context, ast_while_loop.body)?;
+        self.current_block
+            .ins(context)
+            .branch(cond_block, None)
+            .add_metadatum(context, span_md_idx);
 
         // Create the final block after we're finished with the body.
         let final_block = self
@@ -817,22 +1909,231 @@ impl FnCompiler {
             .create_block(context, Some("end_while".into()));
 
         // Add the conditional which jumps into the body or out to the final block.
-        self.current_block = cond_block;
-        let cond_value = self.compile_expression(context, ast_while_loop.condition)?;
-        self.current_block.ins(context).conditional_branch(
-            cond_value,
-            body_block,
-            final_block,
+        cond_end_block
+            .ins(context)
+            .conditional_branch(cond_value, body_block, final_block, None)
+            .add_metadatum(context, span_md_idx);
+        loop_exits.terminate(context, final_block, cond_block);
+
+        self.current_block = final_block;
+        Ok(Constant::get_unit(context))
+    }
+
+    // ---------------------------------------------------------------------------------------------
+
+    fn compile_for_loop(
+        &mut self,
+        context: &mut Context,
+        ast_for_loop: TypedForLoop,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Result<Value, String> {
+        let TypedForLoop {
+            variable,
+            variable_type,
+            iterable,
+            body,
+        } = ast_for_loop;
+
+        // The iterable is evaluated once before the loop, and an index is counted up from the
+        // start of the range, or from zero for an array, to its end.
+        let (array, start_val, end_val, index_type) = match iterable {
+            TypedForLoopIterable::Array(array_expr) => {
+                let array_val = self.compile_expression(context, *array_expr)?;
+                let aggregate = get_array_aggregate(context, array_val)?;
+                let (_, count) = context.aggregates[aggregate.0].array_type();
+                let count = *count;
+                (
+                    Some((array_val, aggregate)),
+                    Constant::get_uint(context, 64, 0),
+                    Constant::get_uint(context, 64, count),
+                    Type::Uint(64),
+                )
+            }
+            TypedForLoopIterable::Range { start, end } => {
+                let index_type = convert_resolved_typeid(context, &variable_type, variable.span())?;
+                let start_val = self.compile_expression(context, *start)?;
+                let end_val = self.compile_expression(context, *end)?;
+                (None, start_val, end_val, index_type)
+            }
+        };
+        let index_ptr = self.function.new_unique_local_ptr(
+            context,
+            "__for_index".into(),
+            index_type,
+            true,
             None,
         );
+        self.current_block
+            .ins(context)
+            .store(index_ptr, start_val)
+            .add_metadatum(context, span_md_idx);
+
+        // The loop variable is a local like any other, and shadows any variable of the same name.
+        let variable_type = convert_resolved_typeid(context, &variable_type, variable.span())?;
+        let local_name = self.new_local_name(variable.as_str());
+        let variable_ptr =
+            self.function
+                .new_local_ptr(context, local_name, variable_type, false, None)?;
+
+        // Jump to the for cond block, which compares the index to the end.
+        let cond_block = self.function.create_block(context, Some("for".into()));
+        self.current_block
+            .ins(context)
+            .branch(cond_block, None)
+            .add_metadatum(context, span_md_idx);
+        self.current_block = cond_block;
+        let index_val = self
+            .current_block
+            .ins(context)
+            .load(index_ptr)
+            .add_metadatum(context, span_md_idx);
+        let cond_value = self.compile_binary_asm_op(context, "lt", index_val, end_val, span_md_idx);
+
+        // The body block starts by setting the loop variable to the index or the element at it.
+        let body_block = self.function.create_block(context, Some("for_body".into()));
+        self.current_block = body_block;
+        let index_val = self
+            .current_block
+            .ins(context)
+            .load(index_ptr)
+            .add_metadatum(context, span_md_idx);
+        let variable_val = match array {
+            Some((array_val, aggregate)) => self
+                .current_block
+                .ins(context)
+                .extract_element(array_val, aggregate, index_val)
+                .add_metadatum(context, span_md_idx),
+            None => index_val,
+        };
+        self.current_block
+            .ins(context)
+            .store(variable_ptr, variable_val)
+            .add_metadatum(context, span_md_idx);
+        let loop_exits = self.compile_loop_body(context, body)?;
+
+        // The step block, which `continue` also jumps to, increments the index.
+        let step_block = self.function.create_block(context, Some("for_step".into()));
+        self.current_block
+            .ins(context)
+            .branch(step_block, None)
+            .add_metadatum(context, span_md_idx);
+        self.current_block = step_block;
+        let index_val = self
+            .current_block
+            .ins(context)
+            .load(index_ptr)
+            .add_metadatum(context, span_md_idx);
+        let one = Constant::get_uint(context, 64, 1);
+        let next_index_val =
+            self.compile_binary_asm_op(context, "add", index_val, one, span_md_idx);
+        self.current_block
+            .ins(context)
+            .store(index_ptr, next_index_val)
+            .add_metadatum(context, span_md_idx);
+        self.current_block
+            .ins(context)
+            .branch(cond_block, None)
+            .add_metadatum(context, span_md_idx);
+
+        let final_block = self.function.create_block(context, Some("end_for".into()));
+        cond_block
+            .ins(context)
+            .conditional_branch(cond_value, body_block, final_block, None)
+            .add_metadatum(context, span_md_idx);
+        loop_exits.terminate(context, final_block, step_block);
 
         self.current_block = final_block;
         Ok(Constant::get_unit(context))
     }
 
+    // Compile the body of a loop, returning the blocks in it which end with `break` or `continue`.
+    fn compile_loop_body(
+        &mut self,
+        context: &mut Context,
+        body: TypedCodeBlock,
+    ) -> Result<LoopExits, String> {
+        self.loops.push(LoopExits::default());
+        let body_result = self.compile_code_block(context, body);
+        let loop_exits = self.loops.pop().unwrap();
+        body_result.map(|_| loop_exits)
+    }
+
+    // ---------------------------------------------------------------------------------------------
+
+    fn compile_loop_exit(
+        &mut self,
+        context: &mut Context,
+        is_break: bool,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Result<Value, String> {
+        let loop_exits = self
+            .loops
+            .last_mut()
+            .ok_or("Found `break` or `continue` outside of a loop.")?;
+        let exit_block = (self.current_block, span_md_idx);
+        if is_break {
+            loop_exits.break_blocks.push(exit_block);
+        } else {
+            loop_exits.continue_blocks.push(exit_block);
+        }
+
+        // Anything which follows is unreachable, but it still needs a block to go in.
+        self.current_block = self.function.create_block(context, None);
+        Ok(Constant::get_unit(context))
+    }
+
+    // ---------------------------------------------------------------------------------------------
+
+    // An asm block for an op which takes two registers and writes to a third, e.g., `lt r a b`.
+    fn compile_binary_asm_op(
+        &mut self,
+        context: &mut Context,
+        op_name: &'static str,
+        lhs: Value,
+        rhs: Value,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Value {
+        let ident = |name: &str| {
+            Ident::new(Span {
+                span: pest::Span::new(name.into(), 0, name.len()).unwrap(),
+                path: None,
+            })
+        };
+        self.current_block
+            .ins(context)
+            .asm_block(
+                vec![
+                    AsmArg {
+                        name: ident("a"),
+                        initializer: Some(lhs),
+                    },
+                    AsmArg {
+                        name: ident("b"),
+                        initializer: Some(rhs),
+                    },
+                    AsmArg {
+                        name: ident("r"),
+                        initializer: None,
+                    },
+                ],
+                vec![AsmInstruction {
+                    name: ident(op_name),
+                    args: vec![ident("r"), ident("a"), ident("b")],
+                    immediate: None,
+                }],
+                Some(ident("r")),
+            )
+            .add_metadatum(context, span_md_idx)
+    }
+
     // ---------------------------------------------------------------------------------------------
 
-    fn compile_var_expr(&mut self, context: &mut Context, name: &str) -> Result<Value, String> {
+    fn compile_var_expr(
+        &mut self,
+        context: &mut Context,
+        name: &str,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Result<Value, String> {
         // We need to check the symbol map first, in case locals are shadowing the args, other
         // locals or even constants.
         if let Some(ptr) = self
@@ -844,9 +2145,19 @@ impl FnCompiler {
                 self.current_block.ins(context).get_ptr(ptr)
             } else {
                 self.current_block.ins(context).load(ptr)
-            })
+            }
+            .add_metadatum(context, span_md_idx))
         } else if let Some(val) = self.function.get_arg(context, name) {
-            Ok(val)
+            Ok(match self.references.get(name) {
+                Some(pointee) if pointee.is_held_in_register() => self.compile_asm_body(
+                    context,
+                    vec![("addr", val)],
+                    &["lw value addr i0"],
+                    Some("value"),
+                    span_md_idx,
+                ),
+                _otherwise => val,
+            })
         } else if let Some(const_val) = self.module.get_global_constant(context, name) {
             Ok(const_val)
         } else {
@@ -854,12 +2165,47 @@ impl FnCompiler {
         }
     }
 
+    // The argument to a `ref mut` parameter is the address of the variable passed, which is either
+    // a local or, when it's passed on from a `ref mut` parameter of this function, the address
+    // which this function was passed.
+    fn compile_reference(
+        &mut self,
+        context: &mut Context,
+        ast_expr: TypedExpression,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Result<Value, String> {
+        let name = match &ast_expr.expression {
+            TypedExpressionVariant::VariableExpression { name } => name.as_str(),
+            _otherwise => return Err("Only a variable can be passed by reference.".into()),
+        };
+        if let Some(ptr) = self
+            .symbol_map
+            .get(name)
+            .and_then(|local_name| self.function.get_local_ptr(context, local_name))
+        {
+            Ok(self
+                .current_block
+                .ins(context)
+                .get_ptr(ptr)
+                .add_metadatum(context, span_md_idx))
+        } else {
+            self.function
+                .get_arg(context, name)
+                .filter(|_| self.references.contains_key(name))
+                .ok_or(format!(
+                    "Unable to resolve reference to variable '{}'.",
+                    name
+                ))
+        }
+    }
+
     // ---------------------------------------------------------------------------------------------
 
     fn compile_var_decl(
         &mut self,
         context: &mut Context,
         ast_var_decl: TypedVariableDeclaration,
+        span_md_idx: Option<MetadataIndex>,
     ) -> Result<Value, String> {
         let TypedVariableDeclaration {
             name,
@@ -868,24 +2214,17 @@ impl FnCompiler {
             ..
         } = ast_var_decl;
 
+        // a variable bound to a function value is only another name for it
+        if let TypedExpressionVariant::FunctionValue(_) = body.expression {
+            return Ok(Constant::get_unit(context));
+        }
+
         // We must compile the RHS before checking for shadowing, as it will still be in the
         // previous scope.
         let return_type = convert_resolved_typeid(context, &body.return_type, &body.span)?;
         let init_val = self.compile_expression(context, body)?;
 
-        let local_name = match self.symbol_map.get(name.as_str()) {
-            None => {
-                // Haven't seen this name before.  Use it as-is.
-                name.as_str().to_owned()
-            }
-            Some(shadowed_name) => {
-                // Seen before, and this is shadowing the old one.  Update to a new name.
-                format!("{}_", shadowed_name)
-            }
-        };
-        self.symbol_map
-            .insert(name.as_str().to_owned(), local_name.clone());
-
+        let local_name = self.new_local_name(name.as_str());
         let ptr = self.function.new_local_ptr(
             context,
             local_name,
@@ -894,12 +2233,34 @@ impl FnCompiler {
             None,
         )?;
 
-        self.current_block.ins(context).store(ptr, init_val);
+        self.current_block
+            .ins(context)
+            .store(ptr, init_val)
+            .add_metadatum(context, span_md_idx);
         Ok(init_val)
     }
 
     // ---------------------------------------------------------------------------------------------
 
+    // Return the name for a new local called `name` in the source, which shadows any previous one,
+    // and add it to the symbol map.
+    fn new_local_name(&mut self, name: &str) -> String {
+        let local_name = match self.symbol_map.get(name) {
+            None => {
+                // Haven't seen this name before.  Use it as-is.
+                name.to_owned()
+            }
+            Some(shadowed_name) => {
+                // Seen before, and this is shadowing the old one.  Update to a new name.
+                format!("{}_", shadowed_name)
+            }
+        };
+        self.symbol_map.insert(name.to_owned(), local_name.clone());
+        local_name
+    }
+
+    // ---------------------------------------------------------------------------------------------
+
     fn compile_const_decl(
         &mut self,
         context: &mut Context,
@@ -909,27 +2270,13 @@ impl FnCompiler {
         // globals like other const decls.
         let TypedConstantDeclaration { name, value, .. } = ast_const_decl;
 
-        if let TypedExpressionVariant::Literal(literal) = &value.expression {
-            let initialiser = convert_literal_to_constant(literal);
-            let return_type = convert_resolved_typeid(context, &value.return_type, &value.span)?;
-            let name = name.as_str().to_owned();
-            self.function.new_local_ptr(
-                context,
-                name.clone(),
-                return_type,
-                false,
-                Some(initialiser),
-            )?;
-
-            // We still insert this into the symbol table, as itself... can they be shadowed?
-            // (Hrmm, name resolution in the variable expression code could be smarter about var
-            // decls vs const decls, for now they're essentially the same...)
-            self.symbol_map.insert(name.clone(), name);
+        let initialiser = compile_constant(context, &value)?;
+        let return_type = convert_resolved_typeid(context, &value.return_type, &value.span)?;
+        let local_name = self.new_local_name(name.as_str());
+        self.function
+            .new_local_ptr(context, local_name, return_type, false, Some(initialiser))?;
 
-            Ok(Constant::get_unit(context))
-        } else {
-            Err("Unsupported constant declaration type.".into())
-        }
+        Ok(Constant::get_unit(context))
     }
 
     // ---------------------------------------------------------------------------------------------
@@ -938,67 +2285,109 @@ impl FnCompiler {
         &mut self,
         context: &mut Context,
         ast_reassignment: TypedReassignment,
+        span_md_idx: Option<MetadataIndex>,
     ) -> Result<Value, String> {
+        // As with variable expressions, the name may refer to a local which shadows another.
+        // Otherwise it's a `ref mut` arg, the address of the caller's variable.
         let name = ast_reassignment.lhs[0].name.as_str();
         let ptr_val = self
-            .function
-            .get_local_ptr(context, name)
-            .ok_or(format!("variable not found: {}", name))?;
+            .symbol_map
+            .get(name)
+            .and_then(|local_name| self.function.get_local_ptr(context, local_name));
+        let reference = match ptr_val {
+            Some(_) => None,
+            None => self
+                .function
+                .get_arg(context, name)
+                .zip(self.references.get(name).cloned()),
+        };
+        if ptr_val.is_none() && reference.is_none() {
+            return Err(format!("variable not found: {}", name));
+        }
 
         let reassign_val = self.compile_expression(context, ast_reassignment.rhs)?;
 
         if ast_reassignment.lhs.len() == 1 {
-            // A non-aggregate; use a `store`.
-            self.current_block.ins(context).store(ptr_val, reassign_val);
+            match (ptr_val, reference) {
+                // A non-aggregate; use a `store`.
+                (Some(ptr_val), _) => {
+                    self.current_block
+                        .ins(context)
+                        .store(ptr_val, reassign_val)
+                        .add_metadatum(context, span_md_idx);
+                }
+                // The whole of the caller's variable is replaced through its address.
+                (None, Some((addr_val, pointee))) => self.compile_reference_store(
+                    context,
+                    addr_val,
+                    reassign_val,
+                    &pointee,
+                    ast_reassignment.lhs[0].name.span(),
+                    span_md_idx,
+                )?,
+                (None, None) => unreachable!(),
+            }
         } else {
-            // An aggregate.  Iterate over the field names from the left hand side and collect
-            // field indices.
+            // An aggregate, whose value is its address whether it's a local or a `ref mut` arg.
+            let (aggregate_val, aggregate_type) = match ptr_val {
+                Some(ptr_val) => (
+                    self.current_block
+                        .ins(context)
+                        .get_ptr(ptr_val)
+                        .add_metadatum(context, span_md_idx),
+                    *ptr_val.get_type(context),
+                ),
+                None => {
+                    let addr_val = reference.unwrap().0;
+                    let addr_type = addr_val
+                        .get_type(context)
+                        .ok_or("Reassignment with multiple accessors to non-aggregate.")?;
+                    (addr_val, addr_type)
+                }
+            };
+
+            // Iterate over the field names from the left hand side and collect field indices.
             let field_idcs = ast_reassignment.lhs[1..]
                 .iter()
-                .fold(
-                    Ok((Vec::new(), *ptr_val.get_type(context))),
-                    |acc, field_name| {
-                        // Make sure we have an aggregate to index into.
-                        acc.and_then(|(mut fld_idcs, ty)| match ty {
-                            Type::Struct(aggregate) => {
-                                // Get the field index and also its type for the next iteration.
-                                match context
-                                    .get_aggregate_index(&aggregate, field_name.name.as_str())
-                                {
-                                    None => Err(format!(
-                                        "Unknown field name {} for struct ???",
-                                        field_name.name.as_str()
-                                    )),
-                                    Some(field_idx) => {
-                                        let field_type = context.aggregates[aggregate.0]
-                                            .field_types()
-                                            [field_idx as usize];
-
-                                        // Save the field index.
-                                        fld_idcs.push(field_idx);
-                                        Ok((fld_idcs, field_type))
-                                    }
+                .fold(Ok((Vec::new(), aggregate_type)), |acc, field_name| {
+                    // Make sure we have an aggregate to index into.
+                    acc.and_then(|(mut fld_idcs, ty)| match ty {
+                        Type::Struct(aggregate) => {
+                            // Get the field index and also its type for the next iteration.
+                            match context.get_aggregate_index(&aggregate, field_name.name.as_str())
+                            {
+                                None => Err(format!(
+                                    "Unknown field name {} for struct ???",
+                                    field_name.name.as_str()
+                                )),
+                                Some(field_idx) => {
+                                    let field_type = context.aggregates[aggregate.0].field_types()
+                                        [field_idx as usize];
+
+                                    // Save the field index.
+                                    fld_idcs.push(field_idx);
+                                    Ok((fld_idcs, field_type))
                                 }
                             }
-                            _otherwise => {
-                                Err("Reassignment with multiple accessors to non-aggregate.".into())
-                            }
-                        })
-                    },
-                )?
+                        }
+                        _otherwise => {
+                            Err("Reassignment with multiple accessors to non-aggregate.".into())
+                        }
+                    })
+                })?
                 .0;
 
-            let ty = match ptr_val.get_type(context) {
-                Type::Struct(aggregate) => *aggregate,
+            let ty = match aggregate_type {
+                Type::Struct(aggregate) => aggregate,
                 _otherwise => {
                     return Err("Reassignment with multiple accessors to non-aggregate.".into())
                 }
             };
 
-            let get_ptr_val = self.current_block.ins(context).get_ptr(ptr_val);
             self.current_block
                 .ins(context)
-                .insert_value(get_ptr_val, ty, reassign_val, field_idcs);
+                .insert_value(aggregate_val, ty, reassign_val, field_idcs)
+                .add_metadatum(context, span_md_idx);
         }
 
         // This shouldn't really return a value, it doesn't make sense to return the `store` or
@@ -1006,12 +2395,85 @@ impl FnCompiler {
         Ok(reassign_val)
     }
 
+    // A value held in a register is stored as a word at the address of the caller's variable, and
+    // otherwise its value is the address of the memory it's laid out in, which is copied.
+    fn compile_reference_store(
+        &mut self,
+        context: &mut Context,
+        addr_val: Value,
+        value: Value,
+        pointee: &TypeInfo,
+        span: &Span,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Result<(), String> {
+        if pointee.is_held_in_register() {
+            self.compile_asm_body(
+                context,
+                vec![("addr", addr_val), ("value", value)],
+                &["sw addr value i0"],
+                None,
+                span_md_idx,
+            );
+        } else {
+            let size_in_bytes = pointee
+                .size_in_words(span)
+                .map_err(|err| format!("{:?}", err))?
+                * 8;
+            let size = Constant::get_uint(context, 64, size_in_bytes);
+            self.compile_asm_body(
+                context,
+                vec![("addr", addr_val), ("value", value), ("size", size)],
+                &["mcp addr value size"],
+                None,
+                span_md_idx,
+            );
+        }
+        Ok(())
+    }
+
+    // ---------------------------------------------------------------------------------------------
+
+    fn compile_storage_access(
+        &mut self,
+        context: &mut Context,
+        ix: usize,
+        return_type: &TypeId,
+        span: &Span,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Result<Value, String> {
+        let ty = convert_resolved_typeid(context, return_type, span)?;
+        let key_val = Constant::get_b256(context, get_storage_key(ix));
+        Ok(self
+            .current_block
+            .ins(context)
+            .state_load(key_val, ty)
+            .add_metadatum(context, span_md_idx))
+    }
+
+    fn compile_storage_reassignment(
+        &mut self,
+        context: &mut Context,
+        ast_reassignment: TypedStorageReassignment,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Result<Value, String> {
+        let reassign_val = self.compile_expression(context, ast_reassignment.rhs)?;
+        let key_val = Constant::get_b256(context, get_storage_key(ast_reassignment.ix));
+        self.current_block
+            .ins(context)
+            .state_store(key_val, reassign_val)
+            .add_metadatum(context, span_md_idx);
+
+        // As with regular reassignments we need to return something here.
+        Ok(reassign_val)
+    }
+
     // ---------------------------------------------------------------------------------------------
 
     fn compile_array_expr(
         &mut self,
         context: &mut Context,
         contents: Vec<TypedExpression>,
+        span_md_idx: Option<MetadataIndex>,
     ) -> Result<Value, String> {
         if contents.is_empty() {
             return Err("Unable to create zero sized static arrays.".into());
@@ -1034,12 +2496,10 @@ impl FnCompiler {
                         let index_val = Constant::get_uint(context, 64, idx as u64);
                         self.compile_expression(context, elem_expr)
                             .map(|elem_value| {
-                                self.current_block.ins(context).insert_element(
-                                    array_value,
-                                    aggregate,
-                                    elem_value,
-                                    index_val,
-                                )
+                                self.current_block
+                                    .ins(context)
+                                    .insert_element(array_value, aggregate, elem_value, index_val)
+                                    .add_metadatum(context, span_md_idx)
                             })
                     }
                 }
@@ -1053,23 +2513,10 @@ impl FnCompiler {
         context: &mut Context,
         array_expr: TypedExpression,
         index_expr: TypedExpression,
+        span_md_idx: Option<MetadataIndex>,
     ) -> Result<Value, String> {
         let array_val = self.compile_expression(context, array_expr)?;
-        let aggregate = match &context.values[array_val.0] {
-            ValueContent::Instruction(instruction) => {
-                instruction.get_aggregate(context).ok_or_else(|| {
-                    format!(
-                        "Unsupported instruction as array value for index expression. {:?}",
-                        instruction
-                    )
-                })
-            }
-            ValueContent::Argument(Type::Array(aggregate)) => Ok(*aggregate),
-            otherwise => Err(format!(
-                "Unsupported array value for index expression: {:?}",
-                otherwise
-            )),
-        }?;
+        let aggregate = get_array_aggregate(context, array_val)?;
 
         // Check for out of bounds if we have a literal index.
         let (_, count) = context.aggregates[aggregate.0].array_type();
@@ -1090,7 +2537,8 @@ impl FnCompiler {
         Ok(self
             .current_block
             .ins(context)
-            .extract_element(array_val, aggregate, index_val))
+            .extract_element(array_val, aggregate, index_val)
+            .add_metadatum(context, span_md_idx))
     }
 
     // ---------------------------------------------------------------------------------------------
@@ -1099,11 +2547,19 @@ impl FnCompiler {
         &mut self,
         context: &mut Context,
         struct_name: &str,
+        struct_type: TypeId,
         fields: Vec<TypedStructExpressionField>,
+        span_md_idx: Option<MetadataIndex>,
     ) -> Result<Value, String> {
-        let aggregate = context
-            .get_aggregate_by_name(struct_name)
-            .ok_or_else(|| format!("Unknown aggregate {}", struct_name))?;
+        // The aggregate is usually created along with the first variable or parameter of the
+        // struct's type, but a struct expression may come first, e.g. as the argument of `log`.
+        let aggregate = match context.get_aggregate_by_name(struct_name) {
+            Some(aggregate) => aggregate,
+            None => match convert_resolved_typeid_no_span(context, &struct_type)? {
+                Type::Struct(aggregate) => aggregate,
+                _ => return Err(format!("Unknown aggregate {}", struct_name)),
+            },
+        };
 
         // Compile each of the values for field initialisers and calculate their indices.
         let inserted_values_indices = fields
@@ -1127,12 +2583,10 @@ impl FnCompiler {
         Ok(inserted_values_indices.into_iter().fold(
             agg_value,
             |agg_value, (insert_val, insert_idx)| {
-                self.current_block.ins(context).insert_value(
-                    agg_value,
-                    aggregate,
-                    insert_val,
-                    vec![insert_idx],
-                )
+                self.current_block
+                    .ins(context)
+                    .insert_value(agg_value, aggregate, insert_val, vec![insert_idx])
+                    .add_metadatum(context, span_md_idx)
             },
         ))
     }
@@ -1145,6 +2599,7 @@ impl FnCompiler {
         ast_struct_expr: TypedExpression,
         ast_field: OwnedTypedStructField,
         _ast_parent_type: TypeId,
+        span_md_idx: Option<MetadataIndex>,
     ) -> Result<Value, String> {
         let struct_val = self.compile_expression(context, ast_struct_expr)?;
         let aggregate = match &context.values[struct_val.0] {
@@ -1157,6 +2612,10 @@ impl FnCompiler {
                 })
             }
             ValueContent::Argument(Type::Struct(aggregate)) => Ok(*aggregate),
+            ValueContent::Constant(Constant {
+                ty: Type::Struct(aggregate),
+                ..
+            }) => Ok(*aggregate),
             otherwise => Err(format!(
                 "Unsupported struct value for field expression: {:?}",
                 otherwise
@@ -1170,7 +2629,8 @@ impl FnCompiler {
         Ok(self
             .current_block
             .ins(context)
-            .extract_value(struct_val, aggregate, vec![field_idx]))
+            .extract_value(struct_val, aggregate, vec![field_idx])
+            .add_metadatum(context, span_md_idx))
     }
 
     // ---------------------------------------------------------------------------------------------
@@ -1182,6 +2642,7 @@ impl FnCompiler {
         enum_decl: TypedEnumDeclaration,
         tag: usize,
         contents: Option<Box<TypedExpression>>,
+        span_md_idx: Option<MetadataIndex>,
     ) -> Result<Value, String> {
         // XXX The enum instantiation AST node includes the full declaration.  If the enum was
         // declared in a different module then it seems for now there's no easy way to pre-analyse
@@ -1197,32 +2658,54 @@ impl FnCompiler {
 
         // Start with the undef and insert the tag.
         let agg_value = Constant::get_undef(context, Type::Struct(aggregate));
-        let agg_value =
-            self.current_block
-                .ins(context)
-                .insert_value(agg_value, aggregate, tag_value, vec![0]);
+        let agg_value = self
+            .current_block
+            .ins(context)
+            .insert_value(agg_value, aggregate, tag_value, vec![0])
+            .add_metadatum(context, span_md_idx);
 
         Ok(match contents {
             None => agg_value,
             Some(te) => {
                 // Insert the value too.
                 let contents_value = self.compile_expression(context, *te)?;
-                self.current_block.ins(context).insert_value(
-                    agg_value,
-                    aggregate,
-                    contents_value,
-                    vec![1],
-                )
+                self.current_block
+                    .ins(context)
+                    .insert_value(agg_value, aggregate, contents_value, vec![1])
+                    .add_metadatum(context, span_md_idx)
             }
         })
     }
 
     // ---------------------------------------------------------------------------------------------
 
+    fn compile_enum_tag(
+        &mut self,
+        context: &mut Context,
+        enum_expr: TypedExpression,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Result<Value, String> {
+        let enum_type = convert_resolved_typeid(context, &enum_expr.return_type, &enum_expr.span)?;
+        let enum_value = self.compile_expression(context, enum_expr)?;
+        if let Type::Struct(aggregate) = enum_type {
+            // The tag is the first field of the aggregate, see compile_enum_expr().
+            Ok(self
+                .current_block
+                .ins(context)
+                .extract_value(enum_value, aggregate, vec![0])
+                .add_metadatum(context, span_md_idx))
+        } else {
+            Err("Invalid (non-aggregate?) enum type for EnumTag?".into())
+        }
+    }
+
+    // ---------------------------------------------------------------------------------------------
+
     fn compile_tuple_expr(
         &mut self,
         context: &mut Context,
         fields: Vec<TypedExpression>,
+        span_md_idx: Option<MetadataIndex>,
     ) -> Result<Value, String> {
         if fields.is_empty() {
             // This is a Unit.  We're still debating whether Unit should just be an empty tuple in
@@ -1249,12 +2732,10 @@ impl FnCompiler {
             Ok(init_values.into_iter().enumerate().fold(
                 agg_value,
                 |agg_value, (insert_idx, insert_val)| {
-                    self.current_block.ins(context).insert_value(
-                        agg_value,
-                        aggregate,
-                        insert_val,
-                        vec![insert_idx as u64],
-                    )
+                    self.current_block
+                        .ins(context)
+                        .insert_value(agg_value, aggregate, insert_val, vec![insert_idx as u64])
+                        .add_metadatum(context, span_md_idx)
                 },
             ))
         }
@@ -1269,14 +2750,15 @@ impl FnCompiler {
         tuple_type: TypeId,
         idx: usize,
         span: Span,
+        span_md_idx: Option<MetadataIndex>,
     ) -> Result<Value, String> {
         let tuple_value = self.compile_expression(context, tuple)?;
         if let Type::Struct(aggregate) = convert_resolved_typeid(context, &tuple_type, &span)? {
-            Ok(self.current_block.ins(context).extract_value(
-                tuple_value,
-                aggregate,
-                vec![idx as u64],
-            ))
+            Ok(self
+                .current_block
+                .ins(context)
+                .extract_value(tuple_value, aggregate, vec![idx as u64])
+                .add_metadatum(context, span_md_idx))
         } else {
             Err("Invalid (non-aggregate?) tuple type for TupleElemAccess?".into())
         }
@@ -1290,6 +2772,7 @@ impl FnCompiler {
         registers: Vec<TypedAsmRegisterDeclaration>,
         body: Vec<AsmOp>,
         returns: Option<(AsmRegister, Span)>,
+        span_md_idx: Option<MetadataIndex>,
     ) -> Result<Value, String> {
         let registers = registers
             .into_iter()
@@ -1333,7 +2816,32 @@ impl FnCompiler {
         Ok(self
             .current_block
             .ins(context)
-            .asm_block(registers, body, returns))
+            .asm_block(registers, body, returns)
+            .add_metadatum(context, span_md_idx))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+fn get_array_aggregate(context: &Context, array_val: Value) -> Result<Aggregate, String> {
+    match &context.values[array_val.0] {
+        ValueContent::Instruction(instruction) => {
+            instruction.get_aggregate(context).ok_or_else(|| {
+                format!(
+                    "Unsupported instruction as array value for index expression. {:?}",
+                    instruction
+                )
+            })
+        }
+        ValueContent::Argument(Type::Array(aggregate)) => Ok(*aggregate),
+        ValueContent::Constant(Constant {
+            ty: Type::Array(aggregate),
+            ..
+        }) => Ok(*aggregate),
+        otherwise => Err(format!(
+            "Unsupported array value for index expression: {:?}",
+            otherwise
+        )),
     }
 }
 
@@ -1345,6 +2853,12 @@ fn convert_literal_to_value(context: &mut Context, ast_literal: &Literal) -> Val
         Literal::U16(n) => Constant::get_uint(context, 16, *n as u64),
         Literal::U32(n) => Constant::get_uint(context, 32, *n as u64),
         Literal::U64(n) => Constant::get_uint(context, 64, *n),
+        Literal::U128(n) => Constant::get_wide_uint(context, 128, n.to_be_bytes().to_vec()),
+        Literal::U256(bs) => Constant::get_wide_uint(context, 256, bs.to_vec()),
+        Literal::I8(n) => Constant::get_uint(context, 8, *n as i64 as u64),
+        Literal::I16(n) => Constant::get_uint(context, 16, *n as i64 as u64),
+        Literal::I32(n) => Constant::get_uint(context, 32, *n as i64 as u64),
+        Literal::I64(n) => Constant::get_uint(context, 64, *n as u64),
         Literal::Numeric(n) => Constant::get_uint(context, 64, *n),
         Literal::String(s) => Constant::get_string(context, s.as_str().to_owned()),
         Literal::Boolean(b) => Constant::get_bool(context, *b),
@@ -1358,6 +2872,12 @@ fn convert_literal_to_constant(ast_literal: &Literal) -> Constant {
         Literal::U16(n) => Constant::new_uint(16, *n as u64),
         Literal::U32(n) => Constant::new_uint(32, *n as u64),
         Literal::U64(n) => Constant::new_uint(64, *n),
+        Literal::U128(n) => Constant::new_wide_uint(128, n.to_be_bytes().to_vec()),
+        Literal::U256(bs) => Constant::new_wide_uint(256, bs.to_vec()),
+        Literal::I8(n) => Constant::new_uint(8, *n as i64 as u64),
+        Literal::I16(n) => Constant::new_uint(16, *n as i64 as u64),
+        Literal::I32(n) => Constant::new_uint(32, *n as i64 as u64),
+        Literal::I64(n) => Constant::new_uint(64, *n as u64),
         Literal::Numeric(n) => Constant::new_uint(64, *n),
         Literal::String(s) => Constant::new_string(s.as_str().to_owned()),
         Literal::Boolean(b) => Constant::new_bool(*b),
@@ -1391,13 +2911,16 @@ fn convert_resolved_typeid_no_span(
 
 fn convert_resolved_type(context: &mut Context, ast_type: &TypeInfo) -> Result<Type, String> {
     Ok(match ast_type {
-        TypeInfo::UnsignedInteger(nbits) => {
+        // IR integers have no sign, which is instead given by the operations on them.
+        TypeInfo::UnsignedInteger(nbits) | TypeInfo::SignedInteger(nbits) => {
             // We need impl IntegerBits { fn num_bits() -> u64 { ... } }
             let nbits = match nbits {
                 IntegerBits::Eight => 8,
                 IntegerBits::Sixteen => 16,
                 IntegerBits::ThirtyTwo => 32,
                 IntegerBits::SixtyFour => 64,
+                IntegerBits::OneTwentyEight => 128,
+                IntegerBits::TwoFiftySix => 256,
             };
             Type::Uint(nbits)
         }
@@ -1429,6 +2952,8 @@ fn convert_resolved_type(context: &mut Context, ast_type: &TypeInfo) -> Result<T
             let elem_type = convert_resolved_typeid_no_span(context, elem_type_id)?;
             Type::Array(Aggregate::new_array(context, elem_type, *count as u64))
         }
+        // A vector is a pointer to its header on the heap.
+        TypeInfo::Vector(_) => Type::Uint(64),
         TypeInfo::Tuple(fields) => {
             if fields.is_empty() {
                 // XXX We've removed Unit from the core compiler, replaced with an empty Tuple.
@@ -1452,6 +2977,10 @@ fn convert_resolved_type(context: &mut Context, ast_type: &TypeInfo) -> Result<T
         TypeInfo::UnknownGeneric { .. } => return Err("unknowngeneric type found in AST..?".into()),
         TypeInfo::Numeric => return Err("'numeric' type found in AST..?".into()),
         TypeInfo::Ref(_) => return Err("ref type found in AST..?".into()),
+        TypeInfo::Alias { .. } => return Err("alias type found in AST..?".into()),
+        TypeInfo::Function { .. } => {
+            return Err("function values are bound at their call sites and never stored".into())
+        }
         TypeInfo::ErrorRecovery => return Err("error recovery type found in AST..?".into()),
     })
 }
@@ -1466,7 +2995,7 @@ mod tests {
     use crate::{
         control_flow_analysis::{ControlFlowGraph, Graph},
         parser::{Rule, SwayParser},
-        semantic_analysis::{TreeType, TypedParseTree},
+        semantic_analysis::TypedParseTree,
     };
     use pest::Parser;
 
@@ -1474,8 +3003,18 @@ mod tests {
 
     #[test]
     fn sway_to_ir_tests() {
+        run_sway_to_ir_tests("tests/sway_to_ir", false);
+    }
+
+    // Functions may only call themselves when they're type checked to be compiled via the IR.
+    #[test]
+    fn sway_to_ir_recursive_tests() {
+        run_sway_to_ir_tests("tests/sway_to_ir_recursive", true);
+    }
+
+    fn run_sway_to_ir_tests(tests_dir: &str, use_ir: bool) {
         let manifest_dir = env!("CARGO_MANIFEST_DIR");
-        let dir: PathBuf = format!("{}/tests/sway_to_ir", manifest_dir).into();
+        let dir: PathBuf = format!("{}/{}", manifest_dir, tests_dir).into();
         for entry in std::fs::read_dir(dir).unwrap() {
             // We're only interested in the `.sw` files here.
             let path = entry.unwrap().path();
@@ -1485,7 +3024,7 @@ mod tests {
                     // Run the tests!
                     //
                     println!("---- Sway To IR: {:?} ----", path);
-                    test_sway_to_ir(path);
+                    test_sway_to_ir(path, use_ir);
                 }
                 Some("ir") | Some("disabled") => (),
                 _ => panic!(
@@ -1496,7 +3035,7 @@ mod tests {
         }
     }
 
-    fn test_sway_to_ir(mut path: PathBuf) {
+    fn test_sway_to_ir(mut path: PathBuf, use_ir: bool) {
         let input_bytes = std::fs::read(&path).unwrap();
         let input = String::from_utf8_lossy(&input_bytes);
 
@@ -1505,7 +3044,7 @@ mod tests {
         let expected_bytes = std::fs::read(&path).unwrap();
         let expected = String::from_utf8_lossy(&expected_bytes);
 
-        let typed_ast = parse_to_typed_ast(&input);
+        let typed_ast = parse_to_typed_ast(&input, use_ir);
         let ir = super::compile_ast(typed_ast).unwrap();
         let output = sway_ir::printer::to_string(&ir);
 
@@ -1561,7 +3100,7 @@ mod tests {
 
     // -------------------------------------------------------------------------------------------------
 
-    fn parse_to_typed_ast(input: &str) -> TypedParseTree {
+    fn parse_to_typed_ast(input: &str, use_ir: bool) -> TypedParseTree {
         let mut parsed =
             SwayParser::parse(Rule::program, std::sync::Arc::from(input)).expect("parse_tree");
 
@@ -1574,21 +3113,25 @@ mod tests {
             graph: Graph::new(),
             entry_points: vec![],
             namespace: Default::default(),
+            loops: vec![],
         };
         let build_config = crate::build_config::BuildConfig {
             file_name: std::sync::Arc::new("test.sw".into()),
             dir_of_code: std::sync::Arc::new("tests".into()),
             manifest_path: std::sync::Arc::new(".".into()),
-            use_ir: false,
+            use_ir,
             print_intermediate_asm: false,
             print_finalized_asm: false,
             print_ir: false,
+            print_peephole_savings: false,
+            optimize_asm: true,
+            ir_opt_level: 1,
         };
         TypedParseTree::type_check(
             parse_tree.tree,
             crate::create_module(),
             crate::create_module(),
-            &TreeType::Script,
+            &parse_tree.tree_type,
             &build_config,
             &mut dead_code_graph,
             &mut std::collections::HashMap::new(),
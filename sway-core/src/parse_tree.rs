@@ -1,8 +1,10 @@
 //! Contains all the code related to parsing Sway source code.
+pub(crate) mod attribute;
 mod call_path;
 mod code_block;
 pub mod declaration;
 mod expression;
+mod for_loop;
 pub mod ident;
 mod include_statement;
 mod literal;
@@ -11,10 +13,13 @@ mod use_statement;
 mod visibility;
 mod while_loop;
 
+pub(crate) use attribute::AttributeTarget;
+pub use attribute::{Attribute, Inline};
 pub use call_path::*;
 pub use code_block::*;
 pub use declaration::*;
 pub use expression::*;
+pub use for_loop::{ForLoop, ForLoopIterable};
 pub(crate) use include_statement::IncludeStatement;
 pub use literal::Literal;
 pub use return_statement::*;
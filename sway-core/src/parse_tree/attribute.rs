@@ -0,0 +1,271 @@
+use crate::{
+    build_config::BuildConfig,
+    error::*,
+    parse_tree::{ident, FunctionDeclaration},
+    Rule,
+};
+
+use sway_types::{ident::Ident, span::Span};
+
+use pest::iterators::Pair;
+
+use std::iter::Peekable;
+
+/// An attribute on a declaration, such as `#[inline(never)]` or `#[deprecated]`.
+///
+/// Attributes the compiler doesn't know about are warned about and otherwise kept around
+/// untouched, so that tooling may still find them on the tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attribute {
+    pub name: Ident,
+    pub args: Vec<Ident>,
+    pub span: Span,
+}
+
+/// The kind of declaration a list of attributes is attached to, which decides which of the
+/// known attributes are allowed there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AttributeTarget {
+    /// A function with a body, free or within an impl or trait.
+    Function,
+    /// A function signature within a trait or ABI declaration.
+    FunctionSignature,
+    Struct,
+    Enum,
+    Trait,
+    Abi,
+    Impl,
+}
+
+impl AttributeTarget {
+    fn friendly_name(&self) -> &'static str {
+        use AttributeTarget::*;
+        match self {
+            Function => "functions",
+            FunctionSignature => "function signatures",
+            Struct => "structs",
+            Enum => "enums",
+            Trait => "traits",
+            Abi => "ABI declarations",
+            Impl => "impl blocks",
+        }
+    }
+}
+
+/// A hint to the optimizer about whether calls to a function should be inlined.  Only the IR
+/// backend follows it, as the older backend inlines every call regardless.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Inline {
+    Always,
+    Never,
+}
+
+const INLINE: &str = "inline";
+const DEPRECATED: &str = "deprecated";
+const TEST: &str = "test";
+
+impl Attribute {
+    pub(crate) fn parse_from_pair(
+        pair: Pair<Rule>,
+        config: Option<&BuildConfig>,
+    ) -> CompileResult<Self> {
+        let path = config.map(|c| c.path());
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+        let span = Span {
+            span: pair.as_span(),
+            path,
+        };
+        let mut iter = pair.into_inner();
+        let name = check!(
+            ident::parse_from_pair(iter.next().expect("guaranteed by grammar"), config),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        let mut args = Vec::new();
+        if let Some(args_pair) = iter.next() {
+            for arg in args_pair.into_inner() {
+                args.push(check!(
+                    ident::parse_from_pair(arg, config),
+                    continue,
+                    warnings,
+                    errors
+                ));
+            }
+        }
+        ok(Attribute { name, args, span }, warnings, errors)
+    }
+
+    /// Parses the `attribute` pairs at the front of a declaration's parts, checking each one
+    /// against the kind of declaration it is attached to.
+    pub(crate) fn parse_leading<I: Iterator<Item = Pair<Rule>>>(
+        parts: &mut Peekable<I>,
+        target: AttributeTarget,
+        config: Option<&BuildConfig>,
+    ) -> CompileResult<Vec<Attribute>> {
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+        let mut attributes = Vec::new();
+        while let Some(pair) = parts.next_if(|pair| pair.as_rule() == Rule::attribute) {
+            let attribute = check!(
+                Attribute::parse_from_pair(pair, config),
+                continue,
+                warnings,
+                errors
+            );
+            check!(attribute.check(target), (), warnings, errors);
+            // only the IR backend compiles calls which aren't inlined
+            let without_ir = matches!(config, Some(config) if !config.use_ir);
+            if attribute.name.as_str() == INLINE && without_ir {
+                warnings.push(CompileWarning {
+                    warning_content: Warning::InlineHintIgnored,
+                    span: attribute.span.clone(),
+                });
+            }
+            attributes.push(attribute);
+        }
+        ok(attributes, warnings, errors)
+    }
+
+    /// Checks that this attribute is well formed and allowed on `target`. Unknown attributes
+    /// only warrant a warning.
+    pub(crate) fn check(&self, target: AttributeTarget) -> CompileResult<()> {
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+        let (allowed, well_formed, expected) = match self.name.as_str() {
+            INLINE => (
+                matches!(target, AttributeTarget::Function | AttributeTarget::Impl),
+                self.inline_hint().is_some(),
+                "`#[inline(always)]` or `#[inline(never)]`",
+            ),
+            DEPRECATED => (true, self.args.is_empty(), "`#[deprecated]`"),
+            TEST => (
+                target == AttributeTarget::Function,
+                self.args.is_empty(),
+                "`#[test]`",
+            ),
+            name => {
+                warnings.push(CompileWarning {
+                    warning_content: Warning::UnknownAttribute {
+                        name: name.to_string(),
+                    },
+                    span: self.span.clone(),
+                });
+                return ok((), warnings, errors);
+            }
+        };
+        if !allowed {
+            errors.push(CompileError::MisplacedAttribute {
+                name: self.name.as_str().to_string(),
+                target: target.friendly_name(),
+                span: self.span.clone(),
+            });
+        } else if !well_formed {
+            errors.push(CompileError::MalformedAttribute {
+                name: self.name.as_str().to_string(),
+                expected,
+                span: self.span.clone(),
+            });
+        }
+        ok((), warnings, errors)
+    }
+
+    fn inline_hint(&self) -> Option<Inline> {
+        match self.args.as_slice() {
+            [arg] if arg.as_str() == "always" => Some(Inline::Always),
+            [arg] if arg.as_str() == "never" => Some(Inline::Never),
+            _ => None,
+        }
+    }
+}
+
+/// The inlining hint given by an `#[inline(..)]` attribute, if there is one.
+pub(crate) fn inline_hint(attributes: &[Attribute]) -> Option<Inline> {
+    attributes
+        .iter()
+        .filter(|attribute| attribute.name.as_str() == INLINE)
+        .find_map(Attribute::inline_hint)
+}
+
+pub(crate) fn is_deprecated(attributes: &[Attribute]) -> bool {
+    attributes
+        .iter()
+        .any(|attribute| attribute.name.as_str() == DEPRECATED)
+}
+
+pub(crate) fn is_test(attributes: &[Attribute]) -> bool {
+    attributes
+        .iter()
+        .any(|attribute| attribute.name.as_str() == TEST)
+}
+
+/// Attributes on an impl block apply to each of its methods, unless the method has an
+/// attribute of the same name itself.
+pub(crate) fn inherit(attributes: &mut Vec<Attribute>, from: &[Attribute]) {
+    for attribute in from {
+        if !attributes
+            .iter()
+            .any(|existing| existing.name.as_str() == attribute.name.as_str())
+        {
+            attributes.push(attribute.clone());
+        }
+    }
+}
+
+/// Methods are called on a value, so they can't be run as tests.
+pub(crate) fn check_method(method: &FunctionDeclaration) -> CompileResult<()> {
+    let errors = method
+        .attributes
+        .iter()
+        .filter(|attribute| attribute.name.as_str() == TEST)
+        .map(|attribute| CompileError::MisplacedAttribute {
+            name: TEST.to_string(),
+            target: "methods",
+            span: attribute.span.clone(),
+        })
+        .collect();
+    ok((), Vec::new(), errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_config::BuildConfig, Warning};
+
+    fn inline_hint_warnings(use_ir: bool) -> usize {
+        let build_config = BuildConfig {
+            file_name: std::sync::Arc::new("test.sw".into()),
+            dir_of_code: std::sync::Arc::new("tests".into()),
+            manifest_path: std::sync::Arc::new(".".into()),
+            use_ir,
+            print_intermediate_asm: false,
+            print_finalized_asm: false,
+            print_ir: false,
+            print_peephole_savings: false,
+            optimize_asm: true,
+            ir_opt_level: 1,
+        };
+        let input = "script;
+
+            #[inline(never)]
+            fn one() -> u64 {
+                1
+            }
+
+            fn main() -> u64 {
+                one()
+            }
+        ";
+        crate::parse(std::sync::Arc::from(input), Some(&build_config))
+            .warnings
+            .iter()
+            .filter(|warning| warning.warning_content == Warning::InlineHintIgnored)
+            .count()
+    }
+
+    #[test]
+    fn inline_hint_is_only_ignored_without_ir() {
+        assert_eq!(inline_hint_warnings(false), 1);
+        assert_eq!(inline_hint_warnings(true), 0);
+    }
+}
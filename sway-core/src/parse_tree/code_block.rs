@@ -1,4 +1,4 @@
-use super::WhileLoop;
+use super::{ForLoop, WhileLoop};
 use crate::{
     build_config::BuildConfig,
     error::*,
@@ -32,19 +32,24 @@ impl CodeBlock {
         let block_inner = block.into_inner();
         let mut contents = Vec::new();
         for pair in block_inner {
-            contents.push(match pair.as_rule() {
-                Rule::declaration => AstNode {
-                    content: AstNodeContent::Declaration(check!(
+            let node = match pair.as_rule() {
+                Rule::declaration => {
+                    // a single `let` may declare several variables, so this pushes them all
+                    let decls = check!(
                         Declaration::parse_from_pair(pair.clone(), config),
                         continue,
                         warnings,
                         errors
-                    )),
-                    span: span::Span {
-                        span: pair.as_span(),
-                        path: path.clone(),
-                    },
-                },
+                    );
+                    contents.extend(decls.into_iter().map(|decl| AstNode {
+                        content: AstNodeContent::Declaration(decl),
+                        span: span::Span {
+                            span: pair.as_span(),
+                            path: path.clone(),
+                        },
+                    }));
+                    continue;
+                }
                 Rule::expr_statement => {
                     let evaluated_node = check!(
                         Expression::parse_from_pair(
@@ -105,6 +110,35 @@ impl CodeBlock {
                         },
                     }
                 }
+                Rule::for_loop => {
+                    let res = check!(
+                        ForLoop::parse_from_pair(pair.clone(), config),
+                        continue,
+                        warnings,
+                        errors
+                    );
+                    AstNode {
+                        content: AstNodeContent::ForLoop(res),
+                        span: span::Span {
+                            span: pair.as_span(),
+                            path: path.clone(),
+                        },
+                    }
+                }
+                Rule::break_statement => AstNode {
+                    content: AstNodeContent::Break,
+                    span: span::Span {
+                        span: pair.as_span(),
+                        path: path.clone(),
+                    },
+                },
+                Rule::continue_statement => AstNode {
+                    content: AstNodeContent::Continue,
+                    span: span::Span {
+                        span: pair.as_span(),
+                        path: path.clone(),
+                    },
+                },
                 a => {
                     println!("In code block parsing: {:?} {:?}", a, pair.as_str());
                     errors.push(CompileError::UnimplementedRule(
@@ -116,7 +150,8 @@ impl CodeBlock {
                     ));
                     continue;
                 }
-            })
+            };
+            contents.push(node);
         }
 
         ok(
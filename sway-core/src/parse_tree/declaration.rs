@@ -7,6 +7,7 @@ mod reassignment;
 mod storage;
 mod r#struct;
 mod r#trait;
+mod type_alias;
 mod type_parameter;
 mod variable;
 
@@ -19,18 +20,11 @@ pub use r#struct::*;
 pub use r#trait::*;
 pub(crate) use reassignment::*;
 pub use storage::*;
+pub use type_alias::*;
 pub(crate) use type_parameter::*;
 pub use variable::*;
 
-use crate::{
-    build_config::BuildConfig,
-    error::*,
-    parse_tree::{ident, Expression},
-    parser::Rule,
-    TypeInfo,
-};
-
-use sway_types::span::Span;
+use crate::{build_config::BuildConfig, error::*, parser::Rule};
 
 use pest::iterators::Pair;
 
@@ -47,6 +41,7 @@ pub enum Declaration {
     AbiDeclaration(AbiDeclaration),
     ConstantDeclaration(ConstantDeclaration),
     StorageDeclaration(StorageDeclaration),
+    TypeAliasDeclaration(TypeAliasDeclaration),
 }
 impl Declaration {
     pub(crate) fn parse_non_var_from_pair(
@@ -130,14 +125,22 @@ impl Declaration {
                 warnings,
                 errors
             )),
+            Rule::type_alias_decl => Declaration::TypeAliasDeclaration(check!(
+                TypeAliasDeclaration::parse_from_pair(decl_inner, config),
+                return err(warnings, errors),
+                warnings,
+                errors
+            )),
             a => unreachable!("declarations don't have any other sub-types: {:?}", a),
         };
         ok(parsed_declaration, warnings, errors)
     }
+    /// Parses a declaration, which is usually a single one.  A `let` which destructures a value
+    /// is parsed as several variable declarations, though.
     pub(crate) fn parse_from_pair(
         decl: Pair<Rule>,
         config: Option<&BuildConfig>,
-    ) -> CompileResult<Self> {
+    ) -> CompileResult<Vec<Self>> {
         let mut warnings = Vec::new();
         let mut errors = Vec::new();
         let mut pair = decl.into_inner();
@@ -150,59 +153,21 @@ impl Declaration {
                 errors
             )),
             Rule::var_decl => {
-                let mut var_decl_parts = decl_inner.into_inner();
-                let _let_keyword = var_decl_parts.next();
-                let maybe_mut_keyword = var_decl_parts.next().unwrap();
-                let is_mutable = maybe_mut_keyword.as_rule() == Rule::mut_keyword;
-                let name_pair = if is_mutable {
-                    var_decl_parts.next().unwrap()
-                } else {
-                    maybe_mut_keyword
-                };
-                let mut maybe_body = var_decl_parts.next().unwrap();
-                let type_ascription = match maybe_body.as_rule() {
-                    Rule::type_ascription => {
-                        let type_asc = maybe_body.clone();
-                        maybe_body = var_decl_parts.next().unwrap();
-                        Some(type_asc)
-                    }
-                    _ => None,
-                };
-                let type_ascription_span = type_ascription
-                    .clone()
-                    .map(|x| x.into_inner().next().unwrap().as_span());
-                let type_ascription = if let Some(ascription) = type_ascription {
-                    let type_name = ascription.into_inner().next().unwrap();
-                    check!(
-                        TypeInfo::parse_from_pair(type_name, config),
-                        TypeInfo::Tuple(Vec::new()),
-                        warnings,
-                        errors
-                    )
-                } else {
-                    TypeInfo::Unknown
-                };
-                let body = check!(
-                    Expression::parse_from_pair(maybe_body, config),
+                // A `let` which destructures a value declares a variable for each of its parts.
+                let var_decls = check!(
+                    VariableDeclaration::parse_from_pair(decl_inner, config),
                     return err(warnings, errors),
                     warnings,
                     errors
                 );
-                Declaration::VariableDeclaration(VariableDeclaration {
-                    name: check!(
-                        ident::parse_from_pair(name_pair, config),
-                        return err(warnings, errors),
-                        warnings,
-                        errors
-                    ),
-                    body,
-                    is_mutable,
-                    type_ascription,
-                    type_ascription_span: type_ascription_span.map(|type_ascription_span| Span {
-                        span: type_ascription_span,
-                        path: config.map(|x| x.path()),
-                    }),
-                })
+                return ok(
+                    var_decls
+                        .into_iter()
+                        .map(Declaration::VariableDeclaration)
+                        .collect(),
+                    warnings,
+                    errors,
+                );
             }
             Rule::trait_decl => Declaration::TraitDeclaration(check!(
                 TraitDeclaration::parse_from_pair(decl_inner, config),
@@ -260,6 +225,6 @@ impl Declaration {
             )),
             a => unreachable!("declarations don't have any other sub-types: {:?}", a),
         };
-        ok(parsed_declaration, warnings, errors)
+        ok(vec![parsed_declaration], warnings, errors)
     }
 }
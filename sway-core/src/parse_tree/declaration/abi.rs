@@ -1,5 +1,10 @@
 use super::{FunctionDeclaration, TraitFn};
-use crate::{build_config::BuildConfig, error::*, parse_tree::ident, parser::Rule};
+use crate::{
+    build_config::BuildConfig,
+    error::*,
+    parse_tree::{attribute, ident, Attribute, AttributeTarget},
+    parser::Rule,
+};
 
 use sway_types::{ident::Ident, span::Span};
 
@@ -16,6 +21,7 @@ pub struct AbiDeclaration {
     /// The methods provided to a contract "for free" upon opting in to this interface
     pub(crate) methods: Vec<FunctionDeclaration>,
     pub(crate) span: Span,
+    pub(crate) attributes: Vec<Attribute>,
 }
 
 impl AbiDeclaration {
@@ -27,9 +33,15 @@ impl AbiDeclaration {
             span: pair.as_span(),
             path: config.map(|c| c.path()),
         };
-        let mut iter = pair.into_inner();
+        let mut iter = pair.into_inner().peekable();
         let mut warnings = Vec::new();
         let mut errors = Vec::new();
+        let attributes = check!(
+            Attribute::parse_leading(&mut iter, AttributeTarget::Abi, config),
+            Vec::new(),
+            warnings,
+            errors
+        );
         let _abi_keyword = iter.next().expect("guaranteed by grammar");
         let name = check!(
             ident::parse_from_pair(iter.next().expect("guaranteed by grammar"), config),
@@ -40,23 +52,41 @@ impl AbiDeclaration {
         let mut interface_surface = vec![];
         let mut methods = vec![];
         let trait_methods = iter.next().expect("guaranteed by grammar");
-        for func in trait_methods.into_inner() {
+        let mut trait_methods = trait_methods.into_inner().peekable();
+        while trait_methods.peek().is_some() {
+            let fn_attributes = check!(
+                Attribute::parse_leading(
+                    &mut trait_methods,
+                    AttributeTarget::FunctionSignature,
+                    config
+                ),
+                Vec::new(),
+                warnings,
+                errors
+            );
+            let func = trait_methods
+                .next()
+                .expect("attributes are followed by a signature");
             match func.as_rule() {
                 Rule::fn_signature => {
                     let fn_sig = check!(
-                        TraitFn::parse_from_pair(func, config),
+                        TraitFn::parse_from_pair(func, fn_attributes, config),
                         continue,
                         warnings,
                         errors
                     );
                     interface_surface.push(fn_sig);
                 }
-                Rule::fn_decl => methods.push(check!(
-                    FunctionDeclaration::parse_from_pair(func, config),
-                    continue,
-                    warnings,
-                    errors
-                )),
+                Rule::fn_decl => {
+                    let method = check!(
+                        FunctionDeclaration::parse_from_pair(func, config),
+                        continue,
+                        warnings,
+                        errors
+                    );
+                    check!(attribute::check_method(&method), (), warnings, errors);
+                    methods.push(method);
+                }
                 x => unreachable!("guaranteed to not be here: {:?}", x),
             }
         }
@@ -66,6 +96,7 @@ impl AbiDeclaration {
                 interface_surface,
                 name,
                 span,
+                attributes,
             },
             warnings,
             errors,
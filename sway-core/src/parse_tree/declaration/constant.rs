@@ -58,7 +58,7 @@ impl ConstantDeclaration {
             })
             .unwrap_or(TypeInfo::Unknown);
         let value = check!(
-            Expression::parse_from_pair_inner(maybe_value, config),
+            Expression::parse_from_pair(maybe_value, config),
             return err(warnings, errors),
             warnings,
             errors
@@ -1,7 +1,7 @@
 use crate::{
     build_config::BuildConfig,
     error::*,
-    parse_tree::{declaration::TypeParameter, ident, Visibility},
+    parse_tree::{declaration::TypeParameter, ident, Attribute, AttributeTarget, Visibility},
     parser::Rule,
     semantic_analysis::{
         ast_node::{declaration::insert_type_parameters, TypedEnumDeclaration, TypedEnumVariant},
@@ -22,6 +22,7 @@ pub struct EnumDeclaration {
     pub(crate) variants: Vec<EnumVariant>,
     pub(crate) span: Span,
     pub visibility: Visibility,
+    pub attributes: Vec<Attribute>,
 }
 
 #[derive(Debug, Clone)]
@@ -59,6 +60,7 @@ impl EnumDeclaration {
             variants: variants_buf,
             span: self.span.clone(),
             visibility: self.visibility,
+            attributes: self.attributes.clone(),
         }
     }
 
@@ -73,7 +75,13 @@ impl EnumDeclaration {
         };
         let mut warnings = Vec::new();
         let mut errors = Vec::new();
-        let inner = decl_inner.into_inner();
+        let mut inner = decl_inner.into_inner().peekable();
+        let attributes = check!(
+            Attribute::parse_leading(&mut inner, AttributeTarget::Enum, config),
+            Vec::new(),
+            warnings,
+            errors
+        );
         let mut visibility = Visibility::Private;
         let mut enum_name = None;
         let mut type_params = None;
@@ -142,6 +150,7 @@ impl EnumDeclaration {
                 variants,
                 span: whole_enum_span,
                 visibility,
+                attributes,
             },
             warnings,
             errors,
@@ -158,6 +167,10 @@ impl EnumVariant {
         type_mapping: &[(TypeParameter, TypeId)],
     ) -> CompileResult<TypedEnumVariant> {
         let mut errors = vec![];
+        // function values have no run-time representation
+        if let TypeInfo::Function { .. } = self.r#type {
+            errors.push(CompileError::MisplacedFunctionValue(span.clone()));
+        }
         let enum_variant_type =
             if let Some(matching_id) = self.r#type.matches_type_parameter(type_mapping) {
                 insert_type(TypeInfo::Ref(matching_id))
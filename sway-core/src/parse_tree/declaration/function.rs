@@ -1,7 +1,9 @@
 use crate::{
     build_config::BuildConfig,
     error::*,
-    parse_tree::{declaration::TypeParameter, ident, Visibility},
+    parse_tree::{
+        attribute, declaration::TypeParameter, ident, Attribute, AttributeTarget, Visibility,
+    },
     style::is_snake_case,
     type_engine::TypeInfo,
     CodeBlock, Rule,
@@ -25,14 +27,21 @@ pub struct FunctionDeclaration {
     pub(crate) return_type: TypeInfo,
     pub(crate) type_parameters: Vec<TypeParameter>,
     pub(crate) return_type_span: Span,
+    pub attributes: Vec<Attribute>,
 }
 
 impl FunctionDeclaration {
     pub fn parse_from_pair(pair: Pair<Rule>, config: Option<&BuildConfig>) -> CompileResult<Self> {
         let path = config.map(|c| c.path());
-        let mut parts = pair.clone().into_inner();
+        let mut parts = pair.clone().into_inner().peekable();
         let mut warnings = Vec::new();
         let mut errors = Vec::new();
+        let attributes = check!(
+            Attribute::parse_leading(&mut parts, AttributeTarget::Function, config),
+            Vec::new(),
+            warnings,
+            errors
+        );
         let signature_or_visibility = parts.next().unwrap();
         let (visibility, signature) = if signature_or_visibility.as_rule() == Rule::visibility {
             (
@@ -68,7 +77,7 @@ impl FunctionDeclaration {
         assert_or_warn!(
             is_snake_case(name.as_str()),
             warnings,
-            name_span,
+            name_span.clone(),
             Warning::NonSnakeCaseFunctionName { name: name.clone() }
         );
         let mut type_params_pair = None;
@@ -136,6 +145,17 @@ impl FunctionDeclaration {
             config,
         )
         .unwrap_or_else(&mut warnings, &mut errors, Vec::new);
+        // a test method taking `self` is reported as a misplaced attribute by its impl instead
+        let is_method = parameters.iter().any(|param| param.name.as_str() == "self");
+        if attribute::is_test(&attributes)
+            && !is_method
+            && !(parameters.is_empty() && type_parameters.is_empty())
+        {
+            errors.push(CompileError::InvalidTestFunction {
+                name: name.clone(),
+                span: name_span,
+            });
+        }
 
         let body = parts.next().unwrap();
         let whole_block_span = Span {
@@ -165,6 +185,7 @@ impl FunctionDeclaration {
                 },
                 return_type,
                 type_parameters,
+                attributes,
             },
             warnings,
             errors,
@@ -194,10 +215,13 @@ impl FunctionDeclaration {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub(crate) struct FunctionParameter {
+pub struct FunctionParameter {
     pub(crate) name: Ident,
     pub(crate) r#type: TypeInfo,
     pub(crate) type_span: Span,
+    /// Whether this is a `ref mut` parameter, or `&mut self`, which is passed a pointer to the
+    /// caller's variable so that the function can mutate it.
+    pub(crate) is_reference: bool,
 }
 
 impl FunctionParameter {
@@ -210,7 +234,13 @@ impl FunctionParameter {
         let mut errors = Vec::new();
         let mut pairs_buf = Vec::new();
         for pair in pairs {
-            if pair.as_str().trim() == "self" {
+            let is_self_reference = pair
+                .clone()
+                .into_inner()
+                .next()
+                .map(|part| part.as_rule() == Rule::ref_self_param)
+                .unwrap_or(false);
+            if is_self_reference || pair.as_str().trim() == "self" {
                 let type_span = Span {
                     span: pair.as_span(),
                     path: path.clone(),
@@ -227,10 +257,14 @@ impl FunctionParameter {
                     name,
                     r#type,
                     type_span,
+                    is_reference: is_self_reference,
                 });
                 continue;
             }
-            let mut parts = pair.clone().into_inner();
+            let mut parts = pair.clone().into_inner().peekable();
+            let is_reference = parts
+                .next_if(|part| part.as_rule() == Rule::ref_mut_keyword)
+                .is_some();
             let name_pair = parts.next().unwrap();
             let name = check!(
                 ident::parse_from_pair(name_pair, config),
@@ -253,6 +287,7 @@ impl FunctionParameter {
                 name,
                 r#type,
                 type_span,
+                is_reference,
             });
         }
         ok(pairs_buf, warnings, errors)
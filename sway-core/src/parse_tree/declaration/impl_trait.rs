@@ -1,6 +1,10 @@
-use super::{FunctionDeclaration, TypeParameter};
+use super::{FunctionDeclaration, TypeAliasDeclaration, TypeParameter};
 use crate::{
-    build_config::BuildConfig, error::*, parse_tree::CallPath, parser::Rule, type_engine::TypeInfo,
+    build_config::BuildConfig,
+    error::*,
+    parse_tree::{attribute, Attribute, AttributeTarget, CallPath},
+    parser::Rule,
+    type_engine::TypeInfo,
 };
 
 use sway_types::span::Span;
@@ -10,13 +14,18 @@ use pest::iterators::Pair;
 #[derive(Debug, Clone)]
 pub struct ImplTrait {
     pub(crate) trait_name: CallPath,
+    /// The types given for the trait's type parameters, e.g. `u64` in `impl From<u64> for X`.
+    pub(crate) trait_type_arguments: Vec<(TypeInfo, Span)>,
     pub(crate) type_implementing_for: TypeInfo,
     pub(crate) type_implementing_for_span: Span,
     pub(crate) type_arguments: Vec<TypeParameter>,
+    /// The types given for the trait's associated types, e.g. `type Output = u64;`.
+    pub(crate) associated_types: Vec<TypeAliasDeclaration>,
     pub functions: Vec<FunctionDeclaration>,
     // the span of the whole impl trait and block
     pub(crate) block_span: Span,
     pub(crate) type_arguments_span: Span,
+    pub attributes: Vec<Attribute>,
 }
 
 /// An impl of methods without a trait
@@ -33,6 +42,7 @@ pub struct ImplSelf {
     pub(crate) type_arguments_span: Span,
     #[allow(dead_code)]
     pub(crate) type_name_span: Span,
+    pub attributes: Vec<Attribute>,
 }
 
 impl ImplTrait {
@@ -47,7 +57,13 @@ impl ImplTrait {
             span: pair.as_span(),
             path: path.clone(),
         };
-        let mut iter = pair.into_inner();
+        let mut iter = pair.into_inner().peekable();
+        let attributes = check!(
+            Attribute::parse_leading(&mut iter, AttributeTarget::Impl, config),
+            Vec::new(),
+            warnings,
+            errors
+        );
         let impl_keyword = iter.next().unwrap();
         assert_eq!(impl_keyword.as_str(), "impl");
         let trait_name = iter.next().unwrap();
@@ -59,11 +75,27 @@ impl ImplTrait {
             errors
         );
         let mut iter = iter.peekable();
-        let type_params_pair = if iter.peek().unwrap().as_rule() == Rule::type_params {
+        let trait_type_args_pair = if iter.peek().unwrap().as_rule() == Rule::trait_type_args {
             iter.next()
         } else {
             None
         };
+        let mut trait_type_arguments = Vec::new();
+        if let Some(ref trait_type_args_pair) = trait_type_args_pair {
+            for type_pair in trait_type_args_pair.clone().into_inner() {
+                let type_span = Span {
+                    span: type_pair.as_span(),
+                    path: path.clone(),
+                };
+                let ty = check!(
+                    TypeInfo::parse_from_pair(type_pair, config),
+                    TypeInfo::ErrorRecovery,
+                    warnings,
+                    errors
+                );
+                trait_type_arguments.push((ty, type_span));
+            }
+        }
 
         let type_implementing_for_pair = iter.next().expect("guaranteed by grammar");
         let type_implementing_for_span = Span {
@@ -82,40 +114,53 @@ impl ImplTrait {
         } else {
             None
         };
-        let type_arguments_span = match type_params_pair {
+        let type_arguments_span = match trait_type_args_pair {
             Some(ref x) => Span {
                 span: x.as_span(),
                 path,
             },
             None => trait_name.span(),
         };
-        let type_arguments = TypeParameter::parse_from_type_params_and_where_clause(
-            type_params_pair,
-            where_clause_pair,
-            config,
-        )
-        .unwrap_or_else(&mut warnings, &mut errors, Vec::new);
+        let type_arguments =
+            TypeParameter::parse_from_type_params_and_where_clause(None, where_clause_pair, config)
+                .unwrap_or_else(&mut warnings, &mut errors, Vec::new);
 
+        let mut associated_types = vec![];
         let mut fn_decls_buf = vec![];
 
         for pair in iter {
-            fn_decls_buf.push(check!(
+            if pair.as_rule() == Rule::type_alias_decl {
+                associated_types.push(check!(
+                    TypeAliasDeclaration::parse_from_pair(pair, config),
+                    continue,
+                    warnings,
+                    errors
+                ));
+                continue;
+            }
+            let mut method = check!(
                 FunctionDeclaration::parse_from_pair(pair, config),
                 continue,
                 warnings,
                 errors
-            ));
+            );
+            check!(attribute::check_method(&method), (), warnings, errors);
+            attribute::inherit(&mut method.attributes, &attributes);
+            fn_decls_buf.push(method);
         }
 
         ok(
             ImplTrait {
                 trait_name,
+                trait_type_arguments,
                 type_arguments,
                 type_arguments_span,
                 type_implementing_for,
                 type_implementing_for_span,
+                associated_types,
                 functions: fn_decls_buf,
                 block_span,
+                attributes,
             },
             warnings,
             errors,
@@ -135,7 +180,13 @@ impl ImplSelf {
             span: pair.as_span(),
             path: path.clone(),
         };
-        let mut iter = pair.into_inner();
+        let mut iter = pair.into_inner().peekable();
+        let attributes = check!(
+            Attribute::parse_leading(&mut iter, AttributeTarget::Impl, config),
+            Vec::new(),
+            warnings,
+            errors
+        );
         let impl_keyword = iter.next().unwrap();
         assert_eq!(impl_keyword.as_str(), "impl");
         let mut iter = iter.peekable();
@@ -178,12 +229,15 @@ impl ImplSelf {
         let mut fn_decls_buf = vec![];
 
         for pair in iter {
-            fn_decls_buf.push(check!(
+            let mut method = check!(
                 FunctionDeclaration::parse_from_pair(pair, config),
                 continue,
                 warnings,
                 errors
-            ));
+            );
+            check!(attribute::check_method(&method), (), warnings, errors);
+            attribute::inherit(&mut method.attributes, &attributes);
+            fn_decls_buf.push(method);
         }
 
         ok(
@@ -194,6 +248,7 @@ impl ImplSelf {
                 functions: fn_decls_buf,
                 type_name_span,
                 block_span,
+                attributes,
             },
             warnings,
             errors,
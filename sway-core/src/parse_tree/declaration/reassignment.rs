@@ -1,7 +1,7 @@
 use crate::{
     build_config::BuildConfig,
     error::{err, ok, CompileError, CompileResult},
-    parse_array_index,
+    parse_array_index, parse_storage_access,
     parse_tree::{ident, Expression},
     parser::Rule,
 };
@@ -130,6 +130,39 @@ impl Reassignment {
                     errors,
                 )
             }
+            Rule::storage_reassignment => {
+                let mut iter = variable_or_struct_reassignment.into_inner();
+                let lhs = check!(
+                    parse_storage_access(iter.next().expect("guaranteed by grammar"), config),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                );
+                let rhs = iter.next().expect("guaranteed by grammar");
+                let rhs_span = Span {
+                    span: rhs.as_span(),
+                    path,
+                };
+                let body = check!(
+                    Expression::parse_from_pair(rhs, config),
+                    Expression::Tuple {
+                        fields: vec![],
+                        span: rhs_span
+                    },
+                    warnings,
+                    errors
+                );
+
+                ok(
+                    Reassignment {
+                        lhs: Box::new(lhs),
+                        rhs: body,
+                        span,
+                    },
+                    warnings,
+                    errors,
+                )
+            }
             _ => unreachable!("guaranteed by grammar"),
         }
     }
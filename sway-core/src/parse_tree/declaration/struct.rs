@@ -1,7 +1,7 @@
 use crate::{
     build_config::BuildConfig,
     error::*,
-    parse_tree::{declaration::TypeParameter, ident, Visibility},
+    parse_tree::{declaration::TypeParameter, ident, Attribute, AttributeTarget, Visibility},
     parser::Rule,
     style::{is_snake_case, is_upper_camel_case},
     type_engine::TypeInfo,
@@ -17,6 +17,7 @@ pub struct StructDeclaration {
     pub(crate) fields: Vec<StructField>,
     pub(crate) type_parameters: Vec<TypeParameter>,
     pub visibility: Visibility,
+    pub attributes: Vec<Attribute>,
 }
 
 #[derive(Debug, Clone)]
@@ -35,7 +36,13 @@ impl StructDeclaration {
         let path = config.map(|c| c.path());
         let mut warnings = Vec::new();
         let mut errors = Vec::new();
-        let decl = decl.into_inner();
+        let mut decl = decl.into_inner().peekable();
+        let attributes = check!(
+            Attribute::parse_leading(&mut decl, AttributeTarget::Struct, config),
+            Vec::new(),
+            warnings,
+            errors
+        );
         let mut visibility = Visibility::Private;
         let mut name = None;
         let mut type_params_pair = None;
@@ -106,6 +113,7 @@ impl StructDeclaration {
                 fields,
                 type_parameters,
                 visibility,
+                attributes,
             },
             warnings,
             errors,
@@ -1,9 +1,9 @@
-use super::{FunctionDeclaration, FunctionParameter};
+use super::{FunctionDeclaration, FunctionParameter, Purity};
 
 use crate::{
     build_config::BuildConfig,
     error::*,
-    parse_tree::{ident, TypeParameter, Visibility},
+    parse_tree::{attribute, ident, Attribute, AttributeTarget, TypeParameter, Visibility},
     parser::Rule,
     style::{is_snake_case, is_upper_camel_case},
     type_engine::TypeInfo,
@@ -19,7 +19,10 @@ pub struct TraitDeclaration {
     pub(crate) interface_surface: Vec<TraitFn>,
     pub(crate) methods: Vec<FunctionDeclaration>,
     pub(crate) type_parameters: Vec<TypeParameter>,
+    /// The types declared with `type Name;`, which each implementation gives.
+    pub(crate) associated_types: Vec<Ident>,
     pub visibility: Visibility,
+    pub attributes: Vec<Attribute>,
 }
 
 impl TraitDeclaration {
@@ -30,6 +33,12 @@ impl TraitDeclaration {
         let mut warnings = Vec::new();
         let mut errors = Vec::new();
         let mut trait_parts = pair.into_inner().peekable();
+        let attributes = check!(
+            Attribute::parse_leading(&mut trait_parts, AttributeTarget::Trait, config),
+            Vec::new(),
+            warnings,
+            errors
+        );
         let trait_keyword_or_visibility = trait_parts.next().unwrap();
         let (visibility, _trait_keyword) =
             if trait_keyword_or_visibility.as_rule() == Rule::visibility {
@@ -58,6 +67,7 @@ impl TraitDeclaration {
         let mut where_clause_pair = None;
         let mut methods = Vec::new();
         let mut interface = Vec::new();
+        let mut associated_types = Vec::new();
 
         for _ in 0..2 {
             match trait_parts.peek().map(|x| x.as_rule()) {
@@ -72,23 +82,51 @@ impl TraitDeclaration {
         }
 
         if let Some(methods_and_interface) = trait_parts.next() {
-            for fn_sig_or_decl in methods_and_interface.into_inner() {
+            let mut methods_and_interface = methods_and_interface.into_inner().peekable();
+            while methods_and_interface.peek().is_some() {
+                let fn_attributes = check!(
+                    Attribute::parse_leading(
+                        &mut methods_and_interface,
+                        AttributeTarget::FunctionSignature,
+                        config
+                    ),
+                    Vec::new(),
+                    warnings,
+                    errors
+                );
+                let fn_sig_or_decl = methods_and_interface
+                    .next()
+                    .expect("attributes are followed by a signature");
                 match fn_sig_or_decl.as_rule() {
+                    Rule::associated_type_decl => {
+                        let name = fn_sig_or_decl
+                            .into_inner()
+                            .find(|pair| pair.as_rule() == Rule::type_alias_name)
+                            .expect("guaranteed by grammar");
+                        associated_types.push(check!(
+                            ident::parse_from_pair(name, config),
+                            continue,
+                            warnings,
+                            errors
+                        ));
+                    }
                     Rule::fn_signature => {
                         interface.push(check!(
-                            TraitFn::parse_from_pair(fn_sig_or_decl, config),
+                            TraitFn::parse_from_pair(fn_sig_or_decl, fn_attributes, config),
                             continue,
                             warnings,
                             errors
                         ));
                     }
                     Rule::fn_decl => {
-                        methods.push(check!(
+                        let method = check!(
                             FunctionDeclaration::parse_from_pair(fn_sig_or_decl, config),
                             continue,
                             warnings,
                             errors
-                        ));
+                        );
+                        check!(attribute::check_method(&method), (), warnings, errors);
+                        methods.push(method);
                     }
                     a => unreachable!("{:?}", a),
                 }
@@ -104,10 +142,12 @@ impl TraitDeclaration {
         ok(
             TraitDeclaration {
                 type_parameters,
+                associated_types,
                 name,
                 interface_surface: interface,
                 methods,
                 visibility,
+                attributes,
             },
             warnings,
             errors,
@@ -117,21 +157,34 @@ impl TraitDeclaration {
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) struct TraitFn {
+    pub(crate) purity: Purity,
     pub(crate) name: Ident,
     pub(crate) parameters: Vec<FunctionParameter>,
     pub(crate) return_type: TypeInfo,
     pub(crate) return_type_span: Span,
+    pub(crate) attributes: Vec<Attribute>,
 }
 
 impl TraitFn {
     pub(crate) fn parse_from_pair(
         pair: Pair<Rule>,
+        attributes: Vec<Attribute>,
         config: Option<&BuildConfig>,
     ) -> CompileResult<Self> {
         let path = config.map(|c| c.path());
         let mut warnings = Vec::new();
         let mut errors = Vec::new();
-        let mut signature = pair.into_inner();
+        let mut signature = pair.into_inner().peekable();
+        let purity = if signature
+            .peek()
+            .map(|x| x.as_rule() == Rule::impurity_keyword)
+            .unwrap_or(false)
+        {
+            let _ = signature.next();
+            Purity::Impure
+        } else {
+            Purity::Pure
+        };
         let _fn_keyword = signature.next().unwrap();
         let name = signature.next().unwrap();
         let name_span = Span {
@@ -230,10 +283,12 @@ impl TraitFn {
 
         ok(
             TraitFn {
+                purity,
                 name,
                 parameters,
                 return_type,
                 return_type_span,
+                attributes,
             },
             warnings,
             errors,
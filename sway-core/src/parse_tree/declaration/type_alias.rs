@@ -0,0 +1,101 @@
+use crate::{
+    build_config::BuildConfig,
+    error::*,
+    parse_tree::{declaration::TypeParameter, ident, Visibility},
+    parser::Rule,
+    style::is_upper_camel_case,
+    type_engine::TypeInfo,
+};
+
+use sway_types::{ident::Ident, span::Span};
+
+use pest::iterators::Pair;
+
+/// A `type Name<T> = ...;` declaration, which gives another name to the type on its right hand
+/// side.
+#[derive(Debug, Clone)]
+pub struct TypeAliasDeclaration {
+    pub name: Ident,
+    pub(crate) type_parameters: Vec<TypeParameter>,
+    pub ty: TypeInfo,
+    pub(crate) type_span: Span,
+    pub visibility: Visibility,
+}
+
+impl TypeAliasDeclaration {
+    pub(crate) fn parse_from_pair(
+        decl: Pair<Rule>,
+        config: Option<&BuildConfig>,
+    ) -> CompileResult<Self> {
+        let path = config.map(|c| c.path());
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+        let mut visibility = Visibility::Private;
+        let mut name = None;
+        let mut type_params_pair = None;
+        let mut type_pair = None;
+        for pair in decl.into_inner() {
+            match pair.as_rule() {
+                Rule::visibility => {
+                    visibility = Visibility::parse_from_pair(pair);
+                }
+                Rule::type_alias_keyword => (),
+                Rule::type_alias_name => {
+                    name = Some(pair);
+                }
+                Rule::type_params => {
+                    type_params_pair = Some(pair);
+                }
+                Rule::type_name => {
+                    type_pair = Some(pair);
+                }
+                a => unreachable!("{:?}", a),
+            }
+        }
+        let name = name.expect("guaranteed to exist by grammar");
+        let type_pair = type_pair.expect("guaranteed to exist by grammar");
+
+        let type_parameters =
+            TypeParameter::parse_from_type_params_and_where_clause(type_params_pair, None, config)
+                .unwrap_or_else(&mut warnings, &mut errors, Vec::new);
+
+        let type_span = Span {
+            span: type_pair.as_span(),
+            path: path.clone(),
+        };
+        let ty = check!(
+            TypeInfo::parse_from_pair(type_pair, config),
+            TypeInfo::ErrorRecovery,
+            warnings,
+            errors
+        );
+
+        let span = Span {
+            span: name.as_span(),
+            path,
+        };
+        let name = check!(
+            ident::parse_from_pair(name, config),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        assert_or_warn!(
+            is_upper_camel_case(name.as_str()),
+            warnings,
+            span,
+            Warning::NonClassCaseTypeAliasName { name: name.clone() }
+        );
+        ok(
+            TypeAliasDeclaration {
+                name,
+                type_parameters,
+                ty,
+                type_span,
+                visibility,
+            },
+            warnings,
+            errors,
+        )
+    }
+}
@@ -1,7 +1,16 @@
-use crate::{parse_tree::Expression, type_engine::TypeInfo, Ident};
+use crate::{
+    build_config::BuildConfig,
+    error::*,
+    parse_tree::{ident, matcher, Expression, Scrutinee},
+    parser::Rule,
+    type_engine::{insert_type, TypeInfo},
+    Ident,
+};
 
 use sway_types::span::Span;
 
+use pest::iterators::Pair;
+
 #[derive(Debug, Clone)]
 pub struct VariableDeclaration {
     pub name: Ident,
@@ -10,3 +19,169 @@ pub struct VariableDeclaration {
     pub body: Expression, // will be codeblock variant
     pub is_mutable: bool,
 }
+
+impl VariableDeclaration {
+    /// Parses a `let` statement, which declares a single variable unless it destructures a
+    /// tuple or struct.  A destructuring `let` is desugared, using the same [Scrutinee] matching
+    /// as a match expression, into a hidden variable holding the whole value followed by a
+    /// declaration for each variable in the pattern.
+    ///
+    /// ```ignore
+    /// let (a, Point { mut x, y: b }) = f();
+    /// ```
+    ///
+    /// becomes roughly:
+    ///
+    /// ```ignore
+    /// let __destructured_4 = f();
+    /// let a = __destructured_4.0;
+    /// let mut x = __destructured_4.1.x;
+    /// let b = __destructured_4.1.y;
+    /// ```
+    pub(crate) fn parse_from_pair(
+        pair: Pair<Rule>,
+        config: Option<&BuildConfig>,
+    ) -> CompileResult<Vec<Self>> {
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+        let mut var_decl_parts = pair.into_inner();
+        let _let_keyword = var_decl_parts.next();
+        let maybe_mut_keyword = var_decl_parts.next().unwrap();
+        let is_mutable = maybe_mut_keyword.as_rule() == Rule::mut_keyword;
+        let name_pair = if is_mutable {
+            var_decl_parts.next().unwrap()
+        } else {
+            maybe_mut_keyword
+        };
+        let mut maybe_body = var_decl_parts.next().unwrap();
+        let type_ascription = match maybe_body.as_rule() {
+            Rule::type_ascription => {
+                let type_asc = maybe_body.clone();
+                maybe_body = var_decl_parts.next().unwrap();
+                Some(type_asc)
+            }
+            _ => None,
+        };
+        let type_ascription_span = type_ascription
+            .clone()
+            .map(|x| x.into_inner().next().unwrap().as_span())
+            .map(|type_ascription_span| Span {
+                span: type_ascription_span,
+                path: config.map(|x| x.path()),
+            });
+        let type_ascription = if let Some(ascription) = type_ascription {
+            let type_name = ascription.into_inner().next().unwrap();
+            check!(
+                TypeInfo::parse_from_pair(type_name, config),
+                TypeInfo::Tuple(Vec::new()),
+                warnings,
+                errors
+            )
+        } else {
+            TypeInfo::Unknown
+        };
+        let body = check!(
+            Expression::parse_from_pair(maybe_body, config),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+
+        if name_pair.as_rule() != Rule::var_pattern {
+            let var_decl = VariableDeclaration {
+                name: check!(
+                    ident::parse_from_pair(name_pair, config),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                ),
+                body,
+                is_mutable,
+                type_ascription,
+                type_ascription_span,
+            };
+            return ok(vec![var_decl], warnings, errors);
+        }
+
+        let pattern = check!(
+            Scrutinee::parse_from_pair(name_pair, config),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        if let Some(span) = pattern.refutable_span() {
+            errors.push(CompileError::RefutablePatternInLet { span });
+            return err(warnings, errors);
+        }
+
+        // The hidden variable is named after where the pattern is, so that it doesn't shadow
+        // another one in the same scope.  If there's no type ascription, the shape of the pattern
+        // is used instead so that, e.g., the number of elements in a tuple is checked.
+        let pattern_span = pattern.span();
+        let destructured_name = format!("__destructured_{}", pattern_span.start());
+        let destructured_name = Ident::new(Span {
+            span: pest::Span::new(
+                destructured_name.as_str().into(),
+                0,
+                destructured_name.len(),
+            )
+            .unwrap(),
+            path: None,
+        });
+        let (type_ascription, type_ascription_span) = match type_ascription {
+            TypeInfo::Unknown => (pattern_type(&pattern), Some(pattern_span.clone())),
+            type_ascription => (type_ascription, type_ascription_span),
+        };
+        let destructured_exp = Expression::VariableExpression {
+            name: destructured_name.clone(),
+            span: pattern_span.clone(),
+        };
+        let match_impl_map = match check!(
+            matcher(&destructured_exp, &pattern),
+            return err(warnings, errors),
+            warnings,
+            errors
+        ) {
+            Some((_match_req_map, match_impl_map)) => match_impl_map,
+            None => {
+                errors.push(CompileError::PatternMatchingAlgorithmFailure(
+                    "found None",
+                    pattern_span,
+                ));
+                return err(warnings, errors);
+            }
+        };
+
+        let mut var_decls = vec![VariableDeclaration {
+            name: destructured_name,
+            body,
+            is_mutable: false,
+            type_ascription,
+            type_ascription_span,
+        }];
+        var_decls.extend(match_impl_map.into_iter().map(|(name, body, is_mutable)| {
+            VariableDeclaration {
+                name,
+                body,
+                is_mutable,
+                type_ascription: TypeInfo::Unknown,
+                type_ascription_span: None,
+            }
+        }));
+        ok(var_decls, warnings, errors)
+    }
+}
+
+/// The type of value which an irrefutable `pattern` destructures, as far as can be told from the
+/// pattern alone.
+fn pattern_type(pattern: &Scrutinee) -> TypeInfo {
+    match pattern {
+        Scrutinee::Tuple { elems, .. } => TypeInfo::Tuple(
+            elems
+                .iter()
+                .map(|elem| insert_type(pattern_type(elem)))
+                .collect(),
+        ),
+        _ => TypeInfo::Unknown,
+    }
+}
@@ -1,16 +1,18 @@
 use crate::{
     error::{err, ok},
-    CallPath, CompileError, CompileResult, DelayedEnumVariantResolution, DelayedResolutionVariant,
-    DelayedStructFieldResolution, DelayedTupleVariantResolution, Expression, Ident, Literal,
-    Scrutinee, StructScrutineeField,
+    CallPath, CompileError, CompileResult, DelayedEnumVariantCheckResolution,
+    DelayedEnumVariantResolution, DelayedResolutionVariant, DelayedStructFieldResolution,
+    DelayedTupleVariantResolution, Expression, Ident, Literal, Scrutinee, StructScrutineeField,
 };
 
-use sway_types::span::Span;
+use sway_types::span::{join_spans, Span};
 
-/// List of requirements that a desugared if expression must include in the conditional.
-pub type MatchReqMap = Vec<(Expression, Expression)>;
-/// List of variable declarations that must be placed inside of the body of the if expression.
-pub type MatchImplMap = Vec<(Ident, Expression)>;
+/// List of requirements that a desugared if expression must include in the conditional, each of
+/// which is a boolean expression.
+pub type MatchReqMap = Vec<Expression>;
+/// List of variable declarations that must be placed inside of the body of the if expression,
+/// along with whether each is mutable.
+pub type MatchImplMap = Vec<(Ident, Expression, bool)>;
 /// This is the result type given back by the matcher.
 pub type MatcherResult = Option<(MatchReqMap, MatchImplMap)>;
 
@@ -43,7 +45,7 @@ pub type MatcherResult = Option<(MatchReqMap, MatchImplMap)>;
 ///
 /// ```ignore
 /// [
-///     (y, 5) // y must equal 5 to trigger this case
+///     y == 5 // y must equal 5 to trigger this case
 /// ]
 /// ```
 ///
@@ -51,7 +53,7 @@ pub type MatcherResult = Option<(MatchReqMap, MatchImplMap)>;
 ///
 /// ```ignore
 /// [
-///     (x, 42) // add `let x = 42` in the body of the desugared if expression
+///     (x, 42, false) // add `let x = 42` in the body of the desugared if expression
 /// ]
 /// ```
 pub fn matcher(exp: &Expression, scrutinee: &Scrutinee) -> CompileResult<MatcherResult> {
@@ -59,7 +61,11 @@ pub fn matcher(exp: &Expression, scrutinee: &Scrutinee) -> CompileResult<Matcher
     let warnings = vec![];
     match scrutinee {
         Scrutinee::Literal { value, span } => match_literal(exp, value, span),
-        Scrutinee::Variable { name, span } => match_variable(exp, name, span),
+        Scrutinee::Variable {
+            name,
+            is_mutable,
+            span,
+        } => match_variable(exp, name, *is_mutable, span),
         Scrutinee::StructScrutinee {
             struct_name,
             fields,
@@ -87,12 +93,15 @@ fn match_literal(
     scrutinee: &Literal,
     scrutinee_span: &Span,
 ) -> CompileResult<MatcherResult> {
-    let match_req_map = vec![(
-        exp.to_owned(),
-        Expression::Literal {
-            value: scrutinee.clone(),
-            span: scrutinee_span.clone(),
-        },
+    let match_req_map = vec![Expression::core_ops_eq(
+        vec![
+            exp.to_owned(),
+            Expression::Literal {
+                value: scrutinee.clone(),
+                span: scrutinee_span.clone(),
+            },
+        ],
+        join_spans(exp.span(), scrutinee_span.clone()),
     )];
     let match_impl_map = vec![];
     ok(Some((match_req_map, match_impl_map)), vec![], vec![])
@@ -101,10 +110,11 @@ fn match_literal(
 fn match_variable(
     exp: &Expression,
     scrutinee_name: &Ident,
+    is_mutable: bool,
     _span: &Span,
 ) -> CompileResult<MatcherResult> {
     let match_req_map = vec![];
-    let match_impl_map = vec![(scrutinee_name.to_owned(), exp.to_owned())];
+    let match_impl_map = vec![(scrutinee_name.to_owned(), exp.to_owned(), is_mutable)];
     ok(Some((match_req_map, match_impl_map)), vec![], vec![])
 }
 
@@ -132,7 +142,7 @@ fn match_struct(
         match scrutinee {
             // if the scrutinee is simply naming the struct field ...
             None => {
-                match_impl_map.push((field_name.clone(), delayed_resolution_exp));
+                match_impl_map.push((field_name.clone(), delayed_resolution_exp, field.is_mutable));
             }
             // or if the scrutinee has a more complex agenda
             Some(scrutinee) => {
@@ -164,7 +174,14 @@ fn match_enum(
 ) -> CompileResult<MatcherResult> {
     let mut warnings = vec![];
     let mut errors = vec![];
-    let mut match_req_map = vec![];
+    // the value must be of the variant before its args are matched
+    let mut match_req_map = vec![Expression::DelayedMatchTypeResolution {
+        variant: DelayedResolutionVariant::EnumVariantCheck(DelayedEnumVariantCheckResolution {
+            exp: Box::new(exp.clone()),
+            call_path: call_path.to_owned(),
+        }),
+        span: span.clone(),
+    }];
     let mut match_impl_map = vec![];
     for (pos, arg) in args.iter().enumerate() {
         let delayed_resolution_exp = Expression::DelayedMatchTypeResolution {
@@ -1,9 +1,9 @@
 use crate::{
     build_config::BuildConfig,
     error::*,
-    parse_tree::{ident, CallPath, Literal},
+    parse_tree::{ident, CallPath, FunctionParameter, Literal},
     parser::Rule,
-    type_engine::TypeInfo,
+    type_engine::{IntegerBits, TypeInfo},
     AstNode, AstNodeContent, CodeBlock, Declaration, VariableDeclaration,
 };
 
@@ -12,7 +12,6 @@ use sway_types::{ident::Ident, join_spans, Span};
 use either::Either;
 use pest;
 use pest::iterators::Pair;
-use std::collections::VecDeque;
 
 mod asm;
 mod match_branch;
@@ -25,7 +24,7 @@ pub(crate) use asm::*;
 pub(crate) use match_branch::MatchBranch;
 pub(crate) use match_condition::CatchAll;
 pub(crate) use match_condition::MatchCondition;
-use matcher::matcher;
+pub(crate) use matcher::{matcher, MatchImplMap, MatchReqMap};
 pub(crate) use method_name::MethodName;
 pub(crate) use scrutinee::{Scrutinee, StructScrutineeField};
 pub(crate) use unary_op::UnaryOp;
@@ -93,7 +92,7 @@ pub enum Expression {
     },
     /// A _subfield expression_ is anything of the form:
     /// ```ignore
-    /// <ident>.<ident>
+    /// <expr>.<ident>
     /// ```
     ///
     SubfieldExpression {
@@ -139,6 +138,16 @@ pub enum Expression {
         index: Box<Expression>,
         span: Span,
     },
+    /// A _tuple index_ accesses an element of a tuple by its position:
+    /// ```ignore
+    /// <expr>.<index>
+    /// ```
+    TupleIndex {
+        prefix: Box<Expression>,
+        index: usize,
+        index_span: Span,
+        span: Span,
+    },
     /// This variant serves as a stand-in for parsing-level match expression desugaring.
     /// Because types cannot be known at parsing-time, a desugared struct or enum gets
     /// special cased into this variant. During type checking, this variant is removed
@@ -149,12 +158,33 @@ pub enum Expression {
         variant: DelayedResolutionVariant,
         span: Span,
     },
+    /// A read of a field in the contract's storage block, written as:
+    /// ```ignore
+    /// storage.<ident>
+    /// ```
+    StorageAccess {
+        field_name: Ident,
+        span: Span,
+    },
+    /// A closure, written as:
+    /// ```ignore
+    /// |<ident>: <type>, ..| <expr>
+    /// |<ident>: <type>, ..| -> <type> { .. }
+    /// ```
+    /// The parameter types may be left out, to be inferred from where the closure is used.
+    Closure {
+        parameters: Vec<FunctionParameter>,
+        return_type: TypeInfo,
+        body: Box<Expression>,
+        span: Span,
+    },
 }
 
 #[derive(Debug, Clone)]
 pub enum DelayedResolutionVariant {
     StructField(DelayedStructFieldResolution),
     EnumVariant(DelayedEnumVariantResolution),
+    EnumVariantCheck(DelayedEnumVariantCheckResolution),
     TupleVariant(DelayedTupleVariantResolution),
 }
 
@@ -174,6 +204,14 @@ pub struct DelayedEnumVariantResolution {
     pub arg_num: usize,
 }
 
+/// During type checking, this gets replaced with a comparison of the tag of the enum with that of
+/// the variant named by `call_path`.
+#[derive(Debug, Clone)]
+pub struct DelayedEnumVariantCheckResolution {
+    pub exp: Box<Expression>,
+    pub call_path: CallPath,
+}
+
 /// During type checking, this gets replaced with tuple arg access.
 #[derive(Debug, Clone)]
 pub struct DelayedTupleVariantResolution {
@@ -264,7 +302,10 @@ impl Expression {
             DelineatedPath { span, .. } => span,
             AbiCast { span, .. } => span,
             ArrayIndex { span, .. } => span,
+            TupleIndex { span, .. } => span,
             DelayedMatchTypeResolution { span, .. } => span,
+            StorageAccess { span, .. } => span,
+            Closure { span, .. } => span,
         })
         .clone()
     }
@@ -520,12 +561,11 @@ impl Expression {
                     );
                     branches.push(res);
                 }
-                check!(
-                    desugar_match_expression(primary_expression, branches, span),
-                    return err(warnings, errors),
-                    warnings,
-                    errors
-                )
+                Expression::MatchExpression {
+                    primary_expression: Box::new(primary_expression),
+                    branches,
+                    span,
+                }
             }
             Rule::struct_expression => {
                 let mut expr_iter = expr.into_inner();
@@ -661,171 +701,108 @@ impl Expression {
                     span: whole_block_span,
                 }
             }
-            Rule::method_exp => {
-                let whole_exp_span = Span {
-                    span: expr.as_span(),
+            Rule::postfix_expr => {
+                let mut parts = expr.into_inner();
+                let primary = parts.next().expect("guaranteed by grammar");
+                let primary_span = Span {
+                    span: primary.as_span(),
                     path: path.clone(),
                 };
-                let mut parts = expr.into_inner();
-                let pair = parts.next().unwrap();
-                match pair.as_rule() {
-                    Rule::subfield_exp => {
-                        let mut pair = pair.into_inner();
-                        let mut name_parts = pair
-                            .next()
-                            .expect("Guaranteed by grammar.")
-                            .into_inner()
-                            .collect::<Vec<_>>();
-                        let function_arguments =
-                            pair.next().expect("Guaranteed by grammar").into_inner();
-                        // remove the last field from the subfield exp, since it is the method name
-                        // the different parts of the exp
-                        // e.g.
-                        // if the method_exp is a.b.c.add()
-                        // then these parts are
-                        //
-                        // ["a", "b", "c", "add"]
-                        let method_name = check!(
-                            ident::parse_from_pair(name_parts.pop().unwrap(), config),
-                            return err(warnings, errors),
-                            warnings,
-                            errors
-                        );
-                        let mut arguments_buf = VecDeque::new();
-                        for argument in function_arguments {
-                            let arg = check!(
-                                Expression::parse_from_pair(argument.clone(), config),
-                                Expression::Tuple {
-                                    fields: vec![],
-                                    span: Span {
-                                        span: argument.as_span(),
-                                        path: path.clone()
-                                    }
-                                },
+                let mut expr = check!(
+                    Expression::parse_from_pair_inner(primary, config),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                );
+                // each postfix operation applies to everything to its left, e.g. `a.b().c[0]` is
+                // `((a.b()).c)[0]`
+                for op in parts {
+                    expr = check!(
+                        parse_postfix_op(expr, op, &primary_span, config),
+                        return err(warnings, errors),
+                        warnings,
+                        errors
+                    );
+                }
+                expr
+            }
+            Rule::fully_qualified_method => {
+                let mut path_parts_buf = vec![];
+                let mut type_name = None;
+                let mut method_name = None;
+                let mut arguments = None;
+                for pair in expr.into_inner() {
+                    match pair.as_rule() {
+                        Rule::path_separator => (),
+                        Rule::path_ident => {
+                            path_parts_buf.push(check!(
+                                ident::parse_from_pair(pair, config),
+                                continue,
                                 warnings,
                                 errors
-                            );
-                            arguments_buf.push_back(arg);
+                            ));
+                        }
+                        Rule::type_name => {
+                            type_name = Some(pair);
                         }
-                        // the first thing is either an exp or a var, everything subsequent must be
-                        // a field
-                        let mut name_parts = name_parts.into_iter();
-                        let mut expr = check!(
-                            parse_subfield_path(
-                                name_parts.next().expect("guaranteed by grammar"),
+                        Rule::call_item => {
+                            method_name = Some(pair);
+                        }
+                        Rule::fn_args => {
+                            arguments = Some(pair);
+                        }
+                        a => unreachable!("guaranteed by grammar: {:?}", a),
+                    }
+                }
+                let type_name = check!(
+                    TypeInfo::parse_from_pair(type_name.expect("guaranteed by grammar"), config),
+                    TypeInfo::ErrorRecovery,
+                    warnings,
+                    errors
+                );
+
+                // parse the method name into a call path
+                let method_name = MethodName::FromType {
+                    call_path: CallPath {
+                        prefixes: path_parts_buf,
+                        suffix: check!(
+                            ident::parse_from_pair(
+                                method_name.expect("guaranteed by grammar"),
                                 config
                             ),
                             return err(warnings, errors),
                             warnings,
                             errors
-                        );
+                        ),
+                    },
+                    type_name: Some(type_name),
+                    is_absolute: false,
+                };
 
-                        for name_part in name_parts {
-                            expr = Expression::SubfieldExpression {
-                                prefix: Box::new(expr.clone()),
+                let mut arguments_buf = vec![];
+                // evaluate  the arguments passed in to the method
+                if let Some(arguments) = arguments {
+                    for argument in arguments.into_inner() {
+                        let arg = check!(
+                            Expression::parse_from_pair(argument.clone(), config),
+                            Expression::Tuple {
+                                fields: vec![],
                                 span: Span {
-                                    span: name_part.as_span(),
-                                    path: path.clone(),
-                                },
-                                field_to_access: check!(
-                                    ident::parse_from_pair(name_part, config),
-                                    continue,
-                                    warnings,
-                                    errors
-                                ),
-                            }
-                        }
-
-                        arguments_buf.push_front(expr);
-                        Expression::MethodApplication {
-                            method_name: MethodName::FromModule { method_name },
-                            arguments: arguments_buf.into_iter().collect(),
-                            span: whole_exp_span,
-                        }
-                    }
-                    Rule::fully_qualified_method => {
-                        let mut path_parts_buf = vec![];
-                        let mut type_name = None;
-                        let mut method_name = None;
-                        let mut arguments = None;
-                        for pair in pair.into_inner() {
-                            match pair.as_rule() {
-                                Rule::path_separator => (),
-                                Rule::path_ident => {
-                                    path_parts_buf.push(check!(
-                                        ident::parse_from_pair(pair, config),
-                                        continue,
-                                        warnings,
-                                        errors
-                                    ));
-                                }
-                                Rule::type_name => {
-                                    type_name = Some(pair);
+                                    span: argument.as_span(),
+                                    path: path.clone()
                                 }
-                                Rule::call_item => {
-                                    method_name = Some(pair);
-                                }
-                                Rule::fn_args => {
-                                    arguments = Some(pair);
-                                }
-                                a => unreachable!("guaranteed by grammar: {:?}", a),
-                            }
-                        }
-                        let type_name = check!(
-                            TypeInfo::parse_from_pair(
-                                type_name.expect("guaranteed by grammar"),
-                                config
-                            ),
-                            TypeInfo::ErrorRecovery,
+                            },
                             warnings,
                             errors
                         );
-
-                        // parse the method name into a call path
-                        let method_name = MethodName::FromType {
-                            call_path: CallPath {
-                                prefixes: path_parts_buf,
-                                suffix: check!(
-                                    ident::parse_from_pair(
-                                        method_name.expect("guaranteed by grammar"),
-                                        config
-                                    ),
-                                    return err(warnings, errors),
-                                    warnings,
-                                    errors
-                                ),
-                            },
-                            type_name: Some(type_name),
-                            is_absolute: false,
-                        };
-
-                        let mut arguments_buf = vec![];
-                        // evaluate  the arguments passed in to the method
-                        if let Some(arguments) = arguments {
-                            for argument in arguments.into_inner() {
-                                let arg = check!(
-                                    Expression::parse_from_pair(argument.clone(), config),
-                                    Expression::Tuple {
-                                        fields: vec![],
-                                        span: Span {
-                                            span: argument.as_span(),
-                                            path: path.clone()
-                                        }
-                                    },
-                                    warnings,
-                                    errors
-                                );
-                                arguments_buf.push(arg);
-                            }
-                        }
-
-                        Expression::MethodApplication {
-                            method_name,
-                            arguments: arguments_buf,
-                            span: whole_exp_span,
-                        }
+                        arguments_buf.push(arg);
                     }
-                    a => unreachable!("{:?}", a),
+                }
+
+                Expression::MethodApplication {
+                    method_name,
+                    arguments: arguments_buf,
+                    span,
                 }
             }
             Rule::delineated_path => {
@@ -892,36 +869,12 @@ impl Expression {
                     span,
                 }
             }
-            Rule::struct_field_access => {
-                let inner = expr.into_inner().next().expect("guaranteed by grammar");
-                assert_eq!(inner.as_rule(), Rule::subfield_path);
-
-                let mut name_parts = inner.into_inner();
-                let mut expr = check!(
-                    parse_subfield_path(name_parts.next().expect("guaranteed by grammar"), config),
-                    return err(warnings, errors),
-                    warnings,
-                    errors
-                );
-
-                for name_part in name_parts {
-                    expr = Expression::SubfieldExpression {
-                        prefix: Box::new(expr.clone()),
-                        span: Span {
-                            span: name_part.as_span(),
-                            path: path.clone(),
-                        },
-                        field_to_access: check!(
-                            ident::parse_from_pair(name_part, config),
-                            continue,
-                            warnings,
-                            errors
-                        ),
-                    }
-                }
-
-                expr
-            }
+            Rule::storage_access => check!(
+                parse_storage_access(expr, config),
+                return err(warnings, errors),
+                warnings,
+                errors
+            ),
             Rule::abi_cast => {
                 let span = Span {
                     span: expr.as_span(),
@@ -957,12 +910,67 @@ impl Expression {
                     errors
                 )
             }
-            Rule::array_index => check!(
-                parse_array_index(expr, config),
-                return err(warnings, errors),
-                warnings,
-                errors
-            ),
+            Rule::closure => {
+                let mut iter = expr.into_inner();
+                let mut parameters = vec![];
+                for param in iter.next().expect("guaranteed by grammar").into_inner() {
+                    let mut parts = param.into_inner();
+                    let name = check!(
+                        ident::parse_from_pair(parts.next().unwrap(), config),
+                        return err(warnings, errors),
+                        warnings,
+                        errors
+                    );
+                    let (r#type, type_span) = match parts.next() {
+                        Some(type_pair) => {
+                            let type_span = Span {
+                                span: type_pair.as_span(),
+                                path: path.clone(),
+                            };
+                            let r#type = check!(
+                                TypeInfo::parse_from_pair(type_pair, config),
+                                TypeInfo::ErrorRecovery,
+                                warnings,
+                                errors
+                            );
+                            (r#type, type_span)
+                        }
+                        None => (TypeInfo::Unknown, name.span().clone()),
+                    };
+                    parameters.push(FunctionParameter {
+                        name,
+                        r#type,
+                        type_span,
+                        is_reference: false,
+                    });
+                }
+                let mut return_type = TypeInfo::Unknown;
+                let mut body = iter.next().expect("guaranteed by grammar");
+                if body.as_rule() == Rule::fn_returns {
+                    return_type = check!(
+                        TypeInfo::parse_from_pair(iter.next().unwrap(), config),
+                        TypeInfo::ErrorRecovery,
+                        warnings,
+                        errors
+                    );
+                    body = iter.next().expect("guaranteed by grammar");
+                }
+                let body = check!(
+                    match body.as_rule() {
+                        Rule::expr => Expression::parse_from_pair(body, config),
+                        _ => Expression::parse_from_pair_inner(body, config),
+                    },
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                );
+                Expression::Closure {
+                    parameters,
+                    return_type,
+                    body: Box::new(body),
+                    span,
+                }
+            }
             a => {
                 eprintln!(
                     "Unimplemented expr: {:?} ({:?}) ({:?})",
@@ -1037,6 +1045,116 @@ fn convert_unary_to_fn_calls(
     ok(expr, warnings, errors)
 }
 
+/// Applies a single postfix operation, such as the `.b()` in `a.b()`, to the expression which
+/// precedes it. `primary_span` is the span of the start of the chain, so method calls and array
+/// indices span from there to the end of `op`.
+fn parse_postfix_op(
+    prefix: Expression,
+    op: Pair<Rule>,
+    primary_span: &Span,
+    config: Option<&BuildConfig>,
+) -> CompileResult<Expression> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let path = config.map(|c| c.path());
+    let span = join_spans(
+        primary_span.clone(),
+        Span {
+            span: op.as_span(),
+            path: path.clone(),
+        },
+    );
+    let rule = op.as_rule();
+    let mut parts = op.into_inner();
+    let exp = match rule {
+        Rule::method_call_op => {
+            let method_name = check!(
+                ident::parse_from_pair(parts.next().expect("guaranteed by grammar"), config),
+                return err(warnings, errors),
+                warnings,
+                errors
+            );
+            // the receiver is passed as the first argument
+            let mut arguments = vec![prefix];
+            for argument in parts.next().expect("guaranteed by grammar").into_inner() {
+                arguments.push(check!(
+                    Expression::parse_from_pair(argument.clone(), config),
+                    Expression::Tuple {
+                        fields: vec![],
+                        span: Span {
+                            span: argument.as_span(),
+                            path: path.clone()
+                        }
+                    },
+                    warnings,
+                    errors
+                ));
+            }
+            Expression::MethodApplication {
+                method_name: MethodName::FromModule { method_name },
+                arguments,
+                span,
+            }
+        }
+        Rule::field_access_op => {
+            let field = parts.next().expect("guaranteed by grammar");
+            Expression::SubfieldExpression {
+                prefix: Box::new(prefix),
+                span: Span {
+                    span: field.as_span(),
+                    path,
+                },
+                field_to_access: check!(
+                    ident::parse_from_pair(field, config),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                ),
+            }
+        }
+        Rule::tuple_index_op => {
+            let index = parts.next().expect("guaranteed by grammar");
+            let index_span = Span {
+                span: index.as_span(),
+                path: path.clone(),
+            };
+            let index = match index.as_str().trim().replace('_', "").parse::<usize>() {
+                Ok(index) => index,
+                Err(e) => {
+                    errors.push(Literal::handle_parse_int_error(
+                        e,
+                        TypeInfo::UnsignedInteger(IntegerBits::SixtyFour),
+                        index.as_span(),
+                        path,
+                    ));
+                    return err(warnings, errors);
+                }
+            };
+            Expression::TupleIndex {
+                prefix: Box::new(prefix),
+                index,
+                index_span,
+                span,
+            }
+        }
+        Rule::array_index_op => {
+            let index = check!(
+                Expression::parse_from_pair(parts.next().expect("guaranteed by grammar"), config),
+                return err(warnings, errors),
+                warnings,
+                errors
+            );
+            Expression::ArrayIndex {
+                prefix: Box::new(prefix),
+                index: Box::new(index),
+                span,
+            }
+        }
+        a => unreachable!("guaranteed by grammar: {:?}", a),
+    };
+    ok(exp, warnings, errors)
+}
+
 pub(crate) fn parse_array_index(
     item: Pair<Rule>,
     config: Option<&BuildConfig>,
@@ -1083,42 +1201,31 @@ pub(crate) fn parse_array_index(
     ok(exp, warnings, errors)
 }
 
-fn parse_subfield_path(
+pub(crate) fn parse_storage_access(
     item: Pair<Rule>,
     config: Option<&BuildConfig>,
 ) -> CompileResult<Expression> {
-    let warnings = vec![];
+    let mut warnings = vec![];
     let mut errors = vec![];
+    assert_eq!(item.as_rule(), Rule::storage_access);
     let path = config.map(|c| c.path());
-    let item = item.into_inner().next().expect("guarenteed by grammar");
-    match item.as_rule() {
-        Rule::call_item => parse_call_item(item, config),
-        Rule::array_index => parse_array_index(item, config),
-        a => {
-            eprintln!(
-                "Unimplemented subfield path: {:?} ({:?}) ({:?})",
-                a,
-                item.as_str(),
-                item.as_rule()
-            );
-            errors.push(CompileError::UnimplementedRule(
-                a,
-                Span {
-                    span: item.as_span(),
-                    path: path.clone(),
-                },
-            ));
-            // construct unit expression for error recovery
-            let exp = Expression::Tuple {
-                fields: vec![],
-                span: Span {
-                    span: item.as_span(),
-                    path,
-                },
-            };
-            ok(exp, warnings, errors)
-        }
-    }
+    let span = Span {
+        span: item.as_span(),
+        path,
+    };
+    let mut parts = item.into_inner();
+    let _storage_keyword = parts.next();
+    let field_name = check!(
+        ident::parse_from_pair(parts.next().expect("guaranteed by grammar"), config),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    ok(
+        Expression::StorageAccess { field_name, span },
+        warnings,
+        errors,
+    )
 }
 
 // A call item is parsed as either an `ident` or a parenthesized `expr`. This method's job is to
@@ -1248,6 +1355,8 @@ fn parse_op(op: Pair<Rule>, config: Option<&BuildConfig>) -> CompileResult<Op> {
         "<" => LessThan,
         ">=" => GreaterThanOrEqualTo,
         "<=" => LessThanOrEqualTo,
+        "<<" => ShiftLeft,
+        ">>" => ShiftRight,
         a => {
             errors.push(CompileError::ExpectedOp {
                 op: a.to_string(),
@@ -1284,7 +1393,7 @@ impl Op {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum OpVariant {
     Add,
     Subtract,
@@ -1302,10 +1411,12 @@ pub enum OpVariant {
     LessThan,
     GreaterThanOrEqualTo,
     LessThanOrEqualTo,
+    ShiftLeft,
+    ShiftRight,
 }
 
 impl OpVariant {
-    fn as_str(&self) -> &'static str {
+    pub(crate) fn as_str(&self) -> &'static str {
         use OpVariant::*;
         match self {
             Add => "add",
@@ -1324,8 +1435,48 @@ impl OpVariant {
             LessThan => "lt",
             LessThanOrEqualTo => "le",
             GreaterThanOrEqualTo => "ge",
+            ShiftLeft => "lsh",
+            ShiftRight => "rsh",
         }
     }
+    /// The operator whose `core::ops` method is called `name`, if any.  The lazy operators
+    /// aren't methods so are never returned.
+    pub(crate) fn from_method_name(name: &str) -> Option<OpVariant> {
+        use OpVariant::*;
+        [
+            Add,
+            Subtract,
+            Divide,
+            Multiply,
+            Modulo,
+            Equals,
+            NotEquals,
+            Xor,
+            BinaryOr,
+            BinaryAnd,
+            GreaterThan,
+            LessThan,
+            GreaterThanOrEqualTo,
+            LessThanOrEqualTo,
+            ShiftLeft,
+            ShiftRight,
+        ]
+        .iter()
+        .copied()
+        .find(|op| op.as_str() == name)
+    }
+    /// Shifts are applied to any integer by a `u64` amount, rather than to two operands of the
+    /// same type.
+    pub(crate) fn is_shift(&self) -> bool {
+        matches!(self, OpVariant::ShiftLeft | OpVariant::ShiftRight)
+    }
+    pub(crate) fn is_comparison(&self) -> bool {
+        use OpVariant::*;
+        matches!(
+            self,
+            Equals | NotEquals | GreaterThan | LessThan | GreaterThanOrEqualTo | LessThanOrEqualTo
+        )
+    }
     fn precedence(&self) -> usize {
         use OpVariant::*;
         // a higher number means the operation has higher precedence
@@ -1341,16 +1492,19 @@ impl OpVariant {
             GreaterThanOrEqualTo => 2,
             LessThanOrEqualTo => 2,
 
-            Add => 3,
-            Subtract => 3,
+            ShiftLeft => 3,
+            ShiftRight => 3,
+
+            Add => 4,
+            Subtract => 4,
 
-            Divide => 4,
-            Multiply => 4,
-            Modulo => 4,
+            Divide => 5,
+            Multiply => 5,
+            Modulo => 5,
 
-            BinaryOr => 5,
-            BinaryAnd => 5,
-            Xor => 5,
+            BinaryOr => 6,
+            BinaryAnd => 6,
+            Xor => 6,
         }
     }
 }
@@ -1460,8 +1614,8 @@ fn arrange_by_order_of_operations(
 
 struct MatchedBranch {
     result: Expression,
-    match_req_map: Vec<(Expression, Expression)>,
-    match_impl_map: Vec<(Ident, Expression)>,
+    match_req_map: MatchReqMap,
+    match_impl_map: MatchImplMap,
     branch_span: Span,
 }
 
@@ -1501,6 +1655,10 @@ struct MatchedBranch {
 /// }
 /// ```
 ///
+/// When the branches are known to be exhaustive the last branch is taken whenever none of the
+/// others are, so its conditional is left out.  That way a match which ends with, say, a `false`
+/// branch after a `true` branch still has a final `else`.
+///
 /// The steps of the algorithm can roughly be broken down into:
 ///
 /// 1. Assemble the "matched branches."
@@ -1509,10 +1667,10 @@ struct MatchedBranch {
 ///     2b. Assemble the statements that go inside of the body of the if expression
 ///     2c. Assemble the giant if statement.
 /// 3. Return!
-pub fn desugar_match_expression(
+pub(crate) fn desugar_match_expression(
     primary_expression: Expression,
     branches: Vec<MatchBranch>,
-    _span: Span,
+    is_exhaustive: bool,
 ) -> CompileResult<Expression> {
     let mut errors = vec![];
     let mut warnings = vec![];
@@ -1558,7 +1716,7 @@ pub fn desugar_match_expression(
     }
 
     // 2. Assemble the possibly nested giant if statement using the matched branches.
-    let mut if_statement = None;
+    let mut if_statement: Option<Expression> = None;
     for MatchedBranch {
         result,
         match_req_map,
@@ -1568,12 +1726,7 @@ pub fn desugar_match_expression(
     {
         // 2a. Assemble the conditional that goes in the if primary expression.
         let mut conditional = None;
-        for (left_req, right_req) in match_req_map.iter() {
-            let joined_span = join_spans(left_req.clone().span(), right_req.clone().span());
-            let condition = Expression::core_ops_eq(
-                vec![left_req.to_owned(), right_req.to_owned()],
-                joined_span,
-            );
+        for condition in match_req_map.iter().cloned() {
             match conditional {
                 None => {
                     conditional = Some(condition);
@@ -1592,10 +1745,10 @@ pub fn desugar_match_expression(
         // 2b. Assemble the statements that go inside of the body of the if expression
         let mut code_block_stmts = vec![];
         let mut code_block_stmts_span = None;
-        for (left_impl, right_impl) in match_impl_map.iter() {
+        for (left_impl, right_impl, is_mutable) in match_impl_map.iter() {
             let decl = Declaration::VariableDeclaration(VariableDeclaration {
                 name: left_impl.clone(),
-                is_mutable: false,
+                is_mutable: *is_mutable,
                 body: right_impl.clone(),
                 type_ascription: TypeInfo::Unknown,
                 type_ascription_span: None,
@@ -1649,77 +1802,26 @@ pub fn desugar_match_expression(
             span: code_block_stmts_span,
         };
 
-        // 2c. Assemble the giant if statement.
-        match if_statement {
-            None => {
-                if_statement = match conditional {
-                    None => Some(code_block),
-                    Some(conditional) => Some(Expression::IfExp {
-                        condition: Box::new(conditional.clone()),
-                        then: Box::new(code_block.clone()),
-                        r#else: None,
-                        span: join_spans(conditional.span(), code_block.span()),
-                    }),
-                };
-            }
-            Some(Expression::CodeBlock {
-                contents: right_block,
-                span: exp_span,
-            }) => {
-                let right = Expression::CodeBlock {
-                    contents: right_block,
-                    span: exp_span,
-                };
-                if_statement = match conditional {
-                    None => Some(Expression::IfExp {
-                        condition: Box::new(Expression::Literal {
-                            value: Literal::Boolean(true),
-                            span: branch_span.clone(),
-                        }),
-                        then: Box::new(code_block.clone()),
-                        r#else: Some(Box::new(right.clone())),
-                        span: join_spans(code_block.clone().span(), right.clone().span()),
-                    }),
-                    Some(the_conditional) => Some(Expression::IfExp {
-                        condition: Box::new(the_conditional),
-                        then: Box::new(code_block.clone()),
-                        r#else: Some(Box::new(right.clone())),
-                        span: join_spans(code_block.clone().span(), right.clone().span()),
-                    }),
-                };
-            }
-            Some(Expression::IfExp {
-                condition,
-                then,
-                r#else,
-                span: exp_span,
-            }) => {
-                if_statement = Some(Expression::IfExp {
-                    condition: Box::new(conditional.unwrap()),
-                    then: Box::new(code_block.clone()),
-                    r#else: Some(Box::new(Expression::IfExp {
-                        condition,
-                        then,
-                        r#else,
-                        span: exp_span.clone(),
-                    })),
-                    span: join_spans(code_block.clone().span(), exp_span),
-                });
-            }
-            Some(if_statement) => {
-                eprintln!("Unimplemented if_statement_pattern: {:?}", if_statement,);
-                errors.push(CompileError::Unimplemented(
-                    "this desugared if expression pattern is not implemented",
-                    if_statement.span(),
-                ));
-                // construct unit expression for error recovery
-                let exp = Expression::Tuple {
-                    fields: vec![],
-                    span: if_statement.span(),
+        // 2c. Assemble the giant if statement.  A branch without a conditional is taken whenever
+        // it is reached, so it replaces the branches after it rather than being the first of them.
+        if is_exhaustive && if_statement.is_none() {
+            conditional = None;
+        }
+        if_statement = Some(match conditional {
+            None => code_block,
+            Some(conditional) => {
+                let span = match &if_statement {
+                    None => join_spans(conditional.span(), code_block.span()),
+                    Some(if_statement) => join_spans(code_block.span(), if_statement.span()),
                 };
-                return ok(exp, warnings, errors);
+                Expression::IfExp {
+                    condition: Box::new(conditional),
+                    then: Box::new(code_block),
+                    r#else: if_statement.map(Box::new),
+                    span,
+                }
             }
-        }
+        });
     }
 
     // 3. Return!
@@ -25,6 +25,8 @@ pub enum Scrutinee {
     },
     Variable {
         name: Ident,
+        /// Whether the variable is declared as `mut`, e.g., `(mut x, y)`.
+        is_mutable: bool,
         span: Span,
     },
     StructScrutinee {
@@ -47,6 +49,9 @@ pub enum Scrutinee {
 pub struct StructScrutineeField {
     pub field: Ident,
     pub scrutinee: Option<Scrutinee>,
+    /// Whether the field is bound to a variable of the same name declared as `mut`, e.g.,
+    /// `Point { mut x, y }`.  Only a field without a scrutinee of its own may be `mut`.
+    pub is_mutable: bool,
     pub span: Span,
 }
 
@@ -62,6 +67,23 @@ impl Scrutinee {
         }
     }
 
+    /// Returns the span of a part of this scrutinee which only matches some values of its type,
+    /// i.e., a literal or an enum variant, if there is one.  A scrutinee without such a part
+    /// always matches, so it may be used to destructure a value in a `let` binding.
+    pub(crate) fn refutable_span(&self) -> Option<Span> {
+        match self {
+            Scrutinee::Literal { span, .. } | Scrutinee::EnumScrutinee { span, .. } => {
+                Some(span.clone())
+            }
+            Scrutinee::Unit { .. } | Scrutinee::Variable { .. } => None,
+            Scrutinee::StructScrutinee { fields, .. } => fields
+                .iter()
+                .filter_map(|field| field.scrutinee.as_ref())
+                .find_map(Scrutinee::refutable_span),
+            Scrutinee::Tuple { elems, .. } => elems.iter().find_map(Scrutinee::refutable_span),
+        }
+    }
+
     pub fn parse_from_pair(pair: Pair<Rule>, config: Option<&BuildConfig>) -> CompileResult<Self> {
         let mut warnings = Vec::new();
         let mut errors = Vec::new();
@@ -94,7 +116,7 @@ impl Scrutinee {
                 warnings,
                 errors
             ),
-            Rule::ident => check!(
+            Rule::ident | Rule::mut_scrutinee => check!(
                 Self::parse_from_pair_ident(scrutinee, config, span),
                 return err(warnings, errors),
                 warnings,
@@ -166,9 +188,18 @@ impl Scrutinee {
     ) -> CompileResult<Self> {
         let mut warnings = vec![];
         let mut errors = vec![];
+        let is_mutable = scrutinee.as_rule() == Rule::mut_scrutinee;
+        let scrutinee = if is_mutable {
+            let mut parts = scrutinee.into_inner();
+            let _mut_keyword = parts.next();
+            parts.next().unwrap()
+        } else {
+            scrutinee
+        };
         let scrutinee = ident::parse_from_pair(scrutinee, config)
             .map(|name| Scrutinee::Variable {
                 name,
+                is_mutable,
                 span: span.clone(),
             })
             .unwrap_or_else(&mut warnings, &mut errors, || Scrutinee::Unit {
@@ -200,7 +231,14 @@ impl Scrutinee {
                 span: field.as_span(),
                 path: path.clone(),
             };
-            let mut field_parts = field.clone().into_inner();
+            let mut field_parts = field.clone().into_inner().peekable();
+            let is_mutable = field_parts
+                .peek()
+                .map(|part| part.as_rule() == Rule::mut_keyword)
+                .unwrap_or(false);
+            if is_mutable {
+                let _mut_keyword = field_parts.next();
+            }
             let name = field_parts.next().unwrap();
             let name = check!(
                 ident::parse_from_pair(name, config),
@@ -228,6 +266,7 @@ impl Scrutinee {
             fields_buf.push(StructScrutineeField {
                 field: name,
                 scrutinee: field_scrutinee,
+                is_mutable,
                 span,
             });
         }
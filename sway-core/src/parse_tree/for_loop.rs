@@ -0,0 +1,106 @@
+use crate::{
+    build_config::BuildConfig,
+    error::{err, ok, CompileResult},
+    parse_tree::ident,
+    parser::Rule,
+    CodeBlock, Expression, Ident,
+};
+
+use sway_types::span::Span;
+
+use pest::iterators::Pair;
+
+/// A parsed for loop, of the form `for x in iterable { .. }`. The `variable` is bound to each value
+/// produced by the `iterable` in turn, and the `body` is run once for each of them.
+#[derive(Debug, Clone)]
+pub struct ForLoop {
+    pub(crate) variable: Ident,
+    pub(crate) iterable: ForLoopIterable,
+    pub(crate) body: CodeBlock,
+}
+
+/// What a [ForLoop] iterates over.
+#[derive(Debug, Clone)]
+pub enum ForLoopIterable {
+    /// Each element of an array, in order.
+    Array(Box<Expression>),
+    /// Each integer from `start` up to but not including `end`, written `start..end`.
+    Range {
+        start: Box<Expression>,
+        end: Box<Expression>,
+    },
+}
+
+impl ForLoop {
+    pub(crate) fn parse_from_pair(
+        pair: Pair<Rule>,
+        config: Option<&BuildConfig>,
+    ) -> CompileResult<Self> {
+        let path = config.map(|c| c.path());
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+        let mut iter = pair.into_inner();
+        let _for_keyword = iter.next().unwrap();
+        let variable = check!(
+            ident::parse_from_pair(iter.next().unwrap(), config),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        let _in_keyword = iter.next().unwrap();
+        let iterable = iter.next().unwrap();
+        let body = iter.next().unwrap();
+        let whole_block_span = Span {
+            span: body.as_span(),
+            path,
+        };
+
+        let iterable = match iterable.as_rule() {
+            Rule::for_range => {
+                let mut range = iterable.into_inner();
+                let start = check!(
+                    Expression::parse_from_pair(range.next().unwrap(), config),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                );
+                let end = check!(
+                    Expression::parse_from_pair(range.next().unwrap(), config),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                );
+                ForLoopIterable::Range {
+                    start: Box::new(start),
+                    end: Box::new(end),
+                }
+            }
+            _ => ForLoopIterable::Array(Box::new(check!(
+                Expression::parse_from_pair(iterable, config),
+                return err(warnings, errors),
+                warnings,
+                errors
+            ))),
+        };
+
+        let body = check!(
+            CodeBlock::parse_from_pair(body, config),
+            CodeBlock {
+                contents: Default::default(),
+                whole_block_span,
+            },
+            warnings,
+            errors
+        );
+
+        ok(
+            ForLoop {
+                variable,
+                iterable,
+                body,
+            },
+            warnings,
+            errors,
+        )
+    }
+}
@@ -21,6 +21,13 @@ pub enum Literal {
     U16(u16),
     U32(u32),
     U64(u64),
+    U128(u128),
+    /// Big-endian, like `B256`.
+    U256([u8; 32]),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
     String(span::Span),
     Numeric(u64),
     Boolean(bool),
@@ -37,6 +44,12 @@ impl Literal {
             U16(_) => ResolvedType::UnsignedInteger(IntegerBits::Sixteen),
             U32(_) => ResolvedType::UnsignedInteger(IntegerBits::ThirtyTwo),
             U64(_) => ResolvedType::UnsignedInteger(IntegerBits::SixtyFour),
+            U128(_) => ResolvedType::UnsignedInteger(IntegerBits::OneTwentyEight),
+            U256(_) => ResolvedType::UnsignedInteger(IntegerBits::TwoFiftySix),
+            I8(_) => ResolvedType::SignedInteger(IntegerBits::Eight),
+            I16(_) => ResolvedType::SignedInteger(IntegerBits::Sixteen),
+            I32(_) => ResolvedType::SignedInteger(IntegerBits::ThirtyTwo),
+            I64(_) => ResolvedType::SignedInteger(IntegerBits::SixtyFour),
             Numeric(_) => ResolvedType::UnsignedInteger(IntegerBits::SixtyFour),
             String(inner) => ResolvedType::Str(inner.as_str().len() as u64),
             Boolean(_) => ResolvedType::Boolean,
@@ -77,6 +90,8 @@ impl Literal {
             Rule::typed_integer => {
                 let mut int_inner = lit_inner.into_inner().next().unwrap();
                 let rule = int_inner.as_rule();
+                // The sign of a negative signed integer isn't part of its digits.
+                let is_negative = int_inner.as_str().starts_with('-');
                 if int_inner.as_rule() != Rule::basic_integer {
                     int_inner = int_inner.into_inner().next().unwrap()
                 }
@@ -142,6 +157,76 @@ impl Literal {
                                     path.clone(),
                                 )
                             }),
+                        Rule::u128_integer => int_inner
+                            .as_str()
+                            .trim()
+                            .replace('_', "")
+                            .parse()
+                            .map(Literal::U128)
+                            .map_err(|e| {
+                                Literal::handle_parse_int_error(
+                                    e,
+                                    TypeInfo::UnsignedInteger(IntegerBits::OneTwentyEight),
+                                    int_inner.as_span(),
+                                    path.clone(),
+                                )
+                            }),
+                        Rule::u256_integer => {
+                            parse_u256(&int_inner.as_str().trim().replace('_', ""))
+                                .map(Literal::U256)
+                                .map_err(|kind| {
+                                    Literal::handle_int_error_kind(
+                                        &kind,
+                                        TypeInfo::UnsignedInteger(IntegerBits::TwoFiftySix),
+                                        int_inner.as_span(),
+                                        path.clone(),
+                                    )
+                                })
+                        }
+                        Rule::i8_integer => signed_digits(int_inner.as_str(), is_negative)
+                            .parse()
+                            .map(Literal::I8)
+                            .map_err(|e| {
+                                Literal::handle_parse_int_error(
+                                    e,
+                                    TypeInfo::SignedInteger(IntegerBits::Eight),
+                                    int_inner.as_span(),
+                                    path.clone(),
+                                )
+                            }),
+                        Rule::i16_integer => signed_digits(int_inner.as_str(), is_negative)
+                            .parse()
+                            .map(Literal::I16)
+                            .map_err(|e| {
+                                Literal::handle_parse_int_error(
+                                    e,
+                                    TypeInfo::SignedInteger(IntegerBits::Sixteen),
+                                    int_inner.as_span(),
+                                    path.clone(),
+                                )
+                            }),
+                        Rule::i32_integer => signed_digits(int_inner.as_str(), is_negative)
+                            .parse()
+                            .map(Literal::I32)
+                            .map_err(|e| {
+                                Literal::handle_parse_int_error(
+                                    e,
+                                    TypeInfo::SignedInteger(IntegerBits::ThirtyTwo),
+                                    int_inner.as_span(),
+                                    path.clone(),
+                                )
+                            }),
+                        Rule::i64_integer => signed_digits(int_inner.as_str(), is_negative)
+                            .parse()
+                            .map(Literal::I64)
+                            .map_err(|e| {
+                                Literal::handle_parse_int_error(
+                                    e,
+                                    TypeInfo::SignedInteger(IntegerBits::SixtyFour),
+                                    int_inner.as_span(),
+                                    path.clone(),
+                                )
+                            }),
                         _ => unreachable!(),
                     },
                     span,
@@ -235,6 +320,13 @@ impl Literal {
                 vec![0, 0, 0, 0, bytes[0], bytes[1], bytes[2], bytes[3]]
             }
             U64(val) => val.to_be_bytes().to_vec(),
+            U128(val) => val.to_be_bytes().to_vec(),
+            U256(b) => b.to_vec(),
+            // signed integers are sign extended to a whole word
+            I8(val) => (*val as i64).to_be_bytes().to_vec(),
+            I16(val) => (*val as i64).to_be_bytes().to_vec(),
+            I32(val) => (*val as i64).to_be_bytes().to_vec(),
+            I64(val) => val.to_be_bytes().to_vec(),
             Numeric(val) => val.to_be_bytes().to_vec(),
             Boolean(b) => {
                 vec![
@@ -268,14 +360,37 @@ impl Literal {
         Literal::U64(offset_bytes)
     }
 
-    #[allow(clippy::wildcard_in_or_patterns)]
+    /// A numeric literal's type may only be inferred to be `u128` or `u256` after the literal
+    /// itself has been type checked, in which case it is widened here before being compiled.
+    pub(crate) fn widen_to(&self, ty: &TypeInfo) -> Literal {
+        match (self, ty) {
+            (Literal::Numeric(num), TypeInfo::UnsignedInteger(IntegerBits::OneTwentyEight)) => {
+                Literal::U128(*num as u128)
+            }
+            (Literal::Numeric(num), TypeInfo::UnsignedInteger(IntegerBits::TwoFiftySix)) => {
+                Literal::U256(u256_from_u64(*num))
+            }
+            _ => self.clone(),
+        }
+    }
+
     pub(crate) fn handle_parse_int_error(
         e: ParseIntError,
         ty: TypeInfo,
         span: Span,
         path: Option<Arc<PathBuf>>,
     ) -> CompileError {
-        match e.kind() {
+        Literal::handle_int_error_kind(e.kind(), ty, span, path)
+    }
+
+    #[allow(clippy::wildcard_in_or_patterns)]
+    pub(crate) fn handle_int_error_kind(
+        kind: &IntErrorKind,
+        ty: TypeInfo,
+        span: Span,
+        path: Option<Arc<PathBuf>>,
+    ) -> CompileError {
+        match kind {
             IntErrorKind::PosOverflow => CompileError::IntegerTooLarge {
                 ty: ty.friendly_type_str(),
                 span: span::Span { span, path },
@@ -296,6 +411,42 @@ impl Literal {
     }
 }
 
+fn signed_digits(digits: &str, is_negative: bool) -> String {
+    let digits = digits.trim().replace('_', "");
+    if is_negative {
+        format!("-{}", digits)
+    } else {
+        digits
+    }
+}
+
+pub(crate) fn u256_from_u64(num: u64) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    bytes[24..].copy_from_slice(&num.to_be_bytes());
+    bytes
+}
+
+/// There is no native 256 bit integer to parse into, so the decimal digits are accumulated into
+/// a big-endian byte array by hand.
+fn parse_u256(digits: &str) -> Result<[u8; 32], IntErrorKind> {
+    if digits.is_empty() {
+        return Err(IntErrorKind::Empty);
+    }
+    let mut bytes = [0u8; 32];
+    for digit in digits.chars() {
+        let mut carry = digit.to_digit(10).ok_or(IntErrorKind::InvalidDigit)?;
+        for byte in bytes.iter_mut().rev() {
+            let acc = *byte as u32 * 10 + carry;
+            *byte = acc as u8;
+            carry = acc >> 8;
+        }
+        if carry != 0 {
+            return Err(IntErrorKind::PosOverflow);
+        }
+    }
+    Ok(bytes)
+}
+
 fn parse_hex_from_pair(
     pair: Pair<Rule>,
     config: Option<&BuildConfig>,
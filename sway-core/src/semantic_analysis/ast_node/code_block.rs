@@ -1,6 +1,7 @@
 use super::*;
 use crate::semantic_analysis::{ast_node::Mode, TypeCheckArguments};
 use crate::CodeBlock;
+use std::borrow::Cow;
 
 #[derive(Clone, Debug)]
 pub(crate) struct TypedCodeBlock {
@@ -94,19 +95,30 @@ impl TypedCodeBlock {
             // The annotation will result in a cast, so set the return type accordingly.
         }
 
-        ok(
-            (
-                TypedCodeBlock {
-                    contents: evaluated_contents,
-                    whole_block_span: other.whole_block_span,
-                },
-                return_type.unwrap_or_else(|| {
-                    crate::type_engine::insert_type(TypeInfo::Tuple(Vec::new()))
-                }),
-            ),
-            warnings,
-            errors,
-        )
+        let block = TypedCodeBlock {
+            contents: evaluated_contents,
+            whole_block_span: other.whole_block_span,
+        };
+        // A block which always reverts never produces a value, so like a `revert` it takes on
+        // whatever type its context expects of it.
+        let return_type = return_type.unwrap_or_else(|| {
+            crate::type_engine::insert_type(if block.diverges() {
+                TypeInfo::Unknown
+            } else {
+                TypeInfo::Tuple(Vec::new())
+            })
+        });
+
+        ok((block, return_type), warnings, errors)
+    }
+
+    /// Whether some statement of this block always reverts, so that the block never completes.
+    pub(crate) fn diverges(&self) -> bool {
+        self.contents.iter().any(|node| match &node.content {
+            TypedAstNodeContent::Expression(expr)
+            | TypedAstNodeContent::ImplicitReturnExpression(expr) => expr.diverges(),
+            _ => false,
+        })
     }
 
     pub(crate) fn copy_types(&mut self, type_mapping: &[(TypeParameter, TypeId)]) {
@@ -114,4 +126,40 @@ impl TypedCodeBlock {
             .iter_mut()
             .for_each(|x| x.copy_types(type_mapping));
     }
+
+    /// See [TypedExpressionVariant::resolve_constrained_methods].
+    pub(crate) fn resolve_constrained_methods(
+        &mut self,
+        namespace: NamespaceRef,
+        self_type: TypeId,
+    ) -> CompileResult<()> {
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        for node in self.contents.iter_mut() {
+            check!(
+                node.resolve_constrained_methods(namespace, self_type),
+                (),
+                warnings,
+                errors
+            );
+        }
+        ok((), warnings, errors)
+    }
+
+    /// See [TypedExpressionVariant::bind_function_values].
+    pub(crate) fn bind_function_values(&mut self, bindings: &[(Ident, TypedFunctionValue)]) {
+        let mut bindings = Cow::Borrowed(bindings);
+        for node in self.contents.iter_mut() {
+            node.bind_function_values(&bindings);
+            // a variable shadows the parameter it has the name of for the rest of the block
+            if let TypedAstNodeContent::Declaration(TypedDeclaration::VariableDeclaration(
+                TypedVariableDeclaration { name, .. },
+            )) = &node.content
+            {
+                if bindings.iter().any(|(param, _)| param == name) {
+                    bindings.to_mut().retain(|(param, _)| param != name);
+                }
+            }
+        }
+    }
 }
@@ -1,11 +1,13 @@
-use super::{impl_trait::Mode, TypedCodeBlock, TypedExpression};
-use crate::{error::*, parse_tree::*, type_engine::*, Ident};
+use super::{impl_trait::Mode, TypedCodeBlock, TypedExpression, TypedFunctionValue};
+use crate::{error::*, parse_tree::*, semantic_analysis::NamespaceRef, type_engine::*, Ident};
 
 use sway_types::{join_spans, span::Span, Property};
 
 mod function;
+mod storage;
 mod variable;
 pub use function::*;
+pub use storage::*;
 pub use variable::*;
 
 #[derive(Clone, Debug)]
@@ -19,11 +21,18 @@ pub enum TypedDeclaration {
     Reassignment(TypedReassignment),
     ImplTrait {
         trait_name: CallPath,
+        /// The types given for the trait's type parameters, which are a part of the identity of
+        /// the implementation.
+        trait_type_arguments: Vec<TypeInfo>,
         span: Span,
         methods: Vec<TypedFunctionDeclaration>,
         type_implementing_for: TypeInfo,
+        attributes: Vec<Attribute>,
     },
     AbiDeclaration(TypedAbiDeclaration),
+    StorageDeclaration(TypedStorageDeclaration),
+    StorageReassignment(TypedStorageReassignment),
+    TypeAliasDeclaration(TypedTypeAliasDeclaration),
     // If type parameters are defined for a function, they are put in the namespace just for
     // the body of that function.
     GenericTypeForFunctionScope {
@@ -50,11 +59,50 @@ impl TypedDeclaration {
             } => {
                 methods.iter_mut().for_each(|x| x.copy_types(type_mapping));
             }
+            StorageReassignment(ref mut reassignment) => reassignment.copy_types(type_mapping),
             // generics in an ABI is unsupported by design
             AbiDeclaration(..) => (),
+            // storage is only declared at the top level of a contract
+            StorageDeclaration(..) => (),
+            // the aliased type is instantiated afresh wherever the alias is used
+            TypeAliasDeclaration(..) => (),
             GenericTypeForFunctionScope { .. } | ErrorRecovery => (),
         }
     }
+
+    /// See [TypedExpressionVariant::resolve_constrained_methods]. Only the declarations which
+    /// may appear in a function body are visited.
+    pub(crate) fn resolve_constrained_methods(
+        &mut self,
+        namespace: NamespaceRef,
+        self_type: TypeId,
+    ) -> CompileResult<()> {
+        use TypedDeclaration::*;
+        match self {
+            VariableDeclaration(TypedVariableDeclaration { body: expr, .. })
+            | ConstantDeclaration(TypedConstantDeclaration { value: expr, .. })
+            | Reassignment(TypedReassignment { rhs: expr, .. })
+            | StorageReassignment(TypedStorageReassignment { rhs: expr, .. }) => {
+                expr.resolve_constrained_methods(namespace, self_type)
+            }
+            _ => ok((), vec![], vec![]),
+        }
+    }
+
+    /// See [TypedExpressionVariant::bind_function_values]. Only the declarations which may appear
+    /// in a function body are visited.
+    pub(crate) fn bind_function_values(&mut self, bindings: &[(Ident, TypedFunctionValue)]) {
+        use TypedDeclaration::*;
+        match self {
+            VariableDeclaration(TypedVariableDeclaration { body: expr, .. })
+            | ConstantDeclaration(TypedConstantDeclaration { value: expr, .. })
+            | Reassignment(TypedReassignment { rhs: expr, .. })
+            | StorageReassignment(TypedStorageReassignment { rhs: expr, .. }) => {
+                expr.bind_function_values(bindings)
+            }
+            _ => (),
+        }
+    }
 }
 
 impl TypedDeclaration {
@@ -71,6 +119,9 @@ impl TypedDeclaration {
             Reassignment(_) => "reassignment",
             ImplTrait { .. } => "impl trait",
             AbiDeclaration(..) => "abi",
+            StorageDeclaration(..) => "contract storage",
+            StorageReassignment(..) => "contract storage reassignment",
+            TypeAliasDeclaration(..) => "type alias",
             GenericTypeForFunctionScope { .. } => "generic type parameter",
             ErrorRecovery => "error",
         }
@@ -81,6 +132,9 @@ impl TypedDeclaration {
                 TypedDeclaration::VariableDeclaration(TypedVariableDeclaration {
                     body, ..
                 }) => body.return_type,
+                TypedDeclaration::ConstantDeclaration(TypedConstantDeclaration {
+                    value, ..
+                }) => value.return_type,
                 TypedDeclaration::FunctionDeclaration { .. } => {
                     return err(
                         vec![],
@@ -102,6 +156,9 @@ impl TypedDeclaration {
                         .collect(),
                 }),
                 TypedDeclaration::Reassignment(TypedReassignment { rhs, .. }) => rhs.return_type,
+                TypedDeclaration::StorageReassignment(TypedStorageReassignment { rhs, .. }) => {
+                    rhs.return_type
+                }
                 TypedDeclaration::GenericTypeForFunctionScope { name } => {
                     insert_type(TypeInfo::UnknownGeneric { name: name.clone() })
                 }
@@ -135,6 +192,9 @@ impl TypedDeclaration {
                 .fold(lhs[0].span(), |acc, this| join_spans(acc, this.span())),
             AbiDeclaration(TypedAbiDeclaration { span, .. }) => span.clone(),
             ImplTrait { span, .. } => span.clone(),
+            StorageDeclaration(TypedStorageDeclaration { span, .. }) => span.clone(),
+            StorageReassignment(reassignment) => reassignment.span(),
+            TypeAliasDeclaration(TypedTypeAliasDeclaration { name, .. }) => name.span().clone(),
             ErrorRecovery | GenericTypeForFunctionScope { .. } => {
                 unreachable!("No span exists for these ast node types")
             }
@@ -155,7 +215,6 @@ impl TypedDeclaration {
                     match is_mutable {
                         VariableMutability::Mutable => "mut",
                         VariableMutability::Immutable => "",
-                        VariableMutability::ExportedConst => "pub const",
                     },
                     name.as_str()
                 ),
@@ -170,11 +229,18 @@ impl TypedDeclaration {
                     name.as_str().into(),
                 TypedDeclaration::EnumDeclaration(TypedEnumDeclaration { name, .. }) =>
                     name.as_str().into(),
+                TypedDeclaration::TypeAliasDeclaration(TypedTypeAliasDeclaration {
+                    name, ..
+                }) => name.as_str().into(),
                 TypedDeclaration::Reassignment(TypedReassignment { lhs, .. }) => lhs
                     .iter()
                     .map(|x| x.name.as_str())
                     .collect::<Vec<_>>()
                     .join("."),
+                TypedDeclaration::StorageReassignment(TypedStorageReassignment {
+                    field_name,
+                    ..
+                }) => format!("storage.{}", field_name.as_str()),
                 _ => String::new(),
             }
         )
@@ -187,15 +253,16 @@ impl TypedDeclaration {
             | Reassignment(..)
             | ImplTrait { .. }
             | AbiDeclaration(..)
+            | StorageDeclaration(..)
+            | StorageReassignment(..)
             | ErrorRecovery => Visibility::Public,
-            VariableDeclaration(TypedVariableDeclaration { is_mutable, .. }) => {
-                is_mutable.visibility()
-            }
+            VariableDeclaration(..) => Visibility::Private,
             EnumDeclaration(TypedEnumDeclaration { visibility, .. })
             | ConstantDeclaration(TypedConstantDeclaration { visibility, .. })
             | FunctionDeclaration(TypedFunctionDeclaration { visibility, .. })
             | TraitDeclaration(TypedTraitDeclaration { visibility, .. })
-            | StructDeclaration(TypedStructDeclaration { visibility, .. }) => *visibility,
+            | StructDeclaration(TypedStructDeclaration { visibility, .. })
+            | TypeAliasDeclaration(TypedTypeAliasDeclaration { visibility, .. }) => *visibility,
         }
     }
 }
@@ -210,6 +277,7 @@ pub struct TypedAbiDeclaration {
     /// The methods provided to a contract "for free" upon opting in to this interface
     pub(crate) methods: Vec<FunctionDeclaration>,
     pub(crate) span: Span,
+    pub(crate) attributes: Vec<Attribute>,
 }
 
 #[derive(Clone, Debug)]
@@ -218,14 +286,17 @@ pub struct TypedStructDeclaration {
     pub(crate) fields: Vec<TypedStructField>,
     pub(crate) type_parameters: Vec<TypeParameter>,
     pub(crate) visibility: Visibility,
+    pub(crate) attributes: Vec<Attribute>,
 }
 
 impl TypedStructDeclaration {
-    pub(crate) fn monomorphize(&self) -> Self {
+    /// Returns a copy of this declaration with fresh types for its type parameters, along with
+    /// the mapping from the parameters to those types.
+    pub(crate) fn monomorphize(&self) -> (Self, Vec<(TypeParameter, TypeId)>) {
         let mut new_decl = self.clone();
         let type_mapping = insert_type_parameters(&self.type_parameters);
         new_decl.copy_types(&type_mapping);
-        new_decl
+        (new_decl, type_mapping)
     }
 
     pub(crate) fn copy_types(&mut self, type_mapping: &[(TypeParameter, TypeId)]) {
@@ -294,13 +365,16 @@ pub struct TypedEnumDeclaration {
     pub(crate) variants: Vec<TypedEnumVariant>,
     pub(crate) span: Span,
     pub(crate) visibility: Visibility,
+    pub(crate) attributes: Vec<Attribute>,
 }
 impl TypedEnumDeclaration {
-    pub(crate) fn monomorphize(&self) -> Self {
+    /// Returns a copy of this declaration with fresh types for its type parameters, along with
+    /// the mapping from the parameters to those types.
+    pub(crate) fn monomorphize(&self) -> (Self, Vec<(TypeParameter, TypeId)>) {
         let mut new_decl = self.clone();
         let type_mapping = insert_type_parameters(&self.type_parameters);
         new_decl.copy_types(&type_mapping);
-        new_decl
+        (new_decl, type_mapping)
     }
     pub(crate) fn copy_types(&mut self, type_mapping: &[(TypeParameter, TypeId)]) {
         self.variants
@@ -364,6 +438,45 @@ impl OwnedTypedEnumVariant {
     }
 }
 
+/// A type alias, whose aliased type has been resolved in the scope which it's declared in.
+#[derive(Clone, Debug)]
+pub struct TypedTypeAliasDeclaration {
+    pub(crate) name: Ident,
+    pub(crate) type_parameters: Vec<TypeParameter>,
+    pub(crate) ty: TypeId,
+    pub(crate) visibility: Visibility,
+}
+
+impl TypedTypeAliasDeclaration {
+    /// The type which a use of the alias with `type_arguments` refers to, or `None` if there are
+    /// a different number of them than there are type parameters. If there are none, the type
+    /// parameters are inferred instead.
+    pub(crate) fn instantiate(&self, type_arguments: Vec<TypeId>) -> Option<TypeId> {
+        let type_mapping = if type_arguments.is_empty() {
+            self.type_parameters
+                .iter()
+                .map(|param| (param.clone(), insert_type(TypeInfo::Unknown)))
+                .collect::<Vec<_>>()
+        } else if type_arguments.len() == self.type_parameters.len() {
+            self.type_parameters
+                .iter()
+                .cloned()
+                .zip(type_arguments)
+                .collect()
+        } else {
+            return None;
+        };
+        // each use gets its own copy, so that inferring the type of one doesn't affect the others
+        let ty = look_up_type_id(self.ty)
+            .matches_type_parameter(&type_mapping)
+            .unwrap_or_else(|| insert_type(look_up_type_id(self.ty)));
+        Some(insert_type(TypeInfo::Alias {
+            name: self.name.clone(),
+            ty,
+        }))
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct TypedConstantDeclaration {
     pub(crate) name: Ident,
@@ -383,7 +496,9 @@ pub struct TypedTraitDeclaration {
     pub(crate) interface_surface: Vec<TypedTraitFn>,
     pub(crate) methods: Vec<FunctionDeclaration>,
     pub(crate) type_parameters: Vec<TypeParameter>,
+    pub(crate) associated_types: Vec<Ident>,
     pub(crate) visibility: Visibility,
+    pub(crate) attributes: Vec<Attribute>,
 }
 impl TypedTraitDeclaration {
     pub(crate) fn copy_types(&mut self, type_mapping: &[(TypeParameter, TypeId)]) {
@@ -394,13 +509,31 @@ impl TypedTraitDeclaration {
             .for_each(|x| x.copy_types(&type_mapping[..]));
         // we don't have to type check the methods because it hasn't been type checked yet
     }
+
+    /// The trait's associated types as type parameters, which an implementation gives types
+    /// for in the same way as it gives type arguments for the trait's own type parameters.
+    pub(crate) fn associated_type_parameters(&self) -> Vec<TypeParameter> {
+        self.associated_types
+            .iter()
+            .map(|name| TypeParameter {
+                name: TypeInfo::Custom {
+                    name: name.clone(),
+                    type_arguments: Vec::new(),
+                },
+                name_ident: name.clone(),
+                trait_constraints: Vec::new(),
+            })
+            .collect()
+    }
 }
 #[derive(Clone, Debug)]
 pub struct TypedTraitFn {
+    pub(crate) purity: Purity,
     pub(crate) name: Ident,
     pub(crate) parameters: Vec<TypedFunctionParameter>,
     pub(crate) return_type: TypeId,
     pub(crate) return_type_span: Span,
+    pub(crate) attributes: Vec<Attribute>,
 }
 
 /// Represents the left hand side of a reassignment -- a name to locate it in the
@@ -445,6 +578,9 @@ impl TypedReassignment {
 
 impl TypedTraitFn {
     pub(crate) fn copy_types(&mut self, type_mapping: &[(TypeParameter, TypeId)]) {
+        self.parameters
+            .iter_mut()
+            .for_each(|x| x.copy_types(type_mapping));
         self.return_type = if let Some(matching_id) =
             look_up_type_id(self.return_type).matches_type_parameter(type_mapping)
         {
@@ -458,7 +594,7 @@ impl TypedTraitFn {
     /// interface surface.
     pub(crate) fn to_dummy_func(&self, mode: Mode) -> TypedFunctionDeclaration {
         TypedFunctionDeclaration {
-            purity: Default::default(),
+            purity: self.purity,
             name: self.name.clone(),
             body: TypedCodeBlock {
                 contents: vec![],
@@ -471,6 +607,7 @@ impl TypedTraitFn {
             visibility: Visibility::Public,
             type_parameters: vec![],
             is_contract_call: mode == Mode::ImplAbiFn,
+            attributes: self.attributes.clone(),
         }
     }
 }
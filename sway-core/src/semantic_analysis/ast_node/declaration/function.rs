@@ -5,21 +5,27 @@ use crate::{
         ast_node::{
             IsConstant, Mode, TypedCodeBlock, TypedDeclaration, TypedExpression,
             TypedExpressionVariant, TypedReturnStatement, TypedVariableDeclaration,
-            VariableMutability,
         },
-        create_new_scope, NamespaceWrapper, TypeCheckArguments,
+        create_new_scope, NamespaceRef, NamespaceWrapper, TypeCheckArguments,
     },
     type_engine::*,
     Ident, TypeParameter,
 };
 
-use sway_types::{join_spans, span::Span, Function, Property};
+use sway_types::{join_spans, span::Span, Function, LoggedType, Property, RevertCodes};
 
 use sha2::{Digest, Sha256};
 
 mod function_parameter;
 pub use function_parameter::*;
 
+mod revert_codes;
+use revert_codes::RevertCodeSet;
+
+mod walk;
+use walk::walk_code_block;
+pub(crate) use walk::walk_expression;
+
 #[derive(Clone, Debug)]
 pub struct TypedFunctionDeclaration {
     pub(crate) name: Ident,
@@ -35,6 +41,7 @@ pub struct TypedFunctionDeclaration {
     /// whether this function exists in another contract and requires a call to it or not
     pub(crate) is_contract_call: bool,
     pub(crate) purity: Purity,
+    pub(crate) attributes: Vec<Attribute>,
 }
 
 impl TypedFunctionDeclaration {
@@ -65,11 +72,19 @@ impl TypedFunctionDeclaration {
             return_type_span,
             visibility,
             purity,
-            ..
+            attributes,
         } = fn_decl.clone();
         opts.purity = purity;
+        opts.in_loop = false;
         // insert type parameters as Unknown types
         let type_mapping = insert_type_parameters(&type_parameters);
+
+        // insert parameters and generic type declarations into namespace
+        let namespace = create_new_scope(namespace);
+        type_parameters.iter().for_each(|param| {
+            namespace.insert(param.name_ident.clone(), param.into());
+        });
+        // the type parameters may be given as type arguments within the return type
         let return_type =
             if let Some(matching_id) = return_type.matches_type_parameter(&type_mapping) {
                 insert_type(TypeInfo::Ref(matching_id))
@@ -83,16 +98,100 @@ impl TypedFunctionDeclaration {
                         insert_type(TypeInfo::ErrorRecovery)
                     })
             };
+        // function values have no run-time representation, so they can't be returned
+        if let TypeInfo::Function { .. } = look_up_type_id(return_type) {
+            errors.push(CompileError::MisplacedFunctionValue(
+                return_type_span.clone(),
+            ));
+        }
 
-        // insert parameters and generic type declarations into namespace
-        let namespace = create_new_scope(namespace);
-        type_parameters.iter().for_each(|param| {
-            namespace.insert(param.name_ident.clone(), param.into());
-        });
+        // A type parameter has the methods of the traits it is constrained by. Calls to them are
+        // resolved to the implementation for the actual type when the function is monomorphized.
+        for (type_parameter, type_id) in type_mapping.iter() {
+            for TraitConstraint { name: trait_name } in type_parameter.trait_constraints.iter() {
+                match namespace.get_symbol(trait_name).value {
+                    Some(TypedDeclaration::TraitDeclaration(trait_decl)) => {
+                        // the trait's own type parameters and associated types are unknown for
+                        // each constrained type parameter, rather than shared with the trait
+                        let trait_type_mapping = insert_type_parameters(
+                            &[
+                                trait_decl.type_parameters.clone(),
+                                trait_decl.associated_type_parameters(),
+                            ]
+                            .concat(),
+                        );
+                        check!(
+                            namespace.insert_trait_implementation(
+                                CallPath {
+                                    prefixes: vec![],
+                                    suffix: trait_name.clone(),
+                                },
+                                vec![],
+                                look_up_type_id(*type_id),
+                                trait_decl
+                                    .interface_surface
+                                    .iter()
+                                    .map(|x| {
+                                        let mut x = x.clone();
+                                        x.copy_types(&trait_type_mapping);
+                                        x.to_dummy_func(Mode::NonAbi).replace_self_types(*type_id)
+                                    })
+                                    .collect(),
+                            ),
+                            (),
+                            warnings,
+                            errors
+                        );
+                    }
+                    _ => errors.push(CompileError::UnknownTrait {
+                        name: trait_name.clone(),
+                        span: trait_name.span().clone(),
+                    }),
+                }
+            }
+        }
+
+        // A function which may call itself is declared within its own body.  Only its signature
+        // is needed to type check the calls, so the body is left empty.  Any errors resolving the
+        // types are reported below.
+        if opts.allow_self_recursion && type_parameters.is_empty() {
+            let parameters = parameters
+                .iter()
+                .map(|param| TypedFunctionParameter {
+                    name: param.name.clone(),
+                    r#type: namespace
+                        .resolve_type_with_self(param.r#type.clone(), self_type)
+                        .unwrap_or_else(|_| insert_type(TypeInfo::ErrorRecovery)),
+                    type_span: param.type_span.clone(),
+                    is_reference: param.is_reference,
+                })
+                .collect();
+            namespace.insert(
+                name.clone(),
+                TypedDeclaration::FunctionDeclaration(TypedFunctionDeclaration {
+                    name: name.clone(),
+                    body: TypedCodeBlock {
+                        contents: vec![],
+                        whole_block_span: body.whole_block_span.clone(),
+                    },
+                    parameters,
+                    span: span.clone(),
+                    return_type,
+                    type_parameters: vec![],
+                    return_type_span: return_type_span.clone(),
+                    visibility,
+                    is_contract_call: false,
+                    purity,
+                    attributes: attributes.clone(),
+                }),
+            );
+        }
+        opts.allow_self_recursion = false;
         for FunctionParameter {
             name,
             r#type,
             type_span,
+            is_reference,
         } in parameters.clone()
         {
             let r#type = if let Some(matching_id) = r#type.matches_type_parameter(&type_mapping) {
@@ -117,7 +216,8 @@ impl TypedFunctionDeclaration {
                         is_constant: IsConstant::No,
                         span: name.span().clone(),
                     },
-                    is_mutable: VariableMutability::Immutable,
+                    // a `ref mut` parameter is the caller's mutable variable
+                    is_mutable: is_reference.into(),
                     type_ascription: r#type,
                 }),
             );
@@ -158,8 +258,10 @@ impl TypedFunctionDeclaration {
                      name,
                      r#type,
                      type_span,
+                     is_reference,
                  }| TypedFunctionParameter {
                     name,
+                    is_reference,
                     r#type: if let Some(matching_id) = r#type.matches_type_parameter(&type_mapping)
                     {
                         insert_type(TypeInfo::Ref(matching_id))
@@ -266,6 +368,7 @@ impl TypedFunctionDeclaration {
                 // if this is for a contract, then it is a contract call
                 is_contract_call: mode == Mode::ImplAbiFn,
                 purity,
+                attributes,
             },
             warnings,
             errors,
@@ -289,11 +392,14 @@ impl TypedFunctionDeclaration {
     /// type ids which refer to generic types to be fresh copies, maintaining their referential
     /// relationship. This is used so when this function is resolved, the types don't clobber the
     /// generic type info.
+    ///
+    /// The mapping from the type parameters to their fresh types is returned too, so that their
+    /// trait constraints can be checked once the types are known.
     pub(crate) fn monomorphize(
         &self,
         type_arguments: Vec<(TypeInfo, Span)>,
         self_type: TypeId,
-    ) -> CompileResult<TypedFunctionDeclaration> {
+    ) -> CompileResult<(TypedFunctionDeclaration, Vec<(TypeParameter, TypeId)>)> {
         let mut warnings: Vec<CompileWarning> = vec![];
         let mut errors: Vec<CompileError> = vec![];
         debug_assert!(
@@ -351,7 +457,7 @@ impl TypedFunctionDeclaration {
             insert_type(look_up_type_id_raw(new_decl.return_type))
         };
 
-        ok(new_decl, warnings, errors)
+        ok((new_decl, type_mapping), warnings, errors)
     }
     /// If there are parameters, join their spans. Otherwise, use the fn name span.
     pub(crate) fn parameters_span(&self) -> Span {
@@ -364,6 +470,33 @@ impl TypedFunctionDeclaration {
             self.name.span().clone()
         }
     }
+    /// The hint given by an `#[inline(..)]` attribute on this function, if there is one.
+    pub(crate) fn inline(&self) -> Option<Inline> {
+        attribute::inline_hint(&self.attributes)
+    }
+    /// Whether this is a `#[test]` function, which is checked but left out of the program.
+    pub(crate) fn is_test(&self) -> bool {
+        attribute::is_test(&self.attributes)
+    }
+    pub(crate) fn is_deprecated(&self) -> bool {
+        attribute::is_deprecated(&self.attributes)
+    }
+    /// Whether a parameter is of function type, in which case the function only exists at its
+    /// call sites, bound to the function values it is passed.
+    pub(crate) fn has_function_parameters(&self) -> bool {
+        self.parameters
+            .iter()
+            .any(|param| matches!(look_up_type_id(param.r#type), TypeInfo::Function { .. }))
+    }
+    /// Whether these arguments could be passed to this function, judging by their types.
+    pub(crate) fn accepts_arguments<'a>(
+        &self,
+        arguments: impl IntoIterator<Item = &'a TypedExpression>,
+    ) -> bool {
+        self.parameters.iter().zip(arguments).all(|(param, arg)| {
+            look_up_type_id(arg.return_type).could_unify_with(&look_up_type_id(param.r#type))
+        })
+    }
     pub(crate) fn replace_self_types(self, self_type: TypeId) -> Self {
         TypedFunctionDeclaration {
             parameters: self
@@ -465,6 +598,33 @@ impl TypedFunctionDeclaration {
             }],
         }
     }
+
+    /// The codes this function may revert with through the `revert` and `require` intrinsics,
+    /// including within the functions it calls.
+    pub fn generate_revert_codes(&self) -> RevertCodes {
+        let mut codes = RevertCodeSet::default();
+        codes.gather_code_block(&self.body);
+        RevertCodes {
+            name: self.name.as_str().to_string(),
+            codes: codes.codes.into_iter().collect(),
+            dynamic: codes.dynamic,
+        }
+    }
+
+    /// The types of the values which this function may log through the `log` intrinsic,
+    /// including within the functions it calls, in the order they're first logged.
+    pub fn generate_logged_types(&self) -> Vec<LoggedType> {
+        let mut logged_types: Vec<LoggedType> = vec![];
+        walk_code_block(&self.body, &mut |expr| {
+            if let TypedExpressionVariant::Log { value } = &expr.expression {
+                let logged_type = value.return_type.generate_logged_type();
+                if !logged_types.iter().any(|x| x.log_id == logged_type.log_id) {
+                    logged_types.push(logged_type);
+                }
+            }
+        });
+        logged_types
+    }
 }
 
 #[test]
@@ -499,6 +659,7 @@ fn test_function_selector_behavior() {
         },
         visibility: Visibility::Public,
         is_contract_call: false,
+        attributes: vec![],
     };
 
     let selector_text = match decl.to_selector_name().value {
@@ -538,6 +699,7 @@ fn test_function_selector_behavior() {
                     span: pest::Span::new(" ".into(), 0, 0).unwrap(),
                     path: None,
                 },
+                is_reference: false,
             },
             TypedFunctionParameter {
                 name: Ident::new_with_override(
@@ -552,6 +714,7 @@ fn test_function_selector_behavior() {
                     span: pest::Span::new(" ".into(), 0, 0).unwrap(),
                     path: None,
                 },
+                is_reference: false,
             },
         ],
         span: Span {
@@ -566,6 +729,7 @@ fn test_function_selector_behavior() {
         },
         visibility: Visibility::Public,
         is_contract_call: false,
+        attributes: vec![],
     };
 
     let selector_text = match decl.to_selector_name().value {
@@ -590,3 +754,56 @@ pub(crate) fn insert_type_parameters(params: &[TypeParameter]) -> Vec<(TypeParam
         })
         .collect()
 }
+
+/// Check that the types which the type parameters in `type_mapping` have been resolved to
+/// implement the traits which the parameters are constrained by.
+pub(crate) fn check_trait_constraints(
+    type_mapping: &[(TypeParameter, TypeId)],
+    namespace: NamespaceRef,
+    self_type: TypeId,
+    span: &Span,
+) -> CompileResult<()> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    for (type_parameter, type_id) in type_mapping {
+        if type_parameter.trait_constraints.is_empty() {
+            continue;
+        }
+        // a numeric literal is a `u64` unless something else has given it a type, and the impl
+        // for `u64` is the one it should use
+        if look_up_type_id(*type_id) == TypeInfo::Numeric {
+            let u64_type = insert_type(TypeInfo::UnsignedInteger(IntegerBits::SixtyFour));
+            match unify_with_self(*type_id, u64_type, self_type, span) {
+                Ok(mut ws) => warnings.append(&mut ws),
+                Err(e) => errors.push(e.into()),
+            }
+        }
+        for TraitConstraint { name: trait_name } in type_parameter.trait_constraints.iter() {
+            match look_up_type_id(*type_id) {
+                // the type couldn't be inferred, which has already been reported
+                TypeInfo::Unknown | TypeInfo::ErrorRecovery => (),
+                _ if namespace.implements_trait(trait_name, *type_id) => (),
+                // name structs and enums as they would be in an `impl`
+                TypeInfo::Struct { name, .. } | TypeInfo::Enum { name, .. } => {
+                    errors.push(CompileError::TraitConstraintNotSatisfied {
+                        ty: name,
+                        trait_name: trait_name.clone(),
+                        param: type_parameter.name_ident.clone(),
+                        span: span.clone(),
+                    })
+                }
+                _ => errors.push(CompileError::TraitConstraintNotSatisfied {
+                    ty: type_id.friendly_type_str(),
+                    trait_name: trait_name.clone(),
+                    param: type_parameter.name_ident.clone(),
+                    span: span.clone(),
+                }),
+            }
+        }
+    }
+    if errors.is_empty() {
+        ok((), warnings, errors)
+    } else {
+        err(warnings, errors)
+    }
+}
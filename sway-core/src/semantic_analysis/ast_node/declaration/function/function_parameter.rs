@@ -7,6 +7,9 @@ pub struct TypedFunctionParameter {
     pub(crate) name: Ident,
     pub(crate) r#type: TypeId,
     pub(crate) type_span: Span,
+    /// Whether this is a `ref mut` parameter, or `&mut self`, through which the function mutates
+    /// the caller's variable.
+    pub(crate) is_reference: bool,
 }
 
 impl TypedFunctionParameter {
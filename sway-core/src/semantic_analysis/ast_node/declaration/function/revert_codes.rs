@@ -0,0 +1,37 @@
+//! Gathering the codes which a function may revert with, for the revert code manifest.
+
+use super::walk::walk_code_block;
+use crate::{
+    parse_tree,
+    semantic_analysis::ast_node::{TypedCodeBlock, TypedExpressionVariant},
+};
+
+use std::collections::BTreeSet;
+
+#[derive(Default)]
+pub(crate) struct RevertCodeSet {
+    pub(crate) codes: BTreeSet<u64>,
+    /// Whether some code can't be known until run time, including any reverts in inline asm.
+    pub(crate) dynamic: bool,
+}
+
+impl RevertCodeSet {
+    /// Adds the codes of every revert within `block`. The bodies of called functions are part of
+    /// the typed tree, so those are included.
+    pub(crate) fn gather_code_block(&mut self, block: &TypedCodeBlock) {
+        walk_code_block(block, &mut |expr| match &expr.expression {
+            TypedExpressionVariant::Revert { code } => match code.expression {
+                TypedExpressionVariant::Literal(parse_tree::Literal::U64(code)) => {
+                    self.codes.insert(code);
+                }
+                _ => self.dynamic = true,
+            },
+            TypedExpressionVariant::AsmExpression { body, .. }
+                if body.iter().any(|op| op.op_name.as_str() == "rvrt") =>
+            {
+                self.dynamic = true
+            }
+            _ => (),
+        });
+    }
+}
@@ -0,0 +1,135 @@
+//! A traversal of every expression within a function body, including the bodies of the
+//! functions it calls, which are part of the typed tree.
+
+use crate::semantic_analysis::{
+    ast_node::{
+        TypedCodeBlock, TypedConstantDeclaration, TypedDeclaration, TypedExpression,
+        TypedExpressionVariant, TypedForLoop, TypedForLoopIterable, TypedReassignment,
+        TypedReturnStatement, TypedStorageReassignment, TypedVariableDeclaration, TypedWhileLoop,
+    },
+    TypedAstNode, TypedAstNodeContent,
+};
+
+/// Calls `f` on every expression within `block`, outermost first.
+pub(crate) fn walk_code_block(block: &TypedCodeBlock, f: &mut impl FnMut(&TypedExpression)) {
+    for node in &block.contents {
+        walk_node(node, f);
+    }
+}
+
+fn walk_node(node: &TypedAstNode, f: &mut impl FnMut(&TypedExpression)) {
+    match &node.content {
+        TypedAstNodeContent::ReturnStatement(TypedReturnStatement { expr })
+        | TypedAstNodeContent::Expression(expr)
+        | TypedAstNodeContent::ImplicitReturnExpression(expr) => walk_expression(expr, f),
+        TypedAstNodeContent::Declaration(
+            TypedDeclaration::VariableDeclaration(TypedVariableDeclaration { body: expr, .. })
+            | TypedDeclaration::ConstantDeclaration(TypedConstantDeclaration { value: expr, .. })
+            | TypedDeclaration::Reassignment(TypedReassignment { rhs: expr, .. })
+            | TypedDeclaration::StorageReassignment(TypedStorageReassignment { rhs: expr, .. }),
+        ) => walk_expression(expr, f),
+        TypedAstNodeContent::WhileLoop(TypedWhileLoop { condition, body }) => {
+            walk_expression(condition, f);
+            walk_code_block(body, f);
+        }
+        TypedAstNodeContent::ForLoop(TypedForLoop { iterable, body, .. }) => {
+            match iterable {
+                TypedForLoopIterable::Array(array) => walk_expression(array, f),
+                TypedForLoopIterable::Range { start, end } => {
+                    walk_expression(start, f);
+                    walk_expression(end, f);
+                }
+            }
+            walk_code_block(body, f);
+        }
+        TypedAstNodeContent::Declaration(_)
+        | TypedAstNodeContent::Break
+        | TypedAstNodeContent::Continue
+        | TypedAstNodeContent::SideEffect => (),
+    }
+}
+
+/// Calls `f` on every expression within `expr`, including `expr` itself, outermost first.
+pub(crate) fn walk_expression(expr: &TypedExpression, f: &mut impl FnMut(&TypedExpression)) {
+    use TypedExpressionVariant::*;
+    f(expr);
+    match &expr.expression {
+        FunctionApplication {
+            arguments,
+            function_body,
+            ..
+        } => {
+            for (_name, arg) in arguments {
+                walk_expression(arg, f);
+            }
+            walk_code_block(function_body, f);
+        }
+        LazyOperator { lhs, rhs, .. }
+        | BuiltinOperator { lhs, rhs, .. }
+        | ArrayIndex {
+            prefix: lhs,
+            index: rhs,
+        } => {
+            walk_expression(lhs, f);
+            walk_expression(rhs, f);
+        }
+        // the body of a function value is within each call it is bound to
+        FunctionValue(value) => {
+            for (_name, capture) in &value.captures {
+                walk_expression(capture, f);
+            }
+        }
+        Tuple { fields: exprs }
+        | Array { contents: exprs }
+        | VecOperation {
+            arguments: exprs, ..
+        }
+        | FunctionValueApplication {
+            arguments: exprs, ..
+        } => {
+            for expr in exprs {
+                walk_expression(expr, f);
+            }
+        }
+        StructExpression { fields, .. } => {
+            for field in fields {
+                walk_expression(&field.value, f);
+            }
+        }
+        CodeBlock(block) => walk_code_block(block, f),
+        IfExp {
+            condition,
+            then,
+            r#else,
+        } => {
+            walk_expression(condition, f);
+            walk_expression(then, f);
+            if let Some(r#else) = r#else {
+                walk_expression(r#else, f);
+            }
+        }
+        AsmExpression { registers, .. } => {
+            for initializer in registers.iter().filter_map(|x| x.initializer.as_ref()) {
+                walk_expression(initializer, f);
+            }
+        }
+        StructFieldAccess { prefix, .. }
+        | EnumArgAccess { prefix, .. }
+        | EnumTag { prefix }
+        | TupleElemAccess { prefix, .. }
+        | AbiCast {
+            address: prefix, ..
+        }
+        | EnumInstantiation {
+            contents: Some(prefix),
+            ..
+        }
+        | Revert { code: prefix }
+        | Log { value: prefix } => walk_expression(prefix, f),
+        Literal(..)
+        | VariableExpression { .. }
+        | FunctionParameter
+        | EnumInstantiation { contents: None, .. }
+        | StorageAccess { .. } => (),
+    }
+}
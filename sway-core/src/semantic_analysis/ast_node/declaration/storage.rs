@@ -0,0 +1,112 @@
+use crate::{error::*, semantic_analysis::TypedExpression, type_engine::*, Ident, TypeParameter};
+
+use sway_types::span::Span;
+
+use sha2::{Digest, Sha256};
+
+/// The type-checked version of a contract's `storage` block. Every field is given its own slot in
+/// contract storage, determined only by the position of the field within the declaration, so the
+/// layout is stable across compilations.
+#[derive(Clone, Debug)]
+pub struct TypedStorageDeclaration {
+    pub(crate) fields: Vec<TypedStorageField>,
+    pub(crate) span: Span,
+}
+
+impl TypedStorageDeclaration {
+    pub(crate) fn new(fields: Vec<TypedStorageField>, span: Span) -> Self {
+        TypedStorageDeclaration { fields, span }
+    }
+
+    /// Given the name of a storage field, find its index within the declaration (which
+    /// determines its slot) along with its type.
+    pub(crate) fn find_field(&self, field_name: &Ident) -> CompileResult<(usize, TypeId)> {
+        match self
+            .fields
+            .iter()
+            .enumerate()
+            .find(|(_, field)| field.name == *field_name)
+        {
+            Some((ix, field)) => ok((ix, field.r#type), vec![], vec![]),
+            None => err(
+                vec![],
+                vec![CompileError::StorageFieldDoesNotExist {
+                    field_name: field_name.as_str().to_string(),
+                    span: field_name.span().clone(),
+                }],
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TypedStorageField {
+    pub(crate) name: Ident,
+    pub(crate) r#type: TypeId,
+    pub(crate) initializer: TypedExpression,
+}
+
+impl TypedStorageField {
+    pub(crate) fn new(name: Ident, r#type: TypeId, initializer: TypedExpression) -> Self {
+        TypedStorageField {
+            name,
+            r#type,
+            initializer,
+        }
+    }
+}
+
+/// A write to a single storage field, e.g. `storage.counter = 5;`.
+#[derive(Clone, Debug)]
+pub struct TypedStorageReassignment {
+    pub(crate) field_name: Ident,
+    /// The index of the field within the storage declaration.
+    pub(crate) ix: usize,
+    pub(crate) rhs: TypedExpression,
+}
+
+impl TypedStorageReassignment {
+    pub(crate) fn copy_types(&mut self, type_mapping: &[(TypeParameter, TypeId)]) {
+        self.rhs.copy_types(type_mapping);
+    }
+
+    pub(crate) fn span(&self) -> Span {
+        self.field_name.span().clone()
+    }
+}
+
+/// Returns the storage key of the first slot backing the field at index `ix`, which is
+/// `sha256("storage_<ix>")`.
+pub(crate) fn get_storage_key(ix: usize) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(format!("storage_{}", ix).as_bytes());
+    hasher.finalize().into()
+}
+
+/// Returns the storage key of the flag which is set once the storage fields have been given the
+/// values of their initializers, which is `sha256("storage_initialized")`. The fields are
+/// initialized by the first call to the contract, which finds the flag unset.
+pub(crate) fn get_storage_initialized_key() -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"storage_initialized");
+    hasher.finalize().into()
+}
+
+/// Returns the keys of all the slots backing a value `size_in_words` words long which is stored
+/// starting at `base_key`.
+///
+/// Values of a single word live in one slot and are accessed with `SRW`/`SWW`. Anything larger is
+/// split into 32 byte chunks, each in its own slot, and accessed with `SRWQ`/`SWWQ`. The first
+/// chunk lives at `base_key` and chunk `n` at `sha256(base_key ++ n)`, with `n` as a big endian
+/// `u64`.
+pub(crate) fn get_storage_chunk_keys(base_key: &[u8; 32], size_in_words: u64) -> Vec<[u8; 32]> {
+    let num_chunks = std::cmp::max(1, (size_in_words + 3) / 4);
+    std::iter::once(*base_key)
+        .chain((1..num_chunks).map(|chunk| {
+            let mut hasher = Sha256::new();
+            hasher.update(base_key);
+            hasher.update(chunk.to_be_bytes());
+            hasher.finalize().into()
+        }))
+        .collect()
+}
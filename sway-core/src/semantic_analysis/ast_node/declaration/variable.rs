@@ -1,7 +1,6 @@
 use crate::semantic_analysis::TypedExpression;
 use crate::type_engine::*;
 use crate::Ident;
-use crate::Visibility;
 use crate::{type_engine::TypeId, TypeParameter};
 
 #[derive(Clone, Debug)]
@@ -10,8 +9,6 @@ pub enum VariableMutability {
     Mutable,
     // private + immutable
     Immutable,
-    // public + immutable
-    ExportedConst,
     // public + mutable is invalid
 }
 
@@ -24,12 +21,6 @@ impl VariableMutability {
     pub fn is_mutable(&self) -> bool {
         matches!(self, VariableMutability::Mutable)
     }
-    pub fn visibility(&self) -> Visibility {
-        match self {
-            VariableMutability::ExportedConst => Visibility::Public,
-            _ => Visibility::Private,
-        }
-    }
     pub fn is_immutable(&self) -> bool {
         !self.is_mutable()
     }
@@ -0,0 +1,742 @@
+//! Evaluation of the values of `const` declarations at compile time.
+//!
+//! A constant's value is folded down to a literal, or to a struct, tuple or array expression whose
+//! leaves are all literals.  In that form both backends can put it straight in the data section.
+
+use super::*;
+use crate::{
+    error::*,
+    parse_tree::{CallPath, LazyOp, Literal, OpVariant},
+    semantic_analysis::{ast_node::*, NamespaceRef, NamespaceWrapper},
+    type_engine::{look_up_type_id, IntegerBits, TypeInfo},
+};
+
+use std::{cmp::Ordering, convert::TryInto};
+
+/// Evaluates `expr`, the value of a constant, to a literal or an aggregate of literals.  Anything
+/// which can't be known at compile time is an error.
+pub(crate) fn evaluate_constant(
+    expr: &TypedExpression,
+    namespace: NamespaceRef,
+) -> CompileResult<TypedExpression> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let expression = match &expr.expression {
+        TypedExpressionVariant::Literal(lit) => TypedExpressionVariant::Literal(lit.clone()),
+        TypedExpressionVariant::VariableExpression { name } => {
+            match namespace.get_symbol(name).value {
+                // The value of another constant has already been evaluated.
+                Some(TypedDeclaration::ConstantDeclaration(TypedConstantDeclaration {
+                    value,
+                    ..
+                })) => value.expression,
+                _ => {
+                    errors.push(non_constant(expr));
+                    return err(warnings, errors);
+                }
+            }
+        }
+        TypedExpressionVariant::Tuple { fields } => TypedExpressionVariant::Tuple {
+            fields: check!(
+                evaluate_all(fields, namespace),
+                return err(warnings, errors),
+                warnings,
+                errors
+            ),
+        },
+        TypedExpressionVariant::Array { contents } => TypedExpressionVariant::Array {
+            contents: check!(
+                evaluate_all(contents, namespace),
+                return err(warnings, errors),
+                warnings,
+                errors
+            ),
+        },
+        TypedExpressionVariant::StructExpression {
+            struct_name,
+            fields,
+        } => {
+            let mut evaluated_fields = Vec::with_capacity(fields.len());
+            for TypedStructExpressionField { name, value } in fields {
+                let value = check!(
+                    evaluate_constant(value, namespace),
+                    continue,
+                    warnings,
+                    errors
+                );
+                evaluated_fields.push(TypedStructExpressionField {
+                    name: name.clone(),
+                    value,
+                });
+            }
+            if !errors.is_empty() {
+                return err(warnings, errors);
+            }
+            TypedExpressionVariant::StructExpression {
+                struct_name: struct_name.clone(),
+                fields: evaluated_fields,
+            }
+        }
+        TypedExpressionVariant::StructFieldAccess {
+            prefix,
+            field_to_access,
+            ..
+        } => {
+            let prefix = check!(
+                evaluate_constant(prefix, namespace),
+                return err(warnings, errors),
+                warnings,
+                errors
+            );
+            let field = match prefix.expression {
+                TypedExpressionVariant::StructExpression { fields, .. } => fields
+                    .into_iter()
+                    .find(|field| field.name.as_str() == field_to_access.name),
+                _ => None,
+            };
+            match field {
+                Some(field) => field.value.expression,
+                None => {
+                    errors.push(non_constant(expr));
+                    return err(warnings, errors);
+                }
+            }
+        }
+        TypedExpressionVariant::TupleElemAccess {
+            prefix,
+            elem_to_access_num,
+            ..
+        } => {
+            let prefix = check!(
+                evaluate_constant(prefix, namespace),
+                return err(warnings, errors),
+                warnings,
+                errors
+            );
+            match prefix.expression {
+                TypedExpressionVariant::Tuple { mut fields }
+                    if *elem_to_access_num < fields.len() =>
+                {
+                    fields.swap_remove(*elem_to_access_num).expression
+                }
+                _ => {
+                    errors.push(non_constant(expr));
+                    return err(warnings, errors);
+                }
+            }
+        }
+        TypedExpressionVariant::ArrayIndex { prefix, index } => {
+            let prefix = check!(
+                evaluate_constant(prefix, namespace),
+                return err(warnings, errors),
+                warnings,
+                errors
+            );
+            let index_value = check!(
+                evaluate_constant(index, namespace),
+                return err(warnings, errors),
+                warnings,
+                errors
+            );
+            let (mut contents, ix) = match (prefix.expression, index_value.expression) {
+                (
+                    TypedExpressionVariant::Array { contents },
+                    TypedExpressionVariant::Literal(Literal::U64(ix) | Literal::Numeric(ix)),
+                ) => (contents, ix),
+                _ => {
+                    errors.push(non_constant(expr));
+                    return err(warnings, errors);
+                }
+            };
+            if ix >= contents.len() as u64 {
+                errors.push(CompileError::ArrayOutOfBounds {
+                    index: ix,
+                    count: contents.len() as u64,
+                    span: index.span.clone(),
+                });
+                return err(warnings, errors);
+            }
+            contents.swap_remove(ix as usize).expression
+        }
+        TypedExpressionVariant::FunctionApplication {
+            name,
+            arguments,
+            selector: None,
+            ..
+        } => match (core_op(name), &arguments[..]) {
+            (Some(op), [(_, lhs), (_, rhs)]) => check!(
+                evaluate_operator(expr, op, lhs, rhs, namespace),
+                return err(warnings, errors),
+                warnings,
+                errors
+            ),
+            _ => {
+                errors.push(non_constant(expr));
+                return err(warnings, errors);
+            }
+        },
+        TypedExpressionVariant::BuiltinOperator { op, lhs, rhs } => check!(
+            evaluate_operator(expr, *op, lhs, rhs, namespace),
+            return err(warnings, errors),
+            warnings,
+            errors
+        ),
+        TypedExpressionVariant::LazyOperator { op, lhs, rhs } => {
+            let lhs = check!(
+                evaluate_constant(lhs, namespace),
+                return err(warnings, errors),
+                warnings,
+                errors
+            );
+            let rhs = check!(
+                evaluate_constant(rhs, namespace),
+                return err(warnings, errors),
+                warnings,
+                errors
+            );
+            match (lhs.expression, rhs.expression) {
+                (
+                    TypedExpressionVariant::Literal(Literal::Boolean(lhs)),
+                    TypedExpressionVariant::Literal(Literal::Boolean(rhs)),
+                ) => TypedExpressionVariant::Literal(Literal::Boolean(match op {
+                    LazyOp::And => lhs && rhs,
+                    LazyOp::Or => lhs || rhs,
+                })),
+                _ => {
+                    errors.push(non_constant(expr));
+                    return err(warnings, errors);
+                }
+            }
+        }
+        _ => {
+            errors.push(non_constant(expr));
+            return err(warnings, errors);
+        }
+    };
+    ok(
+        TypedExpression {
+            expression,
+            return_type: expr.return_type,
+            is_constant: IsConstant::Yes,
+            span: expr.span.clone(),
+        },
+        warnings,
+        errors,
+    )
+}
+
+fn evaluate_all(
+    exprs: &[TypedExpression],
+    namespace: NamespaceRef,
+) -> CompileResult<Vec<TypedExpression>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let mut evaluated = Vec::with_capacity(exprs.len());
+    for expr in exprs {
+        evaluated.push(check!(
+            evaluate_constant(expr, namespace),
+            continue,
+            warnings,
+            errors
+        ));
+    }
+    if errors.is_empty() {
+        ok(evaluated, warnings, errors)
+    } else {
+        err(warnings, errors)
+    }
+}
+
+fn non_constant(expr: &TypedExpression) -> CompileError {
+    CompileError::NonConstantExpression {
+        span: expr.span.clone(),
+    }
+}
+
+/// The operator implemented by a call to `core::ops`, if that's what `name` refers to.  Whether
+/// the call is to one of the core library's implementations for a primitive type is decided by
+/// its operands, which must evaluate to literals.
+fn core_op(name: &CallPath) -> Option<OpVariant> {
+    let is_core_ops = name
+        .prefixes
+        .iter()
+        .map(|prefix| prefix.as_str())
+        .eq(["core", "ops"].iter().copied());
+    if is_core_ops {
+        OpVariant::from_method_name(name.suffix.as_str())
+    } else {
+        None
+    }
+}
+
+/// A literal operand of an operator.
+enum Scalar {
+    Unsigned(IntegerBits, U256),
+    Signed(IntegerBits, i128),
+    Boolean(bool),
+    /// Strings, bytes and `b256`s, which may only be compared for equality.
+    Other(Literal),
+}
+
+impl Scalar {
+    fn from_expression(expr: &TypedExpression) -> Option<Scalar> {
+        use IntegerBits::*;
+        let lit = match &expr.expression {
+            TypedExpressionVariant::Literal(lit) => lit,
+            _ => return None,
+        };
+        Some(match lit {
+            Literal::U8(n) => Scalar::Unsigned(Eight, U256::from(*n as u128)),
+            Literal::U16(n) => Scalar::Unsigned(Sixteen, U256::from(*n as u128)),
+            Literal::U32(n) => Scalar::Unsigned(ThirtyTwo, U256::from(*n as u128)),
+            Literal::U64(n) => Scalar::Unsigned(SixtyFour, U256::from(*n as u128)),
+            Literal::U128(n) => Scalar::Unsigned(OneTwentyEight, U256::from(*n)),
+            Literal::U256(bytes) => Scalar::Unsigned(TwoFiftySix, U256::from_be_bytes(bytes)),
+            Literal::I8(n) => Scalar::Signed(Eight, *n as i128),
+            Literal::I16(n) => Scalar::Signed(Sixteen, *n as i128),
+            Literal::I32(n) => Scalar::Signed(ThirtyTwo, *n as i128),
+            Literal::I64(n) => Scalar::Signed(SixtyFour, *n as i128),
+            Literal::Numeric(n) => match look_up_type_id(expr.return_type) {
+                TypeInfo::SignedInteger(bits) => Scalar::Signed(bits, *n as i128),
+                TypeInfo::UnsignedInteger(bits) => Scalar::Unsigned(bits, U256::from(*n as u128)),
+                _ => Scalar::Unsigned(SixtyFour, U256::from(*n as u128)),
+            },
+            Literal::Boolean(b) => Scalar::Boolean(*b),
+            Literal::String(_) | Literal::Byte(_) | Literal::B256(_) => Scalar::Other(lit.clone()),
+        })
+    }
+}
+
+/// Why an operator couldn't be applied to constant operands.
+enum Failure {
+    Overflow,
+    DivisionByZero,
+    Unsupported,
+}
+
+fn evaluate_operator(
+    expr: &TypedExpression,
+    op: OpVariant,
+    lhs: &TypedExpression,
+    rhs: &TypedExpression,
+    namespace: NamespaceRef,
+) -> CompileResult<TypedExpressionVariant> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let lhs = check!(
+        evaluate_constant(lhs, namespace),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    let rhs = check!(
+        evaluate_constant(rhs, namespace),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    let result = match (Scalar::from_expression(&lhs), Scalar::from_expression(&rhs)) {
+        (Some(lhs), Some(rhs)) if op.is_comparison() => compare(op, lhs, rhs),
+        (Some(Scalar::Unsigned(bits, lhs)), Some(Scalar::Unsigned(_, rhs))) => {
+            unsigned_op(op, bits, lhs, rhs)
+        }
+        (Some(Scalar::Signed(bits, lhs)), Some(Scalar::Unsigned(_, rhs))) if op.is_shift() => {
+            signed_shift(op, bits, lhs, shift_amount(rhs))
+        }
+        (Some(Scalar::Signed(bits, lhs)), Some(Scalar::Signed(_, rhs))) => {
+            signed_op(op, bits, lhs, rhs)
+        }
+        (Some(Scalar::Boolean(lhs)), Some(Scalar::Boolean(rhs))) => match op {
+            OpVariant::Xor => Ok(Literal::Boolean(lhs ^ rhs)),
+            OpVariant::BinaryOr => Ok(Literal::Boolean(lhs | rhs)),
+            OpVariant::BinaryAnd => Ok(Literal::Boolean(lhs & rhs)),
+            _ => Err(Failure::Unsupported),
+        },
+        _ => Err(Failure::Unsupported),
+    };
+    match result {
+        Ok(lit) => ok(TypedExpressionVariant::Literal(lit), warnings, errors),
+        Err(failure) => {
+            errors.push(match failure {
+                Failure::Overflow => CompileError::ConstantOverflow {
+                    span: expr.span.clone(),
+                    ty: look_up_type_id(expr.return_type).friendly_type_str(),
+                },
+                Failure::DivisionByZero => CompileError::ConstantDivisionByZero {
+                    span: expr.span.clone(),
+                },
+                Failure::Unsupported => non_constant(expr),
+            });
+            err(warnings, errors)
+        }
+    }
+}
+
+fn compare(op: OpVariant, lhs: Scalar, rhs: Scalar) -> Result<Literal, Failure> {
+    let ordering = match (lhs, rhs) {
+        (Scalar::Unsigned(_, lhs), Scalar::Unsigned(_, rhs)) => lhs.cmp(&rhs),
+        (Scalar::Signed(_, lhs), Scalar::Signed(_, rhs)) => lhs.cmp(&rhs),
+        (Scalar::Boolean(lhs), Scalar::Boolean(rhs)) => lhs.cmp(&rhs),
+        (Scalar::Other(lhs), Scalar::Other(rhs)) => {
+            // A string literal holds its span, which mustn't take part in the comparison.
+            let equal = match (&lhs, &rhs) {
+                (Literal::String(lhs), Literal::String(rhs)) => lhs.as_str() == rhs.as_str(),
+                _ => lhs == rhs,
+            };
+            return match op {
+                OpVariant::Equals => Ok(Literal::Boolean(equal)),
+                OpVariant::NotEquals => Ok(Literal::Boolean(!equal)),
+                _ => Err(Failure::Unsupported),
+            };
+        }
+        _ => return Err(Failure::Unsupported),
+    };
+    Ok(Literal::Boolean(match op {
+        OpVariant::Equals => ordering == Ordering::Equal,
+        OpVariant::NotEquals => ordering != Ordering::Equal,
+        OpVariant::GreaterThan => ordering == Ordering::Greater,
+        OpVariant::LessThan => ordering == Ordering::Less,
+        OpVariant::GreaterThanOrEqualTo => ordering != Ordering::Less,
+        OpVariant::LessThanOrEqualTo => ordering != Ordering::Greater,
+        _ => return Err(Failure::Unsupported),
+    }))
+}
+
+fn unsigned_op(op: OpVariant, bits: IntegerBits, lhs: U256, rhs: U256) -> Result<Literal, Failure> {
+    let width = width(bits);
+    let result = match op {
+        OpVariant::Add => lhs.checked_add(rhs).ok_or(Failure::Overflow)?,
+        OpVariant::Subtract => lhs.checked_sub(rhs).ok_or(Failure::Overflow)?,
+        OpVariant::Multiply => lhs.checked_mul(rhs).ok_or(Failure::Overflow)?,
+        OpVariant::Divide | OpVariant::Modulo => {
+            if rhs == U256::ZERO {
+                return Err(Failure::DivisionByZero);
+            }
+            let (quotient, remainder) = lhs.div_rem(rhs);
+            if matches!(op, OpVariant::Divide) {
+                quotient
+            } else {
+                remainder
+            }
+        }
+        OpVariant::Xor => lhs.zip_with(rhs, |l, r| l ^ r),
+        OpVariant::BinaryOr => lhs.zip_with(rhs, |l, r| l | r),
+        OpVariant::BinaryAnd => lhs.zip_with(rhs, |l, r| l & r),
+        // Bits shifted out of the type are lost, as they are at run time.
+        OpVariant::ShiftLeft => lhs.shl(shift_amount(rhs)).low_bits(width),
+        OpVariant::ShiftRight => lhs.shr(shift_amount(rhs)),
+        _ => return Err(Failure::Unsupported),
+    };
+    if result.bits() > width {
+        return Err(Failure::Overflow);
+    }
+    let low = result.low_u128();
+    Ok(match bits {
+        IntegerBits::Eight => Literal::U8(low as u8),
+        IntegerBits::Sixteen => Literal::U16(low as u16),
+        IntegerBits::ThirtyTwo => Literal::U32(low as u32),
+        IntegerBits::SixtyFour => Literal::U64(low as u64),
+        IntegerBits::OneTwentyEight => Literal::U128(low),
+        IntegerBits::TwoFiftySix => Literal::U256(result.to_be_bytes()),
+    })
+}
+
+fn signed_op(op: OpVariant, bits: IntegerBits, lhs: i128, rhs: i128) -> Result<Literal, Failure> {
+    // The operands are at most a word wide, so none of these can overflow an `i128`.
+    let result = match op {
+        OpVariant::Add => lhs + rhs,
+        OpVariant::Subtract => lhs - rhs,
+        OpVariant::Multiply => lhs * rhs,
+        OpVariant::Divide | OpVariant::Modulo if rhs == 0 => return Err(Failure::DivisionByZero),
+        OpVariant::Divide => lhs / rhs,
+        OpVariant::Modulo => lhs % rhs,
+        OpVariant::Xor => lhs ^ rhs,
+        OpVariant::BinaryOr => lhs | rhs,
+        OpVariant::BinaryAnd => lhs & rhs,
+        _ => return Err(Failure::Unsupported),
+    };
+    signed_literal(bits, result)
+}
+
+fn signed_shift(
+    op: OpVariant,
+    bits: IntegerBits,
+    lhs: i128,
+    amount: u32,
+) -> Result<Literal, Failure> {
+    let width = width(bits);
+    let result = match op {
+        // The result is truncated to the type and sign extended again, as at run time.
+        OpVariant::ShiftLeft if amount >= width => 0,
+        OpVariant::ShiftLeft => ((lhs << amount) << (128 - width)) >> (128 - width),
+        OpVariant::ShiftRight => lhs >> amount.min(127),
+        _ => return Err(Failure::Unsupported),
+    };
+    signed_literal(bits, result)
+}
+
+fn signed_literal(bits: IntegerBits, value: i128) -> Result<Literal, Failure> {
+    let max = (1i128 << (width(bits) - 1)) - 1;
+    if value > max || value < -max - 1 {
+        return Err(Failure::Overflow);
+    }
+    Ok(match bits {
+        IntegerBits::Eight => Literal::I8(value as i8),
+        IntegerBits::Sixteen => Literal::I16(value as i16),
+        IntegerBits::ThirtyTwo => Literal::I32(value as i32),
+        IntegerBits::SixtyFour => Literal::I64(value as i64),
+        IntegerBits::OneTwentyEight | IntegerBits::TwoFiftySix => {
+            unreachable!("signed integers are at most a word wide")
+        }
+    })
+}
+
+fn width(bits: IntegerBits) -> u32 {
+    match bits {
+        IntegerBits::Eight => 8,
+        IntegerBits::Sixteen => 16,
+        IntegerBits::ThirtyTwo => 32,
+        IntegerBits::SixtyFour => 64,
+        IntegerBits::OneTwentyEight => 128,
+        IntegerBits::TwoFiftySix => 256,
+    }
+}
+
+/// Shifting by the width of the widest type or more gives the same result as shifting by exactly
+/// that much.
+fn shift_amount(amount: U256) -> u32 {
+    amount.min(U256::from(256)).low_u128() as u32
+}
+
+/// An unsigned integer of up to 256 bits, as little-endian words.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct U256([u64; 4]);
+
+impl U256 {
+    const ZERO: U256 = U256([0; 4]);
+
+    fn from_be_bytes(bytes: &[u8; 32]) -> Self {
+        let mut words = [0; 4];
+        for (word, chunk) in words.iter_mut().rev().zip(bytes.chunks(8)) {
+            *word = u64::from_be_bytes(chunk.try_into().unwrap());
+        }
+        U256(words)
+    }
+
+    fn to_be_bytes(self) -> [u8; 32] {
+        let mut bytes = [0; 32];
+        for (chunk, word) in bytes.chunks_mut(8).zip(self.0.iter().rev()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        bytes
+    }
+
+    fn low_u128(self) -> u128 {
+        (self.0[1] as u128) << 64 | self.0[0] as u128
+    }
+
+    /// The number of bits needed to hold this value.
+    fn bits(self) -> u32 {
+        self.0
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, word)| **word != 0)
+            .map(|(ix, word)| ix as u32 * 64 + 64 - word.leading_zeros())
+            .unwrap_or(0)
+    }
+
+    fn bit(self, n: u32) -> bool {
+        self.0[(n / 64) as usize] >> (n % 64) & 1 == 1
+    }
+
+    fn overflowing_add(self, rhs: U256) -> (U256, bool) {
+        let mut words = [0; 4];
+        let mut carry = false;
+        for (word, (lhs, rhs)) in words.iter_mut().zip(self.0.iter().zip(rhs.0.iter())) {
+            let (sum, carry_a) = lhs.overflowing_add(*rhs);
+            let (sum, carry_b) = sum.overflowing_add(carry as u64);
+            *word = sum;
+            carry = carry_a || carry_b;
+        }
+        (U256(words), carry)
+    }
+
+    fn overflowing_sub(self, rhs: U256) -> (U256, bool) {
+        let mut words = [0; 4];
+        let mut borrow = false;
+        for (word, (lhs, rhs)) in words.iter_mut().zip(self.0.iter().zip(rhs.0.iter())) {
+            let (difference, borrow_a) = lhs.overflowing_sub(*rhs);
+            let (difference, borrow_b) = difference.overflowing_sub(borrow as u64);
+            *word = difference;
+            borrow = borrow_a || borrow_b;
+        }
+        (U256(words), borrow)
+    }
+
+    fn checked_add(self, rhs: U256) -> Option<U256> {
+        match self.overflowing_add(rhs) {
+            (sum, false) => Some(sum),
+            (_, true) => None,
+        }
+    }
+
+    fn checked_sub(self, rhs: U256) -> Option<U256> {
+        match self.overflowing_sub(rhs) {
+            (difference, false) => Some(difference),
+            (_, true) => None,
+        }
+    }
+
+    fn checked_mul(self, rhs: U256) -> Option<U256> {
+        // Long multiplication into twice as many words, which must then fit back into four.
+        let mut words = [0u64; 8];
+        for (i, lhs) in self.0.iter().enumerate() {
+            let mut carry = 0u128;
+            for (j, rhs) in rhs.0.iter().enumerate() {
+                let product = *lhs as u128 * *rhs as u128 + words[i + j] as u128 + carry;
+                words[i + j] = product as u64;
+                carry = product >> 64;
+            }
+            words[i + 4] = carry as u64;
+        }
+        if words[4..].iter().any(|word| *word != 0) {
+            None
+        } else {
+            Some(U256(words[..4].try_into().unwrap()))
+        }
+    }
+
+    /// Long division, a bit at a time.  `rhs` must not be zero.
+    fn div_rem(self, rhs: U256) -> (U256, U256) {
+        let mut quotient = U256::ZERO;
+        let mut remainder = U256::ZERO;
+        for n in (0..self.bits()).rev() {
+            // The remainder is less than `rhs`, but doubling it may still overflow.
+            let overflow = remainder.bit(255);
+            remainder = remainder.shl(1);
+            remainder.0[0] |= self.bit(n) as u64;
+            if overflow || remainder >= rhs {
+                remainder = remainder.overflowing_sub(rhs).0;
+                quotient.0[(n / 64) as usize] |= 1 << (n % 64);
+            }
+        }
+        (quotient, remainder)
+    }
+
+    fn zip_with(self, rhs: U256, f: impl Fn(u64, u64) -> u64) -> U256 {
+        let mut words = self.0;
+        for (word, rhs) in words.iter_mut().zip(rhs.0.iter()) {
+            *word = f(*word, *rhs);
+        }
+        U256(words)
+    }
+
+    fn shl(self, n: u32) -> U256 {
+        let mut words = [0; 4];
+        if n < 256 {
+            let (skip, shift) = ((n / 64) as usize, n % 64);
+            for (ix, word) in words.iter_mut().enumerate().skip(skip) {
+                *word = self.0[ix - skip] << shift;
+                if shift > 0 && ix > skip {
+                    *word |= self.0[ix - skip - 1] >> (64 - shift);
+                }
+            }
+        }
+        U256(words)
+    }
+
+    fn shr(self, n: u32) -> U256 {
+        let mut words = [0; 4];
+        if n < 256 {
+            let (skip, shift) = ((n / 64) as usize, n % 64);
+            for (ix, word) in words.iter_mut().enumerate().take(4 - skip) {
+                *word = self.0[ix + skip] >> shift;
+                if shift > 0 && ix + skip + 1 < 4 {
+                    *word |= self.0[ix + skip + 1] << (64 - shift);
+                }
+            }
+        }
+        U256(words)
+    }
+
+    /// The lowest `n` bits of this value.
+    fn low_bits(self, n: u32) -> U256 {
+        self.shl(256 - n).shr(256 - n)
+    }
+}
+
+impl From<u128> for U256 {
+    fn from(value: u128) -> Self {
+        U256([value as u64, (value >> 64) as u64, 0, 0])
+    }
+}
+
+impl Ord for U256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u256(hi: u128, lo: u128) -> U256 {
+        U256::from(lo).zip_with(U256::from(hi).shl(128), |l, h| l | h)
+    }
+
+    #[test]
+    fn u256_arithmetic() {
+        let max = u256(u128::MAX, u128::MAX);
+        assert_eq!(max.checked_add(U256::from(1)), None);
+        assert_eq!(
+            U256::from(u128::MAX).checked_add(U256::from(1)),
+            Some(u256(1, 0))
+        );
+        assert_eq!(U256::ZERO.checked_sub(U256::from(1)), None);
+        assert_eq!(
+            u256(1, 0).checked_sub(U256::from(1)),
+            Some(U256::from(u128::MAX))
+        );
+        assert_eq!(
+            U256::from(u128::MAX).checked_mul(U256::from(u128::MAX)),
+            Some(u256(u128::MAX - 1, 1))
+        );
+        assert_eq!(u256(1, 0).checked_mul(u256(1, 0)), None);
+        assert_eq!(max.bits(), 256);
+        assert_eq!(U256::from(5).bits(), 3);
+    }
+
+    #[test]
+    fn u256_division() {
+        let max = u256(u128::MAX, u128::MAX);
+        assert_eq!(
+            U256::from(1000).div_rem(U256::from(7)),
+            (U256::from(142), U256::from(6))
+        );
+        assert_eq!(max.div_rem(max), (U256::from(1), U256::ZERO));
+        // A divisor with its top bit set exercises the overflow of the doubled remainder.
+        let divisor = u256(1 << 127, 1);
+        assert_eq!(
+            max.div_rem(divisor),
+            (U256::from(1), max.checked_sub(divisor).unwrap())
+        );
+    }
+
+    #[test]
+    fn u256_shifts() {
+        let one = U256::from(1);
+        assert_eq!(one.shl(200).shr(200), one);
+        assert_eq!(one.shl(255).bits(), 256);
+        assert_eq!(one.shl(256), U256::ZERO);
+        assert_eq!(U256::from(0x1ff).low_bits(8), U256::from(0xff));
+        assert_eq!(U256::from_be_bytes(&u256(3, 4).to_be_bytes()), u256(3, 4));
+    }
+}
@@ -1,6 +1,7 @@
 use crate::build_config::BuildConfig;
 use crate::control_flow_analysis::ControlFlowGraph;
 use crate::error::*;
+use crate::parse_tree::attribute;
 use crate::semantic_analysis::{ast_node::*, TCOpts, TypeCheckArguments};
 use crate::type_engine::{look_up_type_id, TypeId};
 
@@ -21,11 +22,19 @@ pub(crate) fn instantiate_enum(
 ) -> CompileResult<TypedExpression> {
     let mut warnings = vec![];
     let mut errors = vec![];
+    if attribute::is_deprecated(&enum_decl.attributes) {
+        warnings.push(CompileWarning {
+            warning_content: Warning::UsingDeprecated {
+                name: enum_decl.name.as_str().to_string(),
+            },
+            span: enum_field_name.span().clone(),
+        });
+    }
     // if this is a generic enum, i.e. it has some type
     // parameters, monomorphize it before unifying the
     // types
-    let enum_decl = if enum_decl.type_parameters.is_empty() {
-        enum_decl
+    let (enum_decl, type_mapping) = if enum_decl.type_parameters.is_empty() {
+        (enum_decl, vec![])
     } else {
         enum_decl.monomorphize()
     };
@@ -84,8 +93,21 @@ pub(crate) fn instantiate_enum(
                 errors
             );
 
+            check_not_function_value(&typed_expr, &mut errors);
+
             // we now know that the instantiator type matches the declared type, via the above tpe
             // check
+            check!(
+                check_trait_constraints(
+                    &type_mapping,
+                    namespace,
+                    self_type,
+                    enum_field_name.span()
+                ),
+                (),
+                warnings,
+                errors
+            );
 
             ok(
                 TypedExpression {
@@ -1,8 +1,13 @@
+mod const_eval;
 mod enum_instantiation;
 mod struct_expr_field;
 mod typed_expression;
 mod typed_expression_variant;
+pub(crate) use const_eval::evaluate_constant;
 pub(crate) use enum_instantiation::instantiate_enum;
 pub(crate) use struct_expr_field::TypedStructExpressionField;
-pub(crate) use typed_expression::{error_recovery_expr, TypedExpression};
+pub(crate) use typed_expression::{
+    bind_function_arguments_at, check_not_function_value, error_recovery_expr,
+    resolve_storage_access, TypedExpression, TypedFunctionValue, VecOp,
+};
 pub(crate) use typed_expression_variant::*;
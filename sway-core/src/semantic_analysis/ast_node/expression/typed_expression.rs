@@ -10,11 +10,25 @@ use sway_types::join_spans;
 
 use either::Either;
 use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 
+mod function_value;
+mod intrinsic;
+mod match_expression;
 mod method_application;
+mod vec;
 use crate::type_engine::TypeId;
+use function_value::{
+    bind_function_arguments, type_check_closure, type_check_function_value_application,
+};
+pub(crate) use function_value::{
+    bind_function_arguments_at, check_not_function_value, TypedFunctionValue,
+};
+use intrinsic::{type_check_intrinsic, Intrinsic};
+use match_expression::type_check_match_expression;
 use method_application::type_check_method_application;
+pub(crate) use vec::VecOp;
+use vec::{type_check_vec_constructor, type_check_vec_op};
 
 #[derive(Clone, Debug)]
 pub struct TypedExpression {
@@ -35,6 +49,73 @@ pub(crate) fn error_recovery_expr(span: Span) -> TypedExpression {
     }
 }
 
+/// `storage.<field>` accesses the contract's storage, unless a variable named `storage` is in
+/// scope, in which case it's an access of that variable's field. Only contracts may declare
+/// storage, so elsewhere `storage` is an ordinary name.
+pub(crate) fn resolve_storage_access(expr: Expression, namespace: NamespaceRef) -> Expression {
+    match expr {
+        Expression::StorageAccess { field_name, span } => {
+            let storage = Ident::new(Span {
+                span: pest::Span::new(
+                    span.span.input().clone(),
+                    span.start(),
+                    span.start() + "storage".len(),
+                )
+                .unwrap(),
+                path: span.path.clone(),
+            });
+            match namespace.get_symbol(&storage).value {
+                Some(TypedDeclaration::VariableDeclaration(_)) => Expression::SubfieldExpression {
+                    prefix: Box::new(Expression::VariableExpression {
+                        span: storage.span().clone(),
+                        name: storage,
+                    }),
+                    field_to_access: field_name,
+                    span,
+                },
+                _ => Expression::StorageAccess { field_name, span },
+            }
+        }
+        other => other,
+    }
+}
+
+/// Returns the names of the `ref mut` parameters, after checking that the argument passed to each
+/// of them is a mutable variable.
+fn check_reference_arguments(
+    parameters: &[TypedFunctionParameter],
+    arguments: &[(Ident, TypedExpression)],
+    namespace: NamespaceRef,
+) -> CompileResult<Vec<Ident>> {
+    let mut errors = vec![];
+    let reference_parameters = parameters
+        .iter()
+        .zip(arguments.iter())
+        .filter(|(param, _)| param.is_reference)
+        .map(|(param, (_, arg))| {
+            match &arg.expression {
+                TypedExpressionVariant::VariableExpression { name } => {
+                    match namespace.get_symbol(name).value {
+                        Some(TypedDeclaration::VariableDeclaration(TypedVariableDeclaration {
+                            is_mutable,
+                            ..
+                        })) if is_mutable.is_mutable() => (),
+                        _ => errors.push(CompileError::MutableReferenceToNonMutable(
+                            name.as_str().to_string(),
+                            arg.span.clone(),
+                        )),
+                    }
+                }
+                _ => errors.push(CompileError::MutableReferenceToNonVariable(
+                    arg.span.clone(),
+                )),
+            }
+            param.name.clone()
+        })
+        .collect();
+    ok(reference_parameters, vec![], errors)
+}
+
 #[allow(clippy::too_many_arguments)]
 impl TypedExpression {
     pub(crate) fn type_check(arguments: TypeCheckArguments<'_, Expression>) -> CompileResult<Self> {
@@ -52,7 +133,7 @@ impl TypedExpression {
             ..
         } = arguments;
         let expr_span = other.span();
-        let res = match other {
+        let res = match resolve_storage_access(other, namespace) {
             Expression::Literal { value: lit, span } => Self::type_check_literal(lit, span),
             Expression::VariableExpression { name, span, .. } => {
                 Self::type_check_variable_expression(name, span, namespace)
@@ -142,6 +223,26 @@ impl TypedExpression {
                 dependency_graph,
                 opts,
             ),
+            Expression::MatchExpression {
+                primary_expression,
+                branches,
+                span,
+            } => type_check_match_expression(
+                TypeCheckArguments {
+                    checkee: (*primary_expression, branches),
+                    return_type_annotation: type_annotation,
+                    namespace,
+                    crate_namespace,
+                    self_type,
+                    build_config,
+                    dead_code_graph,
+                    dependency_graph,
+                    mode: Mode::NonAbi,
+                    help_text,
+                    opts,
+                },
+                span,
+            ),
             Expression::StructExpression {
                 span,
                 struct_name,
@@ -174,6 +275,24 @@ impl TypedExpression {
                 dependency_graph,
                 opts,
             ),
+            Expression::TupleIndex {
+                prefix,
+                index,
+                index_span,
+                span,
+            } => Self::type_check_tuple_index(
+                prefix,
+                index,
+                index_span,
+                span,
+                namespace,
+                crate_namespace,
+                self_type,
+                build_config,
+                dead_code_graph,
+                dependency_graph,
+                opts,
+            ),
             Expression::MethodApplication {
                 method_name,
                 arguments,
@@ -280,15 +399,28 @@ impl TypedExpression {
                     opts,
                 )
             }
-            a => {
-                let errors = vec![CompileError::Unimplemented(
-                    "Unimplemented expression",
-                    a.span(),
-                )];
-
-                let exp = error_recovery_expr(a.span());
-                ok(exp, vec![], errors)
+            Expression::StorageAccess { field_name, span } => {
+                Self::type_check_storage_access(field_name, span, namespace, opts)
             }
+            Expression::Closure {
+                parameters,
+                return_type,
+                body,
+                span,
+            } => type_check_closure(
+                parameters,
+                return_type,
+                *body,
+                span,
+                namespace,
+                crate_namespace,
+                type_annotation,
+                self_type,
+                build_config,
+                dead_code_graph,
+                dependency_graph,
+                opts,
+            ),
         };
         let mut typed_expression = match res.value {
             Some(r) => r,
@@ -326,7 +458,9 @@ impl TypedExpression {
         if let TypedExpressionVariant::Literal(lit) = typed_expression.clone().expression {
             if let Literal::Numeric(_) = lit {
                 match look_up_type_id(typed_expression.return_type) {
-                    TypeInfo::UnsignedInteger(_) | TypeInfo::Numeric => {
+                    TypeInfo::UnsignedInteger(_)
+                    | TypeInfo::SignedInteger(_)
+                    | TypeInfo::Numeric => {
                         typed_expression = check!(
                             Self::resolve_numeric_literal(
                                 lit,
@@ -359,6 +493,43 @@ impl TypedExpression {
         self.expression.copy_types(type_mapping);
     }
 
+    /// Whether evaluating this expression never completes, because every path through it reverts.
+    pub(crate) fn diverges(&self) -> bool {
+        match &self.expression {
+            TypedExpressionVariant::Revert { .. } => true,
+            TypedExpressionVariant::CodeBlock(block)
+            | TypedExpressionVariant::FunctionApplication {
+                function_body: block,
+                ..
+            } => block.diverges(),
+            TypedExpressionVariant::IfExp {
+                condition,
+                then,
+                r#else,
+            } => {
+                condition.diverges()
+                    || (then.diverges()
+                        && r#else.as_ref().map_or(false, |r#else| r#else.diverges()))
+            }
+            _ => false,
+        }
+    }
+
+    /// See [TypedExpressionVariant::resolve_constrained_methods].
+    pub(crate) fn resolve_constrained_methods(
+        &mut self,
+        namespace: NamespaceRef,
+        self_type: TypeId,
+    ) -> CompileResult<()> {
+        self.expression
+            .resolve_constrained_methods(namespace, self_type)
+    }
+
+    /// See [TypedExpressionVariant::bind_function_values].
+    pub(crate) fn bind_function_values(&mut self, bindings: &[(Ident, TypedFunctionValue)]) {
+        self.expression.bind_function_values(bindings)
+    }
+
     fn type_check_literal(lit: Literal, span: Span) -> CompileResult<TypedExpression> {
         let return_type = match &lit {
             Literal::String(s) => TypeInfo::Str(s.as_str().len() as u64),
@@ -368,6 +539,12 @@ impl TypedExpression {
 
             Literal::U32(_) => TypeInfo::UnsignedInteger(IntegerBits::ThirtyTwo),
             Literal::U64(_) => TypeInfo::UnsignedInteger(IntegerBits::SixtyFour),
+            Literal::U128(_) => TypeInfo::UnsignedInteger(IntegerBits::OneTwentyEight),
+            Literal::U256(_) => TypeInfo::UnsignedInteger(IntegerBits::TwoFiftySix),
+            Literal::I8(_) => TypeInfo::SignedInteger(IntegerBits::Eight),
+            Literal::I16(_) => TypeInfo::SignedInteger(IntegerBits::Sixteen),
+            Literal::I32(_) => TypeInfo::SignedInteger(IntegerBits::ThirtyTwo),
+            Literal::I64(_) => TypeInfo::SignedInteger(IntegerBits::SixtyFour),
             Literal::Boolean(_) => TypeInfo::Boolean,
             Literal::Byte(_) => TypeInfo::Byte,
             Literal::B256(_) => TypeInfo::B256,
@@ -387,8 +564,18 @@ impl TypedExpression {
         span: Span,
         namespace: crate::semantic_analysis::NamespaceRef,
     ) -> CompileResult<TypedExpression> {
+        let mut warnings = vec![];
         let mut errors = vec![];
         let exp = match namespace.get_symbol(&name).value {
+            // a variable bound to a function value is another name for it
+            Some(TypedDeclaration::VariableDeclaration(TypedVariableDeclaration {
+                body:
+                    body @ TypedExpression {
+                        expression: TypedExpressionVariant::FunctionValue(_),
+                        ..
+                    },
+                ..
+            })) => TypedExpression { span, ..body },
             Some(TypedDeclaration::VariableDeclaration(TypedVariableDeclaration {
                 body, ..
             })) => TypedExpression {
@@ -407,6 +594,12 @@ impl TypedExpression {
                 expression: TypedExpressionVariant::VariableExpression { name: name.clone() },
                 span,
             },
+            Some(TypedDeclaration::FunctionDeclaration(decl)) => check!(
+                TypedFunctionValue::from_declaration(decl, span),
+                error_recovery_expr(name.span().clone()),
+                warnings,
+                errors
+            ),
             Some(a) => {
                 errors.push(CompileError::NotAVariable {
                     name: name.span().as_str().to_string(),
@@ -423,13 +616,42 @@ impl TypedExpression {
                 error_recovery_expr(name.span().clone())
             }
         };
-        ok(exp, vec![], errors)
+        ok(exp, warnings, errors)
+    }
+
+    fn type_check_storage_access(
+        field_name: Ident,
+        span: Span,
+        namespace: crate::semantic_analysis::NamespaceRef,
+        opts: TCOpts,
+    ) -> CompileResult<TypedExpression> {
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        if opts.purity == Purity::Pure {
+            errors.push(CompileError::PureAccessedStorage { span: span.clone() });
+        }
+        let (ix, return_type) = check!(
+            namespace.get_storage_field(&field_name, &span),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        ok(
+            TypedExpression {
+                expression: TypedExpressionVariant::StorageAccess { field_name, ix },
+                return_type,
+                is_constant: IsConstant::No,
+                span,
+            },
+            warnings,
+            errors,
+        )
     }
 
     #[allow(clippy::type_complexity)]
     fn type_check_function_application(
         arguments: TypeCheckArguments<'_, (CallPath, Vec<Expression>, Vec<(TypeInfo, Span)>)>,
-        _span: Span,
+        call_span: Span,
     ) -> CompileResult<TypedExpression> {
         let TypeCheckArguments {
             checkee: (name, arguments, type_arguments),
@@ -442,6 +664,21 @@ impl TypedExpression {
             opts,
             ..
         } = arguments;
+        if let Some(intrinsic) = Intrinsic::from_call_path(&name, namespace) {
+            return type_check_intrinsic(
+                intrinsic,
+                name,
+                arguments,
+                call_span,
+                namespace,
+                crate_namespace,
+                self_type,
+                build_config,
+                dead_code_graph,
+                dependency_graph,
+                opts,
+            );
+        }
         let mut warnings = vec![];
         let mut errors = vec![];
         let function_declaration = check!(
@@ -450,18 +687,59 @@ impl TypedExpression {
             warnings,
             errors
         );
-        let TypedFunctionDeclaration {
-            parameters,
-            return_type,
-            body,
-            span,
-            purity,
-            ..
-        } = if let TypedDeclaration::FunctionDeclaration(decl) = function_declaration {
+        // a call through a parameter of function type
+        match &function_declaration {
+            TypedDeclaration::VariableDeclaration(TypedVariableDeclaration { body, .. })
+                if name.prefixes.is_empty()
+                    && matches!(look_up_type_id(body.return_type), TypeInfo::Function { .. }) =>
+            {
+                let application = type_check_function_value_application(
+                    name.suffix,
+                    body.return_type,
+                    arguments,
+                    call_span,
+                    namespace,
+                    crate_namespace,
+                    self_type,
+                    build_config,
+                    dead_code_graph,
+                    dependency_graph,
+                    opts,
+                );
+                // a variable bound to a function value is called directly
+                return match &body.expression {
+                    TypedExpressionVariant::FunctionValue(value) => {
+                        application.map(|application| match application.expression {
+                            TypedExpressionVariant::FunctionValueApplication {
+                                arguments, ..
+                            } => TypedExpression {
+                                expression: value.apply(arguments),
+                                ..application
+                            },
+                            _ => application,
+                        })
+                    }
+                    _ => application,
+                };
+            }
+            _ => (),
+        }
+        let (
+            TypedFunctionDeclaration {
+                parameters,
+                return_type,
+                mut body,
+                span,
+                purity,
+                attributes,
+                ..
+            },
+            type_mapping,
+        ) = if let TypedDeclaration::FunctionDeclaration(decl) = function_declaration {
             // if this is a generic function, monomorphize its internal types and insert the resulting
             // declaration into the namespace. Then, use that instead.
             if decl.type_parameters.is_empty() {
-                decl
+                (decl, vec![])
             } else {
                 check!(
                     decl.monomorphize(type_arguments, self_type),
@@ -479,9 +757,17 @@ impl TypedExpression {
             return err(warnings, errors);
         };
 
-        if opts.purity != purity {
+        if opts.purity == Purity::Pure && purity == Purity::Impure {
             errors.push(CompileError::PureCalledImpure { span: name.span() });
         }
+        if attribute::is_deprecated(&attributes) {
+            warnings.push(CompileWarning {
+                warning_content: Warning::UsingDeprecated {
+                    name: name.suffix.as_str().to_string(),
+                },
+                span: name.span(),
+            });
+        }
 
         match arguments.len().cmp(&parameters.len()) {
             Ordering::Greater => {
@@ -520,7 +806,7 @@ impl TypedExpression {
         // declaration. Use parameter type annotations as annotations for the
         // arguments
         //
-        let typed_call_arguments = arguments
+        let mut typed_call_arguments = arguments
             .into_iter()
             .zip(parameters.iter())
             .map(|(arg, param)| {
@@ -547,7 +833,29 @@ impl TypedExpression {
                     }),
                 )
             })
-            .collect();
+            .collect::<Vec<_>>();
+
+        let reference_parameters = check!(
+            check_reference_arguments(&parameters, &typed_call_arguments, namespace),
+            vec![],
+            warnings,
+            errors
+        );
+
+        // now that the type parameters are known from the arguments, the methods called through
+        // their trait constraints can be resolved
+        if check_trait_constraints(&type_mapping, namespace, self_type, &call_span)
+            .ok(&mut warnings, &mut errors)
+            .is_some()
+        {
+            check!(
+                body.resolve_constrained_methods(namespace, self_type),
+                (),
+                warnings,
+                errors
+            );
+        }
+        bind_function_arguments(&mut typed_call_arguments, &mut body);
 
         ok(
             TypedExpression {
@@ -563,6 +871,9 @@ impl TypedExpression {
                     name,
                     function_body: body,
                     selector: None, // regular functions cannot be in a contract call; only methods
+                    constrained_type: None,
+                    inline: attribute::inline_hint(&attributes),
+                    reference_parameters,
                 },
                 span,
             },
@@ -923,11 +1234,19 @@ impl TypedExpression {
                     return err(warnings, errors);
                 }
             };
+        if attribute::is_deprecated(&definition.attributes) {
+            warnings.push(CompileWarning {
+                warning_content: Warning::UsingDeprecated {
+                    name: definition.name.as_str().to_string(),
+                },
+                span: struct_name.span().clone(),
+            });
+        }
         // if this is a generic struct, i.e. it has some type
         // parameters, monomorphize it before unifying the
         // types
-        let definition = if definition.type_parameters.is_empty() {
-            definition
+        let (definition, type_mapping) = if definition.type_parameters.is_empty() {
+            (definition, vec![])
         } else {
             definition.monomorphize()
         };
@@ -976,6 +1295,7 @@ impl TypedExpression {
                 errors
             );
 
+            check_not_function_value(&typed_field, &mut errors);
             typed_fields_buf.push(TypedStructExpressionField {
                 value: typed_field,
                 name: expr_field.name.clone(),
@@ -992,6 +1312,12 @@ impl TypedExpression {
                 });
             }
         }
+        check!(
+            check_trait_constraints(&type_mapping, namespace, self_type, &span),
+            (),
+            warnings,
+            errors
+        );
         let struct_type_id = crate::type_engine::insert_type(TypeInfo::Struct {
             name: definition.name.as_str().to_string(),
             fields: definition
@@ -1088,6 +1414,73 @@ impl TypedExpression {
         ok(exp, warnings, errors)
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn type_check_tuple_index(
+        prefix: Box<Expression>,
+        index: usize,
+        index_span: Span,
+        span: Span,
+        namespace: crate::semantic_analysis::NamespaceRef,
+        crate_namespace: NamespaceRef,
+        self_type: TypeId,
+        build_config: &BuildConfig,
+        dead_code_graph: &mut ControlFlowGraph,
+        dependency_graph: &mut HashMap<String, HashSet<String>>,
+        opts: TCOpts,
+    ) -> CompileResult<TypedExpression> {
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        let prefix_span = prefix.span();
+        let parent = check!(
+            TypedExpression::type_check(TypeCheckArguments {
+                checkee: *prefix,
+                namespace,
+                crate_namespace,
+                return_type_annotation: insert_type(TypeInfo::Unknown),
+                help_text: Default::default(),
+                self_type,
+                build_config,
+                dead_code_graph,
+                dependency_graph,
+                mode: Mode::NonAbi,
+                opts,
+            }),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        let tuple_elems = check!(
+            namespace.get_tuple_elems(parent.return_type, prefix_span.as_str(), &prefix_span),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        let elem_type = match tuple_elems.get(index) {
+            Some(elem_type) => *elem_type,
+            None => {
+                errors.push(CompileError::TupleIndexOutOfBounds {
+                    index,
+                    count: tuple_elems.len(),
+                    tuple_name: prefix_span.as_str().to_string(),
+                    span: index_span,
+                });
+                return err(warnings, errors);
+            }
+        };
+        let exp = TypedExpression {
+            expression: TypedExpressionVariant::TupleElemAccess {
+                resolved_type_of_parent: parent.return_type,
+                prefix: Box::new(parent),
+                elem_to_access_num: index,
+                elem_to_access_span: index_span,
+            },
+            return_type: elem_type,
+            is_constant: IsConstant::No,
+            span,
+        };
+        ok(exp, warnings, errors)
+    }
+
     fn type_check_tuple(
         fields: Vec<Expression>,
         span: Span,
@@ -1115,7 +1508,7 @@ impl TypedExpression {
             let field_type_id = field_type_ids_opt
                 .as_ref()
                 .map(|field_type_ids| field_type_ids[i])
-                .unwrap_or_default();
+                .unwrap_or_else(|| insert_type(TypeInfo::Unknown));
             let field_span = field.span();
             let typed_field = check!(
                 TypedExpression::type_check(TypeCheckArguments {
@@ -1138,6 +1531,7 @@ impl TypedExpression {
             if let IsConstant::No = typed_field.is_constant {
                 is_constant = IsConstant::No;
             }
+            check_not_function_value(&typed_field, &mut errors);
             typed_field_types.push(typed_field.return_type);
             typed_fields.push(typed_field);
         }
@@ -1167,6 +1561,21 @@ impl TypedExpression {
         dependency_graph: &mut HashMap<String, HashSet<String>>,
         opts: TCOpts,
     ) -> CompileResult<TypedExpression> {
+        if let Some(op) = VecOp::from_call_path(&call_path, namespace) {
+            return type_check_vec_constructor(
+                op,
+                call_path,
+                args,
+                span,
+                namespace,
+                crate_namespace,
+                self_type,
+                build_config,
+                dead_code_graph,
+                dependency_graph,
+                opts,
+            );
+        }
         let mut warnings = vec![];
         let mut errors = vec![];
         // The first step is to determine if the call path refers to a module or an enum.
@@ -1341,6 +1750,7 @@ impl TypedExpression {
         functions_buf.append(&mut type_checked_fn_buf);
         namespace.insert_trait_implementation(
             abi_name.clone(),
+            vec![],
             look_up_type_id(return_type),
             functions_buf,
         );
@@ -1410,6 +1820,9 @@ impl TypedExpression {
                 )
             })
             .collect();
+        for typed_elem in &typed_contents {
+            check_not_function_value(typed_elem, &mut errors);
+        }
 
         let elem_type = typed_contents[0].return_type;
         for typed_elem in &typed_contents[1..] {
@@ -1488,7 +1901,8 @@ impl TypedExpression {
         );
 
         // If the return type is a static array then create a TypedArrayIndex.
-        if let TypeInfo::Array(elem_type_id, _) = look_up_type_id(prefix_te.return_type) {
+        let prefix_type = look_up_type_id(prefix_te.return_type);
+        if let TypeInfo::Array(_, _) | TypeInfo::Vector(_) = prefix_type {
             let index_te = check!(
                 TypedExpression::type_check(TypeCheckArguments {
                     checkee: index,
@@ -1510,6 +1924,20 @@ impl TypedExpression {
                 errors
             );
 
+            let elem_type_id = match prefix_type {
+                TypeInfo::Array(elem_type_id, _) => elem_type_id,
+                // Indexing a vector is the same as calling its `get` method.
+                _ => {
+                    return type_check_vec_op(
+                        VecOp::Get,
+                        Ident::new_with_override("get", span.clone()),
+                        VecDeque::from([prefix_te, index_te]),
+                        span,
+                        namespace,
+                        self_type,
+                    )
+                }
+            };
             ok(
                 TypedExpression {
                     expression: TypedExpressionVariant::ArrayIndex {
@@ -1730,6 +2158,84 @@ impl TypedExpression {
                 };
                 ok(exp, warnings, errors)
             }
+            DelayedResolutionVariant::EnumVariantCheck(DelayedEnumVariantCheckResolution {
+                exp,
+                call_path,
+            }) => {
+                let args = TypeCheckArguments {
+                    checkee: *exp,
+                    namespace,
+                    crate_namespace,
+                    return_type_annotation: insert_type(TypeInfo::Unknown),
+                    help_text: "",
+                    self_type,
+                    build_config,
+                    dead_code_graph,
+                    dependency_graph,
+                    mode: Mode::NonAbi,
+                    opts,
+                };
+                let parent = check!(
+                    TypedExpression::type_check(args),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                );
+                let tag = match look_up_type_id(parent.return_type) {
+                    TypeInfo::Enum {
+                        name,
+                        variant_types,
+                    } if call_path
+                        .prefixes
+                        .last()
+                        .filter(|enum_name| enum_name.as_str() != name)
+                        .is_none() =>
+                    {
+                        variant_types
+                            .iter()
+                            .find(|variant| variant.name == call_path.suffix.as_str())
+                            .map(|variant| variant.tag)
+                    }
+                    _ => None,
+                };
+                let tag = match tag {
+                    Some(tag) => tag,
+                    None => {
+                        errors.push(CompileError::MatchWrongType {
+                            expected: parent.return_type,
+                            span: call_path.suffix.span().clone(),
+                        });
+                        return ok(error_recovery_expr(span), warnings, errors);
+                    }
+                };
+                let exp = TypedExpression {
+                    expression: TypedExpressionVariant::BuiltinOperator {
+                        op: OpVariant::Equals,
+                        lhs: Box::new(TypedExpression {
+                            expression: TypedExpressionVariant::EnumTag {
+                                prefix: Box::new(parent),
+                            },
+                            return_type: insert_type(TypeInfo::UnsignedInteger(
+                                IntegerBits::SixtyFour,
+                            )),
+                            is_constant: IsConstant::No,
+                            span: span.clone(),
+                        }),
+                        rhs: Box::new(TypedExpression {
+                            expression: TypedExpressionVariant::Literal(Literal::U64(tag as u64)),
+                            return_type: insert_type(TypeInfo::UnsignedInteger(
+                                IntegerBits::SixtyFour,
+                            )),
+                            is_constant: IsConstant::Yes,
+                            span: span.clone(),
+                        }),
+                    },
+                    return_type: insert_type(TypeInfo::Boolean),
+                    is_constant: IsConstant::No,
+                    span,
+                };
+                ok(exp, warnings, errors)
+            }
             DelayedResolutionVariant::StructField(DelayedStructFieldResolution {
                 exp,
                 struct_name,
@@ -1862,6 +2368,58 @@ impl TypedExpression {
                         }),
                         new_type,
                     ),
+                    IntegerBits::OneTwentyEight | IntegerBits::TwoFiftySix => {
+                        (Ok(lit.widen_to(&TypeInfo::UnsignedInteger(n))), new_type)
+                    }
+                },
+                TypeInfo::SignedInteger(n) => match n {
+                    IntegerBits::Eight => (
+                        num.to_string().parse().map(Literal::I8).map_err(|e| {
+                            Literal::handle_parse_int_error(
+                                e,
+                                TypeInfo::SignedInteger(IntegerBits::Eight),
+                                pest_span,
+                                path,
+                            )
+                        }),
+                        new_type,
+                    ),
+                    IntegerBits::Sixteen => (
+                        num.to_string().parse().map(Literal::I16).map_err(|e| {
+                            Literal::handle_parse_int_error(
+                                e,
+                                TypeInfo::SignedInteger(IntegerBits::Sixteen),
+                                pest_span,
+                                path,
+                            )
+                        }),
+                        new_type,
+                    ),
+                    IntegerBits::ThirtyTwo => (
+                        num.to_string().parse().map(Literal::I32).map_err(|e| {
+                            Literal::handle_parse_int_error(
+                                e,
+                                TypeInfo::SignedInteger(IntegerBits::ThirtyTwo),
+                                pest_span,
+                                path,
+                            )
+                        }),
+                        new_type,
+                    ),
+                    IntegerBits::SixtyFour => (
+                        num.to_string().parse().map(Literal::I64).map_err(|e| {
+                            Literal::handle_parse_int_error(
+                                e,
+                                TypeInfo::SignedInteger(IntegerBits::SixtyFour),
+                                pest_span,
+                                path,
+                            )
+                        }),
+                        new_type,
+                    ),
+                    IntegerBits::OneTwentyEight | IntegerBits::TwoFiftySix => {
+                        unreachable!("There are no signed integers wider than 64 bits")
+                    }
                 },
                 TypeInfo::Numeric => (
                     num.to_string().parse().map(Literal::U64).map_err(|e| {
@@ -1921,6 +2479,9 @@ mod tests {
             print_intermediate_asm: false,
             print_finalized_asm: false,
             print_ir: false,
+            print_peephole_savings: false,
+            optimize_asm: true,
+            ir_opt_level: 1,
         };
         let mut dead_code_graph: ControlFlowGraph = Default::default();
         let mut dependency_graph = HashMap::new();
@@ -0,0 +1,427 @@
+//! Function values, which are functions passed as arguments by name, and closures.
+//!
+//! A function value has no runtime representation. When one is passed to a function, the call is
+//! bound to it: each call through the parameter in the body of the called function is replaced by
+//! a call to the function value, and the variables a closure captures are passed along as extra
+//! arguments. A function value can therefore only be passed as an argument, or bound to an
+//! immutable variable which is then another name for it, and can't be returned. A closure which
+//! captures variables can't be bound to a variable either, as the variables would be read where
+//! it's used rather than where it's declared.
+
+use super::*;
+use crate::build_config::BuildConfig;
+use crate::control_flow_analysis::ControlFlowGraph;
+use crate::semantic_analysis::{ast_node::declaration::walk_expression, TCOpts};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The name of a closure, which it can't be referred to by.
+const CLOSURE_NAME: &str = "<closure>";
+
+#[derive(Clone, Debug)]
+pub(crate) struct TypedFunctionValue {
+    /// The name of the function, or `<closure>` for a closure.
+    pub(crate) name: CallPath,
+    pub(crate) parameters: Vec<TypedFunctionParameter>,
+    pub(crate) body: TypedCodeBlock,
+    pub(crate) inline: Option<Inline>,
+    /// The variables of the enclosing scope which a closure reads, and the expressions they are
+    /// passed as.
+    pub(crate) captures: Vec<(Ident, TypedExpression)>,
+}
+
+impl TypedFunctionValue {
+    /// Refers to the function `decl` as a value, unless it can't be bound to a parameter.
+    pub(crate) fn from_declaration(
+        decl: TypedFunctionDeclaration,
+        span: Span,
+    ) -> CompileResult<TypedExpression> {
+        let mut errors = vec![];
+        if !decl.type_parameters.is_empty() {
+            errors.push(CompileError::InvalidFunctionValue {
+                name: decl.name.clone(),
+                reason: "is generic",
+                span: span.clone(),
+            });
+        }
+        if decl.parameters.iter().any(|param| param.is_reference) {
+            errors.push(CompileError::InvalidFunctionValue {
+                name: decl.name.clone(),
+                reason: "has `ref mut` parameters",
+                span: span.clone(),
+            });
+        }
+        if !errors.is_empty() {
+            return err(vec![], errors);
+        }
+        let return_type = insert_type(TypeInfo::Function {
+            parameters: decl.parameters.iter().map(|param| param.r#type).collect(),
+            return_type: decl.return_type,
+        });
+        let value = TypedFunctionValue {
+            inline: decl.inline(),
+            name: decl.name.into(),
+            parameters: decl.parameters,
+            body: decl.body,
+            captures: vec![],
+        };
+        ok(
+            TypedExpression {
+                expression: TypedExpressionVariant::FunctionValue(value),
+                return_type,
+                is_constant: IsConstant::No,
+                span,
+            },
+            vec![],
+            vec![],
+        )
+    }
+
+    pub(crate) fn is_closure(&self) -> bool {
+        self.name.suffix.as_str() == CLOSURE_NAME
+    }
+
+    pub(crate) fn copy_types(&mut self, type_mapping: &[(TypeParameter, TypeId)]) {
+        self.parameters
+            .iter_mut()
+            .for_each(|param| param.copy_types(type_mapping));
+        self.body.copy_types(type_mapping);
+        self.captures
+            .iter_mut()
+            .for_each(|(_ident, expr)| expr.copy_types(type_mapping));
+    }
+
+    /// The call of this function value with `arguments`, which a call through a parameter it is
+    /// bound to is replaced with.
+    pub(crate) fn apply(&self, arguments: Vec<TypedExpression>) -> TypedExpressionVariant {
+        let mut arguments = self
+            .parameters
+            .iter()
+            .map(|param| param.name.clone())
+            .zip(arguments)
+            .chain(self.captures.iter().cloned())
+            .collect();
+        let mut function_body = self.body.clone();
+        bind_function_arguments(&mut arguments, &mut function_body);
+        TypedExpressionVariant::FunctionApplication {
+            name: self.name.clone(),
+            arguments,
+            function_body,
+            selector: None,
+            constrained_type: None,
+            inline: self.inline,
+            reference_parameters: vec![],
+        }
+    }
+}
+
+/// Reports `expr` if it is a function value, which can't be stored as a part of another value.
+pub(crate) fn check_not_function_value(expr: &TypedExpression, errors: &mut Vec<CompileError>) {
+    if let TypeInfo::Function { .. } = look_up_type_id(expr.return_type) {
+        errors.push(CompileError::MisplacedFunctionValue(expr.span.clone()));
+    }
+}
+
+/// Binds each argument which is a function value to its parameter in `function_body`, the body
+/// of the function it is passed to.
+pub(crate) fn bind_function_arguments(
+    arguments: &mut Vec<(Ident, TypedExpression)>,
+    function_body: &mut TypedCodeBlock,
+) {
+    let function_values = arguments
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, arg))| matches!(arg.expression, TypedExpressionVariant::FunctionValue(_)))
+        .map(|(ix, _)| ix)
+        .collect();
+    bind_function_arguments_at(arguments, function_values, function_body);
+}
+
+/// Binds the function values among `arguments` at the indices `function_values` to their
+/// parameters in `function_body`.
+///
+/// The variables captured by a closure become arguments of their own, named after the parameter
+/// and the variable, so that the closure refers to them through the parameters of the called
+/// function rather than the variables of the caller.
+pub(crate) fn bind_function_arguments_at(
+    arguments: &mut Vec<(Ident, TypedExpression)>,
+    mut function_values: Vec<usize>,
+    function_body: &mut TypedCodeBlock,
+) {
+    let mut bindings = vec![];
+    while let Some(ix) = function_values.pop() {
+        let (param, arg) = &mut arguments[ix];
+        let value = match &mut arg.expression {
+            TypedExpressionVariant::FunctionValue(value) => value,
+            _ => continue,
+        };
+        let mut captured_arguments = vec![];
+        for (name, expr) in value.captures.iter_mut() {
+            let captured_name = format!("{}.{}", param.as_str(), name.as_str());
+            let captured_name = Ident::new(Span {
+                span: pest::Span::new(Arc::from(captured_name.as_str()), 0, captured_name.len())
+                    .unwrap(),
+                path: None,
+            });
+            let captured_expr = TypedExpression {
+                expression: TypedExpressionVariant::VariableExpression {
+                    name: captured_name.clone(),
+                },
+                return_type: expr.return_type,
+                is_constant: IsConstant::No,
+                span: expr.span.clone(),
+            };
+            captured_arguments.push((captured_name, std::mem::replace(expr, captured_expr)));
+        }
+        bindings.push((param.clone(), value.clone()));
+        for (name, expr) in captured_arguments {
+            if let TypedExpressionVariant::FunctionValue(_) = expr.expression {
+                function_values.push(arguments.len());
+            }
+            arguments.push((name, expr));
+        }
+    }
+    if !bindings.is_empty() {
+        function_body.bind_function_values(&bindings);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn type_check_closure(
+    parameters: Vec<FunctionParameter>,
+    return_type: TypeInfo,
+    body: Expression,
+    span: Span,
+    namespace: NamespaceRef,
+    crate_namespace: NamespaceRef,
+    type_annotation: TypeId,
+    self_type: TypeId,
+    build_config: &BuildConfig,
+    dead_code_graph: &mut ControlFlowGraph,
+    dependency_graph: &mut HashMap<String, HashSet<String>>,
+    opts: TCOpts,
+) -> CompileResult<TypedExpression> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    // the closure can read the variables of the enclosing scope, but not reassign them
+    let closure_namespace = create_new_scope(namespace);
+    write_module(|ns| ns.make_variables_immutable(), closure_namespace);
+
+    // the types of the parameter the closure is passed to take the place of the ones left out
+    let passed_to_function_type =
+        matches!(look_up_type_id(type_annotation), TypeInfo::Function { .. });
+    let expected_type = match look_up_type_id(type_annotation) {
+        TypeInfo::Function {
+            parameters: expected_parameters,
+            return_type: expected_return_type,
+        } if expected_parameters.len() == parameters.len() => {
+            Some((expected_parameters, expected_return_type))
+        }
+        _ => None,
+    };
+    let mut unify_with_expected =
+        |received: TypeId, expected: TypeId, span: &Span| match unify_with_self(
+            received, expected, self_type, span,
+        ) {
+            Ok(mut ws) => warnings.append(&mut ws),
+            Err(e) => errors.push(CompileError::TypeError(e)),
+        };
+    let mut typed_parameters = Vec::with_capacity(parameters.len());
+    for (ix, param) in parameters.into_iter().enumerate() {
+        let r#type = closure_namespace
+            .resolve_type_with_self(param.r#type, self_type)
+            .unwrap_or_else(|_| insert_type(TypeInfo::ErrorRecovery));
+        if let Some((expected_parameters, _)) = &expected_type {
+            unify_with_expected(r#type, expected_parameters[ix], &param.type_span);
+        }
+        closure_namespace.insert(
+            param.name.clone(),
+            TypedDeclaration::VariableDeclaration(TypedVariableDeclaration {
+                name: param.name.clone(),
+                body: TypedExpression {
+                    expression: TypedExpressionVariant::FunctionParameter,
+                    return_type: r#type,
+                    is_constant: IsConstant::No,
+                    span: param.name.span().clone(),
+                },
+                is_mutable: VariableMutability::Immutable,
+                type_ascription: r#type,
+            }),
+        );
+        typed_parameters.push(TypedFunctionParameter {
+            name: param.name,
+            r#type,
+            type_span: param.type_span,
+            is_reference: false,
+        });
+    }
+    let return_type = closure_namespace
+        .resolve_type_with_self(return_type, self_type)
+        .unwrap_or_else(|_| insert_type(TypeInfo::ErrorRecovery));
+    if let Some((_, expected_return_type)) = &expected_type {
+        unify_with_expected(return_type, *expected_return_type, &span);
+    }
+
+    let body_span = body.span();
+    let body = check!(
+        TypedExpression::type_check(TypeCheckArguments {
+            checkee: body,
+            namespace: closure_namespace,
+            crate_namespace,
+            return_type_annotation: return_type,
+            help_text: "The closure's body does not match up with its return type.",
+            self_type,
+            build_config,
+            dead_code_graph,
+            dependency_graph,
+            mode: Mode::NonAbi,
+            opts,
+        }),
+        error_recovery_expr(body_span.clone()),
+        warnings,
+        errors
+    );
+    // the parameter types may yet be inferred from the other arguments of the called function,
+    // and otherwise the mismatch with the type of its parameter is reported
+    for param in typed_parameters.iter().filter(|_| !passed_to_function_type) {
+        if let TypeInfo::Unknown = look_up_type_id(param.r#type) {
+            errors.push(CompileError::UnknownClosureParameterType {
+                name: param.name.clone(),
+                span: param.name.span().clone(),
+            });
+        }
+    }
+
+    // every variable of the enclosing scope which a name in the body refers to is captured, even
+    // if that name is shadowed inside of the body or is within the body of a called function
+    let mut names = vec![];
+    walk_expression(&body, &mut |expr| match &expr.expression {
+        TypedExpressionVariant::VariableExpression { name }
+        | TypedExpressionVariant::FunctionValueApplication { name, .. } => names.push(name.clone()),
+        _ => (),
+    });
+    let mut captures: Vec<(Ident, TypedExpression)> = vec![];
+    for name in names {
+        if typed_parameters.iter().any(|param| param.name == name)
+            || captures.iter().any(|(captured, _)| *captured == name)
+        {
+            continue;
+        }
+        if let Some(TypedDeclaration::VariableDeclaration(decl)) = namespace.get_symbol(&name).value
+        {
+            captures.push((
+                name.clone(),
+                TypedExpression {
+                    expression: TypedExpressionVariant::VariableExpression { name: name.clone() },
+                    return_type: decl.body.return_type,
+                    is_constant: IsConstant::No,
+                    span: name.span().clone(),
+                },
+            ));
+        }
+    }
+
+    let closure_type = insert_type(TypeInfo::Function {
+        parameters: typed_parameters.iter().map(|param| param.r#type).collect(),
+        return_type,
+    });
+    let value = TypedFunctionValue {
+        name: Ident::new_with_override(CLOSURE_NAME, span.clone()).into(),
+        parameters: typed_parameters,
+        body: TypedCodeBlock {
+            contents: vec![TypedAstNode {
+                content: TypedAstNodeContent::ImplicitReturnExpression(body),
+                span: body_span.clone(),
+            }],
+            whole_block_span: body_span,
+        },
+        inline: Some(Inline::Always),
+        captures,
+    };
+    ok(
+        TypedExpression {
+            expression: TypedExpressionVariant::FunctionValue(value),
+            return_type: closure_type,
+            is_constant: IsConstant::No,
+            span,
+        },
+        warnings,
+        errors,
+    )
+}
+
+/// Checks a call through `name`, a variable or parameter of function type, with `arguments`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn type_check_function_value_application(
+    name: Ident,
+    function_type: TypeId,
+    arguments: Vec<Expression>,
+    span: Span,
+    namespace: NamespaceRef,
+    crate_namespace: NamespaceRef,
+    self_type: TypeId,
+    build_config: &BuildConfig,
+    dead_code_graph: &mut ControlFlowGraph,
+    dependency_graph: &mut HashMap<String, HashSet<String>>,
+    opts: TCOpts,
+) -> CompileResult<TypedExpression> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let (parameters, return_type) = match look_up_type_id(function_type) {
+        TypeInfo::Function {
+            parameters,
+            return_type,
+        } => (parameters, return_type),
+        _ => unreachable!("only a variable of function type can be called"),
+    };
+    match arguments.len().cmp(&parameters.len()) {
+        Ordering::Greater => errors.push(CompileError::TooManyArgumentsForFunction {
+            span: span.clone(),
+            method_name: name.clone(),
+            expected: parameters.len(),
+            received: arguments.len(),
+        }),
+        Ordering::Less => errors.push(CompileError::TooFewArgumentsForFunction {
+            span: span.clone(),
+            method_name: name.clone(),
+            expected: parameters.len(),
+            received: arguments.len(),
+        }),
+        Ordering::Equal => {}
+    }
+    let typed_arguments = arguments
+        .into_iter()
+        .zip(parameters)
+        .map(|(arg, param)| {
+            let arg_span = arg.span();
+            TypedExpression::type_check(TypeCheckArguments {
+                checkee: arg,
+                namespace,
+                crate_namespace,
+                return_type_annotation: param,
+                help_text: "The argument that has been provided to this function value's type \
+                    does not match the type of the parameter.",
+                self_type,
+                build_config,
+                dead_code_graph,
+                dependency_graph,
+                mode: Mode::NonAbi,
+                opts,
+            })
+            .unwrap_or_else(&mut warnings, &mut errors, || error_recovery_expr(arg_span))
+        })
+        .collect();
+    ok(
+        TypedExpression {
+            expression: TypedExpressionVariant::FunctionValueApplication {
+                name,
+                arguments: typed_arguments,
+            },
+            return_type,
+            is_constant: IsConstant::No,
+            span,
+        },
+        warnings,
+        errors,
+    )
+}
@@ -0,0 +1,161 @@
+//! Functions which are known to the compiler, rather than declared in Sway.
+//!
+//! An intrinsic is called like any other function, and a declaration of the same name in scope
+//! takes precedence over it.
+
+use super::*;
+use crate::build_config::BuildConfig;
+use crate::control_flow_analysis::ControlFlowGraph;
+use crate::semantic_analysis::TCOpts;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Intrinsic {
+    /// `revert(code: u64)` halts the program with the revert code `code`.
+    Revert,
+    /// `require(condition: bool, code: u64)` reverts with `code` unless `condition` holds.
+    Require,
+    /// `log(value: T)` records `value` in the receipts, along with the id of its type.
+    Log,
+}
+
+impl Intrinsic {
+    /// The intrinsic which `name` refers to, unless it is shadowed by a declaration in
+    /// `namespace`.
+    pub(crate) fn from_call_path(name: &CallPath, namespace: NamespaceRef) -> Option<Self> {
+        if !name.prefixes.is_empty() || namespace.get_symbol(&name.suffix).value.is_some() {
+            return None;
+        }
+        match name.suffix.as_str() {
+            "revert" => Some(Intrinsic::Revert),
+            "require" => Some(Intrinsic::Require),
+            "log" => Some(Intrinsic::Log),
+            _ => None,
+        }
+    }
+
+    fn parameters(&self) -> Vec<TypeInfo> {
+        let code = TypeInfo::UnsignedInteger(IntegerBits::SixtyFour);
+        match self {
+            Intrinsic::Revert => vec![code],
+            Intrinsic::Require => vec![TypeInfo::Boolean, code],
+            Intrinsic::Log => vec![TypeInfo::Unknown],
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn type_check_intrinsic(
+    intrinsic: Intrinsic,
+    name: CallPath,
+    arguments: Vec<Expression>,
+    span: Span,
+    namespace: NamespaceRef,
+    crate_namespace: NamespaceRef,
+    self_type: TypeId,
+    build_config: &BuildConfig,
+    dead_code_graph: &mut ControlFlowGraph,
+    dependency_graph: &mut HashMap<String, HashSet<String>>,
+    opts: TCOpts,
+) -> CompileResult<TypedExpression> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let parameters = intrinsic.parameters();
+    match arguments.len().cmp(&parameters.len()) {
+        Ordering::Greater => errors.push(CompileError::TooManyArgumentsForFunction {
+            span: span.clone(),
+            method_name: name.suffix.clone(),
+            expected: parameters.len(),
+            received: arguments.len(),
+        }),
+        Ordering::Less => errors.push(CompileError::TooFewArgumentsForFunction {
+            span: span.clone(),
+            method_name: name.suffix.clone(),
+            expected: parameters.len(),
+            received: arguments.len(),
+        }),
+        Ordering::Equal => {}
+    }
+    if !errors.is_empty() {
+        return err(warnings, errors);
+    }
+    let mut typed_arguments = Vec::with_capacity(arguments.len());
+    for (arg, param) in arguments.into_iter().zip(parameters) {
+        let arg_span = arg.span();
+        typed_arguments.push(check!(
+            TypedExpression::type_check(TypeCheckArguments {
+                checkee: arg,
+                namespace,
+                crate_namespace,
+                return_type_annotation: insert_type(param),
+                help_text: "The argument that has been provided to this intrinsic does not match \
+                    the type it expects.",
+                self_type,
+                build_config,
+                dead_code_graph,
+                dependency_graph,
+                mode: Mode::NonAbi,
+                opts,
+            }),
+            error_recovery_expr(arg_span),
+            warnings,
+            errors
+        ));
+    }
+    let expression = match intrinsic {
+        Intrinsic::Revert => {
+            let code = typed_arguments.pop().expect("revert takes a code");
+            revert(code, span, namespace)
+        }
+        Intrinsic::Require => {
+            let code = typed_arguments.pop().expect("require takes a code");
+            let condition = typed_arguments.pop().expect("require takes a condition");
+            // `if condition { } else { revert(code) }`
+            let unit = insert_type(TypeInfo::Tuple(Vec::new()));
+            let mut otherwise = revert(code, span.clone(), namespace);
+            otherwise.return_type = unit;
+            TypedExpression {
+                expression: TypedExpressionVariant::IfExp {
+                    condition: Box::new(condition),
+                    then: Box::new(TypedExpression {
+                        expression: TypedExpressionVariant::Tuple { fields: vec![] },
+                        return_type: unit,
+                        is_constant: IsConstant::Yes,
+                        span: span.clone(),
+                    }),
+                    r#else: Some(Box::new(otherwise)),
+                },
+                return_type: unit,
+                is_constant: IsConstant::No,
+                span,
+            }
+        }
+        Intrinsic::Log => {
+            let value = typed_arguments.pop().expect("log takes a value");
+            TypedExpression {
+                expression: TypedExpressionVariant::Log {
+                    value: Box::new(value),
+                },
+                return_type: insert_type(TypeInfo::Tuple(Vec::new())),
+                is_constant: IsConstant::No,
+                span,
+            }
+        }
+    };
+    ok(expression, warnings, errors)
+}
+
+/// A `revert` never produces a value, so it takes on whatever type its context expects of it.
+/// A code which can be evaluated at compile time is folded to a literal, so that it may be
+/// listed in the revert code manifest.
+fn revert(code: TypedExpression, span: Span, namespace: NamespaceRef) -> TypedExpression {
+    let code = evaluate_constant(&code, namespace).value.unwrap_or(code);
+    TypedExpression {
+        expression: TypedExpressionVariant::Revert {
+            code: Box::new(code),
+        },
+        return_type: insert_type(TypeInfo::Unknown),
+        is_constant: IsConstant::No,
+        span,
+    }
+}
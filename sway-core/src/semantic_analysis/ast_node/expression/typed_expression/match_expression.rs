@@ -0,0 +1,674 @@
+//! Type checking of match expressions.
+//!
+//! The matched value is type checked first, so that the arms can be checked against its type
+//! before the match is lowered.  A match must be exhaustive, i.e., every value of the type must be
+//! matched by some arm, and an arm which can't match any value that an earlier arm doesn't is
+//! unreachable.  Both are found with the usefulness algorithm from 'Warnings for pattern matching'
+//! by Luc Maranget: an arm is reachable if it is _useful_ with respect to the arms before it, and
+//! the match is exhaustive if a catch-all arm added after the others wouldn't be.
+//!
+//! The checked value is bound to a hidden variable and the match is lowered against that variable
+//! to nested if expressions by [desugar_match_expression], so that the value is evaluated and type
+//! checked just once however many arms refer to it.  The lowered match and the declaration of the
+//! variable make up the code block which the match is type checked to.
+
+use super::*;
+use crate::{desugar_match_expression, MatchBranch, MatchCondition, Scrutinee};
+
+pub(crate) fn type_check_match_expression(
+    arguments: TypeCheckArguments<'_, (Expression, Vec<MatchBranch>)>,
+    span: Span,
+) -> CompileResult<TypedExpression> {
+    let TypeCheckArguments {
+        checkee: (primary_expression, branches),
+        namespace,
+        crate_namespace,
+        return_type_annotation: type_annotation,
+        help_text,
+        self_type,
+        build_config,
+        dead_code_graph,
+        dependency_graph,
+        opts,
+        ..
+    } = arguments;
+    let mut warnings = vec![];
+    let mut errors = vec![];
+
+    let typed_value = check!(
+        TypedExpression::type_check(TypeCheckArguments {
+            checkee: primary_expression,
+            namespace,
+            crate_namespace,
+            return_type_annotation: insert_type(TypeInfo::Unknown),
+            help_text: Default::default(),
+            self_type,
+            build_config,
+            dead_code_graph,
+            dependency_graph,
+            mode: Mode::NonAbi,
+            opts,
+        }),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+
+    // If an arm doesn't fit the type of the value then lowering the match will report it.
+    let patterns = branches
+        .iter()
+        .map(|branch| match &branch.condition {
+            MatchCondition::CatchAll(_) => Some(Pattern::Wildcard),
+            MatchCondition::Scrutinee(scrutinee) => {
+                Pattern::from_scrutinee(scrutinee, typed_value.return_type)
+            }
+        })
+        .collect::<Option<Vec<_>>>();
+    let is_exhaustive = match patterns {
+        None => false,
+        Some(patterns) => {
+            let types = [typed_value.return_type];
+            let mut rows = Vec::new();
+            for (branch, pattern) in branches.iter().zip(patterns) {
+                let row = vec![pattern];
+                if !is_useful(&rows, &row, &types) {
+                    warnings.push(CompileWarning {
+                        warning_content: Warning::MatchExpressionUnreachableArm,
+                        span: match &branch.condition {
+                            MatchCondition::CatchAll(catch_all) => catch_all.span.clone(),
+                            MatchCondition::Scrutinee(scrutinee) => scrutinee.span(),
+                        },
+                    });
+                }
+                rows.push(row);
+            }
+            let missing_values = find_missing_values(&rows, &types);
+            if !missing_values.is_empty() {
+                errors.push(CompileError::MatchExpressionNonExhaustive {
+                    missing_patterns: missing_values
+                        .iter()
+                        .map(|values| values[0].display(typed_value.return_type))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    span: span.clone(),
+                });
+            }
+            // A match which isn't exhaustive is an error already, so the last arm may as well be
+            // lowered as if it were.
+            true
+        }
+    };
+
+    // The hidden variable is declared in a scope of its own around the lowered match.  It is named
+    // after where the match is so that, in the lowered code and in the IR, the value of a match in
+    // an arm of another is told apart from the value of the outer one.
+    let value_name = format!("__match_value_{}", span.start());
+    let value_name = Ident::new(Span {
+        span: pest::Span::new(value_name.as_str().into(), 0, value_name.len()).unwrap(),
+        path: None,
+    });
+    let value_span = typed_value.span.clone();
+    let value_decl = TypedDeclaration::VariableDeclaration(TypedVariableDeclaration {
+        name: value_name.clone(),
+        type_ascription: typed_value.return_type,
+        body: typed_value,
+        is_mutable: VariableMutability::Immutable,
+    });
+    let match_namespace = create_new_scope(namespace);
+    match_namespace.insert(value_name.clone(), value_decl.clone());
+
+    let lowered_expression = check!(
+        desugar_match_expression(
+            Expression::VariableExpression {
+                name: value_name,
+                span: value_span.clone(),
+            },
+            branches,
+            is_exhaustive
+        ),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    let typed_expression = check!(
+        TypedExpression::type_check(TypeCheckArguments {
+            checkee: lowered_expression,
+            namespace: match_namespace,
+            crate_namespace,
+            return_type_annotation: type_annotation,
+            help_text,
+            self_type,
+            build_config,
+            dead_code_graph,
+            dependency_graph,
+            mode: Mode::NonAbi,
+            opts,
+        }),
+        return ok(error_recovery_expr(span), warnings, errors),
+        warnings,
+        errors
+    );
+    let return_type = typed_expression.return_type;
+    let block = TypedCodeBlock {
+        contents: vec![
+            TypedAstNode {
+                content: TypedAstNodeContent::Declaration(value_decl),
+                span: value_span,
+            },
+            TypedAstNode {
+                span: typed_expression.span.clone(),
+                content: TypedAstNodeContent::ImplicitReturnExpression(typed_expression),
+            },
+        ],
+        whole_block_span: span.clone(),
+    };
+    ok(
+        TypedExpression {
+            expression: TypedExpressionVariant::CodeBlock(block),
+            return_type,
+            is_constant: IsConstant::No,
+            span,
+        },
+        warnings,
+        errors,
+    )
+}
+
+/// The constructors which make up the values of a type.  Structs, tuples and the unit type each
+/// have a single constructor, with a field for each of their elements, and each enum variant is a
+/// constructor with a single field.
+#[derive(Clone, Debug, PartialEq)]
+enum Constructor {
+    Single,
+    Bool(bool),
+    /// An enum variant, by its position in the declaration.
+    Variant(usize),
+    /// A literal of a type which has too many values to list, so that a match over it is only
+    /// exhaustive if it has a catch-all arm.
+    Literal(LiteralValue),
+}
+
+/// A literal, compared by value.  Integer literals of any width are the same if their values are.
+#[derive(Clone, Debug, PartialEq)]
+enum LiteralValue {
+    Integer(u128),
+    /// Non-negative signed integers are an `Integer`, so that they compare equal to numerics.
+    NegativeInteger(i64),
+    U256([u8; 32]),
+    Byte(u8),
+    B256([u8; 32]),
+    String(String),
+}
+
+impl LiteralValue {
+    fn new(literal: &Literal) -> Option<Self> {
+        Some(match literal {
+            Literal::U8(value) => LiteralValue::Integer(*value as u128),
+            Literal::U16(value) => LiteralValue::Integer(*value as u128),
+            Literal::U32(value) => LiteralValue::Integer(*value as u128),
+            Literal::U64(value) | Literal::Numeric(value) => LiteralValue::Integer(*value as u128),
+            Literal::U128(value) => LiteralValue::Integer(*value),
+            Literal::I8(value) => LiteralValue::signed(*value as i64),
+            Literal::I16(value) => LiteralValue::signed(*value as i64),
+            Literal::I32(value) => LiteralValue::signed(*value as i64),
+            Literal::I64(value) => LiteralValue::signed(*value),
+            Literal::U256(value) => LiteralValue::U256(*value),
+            Literal::Byte(value) => LiteralValue::Byte(*value),
+            Literal::B256(value) => LiteralValue::B256(*value),
+            Literal::String(span) => LiteralValue::String(span.as_str().to_owned()),
+            Literal::Boolean(_) => return None,
+        })
+    }
+
+    fn signed(value: i64) -> Self {
+        if value < 0 {
+            LiteralValue::NegativeInteger(value)
+        } else {
+            LiteralValue::Integer(value as u128)
+        }
+    }
+}
+
+/// A scrutinee, checked against and normalized to the type of the value it matches.  Variables,
+/// catch-alls and struct fields without a scrutinee are all wildcards, and the fields of a struct
+/// are in the order in which they're declared.
+#[derive(Clone, Debug)]
+enum Pattern {
+    Wildcard,
+    Constructor(Constructor, Vec<Pattern>),
+}
+
+impl Pattern {
+    /// Returns `None` if the scrutinee doesn't fit the type.
+    fn from_scrutinee(scrutinee: &Scrutinee, type_id: TypeId) -> Option<Self> {
+        let type_info = look_up_type_id(type_id);
+        match (scrutinee, &type_info) {
+            (Scrutinee::Variable { .. }, _) => Some(Pattern::Wildcard),
+            (Scrutinee::Unit { .. }, TypeInfo::Tuple(elem_types)) if elem_types.is_empty() => {
+                Some(Pattern::Constructor(Constructor::Single, vec![]))
+            }
+            (
+                Scrutinee::Literal {
+                    value: Literal::Boolean(value),
+                    ..
+                },
+                TypeInfo::Boolean,
+            ) => Some(Pattern::Constructor(Constructor::Bool(*value), vec![])),
+            (
+                Scrutinee::Literal { value, .. },
+                TypeInfo::UnsignedInteger(_)
+                | TypeInfo::SignedInteger(_)
+                | TypeInfo::Numeric
+                | TypeInfo::Byte
+                | TypeInfo::B256
+                | TypeInfo::Str(_),
+            ) => LiteralValue::new(value)
+                .map(|value| Pattern::Constructor(Constructor::Literal(value), vec![])),
+            (
+                Scrutinee::StructScrutinee {
+                    struct_name,
+                    fields,
+                    ..
+                },
+                TypeInfo::Struct {
+                    name,
+                    fields: type_fields,
+                },
+            ) if struct_name.as_str() == name => {
+                if fields.iter().any(|field| {
+                    !type_fields
+                        .iter()
+                        .any(|type_field| type_field.name == field.field.as_str())
+                }) {
+                    return None;
+                }
+                let field_patterns = type_fields
+                    .iter()
+                    .map(|type_field| {
+                        match fields
+                            .iter()
+                            .find(|field| field.field.as_str() == type_field.name)
+                            .and_then(|field| field.scrutinee.as_ref())
+                        {
+                            Some(scrutinee) => {
+                                Pattern::from_scrutinee(scrutinee, type_field.r#type)
+                            }
+                            None => Some(Pattern::Wildcard),
+                        }
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some(Pattern::Constructor(Constructor::Single, field_patterns))
+            }
+            (
+                Scrutinee::EnumScrutinee {
+                    call_path, args, ..
+                },
+                TypeInfo::Enum {
+                    name,
+                    variant_types,
+                },
+            ) => {
+                if call_path
+                    .prefixes
+                    .last()
+                    .filter(|enum_name| enum_name.as_str() != name)
+                    .is_some()
+                {
+                    return None;
+                }
+                let variant_idx = variant_types
+                    .iter()
+                    .position(|variant| variant.name == call_path.suffix.as_str())?;
+                let arg_pattern = match args.as_slice() {
+                    [] => Pattern::Wildcard,
+                    [arg] => Pattern::from_scrutinee(arg, variant_types[variant_idx].r#type)?,
+                    _ => return None,
+                };
+                Some(Pattern::Constructor(
+                    Constructor::Variant(variant_idx),
+                    vec![arg_pattern],
+                ))
+            }
+            (Scrutinee::Tuple { elems, .. }, TypeInfo::Tuple(elem_types))
+                if elems.len() == elem_types.len() =>
+            {
+                let elem_patterns = elems
+                    .iter()
+                    .zip(elem_types)
+                    .map(|(elem, elem_type)| Pattern::from_scrutinee(elem, *elem_type))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(Pattern::Constructor(Constructor::Single, elem_patterns))
+            }
+            _ => None,
+        }
+    }
+
+    /// Write the pattern as it would appear in an arm matching a value of type `type_id`.
+    fn display(&self, type_id: TypeId) -> String {
+        let (constructor, fields) = match self {
+            Pattern::Wildcard => return "_".into(),
+            Pattern::Constructor(constructor, fields) => (constructor, fields),
+        };
+        let field_types = constructor.field_types(type_id);
+        let field_strs = fields
+            .iter()
+            .zip(field_types)
+            .map(|(field, field_type)| field.display(field_type))
+            .collect::<Vec<_>>();
+        match (constructor, look_up_type_id(type_id)) {
+            (Constructor::Single, TypeInfo::Struct { name, fields }) => format!(
+                "{} {{ {} }}",
+                name,
+                fields
+                    .iter()
+                    .zip(field_strs)
+                    .map(|(field, field_str)| format!("{}: {}", field.name, field_str))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            (Constructor::Single, _) => format!("({})", field_strs.join(", ")),
+            (
+                Constructor::Variant(variant_idx),
+                TypeInfo::Enum {
+                    name,
+                    variant_types,
+                },
+            ) => {
+                let variant = &variant_types[*variant_idx];
+                if look_up_type_id(variant.r#type).is_unit() {
+                    format!("{}::{}", name, variant.name)
+                } else {
+                    format!("{}::{}({})", name, variant.name, field_strs[0])
+                }
+            }
+            (Constructor::Bool(value), _) => value.to_string(),
+            (Constructor::Literal(LiteralValue::Integer(value)), _) => value.to_string(),
+            (Constructor::Literal(LiteralValue::NegativeInteger(value)), _) => value.to_string(),
+            (Constructor::Literal(LiteralValue::Byte(value)), _) => format!("{:#010b}", value),
+            (Constructor::Literal(LiteralValue::B256(value) | LiteralValue::U256(value)), _) => {
+                format!(
+                    "0x{}",
+                    value
+                        .iter()
+                        .map(|byte| format!("{:02x}", byte))
+                        .collect::<String>()
+                )
+            }
+            (Constructor::Literal(LiteralValue::String(value)), _) => format!("\"{}\"", value),
+            (Constructor::Variant(_), _) => unreachable!("Variants are only made for enums."),
+        }
+    }
+}
+
+impl Constructor {
+    /// Get the types of the fields of this constructor of the type `type_id`.
+    fn field_types(&self, type_id: TypeId) -> Vec<TypeId> {
+        match (self, look_up_type_id(type_id)) {
+            (Constructor::Single, TypeInfo::Tuple(elem_types)) => elem_types,
+            (Constructor::Single, TypeInfo::Struct { fields, .. }) => {
+                fields.iter().map(|field| field.r#type).collect()
+            }
+            (Constructor::Variant(variant_idx), TypeInfo::Enum { variant_types, .. }) => {
+                vec![variant_types[*variant_idx].r#type]
+            }
+            _otherwise => vec![],
+        }
+    }
+}
+
+/// Get every constructor of the type `type_id`, or `None` if it has too many to list.
+fn all_constructors(type_id: TypeId) -> Option<Vec<Constructor>> {
+    match look_up_type_id(type_id) {
+        TypeInfo::Boolean => Some(vec![Constructor::Bool(true), Constructor::Bool(false)]),
+        TypeInfo::Tuple(_) | TypeInfo::Struct { .. } => Some(vec![Constructor::Single]),
+        TypeInfo::Enum { variant_types, .. } => {
+            Some((0..variant_types.len()).map(Constructor::Variant).collect())
+        }
+        _otherwise => None,
+    }
+}
+
+/// Get the constructors of the first patterns in the rows, without duplicates.
+fn head_constructors(rows: &[Vec<Pattern>]) -> Vec<Constructor> {
+    let mut constructors = Vec::new();
+    for row in rows {
+        if let Pattern::Constructor(constructor, _) = &row[0] {
+            if !constructors.contains(constructor) {
+                constructors.push(constructor.clone());
+            }
+        }
+    }
+    constructors
+}
+
+/// Specialize the rows for values made by `constructor`, with `arity` fields.  The rows whose first
+/// pattern may match such a value are kept, with that pattern replaced by its fields.
+fn specialize(rows: &[Vec<Pattern>], constructor: &Constructor, arity: usize) -> Vec<Vec<Pattern>> {
+    rows.iter()
+        .filter_map(|row| {
+            let mut specialized_row = match &row[0] {
+                Pattern::Wildcard => vec![Pattern::Wildcard; arity],
+                Pattern::Constructor(row_constructor, fields) if row_constructor == constructor => {
+                    fields.clone()
+                }
+                Pattern::Constructor(..) => return None,
+            };
+            specialized_row.extend_from_slice(&row[1..]);
+            Some(specialized_row)
+        })
+        .collect()
+}
+
+/// Get the rows whose first pattern is a wildcard, without it.  These are the rows which match the
+/// values made by any constructor which isn't at the head of a row.
+fn default_rows(rows: &[Vec<Pattern>]) -> Vec<Vec<Pattern>> {
+    rows.iter()
+        .filter(|row| matches!(row[0], Pattern::Wildcard))
+        .map(|row| row[1..].to_vec())
+        .collect()
+}
+
+/// Whether the head constructors of the rows include every constructor of the type `type_id`.
+/// Returns the full list of constructors if so.
+fn complete_constructors(rows: &[Vec<Pattern>], type_id: TypeId) -> Option<Vec<Constructor>> {
+    let head_constructors = head_constructors(rows);
+    all_constructors(type_id).filter(|constructors| {
+        constructors
+            .iter()
+            .all(|constructor| head_constructors.contains(constructor))
+    })
+}
+
+/// Whether there's a value matched by `row` which isn't matched by any of `rows`.  The patterns in
+/// each row match values of `types`, in order.
+fn is_useful(rows: &[Vec<Pattern>], row: &[Pattern], types: &[TypeId]) -> bool {
+    let (type_id, rest_types) = match types.split_first() {
+        Some(split) => split,
+        None => return rows.is_empty(),
+    };
+    let useful_for_constructor = |constructor: &Constructor| {
+        let mut field_types = constructor.field_types(*type_id);
+        let arity = field_types.len();
+        field_types.extend_from_slice(rest_types);
+        is_useful(
+            &specialize(rows, constructor, arity),
+            &specialize(&[row.to_vec()], constructor, arity)[0],
+            &field_types,
+        )
+    };
+    match &row[0] {
+        Pattern::Constructor(constructor, _) => useful_for_constructor(constructor),
+        Pattern::Wildcard => match complete_constructors(rows, *type_id) {
+            Some(constructors) => constructors.iter().any(useful_for_constructor),
+            None => is_useful(&default_rows(rows), &row[1..], rest_types),
+        },
+    }
+}
+
+/// Find the values which aren't matched by any of `rows`, as rows of patterns.  Each pattern
+/// stands for all the values it matches, and those which are left as wildcards aren't listed any
+/// further.
+fn find_missing_values(rows: &[Vec<Pattern>], types: &[TypeId]) -> Vec<Vec<Pattern>> {
+    let (type_id, rest_types) = match types.split_first() {
+        Some(split) => split,
+        None if rows.is_empty() => return vec![vec![]],
+        None => return vec![],
+    };
+
+    // If the constructors of the type can be listed and some are at the head of a row then the
+    // missing values are those missing for each constructor in turn.
+    match all_constructors(*type_id) {
+        Some(constructors) if !head_constructors(rows).is_empty() => {
+            let mut missing_values = Vec::new();
+            for constructor in constructors {
+                let mut field_types = constructor.field_types(*type_id);
+                let arity = field_types.len();
+                field_types.extend_from_slice(rest_types);
+                for mut values in
+                    find_missing_values(&specialize(rows, &constructor, arity), &field_types)
+                {
+                    let rest_values = values.split_off(arity);
+                    let mut missing_row = vec![Pattern::Constructor(constructor.clone(), values)];
+                    missing_row.extend(rest_values);
+                    missing_values.push(missing_row);
+                }
+            }
+            missing_values
+        }
+
+        // Otherwise any value is missing as long as the rest of its row is.
+        _otherwise => find_missing_values(&default_rows(rows), rest_types)
+            .into_iter()
+            .map(|rest_values| {
+                let mut missing_row = vec![Pattern::Wildcard];
+                missing_row.extend(rest_values);
+                missing_row
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        control_flow_analysis::ControlFlowGraph,
+        parser::{Rule, SwayParser},
+        semantic_analysis::TypedParseTree,
+        CompileError, CompileWarning, Warning,
+    };
+    use pest::Parser;
+
+    const COLOR: &str = "script;
+
+        enum Color {
+            Red: (),
+            Green: (),
+            Blue: (),
+        }
+    ";
+
+    fn type_check(input: &str) -> (Vec<CompileWarning>, Vec<CompileError>) {
+        let mut parsed =
+            SwayParser::parse(Rule::program, std::sync::Arc::from(input)).expect("parse_tree");
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        let parse_tree = crate::parse_root_from_pairs(parsed.next().unwrap().into_inner(), None)
+            .unwrap(&mut warnings, &mut errors);
+        let build_config = crate::build_config::BuildConfig {
+            file_name: std::sync::Arc::new("test.sw".into()),
+            dir_of_code: std::sync::Arc::new("tests".into()),
+            manifest_path: std::sync::Arc::new(".".into()),
+            use_ir: false,
+            print_intermediate_asm: false,
+            print_finalized_asm: false,
+            print_ir: false,
+            print_peephole_savings: false,
+            optimize_asm: true,
+            ir_opt_level: 1,
+        };
+        let result = TypedParseTree::type_check(
+            parse_tree.tree,
+            crate::create_module(),
+            crate::create_module(),
+            &parse_tree.tree_type,
+            &build_config,
+            &mut ControlFlowGraph::default(),
+            &mut std::collections::HashMap::new(),
+        );
+        (result.warnings, result.errors)
+    }
+
+    fn unreachable_arms(warnings: &[CompileWarning]) -> Vec<&str> {
+        warnings
+            .iter()
+            .filter(|warning| {
+                matches!(
+                    warning.warning_content,
+                    Warning::MatchExpressionUnreachableArm
+                )
+            })
+            .map(|warning| warning.span.as_str().trim())
+            .collect()
+    }
+
+    #[test]
+    fn exhaustive_enum_match_without_catch_all() {
+        let (warnings, errors) = type_check(&format!(
+            "{}
+            fn main() -> u64 {{
+                match Color::Blue {{
+                    Color::Red => {{ 1 }},
+                    Color::Green => {{ 2 }},
+                    Color::Blue => {{ 3 }},
+                }}
+            }}",
+            COLOR
+        ));
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert!(unreachable_arms(&warnings).is_empty());
+    }
+
+    #[test]
+    fn non_exhaustive_match_lists_missing_patterns() {
+        let (_, errors) = type_check(&format!(
+            "{}
+            fn main() -> u64 {{
+                match (Color::Blue, true) {{
+                    (Color::Red, b) => {{ 1 }},
+                    (Color::Green, true) => {{ 2 }},
+                }}
+            }}",
+            COLOR
+        ));
+        let missing_patterns = errors
+            .iter()
+            .filter_map(|error| match error {
+                CompileError::MatchExpressionNonExhaustive {
+                    missing_patterns, ..
+                } => Some(missing_patterns.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            missing_patterns,
+            ["(Color::Green, false), (Color::Blue, _)"]
+        );
+    }
+
+    #[test]
+    fn unreachable_arm_is_warned() {
+        let (warnings, errors) = type_check(&format!(
+            "{}
+            fn main() -> u64 {{
+                match Color::Blue {{
+                    Color::Red => {{ 1 }},
+                    c => {{ 2 }},
+                    Color::Blue => {{ 3 }},
+                }}
+            }}",
+            COLOR
+        ));
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(unreachable_arms(&warnings), ["Color::Blue"]);
+    }
+}
@@ -1,7 +1,7 @@
 use super::*;
 use crate::build_config::BuildConfig;
 use crate::control_flow_analysis::ControlFlowGraph;
-use crate::parse_tree::MethodName;
+use crate::parse_tree::{MethodName, OpVariant};
 use crate::parser::{Rule, SwayParser};
 use crate::semantic_analysis::TCOpts;
 use pest::Parser;
@@ -23,6 +23,18 @@ pub(crate) fn type_check_method_application(
     let mut warnings = vec![];
     let mut errors = vec![];
     let mut args_buf = VecDeque::new();
+    let numeric_literal_args = arguments
+        .iter()
+        .map(|arg| {
+            matches!(
+                arg,
+                Expression::Literal {
+                    value: Literal::Numeric(_),
+                    ..
+                }
+            )
+        })
+        .collect::<Vec<_>>();
     for arg in arguments {
         args_buf.push_back(check!(
             TypedExpression::type_check(TypeCheckArguments {
@@ -44,7 +56,23 @@ pub(crate) fn type_check_method_application(
         ));
     }
 
-    let method = match method_name {
+    if let Some(op) = builtin_operator(&method_name, &args_buf) {
+        return type_check_builtin_operator(op, args_buf, &numeric_literal_args, span, self_type);
+    }
+    if let MethodName::FromModule { ref method_name } = method_name {
+        if let Some(op) = VecOp::from_method_name(method_name, &args_buf) {
+            return type_check_vec_op(
+                op,
+                method_name.clone(),
+                args_buf,
+                span,
+                namespace,
+                self_type,
+            );
+        }
+    }
+
+    let (method, ty) = match method_name {
         MethodName::FromType {
             ref type_name,
             ref call_path,
@@ -68,7 +96,7 @@ pub(crate) fn type_check_method_application(
             } else {
                 None
             };
-            check!(
+            let method = check!(
                 namespace.find_method_for_type(
                     ty,
                     &call_path.suffix,
@@ -80,21 +108,41 @@ pub(crate) fn type_check_method_application(
                 return err(warnings, errors),
                 warnings,
                 errors
-            )
+            );
+            (method, ty)
         }
         MethodName::FromModule { ref method_name } => {
             let ty = args_buf
                 .get(0)
                 .map(|x| x.return_type)
                 .unwrap_or_else(|| insert_type(TypeInfo::Unknown));
-            check!(
+            let method = check!(
                 namespace.find_method_for_type(ty, method_name, &[], None, self_type, &args_buf),
                 return err(warnings, errors),
                 warnings,
                 errors
-            )
+            );
+            (method, ty)
         }
     };
+    // methods found for a generic type are those of the traits it is constrained by, which are
+    // only resolved to an implementation once the function containing this call is monomorphized
+    let constrained_type = match namespace.resolve_type_with_self(look_up_type_id(ty), self_type) {
+        Ok(resolved) if matches!(look_up_type_id(resolved), TypeInfo::UnknownGeneric { .. }) => {
+            namespace
+                .find_trait_of_method(resolved, &method)
+                .map(|trait_name| (resolved, trait_name))
+        }
+        _ => None,
+    };
+    if method.is_deprecated() {
+        warnings.push(CompileWarning {
+            warning_content: Warning::UsingDeprecated {
+                name: method.name.as_str().to_string(),
+            },
+            span: span.clone(),
+        });
+    }
     let contract_caller = if method.is_contract_call {
         args_buf.pop_front()
     } else {
@@ -123,7 +171,7 @@ pub(crate) fn type_check_method_application(
         }
         // The annotation may result in a cast, which is handled in the type engine.
     }
-    let exp = match method_name {
+    let mut exp = match method_name {
         // something like a.b(c)
         MethodName::FromModule { method_name } => {
             if args_buf.len() > method.parameters.len() {
@@ -150,6 +198,12 @@ pub(crate) fn type_check_method_application(
                 .zip(args_buf.into_iter())
                 .map(|(param, arg)| (param.name.clone(), arg))
                 .collect::<Vec<(_, _)>>();
+            let reference_parameters = check!(
+                check_reference_arguments(&method.parameters, &args_and_names, namespace),
+                vec![],
+                warnings,
+                errors
+            );
 
             TypedExpression {
                 expression: TypedExpressionVariant::FunctionApplication {
@@ -198,6 +252,9 @@ pub(crate) fn type_check_method_application(
                     } else {
                         None
                     },
+                    constrained_type,
+                    inline: method.inline(),
+                    reference_parameters,
                 },
                 return_type: method.return_type,
                 is_constant: IsConstant::No,
@@ -231,6 +288,12 @@ pub(crate) fn type_check_method_application(
                 .zip(args_buf.into_iter())
                 .map(|(param, arg)| (param.name.clone(), arg))
                 .collect::<Vec<(_, _)>>();
+            let reference_parameters = check!(
+                check_reference_arguments(&method.parameters, &args_and_names, namespace),
+                vec![],
+                warnings,
+                errors
+            );
             TypedExpression {
                 expression: TypedExpressionVariant::FunctionApplication {
                     name: call_path.clone(),
@@ -273,6 +336,9 @@ pub(crate) fn type_check_method_application(
                     } else {
                         None
                     },
+                    constrained_type,
+                    inline: method.inline(),
+                    reference_parameters,
                 },
                 return_type: method.return_type,
                 is_constant: IsConstant::No,
@@ -280,9 +346,162 @@ pub(crate) fn type_check_method_application(
             }
         }
     };
+    if let TypedExpressionVariant::FunctionApplication {
+        arguments,
+        function_body,
+        ..
+    } = &mut exp.expression
+    {
+        bind_function_arguments(arguments, function_body);
+    }
     ok(exp, warnings, errors)
 }
 
+/// The operator which `method_name` refers to, if it's a `core::ops` method applied to
+/// arguments which the core library has no implementation for.  That is any operator on wide or
+/// signed integers, and a shift of any integer.
+fn builtin_operator(
+    method_name: &MethodName,
+    args: &VecDeque<TypedExpression>,
+) -> Option<OpVariant> {
+    let call_path = match method_name {
+        MethodName::FromType {
+            call_path,
+            type_name: None,
+            is_absolute: true,
+        } => call_path,
+        _ => return None,
+    };
+    let is_core_ops = call_path
+        .prefixes
+        .iter()
+        .map(|prefix| prefix.as_str())
+        .eq(["core", "ops"].iter().copied());
+    if !is_core_ops || args.len() != 2 {
+        return None;
+    }
+    let op = OpVariant::from_method_name(call_path.suffix.as_str())?;
+    let has_builtin_arg = args
+        .iter()
+        .any(|arg| match look_up_type_id(arg.return_type) {
+            TypeInfo::UnsignedInteger(bits) => bits.is_wide(),
+            TypeInfo::SignedInteger(_) => true,
+            _ => false,
+        });
+    let is_integer_shift = op.is_shift()
+        && matches!(
+            look_up_type_id(args[0].return_type),
+            TypeInfo::UnsignedInteger(_) | TypeInfo::SignedInteger(_)
+        );
+    if has_builtin_arg || is_integer_shift {
+        Some(op)
+    } else {
+        None
+    }
+}
+
+fn type_check_builtin_operator(
+    op: OpVariant,
+    mut args: VecDeque<TypedExpression>,
+    numeric_literal_args: &[bool],
+    span: Span,
+    self_type: TypeId,
+) -> CompileResult<TypedExpression> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let (mut lhs, mut rhs) = match (args.pop_front(), args.pop_front()) {
+        (Some(lhs), Some(rhs)) => (lhs, rhs),
+        _ => unreachable!("Builtin operators are binary."),
+    };
+    // A shift amount is always a `u64`, whatever the type of the value being shifted.
+    if op.is_shift() {
+        match unify_with_self(
+            rhs.return_type,
+            insert_type(TypeInfo::UnsignedInteger(IntegerBits::SixtyFour)),
+            self_type,
+            &rhs.span,
+        ) {
+            Ok(mut ws) => warnings.append(&mut ws),
+            Err(e) => errors.push(CompileError::TypeError(e)),
+        }
+        return ok(
+            TypedExpression {
+                return_type: lhs.return_type,
+                expression: TypedExpressionVariant::BuiltinOperator {
+                    op,
+                    lhs: Box::new(lhs),
+                    rhs: Box::new(rhs),
+                },
+                is_constant: IsConstant::No,
+                span,
+            },
+            warnings,
+            errors,
+        );
+    }
+    // A numeric literal operand will have been resolved to a `u64` without knowing the type of
+    // the other operand, so is resolved again as that type.
+    if numeric_literal_args[0] {
+        lhs = check!(
+            resolve_numeric_literal_operand(lhs, rhs.return_type),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+    } else if numeric_literal_args[1] {
+        rhs = check!(
+            resolve_numeric_literal_operand(rhs, lhs.return_type),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+    }
+    // Both operands must be of the same type, though either may be a numeric literal.
+    match unify_with_self(rhs.return_type, lhs.return_type, self_type, &rhs.span) {
+        Ok(mut ws) => warnings.append(&mut ws),
+        Err(e) => errors.push(CompileError::TypeError(e)),
+    }
+    let return_type = if op.is_comparison() {
+        insert_type(TypeInfo::Boolean)
+    } else {
+        lhs.return_type
+    };
+    ok(
+        TypedExpression {
+            expression: TypedExpressionVariant::BuiltinOperator {
+                op,
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+            },
+            return_type,
+            is_constant: IsConstant::No,
+            span,
+        },
+        warnings,
+        errors,
+    )
+}
+
+fn resolve_numeric_literal_operand(
+    operand: TypedExpression,
+    other_type: TypeId,
+) -> CompileResult<TypedExpression> {
+    let is_other_integer = matches!(
+        look_up_type_id(other_type),
+        TypeInfo::UnsignedInteger(_) | TypeInfo::SignedInteger(_)
+    );
+    match operand.expression {
+        TypedExpressionVariant::Literal(Literal::U64(num)) if is_other_integer => {
+            TypedExpression::resolve_numeric_literal(
+                Literal::Numeric(num),
+                operand.span,
+                other_type,
+            )
+        }
+        _ => ok(operand, vec![], vec![]),
+    }
+}
+
 // TODO(static span): this whole method can go away and the address can go back in the contract
 // caller type.
 #[allow(clippy::too_many_arguments)]
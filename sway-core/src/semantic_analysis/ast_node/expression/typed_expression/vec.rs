@@ -0,0 +1,228 @@
+//! The growable `Vec<T>`, whose elements are allocated on the heap.
+//!
+//! A vector is created with `Vec::new()` or `Vec::with_capacity(capacity)`, and its methods are
+//! known to the compiler rather than declared in Sway. Reading or writing an element at an index
+//! which is not less than the length of the vector reverts. Like reassigning it, pushing or setting
+//! an element of a vector which is held by a variable requires that variable to be mutable.
+
+use super::*;
+use crate::build_config::BuildConfig;
+use crate::control_flow_analysis::ControlFlowGraph;
+use crate::semantic_analysis::TCOpts;
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum VecOp {
+    /// `Vec::new()` creates an empty vector, without allocating any room for elements.
+    New,
+    /// `Vec::with_capacity(capacity: u64)` creates an empty vector with room for `capacity`
+    /// elements.
+    WithCapacity,
+    /// `v.push(elem: T)` appends `elem`, first moving the elements to a larger allocation if
+    /// there's no room for it.
+    Push,
+    /// `v.len() -> u64` is the number of elements.
+    Len,
+    /// `v.capacity() -> u64` is the number of elements there's room for.
+    Capacity,
+    /// `v.get(index: u64) -> T`, or `v[index]`, is the element at `index`.
+    Get,
+    /// `v.set(index: u64, elem: T)` replaces the element at `index` with `elem`.
+    Set,
+}
+
+impl VecOp {
+    /// The constructor which `name` refers to, unless `Vec` is shadowed by a declaration in
+    /// `namespace`.
+    pub(crate) fn from_call_path(name: &CallPath, namespace: NamespaceRef) -> Option<Self> {
+        match name.prefixes.as_slice() {
+            [prefix]
+                if prefix.as_str() == "Vec" && namespace.get_symbol(prefix).value.is_none() => {}
+            _ => return None,
+        }
+        match name.suffix.as_str() {
+            "new" => Some(VecOp::New),
+            "with_capacity" => Some(VecOp::WithCapacity),
+            _ => None,
+        }
+    }
+
+    /// The method which `method_name` refers to, if it is called on a vector.
+    pub(crate) fn from_method_name(
+        method_name: &Ident,
+        arguments: &VecDeque<TypedExpression>,
+    ) -> Option<Self> {
+        match arguments
+            .front()
+            .map(|arg| look_up_type_id(arg.return_type))
+        {
+            Some(TypeInfo::Vector(_)) => (),
+            _ => return None,
+        }
+        match method_name.as_str() {
+            "push" => Some(VecOp::Push),
+            "len" => Some(VecOp::Len),
+            "capacity" => Some(VecOp::Capacity),
+            "get" => Some(VecOp::Get),
+            "set" => Some(VecOp::Set),
+            _ => None,
+        }
+    }
+
+    /// The types of the arguments, starting with the vector itself for a method.
+    fn parameters(&self, elem_type: TypeId) -> Vec<TypeId> {
+        let vec = || insert_type(TypeInfo::Vector(elem_type));
+        let index = || insert_type(TypeInfo::UnsignedInteger(IntegerBits::SixtyFour));
+        match self {
+            VecOp::New => vec![],
+            VecOp::WithCapacity => vec![index()],
+            VecOp::Push => vec![vec(), elem_type],
+            VecOp::Len | VecOp::Capacity => vec![vec()],
+            VecOp::Get => vec![vec(), index()],
+            VecOp::Set => vec![vec(), index(), elem_type],
+        }
+    }
+
+    fn return_type(&self, elem_type: TypeId) -> TypeInfo {
+        match self {
+            VecOp::New | VecOp::WithCapacity => TypeInfo::Vector(elem_type),
+            VecOp::Push | VecOp::Set => TypeInfo::Tuple(Vec::new()),
+            VecOp::Len | VecOp::Capacity => TypeInfo::UnsignedInteger(IntegerBits::SixtyFour),
+            VecOp::Get => TypeInfo::Ref(elem_type),
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn type_check_vec_constructor(
+    op: VecOp,
+    name: CallPath,
+    arguments: Vec<Expression>,
+    span: Span,
+    namespace: NamespaceRef,
+    crate_namespace: NamespaceRef,
+    self_type: TypeId,
+    build_config: &BuildConfig,
+    dead_code_graph: &mut ControlFlowGraph,
+    dependency_graph: &mut HashMap<String, HashSet<String>>,
+    opts: TCOpts,
+) -> CompileResult<TypedExpression> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let mut typed_arguments = VecDeque::with_capacity(arguments.len());
+    for arg in arguments {
+        let arg_span = arg.span();
+        typed_arguments.push_back(check!(
+            TypedExpression::type_check(TypeCheckArguments {
+                checkee: arg,
+                namespace,
+                crate_namespace,
+                return_type_annotation: insert_type(TypeInfo::Unknown),
+                help_text: Default::default(),
+                self_type,
+                build_config,
+                dead_code_graph,
+                dependency_graph,
+                mode: Mode::NonAbi,
+                opts,
+            }),
+            error_recovery_expr(arg_span),
+            warnings,
+            errors
+        ));
+    }
+    let expression = check!(
+        type_check_vec_op(op, name.suffix, typed_arguments, span, namespace, self_type),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    ok(expression, warnings, errors)
+}
+
+/// Checks the arguments of `op`, which have been type checked already, against its parameters.
+pub(crate) fn type_check_vec_op(
+    op: VecOp,
+    name: Ident,
+    arguments: VecDeque<TypedExpression>,
+    span: Span,
+    namespace: NamespaceRef,
+    self_type: TypeId,
+) -> CompileResult<TypedExpression> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let elem_type = match arguments
+        .front()
+        .map(|arg| look_up_type_id(arg.return_type))
+    {
+        Some(TypeInfo::Vector(elem_type)) => elem_type,
+        _ => insert_type(TypeInfo::Unknown),
+    };
+    let parameters = op.parameters(elem_type);
+    match arguments.len().cmp(&parameters.len()) {
+        Ordering::Greater => errors.push(CompileError::TooManyArgumentsForFunction {
+            span: span.clone(),
+            method_name: name,
+            expected: parameters.len(),
+            received: arguments.len(),
+        }),
+        Ordering::Less => errors.push(CompileError::TooFewArgumentsForFunction {
+            span: span.clone(),
+            method_name: name,
+            expected: parameters.len(),
+            received: arguments.len(),
+        }),
+        Ordering::Equal => {}
+    }
+    if !errors.is_empty() {
+        return err(warnings, errors);
+    }
+    if let (VecOp::Push | VecOp::Set, Some(vec)) = (op, arguments.front()) {
+        errors.append(&mut check_vec_is_mutable(vec, namespace));
+    }
+    for (arg, param) in arguments.iter().zip(parameters) {
+        match crate::type_engine::unify_with_self(arg.return_type, param, self_type, &arg.span) {
+            Ok(mut ws) => warnings.append(&mut ws),
+            Err(_e) => errors.push(CompileError::ArgumentParameterTypeMismatch {
+                span: arg.span.clone(),
+                provided: arg.return_type.friendly_type_str(),
+                should_be: param.friendly_type_str(),
+            }),
+        }
+    }
+    let expression = TypedExpression {
+        expression: TypedExpressionVariant::VecOperation {
+            op,
+            arguments: arguments.into(),
+        },
+        return_type: insert_type(op.return_type(elem_type)),
+        is_constant: IsConstant::No,
+        span,
+    };
+    ok(expression, warnings, errors)
+}
+
+/// Checks that the variable holding `vec`, if it's held by one, is mutable. A vector which is a
+/// field of a struct or tuple is held by the variable holding that.
+fn check_vec_is_mutable(vec: &TypedExpression, namespace: NamespaceRef) -> Vec<CompileError> {
+    let mut holder = vec;
+    loop {
+        match &holder.expression {
+            TypedExpressionVariant::StructFieldAccess { prefix, .. }
+            | TypedExpressionVariant::TupleElemAccess { prefix, .. } => holder = prefix,
+            TypedExpressionVariant::VariableExpression { name } => {
+                return match namespace.get_symbol(name).value {
+                    Some(TypedDeclaration::VariableDeclaration(TypedVariableDeclaration {
+                        is_mutable,
+                        ..
+                    })) if !is_mutable.is_mutable() => vec![CompileError::AssignmentToNonMutable(
+                        name.as_str().to_string(),
+                        vec.span.clone(),
+                    )],
+                    _ => vec![],
+                };
+            }
+            _ => return vec![],
+        }
+    }
+}
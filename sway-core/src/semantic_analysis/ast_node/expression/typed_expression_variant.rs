@@ -18,12 +18,28 @@ pub(crate) enum TypedExpressionVariant {
         /// If this is `Some(val)` then `val` is the metadata. If this is `None`, then
         /// there is no selector.
         selector: Option<ContractCallMetadata>,
+        /// If this is `Some((ty, trait_name))` then this calls a method of the trait `trait_name`
+        /// which the generic type `ty` is constrained to implement. The `function_body` is empty
+        /// until `ty` is known, when the function containing the call is monomorphized.
+        constrained_type: Option<(TypeId, Ident)>,
+        /// The called function's `#[inline(..)]` hint, if it has one.
+        inline: Option<Inline>,
+        /// The `ref mut` parameters, whose arguments are mutable variables which the function is
+        /// passed pointers to rather than their values.
+        reference_parameters: Vec<Ident>,
     },
     LazyOperator {
         op: LazyOp,
         lhs: Box<TypedExpression>,
         rhs: Box<TypedExpression>,
     },
+    /// An operator on a type which the core library has no implementation for, such as a `u128`,
+    /// which the compiler implements itself.
+    BuiltinOperator {
+        op: OpVariant,
+        lhs: Box<TypedExpression>,
+        rhs: Box<TypedExpression>,
+    },
     VariableExpression {
         name: Ident,
     },
@@ -69,6 +85,10 @@ pub(crate) enum TypedExpressionVariant {
         arg_num_to_access: usize,
         resolved_type_of_parent: TypeId,
     },
+    /// The tag of the enum `prefix`, which is the position of its variant in the declaration.
+    EnumTag {
+        prefix: Box<TypedExpression>,
+    },
     TupleElemAccess {
         prefix: Box<TypedExpression>,
         elem_to_access_num: usize,
@@ -90,6 +110,33 @@ pub(crate) enum TypedExpressionVariant {
         // this span may be used for errors in the future, although it is not right now.
         span: Span,
     },
+    /// A read of a contract storage field. `ix` is the index of the field within the storage
+    /// declaration, which determines the slot it lives in.
+    StorageAccess {
+        field_name: Ident,
+        ix: usize,
+    },
+    /// A call to the `revert` intrinsic, which halts the program with `code`.
+    Revert {
+        code: Box<TypedExpression>,
+    },
+    /// A call to the `log` intrinsic, which records `value` in the receipts.
+    Log {
+        value: Box<TypedExpression>,
+    },
+    /// A `Vec<T>` constructor or method. For a method, the vector is the first argument.
+    VecOperation {
+        op: VecOp,
+        arguments: Vec<TypedExpression>,
+    },
+    /// A function passed by name, or a closure.
+    FunctionValue(TypedFunctionValue),
+    /// A call through `name`, a parameter of function type. Once the function value passed to the
+    /// parameter is bound, this is replaced by a call to that function value.
+    FunctionValueApplication {
+        name: Ident,
+        arguments: Vec<TypedExpression>,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -116,11 +163,16 @@ impl TypedExpressionVariant {
                     Literal::U16(content) => content.to_string(),
                     Literal::U32(content) => content.to_string(),
                     Literal::U64(content) => content.to_string(),
+                    Literal::U128(content) => content.to_string(),
+                    Literal::I8(content) => content.to_string(),
+                    Literal::I16(content) => content.to_string(),
+                    Literal::I32(content) => content.to_string(),
+                    Literal::I64(content) => content.to_string(),
                     Literal::Numeric(content) => content.to_string(),
                     Literal::String(content) => content.as_str().to_string(),
                     Literal::Boolean(content) => content.to_string(),
                     Literal::Byte(content) => content.to_string(),
-                    Literal::B256(content) => content
+                    Literal::B256(content) | Literal::U256(content) => content
                         .iter()
                         .map(|x| x.to_string())
                         .collect::<Vec<_>>()
@@ -134,6 +186,9 @@ impl TypedExpressionVariant {
                 LazyOp::And => "&&".into(),
                 LazyOp::Or => "||".into(),
             },
+            TypedExpressionVariant::BuiltinOperator { op, .. } => {
+                format!("builtin {}", op.as_str())
+            }
             TypedExpressionVariant::Tuple { fields } => {
                 let fields = fields
                     .iter()
@@ -190,6 +245,19 @@ impl TypedExpressionVariant {
             TypedExpressionVariant::VariableExpression { name, .. } => {
                 format!("\"{}\" variable exp", name.as_str())
             }
+            TypedExpressionVariant::StorageAccess { field_name, .. } => {
+                format!("storage field \"{}\" access", field_name.as_str())
+            }
+            TypedExpressionVariant::EnumTag { .. } => "enum tag".into(),
+            TypedExpressionVariant::Revert { .. } => "revert".into(),
+            TypedExpressionVariant::Log { .. } => "log".into(),
+            TypedExpressionVariant::VecOperation { op, .. } => format!("vec {:?}", op),
+            TypedExpressionVariant::FunctionValue(value) => {
+                format!("\"{}\" fn value", value.name.suffix.as_str())
+            }
+            TypedExpressionVariant::FunctionValueApplication { name, .. } => {
+                format!("\"{}\" fn value application", name.as_str())
+            }
             TypedExpressionVariant::EnumInstantiation {
                 tag,
                 enum_decl,
@@ -213,14 +281,24 @@ impl TypedExpressionVariant {
             FunctionApplication {
                 arguments,
                 function_body,
+                constrained_type,
                 ..
             } => {
                 arguments
                     .iter_mut()
                     .for_each(|(_ident, expr)| expr.copy_types(type_mapping));
                 function_body.copy_types(type_mapping);
+                if let Some((ref mut constrained_type, _)) = constrained_type {
+                    *constrained_type = if let Some(matching_id) =
+                        look_up_type_id(*constrained_type).matches_type_parameter(type_mapping)
+                    {
+                        insert_type(TypeInfo::Ref(matching_id))
+                    } else {
+                        insert_type(look_up_type_id_raw(*constrained_type))
+                    };
+                }
             }
-            LazyOperator { lhs, rhs, .. } => {
+            LazyOperator { lhs, rhs, .. } | BuiltinOperator { lhs, rhs, .. } => {
                 (*lhs).copy_types(type_mapping);
                 (*rhs).copy_types(type_mapping);
             }
@@ -317,6 +395,338 @@ impl TypedExpressionVariant {
                 };
             }
             AbiCast { address, .. } => address.copy_types(type_mapping),
+            StorageAccess { .. } => (),
+            EnumTag { prefix } => prefix.copy_types(type_mapping),
+            Revert { code } => code.copy_types(type_mapping),
+            Log { value } => value.copy_types(type_mapping),
+            VecOperation { arguments, .. } | FunctionValueApplication { arguments, .. } => {
+                arguments
+                    .iter_mut()
+                    .for_each(|arg| arg.copy_types(type_mapping))
+            }
+            FunctionValue(value) => value.copy_types(type_mapping),
+        }
+    }
+
+    /// Replaces the body of every call to a method of a constrained generic type, whose type is
+    /// now known, with the body of that method as implemented for the type.
+    pub(crate) fn resolve_constrained_methods(
+        &mut self,
+        namespace: NamespaceRef,
+        self_type: TypeId,
+    ) -> CompileResult<()> {
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        use TypedExpressionVariant::*;
+        match self {
+            FunctionApplication {
+                name,
+                arguments,
+                function_body,
+                constrained_type,
+                inline,
+                ..
+            } => {
+                for (_ident, expr) in arguments.iter_mut() {
+                    check!(
+                        expr.resolve_constrained_methods(namespace, self_type),
+                        (),
+                        warnings,
+                        errors
+                    );
+                }
+                if let Some((ty, trait_name)) = constrained_type.clone() {
+                    match look_up_type_id(ty) {
+                        TypeInfo::UnknownGeneric { .. } | TypeInfo::ErrorRecovery => (),
+                        _ => {
+                            let method = check!(
+                                namespace.find_trait_method_for_type(
+                                    ty,
+                                    &trait_name,
+                                    &name.suffix,
+                                    self_type,
+                                    &arguments.iter().map(|(_, arg)| arg.clone()).collect(),
+                                ),
+                                return err(warnings, errors),
+                                warnings,
+                                errors
+                            );
+                            // the implementation may name its parameters differently from the
+                            // trait
+                            for ((ident, _expr), param) in
+                                arguments.iter_mut().zip(method.parameters.iter())
+                            {
+                                *ident = param.name.clone();
+                            }
+                            *inline = method.inline();
+                            *function_body = method.body;
+                            *constrained_type = None;
+                        }
+                    }
+                }
+                check!(
+                    function_body.resolve_constrained_methods(namespace, self_type),
+                    (),
+                    warnings,
+                    errors
+                );
+            }
+            LazyOperator { lhs, rhs, .. }
+            | BuiltinOperator { lhs, rhs, .. }
+            | ArrayIndex {
+                prefix: lhs,
+                index: rhs,
+            } => {
+                check!(
+                    lhs.resolve_constrained_methods(namespace, self_type),
+                    (),
+                    warnings,
+                    errors
+                );
+                check!(
+                    rhs.resolve_constrained_methods(namespace, self_type),
+                    (),
+                    warnings,
+                    errors
+                );
+            }
+            Tuple { fields: exprs }
+            | Array { contents: exprs }
+            | VecOperation {
+                arguments: exprs, ..
+            }
+            | FunctionValueApplication {
+                arguments: exprs, ..
+            } => {
+                for expr in exprs.iter_mut() {
+                    check!(
+                        expr.resolve_constrained_methods(namespace, self_type),
+                        (),
+                        warnings,
+                        errors
+                    );
+                }
+            }
+            StructExpression { fields, .. } => {
+                for field in fields.iter_mut() {
+                    check!(
+                        field
+                            .value
+                            .resolve_constrained_methods(namespace, self_type),
+                        (),
+                        warnings,
+                        errors
+                    );
+                }
+            }
+            CodeBlock(block) => {
+                check!(
+                    block.resolve_constrained_methods(namespace, self_type),
+                    (),
+                    warnings,
+                    errors
+                );
+            }
+            IfExp {
+                condition,
+                then,
+                r#else,
+            } => {
+                check!(
+                    condition.resolve_constrained_methods(namespace, self_type),
+                    (),
+                    warnings,
+                    errors
+                );
+                check!(
+                    then.resolve_constrained_methods(namespace, self_type),
+                    (),
+                    warnings,
+                    errors
+                );
+                if let Some(ref mut r#else) = r#else {
+                    check!(
+                        r#else.resolve_constrained_methods(namespace, self_type),
+                        (),
+                        warnings,
+                        errors
+                    );
+                }
+            }
+            AsmExpression { registers, .. } => {
+                for initializer in registers.iter_mut().filter_map(|x| x.initializer.as_mut()) {
+                    check!(
+                        initializer.resolve_constrained_methods(namespace, self_type),
+                        (),
+                        warnings,
+                        errors
+                    );
+                }
+            }
+            StructFieldAccess { prefix, .. }
+            | EnumArgAccess { prefix, .. }
+            | EnumTag { prefix }
+            | TupleElemAccess { prefix, .. }
+            | Revert { code: prefix }
+            | Log { value: prefix } => {
+                check!(
+                    prefix.resolve_constrained_methods(namespace, self_type),
+                    (),
+                    warnings,
+                    errors
+                );
+            }
+            EnumInstantiation {
+                contents: Some(contents),
+                ..
+            } => {
+                check!(
+                    contents.resolve_constrained_methods(namespace, self_type),
+                    (),
+                    warnings,
+                    errors
+                );
+            }
+            AbiCast { address, .. } => {
+                check!(
+                    address.resolve_constrained_methods(namespace, self_type),
+                    (),
+                    warnings,
+                    errors
+                );
+            }
+            FunctionValue(value) => {
+                for (_ident, expr) in value.captures.iter_mut() {
+                    check!(
+                        expr.resolve_constrained_methods(namespace, self_type),
+                        (),
+                        warnings,
+                        errors
+                    );
+                }
+                check!(
+                    value.body.resolve_constrained_methods(namespace, self_type),
+                    (),
+                    warnings,
+                    errors
+                );
+            }
+            Literal(..)
+            | VariableExpression { .. }
+            | FunctionParameter
+            | EnumInstantiation { contents: None, .. }
+            | StorageAccess { .. } => (),
+        }
+        ok((), warnings, errors)
+    }
+
+    /// Binds the parameters of function type named in `bindings` to the function values they are
+    /// passed: each call through one of them is replaced by a call to its function value.
+    pub(crate) fn bind_function_values(&mut self, bindings: &[(Ident, TypedFunctionValue)]) {
+        use TypedExpressionVariant::*;
+        let bound_value = |name: &Ident| {
+            bindings
+                .iter()
+                .find(|(param, _)| param == name)
+                .map(|(_, value)| value)
+        };
+        match self {
+            VariableExpression { name } => {
+                if let Some(value) = bound_value(name) {
+                    *self = FunctionValue(value.clone());
+                }
+            }
+            FunctionValueApplication { name, arguments } => {
+                for expr in arguments.iter_mut() {
+                    expr.bind_function_values(bindings);
+                }
+                if let Some(value) = bound_value(name) {
+                    *self = value.apply(std::mem::take(arguments));
+                }
+            }
+            FunctionApplication {
+                arguments,
+                function_body,
+                ..
+            } => {
+                // the arguments which are parameters bound here are now bound in turn to the
+                // parameters of the called function
+                let function_values = arguments
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (_, expr))| match &expr.expression {
+                        VariableExpression { name } => bound_value(name).is_some(),
+                        _ => false,
+                    })
+                    .map(|(ix, _)| ix)
+                    .collect();
+                for (_ident, expr) in arguments.iter_mut() {
+                    expr.bind_function_values(bindings);
+                }
+                bind_function_arguments_at(arguments, function_values, function_body);
+            }
+            FunctionValue(value) => {
+                for (_ident, expr) in value.captures.iter_mut() {
+                    expr.bind_function_values(bindings);
+                }
+            }
+            LazyOperator { lhs, rhs, .. }
+            | BuiltinOperator { lhs, rhs, .. }
+            | ArrayIndex {
+                prefix: lhs,
+                index: rhs,
+            } => {
+                lhs.bind_function_values(bindings);
+                rhs.bind_function_values(bindings);
+            }
+            Tuple { fields: exprs }
+            | Array { contents: exprs }
+            | VecOperation {
+                arguments: exprs, ..
+            } => {
+                for expr in exprs.iter_mut() {
+                    expr.bind_function_values(bindings);
+                }
+            }
+            StructExpression { fields, .. } => {
+                for field in fields.iter_mut() {
+                    field.value.bind_function_values(bindings);
+                }
+            }
+            CodeBlock(block) => block.bind_function_values(bindings),
+            IfExp {
+                condition,
+                then,
+                r#else,
+            } => {
+                condition.bind_function_values(bindings);
+                then.bind_function_values(bindings);
+                if let Some(ref mut r#else) = r#else {
+                    r#else.bind_function_values(bindings);
+                }
+            }
+            AsmExpression { registers, .. } => {
+                for initializer in registers.iter_mut().filter_map(|x| x.initializer.as_mut()) {
+                    initializer.bind_function_values(bindings);
+                }
+            }
+            StructFieldAccess { prefix, .. }
+            | EnumArgAccess { prefix, .. }
+            | EnumTag { prefix }
+            | TupleElemAccess { prefix, .. }
+            | Revert { code: prefix }
+            | Log { value: prefix }
+            | AbiCast {
+                address: prefix, ..
+            }
+            | EnumInstantiation {
+                contents: Some(prefix),
+                ..
+            } => prefix.bind_function_values(bindings),
+            Literal(..)
+            | FunctionParameter
+            | EnumInstantiation { contents: None, .. }
+            | StorageAccess { .. } => (),
         }
     }
 }
@@ -0,0 +1,320 @@
+use super::*;
+use crate::semantic_analysis::{ast_node::Mode, TypeCheckArguments};
+use crate::{ForLoop, ForLoopIterable};
+
+#[derive(Clone, Debug)]
+pub(crate) struct TypedForLoop {
+    pub(crate) variable: Ident,
+    /// The type of `variable`, which is the element type of an array or the integer type of a
+    /// range.
+    pub(crate) variable_type: TypeId,
+    pub(crate) iterable: TypedForLoopIterable,
+    pub(crate) body: TypedCodeBlock,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum TypedForLoopIterable {
+    Array(Box<TypedExpression>),
+    Range {
+        start: Box<TypedExpression>,
+        end: Box<TypedExpression>,
+    },
+}
+
+impl TypedForLoop {
+    pub(crate) fn type_check(
+        arguments: TypeCheckArguments<'_, ForLoop>,
+    ) -> CompileResult<TypedForLoop> {
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+
+        let TypeCheckArguments {
+            checkee:
+                ForLoop {
+                    variable,
+                    iterable,
+                    body,
+                },
+            namespace,
+            crate_namespace,
+            self_type,
+            build_config,
+            dead_code_graph,
+            dependency_graph,
+            opts,
+            ..
+        } = arguments;
+
+        // The iterable is evaluated once, before the loop, so it isn't part of the loop body.
+        let (iterable, variable_type, first_value) = match iterable {
+            ForLoopIterable::Array(array) => {
+                let array_span = array.span();
+                let typed_array = check!(
+                    TypedExpression::type_check(TypeCheckArguments {
+                        checkee: *array,
+                        namespace,
+                        crate_namespace,
+                        return_type_annotation: insert_type(TypeInfo::Unknown),
+                        help_text: Default::default(),
+                        self_type,
+                        build_config,
+                        dead_code_graph,
+                        dependency_graph,
+                        mode: Mode::NonAbi,
+                        opts,
+                    }),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                );
+                let elem_type = match look_up_type_id(typed_array.return_type) {
+                    TypeInfo::Array(elem_type, _) => elem_type,
+                    TypeInfo::ErrorRecovery => return err(warnings, errors),
+                    otherwise => {
+                        errors.push(CompileError::NotIterable {
+                            ty: otherwise.friendly_type_str(),
+                            span: array_span,
+                        });
+                        return err(warnings, errors);
+                    }
+                };
+                let first_elem = TypedExpression {
+                    expression: TypedExpressionVariant::ArrayIndex {
+                        prefix: Box::new(typed_array.clone()),
+                        index: Box::new(TypedExpression {
+                            expression: TypedExpressionVariant::Literal(Literal::U64(0)),
+                            return_type: insert_type(TypeInfo::UnsignedInteger(
+                                IntegerBits::SixtyFour,
+                            )),
+                            is_constant: IsConstant::Yes,
+                            span: array_span.clone(),
+                        }),
+                    },
+                    return_type: elem_type,
+                    is_constant: IsConstant::No,
+                    span: array_span,
+                };
+                (
+                    TypedForLoopIterable::Array(Box::new(typed_array)),
+                    elem_type,
+                    first_elem,
+                )
+            }
+            ForLoopIterable::Range { start, end } => {
+                let range_span = join_spans(start.span(), end.span());
+                let typed_start = check!(
+                    TypedExpression::type_check(TypeCheckArguments {
+                        checkee: *start,
+                        namespace,
+                        crate_namespace,
+                        return_type_annotation: insert_type(TypeInfo::Unknown),
+                        help_text: Default::default(),
+                        self_type,
+                        build_config,
+                        dead_code_graph,
+                        dependency_graph,
+                        mode: Mode::NonAbi,
+                        opts,
+                    }),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                );
+                let typed_end = check!(
+                    TypedExpression::type_check(TypeCheckArguments {
+                        checkee: *end,
+                        namespace,
+                        crate_namespace,
+                        return_type_annotation: typed_start.return_type,
+                        help_text: "The end of a range must be of the same type as its start.",
+                        self_type,
+                        build_config,
+                        dead_code_graph,
+                        dependency_graph,
+                        mode: Mode::NonAbi,
+                        opts,
+                    }),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                );
+                // The index is stepped and compared with single word ops.
+                match look_up_type_id(typed_start.return_type) {
+                    TypeInfo::UnsignedInteger(bits) if !bits.is_wide() => (),
+                    TypeInfo::ErrorRecovery => return err(warnings, errors),
+                    otherwise => {
+                        errors.push(CompileError::NotIterable {
+                            ty: otherwise.friendly_type_str(),
+                            span: range_span,
+                        });
+                        return err(warnings, errors);
+                    }
+                }
+                let variable_type = typed_start.return_type;
+                let first_value = TypedExpression {
+                    is_constant: IsConstant::No,
+                    ..typed_start.clone()
+                };
+                (
+                    TypedForLoopIterable::Range {
+                        start: Box::new(typed_start),
+                        end: Box::new(typed_end),
+                    },
+                    variable_type,
+                    first_value,
+                )
+            }
+        };
+
+        // The loop variable is declared in a scope of its own around the body.  Its first value
+        // stands in for its initializer, which is only used to find its type.
+        let loop_namespace = create_new_scope(namespace);
+        check!(
+            loop_namespace.insert(
+                variable.clone(),
+                TypedDeclaration::VariableDeclaration(TypedVariableDeclaration {
+                    name: variable.clone(),
+                    body: first_value,
+                    is_mutable: VariableMutability::Immutable,
+                    type_ascription: variable_type,
+                }),
+            ),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+
+        let whole_block_span = body.whole_block_span.clone();
+        let (typed_body, _block_implicit_return) = check!(
+            TypedCodeBlock::type_check(TypeCheckArguments {
+                checkee: body,
+                namespace: loop_namespace,
+                crate_namespace,
+                return_type_annotation: insert_type(TypeInfo::Tuple(Vec::new())),
+                help_text: "A for loop's loop body cannot implicitly return a value. Try \
+                            assigning it to a mutable variable declared outside of the loop \
+                            instead.",
+                self_type,
+                build_config,
+                dead_code_graph,
+                dependency_graph,
+                mode: Mode::NonAbi,
+                opts: TCOpts {
+                    in_loop: true,
+                    ..opts
+                },
+            }),
+            (
+                TypedCodeBlock {
+                    contents: vec![],
+                    whole_block_span,
+                },
+                insert_type(TypeInfo::Tuple(Vec::new()))
+            ),
+            warnings,
+            errors
+        );
+
+        ok(
+            TypedForLoop {
+                variable,
+                variable_type,
+                iterable,
+                body: typed_body,
+            },
+            warnings,
+            errors,
+        )
+    }
+
+    pub(crate) fn pretty_print(&self) -> String {
+        match &self.iterable {
+            TypedForLoopIterable::Array(array) => format!(
+                "for loop on {} in {}",
+                self.variable.as_str(),
+                array.pretty_print()
+            ),
+            TypedForLoopIterable::Range { start, end } => format!(
+                "for loop on {} in {}..{}",
+                self.variable.as_str(),
+                start.pretty_print(),
+                end.pretty_print()
+            ),
+        }
+    }
+
+    pub(crate) fn copy_types(&mut self, type_mapping: &[(TypeParameter, TypeId)]) {
+        self.variable_type = if let Some(matching_id) =
+            look_up_type_id(self.variable_type).matches_type_parameter(type_mapping)
+        {
+            insert_type(TypeInfo::Ref(matching_id))
+        } else {
+            insert_type(look_up_type_id_raw(self.variable_type))
+        };
+        match &mut self.iterable {
+            TypedForLoopIterable::Array(array) => array.copy_types(type_mapping),
+            TypedForLoopIterable::Range { start, end } => {
+                start.copy_types(type_mapping);
+                end.copy_types(type_mapping);
+            }
+        }
+        self.body.copy_types(type_mapping);
+    }
+
+    /// See [TypedExpressionVariant::resolve_constrained_methods].
+    pub(crate) fn resolve_constrained_methods(
+        &mut self,
+        namespace: NamespaceRef,
+        self_type: TypeId,
+    ) -> CompileResult<()> {
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        match &mut self.iterable {
+            TypedForLoopIterable::Array(array) => check!(
+                array.resolve_constrained_methods(namespace, self_type),
+                (),
+                warnings,
+                errors
+            ),
+            TypedForLoopIterable::Range { start, end } => {
+                check!(
+                    start.resolve_constrained_methods(namespace, self_type),
+                    (),
+                    warnings,
+                    errors
+                );
+                check!(
+                    end.resolve_constrained_methods(namespace, self_type),
+                    (),
+                    warnings,
+                    errors
+                );
+            }
+        }
+        check!(
+            self.body.resolve_constrained_methods(namespace, self_type),
+            (),
+            warnings,
+            errors
+        );
+        ok((), warnings, errors)
+    }
+
+    /// See [TypedExpressionVariant::bind_function_values].
+    pub(crate) fn bind_function_values(&mut self, bindings: &[(Ident, TypedFunctionValue)]) {
+        match &mut self.iterable {
+            TypedForLoopIterable::Array(array) => array.bind_function_values(bindings),
+            TypedForLoopIterable::Range { start, end } => {
+                start.bind_function_values(bindings);
+                end.bind_function_values(bindings);
+            }
+        }
+        // the loop variable shadows the parameter it has the name of
+        let bindings = bindings
+            .iter()
+            .filter(|(param, _)| *param != self.variable)
+            .cloned()
+            .collect::<Vec<_>>();
+        self.body.bind_function_values(&bindings);
+    }
+}
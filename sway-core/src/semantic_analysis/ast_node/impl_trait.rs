@@ -1,10 +1,13 @@
-use super::{declaration::TypedTraitFn, ERROR_RECOVERY_DECLARATION};
+use super::{
+    declaration::{TypedFunctionParameter, TypedTraitFn, TypedTypeAliasDeclaration},
+    ERROR_RECOVERY_DECLARATION,
+};
 
 use crate::{
     build_config::BuildConfig,
     control_flow_analysis::ControlFlowGraph,
     error::*,
-    parse_tree::{FunctionDeclaration, ImplTrait, TypeParameter},
+    parse_tree::{attribute, FunctionDeclaration, ImplTrait, TypeAliasDeclaration, Visibility},
     semantic_analysis::*,
     type_engine::{
         insert_type, look_up_type_id, resolve_type, FriendlyTypeString, TypeId, TypeInfo,
@@ -29,12 +32,15 @@ pub(crate) fn implementation_of_trait(
     let mut warnings = vec![];
     let ImplTrait {
         trait_name,
+        trait_type_arguments,
         type_arguments,
         functions,
         type_implementing_for,
         type_implementing_for_span,
         type_arguments_span,
+        associated_types,
         block_span,
+        attributes,
     } = impl_trait;
     let type_implementing_for = namespace.resolve_type_without_self(&type_implementing_for);
     let type_implementing_for = look_up_type_id(type_implementing_for);
@@ -50,22 +56,122 @@ pub(crate) fn implementation_of_trait(
         .ok(&mut warnings, &mut errors)
     {
         Some(TypedDeclaration::TraitDeclaration(tr)) => {
-            if type_arguments.len() != tr.type_parameters.len() {
+            if attribute::is_deprecated(&tr.attributes) {
+                warnings.push(CompileWarning {
+                    warning_content: Warning::UsingDeprecated {
+                        name: tr.name.as_str().to_string(),
+                    },
+                    span: trait_name.span(),
+                });
+            }
+            if trait_type_arguments.len() != tr.type_parameters.len() {
                 errors.push(CompileError::IncorrectNumberOfTypeArguments {
-                    given: type_arguments.len(),
+                    given: trait_type_arguments.len(),
                     expected: tr.type_parameters.len(),
                     span: type_arguments_span,
                 })
             }
+            let trait_type_arguments = trait_type_arguments
+                .into_iter()
+                .map(|(ty, span)| {
+                    namespace
+                        .resolve_type_with_self(ty, type_implementing_for_id)
+                        .unwrap_or_else(|_| {
+                            errors.push(CompileError::UnknownType { span });
+                            insert_type(TypeInfo::ErrorRecovery)
+                        })
+                })
+                .collect::<Vec<_>>();
+
+            let mut associated_type_ids = vec![];
+            for TypeAliasDeclaration {
+                name,
+                ty,
+                type_span,
+                ..
+            } in associated_types
+            {
+                if !tr.associated_types.contains(&name) {
+                    errors.push(CompileError::TypeNotAnAssociatedTypeOfTrait {
+                        name: name.clone(),
+                        trait_name: tr.name.clone(),
+                        span: name.span().clone(),
+                    });
+                    continue;
+                }
+                let ty = namespace
+                    .resolve_type_with_self(ty, type_implementing_for_id)
+                    .unwrap_or_else(|_| {
+                        errors.push(CompileError::UnknownType { span: type_span });
+                        insert_type(TypeInfo::ErrorRecovery)
+                    });
+                associated_type_ids.push((name, ty));
+            }
+            let missing_types = tr
+                .associated_types
+                .iter()
+                .filter(|name| !associated_type_ids.iter().any(|(given, _)| given == *name))
+                .map(|name| name.as_str().to_string())
+                .collect::<Vec<_>>();
+            if !missing_types.is_empty() {
+                errors.push(CompileError::MissingAssociatedTypes {
+                    missing_types: missing_types.join("\n"),
+                    span: block_span.clone(),
+                });
+            }
+
+            // the trait's interface is declared in terms of its type parameters and associated
+            // types, which this implementation gives
+            let type_mapping = tr
+                .type_parameters
+                .iter()
+                .cloned()
+                .zip(trait_type_arguments.iter().cloned())
+                .chain(tr.associated_type_parameters().into_iter().map(|param| {
+                    let ty = associated_type_ids
+                        .iter()
+                        .find(|(name, _)| *name == param.name_ident)
+                        .map(|(_, ty)| *ty)
+                        .unwrap_or_else(|| insert_type(TypeInfo::ErrorRecovery));
+                    (param, ty)
+                }))
+                .collect::<Vec<_>>();
+            let interface_surface = tr
+                .interface_surface
+                .iter()
+                .map(|trait_fn| {
+                    let mut trait_fn = trait_fn.clone();
+                    trait_fn.copy_types(&type_mapping);
+                    trait_fn
+                })
+                .collect::<Vec<_>>();
+            // within the implementation, the names of the trait's type parameters and associated
+            // types refer to the types given for them
+            let impl_namespace = create_new_scope(namespace);
+            for (param, ty) in type_mapping.iter() {
+                impl_namespace.insert(
+                    param.name_ident.clone(),
+                    TypedDeclaration::TypeAliasDeclaration(TypedTypeAliasDeclaration {
+                        name: param.name_ident.clone(),
+                        type_parameters: vec![],
+                        ty: *ty,
+                        visibility: Visibility::Private,
+                    }),
+                );
+            }
+            let trait_type_arguments = trait_type_arguments
+                .into_iter()
+                .map(look_up_type_id)
+                .collect::<Vec<_>>();
 
             let functions_buf = check!(
                 type_check_trait_implementation(
-                    &tr.interface_surface,
+                    &interface_surface,
                     &functions,
                     &tr.methods,
                     &tr.name,
-                    &tr.type_parameters,
-                    namespace,
+                    &trait_type_arguments,
+                    impl_namespace,
                     crate_namespace,
                     type_implementing_for_id,
                     build_config,
@@ -86,6 +192,7 @@ pub(crate) fn implementation_of_trait(
 
             namespace.insert_trait_implementation(
                 trait_name.clone(),
+                trait_type_arguments.clone(),
                 match resolve_type(type_implementing_for_id, &type_implementing_for_span) {
                     Ok(o) => o,
                     Err(e) => {
@@ -98,15 +205,25 @@ pub(crate) fn implementation_of_trait(
             ok(
                 TypedDeclaration::ImplTrait {
                     trait_name,
+                    trait_type_arguments,
                     span: block_span,
                     methods: functions_buf,
                     type_implementing_for,
+                    attributes,
                 },
                 warnings,
                 errors,
             )
         }
         Some(TypedDeclaration::AbiDeclaration(abi)) => {
+            if attribute::is_deprecated(&abi.attributes) {
+                warnings.push(CompileWarning {
+                    warning_content: Warning::UsingDeprecated {
+                        name: abi.name.as_str().to_string(),
+                    },
+                    span: trait_name.span(),
+                });
+            }
             // if you are comparing this with the `impl_trait` branch above, note that
             // there are no type arguments here because we don't support generic types
             // in contract ABIs yet (or ever?) due to the complexity of communicating
@@ -147,15 +264,18 @@ pub(crate) fn implementation_of_trait(
 
             namespace.insert_trait_implementation(
                 trait_name.clone(),
+                vec![],
                 look_up_type_id(type_implementing_for_id),
                 functions_buf.clone(),
             );
             ok(
                 TypedDeclaration::ImplTrait {
                     trait_name,
+                    trait_type_arguments: vec![],
                     span: block_span,
                     methods: functions_buf,
                     type_implementing_for,
+                    attributes,
                 },
                 warnings,
                 errors,
@@ -189,7 +309,7 @@ fn type_check_trait_implementation(
     functions: &[FunctionDeclaration],
     methods: &[FunctionDeclaration],
     trait_name: &Ident,
-    type_arguments: &[TypeParameter],
+    trait_type_arguments: &[TypeInfo],
     namespace: crate::semantic_analysis::NamespaceRef,
     crate_namespace: NamespaceRef,
     _self_type: TypeId,
@@ -253,10 +373,14 @@ fn type_check_trait_implementation(
             }
         };
         function_checklist.remove(ix_of_thing_to_remove);
-
-        let type_arguments = &(*type_arguments);
-        // add generic params from impl trait into function type params
-        fn_decl.type_parameters.append(&mut type_arguments.to_vec());
+        // attributes on the trait's declaration of this function, such as `#[deprecated]`,
+        // apply to every implementation of it
+        if let Some(trait_fn) = interface_surface
+            .iter()
+            .find(|trait_fn| trait_fn.name == fn_decl.name)
+        {
+            attribute::inherit(&mut fn_decl.attributes, &trait_fn.attributes);
+        }
 
         // ensure this fn decl's parameters and signature lines up with the one
         // in the trait
@@ -265,7 +389,7 @@ fn type_check_trait_implementation(
                  name,
                  parameters,
                  return_type,
-                 return_type_span: _,
+                 ..
              }| {
                 if fn_decl.name == *name {
                     if fn_decl.parameters.len() != parameters.len() {
@@ -289,6 +413,22 @@ fn type_check_trait_implementation(
                             // implement trait constraint solver */
                             let fn_decl_param_type = fn_decl_param.r#type;
                             let trait_param_type = trait_param.r#type;
+                            let describe = |param: &TypedFunctionParameter| {
+                                if param.is_reference {
+                                    format!("ref mut {}", param.r#type.friendly_type_str())
+                                } else {
+                                    param.r#type.friendly_type_str()
+                                }
+                            };
+                            // `fn_decl_param` is the trait's parameter and `trait_param` the
+                            // implementation's, as they're zipped in that order
+                            if fn_decl_param.is_reference != trait_param.is_reference {
+                                errors.push(CompileError::MismatchedTypeInTrait {
+                                    span: trait_param.type_span.clone(),
+                                    given: describe(trait_param),
+                                    expected: describe(fn_decl_param),
+                                });
+                            }
 
                             match crate::type_engine::unify_with_self(
                                 fn_decl_param_type,
@@ -359,6 +499,7 @@ fn type_check_trait_implementation(
             prefixes: vec![],
             suffix: trait_name.clone(),
         },
+        trait_type_arguments.to_vec(),
         match resolve_type(type_implementing_for, type_implementing_for_span) {
             Ok(o) => o,
             Err(e) => {
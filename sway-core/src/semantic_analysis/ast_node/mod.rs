@@ -22,7 +22,9 @@ pub(crate) use crate::semantic_analysis::ast_node::declaration::ReassignmentLhs;
 pub mod declaration;
 use declaration::TypedTraitFn;
 pub(crate) use declaration::{
-    OwnedTypedEnumVariant, OwnedTypedStructField, TypedReassignment, TypedTraitDeclaration,
+    check_trait_constraints, get_storage_chunk_keys, get_storage_initialized_key, get_storage_key,
+    OwnedTypedEnumVariant, OwnedTypedStructField, TypedReassignment, TypedStorageDeclaration,
+    TypedStorageField, TypedStorageReassignment, TypedTraitDeclaration, TypedTypeAliasDeclaration,
     TypedVariableDeclaration, VariableMutability,
 };
 pub use declaration::{
@@ -41,6 +43,9 @@ pub(crate) use code_block::TypedCodeBlock;
 mod expression;
 pub(crate) use expression::*;
 
+mod for_loop;
+pub(crate) use for_loop::{TypedForLoop, TypedForLoopIterable};
+
 mod return_statement;
 pub(crate) use return_statement::TypedReturnStatement;
 
@@ -62,6 +67,9 @@ pub(crate) enum TypedAstNodeContent {
     Expression(TypedExpression),
     ImplicitReturnExpression(TypedExpression),
     WhileLoop(TypedWhileLoop),
+    ForLoop(TypedForLoop),
+    Break,
+    Continue,
     // a no-op node used for something that just issues a side effect, like an import statement.
     SideEffect,
 }
@@ -83,6 +91,9 @@ impl std::fmt::Debug for TypedAstNode {
             Expression(exp) => exp.pretty_print(),
             ImplicitReturnExpression(exp) => format!("return {}", exp.pretty_print()),
             WhileLoop(w_loop) => w_loop.pretty_print(),
+            ForLoop(f_loop) => f_loop.pretty_print(),
+            Break => "break".into(),
+            Continue => "continue".into(),
             SideEffect => "".into(),
         };
         f.write_str(&text)
@@ -107,7 +118,75 @@ impl TypedAstNode {
                 condition.copy_types(type_mapping);
                 body.copy_types(type_mapping);
             }
-            TypedAstNodeContent::SideEffect => (),
+            TypedAstNodeContent::ForLoop(ref mut for_loop) => for_loop.copy_types(type_mapping),
+            TypedAstNodeContent::Break
+            | TypedAstNodeContent::Continue
+            | TypedAstNodeContent::SideEffect => (),
+        }
+    }
+    /// See [TypedExpressionVariant::resolve_constrained_methods].
+    pub(crate) fn resolve_constrained_methods(
+        &mut self,
+        namespace: NamespaceRef,
+        self_type: TypeId,
+    ) -> CompileResult<()> {
+        match self.content {
+            TypedAstNodeContent::ReturnStatement(TypedReturnStatement { ref mut expr })
+            | TypedAstNodeContent::ImplicitReturnExpression(ref mut expr)
+            | TypedAstNodeContent::Expression(ref mut expr) => {
+                expr.resolve_constrained_methods(namespace, self_type)
+            }
+            TypedAstNodeContent::Declaration(ref mut decl) => {
+                decl.resolve_constrained_methods(namespace, self_type)
+            }
+            TypedAstNodeContent::WhileLoop(TypedWhileLoop {
+                ref mut condition,
+                ref mut body,
+            }) => {
+                let mut warnings = vec![];
+                let mut errors = vec![];
+                check!(
+                    condition.resolve_constrained_methods(namespace, self_type),
+                    (),
+                    warnings,
+                    errors
+                );
+                check!(
+                    body.resolve_constrained_methods(namespace, self_type),
+                    (),
+                    warnings,
+                    errors
+                );
+                ok((), warnings, errors)
+            }
+            TypedAstNodeContent::ForLoop(ref mut for_loop) => {
+                for_loop.resolve_constrained_methods(namespace, self_type)
+            }
+            TypedAstNodeContent::Break
+            | TypedAstNodeContent::Continue
+            | TypedAstNodeContent::SideEffect => ok((), vec![], vec![]),
+        }
+    }
+    /// See [TypedExpressionVariant::bind_function_values].
+    pub(crate) fn bind_function_values(&mut self, bindings: &[(Ident, TypedFunctionValue)]) {
+        match self.content {
+            TypedAstNodeContent::ReturnStatement(TypedReturnStatement { ref mut expr })
+            | TypedAstNodeContent::ImplicitReturnExpression(ref mut expr)
+            | TypedAstNodeContent::Expression(ref mut expr) => expr.bind_function_values(bindings),
+            TypedAstNodeContent::Declaration(ref mut decl) => decl.bind_function_values(bindings),
+            TypedAstNodeContent::WhileLoop(TypedWhileLoop {
+                ref mut condition,
+                ref mut body,
+            }) => {
+                condition.bind_function_values(bindings);
+                body.bind_function_values(bindings);
+            }
+            TypedAstNodeContent::ForLoop(ref mut for_loop) => {
+                for_loop.bind_function_values(bindings)
+            }
+            TypedAstNodeContent::Break
+            | TypedAstNodeContent::Continue
+            | TypedAstNodeContent::SideEffect => (),
         }
     }
     fn type_info(&self) -> TypeInfo {
@@ -121,7 +200,9 @@ impl TypedAstNode {
             ImplicitReturnExpression(TypedExpression { return_type, .. }) => {
                 crate::type_engine::look_up_type_id(*return_type)
             }
-            WhileLoop(_) | SideEffect => TypeInfo::Tuple(Vec::new()),
+            WhileLoop(_) | ForLoop(_) | Break | Continue | SideEffect => {
+                TypeInfo::Tuple(Vec::new())
+            }
         }
     }
     pub(crate) fn type_check(
@@ -246,6 +327,22 @@ impl TypedAstNode {
                                 warnings,
                                 errors
                             );
+                            // function values have no run-time representation, so a variable can
+                            // only be another name for one which captures nothing and which it
+                            // can't be reassigned from
+                            if let TypeInfo::Function { .. } = look_up_type_id(body.return_type) {
+                                let is_alias = !is_mutable
+                                    && matches!(
+                                        &body.expression,
+                                        TypedExpressionVariant::FunctionValue(value)
+                                            if value.captures.is_empty()
+                                    );
+                                if !is_alias {
+                                    errors.push(CompileError::MisplacedFunctionValue(
+                                        body.span.clone(),
+                                    ));
+                                }
+                            }
                             let typed_var_decl =
                                 TypedDeclaration::VariableDeclaration(TypedVariableDeclaration {
                                     name: name.clone(),
@@ -274,16 +371,17 @@ impl TypedAstNode {
                                 warnings,
                                 errors
                             );
+                            let value = check!(
+                                evaluate_constant(&value, namespace),
+                                error_recovery_expr(name.span().clone()),
+                                warnings,
+                                errors
+                            );
                             let typed_const_decl =
-                                TypedDeclaration::VariableDeclaration(TypedVariableDeclaration {
+                                TypedDeclaration::ConstantDeclaration(TypedConstantDeclaration {
                                     name: name.clone(),
-                                    body: value,
-                                    is_mutable: if visibility.is_public() {
-                                        VariableMutability::ExportedConst
-                                    } else {
-                                        VariableMutability::Immutable
-                                    },
-                                    type_ascription: insert_type(type_ascription),
+                                    value,
+                                    visibility,
                                 });
                             namespace.insert(name, typed_const_decl.clone());
                             typed_const_decl
@@ -331,16 +429,31 @@ impl TypedAstNode {
                             interface_surface,
                             methods,
                             type_parameters,
+                            associated_types,
                             visibility,
+                            attributes,
                         }) => {
+                            // the type parameters and associated types are generic within the
+                            // trait, and are given by each implementation of it
+                            let trait_namespace = create_new_scope(namespace);
+                            type_parameters.iter().for_each(|param| {
+                                trait_namespace.insert(param.name_ident.clone(), param.into());
+                            });
+                            associated_types.iter().for_each(|name| {
+                                trait_namespace.insert(
+                                    name.clone(),
+                                    TypedDeclaration::GenericTypeForFunctionScope {
+                                        name: name.clone(),
+                                    },
+                                );
+                            });
                             // type check the interface surface
                             let interface_surface = check!(
-                                type_check_interface_surface(interface_surface, namespace),
+                                type_check_interface_surface(interface_surface, trait_namespace),
                                 return err(warnings, errors),
                                 warnings,
                                 errors
                             );
-                            let trait_namespace = create_new_scope(namespace);
                             // insert placeholder functions representing the interface surface
                             // to allow methods to use those functions
                             trait_namespace.insert_trait_implementation(
@@ -348,6 +461,7 @@ impl TypedAstNode {
                                     prefixes: vec![],
                                     suffix: name.clone(),
                                 },
+                                vec![],
                                 TypeInfo::SelfType,
                                 interface_surface
                                     .iter()
@@ -375,7 +489,9 @@ impl TypedAstNode {
                                     interface_surface,
                                     methods,
                                     type_parameters,
+                                    associated_types,
                                     visibility,
+                                    attributes,
                                 });
                             namespace.insert(name, trait_decl.clone());
                             trait_decl
@@ -424,6 +540,7 @@ impl TypedAstNode {
                             functions,
                             type_implementing_for,
                             block_span,
+                            attributes,
                             ..
                         }) => {
                             let implementing_for_type_id =
@@ -482,14 +599,17 @@ impl TypedAstNode {
                             };
                             namespace.insert_trait_implementation(
                                 trait_name.clone(),
+                                vec![],
                                 look_up_type_id(implementing_for_type_id),
                                 functions_buf.clone(),
                             );
                             TypedDeclaration::ImplTrait {
                                 trait_name,
+                                trait_type_arguments: vec![],
                                 span: block_span,
                                 methods: functions_buf,
-                                type_implementing_for,
+                                type_implementing_for: look_up_type_id(implementing_for_type_id),
+                                attributes,
                             }
                         }
                         Declaration::StructDeclaration(decl) => {
@@ -505,23 +625,31 @@ impl TypedAstNode {
                                          r#type,
                                          span,
                                          type_span,
-                                     }| TypedStructField {
-                                        name,
-                                        r#type: if let Some(matching_id) =
-                                            r#type.matches_type_parameter(&type_mapping)
-                                        {
-                                            insert_type(TypeInfo::Ref(matching_id))
-                                        } else {
-                                            namespace
-                                                .resolve_type_with_self(r#type, self_type)
-                                                .unwrap_or_else(|_| {
-                                                    errors.push(CompileError::UnknownType {
-                                                        span: type_span.clone(),
-                                                    });
-                                                    insert_type(TypeInfo::ErrorRecovery)
-                                                })
-                                        },
-                                        span,
+                                     }| {
+                                        // function values have no run-time representation
+                                        if let TypeInfo::Function { .. } = r#type {
+                                            errors.push(CompileError::MisplacedFunctionValue(
+                                                type_span.clone(),
+                                            ));
+                                        }
+                                        TypedStructField {
+                                            name,
+                                            r#type: if let Some(matching_id) =
+                                                r#type.matches_type_parameter(&type_mapping)
+                                            {
+                                                insert_type(TypeInfo::Ref(matching_id))
+                                            } else {
+                                                namespace
+                                                    .resolve_type_with_self(r#type, self_type)
+                                                    .unwrap_or_else(|_| {
+                                                        errors.push(CompileError::UnknownType {
+                                                            span: type_span.clone(),
+                                                        });
+                                                        insert_type(TypeInfo::ErrorRecovery)
+                                                    })
+                                            },
+                                            span,
+                                        }
                                     },
                                 )
                                 .collect::<Vec<_>>();
@@ -530,6 +658,7 @@ impl TypedAstNode {
                                 type_parameters: decl.type_parameters.clone(),
                                 fields,
                                 visibility: decl.visibility,
+                                attributes: decl.attributes,
                             };
 
                             // insert struct into namespace
@@ -545,11 +674,46 @@ impl TypedAstNode {
 
                             TypedDeclaration::StructDeclaration(decl)
                         }
+                        Declaration::TypeAliasDeclaration(TypeAliasDeclaration {
+                            name,
+                            type_parameters,
+                            ty,
+                            type_span,
+                            visibility,
+                        }) => {
+                            // the aliased type is resolved here, with the type parameters in
+                            // scope, so that it means the same wherever the alias is imported
+                            let alias_namespace = create_new_scope(namespace);
+                            type_parameters.iter().for_each(|param| {
+                                alias_namespace.insert(param.name_ident.clone(), param.into());
+                            });
+                            let ty = alias_namespace
+                                .resolve_type_with_self(ty, self_type)
+                                .unwrap_or_else(|_| {
+                                    errors.push(CompileError::UnknownType { span: type_span });
+                                    insert_type(TypeInfo::ErrorRecovery)
+                                });
+                            let decl =
+                                TypedDeclaration::TypeAliasDeclaration(TypedTypeAliasDeclaration {
+                                    name: name.clone(),
+                                    type_parameters,
+                                    ty,
+                                    visibility,
+                                });
+                            let _ = check!(
+                                namespace.insert(name, decl.clone()),
+                                return err(warnings, errors),
+                                warnings,
+                                errors
+                            );
+                            decl
+                        }
                         Declaration::AbiDeclaration(AbiDeclaration {
                             name,
                             interface_surface,
                             methods,
                             span,
+                            attributes,
                         }) => {
                             // type check the interface surface and methods
                             // We don't want the user to waste resources by contract calling
@@ -584,16 +748,56 @@ impl TypedAstNode {
                                 methods,
                                 name: name.clone(),
                                 span,
+                                attributes,
                             });
                             namespace.insert(name, decl.clone());
                             decl
                         }
-                        Declaration::StorageDeclaration(StorageDeclaration { span, .. }) => {
-                            errors.push(CompileError::Unimplemented(
-                                "Storage declarations are not supported yet. Coming soon!",
-                                span,
-                            ));
-                            return err(warnings, errors);
+                        Declaration::StorageDeclaration(StorageDeclaration { span, fields }) => {
+                            let mut fields_buf = Vec::with_capacity(fields.len());
+                            for StorageField {
+                                name,
+                                r#type,
+                                initializer,
+                            } in fields
+                            {
+                                let r#type = namespace
+                                    .resolve_type_with_self(r#type, self_type)
+                                    .unwrap_or_else(|_| {
+                                        errors.push(CompileError::UnknownType {
+                                            span: name.span().clone(),
+                                        });
+                                        insert_type(TypeInfo::ErrorRecovery)
+                                    });
+                                let initializer = check!(
+                                    TypedExpression::type_check(TypeCheckArguments {
+                                        checkee: initializer,
+                                        namespace,
+                                        crate_namespace,
+                                        return_type_annotation: r#type,
+                                        help_text: "Storage field's type annotation does not \
+                                                    match up with the initializer's type.",
+                                        self_type,
+                                        build_config,
+                                        dead_code_graph,
+                                        dependency_graph,
+                                        mode: Mode::NonAbi,
+                                        opts,
+                                    }),
+                                    error_recovery_expr(name.span().clone()),
+                                    warnings,
+                                    errors
+                                );
+                                fields_buf.push(TypedStorageField::new(name, r#type, initializer));
+                            }
+                            let decl = TypedStorageDeclaration::new(fields_buf, span);
+                            check!(
+                                namespace.set_storage_declaration(decl.clone()),
+                                return err(warnings, errors),
+                                warnings,
+                                errors
+                            );
+                            TypedDeclaration::StorageDeclaration(decl)
                         }
                     })
                 }
@@ -698,7 +902,10 @@ impl TypedAstNode {
                             dead_code_graph,
                             dependency_graph,
                             mode: Mode::NonAbi,
-                            opts,
+                            opts: TCOpts {
+                                in_loop: true,
+                                ..opts
+                            },
                         }),
                         (
                             TypedCodeBlock {
@@ -715,20 +922,57 @@ impl TypedAstNode {
                         body: typed_body,
                     })
                 }
+                AstNodeContent::ForLoop(for_loop) => TypedAstNodeContent::ForLoop(check!(
+                    TypedForLoop::type_check(TypeCheckArguments {
+                        checkee: for_loop,
+                        namespace,
+                        crate_namespace,
+                        return_type_annotation,
+                        help_text,
+                        self_type,
+                        build_config,
+                        dead_code_graph,
+                        dependency_graph,
+                        mode: Mode::NonAbi,
+                        opts,
+                    }),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                )),
+                AstNodeContent::Break => {
+                    if !opts.in_loop {
+                        errors.push(CompileError::BreakOutsideLoop {
+                            span: node.span.clone(),
+                        });
+                    }
+                    TypedAstNodeContent::Break
+                }
+                AstNodeContent::Continue => {
+                    if !opts.in_loop {
+                        errors.push(CompileError::ContinueOutsideLoop {
+                            span: node.span.clone(),
+                        });
+                    }
+                    TypedAstNodeContent::Continue
+                }
             },
             span: node.span.clone(),
         };
 
         if let TypedAstNode {
-            content: TypedAstNodeContent::Expression(TypedExpression { .. }),
+            content: TypedAstNodeContent::Expression(ref expr),
             ..
         } = node
         {
             let warning = Warning::UnusedReturnValue {
                 r#type: node.type_info(),
             };
+            // an expression which always reverts has no value to ignore
             assert_or_warn!(
-                node.type_info().is_unit() || node.type_info() == TypeInfo::ErrorRecovery,
+                node.type_info().is_unit()
+                    || node.type_info() == TypeInfo::ErrorRecovery
+                    || expr.diverges(),
                 warnings,
                 node.span.clone(),
                 warning
@@ -834,7 +1078,7 @@ fn reassignment(
     let mut errors = vec![];
     let mut warnings = vec![];
     // ensure that the lhs is a variable expression or struct field access
-    match *lhs {
+    match resolve_storage_access(*lhs, namespace) {
         Expression::VariableExpression { name, span } => {
             // check that the reassigned name exists
             let thing_to_reassign = match namespace.clone().get_symbol(&name).value {
@@ -1003,6 +1247,46 @@ fn reassignment(
                 errors,
             )
         }
+        Expression::StorageAccess { field_name, span } => {
+            if opts.purity == Purity::Pure {
+                errors.push(CompileError::PureAccessedStorage { span: span.clone() });
+            }
+            let (ix, ty_of_field) = check!(
+                namespace.get_storage_field(&field_name, &span),
+                return err(warnings, errors),
+                warnings,
+                errors
+            );
+            // type check the reassignment
+            let rhs = check!(
+                TypedExpression::type_check(TypeCheckArguments {
+                    checkee: rhs,
+                    namespace,
+                    crate_namespace,
+                    return_type_annotation: ty_of_field,
+                    help_text: "You can only assign a value of the same type to a storage field.",
+                    self_type,
+                    build_config,
+                    dead_code_graph,
+                    dependency_graph,
+                    mode: Mode::NonAbi,
+                    opts,
+                }),
+                error_recovery_expr(span),
+                warnings,
+                errors
+            );
+
+            ok(
+                TypedDeclaration::StorageReassignment(TypedStorageReassignment {
+                    field_name,
+                    ix,
+                    rhs,
+                }),
+                warnings,
+                errors,
+            )
+        }
         _ => {
             errors.push(CompileError::InvalidExpressionOnLhs { span });
             err(warnings, errors)
@@ -1020,12 +1304,16 @@ fn type_check_interface_surface(
             .into_iter()
             .map(
                 |TraitFn {
+                     purity,
                      name,
                      parameters,
                      return_type,
                      return_type_span,
+                     attributes,
                  }| TypedTraitFn {
+                    purity,
                     name,
+                    attributes,
                     return_type_span: return_type_span.clone(),
                     parameters: parameters
                         .into_iter()
@@ -1034,8 +1322,10 @@ fn type_check_interface_surface(
                                  name,
                                  r#type,
                                  type_span,
+                                 is_reference,
                              }| TypedFunctionParameter {
                                 name,
+                                is_reference,
                                 r#type: namespace
                                     .resolve_type_with_self(
                                         r#type,
@@ -1091,13 +1381,17 @@ fn type_check_trait_methods(
         type_parameters,
         return_type_span,
         purity,
+        attributes,
         ..
     } in methods
     {
         let function_namespace = namespace;
         parameters.clone().into_iter().for_each(
             |FunctionParameter {
-                 name, ref r#type, ..
+                 name,
+                 ref r#type,
+                 is_reference,
+                 ..
              }| {
                 let r#type = function_namespace
                     .resolve_type_with_self(
@@ -1120,8 +1414,8 @@ fn type_check_trait_methods(
                             is_constant: IsConstant::No,
                             span: name.span().clone(),
                         },
-                        // TODO allow mutable function params?
-                        is_mutable: VariableMutability::Immutable,
+                        // a `ref mut` parameter is the caller's mutable variable
+                        is_mutable: is_reference.into(),
                         type_ascription: r#type,
                     }),
                 );
@@ -1131,7 +1425,7 @@ fn type_check_trait_methods(
         // the type scope
         let mut generic_params_buf_for_error_message = Vec::new();
         for param in parameters.iter() {
-            if let TypeInfo::Custom { ref name } = param.r#type {
+            if let TypeInfo::Custom { ref name, .. } = param.r#type {
                 generic_params_buf_for_error_message.push(name.to_string());
             }
         }
@@ -1150,7 +1444,10 @@ fn type_check_trait_methods(
                     |TypeParameter {
                          name: this_name, ..
                      }| {
-                        if let TypeInfo::Custom { name: this_name } = this_name {
+                        if let TypeInfo::Custom {
+                            name: this_name, ..
+                        } = this_name
+                        {
                             this_name == name
                         } else {
                             false
@@ -1174,9 +1471,11 @@ fn type_check_trait_methods(
                      name,
                      r#type,
                      type_span,
+                     is_reference,
                  }| {
                     TypedFunctionParameter {
                         name,
+                        is_reference,
                         r#type: function_namespace
                             .resolve_type_with_self(
                                 r#type,
@@ -1216,7 +1515,10 @@ fn type_check_trait_methods(
                 dead_code_graph,
                 dependency_graph,
                 mode: Mode::NonAbi,
-                opts: TCOpts { purity }
+                opts: TCOpts {
+                    purity,
+                    ..Default::default()
+                }
             }),
             continue,
             warnings,
@@ -1236,6 +1538,7 @@ fn type_check_trait_methods(
             return_type_span,
             is_contract_call: false,
             purity,
+            attributes,
         });
     }
     ok(methods_buf, warnings, errors)
@@ -1250,6 +1553,7 @@ fn error_recovery_function_declaration(decl: FunctionDeclaration) -> TypedFuncti
         span,
         return_type_span,
         visibility,
+        attributes,
         ..
     } = decl;
     TypedFunctionDeclaration {
@@ -1266,5 +1570,6 @@ fn error_recovery_function_declaration(decl: FunctionDeclaration) -> TypedFuncti
         visibility,
         return_type: crate::type_engine::insert_type(return_type),
         type_parameters: Default::default(),
+        attributes,
     }
 }
@@ -1,6 +1,8 @@
 use crate::{
-    error::*, type_engine::*, CallPath, CompileResult, Ident, TypeInfo, TypedDeclaration,
-    TypedFunctionDeclaration,
+    error::*,
+    semantic_analysis::ast_node::{TypedStorageDeclaration, VariableMutability},
+    type_engine::*,
+    CallPath, CompileResult, Ident, TypeInfo, TypedDeclaration, TypedFunctionDeclaration,
 };
 
 use sway_types::span::Span;
@@ -12,6 +14,10 @@ pub use arena::*;
 
 type ModuleName = String;
 type TraitName = CallPath;
+/// An implementation is identified by the trait, the types given for the trait's type parameters
+/// and the type it's for, so that e.g. `impl From<u64> for X` and `impl From<b256> for X` don't
+/// collide.
+type TraitImplKey = (TraitName, Vec<TypeInfo>, TypeInfo);
 /// A namespace represents all items that exist either via declaration or importing.
 #[derive(Clone, Debug, Default)]
 pub struct Namespace {
@@ -19,7 +25,7 @@ pub struct Namespace {
     // [Namespace::get_all_declared_symbols] -- we need that iterator to have a deterministic
     // order.
     symbols: BTreeMap<Ident, TypedDeclaration>,
-    implemented_traits: HashMap<(TraitName, TypeInfo), Vec<TypedFunctionDeclaration>>,
+    implemented_traits: HashMap<TraitImplKey, Vec<TypedFunctionDeclaration>>,
     // Any other modules within this scope, where a module is a namespace associated with an identifier.
     // This is a BTreeMap because we rely on its ordering being consistent. See
     // [Namespace::get_all_imported_modules] -- we need that iterator to have a deterministic
@@ -28,6 +34,8 @@ pub struct Namespace {
     use_synonyms: HashMap<Ident, Vec<Ident>>,
     // Represents an alternative name for a symbol.
     use_aliases: HashMap<String, Ident>,
+    // The contract storage declaration, if one has been declared.
+    declared_storage: Option<TypedStorageDeclaration>,
 }
 
 impl Namespace {
@@ -66,9 +74,20 @@ impl Namespace {
         ok((), warnings, errors)
     }
 
+    /// Makes every variable in this namespace immutable, so that the body of a closure can read
+    /// the variables it captures but cannot reassign them.
+    pub(crate) fn make_variables_immutable(&mut self) {
+        for decl in self.symbols.values_mut() {
+            if let TypedDeclaration::VariableDeclaration(decl) = decl {
+                decl.is_mutable = VariableMutability::Immutable;
+            }
+        }
+    }
+
     pub(crate) fn insert_trait_implementation(
         &mut self,
         trait_name: CallPath,
+        trait_type_arguments: Vec<TypeInfo>,
         type_implementing_for: TypeInfo,
         functions_buf: Vec<TypedFunctionDeclaration>,
     ) -> CompileResult<()> {
@@ -88,7 +107,14 @@ impl Namespace {
         };
         if self
             .implemented_traits
-            .insert((trait_name.clone(), type_implementing_for), functions_buf)
+            .insert(
+                (
+                    trait_name.clone(),
+                    trait_type_arguments,
+                    type_implementing_for,
+                ),
+                functions_buf,
+            )
             .is_some()
         {
             warnings.push(CompileWarning {
@@ -99,6 +125,38 @@ impl Namespace {
         ok((), warnings, errors)
     }
 
+    pub(crate) fn set_storage_declaration(
+        &mut self,
+        decl: TypedStorageDeclaration,
+    ) -> CompileResult<()> {
+        if self.declared_storage.is_some() {
+            return err(
+                vec![],
+                vec![CompileError::MultipleStorageDeclarations { span: decl.span }],
+            );
+        }
+        self.declared_storage = Some(decl);
+        ok((), vec![], vec![])
+    }
+
+    /// Look up a field of the declared contract storage, returning its index within the
+    /// declaration and its type.
+    pub(crate) fn get_storage_field(
+        &self,
+        field_name: &Ident,
+        access_span: &Span,
+    ) -> CompileResult<(usize, TypeId)> {
+        match self.declared_storage {
+            Some(ref storage) => storage.find_field(field_name),
+            None => err(
+                vec![],
+                vec![CompileError::NoDeclaredStorage {
+                    span: access_span.clone(),
+                }],
+            ),
+        }
+    }
+
     pub fn insert_module(&mut self, module_name: String, ix: NamespaceRef) {
         self.modules.insert(module_name, ix);
     }
@@ -110,7 +168,8 @@ impl Namespace {
     pub(crate) fn get_methods_for_type(&self, r#type: TypeId) -> Vec<TypedFunctionDeclaration> {
         let mut methods = vec![];
         let r#type = crate::type_engine::look_up_type_id(r#type);
-        for ((_trait_name, type_info), l_methods) in &self.implemented_traits {
+        for ((_trait_name, _trait_type_arguments, type_info), l_methods) in &self.implemented_traits
+        {
             if *type_info == r#type {
                 methods.append(&mut l_methods.clone());
             }
@@ -118,6 +177,45 @@ impl Namespace {
         methods
     }
 
+    /// The methods of the implementations of the trait named `trait_name` for `r#type`.
+    pub(crate) fn get_trait_methods_for_type(
+        &self,
+        r#type: TypeId,
+        trait_name: &Ident,
+    ) -> Vec<TypedFunctionDeclaration> {
+        let r#type = crate::type_engine::look_up_type_id(r#type);
+        self.implemented_traits
+            .iter()
+            .filter(|((name, _, type_info), _)| name.suffix == *trait_name && *type_info == r#type)
+            .flat_map(|(_, methods)| methods.iter().cloned())
+            .collect()
+    }
+
+    /// The name of the trait whose implementation for `r#type` has `method`.
+    pub(crate) fn find_trait_of_method(
+        &self,
+        r#type: TypeId,
+        method: &TypedFunctionDeclaration,
+    ) -> Option<Ident> {
+        let r#type = crate::type_engine::look_up_type_id(r#type);
+        self.implemented_traits
+            .iter()
+            .find(|((_, _, type_info), methods)| {
+                *type_info == r#type
+                    && methods
+                        .iter()
+                        .any(|m| m.name == method.name && m.span == method.span)
+            })
+            .map(|((name, _, _), _)| name.suffix.clone())
+    }
+
+    pub(crate) fn implements_trait(&self, trait_name: &Ident, r#type: TypeId) -> bool {
+        let r#type = crate::type_engine::look_up_type_id(r#type);
+        self.implemented_traits
+            .keys()
+            .any(|(name, _, type_info)| name.suffix == *trait_name && *type_info == r#type)
+    }
+
     pub(crate) fn get_tuple_elems(
         &self,
         ty: TypeId,
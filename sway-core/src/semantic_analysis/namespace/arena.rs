@@ -25,6 +25,7 @@ pub trait NamespaceWrapper {
     fn insert_trait_implementation(
         &self,
         trait_name: CallPath,
+        trait_type_arguments: Vec<TypeInfo>,
         type_implementing_for: TypeInfo,
         functions_buf: Vec<TypedFunctionDeclaration>,
     ) -> CompileResult<()>;
@@ -56,6 +57,24 @@ pub trait NamespaceWrapper {
     fn star_import(&self, from_module: Option<NamespaceRef>, path: Vec<Ident>)
         -> CompileResult<()>;
     fn get_methods_for_type(&self, r#type: TypeId) -> Vec<TypedFunctionDeclaration>;
+    /// Find the method of the implementation of the trait named `trait_name` for `r#type`, which
+    /// `args_buf` can be passed to.
+    fn find_trait_method_for_type(
+        &self,
+        r#type: TypeId,
+        trait_name: &Ident,
+        method_name: &Ident,
+        self_type: TypeId,
+        args_buf: &VecDeque<TypedExpression>,
+    ) -> CompileResult<TypedFunctionDeclaration>;
+    /// The name of the trait whose implementation for `r#type` has `method`, if any.
+    fn find_trait_of_method(
+        &self,
+        r#type: TypeId,
+        method: &TypedFunctionDeclaration,
+    ) -> Option<Ident>;
+    /// Whether the trait named `trait_name` has been implemented for `r#type` in this scope.
+    fn implements_trait(&self, trait_name: &Ident, r#type: TypeId) -> bool;
     fn get_name_from_path(&self, path: &[Ident], name: &Ident) -> CompileResult<TypedDeclaration>;
     /// Used for calls that look like this:
     /// `foo::bar::function`
@@ -84,6 +103,14 @@ pub trait NamespaceWrapper {
     /// Returns a tuple where the first element is the [ResolvedType] of the actual expression,
     /// and the second is the [ResolvedType] of its parent, for control-flow analysis.
     fn find_subfield_type(&self, subfield_exp: &[Ident]) -> CompileResult<(TypeId, TypeId)>;
+    fn set_storage_declaration(&self, decl: TypedStorageDeclaration) -> CompileResult<()>;
+    /// Given the name of a storage field, find its index within the storage declaration and its
+    /// type. Errors if no storage has been declared or the field does not exist.
+    fn get_storage_field(
+        &self,
+        field_name: &Ident,
+        access_span: &Span,
+    ) -> CompileResult<(usize, TypeId)>;
 }
 
 impl NamespaceWrapper for NamespaceRef {
@@ -267,6 +294,39 @@ impl NamespaceWrapper for NamespaceRef {
     fn get_methods_for_type(&self, r#type: TypeId) -> Vec<TypedFunctionDeclaration> {
         read_module(|ns| ns.get_methods_for_type(r#type), *self)
     }
+    fn implements_trait(&self, trait_name: &Ident, r#type: TypeId) -> bool {
+        read_module(|ns| ns.implements_trait(trait_name, r#type), *self)
+    }
+    fn find_trait_method_for_type(
+        &self,
+        r#type: TypeId,
+        trait_name: &Ident,
+        method_name: &Ident,
+        self_type: TypeId,
+        args_buf: &VecDeque<TypedExpression>,
+    ) -> CompileResult<TypedFunctionDeclaration> {
+        let mut errors = vec![];
+        let r#type = self
+            .resolve_type_with_self(look_up_type_id(r#type), self_type)
+            .unwrap_or_else(|_| {
+                errors.push(CompileError::UnknownType {
+                    span: method_name.span().clone(),
+                });
+                insert_type(TypeInfo::ErrorRecovery)
+            });
+        let methods = read_module(
+            |ns| ns.get_trait_methods_for_type(r#type, trait_name),
+            *self,
+        );
+        select_method(methods, r#type, method_name, args_buf, vec![], errors)
+    }
+    fn find_trait_of_method(
+        &self,
+        r#type: TypeId,
+        method: &TypedFunctionDeclaration,
+    ) -> Option<Ident> {
+        read_module(|ns| ns.find_trait_of_method(r#type, method), *self)
+    }
     fn star_import(
         &self,
         from_module: Option<NamespaceRef>,
@@ -348,32 +408,19 @@ impl NamespaceWrapper for NamespaceRef {
                 insert_type(TypeInfo::ErrorRecovery)
             });
         let local_methods = self.get_methods_for_type(r#type);
-        let mut ns_methods = read_module(
+        let ns_methods = read_module(
             |namespace| namespace.get_methods_for_type(r#type),
             namespace,
         );
 
-        let mut methods = local_methods;
-        methods.append(&mut ns_methods);
-
-        match methods
-            .into_iter()
-            .find(|TypedFunctionDeclaration { name, .. }| name == method_name)
-        {
-            Some(o) => ok(o, warnings, errors),
-            None => {
-                if args_buf.get(0).map(|x| look_up_type_id(x.return_type))
-                    != Some(TypeInfo::ErrorRecovery)
-                {
-                    errors.push(CompileError::MethodNotFound {
-                        method_name: method_name.as_str().to_string(),
-                        type_name: r#type.friendly_type_str(),
-                        span: method_name.span().clone(),
-                    });
-                }
-                err(warnings, errors)
-            }
-        }
+        select_method(
+            local_methods.into_iter().chain(ns_methods).collect(),
+            r#type,
+            method_name,
+            args_buf,
+            warnings,
+            errors,
+        )
     }
     fn find_module_relative(&self, path: &[Ident]) -> CompileResult<NamespaceRef> {
         let mut errors = vec![];
@@ -455,8 +502,7 @@ impl NamespaceWrapper for NamespaceRef {
                     });
                 }
                 // if this is a const, insert it into the local namespace directly
-                if let TypedDeclaration::VariableDeclaration(TypedVariableDeclaration {
-                    is_mutable: VariableMutability::ExportedConst,
+                if let TypedDeclaration::ConstantDeclaration(TypedConstantDeclaration {
                     ref name,
                     ..
                 }) = decl
@@ -471,7 +517,7 @@ impl NamespaceWrapper for NamespaceRef {
                         namespace
                             .implemented_traits
                             .iter()
-                            .filter(|((_trait_name, type_info), _impl)| {
+                            .filter(|((_trait_name, _trait_type_arguments, type_info), _impl)| {
                                 a.map(look_up_type_id).as_ref() == Some(type_info)
                             })
                             .fold(Vec::new(), |mut acc, (a, b)| {
@@ -522,12 +568,18 @@ impl NamespaceWrapper for NamespaceRef {
     fn insert_trait_implementation(
         &self,
         trait_name: CallPath,
+        trait_type_arguments: Vec<TypeInfo>,
         type_implementing_for: TypeInfo,
         functions_buf: Vec<TypedFunctionDeclaration>,
     ) -> CompileResult<()> {
         write_module(
             move |ns| {
-                ns.insert_trait_implementation(trait_name, type_implementing_for, functions_buf)
+                ns.insert_trait_implementation(
+                    trait_name,
+                    trait_type_arguments,
+                    type_implementing_for,
+                    functions_buf,
+                )
             },
             *self,
         )
@@ -543,42 +595,83 @@ impl NamespaceWrapper for NamespaceRef {
     fn insert(&self, name: Ident, item: TypedDeclaration) -> CompileResult<()> {
         write_module(|ns| ns.insert(name, item), *self)
     }
+    fn set_storage_declaration(&self, decl: TypedStorageDeclaration) -> CompileResult<()> {
+        write_module(|ns| ns.set_storage_declaration(decl), *self)
+    }
+    fn get_storage_field(
+        &self,
+        field_name: &Ident,
+        access_span: &Span,
+    ) -> CompileResult<(usize, TypeId)> {
+        read_module(|ns| ns.get_storage_field(field_name, access_span), *self)
+    }
     fn resolve_type_with_self(&self, ty: TypeInfo, self_type: TypeId) -> Result<TypeId, ()> {
         let mut warnings = vec![];
         let mut errors = vec![];
         Ok(match ty {
-            TypeInfo::Custom { ref name } => {
-                match self.get_symbol(name).ok(&mut warnings, &mut errors) {
-                    Some(TypedDeclaration::StructDeclaration(TypedStructDeclaration {
-                        name,
-                        fields,
-                        ..
-                    })) => crate::type_engine::insert_type(TypeInfo::Struct {
-                        name: name.as_str().to_string(),
-                        fields: fields
-                            .iter()
-                            .map(TypedStructField::as_owned_typed_struct_field)
-                            .collect::<Vec<_>>(),
-                    }),
-                    Some(TypedDeclaration::EnumDeclaration(TypedEnumDeclaration {
-                        name,
-                        variants,
-                        ..
-                    })) => crate::type_engine::insert_type(TypeInfo::Enum {
-                        name: name.as_str().to_string(),
-                        variant_types: variants
-                            .iter()
-                            .map(TypedEnumVariant::as_owned_typed_enum_variant)
-                            .collect(),
-                    }),
-                    Some(TypedDeclaration::GenericTypeForFunctionScope { name, .. }) => {
-                        crate::type_engine::insert_type(TypeInfo::UnknownGeneric { name })
-                    }
-                    _ => return Err(()),
+            TypeInfo::Custom {
+                ref name,
+                ref type_arguments,
+            } => match self.get_symbol(name).ok(&mut warnings, &mut errors) {
+                Some(TypedDeclaration::StructDeclaration(TypedStructDeclaration {
+                    name,
+                    fields,
+                    ..
+                })) => crate::type_engine::insert_type(TypeInfo::Struct {
+                    name: name.as_str().to_string(),
+                    fields: fields
+                        .iter()
+                        .map(TypedStructField::as_owned_typed_struct_field)
+                        .collect::<Vec<_>>(),
+                }),
+                Some(TypedDeclaration::EnumDeclaration(TypedEnumDeclaration {
+                    name,
+                    variants,
+                    ..
+                })) => crate::type_engine::insert_type(TypeInfo::Enum {
+                    name: name.as_str().to_string(),
+                    variant_types: variants
+                        .iter()
+                        .map(TypedEnumVariant::as_owned_typed_enum_variant)
+                        .collect(),
+                }),
+                Some(TypedDeclaration::GenericTypeForFunctionScope { name, .. }) => {
+                    crate::type_engine::insert_type(TypeInfo::UnknownGeneric { name })
                 }
-            }
+                Some(TypedDeclaration::TypeAliasDeclaration(decl)) => {
+                    let type_arguments = type_arguments
+                        .iter()
+                        .map(|arg| self.resolve_type_with_self(look_up_type_id(*arg), self_type))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    decl.instantiate(type_arguments).ok_or(())?
+                }
+                _ => return Err(()),
+            },
             TypeInfo::SelfType => self_type,
             TypeInfo::Ref(id) => id,
+            TypeInfo::Tuple(elem_tys) => insert_type(TypeInfo::Tuple(
+                elem_tys
+                    .into_iter()
+                    .map(|elem_ty| resolve_elem_type_with_self(*self, elem_ty, self_type))
+                    .collect::<Result<_, _>>()?,
+            )),
+            TypeInfo::Array(elem_ty, count) => insert_type(TypeInfo::Array(
+                resolve_elem_type_with_self(*self, elem_ty, self_type)?,
+                count,
+            )),
+            TypeInfo::Vector(elem_ty) => insert_type(TypeInfo::Vector(
+                resolve_elem_type_with_self(*self, elem_ty, self_type)?,
+            )),
+            TypeInfo::Function {
+                parameters,
+                return_type,
+            } => insert_type(TypeInfo::Function {
+                parameters: parameters
+                    .into_iter()
+                    .map(|param_ty| resolve_elem_type_with_self(*self, param_ty, self_type))
+                    .collect::<Result<_, _>>()?,
+                return_type: resolve_elem_type_with_self(*self, return_type, self_type)?,
+            }),
             o => insert_type(o),
         })
     }
@@ -587,39 +680,103 @@ impl NamespaceWrapper for NamespaceRef {
         let mut warnings = vec![];
         let mut errors = vec![];
         match ty {
-            TypeInfo::Custom { name } => {
-                match self.get_symbol(&name).ok(&mut warnings, &mut errors) {
-                    Some(TypedDeclaration::StructDeclaration(TypedStructDeclaration {
-                        name,
-                        fields,
-                        ..
-                    })) => crate::type_engine::insert_type(TypeInfo::Struct {
-                        name: name.as_str().to_string(),
-                        fields: fields
-                            .iter()
-                            .map(TypedStructField::as_owned_typed_struct_field)
-                            .collect::<Vec<_>>(),
-                    }),
-                    Some(TypedDeclaration::EnumDeclaration(TypedEnumDeclaration {
-                        name,
-                        variants,
-                        ..
-                    })) => crate::type_engine::insert_type(TypeInfo::Enum {
-                        name: name.as_str().to_string(),
-                        variant_types: variants
+            TypeInfo::Custom {
+                name,
+                type_arguments,
+            } => match self.get_symbol(&name).ok(&mut warnings, &mut errors) {
+                Some(TypedDeclaration::StructDeclaration(TypedStructDeclaration {
+                    name,
+                    fields,
+                    ..
+                })) => crate::type_engine::insert_type(TypeInfo::Struct {
+                    name: name.as_str().to_string(),
+                    fields: fields
+                        .iter()
+                        .map(TypedStructField::as_owned_typed_struct_field)
+                        .collect::<Vec<_>>(),
+                }),
+                Some(TypedDeclaration::EnumDeclaration(TypedEnumDeclaration {
+                    name,
+                    variants,
+                    ..
+                })) => crate::type_engine::insert_type(TypeInfo::Enum {
+                    name: name.as_str().to_string(),
+                    variant_types: variants
+                        .iter()
+                        .map(TypedEnumVariant::as_owned_typed_enum_variant)
+                        .collect(),
+                }),
+                Some(TypedDeclaration::TypeAliasDeclaration(decl)) => decl
+                    .instantiate(
+                        type_arguments
                             .iter()
-                            .map(TypedEnumVariant::as_owned_typed_enum_variant)
+                            .map(|arg| self.resolve_type_without_self(&look_up_type_id(*arg)))
                             .collect(),
-                    }),
-                    _ => crate::type_engine::insert_type(TypeInfo::Unknown),
-                }
-            }
+                    )
+                    .unwrap_or_else(|| insert_type(TypeInfo::Unknown)),
+                _ => crate::type_engine::insert_type(TypeInfo::Unknown),
+            },
             TypeInfo::Ref(id) => id,
+            TypeInfo::Tuple(elem_tys) => insert_type(TypeInfo::Tuple(
+                elem_tys
+                    .into_iter()
+                    .map(|elem_ty| resolve_elem_type_without_self(*self, elem_ty))
+                    .collect(),
+            )),
+            TypeInfo::Array(elem_ty, count) => insert_type(TypeInfo::Array(
+                resolve_elem_type_without_self(*self, elem_ty),
+                count,
+            )),
+            TypeInfo::Vector(elem_ty) => insert_type(TypeInfo::Vector(
+                resolve_elem_type_without_self(*self, elem_ty),
+            )),
+            TypeInfo::Function {
+                parameters,
+                return_type,
+            } => insert_type(TypeInfo::Function {
+                parameters: parameters
+                    .into_iter()
+                    .map(|param_ty| resolve_elem_type_without_self(*self, param_ty))
+                    .collect(),
+                return_type: resolve_elem_type_without_self(*self, return_type),
+            }),
             o => insert_type(o),
         }
     }
 }
 
+/// Resolves the type of an element of a tuple, array or vector, or of a parameter or the return
+/// type of a function type. The element type is only replaced
+/// if it's one that needs resolving, as an unknown one must stay shared with the container's other
+/// uses to be inferred.
+fn resolve_elem_type_with_self(
+    namespace: NamespaceRef,
+    elem_ty: TypeId,
+    self_type: TypeId,
+) -> Result<TypeId, ()> {
+    match look_up_type_id(elem_ty) {
+        elem @ (TypeInfo::Custom { .. }
+        | TypeInfo::SelfType
+        | TypeInfo::Tuple(_)
+        | TypeInfo::Array(..)
+        | TypeInfo::Vector(_)
+        | TypeInfo::Function { .. }) => namespace.resolve_type_with_self(elem, self_type),
+        _ => Ok(elem_ty),
+    }
+}
+
+/// See [resolve_elem_type_with_self].
+fn resolve_elem_type_without_self(namespace: NamespaceRef, elem_ty: TypeId) -> TypeId {
+    match look_up_type_id(elem_ty) {
+        elem @ (TypeInfo::Custom { .. }
+        | TypeInfo::Tuple(_)
+        | TypeInfo::Array(..)
+        | TypeInfo::Vector(_)
+        | TypeInfo::Function { .. }) => namespace.resolve_type_without_self(&elem),
+        _ => elem_ty,
+    }
+}
+
 /// Create a new module ([Namespace]), insert it into the arena, and get its id back.
 pub fn create_module() -> NamespaceRef {
     let res = {
@@ -630,6 +787,76 @@ pub fn create_module() -> NamespaceRef {
 }
 
 /// Given a function `func` and a reference to a module `ix`, read from `MODULES[ix]` with `func`.
+/// Choose the method named `method_name` among `candidates`, the methods of `r#type`, which
+/// `args_buf` can be passed to.
+fn select_method(
+    candidates: Vec<TypedFunctionDeclaration>,
+    r#type: TypeId,
+    method_name: &Ident,
+    args_buf: &VecDeque<TypedExpression>,
+    warnings: Vec<CompileWarning>,
+    mut errors: Vec<CompileError>,
+) -> CompileResult<TypedFunctionDeclaration> {
+    // implementations of a trait with different type arguments may each have a method of this
+    // name, so the one which the arguments can be passed to is chosen.  The same method may be
+    // found both locally and in the namespace of the type, whereas a method provided by the
+    // trait is declared once but has a copy in each implementation.
+    let is_same_method = |a: &TypedFunctionDeclaration, b: &TypedFunctionDeclaration| {
+        a.span == b.span
+            && a.parameters.len() == b.parameters.len()
+            && a.parameters
+                .iter()
+                .zip(b.parameters.iter())
+                .all(|(a, b)| look_up_type_id(a.r#type) == look_up_type_id(b.r#type))
+    };
+    let mut methods: Vec<TypedFunctionDeclaration> = vec![];
+    for method in candidates {
+        if method.name == *method_name && !methods.iter().any(|m| is_same_method(m, &method)) {
+            methods.push(method);
+        }
+    }
+    let accepting_methods = methods
+        .iter()
+        .filter(|method| method.accepts_arguments(args_buf))
+        .collect::<Vec<_>>();
+    let has_error_recovery_args = args_buf
+        .iter()
+        .any(|arg| look_up_type_id(arg.return_type) == TypeInfo::ErrorRecovery);
+    match accepting_methods[..] {
+        [method] => ok(method.clone(), warnings, errors),
+        _ if has_error_recovery_args => err(warnings, errors),
+        [] if methods.is_empty() => {
+            errors.push(CompileError::MethodNotFound {
+                method_name: method_name.as_str().to_string(),
+                type_name: r#type.friendly_type_str(),
+                span: method_name.span().clone(),
+            });
+            err(warnings, errors)
+        }
+        [] => {
+            errors.push(CompileError::NoMethodAcceptsArguments {
+                method_name: method_name.as_str().to_string(),
+                type_name: r#type.friendly_type_str(),
+                argument_types: args_buf
+                    .iter()
+                    .map(|arg| arg.return_type.friendly_type_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                span: method_name.span().clone(),
+            });
+            err(warnings, errors)
+        }
+        _ => {
+            errors.push(CompileError::AmbiguousMethod {
+                method_name: method_name.as_str().to_string(),
+                type_name: r#type.friendly_type_str(),
+                span: method_name.span().clone(),
+            });
+            err(warnings, errors)
+        }
+    }
+}
+
 pub fn read_module<F, R>(mut func: F, ix: NamespaceRef) -> R
 where
     F: FnMut(&Namespace) -> R,
@@ -2,8 +2,12 @@ use std::collections::{HashMap, HashSet};
 use std::iter::FromIterator;
 
 use crate::{
-    error::*, parse_tree::Scrutinee, parse_tree::*, type_engine::IntegerBits, AstNode,
-    AstNodeContent, CodeBlock, Declaration, Expression, ReturnStatement, TypeInfo, WhileLoop,
+    error::*,
+    parse_tree::Scrutinee,
+    parse_tree::*,
+    type_engine::{look_up_type_id, IntegerBits},
+    AstNode, AstNodeContent, CodeBlock, Declaration, Expression, ReturnStatement, TypeInfo,
+    WhileLoop,
 };
 
 use sway_types::{ident::Ident, span::Span};
@@ -11,13 +15,41 @@ use sway_types::{ident::Ident, span::Span};
 // -------------------------------------------------------------------------------------------------
 /// Take a list of nodes and reorder them so that they may be semantically analysed without any
 /// dependencies breaking.
-
-pub(crate) fn order_ast_nodes_by_dependency(nodes: Vec<AstNode>) -> CompileResult<Vec<AstNode>> {
+///
+/// Recursive calls are errors, except that non-generic functions may call themselves directly if
+/// `allow_self_recursion` is set.
+
+pub(crate) fn order_ast_nodes_by_dependency(
+    nodes: Vec<AstNode>,
+    allow_self_recursion: bool,
+) -> CompileResult<Vec<AstNode>> {
     let decl_dependencies =
         DependencyMap::from_iter(nodes.iter().filter_map(Dependencies::gather_from_decl_node));
 
+    let self_recursive_fns: HashSet<&Ident> = if allow_self_recursion {
+        nodes
+            .iter()
+            .filter_map(|node| match &node.content {
+                // a function with parameters of function type has its calls bound to the
+                // function values they are passed, which can't be done for a recursive call
+                AstNodeContent::Declaration(Declaration::FunctionDeclaration(fn_decl))
+                    if fn_decl.type_parameters.is_empty()
+                        && !fn_decl
+                            .parameters
+                            .iter()
+                            .any(|param| matches!(param.r#type, TypeInfo::Function { .. })) =>
+                {
+                    Some(&fn_decl.name)
+                }
+                _otherwise => None,
+            })
+            .collect()
+    } else {
+        HashSet::new()
+    };
+
     // Check here for recursive calls now that we have a nice map of the dependencies to help us.
-    let mut errors = find_recursive_calls(&decl_dependencies);
+    let mut errors = find_recursive_calls(&decl_dependencies, &self_recursive_fns);
     if !errors.is_empty() {
         // Because we're pulling these errors out of a HashMap they'll probably be in a funny
         // order.  Here we'll sort them by span start.
@@ -42,20 +74,32 @@ pub(crate) fn order_ast_nodes_by_dependency(nodes: Vec<AstNode>) -> CompileResul
 // -------------------------------------------------------------------------------------------------
 // Recursion detection.
 
-fn find_recursive_calls(decl_dependencies: &DependencyMap) -> Vec<CompileError> {
+fn find_recursive_calls(
+    decl_dependencies: &DependencyMap,
+    self_recursive_fns: &HashSet<&Ident>,
+) -> Vec<CompileError> {
     decl_dependencies
         .iter()
-        .filter_map(|(dep_sym, _)| find_recursive_call(decl_dependencies, dep_sym))
+        .filter_map(|(dep_sym, _)| {
+            find_recursive_call(decl_dependencies, self_recursive_fns, dep_sym)
+        })
         .collect()
 }
 
 fn find_recursive_call(
     decl_dependencies: &DependencyMap,
+    self_recursive_fns: &HashSet<&Ident>,
     fn_sym: &DependentSymbol,
 ) -> Option<CompileError> {
     if let DependentSymbol::Fn(_, Some(fn_span)) = fn_sym {
         let mut chain = Vec::new();
-        find_recursive_call_chain(decl_dependencies, fn_sym, fn_span, &mut chain)
+        find_recursive_call_chain(
+            decl_dependencies,
+            self_recursive_fns,
+            fn_sym,
+            fn_span,
+            &mut chain,
+        )
     } else {
         None
     }
@@ -63,6 +107,7 @@ fn find_recursive_call(
 
 fn find_recursive_call_chain(
     decl_dependencies: &DependencyMap,
+    self_recursive_fns: &HashSet<&Ident>,
     fn_sym: &DependentSymbol,
     fn_span: &Span,
     chain: &mut Vec<Ident>,
@@ -84,9 +129,22 @@ fn find_recursive_call_chain(
         }
         decl_dependencies.get(fn_sym).and_then(|deps_set| {
             chain.push(fn_sym_ident.clone());
-            let result = deps_set.deps.iter().find_map(|dep_sym| {
-                find_recursive_call_chain(decl_dependencies, dep_sym, fn_span, chain)
-            });
+            let result = deps_set
+                .deps
+                .iter()
+                .filter(|dep_sym| {
+                    // Skip direct calls to itself if they're allowed.
+                    !(*dep_sym == fn_sym && self_recursive_fns.contains(fn_sym_ident))
+                })
+                .find_map(|dep_sym| {
+                    find_recursive_call_chain(
+                        decl_dependencies,
+                        self_recursive_fns,
+                        dep_sym,
+                        fn_span,
+                        chain,
+                    )
+                });
             chain.pop();
             result
         })
@@ -246,7 +304,9 @@ impl Dependencies {
                     deps.gather_from_typeinfo(&variant.r#type)
                 })
                 .gather_from_traits(type_parameters),
-            Declaration::Reassignment(decl) => self.gather_from_expr(&decl.rhs),
+            Declaration::Reassignment(decl) => {
+                self.gather_from_expr(&decl.lhs).gather_from_expr(&decl.rhs)
+            }
             Declaration::TraitDeclaration(TraitDeclaration {
                 interface_surface,
                 methods,
@@ -265,14 +325,22 @@ impl Dependencies {
                 .gather_from_traits(type_parameters),
             Declaration::ImplTrait(ImplTrait {
                 trait_name,
+                trait_type_arguments,
                 type_implementing_for,
                 type_arguments,
+                associated_types,
                 functions,
                 ..
             }) => self
                 .gather_from_call_path(trait_name, false, false)
+                .gather_from_iter(trait_type_arguments.iter(), |deps, (ty, _)| {
+                    deps.gather_from_typeinfo(ty)
+                })
                 .gather_from_typeinfo(type_implementing_for)
                 .gather_from_traits(type_arguments)
+                .gather_from_iter(associated_types.iter(), |deps, alias| {
+                    deps.gather_from_typeinfo(&alias.ty)
+                })
                 .gather_from_iter(functions.iter(), |deps, fn_decl| {
                     deps.gather_from_fn_decl(fn_decl)
                 }),
@@ -301,6 +369,9 @@ impl Dependencies {
                 .gather_from_iter(methods.iter(), |deps, fn_decl| {
                     deps.gather_from_fn_decl(fn_decl)
                 }),
+            Declaration::TypeAliasDeclaration(TypeAliasDeclaration { ty, .. }) => {
+                self.gather_from_typeinfo(ty)
+            }
             Declaration::StorageDeclaration(StorageDeclaration { fields, .. }) => self
                 .gather_from_iter(
                     fields.iter(),
@@ -335,7 +406,10 @@ impl Dependencies {
 
     fn gather_from_expr(mut self, expr: &Expression) -> Self {
         match expr {
-            Expression::VariableExpression { .. } => self,
+            // a variable may name a function which is passed as a value
+            Expression::VariableExpression { name, .. } => {
+                self.gather_from_call_path(&name.clone().into(), false, true)
+            }
             Expression::FunctionApplication {
                 name, arguments, ..
             } => self
@@ -374,7 +448,8 @@ impl Dependencies {
                     deps.gather_from_expr(&field.value)
                 })
             }
-            Expression::SubfieldExpression { prefix, .. } => self.gather_from_expr(prefix),
+            Expression::SubfieldExpression { prefix, .. }
+            | Expression::TupleIndex { prefix, .. } => self.gather_from_expr(prefix),
             Expression::DelineatedPath { call_path, .. } => {
                 // It's either a module path which we can ignore, or an enum variant path, in which
                 // case we're interested in the enum name, ignoring the variant name.
@@ -413,6 +488,22 @@ impl Dependencies {
                 self.gather_from_iter(fields.iter(), |deps, field| deps.gather_from_expr(field))
             }
             Expression::DelayedMatchTypeResolution { .. } => self,
+            Expression::StorageAccess { .. } => {
+                self.deps
+                    .insert(DependentSymbol::Symbol(STORAGE_SYMBOL.to_string()));
+                self
+            }
+            Expression::Closure {
+                parameters,
+                return_type,
+                body,
+                ..
+            } => self
+                .gather_from_iter(parameters.iter(), |deps, param| {
+                    deps.gather_from_typeinfo(&param.r#type)
+                })
+                .gather_from_typeinfo(return_type)
+                .gather_from_expr(body),
         }
     }
 
@@ -444,10 +535,24 @@ impl Dependencies {
             AstNodeContent::WhileLoop(WhileLoop { condition, body }) => {
                 self.gather_from_expr(condition).gather_from_block(body)
             }
+            AstNodeContent::ForLoop(ForLoop {
+                iterable: ForLoopIterable::Array(array),
+                body,
+                ..
+            }) => self.gather_from_expr(array).gather_from_block(body),
+            AstNodeContent::ForLoop(ForLoop {
+                iterable: ForLoopIterable::Range { start, end },
+                body,
+                ..
+            }) => self
+                .gather_from_expr(start)
+                .gather_from_expr(end)
+                .gather_from_block(body),
 
             // No deps from these guys.
             AstNodeContent::UseStatement(_) => self,
             AstNodeContent::IncludeStatement(_) => self,
+            AstNodeContent::Break | AstNodeContent::Continue => self,
         }
     }
 
@@ -486,10 +591,33 @@ impl Dependencies {
     }
 
     fn gather_from_typeinfo(mut self, type_info: &TypeInfo) -> Self {
-        if let TypeInfo::Custom { name } = type_info {
-            self.deps.insert(DependentSymbol::Symbol(name.to_string()));
+        match type_info {
+            TypeInfo::Custom {
+                name,
+                type_arguments,
+            } => {
+                self.deps.insert(DependentSymbol::Symbol(name.to_string()));
+                self.gather_from_iter(type_arguments.iter(), |deps, type_argument| {
+                    deps.gather_from_typeinfo(&look_up_type_id(*type_argument))
+                })
+            }
+            TypeInfo::Tuple(elem_types) => self
+                .gather_from_iter(elem_types.iter(), |deps, elem| {
+                    deps.gather_from_typeinfo(&look_up_type_id(*elem))
+                }),
+            TypeInfo::Array(elem_type, _) | TypeInfo::Vector(elem_type) => {
+                self.gather_from_typeinfo(&look_up_type_id(*elem_type))
+            }
+            TypeInfo::Function {
+                parameters,
+                return_type,
+            } => self
+                .gather_from_iter(parameters.iter(), |deps, param| {
+                    deps.gather_from_typeinfo(&look_up_type_id(*param))
+                })
+                .gather_from_typeinfo(&look_up_type_id(*return_type)),
+            _ => self,
         }
-        self
     }
 
     fn gather_from_iter<I: Iterator, F: FnMut(Self, I::Item) -> Self>(self, iter: I, f: F) -> Self {
@@ -543,6 +671,10 @@ impl Hash for DependentSymbol {
     }
 }
 
+// The storage declaration has no name of its own; accesses of `storage.<field>` refer to it by the
+// keyword.
+const STORAGE_SYMBOL: &str = "storage";
+
 fn decl_name(decl: &Declaration) -> Option<DependentSymbol> {
     let dep_sym = |name| Some(DependentSymbol::Symbol(name));
     let impl_sym = |trait_name, type_info: &TypeInfo| {
@@ -560,6 +692,7 @@ fn decl_name(decl: &Declaration) -> Option<DependentSymbol> {
         Declaration::EnumDeclaration(decl) => dep_sym(decl.name.as_str().to_string()),
         Declaration::TraitDeclaration(decl) => dep_sym(decl.name.as_str().to_string()),
         Declaration::AbiDeclaration(decl) => dep_sym(decl.name.as_str().to_string()),
+        Declaration::TypeAliasDeclaration(decl) => dep_sym(decl.name.as_str().to_string()),
 
         // These have the added complexity of converting CallPath and/or TypeInfo into a name.
         Declaration::ImplSelf(decl) => {
@@ -577,8 +710,8 @@ fn decl_name(decl: &Declaration) -> Option<DependentSymbol> {
         // These don't have declaration dependencies.
         Declaration::VariableDeclaration(_) => None,
         Declaration::Reassignment(_) => None,
-        // Storage cannot be depended upon or exported
-        Declaration::StorageDeclaration(_) => None,
+        // Storage can't be exported, but storage accesses depend on it.
+        Declaration::StorageDeclaration(_) => dep_sym(STORAGE_SYMBOL.to_string()),
     }
 }
 
@@ -592,9 +725,19 @@ fn type_info_name(type_info: &TypeInfo) -> String {
             IntegerBits::Sixteen => "uint16",
             IntegerBits::ThirtyTwo => "uint32",
             IntegerBits::SixtyFour => "uint64",
+            IntegerBits::OneTwentyEight => "uint128",
+            IntegerBits::TwoFiftySix => "uint256",
+        },
+        TypeInfo::SignedInteger(n) => match n {
+            IntegerBits::Eight => "int8",
+            IntegerBits::Sixteen => "int16",
+            IntegerBits::ThirtyTwo => "int32",
+            IntegerBits::SixtyFour => "int64",
+            IntegerBits::OneTwentyEight => "int128",
+            IntegerBits::TwoFiftySix => "int256",
         },
         TypeInfo::Boolean => "bool",
-        TypeInfo::Custom { name } => name.as_str(),
+        TypeInfo::Custom { name, .. } | TypeInfo::Alias { name, .. } => name.as_str(),
         TypeInfo::Tuple(fields) if fields.is_empty() => "unit",
         TypeInfo::Tuple(..) => "tuple",
         TypeInfo::SelfType => "self",
@@ -610,6 +753,8 @@ fn type_info_name(type_info: &TypeInfo) -> String {
         TypeInfo::Struct { .. } => "struct",
         TypeInfo::Enum { .. } => "enum",
         TypeInfo::Array(..) => "array",
+        TypeInfo::Vector(..) => "vec",
+        TypeInfo::Function { .. } => "fn",
     }
     .to_string()
 }
@@ -9,10 +9,10 @@ use crate::{
     error::*,
     parse_tree::Purity,
     semantic_analysis::{
-        ast_node::Mode, retrieve_module, Namespace, NamespaceRef, TypeCheckArguments,
+        ast_node::Mode, retrieve_module, Namespace, NamespaceRef, TCOpts, TypeCheckArguments,
     },
     type_engine::*,
-    AstNode, ParseTree,
+    AstNode, AstNodeContent, Declaration, ParseTree,
 };
 
 use sway_types::{ident::Ident, span::Span};
@@ -68,13 +68,13 @@ impl TypedParseTree {
         }
     }
 
-    pub fn get_namespace_ref(self) -> NamespaceRef {
+    pub fn get_namespace_ref(&self) -> NamespaceRef {
         use TypedParseTree::*;
         match self {
-            Library { namespace, .. } => namespace,
-            Script { namespace, .. } => namespace,
-            Contract { namespace, .. } => namespace,
-            Predicate { namespace, .. } => namespace,
+            Library { namespace, .. } => *namespace,
+            Script { namespace, .. } => *namespace,
+            Contract { namespace, .. } => *namespace,
+            Predicate { namespace, .. } => *namespace,
         }
     }
 
@@ -100,8 +100,15 @@ impl TypedParseTree {
         let mut warnings = Vec::new();
         let mut errors = Vec::new();
 
+        // Functions may only call themselves if they're compiled via the IR, which doesn't inline
+        // every call.  Library functions are still inlined into their callers.
+        let allow_self_recursion =
+            build_config.use_ir && !matches!(tree_type, TreeType::Library { .. });
         let ordered_nodes = check!(
-            node_dependencies::order_ast_nodes_by_dependency(parsed.root_nodes),
+            node_dependencies::order_ast_nodes_by_dependency(
+                parsed.root_nodes,
+                allow_self_recursion
+            ),
             return err(warnings, errors),
             warnings,
             errors
@@ -114,6 +121,7 @@ impl TypedParseTree {
                 build_config,
                 dead_code_graph,
                 dependency_graph,
+                allow_self_recursion,
             ),
             return err(warnings, errors),
             warnings,
@@ -137,12 +145,21 @@ impl TypedParseTree {
         build_config: &BuildConfig,
         dead_code_graph: &mut ControlFlowGraph,
         dependency_graph: &mut HashMap<String, HashSet<String>>,
+        allow_self_recursion: bool,
     ) -> CompileResult<Vec<TypedAstNode>> {
         let mut warnings = Vec::new();
         let mut errors = Vec::new();
         let typed_nodes = nodes
             .into_iter()
             .map(|node| {
+                let opts = TCOpts {
+                    allow_self_recursion: allow_self_recursion
+                        && matches!(
+                            node.content,
+                            AstNodeContent::Declaration(Declaration::FunctionDeclaration(_))
+                        ),
+                    ..Default::default()
+                };
                 TypedAstNode::type_check(TypeCheckArguments {
                     checkee: node,
                     namespace,
@@ -154,7 +171,7 @@ impl TypedParseTree {
                     dead_code_graph,
                     dependency_graph,
                     mode: Mode::NonAbi,
-                    opts: Default::default(),
+                    opts,
                 })
             })
             .filter_map(|res| res.ok(&mut warnings, &mut errors))
@@ -202,9 +219,10 @@ impl TypedParseTree {
             };
         }
 
-        // impure functions are disallowed in non-contracts
+        // impure functions and storage are disallowed in non-contracts
         if *tree_type != TreeType::Contract {
             errors.append(&mut disallow_impure_functions(&declarations, &mains));
+            errors.append(&mut disallow_storage_declarations(&declarations));
         }
 
         // Perform other validation based on the tree type.
@@ -268,6 +286,20 @@ impl TypedParseTree {
     }
 }
 
+fn disallow_storage_declarations(declarations: &[TypedDeclaration]) -> Vec<CompileError> {
+    declarations
+        .iter()
+        .filter_map(|decl| match decl {
+            TypedDeclaration::StorageDeclaration(storage) => {
+                Some(CompileError::StorageDeclarationInNonContract {
+                    span: storage.span.clone(),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
 fn disallow_impure_functions(
     declarations: &[TypedDeclaration],
     mains: &[TypedFunctionDeclaration],
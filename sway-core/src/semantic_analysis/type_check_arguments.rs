@@ -22,4 +22,9 @@ pub struct TypeCheckArguments<'a, T> {
 #[derive(Default, Clone, Copy)]
 pub struct TCOpts {
     pub(crate) purity: Purity,
+    /// Whether the function declaration being type checked may call itself.
+    pub(crate) allow_self_recursion: bool,
+    /// Whether the node being type checked is within the body of a loop, where `break` and
+    /// `continue` may be used.
+    pub(crate) in_loop: bool,
 }
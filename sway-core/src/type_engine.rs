@@ -9,9 +9,11 @@ mod integer_bits;
 mod type_info;
 pub use engine::*;
 pub use integer_bits::*;
-use sway_types::Property;
+use sway_types::{LoggedType, Property};
 pub use type_info::*;
 
+use sha2::{Digest, Sha256};
+
 /// A identifier to uniquely refer to our type terms
 pub type TypeId = usize;
 
@@ -30,13 +32,18 @@ pub(crate) trait FriendlyTypeString {
 }
 
 impl FriendlyTypeString for TypeId {
+    /// A type which is referred to by an alias is displayed by the alias's name.
     fn friendly_type_str(&self) -> String {
-        look_up_type_id(*self).friendly_type_str()
+        match look_up_type_id_raw(*self) {
+            TypeInfo::Ref(id) => id.friendly_type_str(),
+            ty => ty.friendly_type_str(),
+        }
     }
 }
 
 pub(crate) trait ToJsonAbi {
     fn generate_json_abi(&self) -> Option<Vec<Property>>;
+    fn generate_logged_type(&self) -> LoggedType;
 }
 
 impl ToJsonAbi for TypeId {
@@ -54,6 +61,60 @@ impl ToJsonAbi for TypeId {
             _ => None,
         }
     }
+
+    /// Describes this type for the logged types of the JSON ABI. Its log id is the first eight
+    /// bytes of the SHA-256 hash of the description, so that it's the same in every build.
+    fn generate_logged_type(&self) -> LoggedType {
+        let type_field = logged_type_str(*self);
+        let components = self.generate_json_abi();
+        let mut hasher = Sha256::new();
+        hasher.update(describe_logged_type(&type_field, &components));
+        let hash = hasher.finalize();
+        let mut log_id = [0u8; 8];
+        log_id.copy_from_slice(&hash[..8]);
+        LoggedType {
+            log_id: u64::from_be_bytes(log_id),
+            type_field,
+            components,
+        }
+    }
+}
+
+/// The name of a logged type, where a numeric literal whose type was never constrained is the
+/// `u64` it is logged as.
+fn logged_type_str(type_id: TypeId) -> String {
+    match look_up_type_id(type_id) {
+        TypeInfo::Numeric => "u64".into(),
+        TypeInfo::Tuple(fields) => {
+            let field_strs = fields
+                .iter()
+                .map(|field| logged_type_str(*field))
+                .collect::<Vec<String>>();
+            format!("({})", field_strs.join(", "))
+        }
+        TypeInfo::Array(elem_ty, count) => format!("[{}; {}]", logged_type_str(elem_ty), count),
+        ty => ty.json_abi_str(),
+    }
+}
+
+/// e.g. `struct Foo { a: u64, b: enum Bar { X: bool, Y: () } }`
+fn describe_logged_type(type_field: &str, components: &Option<Vec<Property>>) -> String {
+    match components {
+        None => type_field.to_string(),
+        Some(components) => {
+            let components = components
+                .iter()
+                .map(|x| {
+                    format!(
+                        "{}: {}",
+                        x.name,
+                        describe_logged_type(&x.type_field, &x.components)
+                    )
+                })
+                .collect::<Vec<_>>();
+            format!("{} {{ {} }}", type_field, components.join(", "))
+        }
+    }
 }
 
 #[test]
@@ -118,6 +179,30 @@ fn chain_of_refs_2() {
         TypeInfo::UnsignedInteger(IntegerBits::Eight)
     );
 }
+#[test]
+fn unknown_through_alias() {
+    let engine = Engine::default();
+    let sp = Span {
+        span: pest::Span::new("Word".into(), 0, 4).unwrap(),
+        path: None,
+    };
+    let id = engine.insert_type(TypeInfo::UnsignedInteger(IntegerBits::SixtyFour));
+    let alias = engine.insert_type(TypeInfo::Alias {
+        name: sway_types::ident::Ident::new(sp.clone()),
+        ty: id,
+    });
+    let unknown = engine.insert_type(TypeInfo::Unknown);
+    let boolean = engine.insert_type(TypeInfo::Boolean);
+
+    // The alias is the same type as the one it names...
+    engine.unify(unknown, alias, &sp).unwrap();
+    assert_eq!(
+        engine.resolve_type(unknown, &sp).unwrap(),
+        TypeInfo::UnsignedInteger(IntegerBits::SixtyFour)
+    );
+    // ...and no other.
+    assert!(engine.unify(boolean, alias, &sp).is_err());
+}
 
 fn parse_str_type(raw: &str, span: Span) -> CompileResult<TypeInfo> {
     if raw.starts_with("str[") {
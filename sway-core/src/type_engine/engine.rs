@@ -23,7 +23,7 @@ impl Engine {
 
     pub fn look_up_type_id(&self, id: TypeId) -> TypeInfo {
         match self.slab.get(id) {
-            TypeInfo::Ref(other) => self.look_up_type_id(other),
+            TypeInfo::Ref(other) | TypeInfo::Alias { ty: other, .. } => self.look_up_type_id(other),
             ty => ty,
         }
     }
@@ -76,6 +76,10 @@ impl Engine {
             (
                 ref received_info @ UnsignedInteger(received_width),
                 ref expected_info @ UnsignedInteger(expected_width),
+            )
+            | (
+                ref received_info @ SignedInteger(received_width),
+                ref expected_info @ SignedInteger(expected_width),
             ) => {
                 // E.g., in a variable declaration `let a: u32 = 10u64` the 'expected' type will be
                 // the annotation `u32`, and the 'received' type is 'self' of the initialiser, or
@@ -90,6 +94,14 @@ impl Engine {
                     NumericCastCompatResult::Compatible => {
                         vec![]
                     }
+                    NumericCastCompatResult::Incompatible => {
+                        return Err(TypeError::MismatchedType {
+                            expected,
+                            received,
+                            help_text: Default::default(),
+                            span: span.clone(),
+                        })
+                    }
                 };
 
                 // Cast the expected type to the received type.
@@ -98,18 +110,43 @@ impl Engine {
                 Ok(warn)
             }
 
-            (ref received_info @ UnknownGeneric { .. }, _) => {
+            // The expected type is replaced first so that passing a value of a generic type to a
+            // generic function instantiates the callee's type parameter, rather than replacing
+            // the caller's.  The order only matters when both types are generic, otherwise just
+            // one of these arms can match.
+            (_, ref expected_info @ UnknownGeneric { .. }) => {
                 self.slab
-                    .replace(received, received_info, TypeInfo::Ref(expected));
+                    .replace(expected, expected_info, TypeInfo::Ref(received));
                 Ok(vec![])
             }
 
-            (_, ref expected_info @ UnknownGeneric { .. }) => {
+            (ref received_info @ UnknownGeneric { .. }, _) => {
                 self.slab
-                    .replace(expected, expected_info, TypeInfo::Ref(received));
+                    .replace(received, received_info, TypeInfo::Ref(expected));
                 Ok(vec![])
             }
 
+            // An alias is the same type as the one it refers to. Unknown types are unified with
+            // the alias itself above, so that they're displayed by its name.
+            (Alias { ty, .. }, _) => {
+                self.unify(ty, expected, span)
+                    .map_err(|_| TypeError::MismatchedType {
+                        expected,
+                        received,
+                        help_text: Default::default(),
+                        span: span.clone(),
+                    })
+            }
+            (_, Alias { ty, .. }) => {
+                self.unify(received, ty, span)
+                    .map_err(|_| TypeError::MismatchedType {
+                        expected,
+                        received,
+                        help_text: Default::default(),
+                        span: span.clone(),
+                    })
+            }
+
             // if the types, once their ids have been looked up, are the same, we are done
             (
                 Struct {
@@ -148,13 +185,13 @@ impl Engine {
                 Ok(vec![])
             }
 
-            (Numeric, expected_info @ UnsignedInteger(_)) => {
+            (Numeric, expected_info @ (UnsignedInteger(_) | SignedInteger(_))) => {
                 match self.slab.replace(received, &Numeric, expected_info) {
                     None => Ok(vec![]),
                     Some(_) => self.unify(received, expected, span),
                 }
             }
-            (received_info @ UnsignedInteger(_), Numeric) => {
+            (received_info @ (UnsignedInteger(_) | SignedInteger(_)), Numeric) => {
                 match self.slab.replace(expected, &Numeric, received_info) {
                     None => Ok(vec![]),
                     Some(_) => self.unify(received, expected, span),
@@ -172,13 +209,41 @@ impl Engine {
                     span: span.clone(),
                 }),
 
-            // When unifying complex types, we must check their sub-types. This
-            // can be trivially implemented for tuples, sum types, etc.
-            // (List(a_item), List(b_item)) => self.unify(a_item, b_item),
-            // this can be used for curried function types but we might not want that
-            // (Func(a_i, a_o), Func(b_i, b_o)) => {
-            //     self.unify(a_i, b_i).and_then(|_| self.unify(a_o, b_o))
-            // }
+            (Vector(a_elem), Vector(b_elem)) => {
+                self.unify(a_elem, b_elem, span)
+                    .map_err(|_| TypeError::MismatchedType {
+                        expected,
+                        received,
+                        help_text: Default::default(),
+                        span: span.clone(),
+                    })
+            }
+
+            (
+                Function {
+                    parameters: a_params,
+                    return_type: a_return,
+                },
+                Function {
+                    parameters: b_params,
+                    return_type: b_return,
+                },
+            ) if a_params.len() == b_params.len() => {
+                // As with arrays, report the function types as mismatching rather than their
+                // parameter or return types.
+                let mismatched = |_| TypeError::MismatchedType {
+                    expected,
+                    received,
+                    help_text: Default::default(),
+                    span: span.clone(),
+                };
+                let mut warnings = vec![];
+                for (a_param, b_param) in a_params.iter().zip(b_params.iter()) {
+                    warnings.extend(self.unify(*a_param, *b_param, span).map_err(mismatched)?);
+                }
+                warnings.extend(self.unify(a_return, b_return, span).map_err(mismatched)?);
+                Ok(warnings)
+            }
 
             // If no previous attempts to unify were successful, raise an error
             (the_received, the_expected) => match (the_received, the_expected) {
@@ -254,6 +319,11 @@ fn numeric_cast_compat(new_size: IntegerBits, old_size: IntegerBits) -> NumericC
     // If this is a downcast, warn for loss of precision. If upcast, then no warning.
     use IntegerBits::*;
     match (new_size, old_size) {
+        // Wide integers are kept in memory rather than in a register, so they can't be cast to or
+        // from any other size just by changing their type.
+        _ if new_size != old_size && (new_size.is_wide() || old_size.is_wide()) => {
+            NumericCastCompatResult::Incompatible
+        }
         // These should generate a downcast warning.
         (Eight, Sixteen)
         | (Eight, ThirtyTwo)
@@ -273,4 +343,133 @@ fn numeric_cast_compat(new_size: IntegerBits, old_size: IntegerBits) -> NumericC
 enum NumericCastCompatResult {
     Compatible,
     CastableWithWarning(Warning),
+    Incompatible,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        control_flow_analysis::ControlFlowGraph,
+        parser::{Rule, SwayParser},
+        semantic_analysis::TypedParseTree,
+        CompileError, Ident,
+    };
+    use pest::Parser;
+
+    fn generic(name: &str) -> TypeInfo {
+        TypeInfo::UnknownGeneric {
+            name: Ident::new(Span {
+                span: pest::Span::new(std::sync::Arc::from(name), 0, name.len()).unwrap(),
+                path: None,
+            }),
+        }
+    }
+
+    fn span() -> Span {
+        Span {
+            span: pest::Span::new(std::sync::Arc::from(""), 0, 0).unwrap(),
+            path: None,
+        }
+    }
+
+    #[test]
+    fn unify_generic_with_generic_replaces_expected() {
+        let engine = Engine::default();
+        let received = engine.insert_type(generic("T"));
+        let expected = engine.insert_type(generic("U"));
+        assert!(engine.unify(received, expected, &span()).is_ok());
+        assert_eq!(engine.look_up_type_id_raw(received), generic("T"));
+        assert_eq!(
+            engine.look_up_type_id_raw(expected),
+            TypeInfo::Ref(received)
+        );
+    }
+
+    #[test]
+    fn unify_generic_with_concrete_is_order_independent() {
+        let u64_ty = TypeInfo::UnsignedInteger(IntegerBits::SixtyFour);
+        for generic_is_received in [true, false] {
+            let engine = Engine::default();
+            let generic_id = engine.insert_type(generic("T"));
+            let concrete_id = engine.insert_type(u64_ty.clone());
+            let (received, expected) = if generic_is_received {
+                (generic_id, concrete_id)
+            } else {
+                (concrete_id, generic_id)
+            };
+            assert!(engine.unify(received, expected, &span()).is_ok());
+            assert_eq!(engine.look_up_type_id(generic_id), u64_ty);
+            assert_eq!(engine.look_up_type_id(concrete_id), u64_ty);
+        }
+    }
+
+    fn type_check(input: &str) -> Vec<CompileError> {
+        let mut parsed =
+            SwayParser::parse(Rule::program, std::sync::Arc::from(input)).expect("parse_tree");
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        let parse_tree = crate::parse_root_from_pairs(parsed.next().unwrap().into_inner(), None)
+            .unwrap(&mut warnings, &mut errors);
+        let build_config = crate::build_config::BuildConfig {
+            file_name: std::sync::Arc::new("test.sw".into()),
+            dir_of_code: std::sync::Arc::new("tests".into()),
+            manifest_path: std::sync::Arc::new(".".into()),
+            use_ir: false,
+            print_intermediate_asm: false,
+            print_finalized_asm: false,
+            print_ir: false,
+            print_peephole_savings: false,
+            optimize_asm: true,
+            ir_opt_level: 1,
+        };
+        TypedParseTree::type_check(
+            parse_tree.tree,
+            crate::create_module(),
+            crate::create_module(),
+            &parse_tree.tree_type,
+            &build_config,
+            &mut ControlFlowGraph::default(),
+            &mut std::collections::HashMap::new(),
+        )
+        .errors
+    }
+
+    const GENERIC_CALLS: &str = "script;
+
+        fn inner<U>(u: U) -> U {
+            u
+        }
+
+        fn outer<T>(t: T) -> T {
+            inner(t)
+        }
+    ";
+
+    #[test]
+    fn generic_passed_to_generic_keeps_caller_type() {
+        let errors = type_check(&format!(
+            "{}
+            fn main() -> bool {{
+                let a: u64 = outer(1);
+                let b: bool = outer(true);
+                b
+            }}",
+            GENERIC_CALLS
+        ));
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+
+    #[test]
+    fn generic_passed_to_generic_mismatch_is_reported() {
+        let errors = type_check(&format!(
+            "{}
+            fn main() -> bool {{
+                let b: bool = outer(1);
+                b
+            }}",
+            GENERIC_CALLS
+        ));
+        assert!(!errors.is_empty());
+    }
 }
@@ -4,6 +4,8 @@ pub enum IntegerBits {
     Sixteen,
     ThirtyTwo,
     SixtyFour,
+    OneTwentyEight,
+    TwoFiftySix,
 }
 
 impl IntegerBits {
@@ -14,6 +16,22 @@ impl IntegerBits {
             Sixteen => "sixteen",
             ThirtyTwo => "thirty two",
             SixtyFour => "sixty four",
+            OneTwentyEight => "one hundred twenty eight",
+            TwoFiftySix => "two hundred fifty six",
+        }
+    }
+
+    /// Integers wider than a word don't fit in a register, so like a `b256` they are kept in
+    /// memory and referred to by a pointer.
+    pub(crate) fn is_wide(&self) -> bool {
+        matches!(self, IntegerBits::OneTwentyEight | IntegerBits::TwoFiftySix)
+    }
+
+    pub(crate) fn size_in_words(&self) -> u64 {
+        match self {
+            IntegerBits::OneTwentyEight => 2,
+            IntegerBits::TwoFiftySix => 4,
+            _ => 1,
         }
     }
 }
@@ -23,6 +23,8 @@ pub enum TypeInfo {
     },
     Str(u64),
     UnsignedInteger(IntegerBits),
+    /// A two's complement integer, sign extended to fill a register.
+    SignedInteger(IntegerBits),
     Enum {
         name: String,
         variant_types: Vec<OwnedTypedEnumVariant>,
@@ -52,6 +54,8 @@ pub enum TypeInfo {
     /// until the semantic analysis stage.
     Custom {
         name: Ident,
+        /// The types given between angle brackets, e.g. `u64` in `Pair<u64>`.
+        type_arguments: Vec<TypeId>,
     },
     SelfType,
     Byte,
@@ -64,6 +68,22 @@ pub enum TypeInfo {
     ErrorRecovery,
     // Static, constant size arrays.
     Array(TypeId, usize),
+    /// A growable vector, written `Vec<T>`, laid out on the heap. The value itself is a pointer to a header of
+    /// three words: the address of the elements, the capacity and the length.
+    Vector(TypeId),
+    /// A type referred to by the name of a type alias. It's the same type as `ty`, and is only
+    /// distinguished from it when it's displayed.
+    Alias {
+        name: Ident,
+        ty: TypeId,
+    },
+    /// The type of a function value, written `fn(T) -> U`. Function values are resolved
+    /// statically, by binding them to the parameters of the functions they are passed to, so they
+    /// have no runtime representation.
+    Function {
+        parameters: Vec<TypeId>,
+        return_type: TypeId,
+    },
 }
 
 impl Default for TypeInfo {
@@ -91,7 +111,29 @@ impl TypeInfo {
                 return err(vec![], errors);
             }
         }
-        Self::parse_from_pair_inner(input.into_inner().next().unwrap(), config)
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        let mut parts = input.into_inner();
+        let mut type_info = check!(
+            Self::parse_from_pair_inner(parts.next().unwrap(), config),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        if let (TypeInfo::Custom { type_arguments, .. }, Some(type_params)) =
+            (&mut type_info, parts.next())
+        {
+            for type_argument in type_params.into_inner() {
+                let type_argument = check!(
+                    Self::parse_from_pair(type_argument, config),
+                    TypeInfo::ErrorRecovery,
+                    warnings,
+                    errors
+                );
+                type_arguments.push(insert_type(type_argument));
+            }
+        }
+        ok(type_info, warnings, errors)
     }
 
     fn parse_from_pair_inner(
@@ -118,6 +160,12 @@ impl TypeInfo {
                 "u16" => TypeInfo::UnsignedInteger(IntegerBits::Sixteen),
                 "u32" => TypeInfo::UnsignedInteger(IntegerBits::ThirtyTwo),
                 "u64" => TypeInfo::UnsignedInteger(IntegerBits::SixtyFour),
+                "u128" => TypeInfo::UnsignedInteger(IntegerBits::OneTwentyEight),
+                "u256" => TypeInfo::UnsignedInteger(IntegerBits::TwoFiftySix),
+                "i8" => TypeInfo::SignedInteger(IntegerBits::Eight),
+                "i16" => TypeInfo::SignedInteger(IntegerBits::Sixteen),
+                "i32" => TypeInfo::SignedInteger(IntegerBits::ThirtyTwo),
+                "i64" => TypeInfo::SignedInteger(IntegerBits::SixtyFour),
                 "bool" => TypeInfo::Boolean,
                 "unit" => TypeInfo::Tuple(Vec::new()),
                 "byte" => TypeInfo::Byte,
@@ -126,8 +174,21 @@ impl TypeInfo {
                 "Contract" => TypeInfo::Contract,
                 _other => TypeInfo::Custom {
                     name: Ident::new(span),
+                    type_arguments: Vec::new(),
                 },
             },
+            // `Self::Output` names the associated type `Output`, which is in scope within the trait
+            // declaration and its implementations
+            Rule::associated_type => {
+                let name = input.into_inner().last().expect("guaranteed by grammar");
+                TypeInfo::Custom {
+                    name: Ident::new(Span {
+                        span: name.as_span(),
+                        path: span.path,
+                    }),
+                    type_arguments: Vec::new(),
+                }
+            }
             Rule::array_type => {
                 let mut array_inner_iter = input.into_inner();
                 let elem_type_info = match array_inner_iter.next() {
@@ -197,6 +258,15 @@ impl TypeInfo {
                 };
                 TypeInfo::Array(insert_type(elem_type_info), elem_count)
             }
+            Rule::vec_type => {
+                let elem_type_info = check!(
+                    Self::parse_from_pair(input.into_inner().next().unwrap(), config),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                );
+                TypeInfo::Vector(insert_type(elem_type_info))
+            }
             Rule::tuple_type => {
                 let mut field_type_ids = vec![];
                 for field in input.into_inner() {
@@ -211,6 +281,38 @@ impl TypeInfo {
                 }
                 TypeInfo::Tuple(field_type_ids)
             }
+            Rule::fn_type => {
+                let mut parameters = vec![];
+                let mut return_type = TypeInfo::Tuple(Vec::new());
+                for part in input.into_inner() {
+                    match part.as_rule() {
+                        Rule::fn_decl_keyword | Rule::fn_returns => (),
+                        Rule::fn_type_params => {
+                            for param in part.into_inner() {
+                                let param_type = check!(
+                                    TypeInfo::parse_from_pair(param, config),
+                                    TypeInfo::ErrorRecovery,
+                                    warnings,
+                                    errors
+                                );
+                                parameters.push(insert_type(param_type));
+                            }
+                        }
+                        _ => {
+                            return_type = check!(
+                                TypeInfo::parse_from_pair(part, config),
+                                TypeInfo::ErrorRecovery,
+                                warnings,
+                                errors
+                            );
+                        }
+                    }
+                }
+                TypeInfo::Function {
+                    parameters,
+                    return_type: insert_type(return_type),
+                }
+            }
             _ => {
                 errors.push(CompileError::Internal(
                     "Unexpected token while parsing inner type.",
@@ -233,10 +335,21 @@ impl TypeInfo {
                 IntegerBits::Sixteen => "u16",
                 IntegerBits::ThirtyTwo => "u32",
                 IntegerBits::SixtyFour => "u64",
+                IntegerBits::OneTwentyEight => "u128",
+                IntegerBits::TwoFiftySix => "u256",
+            }
+            .into(),
+            SignedInteger(x) => match x {
+                IntegerBits::Eight => "i8",
+                IntegerBits::Sixteen => "i16",
+                IntegerBits::ThirtyTwo => "i32",
+                IntegerBits::SixtyFour => "i64",
+                IntegerBits::OneTwentyEight => "i128",
+                IntegerBits::TwoFiftySix => "i256",
             }
             .into(),
             Boolean => "bool".into(),
-            Custom { name } => format!("unresolved {}", name.as_str()),
+            Custom { name, .. } => format!("unresolved {}", name.as_str()),
             Ref(id) => format!("T{} ({})", id, (*id).friendly_type_str()),
             Tuple(fields) => {
                 let field_strs = fields
@@ -265,6 +378,12 @@ impl TypeInfo {
                 format!("contract caller {}", abi_name.suffix)
             }
             Array(elem_ty, count) => format!("[{}; {}]", elem_ty.friendly_type_str(), count),
+            Vector(elem_ty) => format!("Vec<{}>", elem_ty.friendly_type_str()),
+            Alias { name, .. } => name.to_string(),
+            Function {
+                parameters,
+                return_type,
+            } => print_function_type(parameters, *return_type, |x| x.friendly_type_str()),
         }
     }
 
@@ -279,10 +398,21 @@ impl TypeInfo {
                 IntegerBits::Sixteen => "u16",
                 IntegerBits::ThirtyTwo => "u32",
                 IntegerBits::SixtyFour => "u64",
+                IntegerBits::OneTwentyEight => "u128",
+                IntegerBits::TwoFiftySix => "u256",
+            }
+            .into(),
+            SignedInteger(x) => match x {
+                IntegerBits::Eight => "i8",
+                IntegerBits::Sixteen => "i16",
+                IntegerBits::ThirtyTwo => "i32",
+                IntegerBits::SixtyFour => "i64",
+                IntegerBits::OneTwentyEight => "i128",
+                IntegerBits::TwoFiftySix => "i256",
             }
             .into(),
             Boolean => "bool".into(),
-            Custom { name } => format!("unresolved {}", name.as_str()),
+            Custom { name, .. } => format!("unresolved {}", name.as_str()),
             Ref(id) => format!("T{} ({})", id, (*id).json_abi_str()),
             Tuple(fields) => {
                 let field_strs = fields
@@ -307,6 +437,13 @@ impl TypeInfo {
                 format!("contract caller {}", abi_name.suffix)
             }
             Array(elem_ty, count) => format!("[{}; {}]", elem_ty.json_abi_str(), count),
+            Vector(elem_ty) => format!("Vec<{}>", elem_ty.json_abi_str()),
+            // the ABI describes the aliased type, as callers may not know the alias
+            Alias { ty, .. } => ty.json_abi_str(),
+            Function {
+                parameters,
+                return_type,
+            } => print_function_type(parameters, *return_type, |x| x.json_abi_str()),
         }
     }
 
@@ -322,6 +459,20 @@ impl TypeInfo {
                     Sixteen => "u16",
                     ThirtyTwo => "u32",
                     SixtyFour => "u64",
+                    OneTwentyEight => "u128",
+                    TwoFiftySix => "u256",
+                }
+                .into()
+            }
+            SignedInteger(bits) => {
+                use IntegerBits::*;
+                match bits {
+                    Eight => "i8",
+                    Sixteen => "i16",
+                    ThirtyTwo => "i32",
+                    SixtyFour => "i64",
+                    OneTwentyEight => "i128",
+                    TwoFiftySix => "i256",
                 }
                 .into()
             }
@@ -397,6 +548,7 @@ impl TypeInfo {
 
                 format!("e({})", variant_names.join(","))
             }
+            Alias { ty, .. } => return look_up_type_id(*ty).to_selector_name(error_msg_span),
             _ => {
                 return err(
                     vec![],
@@ -415,7 +567,10 @@ impl TypeInfo {
             // rounded up to the nearest word
             TypeInfo::Str(len) => Ok((len + 7) / 8),
             // Since things are unpacked, all unsigned integers are 64 bits.....for now
-            TypeInfo::UnsignedInteger(_) | TypeInfo::Numeric => Ok(1),
+            TypeInfo::UnsignedInteger(bits) | TypeInfo::SignedInteger(bits) => {
+                Ok(bits.size_in_words())
+            }
+            TypeInfo::Numeric => Ok(1),
             TypeInfo::Boolean => Ok(1),
             TypeInfo::Tuple(fields) => Ok(fields
                 .iter()
@@ -462,22 +617,34 @@ impl TypeInfo {
                 ty: self.friendly_type_str(),
                 span: err_span.clone(),
             }),
-            TypeInfo::Ref(id) => look_up_type_id(*id).size_in_words(err_span),
+            TypeInfo::Ref(id) | TypeInfo::Alias { ty: id, .. } => {
+                look_up_type_id(*id).size_in_words(err_span)
+            }
             TypeInfo::Array(elem_ty, count) => {
                 Ok(look_up_type_id(*elem_ty).size_in_words(err_span)? * *count as u64)
             }
+            // the elements are on the heap, only the pointer to them is on the stack
+            TypeInfo::Vector(_) => Ok(1),
+            // function values are resolved statically and never stored
+            TypeInfo::Function { .. } => Ok(0),
         }
     }
     pub(crate) fn is_copy_type(&self) -> bool {
         match self {
-            TypeInfo::UnsignedInteger(_) | TypeInfo::Boolean | TypeInfo::Byte => true,
-            TypeInfo::Tuple(fields) => fields
-                .iter()
-                .all(|field_type| look_up_type_id(*field_type).is_copy_type()),
+            TypeInfo::UnsignedInteger(bits) | TypeInfo::SignedInteger(bits) => !bits.is_wide(),
+            TypeInfo::Boolean | TypeInfo::Byte | TypeInfo::Vector(_) => true,
+            // any other tuple is laid out in memory, like a struct, however small its elements are
+            TypeInfo::Tuple(fields) => fields.is_empty(),
             _ => false,
         }
     }
 
+    /// Whether a value of this type is held in a register itself, rather than as a pointer to the
+    /// memory it's laid out in. A string which fits in a word is loaded by value, like a copy type.
+    pub(crate) fn is_held_in_register(&self) -> bool {
+        self.is_copy_type() || matches!(self, TypeInfo::Str(len) if *len <= 8)
+    }
+
     pub fn is_uninhabited(&self) -> bool {
         match self {
             TypeInfo::Enum { variant_types, .. } => variant_types
@@ -547,6 +714,73 @@ impl TypeInfo {
         }
     }
 
+    /// Whether this type could be unified with `other` without either of them changing, other
+    /// than by inferring what isn't known yet. This is used to choose between methods of the same
+    /// name, from implementations of a trait with different type arguments, before unifying.
+    pub(crate) fn could_unify_with(&self, other: &TypeInfo) -> bool {
+        use TypeInfo::*;
+        let all_could_unify = |a: &[TypeId], b: &[TypeId]| {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b.iter())
+                    .all(|(a, b)| look_up_type_id(*a).could_unify_with(&look_up_type_id(*b)))
+        };
+        match (self, other) {
+            (Unknown | UnknownGeneric { .. } | ErrorRecovery, _)
+            | (_, Unknown | UnknownGeneric { .. } | ErrorRecovery) => true,
+            (Numeric, Numeric | UnsignedInteger(_) | SignedInteger(_))
+            | (UnsignedInteger(_) | SignedInteger(_), Numeric) => true,
+            (Tuple(a_elems), Tuple(b_elems)) => all_could_unify(a_elems, b_elems),
+            (
+                Struct {
+                    name: a_name,
+                    fields: a_fields,
+                },
+                Struct {
+                    name: b_name,
+                    fields: b_fields,
+                },
+            ) => {
+                a_name == b_name
+                    && all_could_unify(
+                        &a_fields.iter().map(|x| x.r#type).collect::<Vec<_>>(),
+                        &b_fields.iter().map(|x| x.r#type).collect::<Vec<_>>(),
+                    )
+            }
+            (
+                Enum {
+                    name: a_name,
+                    variant_types: a_variants,
+                },
+                Enum {
+                    name: b_name,
+                    variant_types: b_variants,
+                },
+            ) => {
+                a_name == b_name
+                    && all_could_unify(
+                        &a_variants.iter().map(|x| x.r#type).collect::<Vec<_>>(),
+                        &b_variants.iter().map(|x| x.r#type).collect::<Vec<_>>(),
+                    )
+            }
+            (Array(a_elem, a_count), Array(b_elem, b_count)) => {
+                a_count == b_count && all_could_unify(&[*a_elem], &[*b_elem])
+            }
+            (Vector(a_elem), Vector(b_elem)) => all_could_unify(&[*a_elem], &[*b_elem]),
+            (
+                Function {
+                    parameters: a_params,
+                    return_type: a_return,
+                },
+                Function {
+                    parameters: b_params,
+                    return_type: b_return,
+                },
+            ) => all_could_unify(a_params, b_params) && all_could_unify(&[*a_return], &[*b_return]),
+            (a, b) => a == b,
+        }
+    }
+
     pub(crate) fn matches_type_parameter(
         &self,
         mapping: &[(TypeParameter, TypeId)],
@@ -563,7 +797,12 @@ impl TypeInfo {
             }
             TypeInfo::UnknownGeneric { name, .. } => {
                 for (param, ty_id) in mapping.iter() {
-                    if param.name == (TypeInfo::Custom { name: name.clone() }) {
+                    if param.name
+                        == (TypeInfo::Custom {
+                            name: name.clone(),
+                            type_arguments: Vec::new(),
+                        })
+                    {
                         return Some(*ty_id);
                     }
                 }
@@ -604,6 +843,24 @@ impl TypeInfo {
             TypeInfo::Array(ary_ty_id, count) => look_up_type_id(*ary_ty_id)
                 .matches_type_parameter(mapping)
                 .map(|matching_id| insert_type(TypeInfo::Array(matching_id, *count))),
+            TypeInfo::Vector(elem_ty_id) => look_up_type_id(*elem_ty_id)
+                .matches_type_parameter(mapping)
+                .map(|matching_id| insert_type(TypeInfo::Vector(matching_id))),
+            TypeInfo::Alias { ty, .. } => look_up_type_id(*ty).matches_type_parameter(mapping),
+            TypeInfo::Function {
+                parameters,
+                return_type,
+            } => {
+                let copy_type =
+                    |id: TypeId| match look_up_type_id(id).matches_type_parameter(mapping) {
+                        Some(matching_id) => insert_type(TypeInfo::Ref(matching_id)),
+                        None => id,
+                    };
+                Some(insert_type(TypeInfo::Function {
+                    parameters: parameters.iter().map(|id| copy_type(*id)).collect(),
+                    return_type: copy_type(*return_type),
+                }))
+            }
             TypeInfo::Tuple(fields) => {
                 let mut new_fields = Vec::new();
                 let mut index = 0;
@@ -636,6 +893,7 @@ impl TypeInfo {
             Unknown
             | Str(..)
             | UnsignedInteger(..)
+            | SignedInteger(..)
             | Boolean
             | Ref(..)
             | ContractCaller { .. }
@@ -649,6 +907,24 @@ impl TypeInfo {
     }
 }
 
+/// e.g. `fn(u64, bool) -> u64`, or `fn(u64)` for a function which returns unit.
+fn print_function_type(
+    parameters: &[TypeId],
+    return_type: TypeId,
+    type_str: impl Fn(TypeId) -> String,
+) -> String {
+    let parameters = parameters
+        .iter()
+        .map(|x| type_str(*x))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if look_up_type_id(return_type).is_unit() {
+        format!("fn({})", parameters)
+    } else {
+        format!("fn({}) -> {}", parameters, type_str(return_type))
+    }
+}
+
 fn print_inner_types(name: String, inner_types: impl Iterator<Item = TypeId>) -> String {
     format!(
         "{}<{}>",
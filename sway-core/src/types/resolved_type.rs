@@ -9,6 +9,7 @@ pub enum ResolvedType {
     /// The number in a `Str` represents its size, which must be known at compile time
     Str(u64),
     UnsignedInteger(IntegerBits),
+    SignedInteger(IntegerBits),
     Boolean,
     Unit,
     Byte,
@@ -60,8 +61,11 @@ impl ResolvedType {
             // Each char is a byte, so the size is the num of characters / 8
             // rounded up to the nearest word
             ResolvedType::Str(len) => (len + 7) / 8,
-            // Since things are unpacked, all unsigned integers are 64 bits.....for now
-            ResolvedType::UnsignedInteger(_) => 1,
+            // Since things are unpacked, all unsigned integers up to 64 bits take a word, and the
+            // wide integers take a word per 64 bits.
+            ResolvedType::UnsignedInteger(bits) | ResolvedType::SignedInteger(bits) => {
+                bits.size_in_words()
+            }
             ResolvedType::Boolean => 1,
             ResolvedType::Unit => 0,
             ResolvedType::Byte => 1,
@@ -93,6 +97,9 @@ impl ResolvedType {
     }
 
     pub fn is_numeric(&self) -> bool {
-        matches!(self, ResolvedType::UnsignedInteger(_))
+        matches!(
+            self,
+            ResolvedType::UnsignedInteger(_) | ResolvedType::SignedInteger(_)
+        )
     }
 }
@@ -0,0 +1,57 @@
+.program:
+ji   i4
+noop
+DATA_SECTION_OFFSET[0..32]
+DATA_SECTION_OFFSET[32..64]
+lw   $ds $is 1
+add  $$ds $$ds $is
+lw   $r1 $fp i73              ; load input function selector
+lw   $r0 data_7               ; load fn selector for comparison
+eq   $r0 $r1 $r0              ; function selector comparison
+jnei $zero $r0 i17            ; jump to selected function
+lw   $r0 data_8               ; load fn selector for comparison
+eq   $r0 $r1 $r0              ; function selector comparison
+jnei $zero $r0 i22            ; jump to selected function
+lw   $r0 data_9               ; load fn selector for comparison
+eq   $r0 $r1 $r0              ; function selector comparison
+jnei $zero $r0 i26            ; jump to selected function
+rvrt $zero                    ; revert if no selectors matched
+lw   $r1 data_0               ; literal instantiation
+lw   $r0 data_1               ; literal instantiation
+sww  $r0 $r1                  ; store word to storage
+ret  $zero                    ; returning unit as zero
+lw   $r0 data_1               ; literal instantiation
+srw  $r0 $r0                  ; load word from storage
+ret  $r0
+move $r2 $sp
+cfei i64
+lw   $r0 data_2               ; storage key
+lw   $r1 data_3               ; storage key
+addi $r3 $r2 i0               ; get result memory
+srwq $r3 $r0                  ; load quad word from storage
+addi $r0 $r3 i32              ; get offset
+srwq $r0 $r1                  ; load quad word from storage
+lw   $r0 data_4               ; storage key
+lw   $r2 data_5               ; storage key
+swwq $r0 $r3                  ; store quad word to storage
+addi $r1 $r3 i32              ; get offset
+move $r0 $sp
+cfei i32
+mcli $r0 i32                  ; zero quad word for padding
+mcpi $r0 $r1 i8               ; copy last chunk into padding
+swwq $r2 $r0                  ; store padded quad word to storage
+cfsi i32                      ; free padding
+lw   $r0 data_6               ; loading size for RETD
+retd  $r3 $r0
+noop                          ; word-alignment of data section
+.data:
+data_0 .u64 0x2a
+data_1 .b256 0xf383b0ce51358be57daa3b725fe44acdb2d880604e367199080b4379c41bb6ed
+data_2 .b256 0xde9090cb50e71c2588c773487d1da7066d0c719849a7e58dc8b6397a25c567c0
+data_3 .b256 0x84732339149990d766e07a046ee5db9d158c5bbd6d11dd7a0c656039526c0bd5
+data_4 .b256 0x3ac38e9b1b1d7ad7e2b7dcd36ec2f0a2d71d3a4e1e3ba2c1cc0b38c8e1ac1d5f
+data_5 .b256 0x9f8c1a38378d0de3dc6ac55f5dfd146c061115661b2ece478843b573ec0eb7f6
+data_6 .u64 0x28
+data_7 .u32 0x27e0a920
+data_8 .u32 0x1e5bc1b0
+data_9 .u32 0x3d6e71fa
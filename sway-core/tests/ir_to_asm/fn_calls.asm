@@ -0,0 +1,100 @@
+.program:
+ji   i4
+noop
+DATA_SECTION_OFFSET[0..32]
+DATA_SECTION_OFFSET[32..64]
+lw   $ds $is 1
+add  $$ds $$ds $is
+lw   $r0 data_0               ; literal instantiation
+move $r4 $r0
+addi $r3 $zero i0             ; set return address
+ji   i12                      ; call fib
+move $r0 $r1
+ret  $r0
+lw   $r0 data_1               ; literal instantiation
+lt   $r0 $r4 $r0
+jnei $r0 $one i17
+move $r0 $r4
+ji   i78
+lw   $r1 data_2               ; literal instantiation
+move $r0 $sp
+cfei i24
+sw   $r0 $$cfp i0             ; save previous call frame
+move $$cfp $r0
+sw   $$cfp $r4 i1             ; save register to call frame
+sw   $$cfp $r3 i2             ; save register to call frame
+move $r3 $r4
+addi $r2 $zero i0             ; set return address
+ji   i88                      ; call sub
+lw   $r4 $$cfp i1             ; restore register from call frame
+lw   $r3 $$cfp i2             ; restore register from call frame
+lw   $$cfp $$cfp i0           ; restore previous call frame
+cfsi i24                      ; free call frame
+move $r0 $sp
+cfei i24
+sw   $r0 $$cfp i0             ; save previous call frame
+move $$cfp $r0
+sw   $$cfp $r4 i1             ; save register to call frame
+sw   $$cfp $r3 i2             ; save register to call frame
+move $r4 $r1
+addi $r3 $zero i1             ; set return address
+ji   i12                      ; call fib
+move $r2 $r1
+lw   $r4 $$cfp i1             ; restore register from call frame
+lw   $r3 $$cfp i2             ; restore register from call frame
+lw   $$cfp $$cfp i0           ; restore previous call frame
+cfsi i24                      ; free call frame
+lw   $r1 data_1               ; literal instantiation
+move $r0 $sp
+cfei i32
+sw   $r0 $$cfp i0             ; save previous call frame
+move $$cfp $r0
+sw   $$cfp $r4 i1             ; save register to call frame
+sw   $$cfp $r2 i2             ; save register to call frame
+sw   $$cfp $r3 i3             ; save register to call frame
+move $r3 $r4
+addi $r2 $zero i1             ; set return address
+ji   i88                      ; call sub
+lw   $r4 $$cfp i1             ; restore register from call frame
+lw   $r2 $$cfp i2             ; restore register from call frame
+lw   $r3 $$cfp i3             ; restore register from call frame
+lw   $$cfp $$cfp i0           ; restore previous call frame
+cfsi i32                      ; free call frame
+move $r0 $sp
+cfei i32
+sw   $r0 $$cfp i0             ; save previous call frame
+move $$cfp $r0
+sw   $$cfp $r4 i1             ; save register to call frame
+sw   $$cfp $r2 i2             ; save register to call frame
+sw   $$cfp $r3 i3             ; save register to call frame
+move $r4 $r1
+addi $r3 $zero i2             ; set return address
+ji   i12                      ; call fib
+move $r0 $r1
+lw   $r4 $$cfp i1             ; restore register from call frame
+lw   $r2 $$cfp i2             ; restore register from call frame
+lw   $r3 $$cfp i3             ; restore register from call frame
+lw   $$cfp $$cfp i0           ; restore previous call frame
+cfsi i32                      ; free call frame
+add  $r0 $r2 $r0
+move $r1 $r0                  ; set return value
+addi $r0 $zero i1             ; call site 1
+lt   $r0 $r3 $r0
+jnei $r0 $zero i87
+addi $r0 $zero i2             ; call site 2
+lt   $r0 $r3 $r0
+jnei $r0 $zero i86
+ji   i71
+ji   i40
+ji   i10
+sub  $r0 $r3 $r1
+move $r1 $r0                  ; set return value
+addi $r0 $zero i1             ; call site 1
+lt   $r0 $r2 $r0
+jnei $r0 $zero i94
+ji   i56
+ji   i27
+.data:
+data_0 .u64 0x0a
+data_1 .u64 0x02
+data_2 .u64 0x01
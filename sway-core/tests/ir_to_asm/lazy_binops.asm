@@ -6,17 +6,13 @@ DATA_SECTION_OFFSET[32..64]
 lw   $ds $is 1
 add  $$ds $$ds $is
 lw   $r0 data_0               ; literal instantiation
-move $r1 $r0
-move $r1 $r0
-jnei $r0 $one i12
+jnei $r0 $one i9
+ji   i10
+ji   i11
+lw   $r0 data_1               ; literal instantiation
+jnei $r0 $one i13
+ji   i14
 lw   $r0 data_1               ; literal instantiation
-move $r1 $r0
-move $r0 $r1
-move $r2 $r1
-jnei $r1 $one i16
-ji   i18
-lw   $r1 data_1               ; literal instantiation
-move $r0 $r1
 ret  $r0
 .data:
 data_0 .bool 0x00
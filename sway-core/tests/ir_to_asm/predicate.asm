@@ -0,0 +1,12 @@
+.program:
+ji   i4
+noop
+DATA_SECTION_OFFSET[0..32]
+DATA_SECTION_OFFSET[32..64]
+lw   $ds $is 1
+add  $$ds $$ds $is
+lw   $r0 data_0               ; literal instantiation
+eq   $r0 $r0 $zero
+ret  $r0
+.data:
+data_0 .u64 0x00
@@ -0,0 +1,171 @@
+.program:
+ji   i4
+noop
+DATA_SECTION_OFFSET[0..32]
+DATA_SECTION_OFFSET[32..64]
+move $$spill $sp              ; spill area
+cfei i48
+lw   $ds $is 1
+add  $$ds $$ds $is
+addi $r0 $zero i0
+sw   $$spill $r0 i0           ; spill register
+addi $r0 $zero i1
+move $r44 $r0                 ; return value from inline asm
+addi $r0 $zero i2
+sw   $$spill $r0 i1           ; spill register
+addi $r0 $zero i3
+sw   $$spill $r0 i2           ; spill register
+addi $r0 $zero i4
+sw   $$spill $r0 i3           ; spill register
+addi $r0 $zero i5
+sw   $$spill $r0 i5           ; spill register
+addi $r0 $zero i6
+sw   $$spill $r0 i4           ; spill register
+addi $r0 $zero i7
+move $r43 $r0                 ; return value from inline asm
+addi $r0 $zero i8
+move $r42 $r0                 ; return value from inline asm
+addi $r0 $zero i9
+move $r41 $r0                 ; return value from inline asm
+addi $r0 $zero i10
+move $r40 $r0                 ; return value from inline asm
+addi $r0 $zero i11
+move $r39 $r0                 ; return value from inline asm
+addi $r0 $zero i12
+move $r38 $r0                 ; return value from inline asm
+addi $r0 $zero i13
+move $r37 $r0                 ; return value from inline asm
+addi $r0 $zero i14
+move $r36 $r0                 ; return value from inline asm
+addi $r0 $zero i15
+move $r35 $r0                 ; return value from inline asm
+addi $r0 $zero i16
+move $r34 $r0                 ; return value from inline asm
+addi $r0 $zero i17
+move $r33 $r0                 ; return value from inline asm
+addi $r0 $zero i18
+move $r32 $r0                 ; return value from inline asm
+addi $r0 $zero i19
+move $r31 $r0                 ; return value from inline asm
+addi $r0 $zero i20
+move $r30 $r0                 ; return value from inline asm
+addi $r0 $zero i21
+move $r29 $r0                 ; return value from inline asm
+addi $r0 $zero i22
+move $r28 $r0                 ; return value from inline asm
+addi $r0 $zero i23
+move $r27 $r0                 ; return value from inline asm
+addi $r0 $zero i24
+move $r26 $r0                 ; return value from inline asm
+addi $r0 $zero i25
+move $r25 $r0                 ; return value from inline asm
+addi $r0 $zero i26
+move $r24 $r0                 ; return value from inline asm
+addi $r0 $zero i27
+move $r23 $r0                 ; return value from inline asm
+addi $r0 $zero i28
+move $r22 $r0                 ; return value from inline asm
+addi $r0 $zero i29
+move $r21 $r0                 ; return value from inline asm
+addi $r0 $zero i30
+move $r20 $r0                 ; return value from inline asm
+addi $r0 $zero i31
+move $r19 $r0                 ; return value from inline asm
+addi $r0 $zero i32
+move $r18 $r0                 ; return value from inline asm
+addi $r0 $zero i33
+move $r17 $r0                 ; return value from inline asm
+addi $r0 $zero i34
+move $r16 $r0                 ; return value from inline asm
+addi $r0 $zero i35
+move $r15 $r0                 ; return value from inline asm
+addi $r0 $zero i36
+move $r14 $r0                 ; return value from inline asm
+addi $r0 $zero i37
+move $r13 $r0                 ; return value from inline asm
+addi $r0 $zero i38
+move $r12 $r0                 ; return value from inline asm
+addi $r0 $zero i39
+move $r11 $r0                 ; return value from inline asm
+addi $r0 $zero i40
+move $r10 $r0                 ; return value from inline asm
+addi $r0 $zero i41
+move $r9 $r0                  ; return value from inline asm
+addi $r0 $zero i42
+move $r8 $r0                  ; return value from inline asm
+addi $r0 $zero i43
+move $r7 $r0                  ; return value from inline asm
+addi $r0 $zero i44
+move $r6 $r0                  ; return value from inline asm
+addi $r0 $zero i45
+move $r5 $r0                  ; return value from inline asm
+addi $r0 $zero i46
+move $r4 $r0                  ; return value from inline asm
+addi $r0 $zero i47
+move $r3 $r0                  ; return value from inline asm
+addi $r0 $zero i48
+move $r2 $r0                  ; return value from inline asm
+addi $r0 $zero i49
+move $r1 $r0                  ; return value from inline asm
+lw   $r0 $$spill i0           ; reload spilled register
+add  $r0 $r0 $r44
+move $r44 $r0                 ; return value from inline asm
+lw   $r0 $$spill i1           ; reload spilled register
+add  $r0 $r44 $r0
+move $r44 $r0                 ; return value from inline asm
+lw   $r0 $$spill i2           ; reload spilled register
+add  $r0 $r44 $r0
+move $r44 $r0                 ; return value from inline asm
+lw   $r0 $$spill i3           ; reload spilled register
+add  $r0 $r44 $r0
+move $r44 $r0                 ; return value from inline asm
+lw   $r0 $$spill i5           ; reload spilled register
+add  $r0 $r44 $r0
+move $r44 $r0                 ; return value from inline asm
+lw   $r0 $$spill i4           ; reload spilled register
+add  $r0 $r44 $r0
+add  $r0 $r0 $r43
+add  $r0 $r0 $r42
+add  $r0 $r0 $r41
+add  $r0 $r0 $r40
+add  $r0 $r0 $r39
+add  $r0 $r0 $r38
+add  $r0 $r0 $r37
+add  $r0 $r0 $r36
+add  $r0 $r0 $r35
+add  $r0 $r0 $r34
+add  $r0 $r0 $r33
+add  $r0 $r0 $r32
+add  $r0 $r0 $r31
+add  $r0 $r0 $r30
+add  $r0 $r0 $r29
+add  $r0 $r0 $r28
+add  $r0 $r0 $r27
+add  $r0 $r0 $r26
+add  $r0 $r0 $r25
+add  $r0 $r0 $r24
+add  $r0 $r0 $r23
+add  $r0 $r0 $r22
+add  $r0 $r0 $r21
+add  $r0 $r0 $r20
+add  $r0 $r0 $r19
+add  $r0 $r0 $r18
+add  $r0 $r0 $r17
+add  $r0 $r0 $r16
+add  $r0 $r0 $r15
+add  $r0 $r0 $r14
+add  $r0 $r0 $r13
+add  $r0 $r0 $r12
+add  $r0 $r0 $r11
+add  $r0 $r0 $r10
+add  $r0 $r0 $r9
+add  $r0 $r0 $r8
+add  $r0 $r0 $r7
+add  $r0 $r0 $r6
+add  $r0 $r0 $r5
+add  $r0 $r0 $r4
+add  $r0 $r0 $r3
+add  $r0 $r0 $r2
+add  $r0 $r0 $r1
+ret  $r0
+.data:
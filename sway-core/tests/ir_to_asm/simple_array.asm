@@ -5,33 +5,33 @@ DATA_SECTION_OFFSET[0..32]
 DATA_SECTION_OFFSET[32..64]
 lw   $ds $is 1
 add  $$ds $$ds $is
-move $r0 $sp
-cfei i24
-move $r1 $sp
-cfei i24
-lw   $r2 data_0               ; literal instantiation
-lw   $r3 data_1               ; literal instantiation
-muli $r3 $r3 i8               ; insert_element relative offset
-add  $r4 $r1 $r3              ; insert_element absolute offset
-sw   $r4 $r2 i0               ; insert_element
-lw   $r2 data_2               ; literal instantiation
-lw   $r3 data_3               ; literal instantiation
-muli $r3 $r3 i8               ; insert_element relative offset
-add  $r4 $r1 $r3              ; insert_element absolute offset
-sw   $r4 $r2 i0               ; insert_element
-lw   $r2 data_0               ; literal instantiation
-lw   $r3 data_4               ; literal instantiation
-muli $r3 $r3 i8               ; insert_element relative offset
-add  $r4 $r1 $r3              ; insert_element absolute offset
-sw   $r4 $r2 i0               ; insert_element
-addi $r2 $r0 i0               ; store get offset
-mcpi $r2 $r1 i24              ; store value
-addi $r1 $r0 i0               ; load address
+move $r3 $sp
+cfei i48
+addi $r2 $r3 i24              ; get result memory
+lw   $r1 data_0               ; literal instantiation
+lw   $r0 data_1               ; literal instantiation
+muli $r0 $r0 i8               ; insert_element relative offset
+add  $r0 $r2 $r0              ; insert_element absolute offset
+sw   $r0 $r1 i0               ; insert_element
+lw   $r1 data_2               ; literal instantiation
+lw   $r0 data_3               ; literal instantiation
+muli $r0 $r0 i8               ; insert_element relative offset
+add  $r0 $r2 $r0              ; insert_element absolute offset
+sw   $r0 $r1 i0               ; insert_element
+lw   $r1 data_0               ; literal instantiation
+lw   $r0 data_4               ; literal instantiation
+muli $r0 $r0 i8               ; insert_element relative offset
+add  $r0 $r2 $r0              ; insert_element absolute offset
+sw   $r0 $r1 i0               ; insert_element
+addi $r0 $r3 i0               ; store get offset
+mcpi $r0 $r2 i24              ; store value
+addi $r1 $r3 i0               ; load address
 lw   $r0 data_3               ; literal instantiation
 muli $r0 $r0 i8               ; extract_element relative offset
-add  $r2 $r1 $r0              ; extract_element absolute offset
-lw   $r0 $r2 i0               ; extract_element
+add  $r0 $r1 $r0              ; extract_element absolute offset
+lw   $r0 $r0 i0               ; extract_element
 ret  $r0
+noop                          ; word-alignment of data section
 .data:
 data_0 .bool 0x00
 data_1 .u64 0x00
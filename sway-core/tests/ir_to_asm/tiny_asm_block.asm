@@ -6,6 +6,6 @@ DATA_SECTION_OFFSET[32..64]
 lw   $ds $is 1
 add  $$ds $$ds $is
 bhei $r0
-move $r1 $r0                  ; return value from inline asm
-ret  $r1
+ret  $r0
+noop                          ; word-alignment of data section
 .data:
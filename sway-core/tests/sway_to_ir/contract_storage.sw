@@ -0,0 +1,21 @@
+contract;
+
+abi StorageTest {
+  impure fn set_supply(gas: u64, coins: u64, asset_id: b256, supply: u64);
+  impure fn get_owner(gas: u64, coins: u64, asset_id: b256, input: ()) -> b256;
+}
+
+storage {
+  supply: u64 = 0,
+  owner: b256 = 0x0000000000000000000000000000000000000000000000000000000000000000,
+}
+
+impl StorageTest for Contract {
+  impure fn set_supply(gas: u64, coins: u64, asset_id: b256, supply: u64) {
+    storage.supply = supply;
+  }
+
+  impure fn get_owner(gas: u64, coins: u64, asset_id: b256, input: ()) -> b256 {
+    storage.owner
+  }
+}
@@ -0,0 +1,12 @@
+script;
+
+fn id<T>(x: T) -> T {
+    x
+}
+
+fn main() -> bool {
+    let a: u64 = 0;
+    id(a);
+    id(a);
+    id(true)
+}
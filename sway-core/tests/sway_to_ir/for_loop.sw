@@ -0,0 +1,18 @@
+script;
+
+fn main() -> u64 {
+    let mut a = 0;
+    for i in 0..10 {
+        if asm(r1: i, r2: 2, r3) { eq r3 r1 r2; r3: bool } {
+            continue;
+        };
+        if asm(r1: i, r2: 5, r3) { eq r3 r1 r2; r3: bool } {
+            break;
+        };
+        a = i;
+    }
+    for x in [1, 2] {
+        a = x;
+    }
+    a
+}
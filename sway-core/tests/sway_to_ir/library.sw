@@ -0,0 +1,23 @@
+library test_lib;
+
+pub struct Point {
+    x: u64,
+    y: u64,
+}
+
+impl Point {
+    fn x(self) -> u64 {
+        self.x
+    }
+}
+
+pub fn origin() -> Point {
+    Point {
+        x: 0,
+        y: 0,
+    }
+}
+
+pub fn id<T>(t: T) -> T {
+    t
+}
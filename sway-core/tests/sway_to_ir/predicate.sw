@@ -0,0 +1,12 @@
+predicate;
+
+fn is_zero(x: u64) -> bool {
+    asm(r1: x, r2) {
+        eq r2 r1 zero;
+        r2: bool
+    }
+}
+
+fn main() -> bool {
+    is_zero(0)
+}
@@ -0,0 +1,34 @@
+script;
+
+fn less_than(a: u64, b: u64) -> bool {
+    asm(r1: a, r2: b, r3) {
+        lt r3 r1 r2;
+        r3: bool
+    }
+}
+
+fn minus(a: u64, b: u64) -> u64 {
+    asm(r1: a, r2: b, r3) {
+        sub r3 r1 r2;
+        r3: u64
+    }
+}
+
+fn times(a: u64, b: u64) -> u64 {
+    asm(r1: a, r2: b, r3) {
+        mul r3 r1 r2;
+        r3: u64
+    }
+}
+
+fn factorial(n: u64) -> u64 {
+    if less_than(n, 2) {
+        1
+    } else {
+        times(n, factorial(minus(n, 1)))
+    }
+}
+
+fn main() -> u64 {
+    factorial(5)
+}
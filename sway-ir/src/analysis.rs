@@ -0,0 +1,8 @@
+//! A collection of analyses over the IR.
+//!
+//! Analyses don't modify the IR, they compute information about it which is used by the
+//! optimization passes.  Any modification to the IR may invalidate an analysis, in which case it
+//! must be computed again.
+
+pub mod dominator;
+pub use dominator::*;
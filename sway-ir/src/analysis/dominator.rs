@@ -0,0 +1,223 @@
+//! Dominator tree analysis.
+//!
+//! A block `a` _dominates_ a block `b` if every path from the entry block to `b` passes through
+//! `a`.  Every block reachable from the entry block, other than the entry block itself, has an
+//! _immediate dominator_, the closest of those which dominate it, and so they form a tree rooted
+//! at the entry block.
+//!
+//! The _dominance frontier_ of a block `a` is where its dominance ends: the blocks which `a`
+//! doesn't strictly dominate but which have a predecessor which `a` does dominate.  These are
+//! where a value defined in `a` may meet other values from other paths, and need a phi.
+//!
+//! The tree is computed using the algorithm from 'A Simple, Fast Dominance Algorithm' by Cooper,
+//! Harvey and Kennedy.  Blocks which aren't reachable from the entry block aren't in the tree.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::{block::Block, context::Context, function::Function};
+
+/// The dominator tree for a [`Function`].
+pub struct DominatorTree {
+    // The reachable blocks in reverse postorder, starting with the entry block.  Internally the
+    // blocks are referred to by their index in this order.
+    blocks: Vec<Block>,
+    indices: HashMap<Block, usize>,
+    // The immediate dominator of each block.  The entry block's is itself.
+    idoms: Vec<usize>,
+    children: Vec<Vec<Block>>,
+    frontiers: Vec<Vec<Block>>,
+}
+
+impl DominatorTree {
+    /// Compute the dominator tree for `function`.
+    pub fn new(context: &Context, function: &Function) -> Self {
+        let blocks = reverse_postorder(context, function);
+        let indices = blocks
+            .iter()
+            .enumerate()
+            .map(|(idx, block)| (*block, idx))
+            .collect::<HashMap<_, _>>();
+
+        // Predecessors which aren't reachable are ignored.
+        let mut preds = vec![Vec::new(); blocks.len()];
+        for (idx, block) in blocks.iter().enumerate() {
+            for succ in block.successors(context) {
+                preds[indices[&succ]].push(idx);
+            }
+        }
+
+        // Visiting the blocks in reverse postorder means a block's predecessors have been visited
+        // before it, except along back edges, which is why this usually converges quickly.
+        let mut idoms = vec![None; blocks.len()];
+        idoms[0] = Some(0);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for idx in 1..blocks.len() {
+                let new_idom = preds[idx]
+                    .iter()
+                    .filter(|pred| idoms[**pred].is_some())
+                    .fold(None, |new_idom, pred| match new_idom {
+                        None => Some(*pred),
+                        Some(new_idom) => Some(intersect(&idoms, *pred, new_idom)),
+                    });
+                if idoms[idx] != new_idom {
+                    idoms[idx] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+        let idoms = idoms
+            .into_iter()
+            .map(|idom| idom.expect("Every reachable block has a dominator."))
+            .collect::<Vec<_>>();
+
+        let mut children = vec![Vec::new(); blocks.len()];
+        for idx in 1..blocks.len() {
+            children[idoms[idx]].push(blocks[idx]);
+        }
+
+        // A block is in the frontier of each of its predecessors, and their dominators, up to but
+        // not including its own immediate dominator.
+        let mut frontiers = vec![BTreeSet::new(); blocks.len()];
+        for (idx, block_preds) in preds.iter().enumerate() {
+            if block_preds.len() < 2 {
+                continue;
+            }
+            for pred in block_preds {
+                let mut runner = *pred;
+                while runner != idoms[idx] {
+                    frontiers[runner].insert(idx);
+                    runner = idoms[runner];
+                }
+            }
+        }
+        let frontiers = frontiers
+            .into_iter()
+            .map(|frontier| frontier.into_iter().map(|idx| blocks[idx]).collect())
+            .collect();
+
+        DominatorTree {
+            blocks,
+            indices,
+            idoms,
+            children,
+            frontiers,
+        }
+    }
+
+    /// Return the blocks reachable from the entry block in reverse postorder.  A block always
+    /// comes after its dominators in this order.
+    pub fn reverse_postorder(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    /// Whether `block` is reachable from the entry block.
+    pub fn is_reachable(&self, block: &Block) -> bool {
+        self.indices.contains_key(block)
+    }
+
+    /// Get the immediate dominator of `block`.
+    ///
+    /// Returns `None` for the entry block and for unreachable blocks.
+    pub fn immediate_dominator(&self, block: &Block) -> Option<Block> {
+        match self.indices.get(block) {
+            Some(idx) if *idx != 0 => Some(self.blocks[self.idoms[*idx]]),
+            _otherwise => None,
+        }
+    }
+
+    /// Whether `dominator` dominates `block`.  Every reachable block dominates itself.
+    pub fn dominates(&self, dominator: &Block, block: &Block) -> bool {
+        match (self.indices.get(dominator), self.indices.get(block)) {
+            (Some(dom_idx), Some(idx)) => {
+                // A dominator always comes before the blocks it dominates in reverse postorder.
+                let mut idx = *idx;
+                while idx > *dom_idx {
+                    idx = self.idoms[idx];
+                }
+                idx == *dom_idx
+            }
+            _otherwise => false,
+        }
+    }
+
+    /// Get the blocks which `block` immediately dominates, i.e., its children in the tree.
+    pub fn children(&self, block: &Block) -> &[Block] {
+        self.indices
+            .get(block)
+            .map(|idx| self.children[*idx].as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Get the dominance frontier of `block`, in reverse postorder.
+    pub fn dominance_frontier(&self, block: &Block) -> &[Block] {
+        self.indices
+            .get(block)
+            .map(|idx| self.frontiers[*idx].as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Get the iterated dominance frontier of `blocks`, in reverse postorder.  This is the
+    /// dominance frontier of the blocks, and of the blocks in that frontier, and so on.
+    pub fn iterated_dominance_frontier<I: IntoIterator<Item = Block>>(
+        &self,
+        blocks: I,
+    ) -> Vec<Block> {
+        let mut frontier = HashSet::new();
+        let mut worklist = blocks.into_iter().collect::<Vec<_>>();
+        while let Some(block) = worklist.pop() {
+            for frontier_block in self.dominance_frontier(&block) {
+                if frontier.insert(*frontier_block) {
+                    worklist.push(*frontier_block);
+                }
+            }
+        }
+        self.blocks
+            .iter()
+            .filter(|block| frontier.contains(block))
+            .copied()
+            .collect()
+    }
+}
+
+// Walk up the tree from two blocks until they meet at their nearest common dominator.
+fn intersect(idoms: &[Option<usize>], mut a: usize, mut b: usize) -> usize {
+    while a != b {
+        while a > b {
+            a = idoms[a].unwrap();
+        }
+        while b > a {
+            b = idoms[b].unwrap();
+        }
+    }
+    a
+}
+
+fn reverse_postorder(context: &Context, function: &Function) -> Vec<Block> {
+    // Successors are popped from the end of each list, so they're reversed to visit them in order.
+    let successors = |block: &Block| {
+        let mut succs = block.successors(context);
+        succs.reverse();
+        succs
+    };
+    let entry_block = function.get_entry_block(context);
+    let mut visited = HashSet::from([entry_block]);
+    let mut stack = vec![(entry_block, successors(&entry_block))];
+    let mut postorder = Vec::new();
+    while let Some((block, succs)) = stack.last_mut() {
+        match succs.pop() {
+            Some(succ) => {
+                if visited.insert(succ) {
+                    stack.push((succ, successors(&succ)));
+                }
+            }
+            None => {
+                postorder.push(*block);
+                stack.pop();
+            }
+        }
+    }
+    postorder.reverse();
+    postorder
+}
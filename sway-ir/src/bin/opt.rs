@@ -3,7 +3,7 @@ use std::{
     io::{BufReader, BufWriter, Error, ErrorKind, Read, Write},
 };
 
-use sway_ir::{function::Function, optimize, Context};
+use sway_ir::{PassManager, PassPipeline};
 
 // -------------------------------------------------------------------------------------------------
 
@@ -11,7 +11,8 @@ fn main() -> std::io::Result<()> {
     let str_to_err = |msg| Error::new(ErrorKind::Other, msg);
 
     // Build the config from the command line.
-    let config = ConfigBuilder::build(std::env::args()).map_err(&str_to_err)?;
+    let pass_mgr = PassManager::with_standard_passes();
+    let config = ConfigBuilder::build(&pass_mgr, std::env::args()).map_err(&str_to_err)?;
 
     // Read the input file, or standard in.
     let input_str = read_from_input(&config.input_path)?;
@@ -20,12 +21,14 @@ fn main() -> std::io::Result<()> {
     let mut ir = sway_ir::parser::parse(&input_str).map_err(&str_to_err)?;
 
     // Perform optimisation passes in order.
-    for pass in config.passes {
-        match pass.name.as_ref() {
-            "inline" => perform_inline(&mut ir).map_err(&str_to_err)?,
-            _otherwise => unreachable!("Unknown pass name: {}", pass.name),
-        };
-    }
+    let pipeline = config
+        .passes
+        .iter()
+        .fold(PassPipeline::new(), |pipeline, pass| {
+            pipeline.add_pass(&pass.name)
+        })
+        .verify_each(config.verify_each);
+    pass_mgr.run(&mut ir, &pipeline).map_err(&str_to_err)?;
 
     // Write the output file or standard out.
     write_to_output(ir, &config.output_path)
@@ -62,17 +65,6 @@ fn write_to_output<S: Into<String>>(ir_str: S, path_str: &Option<String>) -> std
 
 // -------------------------------------------------------------------------------------------------
 
-fn perform_inline(ir: &mut Context) -> Result<bool, String> {
-    // For now we inline everything into `main()`.  Eventually we can be more selective.
-    let main_fn = ir
-        .functions
-        .iter()
-        .find_map(|(idx, fc)| if fc.name == "main" { Some(idx) } else { None })
-        .unwrap();
-    optimize::inline_all_function_calls(ir, &Function(main_fn))
-}
-
-// -------------------------------------------------------------------------------------------------
 // Using a bespoke CLI parser since the order in which passes are specified is important.
 
 #[derive(Default)]
@@ -80,7 +72,7 @@ struct Config {
     input_path: Option<String>,
     output_path: Option<String>,
 
-    _verify_each: bool,
+    verify_each: bool,
     _time_passes: bool,
     _stats: bool,
 
@@ -105,17 +97,19 @@ impl From<&str> for Pass {
 
 // This is a little clumsy in that it needs to consume items from the iterator carefully in each
 // method to ensure we don't enter a weird state.
-struct ConfigBuilder<I: Iterator<Item = String>> {
+struct ConfigBuilder<'a, I: Iterator<Item = String>> {
+    pass_mgr: &'a PassManager,
     next: Option<String>,
     rest: I,
     cfg: Config,
 }
 
-impl<I: Iterator<Item = String>> ConfigBuilder<I> {
-    fn build(mut rest: I) -> Result<Config, String> {
+impl<'a, I: Iterator<Item = String>> ConfigBuilder<'a, I> {
+    fn build(pass_mgr: &'a PassManager, mut rest: I) -> Result<Config, String> {
         rest.next(); // Skip the first arg which is the binary name.
         let next = rest.next();
         ConfigBuilder {
+            pass_mgr,
             next,
             rest,
             cfg: Config::default(),
@@ -131,8 +125,12 @@ impl<I: Iterator<Item = String>> ConfigBuilder<I> {
                 match opt.as_str() {
                     "-i" => self.build_input(),
                     "-o" => self.build_output(),
+                    "-verify-each" => {
+                        self.cfg.verify_each = true;
+                        self.build_root()
+                    }
 
-                    "inline" => self.build_inline_pass(),
+                    _otherwise if self.pass_mgr.lookup(&opt).is_some() => self.build_pass(opt),
 
                     _otherwise => Err(format!("Unrecognised option '{}'.", opt)),
                 }
@@ -162,11 +160,10 @@ impl<I: Iterator<Item = String>> ConfigBuilder<I> {
         }
     }
 
-    fn build_inline_pass(mut self) -> Result<Config, String> {
-        // No args yet.  Eventually we should allow specifying which functions are to be inlined
-        // or which functions are to have all embedded calls inlined.
-        self.cfg.passes.push("inline".into());
-        self.next = self.rest.next();
+    fn build_pass(mut self, name: String) -> Result<Config, String> {
+        // No pass takes any args yet.  Eventually we should allow specifying e.g., which functions
+        // are to be inlined.
+        self.cfg.passes.push(name.as_str().into());
         self.build_root()
     }
 }
@@ -4,7 +4,8 @@
 //! instruction or _terminator_.  Terminators are either branches or a return instruction and are
 //! the last instruction in the block.
 //!
-//! Blocks also contain a single 'phi' instruction at its start.  In
+//! Blocks also contain one or more 'phi' instructions at their start, the first of which is always
+//! present even if it has no incoming values.  In
 //! [SSA](https://en.wikipedia.org/wiki/Static_single_assignment_form) form 'phi' instructions are
 //! used to merge values from preceding blocks.
 //!
@@ -64,22 +65,56 @@ impl Block {
         context.blocks[self.0].label.clone()
     }
 
-    /// Get the phi instruction for this block.
+    /// Get the first phi instruction for this block.
     pub fn get_phi(&self, context: &Context) -> Value {
         context.blocks[self.0].instructions[0]
     }
 
+    /// Get all the phi instructions for this block, in order.
+    pub fn get_phis(&self, context: &Context) -> Vec<Value> {
+        context.blocks[self.0]
+            .instructions
+            .iter()
+            .take_while(|ins_val| {
+                matches!(
+                    context.values[ins_val.0],
+                    ValueContent::Instruction(Instruction::Phi(_))
+                )
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Add a new empty phi instruction to this block, after any existing phis.
+    pub fn new_phi(&self, context: &mut Context) -> Value {
+        let phi_idx = self.get_phis(context).len();
+        let phi_val = Value::new_instruction(context, Instruction::Phi(Vec::new()));
+        context.blocks[self.0].instructions.insert(phi_idx, phi_val);
+        phi_val
+    }
+
     /// Add a new phi entry to this block.
     ///
     /// This indicates that if control flow comes from `from_block` then the phi instruction should
     /// use `phi_value`.
     pub fn add_phi(&self, context: &mut Context, from_block: Block, phi_value: Value) {
         let phi_val = self.get_phi(context);
+        self.add_phi_entry(context, phi_val, from_block, phi_value);
+    }
+
+    /// Add a new entry to `phi_val`, which must be one of this block's phi instructions.
+    pub fn add_phi_entry(
+        &self,
+        context: &mut Context,
+        phi_val: Value,
+        from_block: Block,
+        phi_value: Value,
+    ) {
         match &mut context.values[phi_val.0] {
             ValueContent::Instruction(Instruction::Phi(list)) => {
                 list.push((from_block, phi_value));
             }
-            _ => unreachable!("First value in block instructions is not a phi."),
+            _ => unreachable!("Phi value must be a PHI instruction."),
         }
     }
 
@@ -101,26 +136,56 @@ impl Block {
         }
     }
 
-    /// Replace a block reference in the phi instruction.
+    /// Get the values from every phi instruction which correlate to `from_block`.
     ///
-    /// Any reference to `old_source` will be replace with `new_source` in the list of phi values.
+    /// Returns pairs of the phi instruction and its value, for those phis which have one.
+    pub fn get_phi_vals_coming_from(
+        &self,
+        context: &Context,
+        from_block: &Block,
+    ) -> Vec<(Value, Value)> {
+        self.get_phis(context)
+            .into_iter()
+            .filter_map(|phi_val| match &context.values[phi_val.0] {
+                ValueContent::Instruction(Instruction::Phi(pairs)) => pairs
+                    .iter()
+                    .find(|(block, _)| block == from_block)
+                    .map(|(_, value)| (phi_val, *value)),
+                _ => unreachable!("Phi value must be a PHI instruction."),
+            })
+            .collect()
+    }
+
+    /// Replace a block reference in the phi instructions.
+    ///
+    /// Any reference to `old_source` will be replace with `new_source` in the lists of phi values.
     pub fn update_phi_source_block(
         &self,
         context: &mut Context,
         old_source: Block,
         new_source: Block,
     ) {
-        let phi_val = self.get_phi(context);
-        if let ValueContent::Instruction(Instruction::Phi(ref mut pairs)) =
-            &mut context.values[phi_val.0]
-        {
-            for (block, _) in pairs {
-                if *block == old_source {
-                    *block = new_source;
+        for phi_val in self.get_phis(context) {
+            if let ValueContent::Instruction(Instruction::Phi(ref mut pairs)) =
+                &mut context.values[phi_val.0]
+            {
+                for (block, _) in pairs {
+                    if *block == old_source {
+                        *block = new_source;
+                    }
                 }
             }
-        } else {
-            unreachable!("Phi value must be a PHI instruction.");
+        }
+    }
+
+    /// Remove the entries for `from_block` from the phi instructions, if there are any.
+    pub fn remove_phi_val_coming_from(&self, context: &mut Context, from_block: &Block) {
+        for phi_val in self.get_phis(context) {
+            if let ValueContent::Instruction(Instruction::Phi(ref mut pairs)) =
+                &mut context.values[phi_val.0]
+            {
+                pairs.retain(|(block, _)| block != from_block);
+            }
         }
     }
 
@@ -138,6 +203,51 @@ impl Block {
         })
     }
 
+    /// Get the blocks which the terminator may branch to.
+    ///
+    /// A conditional branch with the same destination for both cases has it listed just once.
+    pub fn successors(&self, context: &Context) -> Vec<Block> {
+        match self.get_term_inst(context) {
+            Some(Instruction::Branch(to_block)) => vec![*to_block],
+            Some(Instruction::ConditionalBranch {
+                true_block,
+                false_block,
+                ..
+            }) => {
+                if true_block == false_block {
+                    vec![*true_block]
+                } else {
+                    vec![*true_block, *false_block]
+                }
+            }
+            _otherwise => Vec::new(),
+        }
+    }
+
+    /// Replace any branch to `old_succ` in the terminator with a branch to `new_succ`.
+    ///
+    /// **NOTE:** The phi instructions in the successors are not updated.
+    pub fn replace_successor(&self, context: &mut Context, old_succ: Block, new_succ: Block) {
+        let term_val = *context.blocks[self.0].instructions.last().unwrap();
+        let replace = |block: &mut Block| {
+            if *block == old_succ {
+                *block = new_succ;
+            }
+        };
+        match &mut context.values[term_val.0] {
+            ValueContent::Instruction(Instruction::Branch(to_block)) => replace(to_block),
+            ValueContent::Instruction(Instruction::ConditionalBranch {
+                true_block,
+                false_block,
+                ..
+            }) => {
+                replace(true_block);
+                replace(false_block);
+            }
+            _otherwise => (),
+        }
+    }
+
     /// Replace a value within this block.
     ///
     /// For every instruction within the block, any reference to `old_val` is replaced with
@@ -150,7 +260,7 @@ impl Block {
 
     /// Remove an instruction from this block.
     ///
-    /// **NOTE:** We must be very careful!  We mustn't remove the phi or the terminator.  Some
+    /// **NOTE:** We must be very careful!  We mustn't remove the first phi or the terminator.  Some
     /// extra checks should probably be performed here to avoid corruption! Using `Vec::remove()`
     /// is also O(n) which we may want to avoid someday.
     pub fn remove_instruction(&self, context: &mut Context, instr_val: Value) {
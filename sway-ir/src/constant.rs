@@ -20,6 +20,8 @@ pub enum ConstantValue {
     Unit,
     Bool(bool),
     Uint(u64),
+    /// A big-endian integer wider than 64 bits, as long as its type.
+    WideUint(Vec<u8>),
     B256([u8; 32]),
     String(String),
     Array(Vec<Constant>),
@@ -63,13 +65,21 @@ impl Constant {
         }
     }
 
-    pub fn new_uint(nbits: u8, n: u64) -> Self {
+    pub fn new_uint(nbits: u16, n: u64) -> Self {
         Constant {
             ty: Type::Uint(nbits),
             value: ConstantValue::Uint(n),
         }
     }
 
+    pub fn new_wide_uint(nbits: u16, bytes: Vec<u8>) -> Self {
+        assert_eq!(bytes.len(), nbits as usize / 8);
+        Constant {
+            ty: Type::Uint(nbits),
+            value: ConstantValue::WideUint(bytes),
+        }
+    }
+
     pub fn new_b256(bytes: [u8; 32]) -> Self {
         Constant {
             ty: Type::B256,
@@ -111,10 +121,14 @@ impl Constant {
         Value::new_constant(context, Constant::new_bool(value))
     }
 
-    pub fn get_uint(context: &mut Context, nbits: u8, value: u64) -> Value {
+    pub fn get_uint(context: &mut Context, nbits: u16, value: u64) -> Value {
         Value::new_constant(context, Constant::new_uint(nbits, value))
     }
 
+    pub fn get_wide_uint(context: &mut Context, nbits: u16, value: Vec<u8>) -> Value {
+        Value::new_constant(context, Constant::new_wide_uint(nbits, value))
+    }
+
     pub fn get_b256(context: &mut Context, value: [u8; 32]) -> Value {
         Value::new_constant(context, Constant::new_b256(value))
     }
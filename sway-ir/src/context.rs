@@ -6,7 +6,7 @@
 //!
 //! It is passed around as a mutable reference to many of the Sway-IR APIs.
 
-use std::collections::HashMap;
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use generational_arena::Arena;
 
@@ -15,16 +15,17 @@ use crate::{
     block::BlockContent,
     function::FunctionContent,
     irtype::{AbiInstanceContent, Aggregate, AggregateContent},
+    metadata::{MetadataIndex, Metadatum},
     module::ModuleContent,
     module::ModuleIterator,
     pointer::PointerContent,
-    value::ValueContent,
+    value::{Value, ValueContent},
 };
 
 /// The main IR context handle.
 ///
 /// Every module, function, block and value is stored here.  Some aggregate metadata is also
-/// managed by the context.
+/// managed by the context, as is the [`Metadatum`] attached to each value.
 #[derive(Default)]
 pub struct Context {
     pub modules: Arena<ModuleContent>,
@@ -35,10 +36,14 @@ pub struct Context {
     pub aggregates: Arena<AggregateContent>,
     pub abi_instances: Arena<AbiInstanceContent>,
     pub asm_blocks: Arena<AsmBlockContent>,
+    pub metadata: Arena<Metadatum>,
 
     pub(super) aggregate_names: HashMap<String, Aggregate>,
     aggregate_symbols: HashMap<Aggregate, HashMap<String, u64>>,
 
+    pub(crate) value_metadata: HashMap<Value, MetadataIndex>,
+    pub(crate) metadata_file_locs: HashMap<Arc<PathBuf>, MetadataIndex>,
+
     next_unique_sym_tag: u64,
 }
 
@@ -23,6 +23,15 @@ use crate::{
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub struct Function(pub generational_arena::Index);
 
+/// A hint to the inliner about calls to a [`Function`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Inline {
+    /// Inline every call to the function, unless it is recursive.
+    Always,
+    /// Never inline calls to the function.
+    Never,
+}
+
 #[doc(hidden)]
 pub struct FunctionContent {
     pub name: String,
@@ -31,6 +40,7 @@ pub struct FunctionContent {
     pub blocks: Vec<Block>,
     pub is_public: bool,
     pub selector: Option<[u8; 4]>,
+    pub inline: Option<Inline>,
 
     pub local_storage: BTreeMap<String, Pointer>, // BTree rather than Hash for deterministic ordering.
 
@@ -65,6 +75,7 @@ impl Function {
             blocks: Vec::new(),
             is_public,
             selector,
+            inline: None,
             local_storage: BTreeMap::new(),
             next_label_idx: 0,
         };
@@ -137,6 +148,15 @@ impl Function {
             .ok_or_else(|| "Cannot insert block after other, not found in function.".into())
     }
 
+    /// Remove a [`Block`] from this function.
+    ///
+    /// **NOTE:** The block must no longer be referred to by any branch or phi instruction.
+    pub fn remove_block(&self, context: &mut Context, block: &Block) {
+        context.functions[self.0]
+            .blocks
+            .retain(|func_block| func_block != block);
+    }
+
     /// Get a new unique block label.
     ///
     /// If `hint` is `None` then the label will be in the form `"blockN"` where N is an
@@ -192,6 +212,16 @@ impl Function {
         context.functions[self.0].selector
     }
 
+    /// Return the hint for inlining calls to this function, if it has one.
+    pub fn get_inline(&self, context: &Context) -> Option<Inline> {
+        context.functions[self.0].inline
+    }
+
+    /// Set the hint for inlining calls to this function.
+    pub fn set_inline(&self, context: &mut Context, inline: Option<Inline>) {
+        context.functions[self.0].inline = inline;
+    }
+
     /// Get an arg value by name, if found.
     pub fn get_arg(&self, context: &Context, name: &str) -> Option<Value> {
         context.functions[self.0]
@@ -1,21 +1,29 @@
 //! Instructions for data manipulation, but mostly control flow.
 //!
 //! Since Sway abstracts most low level operations behind traits they are translated into function
-//! calls which contain ASM blocks.  Therefore _at this stage_ Sway-IR doesn't need low level
-//! operations such as binary arithmetic and logic operators.
+//! calls which contain ASM blocks.  Therefore Sway-IR mostly doesn't need low level operations
+//! such as binary arithmetic and logic operators.
+//!
+//! The exception is for types which the core library has no implementations for, such as the
+//! `u128` and `u256` integers which don't fit in a register.  Their operators are built into the
+//! compiler and are represented by [`Instruction::BinaryOp`] and [`Instruction::Cmp`], which are
+//! lowered to multi-word instruction sequences during codegen.
 //!
 //! Unfortuntely, using opaque ASM blocks limits the effectiveness of certain optimizations and
 //! this should be addressed in the future, perhaps by using compiler intrinsic calls instead of
 //! the ASM blocks where possible.
 
+use std::collections::{HashMap, HashSet};
+
 use crate::{
     asm::{AsmArg, AsmBlock, AsmInstruction},
     block::Block,
+    constant::{Constant, ConstantValue},
     context::Context,
     function::Function,
     irtype::{Aggregate, Type},
     pointer::Pointer,
-    value::Value,
+    value::{Value, ValueContent},
 };
 use sway_types::ident::Ident;
 
@@ -23,10 +31,19 @@ use sway_types::ident::Ident;
 pub enum Instruction {
     /// An opaque list of ASM instructions passed directly to codegen.
     AsmBlock(AsmBlock, Vec<AsmArg>),
+    /// A binary arithmetic or logic operation on two integers of the same type, except for shifts
+    /// whose second argument may be any integer.
+    BinaryOp {
+        op: BinaryOpKind,
+        arg1: Value,
+        arg2: Value,
+    },
     /// An unconditional jump.
     Branch(Block),
     /// A function call with a list of arguments.
     Call(Function, Vec<Value>),
+    /// Compare two integers of the same type, returning a `bool`.
+    Cmp(Predicate, Value, Value),
     /// A conditional jump with the boolean condition value and true or false destinations.
     ConditionalBranch {
         cond_value: Value,
@@ -67,10 +84,96 @@ pub enum Instruction {
     Phi(Vec<(Block, Value)>),
     /// Return from a function.
     Ret(Value, Type),
+    /// Read a value of type `ty` from contract storage at `key`.
+    StateLoad { key: Value, ty: Type },
+    /// Write a value to contract storage at `key`.
+    StateStore { key: Value, stored_val: Value },
     /// Write a value to a memory pointer.
     Store { ptr: Pointer, stored_val: Value },
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOpKind {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    And,
+    Or,
+    Xor,
+    /// Division of two's complement integers, rounding towards zero.
+    SDiv,
+    /// The remainder of a [`BinaryOpKind::SDiv`], with the sign of the dividend.
+    SMod,
+    Shl,
+    /// A logical shift right, filling with zeroes.
+    Shr,
+    /// An arithmetic shift right, filling with the sign bit.
+    Sar,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Predicate {
+    Equal,
+    NotEqual,
+    LessThan,
+    GreaterThan,
+    LessThanEqual,
+    GreaterThanEqual,
+    SignedLessThan,
+    SignedGreaterThan,
+    SignedLessThanEqual,
+    SignedGreaterThanEqual,
+}
+
+impl BinaryOpKind {
+    /// The name of the operation, used for printing and parsing.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BinaryOpKind::Add => "add",
+            BinaryOpKind::Sub => "sub",
+            BinaryOpKind::Mul => "mul",
+            BinaryOpKind::Div => "div",
+            BinaryOpKind::Mod => "mod",
+            BinaryOpKind::And => "and",
+            BinaryOpKind::Or => "or",
+            BinaryOpKind::Xor => "xor",
+            BinaryOpKind::SDiv => "sdiv",
+            BinaryOpKind::SMod => "smod",
+            BinaryOpKind::Shl => "shl",
+            BinaryOpKind::Shr => "shr",
+            BinaryOpKind::Sar => "sar",
+        }
+    }
+
+    /// Whether the second argument is a shift amount rather than of the same type as the first.
+    pub fn is_shift(&self) -> bool {
+        matches!(
+            self,
+            BinaryOpKind::Shl | BinaryOpKind::Shr | BinaryOpKind::Sar
+        )
+    }
+}
+
+impl Predicate {
+    /// The name of the predicate, used for printing and parsing.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Predicate::Equal => "eq",
+            Predicate::NotEqual => "ne",
+            Predicate::LessThan => "lt",
+            Predicate::GreaterThan => "gt",
+            Predicate::LessThanEqual => "le",
+            Predicate::GreaterThanEqual => "ge",
+            Predicate::SignedLessThan => "slt",
+            Predicate::SignedGreaterThan => "sgt",
+            Predicate::SignedLessThanEqual => "sle",
+            Predicate::SignedGreaterThanEqual => "sge",
+        }
+    }
+}
+
 impl Instruction {
     /// Some [`Instruction`]s can return a value, but for some a return value doesn't make sense.
     ///
@@ -79,12 +182,30 @@ impl Instruction {
     pub fn get_type(&self, context: &Context) -> Option<Type> {
         match self {
             Instruction::AsmBlock(asm_block, _) => asm_block.get_type(context),
+            Instruction::BinaryOp { arg1, .. } => arg1.get_type(context),
             Instruction::Call(function, _) => Some(context.functions[function.0].return_type),
+            Instruction::Cmp(..) => Some(Type::Bool),
             Instruction::ExtractElement { ty, .. } => ty.get_elem_type(context),
             Instruction::ExtractValue { ty, indices, .. } => ty.get_field_type(context, indices),
             Instruction::Load(ptr) => Some(context.pointers[ptr.0].ty),
-            Instruction::Phi(_alts) => {
-                unimplemented!("phi get type -- I think we should put the type in the enum.")
+            Instruction::StateLoad { ty, .. } => Some(*ty),
+            Instruction::Phi(pairs) => {
+                // A phi may merge the values of other phis, including itself in a loop, so search
+                // through them for one which isn't.
+                let mut worklist = pairs.iter().map(|(_, val)| *val).collect::<Vec<_>>();
+                let mut visited = HashSet::new();
+                while let Some(val) = worklist.pop() {
+                    if !visited.insert(val) {
+                        continue;
+                    }
+                    match &context.values[val.0] {
+                        ValueContent::Instruction(Instruction::Phi(pairs)) => {
+                            worklist.extend(pairs.iter().map(|(_, val)| *val))
+                        }
+                        _otherwise => return val.get_type(context),
+                    }
+                }
+                None
             }
 
             // These are all terminators which don't return, essentially.  No type.
@@ -98,6 +219,7 @@ impl Instruction {
             // These write values but don't return one.  If we're explicit we could return Unit.
             Instruction::InsertElement { .. } => None,
             Instruction::InsertValue { .. } => None,
+            Instruction::StateStore { .. } => None,
             Instruction::Store { .. } => None,
         }
     }
@@ -125,6 +247,16 @@ impl Instruction {
                     _otherwise => None,
                 })
             }
+            Instruction::InsertElement { ty, .. } | Instruction::InsertValue { ty, .. } => {
+                // The aggregate which was inserted into, e.g., an array literal.
+                Some(*ty)
+            }
+            Instruction::Call(function, _) => match context.functions[function.0].return_type {
+                // The aggregate returned by value, e.g., in `make_struct().field`.
+                Type::Array(aggregate) => Some(aggregate),
+                Type::Struct(aggregate) => Some(aggregate),
+                _otherwise => None,
+            },
 
             // Unknown aggregate instruction.  Adding these as we come across them...
             _otherwise => None,
@@ -133,11 +265,24 @@ impl Instruction {
 
     /// Replace `old_val` with `new_val` if it is referenced by this instruction's arguments.
     pub fn replace_value(&mut self, old_val: Value, new_val: Value) {
-        let replace = |val: &mut Value| {
+        self.replace_operands(|val: &mut Value| {
             if val == &old_val {
                 *val = new_val
             }
-        };
+        })
+    }
+
+    /// Replace any of this instruction's arguments which are found in `replacements` with their
+    /// replacement values.
+    pub fn replace_values(&mut self, replacements: &HashMap<Value, Value>) {
+        self.replace_operands(|val: &mut Value| {
+            if let Some(new_val) = replacements.get(val) {
+                *val = *new_val
+            }
+        })
+    }
+
+    fn replace_operands<F: Fn(&mut Value)>(&mut self, replace: F) {
         match self {
             Instruction::AsmBlock(_, args) => args.iter_mut().for_each(|asm_arg| {
                 asm_arg
@@ -145,8 +290,16 @@ impl Instruction {
                     .iter_mut()
                     .for_each(|init_val| replace(init_val))
             }),
+            Instruction::BinaryOp { arg1, arg2, .. } => {
+                replace(arg1);
+                replace(arg2);
+            }
             Instruction::Branch(_) => (),
             Instruction::Call(_, args) => args.iter_mut().for_each(replace),
+            Instruction::Cmp(_, lhs, rhs) => {
+                replace(lhs);
+                replace(rhs);
+            }
             Instruction::ConditionalBranch { cond_value, .. } => replace(cond_value),
             Instruction::GetPointer(_) => (),
             Instruction::InsertElement {
@@ -175,11 +328,101 @@ impl Instruction {
             Instruction::Load(_) => (),
             Instruction::Phi(pairs) => pairs.iter_mut().for_each(|(_, val)| replace(val)),
             Instruction::Ret(ret_val, _) => replace(ret_val),
+            Instruction::StateLoad { key, .. } => replace(key),
+            Instruction::StateStore { key, stored_val } => {
+                replace(key);
+                replace(stored_val);
+            }
             Instruction::Store { stored_val, .. } => {
                 replace(stored_val);
             }
         }
     }
+
+    /// Return the values this instruction uses as its arguments, in no particular order.
+    pub fn get_operands(&self) -> Vec<Value> {
+        match self {
+            Instruction::AsmBlock(_, args) => args
+                .iter()
+                .filter_map(|asm_arg| asm_arg.initializer)
+                .collect(),
+            Instruction::BinaryOp { arg1, arg2, .. } => vec![*arg1, *arg2],
+            Instruction::Branch(_) => vec![],
+            Instruction::Call(_, args) => args.clone(),
+            Instruction::Cmp(_, lhs, rhs) => vec![*lhs, *rhs],
+            Instruction::ConditionalBranch { cond_value, .. } => vec![*cond_value],
+            Instruction::ExtractElement {
+                array, index_val, ..
+            } => vec![*array, *index_val],
+            Instruction::ExtractValue { aggregate, .. } => vec![*aggregate],
+            Instruction::GetPointer(_) => vec![],
+            Instruction::InsertElement {
+                array,
+                value,
+                index_val,
+                ..
+            } => vec![*array, *value, *index_val],
+            Instruction::InsertValue {
+                aggregate, value, ..
+            } => vec![*aggregate, *value],
+            Instruction::Load(_) => vec![],
+            Instruction::Phi(pairs) => pairs.iter().map(|(_, val)| *val).collect(),
+            Instruction::Ret(ret_val, _) => vec![*ret_val],
+            Instruction::StateLoad { key, .. } => vec![*key],
+            Instruction::StateStore { key, stored_val } => vec![*key, *stored_val],
+            Instruction::Store { stored_val, .. } => vec![*stored_val],
+        }
+    }
+
+    /// Return whether this instruction does anything other than produce a value, in which case
+    /// it may not be removed even if its value is unused.
+    ///
+    /// The insert instructions update their aggregate in place, and ASM blocks are opaque so are
+    /// assumed to have side effects.  Every block must start with a phi, so they're included too.
+    /// Dividing by zero is an error, so division and remainder have a side effect unless the
+    /// divisor is a non-zero constant.
+    pub fn may_have_side_effect(&self, context: &Context) -> bool {
+        match self {
+            Instruction::AsmBlock(..)
+            | Instruction::Branch(_)
+            | Instruction::Call(..)
+            | Instruction::ConditionalBranch { .. }
+            | Instruction::InsertElement { .. }
+            | Instruction::InsertValue { .. }
+            | Instruction::Phi(_)
+            | Instruction::Ret(..)
+            | Instruction::StateStore { .. }
+            | Instruction::Store { .. } => true,
+
+            Instruction::BinaryOp {
+                op: BinaryOpKind::Div | BinaryOpKind::Mod | BinaryOpKind::SDiv | BinaryOpKind::SMod,
+                arg2,
+                ..
+            } => !is_non_zero_constant(context, arg2),
+
+            Instruction::BinaryOp { .. }
+            | Instruction::Cmp(..)
+            | Instruction::ExtractElement { .. }
+            | Instruction::ExtractValue { .. }
+            | Instruction::GetPointer(_)
+            | Instruction::Load(_)
+            | Instruction::StateLoad { .. } => false,
+        }
+    }
+}
+
+fn is_non_zero_constant(context: &Context, value: &Value) -> bool {
+    match &context.values[value.0] {
+        ValueContent::Constant(Constant {
+            value: ConstantValue::Uint(n),
+            ..
+        }) => *n != 0,
+        ValueContent::Constant(Constant {
+            value: ConstantValue::WideUint(bytes),
+            ..
+        }) => bytes.iter().any(|byte| *byte != 0),
+        _otherwise => false,
+    }
 }
 
 /// Iterate over all [`Instruction`]s in a specific [`Block`].
@@ -255,6 +498,15 @@ impl<'a> InstructionInserter<'a> {
         asm_val
     }
 
+    pub fn binary_op(self, op: BinaryOpKind, arg1: Value, arg2: Value) -> Value {
+        let binary_op_val =
+            Value::new_instruction(self.context, Instruction::BinaryOp { op, arg1, arg2 });
+        self.context.blocks[self.block.0]
+            .instructions
+            .push(binary_op_val);
+        binary_op_val
+    }
+
     pub fn branch(self, to_block: Block, phi_value: Option<Value>) -> Value {
         let br_val = Value::new_instruction(self.context, Instruction::Branch(to_block));
         phi_value
@@ -273,6 +525,12 @@ impl<'a> InstructionInserter<'a> {
         call_val
     }
 
+    pub fn cmp(self, pred: Predicate, lhs: Value, rhs: Value) -> Value {
+        let cmp_val = Value::new_instruction(self.context, Instruction::Cmp(pred, lhs, rhs));
+        self.context.blocks[self.block.0].instructions.push(cmp_val);
+        cmp_val
+    }
+
     pub fn conditional_branch(
         self,
         cond_value: Value,
@@ -392,6 +650,24 @@ impl<'a> InstructionInserter<'a> {
         ret_val
     }
 
+    pub fn state_load(self, key: Value, ty: Type) -> Value {
+        let state_load_val =
+            Value::new_instruction(self.context, Instruction::StateLoad { key, ty });
+        self.context.blocks[self.block.0]
+            .instructions
+            .push(state_load_val);
+        state_load_val
+    }
+
+    pub fn state_store(self, key: Value, stored_val: Value) -> Value {
+        let state_store_val =
+            Value::new_instruction(self.context, Instruction::StateStore { key, stored_val });
+        self.context.blocks[self.block.0]
+            .instructions
+            .push(state_store_val);
+        state_store_val
+    }
+
     pub fn store(self, ptr: Pointer, stored_val: Value) -> Value {
         let store_val =
             Value::new_instruction(self.context, Instruction::Store { ptr, stored_val });
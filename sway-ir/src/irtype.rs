@@ -17,7 +17,7 @@ use crate::context::Context;
 pub enum Type {
     Unit,
     Bool,
-    Uint(u8),
+    Uint(u16),
     B256,
     String(u64),
     Array(Aggregate),
@@ -29,6 +29,12 @@ pub enum Type {
 }
 
 impl Type {
+    /// Whether this is an integer too wide to fit in a register.  These are kept in memory, like
+    /// a `b256`, rather than being promoted to registers.
+    pub fn is_wide_uint(&self) -> bool {
+        matches!(self, Type::Uint(nbits) if *nbits > 64)
+    }
+
     /// Return a string representation of type, used for printing.
     pub fn as_string(&self, context: &Context) -> String {
         let sep_types_str = |agg_content: &AggregateContent, sep: &str| {
@@ -15,9 +15,11 @@
 //! graph to represent program control flow.
 //!
 //! Other important data types are [`Value`], [`Type`] and [`Constant`].  Function arguments, local
-//! variables, instructions and constants are all [`Value`]s.
+//! variables, instructions and constants are all [`Value`]s.  Values may also have [metadata]
+//! attached, such as the source span they were compiled from.
 //!
-//! The optimization passes are found in the [optimize] module.
+//! The optimization passes are found in the [optimize] module, and are run by a [`PassManager`].
+//! The analyses they depend on, such as the [`DominatorTree`], are found in the [analysis] module.
 //!
 //! # Note:
 //!
@@ -37,6 +39,8 @@
 // For now it's easiest to just export absolutely everything to core_lang, we can refine the public
 // API when it's closer to finished.
 
+pub mod analysis;
+pub use analysis::*;
 pub mod asm;
 pub use asm::*;
 pub mod block;
@@ -51,12 +55,16 @@ pub mod instruction;
 pub use instruction::*;
 pub mod irtype;
 pub use irtype::*;
+pub mod metadata;
+pub use metadata::*;
 pub mod module;
 pub use module::*;
 pub mod optimize;
 pub use optimize::*;
 pub mod parser;
 pub use parser::*;
+pub mod pass_manager;
+pub use pass_manager::*;
 pub mod pointer;
 pub use pointer::*;
 pub mod printer;
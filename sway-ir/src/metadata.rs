@@ -0,0 +1,96 @@
+//! Metadata attached to IR values.
+//!
+//! Metadata is kept out of the values themselves, in a side-table in the [`Context`], so it never
+//! affects what the IR means and passes may ignore it entirely.  For now the only metadata is the
+//! source span which an instruction was compiled from, which is used to build the source map and
+//! to report errors found when generating the final code.
+//!
+//! Each [`Metadatum`] is stored once in the context and referred to by a [`MetadataIndex`].  A span
+//! refers to the file it's in by another index, so each file is only stored once.
+
+use std::{path::PathBuf, sync::Arc};
+
+use sway_types::span::Span;
+
+use crate::context::Context;
+
+/// A wrapper around an [ECS](https://github.com/fitzgen/generational-arena) handle into the
+/// [`Context`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct MetadataIndex(pub generational_arena::Index);
+
+#[derive(Clone, Debug)]
+pub enum Metadatum {
+    /// A source file and its contents.  The contents may be empty if the file couldn't be read,
+    /// e.g., when parsing IR which refers to a file which has since moved.
+    FileLocation(Arc<PathBuf>, Arc<str>),
+    /// A byte range within a file, which is referred to by its own index.
+    Span {
+        loc_idx: MetadataIndex,
+        start: usize,
+        end: usize,
+    },
+}
+
+impl MetadataIndex {
+    /// Return a new index for the file location of `path` with the contents `src`.  Each path is
+    /// only stored once.
+    pub fn from_file_location(context: &mut Context, path: Arc<PathBuf>, src: Arc<str>) -> Self {
+        if let Some(loc_idx) = context.metadata_file_locs.get(&path) {
+            return *loc_idx;
+        }
+        let loc_idx = MetadataIndex(
+            context
+                .metadata
+                .insert(Metadatum::FileLocation(path.clone(), src)),
+        );
+        context.metadata_file_locs.insert(path, loc_idx);
+        loc_idx
+    }
+
+    /// Return a new index for the source span `span`.
+    ///
+    /// Spans which don't refer to a file, such as those made up by the compiler, are ignored and
+    /// `None` is returned.
+    pub fn from_span(context: &mut Context, span: &Span) -> Option<Self> {
+        span.path.as_ref().map(|path| {
+            let loc_idx =
+                Self::from_file_location(context, path.clone(), span.span.input().clone());
+            Self::new_span(context, loc_idx, span.start(), span.end())
+        })
+    }
+
+    /// Return a new index for the span from `start` to `end` in the file at `loc_idx`.
+    pub fn new_span(
+        context: &mut Context,
+        loc_idx: MetadataIndex,
+        start: usize,
+        end: usize,
+    ) -> Self {
+        MetadataIndex(context.metadata.insert(Metadatum::Span {
+            loc_idx,
+            start,
+            end,
+        }))
+    }
+
+    /// Return the source span which this index refers to, if it is a span and it is within the
+    /// contents of its file.
+    pub fn to_span(&self, context: &Context) -> Option<Span> {
+        match &context.metadata[self.0] {
+            Metadatum::Span {
+                loc_idx,
+                start,
+                end,
+            } => match &context.metadata[loc_idx.0] {
+                Metadatum::FileLocation(path, src) => pest::Span::new(src.clone(), *start, *end)
+                    .map(|span| Span {
+                        span,
+                        path: Some(path.clone()),
+                    }),
+                Metadatum::Span { .. } => None,
+            },
+            Metadatum::FileLocation(..) => None,
+        }
+    }
+}
@@ -104,13 +104,11 @@ fn combine_const_aggregate_field(
     // Update the new aggregate with the constant field, based in the indices.
     inject_constant_into_aggregate(&mut new_aggregate, const_value, indices);
 
-    // Replace the old aggregate with the new aggregate.
+    // Replace the old aggregate with the new aggregate.  The old one is left in the context as
+    // constants are shared when inlining, so it may still be used by another function.
     let new_aggregate_value = Value::new_constant(context, new_aggregate);
     function.replace_value(context, aggregate, new_aggregate_value, None);
 
-    // Remove the old aggregate from the context.
-    context.values.remove(aggregate.0);
-
     new_aggregate_value
 }
 
@@ -0,0 +1,79 @@
+//! Dead code elimination.
+//!
+//! Instructions which have no side effects and whose values are never used are removed, which may
+//! in turn leave the instructions they used unused too.  The first phi instruction in a block can't
+//! be removed, so if it is unused it has its list of incoming values cleared instead.
+
+use std::collections::HashMap;
+
+use crate::{
+    block::Block,
+    context::Context,
+    function::Function,
+    instruction::Instruction,
+    value::{Value, ValueContent},
+};
+
+/// Remove the unused instructions without side effects from `function`.
+pub fn dce(context: &mut Context, function: &Function) -> Result<bool, String> {
+    // Count the uses of every value, and find the block which contains each instruction.
+    let mut num_uses: HashMap<Value, usize> = HashMap::new();
+    let mut ins_blocks: HashMap<Value, Block> = HashMap::new();
+    for (block, ins_val) in function.instruction_iter(context) {
+        ins_blocks.insert(ins_val, block);
+        for operand in get_operands(context, &ins_val) {
+            *num_uses.entry(operand).or_default() += 1;
+        }
+    }
+
+    // The order in which they're removed doesn't affect the result.
+    let mut worklist = ins_blocks
+        .iter()
+        .filter(|(ins_val, block)| {
+            !num_uses.contains_key(ins_val) && is_removable(context, ins_val, block)
+        })
+        .map(|(ins_val, block)| (*ins_val, *block))
+        .collect::<Vec<_>>();
+
+    let modified = !worklist.is_empty();
+    while let Some((ins_val, block)) = worklist.pop() {
+        let operands = get_operands(context, &ins_val);
+        if block.get_phi(context) == ins_val {
+            context.values[ins_val.0] = ValueContent::Instruction(Instruction::Phi(Vec::new()));
+        } else {
+            block.remove_instruction(context, ins_val);
+        }
+        for operand in operands {
+            if let Some(count) = num_uses.get_mut(&operand) {
+                *count -= 1;
+                if *count == 0 {
+                    if let Some(operand_block) = ins_blocks.get(&operand) {
+                        if is_removable(context, &operand, operand_block) {
+                            worklist.push((operand, *operand_block));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(modified)
+}
+
+fn get_operands(context: &Context, ins_val: &Value) -> Vec<Value> {
+    match &context.values[ins_val.0] {
+        ValueContent::Instruction(ins) => ins.get_operands(),
+        _otherwise => Vec::new(),
+    }
+}
+
+// An unused instruction may be removed if it has no side effects, or it is a phi which isn't the
+// first in its block or has incoming values to clear.
+fn is_removable(context: &Context, ins_val: &Value, block: &Block) -> bool {
+    match &context.values[ins_val.0] {
+        ValueContent::Instruction(Instruction::Phi(pairs)) => {
+            !pairs.is_empty() || block.get_phi(context) != *ins_val
+        }
+        ValueContent::Instruction(ins) => !ins.may_have_side_effect(context),
+        _otherwise => false,
+    }
+}
@@ -2,13 +2,13 @@
 //!
 //! Function inlining is pretty hairy so these passes must be maintained with care.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
     asm::AsmArg,
     block::Block,
     context::Context,
-    function::Function,
+    function::{Function, Inline},
     instruction::Instruction,
     pointer::Pointer,
     value::{Value, ValueContent},
@@ -44,6 +44,104 @@ pub fn inline_all_function_calls(
     Ok(modified)
 }
 
+/// Inline the calls made from a specific function for which `predicate` returns true.
+///
+/// The predicate is given the context and the called function.  It is re-evaluated for each call
+/// site, including those which are themselves inlined, so it should reject recursive functions.
+pub fn inline_some_function_calls<F: Fn(&Context, &Function) -> bool>(
+    context: &mut Context,
+    function: &Function,
+    predicate: F,
+) -> Result<bool, String> {
+    let mut modified = false;
+    loop {
+        // Find the next call site which should be inlined.
+        let call_data = function
+            .instruction_iter(context)
+            .find_map(|(block, call_val)| match context.values[call_val.0] {
+                ValueContent::Instruction(Instruction::Call(inlined_function, _))
+                    if predicate(context, &inlined_function) =>
+                {
+                    Some((block, call_val, inlined_function))
+                }
+                _ => None,
+            });
+        match call_data {
+            Some((block, call_val, inlined_function)) => {
+                inline_function_call(context, *function, block, call_val, inlined_function)?;
+                modified = true;
+            }
+            None => break,
+        }
+    }
+    Ok(modified)
+}
+
+/// A simple heuristic for [`inline_some_function_calls`].  A function is worth inlining if it is
+/// not recursive, directly or indirectly, and is either called just once or has no more than
+/// `max_instr_count` instructions.
+pub fn is_small_or_called_once(
+    context: &Context,
+    inlined_function: &Function,
+    max_instr_count: usize,
+) -> bool {
+    if is_recursive(context, inlined_function) {
+        return false;
+    }
+    if inlined_function.instruction_iter(context).count() <= max_instr_count {
+        return true;
+    }
+    let call_count = context
+        .functions
+        .iter()
+        .flat_map(|(idx, _)| called_functions(context, &Function(idx)))
+        .filter(|called_function| called_function == inlined_function)
+        .count();
+    call_count == 1
+}
+
+/// The heuristic used by the `inline-small` pass.  A function's own [`Inline`] hint is followed
+/// if it has one, although recursive functions are never inlined.  Otherwise the decision is left
+/// to [`is_small_or_called_once`].
+pub fn should_inline(
+    context: &Context,
+    inlined_function: &Function,
+    max_instr_count: usize,
+) -> bool {
+    match inlined_function.get_inline(context) {
+        Some(Inline::Never) => false,
+        Some(Inline::Always) => !is_recursive(context, inlined_function),
+        None => is_small_or_called_once(context, inlined_function, max_instr_count),
+    }
+}
+
+/// Whether a function may call itself, either directly or via other functions.
+pub fn is_recursive(context: &Context, function: &Function) -> bool {
+    let mut visited = HashSet::new();
+    let mut to_visit = called_functions(context, function);
+    while let Some(called_function) = to_visit.pop() {
+        if called_function == *function {
+            return true;
+        }
+        if visited.insert(called_function) {
+            to_visit.append(&mut called_functions(context, &called_function));
+        }
+    }
+    false
+}
+
+fn called_functions(context: &Context, function: &Function) -> Vec<Function> {
+    function
+        .instruction_iter(context)
+        .filter_map(|(_, instr_val)| match context.values[instr_val.0] {
+            ValueContent::Instruction(Instruction::Call(called_function, _)) => {
+                Some(called_function)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
 /// Inline a function to a specific call site within another function.
 ///
 /// The destination function, block and call site must be specified along with the function to
@@ -128,9 +226,16 @@ pub fn inline_function_call(
     // translating their blocks and values to refer to the new ones.  The value map is still live
     // as we add new instructions which replace the old ones to it too.
     //
-    // Note: inline_instruction() doesn't translate `phi` instructions here.
+    // Note: inline_instruction() doesn't translate `phi` instructions here.  The first is added
+    // upon block creation and any others are added now, but their values are updated below.
     let inlined_blocks = context.functions[inlined_function.0].blocks.clone();
     for block in &inlined_blocks {
+        let new_block = block_map.get(block).unwrap();
+        let old_phis = block.get_phis(context);
+        value_map.insert(old_phis[0], new_block.get_phi(context));
+        for old_phi in &old_phis[1..] {
+            value_map.insert(*old_phi, new_block.new_phi(context));
+        }
         for ins in context.blocks[block.0].instructions.clone() {
             inline_instruction(
                 context,
@@ -149,16 +254,19 @@ pub fn inline_function_call(
     // don't need a peristent `&Context` to access them.
     for old_block in inlined_blocks {
         let new_block = block_map.get(&old_block).unwrap();
-        let old_phi_val = old_block.get_phi(context);
-        if let ValueContent::Instruction(Instruction::Phi(pairs)) =
-            context.values[old_phi_val.0].clone()
-        {
-            for (from_block, phi_value) in pairs {
-                new_block.add_phi(
-                    context,
-                    block_map.get(&from_block).copied().unwrap(),
-                    value_map.get(&phi_value).copied().unwrap_or(phi_value),
-                );
+        for old_phi_val in old_block.get_phis(context) {
+            let new_phi_val = value_map.get(&old_phi_val).copied().unwrap();
+            if let ValueContent::Instruction(Instruction::Phi(pairs)) =
+                context.values[old_phi_val.0].clone()
+            {
+                for (from_block, phi_value) in pairs {
+                    new_block.add_phi_entry(
+                        context,
+                        new_phi_val,
+                        block_map.get(&from_block).copied().unwrap(),
+                        value_map.get(&phi_value).copied().unwrap_or(phi_value),
+                    );
+                }
             }
         }
     }
@@ -206,6 +314,11 @@ fn inline_instruction(
                 // We can re-use the old asm block with the updated args.
                 new_block.ins(context).asm_block_from_asm(asm, new_args)
             }
+            Instruction::BinaryOp { op, arg1, arg2 } => {
+                new_block
+                    .ins(context)
+                    .binary_op(op, map_value(arg1), map_value(arg2))
+            }
             // For `br` and `cbr` below we don't need to worry about the phi values, they're
             // adjusted later in `inline_function_call()`.
             Instruction::Branch(b) => new_block.ins(context).branch(map_block(b), None),
@@ -216,6 +329,11 @@ fn inline_instruction(
                     .collect::<Vec<Value>>()
                     .as_slice(),
             ),
+            Instruction::Cmp(pred, lhs, rhs) => {
+                new_block
+                    .ins(context)
+                    .cmp(pred, map_value(lhs), map_value(rhs))
+            }
             Instruction::ConditionalBranch {
                 cond_value,
                 true_block,
@@ -268,6 +386,12 @@ fn inline_instruction(
             Instruction::Ret(val, _) => new_block
                 .ins(context)
                 .branch(*post_block, Some(map_value(val))),
+            Instruction::StateLoad { key, ty } => {
+                new_block.ins(context).state_load(map_value(key), ty)
+            }
+            Instruction::StateStore { key, stored_val } => new_block
+                .ins(context)
+                .state_store(map_value(key), map_value(stored_val)),
             Instruction::Store { ptr, stored_val } => new_block
                 .ins(context)
                 .store(map_ptr(ptr), map_value(stored_val)),
@@ -275,9 +399,12 @@ fn inline_instruction(
             // NOTE: We're not translating the phi value yet, since this is the single instance of
             // use of a value which may not be mapped yet -- a branch from a subsequent block,
             // back up to this block.  And we don't need to add a `phi` instruction because an
-            // empty one is added upon block creation; we can return that instead.
-            Instruction::Phi(_) => new_block.get_phi(context),
+            // empty one was already added to the new block; we can return that instead.
+            Instruction::Phi(_) => value_map[instruction],
         };
+        // The copy still came from the same source, so it keeps the original's metadata.
+        let md_idx = instruction.get_metadata(context);
+        let new_ins = new_ins.add_metadatum(context, md_idx);
         value_map.insert(*instruction, new_ins);
     }
 }
@@ -0,0 +1,212 @@
+//! Promotion of local variables to SSA values, or 'mem2reg'.
+//!
+//! A local variable which is only ever loaded from and stored to, and so is never referred to by a
+//! pointer, can be replaced by the values which are stored to it.  Where different values reach a
+//! load along different paths a new phi instruction merges them.  These are inserted at the
+//! iterated dominance frontier of the blocks which store to the local, but only where the local is
+//! live, i.e., it may be loaded before it is next stored to.
+//!
+//! Only locals which fit in a register are promoted.  Loading a larger local refers to its memory
+//! rather than copying it, so later stores to the local would change the loaded value.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    analysis::DominatorTree,
+    block::Block,
+    constant::Constant,
+    context::Context,
+    function::Function,
+    instruction::Instruction,
+    irtype::Type,
+    pointer::Pointer,
+    value::{Value, ValueContent},
+};
+
+/// Promote the non-escaping scalar local variables in `function` to SSA values.
+pub fn mem2reg(context: &mut Context, function: &Function) -> Result<bool, String> {
+    let dom_tree = DominatorTree::new(context, function);
+    let locals = get_promotable_locals(context, function, &dom_tree);
+    if locals.is_empty() {
+        return Ok(false);
+    }
+    let local_idcs = locals
+        .iter()
+        .enumerate()
+        .map(|(idx, ptr)| (*ptr, idx))
+        .collect::<HashMap<_, _>>();
+
+    // Insert the new phis, remembering which local each is for.
+    let mut phi_locals = HashMap::new();
+    for (local_idx, phi_blocks) in get_phi_blocks(context, &dom_tree, &local_idcs)
+        .into_iter()
+        .enumerate()
+    {
+        for block in phi_blocks {
+            let phi_val = block.new_phi(context);
+            phi_locals.insert(phi_val, local_idx);
+        }
+    }
+
+    // Walk the dominator tree tracking the current value of each local, which is the value it had
+    // at the end of the block's immediate dominator if the block has no phi for it.  Loads are
+    // replaced by the current value and stores update it, and then both are removed.
+    let initial_vals = locals
+        .iter()
+        .map(|ptr| get_initial_value(context, ptr))
+        .collect::<Vec<_>>();
+    let mut replacements = HashMap::new();
+    let mut worklist = vec![(function.get_entry_block(context), initial_vals)];
+    while let Some((block, mut current_vals)) = worklist.pop() {
+        for ins_val in context.blocks[block.0].instructions.clone() {
+            match &context.values[ins_val.0] {
+                ValueContent::Instruction(Instruction::Phi(_)) => {
+                    if let Some(local_idx) = phi_locals.get(&ins_val) {
+                        current_vals[*local_idx] = ins_val;
+                    }
+                }
+                ValueContent::Instruction(Instruction::Load(ptr)) => {
+                    if let Some(local_idx) = local_idcs.get(ptr) {
+                        replacements.insert(ins_val, current_vals[*local_idx]);
+                        block.remove_instruction(context, ins_val);
+                    }
+                }
+                ValueContent::Instruction(Instruction::Store { ptr, stored_val }) => {
+                    if let Some(local_idx) = local_idcs.get(ptr) {
+                        current_vals[*local_idx] =
+                            replacements.get(stored_val).copied().unwrap_or(*stored_val);
+                        block.remove_instruction(context, ins_val);
+                    }
+                }
+                _otherwise => (),
+            }
+        }
+        for succ in block.successors(context) {
+            for phi_val in succ.get_phis(context) {
+                if let Some(local_idx) = phi_locals.get(&phi_val) {
+                    succ.add_phi_entry(context, phi_val, block, current_vals[*local_idx]);
+                }
+            }
+        }
+        for child in dom_tree.children(&block) {
+            worklist.push((*child, current_vals.clone()));
+        }
+    }
+
+    // Replace the uses of the removed loads and then the locals themselves.
+    for (_, ins_val) in function.instruction_iter(context).collect::<Vec<_>>() {
+        if let ValueContent::Instruction(ins) = &mut context.values[ins_val.0] {
+            ins.replace_values(&replacements);
+        }
+    }
+    context.functions[function.0]
+        .local_storage
+        .retain(|_, ptr| !local_idcs.contains_key(ptr));
+
+    Ok(true)
+}
+
+// The locals which may be promoted are those of a scalar type which are never referred to by
+// `get_ptr` nor used in an unreachable block, in name order.
+fn get_promotable_locals(
+    context: &Context,
+    function: &Function,
+    dom_tree: &DominatorTree,
+) -> Vec<Pointer> {
+    let mut locals = function
+        .locals_iter(context)
+        .filter(|(_, ptr)| {
+            let ty = ptr.get_type(context);
+            matches!(ty, Type::Unit | Type::Bool | Type::Uint(_)) && !ty.is_wide_uint()
+        })
+        .map(|(_, ptr)| *ptr)
+        .collect::<Vec<_>>();
+    let mut rejected = HashSet::new();
+    for (block, ins_val) in function.instruction_iter(context) {
+        match &context.values[ins_val.0] {
+            ValueContent::Instruction(Instruction::GetPointer(ptr)) => {
+                rejected.insert(*ptr);
+            }
+            ValueContent::Instruction(Instruction::Load(ptr) | Instruction::Store { ptr, .. })
+                if !dom_tree.is_reachable(&block) =>
+            {
+                rejected.insert(*ptr);
+            }
+            _otherwise => (),
+        }
+    }
+    locals.retain(|ptr| !rejected.contains(ptr));
+    locals
+}
+
+// Find the blocks which need a phi for each local.
+fn get_phi_blocks(
+    context: &Context,
+    dom_tree: &DominatorTree,
+    local_idcs: &HashMap<Pointer, usize>,
+) -> Vec<Vec<Block>> {
+    // For each local, find the blocks which store to it and those which load from it before any
+    // store.
+    let mut store_blocks = vec![HashSet::new(); local_idcs.len()];
+    let mut exposed_load_blocks = vec![Vec::new(); local_idcs.len()];
+    let mut preds: HashMap<Block, Vec<Block>> = HashMap::new();
+    for block in dom_tree.reverse_postorder() {
+        for ins_val in &context.blocks[block.0].instructions {
+            match &context.values[ins_val.0] {
+                ValueContent::Instruction(Instruction::Load(ptr)) => {
+                    if let Some(local_idx) = local_idcs.get(ptr) {
+                        if !store_blocks[*local_idx].contains(block) {
+                            exposed_load_blocks[*local_idx].push(*block);
+                        }
+                    }
+                }
+                ValueContent::Instruction(Instruction::Store { ptr, .. }) => {
+                    if let Some(local_idx) = local_idcs.get(ptr) {
+                        store_blocks[*local_idx].insert(*block);
+                    }
+                }
+                _otherwise => (),
+            }
+        }
+        for succ in block.successors(context) {
+            preds.entry(succ).or_default().push(*block);
+        }
+    }
+
+    store_blocks
+        .into_iter()
+        .zip(exposed_load_blocks)
+        .map(|(store_blocks, exposed_load_blocks)| {
+            // The local is live on entry to a block if there's a path from it to a load which
+            // doesn't pass through a store.
+            let mut live_in = exposed_load_blocks.iter().copied().collect::<HashSet<_>>();
+            let mut worklist = exposed_load_blocks;
+            while let Some(block) = worklist.pop() {
+                for pred in preds.get(&block).map(Vec::as_slice).unwrap_or_default() {
+                    if !store_blocks.contains(pred) && live_in.insert(*pred) {
+                        worklist.push(*pred);
+                    }
+                }
+            }
+            dom_tree
+                .iterated_dominance_frontier(store_blocks)
+                .into_iter()
+                .filter(|block| live_in.contains(block))
+                .collect()
+        })
+        .collect()
+}
+
+// A local which is loaded before it is ever stored to has the value of its initializer, or zero
+// if it has none.
+fn get_initial_value(context: &mut Context, ptr: &Pointer) -> Value {
+    let ptr_content = &context.pointers[ptr.0];
+    let constant = match (&ptr_content.initializer, ptr_content.ty) {
+        (Some(initializer), _) => initializer.clone(),
+        (None, Type::Unit) => Constant::new_unit(),
+        (None, Type::Bool) => Constant::new_bool(false),
+        (None, Type::Uint(nbits)) => Constant::new_uint(nbits, 0),
+        (None, _) => unreachable!("Only scalar locals are promoted."),
+    };
+    Value::new_constant(context, constant)
+}
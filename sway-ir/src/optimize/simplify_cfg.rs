@@ -0,0 +1,261 @@
+//! Control flow graph simplification.
+//!
+//! - folding - conditional branches on constant conditions, or with the same destination for both
+//!   cases, become unconditional branches.
+//! - unreachable blocks - blocks which can't be reached from the entry block are removed.
+//! - trivial phis - a phi which has the same incoming value from every predecessor is replaced
+//!   with that value.
+//! - merging - a block which is the only successor of its only predecessor is appended to it.
+//! - empty blocks - a block which only branches elsewhere is bypassed by its predecessors.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    block::Block,
+    constant::{Constant, ConstantValue},
+    context::Context,
+    function::Function,
+    instruction::Instruction,
+    value::{Value, ValueContent},
+};
+
+/// Simplify the control flow graph of `function`.
+pub fn simplify_cfg(context: &mut Context, function: &Function) -> Result<bool, String> {
+    let mut modified = false;
+    loop {
+        if fold_branches(context, function)
+            || remove_unreachable_blocks(context, function)
+            || remove_trivial_phis(context, function)
+            || merge_blocks(context, function)
+            || remove_empty_blocks(context, function)
+        {
+            modified = true;
+            continue;
+        }
+
+        // Other simplifications here... always continue to the top if one returns true.
+        break;
+    }
+    Ok(modified)
+}
+
+fn fold_branches(context: &mut Context, function: &Function) -> bool {
+    let mut modified = false;
+    for block in function.block_iter(context) {
+        let term_val = *context.blocks[block.0].instructions.last().unwrap();
+        let (taken, not_taken) = match &context.values[term_val.0] {
+            ValueContent::Instruction(Instruction::ConditionalBranch {
+                cond_value,
+                true_block,
+                false_block,
+            }) if true_block == false_block => (*true_block, *false_block),
+            ValueContent::Instruction(Instruction::ConditionalBranch {
+                cond_value,
+                true_block,
+                false_block,
+            }) => match &context.values[cond_value.0] {
+                ValueContent::Constant(Constant {
+                    value: ConstantValue::Bool(cond),
+                    ..
+                }) => {
+                    if *cond {
+                        (*true_block, *false_block)
+                    } else {
+                        (*false_block, *true_block)
+                    }
+                }
+                _otherwise => continue,
+            },
+            _otherwise => continue,
+        };
+
+        context.values[term_val.0] = ValueContent::Instruction(Instruction::Branch(taken));
+        if not_taken != taken {
+            not_taken.remove_phi_val_coming_from(context, &block);
+        }
+        modified = true;
+    }
+    modified
+}
+
+fn remove_unreachable_blocks(context: &mut Context, function: &Function) -> bool {
+    let entry_block = function.get_entry_block(context);
+    let mut reachable = HashSet::from([entry_block]);
+    let mut worklist = vec![entry_block];
+    while let Some(block) = worklist.pop() {
+        for succ in block.successors(context) {
+            if reachable.insert(succ) {
+                worklist.push(succ);
+            }
+        }
+    }
+
+    let mut modified = false;
+    for block in function.block_iter(context) {
+        if !reachable.contains(&block) {
+            for succ in block.successors(context) {
+                succ.remove_phi_val_coming_from(context, &block);
+            }
+            function.remove_block(context, &block);
+            modified = true;
+        }
+    }
+    modified
+}
+
+fn remove_trivial_phis(context: &mut Context, function: &Function) -> bool {
+    let preds = get_predecessors(context, function);
+    let mut modified = false;
+    for block in function.block_iter(context) {
+        let first_phi_val = block.get_phi(context);
+        for phi_val in block.get_phis(context) {
+            let pairs = match &context.values[phi_val.0] {
+                ValueContent::Instruction(Instruction::Phi(pairs)) => pairs.clone(),
+                _otherwise => unreachable!("Phi value must be a PHI instruction."),
+            };
+
+            // Every predecessor must provide a value, else the phi is undefined along some edge.
+            let block_preds = preds.get(&block).map(Vec::as_slice).unwrap_or_default();
+            if pairs.is_empty()
+                || block_preds
+                    .iter()
+                    .any(|pred| !pairs.iter().any(|(from_block, _)| from_block == pred))
+            {
+                continue;
+            }
+
+            // A phi in a loop may refer to itself, which isn't a distinct value.
+            let mut incoming_vals = pairs
+                .iter()
+                .map(|(_, val)| *val)
+                .filter(|val| *val != phi_val);
+            let first_val = match incoming_vals.next() {
+                Some(val) => val,
+                None => continue,
+            };
+            if incoming_vals.all(|val| val == first_val) {
+                // The first phi must stay, but any others can go.
+                if phi_val == first_phi_val {
+                    context.values[phi_val.0] =
+                        ValueContent::Instruction(Instruction::Phi(Vec::new()));
+                } else {
+                    block.remove_instruction(context, phi_val);
+                }
+                function.replace_value(context, phi_val, first_val, None);
+                modified = true;
+            }
+        }
+    }
+    modified
+}
+
+fn merge_blocks(context: &mut Context, function: &Function) -> bool {
+    let preds = get_predecessors(context, function);
+    let entry_block = function.get_entry_block(context);
+    for block in function.block_iter(context) {
+        let succ = match block.get_term_inst(context) {
+            Some(Instruction::Branch(succ)) => *succ,
+            _otherwise => continue,
+        };
+        if succ == block || succ == entry_block || preds[&succ].len() != 1 {
+            continue;
+        }
+
+        // The successor's phis can only have a value from this block, which replaces them.  If one
+        // has none then it must be unused or we can't remove it.
+        let succ_phi_vals = succ.get_phis(context);
+        let phi_vals = succ.get_phi_vals_coming_from(context, &block);
+        if succ_phi_vals.iter().any(|succ_phi_val| {
+            !phi_vals.iter().any(|(phi, _)| phi == succ_phi_val)
+                && is_used(context, function, succ_phi_val)
+        }) {
+            continue;
+        }
+        for (succ_phi_val, phi_val) in phi_vals {
+            function.replace_value(context, succ_phi_val, phi_val, None);
+        }
+
+        // Replace the branch with the successor's instructions and then any phis which referred
+        // to the successor now refer to this block.
+        let mut succ_instructions = context.blocks[succ.0]
+            .instructions
+            .split_off(succ_phi_vals.len());
+        let instructions = &mut context.blocks[block.0].instructions;
+        instructions.pop();
+        instructions.append(&mut succ_instructions);
+        for succ_succ in block.successors(context) {
+            succ_succ.update_phi_source_block(context, succ, block);
+        }
+        function.remove_block(context, &succ);
+        return true;
+    }
+    false
+}
+
+fn remove_empty_blocks(context: &mut Context, function: &Function) -> bool {
+    let preds = get_predecessors(context, function);
+    let entry_block = function.get_entry_block(context);
+    for block in function.block_iter(context) {
+        // The block must be just an empty phi and a branch.
+        if block == entry_block || context.blocks[block.0].instructions.len() != 2 {
+            continue;
+        }
+        let block_phi_val = block.get_phi(context);
+        if !matches!(&context.values[block_phi_val.0],
+            ValueContent::Instruction(Instruction::Phi(pairs)) if pairs.is_empty())
+        {
+            continue;
+        }
+        let succ = match block.get_term_inst(context) {
+            Some(Instruction::Branch(succ)) if *succ != block => *succ,
+            _otherwise => continue,
+        };
+
+        // If the successor's phis have values from this block then each predecessor will provide
+        // them instead.  The phi values are set before the terminator, so only an unconditional
+        // branch may provide them, else they would be clobbered if the other branch is taken.
+        let block_preds = preds.get(&block).cloned().unwrap_or_default();
+        let phi_vals = succ.get_phi_vals_coming_from(context, &block);
+        if !phi_vals.is_empty()
+            && (phi_vals
+                .iter()
+                .any(|(_, phi_val)| *phi_val == block_phi_val)
+                || block_preds.iter().any(|pred| {
+                    !matches!(pred.get_term_inst(context), Some(Instruction::Branch(_)))
+                        || !succ.get_phi_vals_coming_from(context, pred).is_empty()
+                }))
+        {
+            continue;
+        }
+
+        for pred in block_preds {
+            pred.replace_successor(context, block, succ);
+            for (succ_phi_val, phi_val) in &phi_vals {
+                succ.add_phi_entry(context, *succ_phi_val, pred, *phi_val);
+            }
+        }
+        succ.remove_phi_val_coming_from(context, &block);
+        function.remove_block(context, &block);
+        return true;
+    }
+    false
+}
+
+fn get_predecessors(context: &Context, function: &Function) -> HashMap<Block, Vec<Block>> {
+    let mut preds: HashMap<Block, Vec<Block>> = HashMap::new();
+    for block in function.block_iter(context) {
+        for succ in block.successors(context) {
+            preds.entry(succ).or_default().push(block);
+        }
+    }
+    preds
+}
+
+fn is_used(context: &Context, function: &Function, val: &Value) -> bool {
+    function
+        .instruction_iter(context)
+        .any(|(_, ins_val)| match &context.values[ins_val.0] {
+            ValueContent::Instruction(ins) => ins.get_operands().contains(val),
+            _otherwise => false,
+        })
+}
@@ -24,21 +24,30 @@ mod ir_builder {
     peg::parser! {
         pub(in crate::parser) grammar parser() for str {
             pub(in crate::parser) rule ir_descrs() -> IrAstModule
-                = _ s:script() eoi() {
-                    s
+                = _ m:module() eoi() {
+                    m
                 }
 
-            rule script() -> IrAstModule
-                = "script" _ name:id() "{" _ fn_decls:fn_decl()* "}" _ {
+            rule module() -> IrAstModule
+                = kind:module_kind() name:id() "{" _ fn_decls:fn_decl()* "}" _
+                      metadata:metadata_decl()* {
                     IrAstModule {
                         name,
-                        kind: crate::module::Kind::Script,
-                        fn_decls
+                        kind,
+                        fn_decls,
+                        metadata
                     }
                 }
 
+            rule module_kind() -> Kind
+                = "script" _ { Kind::Script }
+                / "contract" _ { Kind::Contract }
+                / "library" _ { Kind::Library }
+                / "predicate" _ { Kind::Predicate }
+
             rule fn_decl() -> IrAstFnDecl
-                = "fn" _ name:id() "(" _ args:(fn_arg() ** comma()) ")" _ "->" _ ret_type:ast_ty() "{" _
+                = is_public:("pub" _)? inline:inline_hint()? "fn" _ name:fn_id() selector:selector_id()? "(" _
+                      args:(fn_arg() ** comma()) ")" _ "->" _ ret_type:ast_ty() "{" _
                       locals:fn_local()*
                       blocks:block_decl()*
                   "}" _ {
@@ -48,7 +57,23 @@ mod ir_builder {
                         ret_type,
                         locals,
                         blocks,
+                        selector,
+                        is_public: is_public.is_some(),
+                        inline,
+                    }
+                }
+
+            rule inline_hint() -> Inline
+                = "inline" _ "(" _ "always" _ ")" _ { Inline::Always }
+                / "inline" _ "(" _ "never" _ ")" _ { Inline::Never }
+
+            rule selector_id() -> [u8; 4]
+                = "<" _ s:$(['0'..='9' | 'a'..='f' | 'A'..='F']*<8>) _ ">" _ {
+                    let mut bytes: [u8; 4] = [0; 4];
+                    for (idx, byte) in bytes.iter_mut().enumerate() {
+                        *byte = u8::from_str_radix(&s[idx * 2..idx * 2 + 2], 16).unwrap();
                     }
+                    bytes
                 }
 
             rule fn_arg() -> (IrAstTy, String)
@@ -75,10 +100,11 @@ mod ir_builder {
                 }
 
             rule instr_decl() -> IrAstInstruction
-                = value_name:value_assign()? op:operation() {
+                = value_name:value_assign()? op:operation() md_idx:comma_metadata_idx()? {
                     IrAstInstruction {
                         value_name,
                         op,
+                        md_idx,
                     }
                 }
 
@@ -89,9 +115,11 @@ mod ir_builder {
 
             rule operation() -> IrAstOperation
                 = op_asm()
+                / op_binary()
                 / op_branch()
                 / op_call()
                 / op_cbr()
+                / op_cmp()
                 / op_const()
                 / op_extract_element()
                 / op_extract_value()
@@ -101,6 +129,8 @@ mod ir_builder {
                 / op_load()
                 / op_phi()
                 / op_ret()
+                / op_state_load()
+                / op_state_store()
                 / op_store()
 
             rule op_asm() -> IrAstOperation
@@ -110,13 +140,33 @@ mod ir_builder {
                     IrAstOperation::Asm(args, ret, ops)
                 }
 
+            rule op_binary() -> IrAstOperation
+                = op:binary_op_kind() arg1:id() comma() arg2:id() {
+                    IrAstOperation::BinaryOp(op, arg1, arg2)
+                }
+
+            rule binary_op_kind() -> BinaryOpKind
+                = "add" _ { BinaryOpKind::Add }
+                / "sub" _ { BinaryOpKind::Sub }
+                / "mul" _ { BinaryOpKind::Mul }
+                / "div" _ { BinaryOpKind::Div }
+                / "mod" _ { BinaryOpKind::Mod }
+                / "and" _ { BinaryOpKind::And }
+                / "or" _ { BinaryOpKind::Or }
+                / "xor" _ { BinaryOpKind::Xor }
+                / "sdiv" _ { BinaryOpKind::SDiv }
+                / "smod" _ { BinaryOpKind::SMod }
+                / "shl" _ { BinaryOpKind::Shl }
+                / "shr" _ { BinaryOpKind::Shr }
+                / "sar" _ { BinaryOpKind::Sar }
+
             rule op_branch() -> IrAstOperation
                 = "br" _ to_block:id() {
                     IrAstOperation::Br(to_block)
                 }
 
             rule op_call() -> IrAstOperation
-                = "call" _ callee:id() "(" _ args:(id() ** comma()) ")" _ {
+                = "call" _ callee:fn_id() "(" _ args:(id() ** comma()) ")" _ {
                     IrAstOperation::Call(callee, args)
             }
 
@@ -125,9 +175,26 @@ mod ir_builder {
                     IrAstOperation::Cbr(cond, tblock, fblock)
                 }
 
+            rule op_cmp() -> IrAstOperation
+                = "cmp" _ pred:predicate() lhs:id() comma() rhs:id() {
+                    IrAstOperation::Cmp(pred, lhs, rhs)
+                }
+
+            rule predicate() -> Predicate
+                = "eq" _ { Predicate::Equal }
+                / "ne" _ { Predicate::NotEqual }
+                / "lt" _ { Predicate::LessThan }
+                / "gt" _ { Predicate::GreaterThan }
+                / "le" _ { Predicate::LessThanEqual }
+                / "ge" _ { Predicate::GreaterThanEqual }
+                / "slt" _ { Predicate::SignedLessThan }
+                / "sgt" _ { Predicate::SignedGreaterThan }
+                / "sle" _ { Predicate::SignedLessThanEqual }
+                / "sge" _ { Predicate::SignedGreaterThanEqual }
+
             rule op_const() -> IrAstOperation
-                = "const" _ ast_ty() cv:constant() {
-                    IrAstOperation::Const(cv)
+                = "const" _ ty:ast_ty() cv:constant() {
+                    IrAstOperation::Const(cv.with_type(&ty))
                 }
 
             rule op_extract_element() -> IrAstOperation
@@ -170,11 +237,52 @@ mod ir_builder {
                     IrAstOperation::Ret(ty, vn)
                 }
 
+            rule op_state_load() -> IrAstOperation
+                = "state_load" _ ty:ast_ty() "key" _ key:id() {
+                    IrAstOperation::StateLoad(ty, key)
+                }
+
+            rule op_state_store() -> IrAstOperation
+                = "state_store" _ val:id() comma() "key" _ key:id() {
+                    IrAstOperation::StateStore(val, key)
+                }
+
             rule op_store() -> IrAstOperation
                 = "store" _ dst:id() comma() ("mut" _)? "ptr" _ ast_ty() vn:id() {
                     IrAstOperation::Store(dst, vn)
                 }
 
+            rule comma_metadata_idx() -> MdIdxRef
+                = comma() mdi:metadata_idx() {
+                    mdi
+                }
+
+            rule metadata_idx() -> MdIdxRef
+                = "!" idx:decimal() {
+                    idx
+                }
+
+            rule metadata_decl() -> (MdIdxRef, IrMetadatum)
+                = idx:metadata_idx() "=" _ md:metadatum() {
+                    (idx, md)
+                }
+
+            rule metadatum() -> IrMetadatum
+                = "filepath" _ ['"'] path:path_char()* ['"'] _ {
+                    IrMetadatum::FileLocation(path.into_iter().collect())
+                }
+                / "span" _ loc_idx:metadata_idx() start:decimal() end:decimal() {
+                    IrMetadatum::Span {
+                        loc_idx,
+                        start: start as usize,
+                        end: end as usize,
+                    }
+                }
+
+            rule path_char() -> char
+                = ch:$([^ '"' | '\\']) { ch.chars().next().unwrap() }
+                / ['\\'] ch:$(['\\' | '"']) { ch.chars().next().unwrap() }
+
             rule asm_arg() -> (Ident, Option<IrAstAsmArgInit>)
                 = name:id_id() init:asm_arg_init()? {
                     (name, init)
@@ -231,6 +339,12 @@ mod ir_builder {
                     }
                     IrAstConstValue::B256(bytes)
                 }
+                / "0x" s:$(['0'..='9' | 'a'..='f' | 'A'..='F']*<32>) _ {
+                    let bytes = (0..16)
+                        .map(|idx| u8::from_str_radix(&s[idx * 2..idx * 2 + 2], 16).unwrap())
+                        .collect();
+                    IrAstConstValue::WideUint(bytes)
+                }
                 / n:decimal() { IrAstConstValue::Number(n) }
                 / string_const()
                 / array_const()
@@ -258,6 +372,7 @@ mod ir_builder {
 
             rule field_or_element_const() -> (IrAstTy, IrAstConstValue)
                 = ty:ast_ty() cv:constant() {
+                    let cv = cv.with_type(&ty);
                     (ty, cv)
                 }
                 / ty:ast_ty() "undef" _ {
@@ -268,6 +383,8 @@ mod ir_builder {
                 = ("unit" / "()") _ { IrAstTy::Unit }
                 / "bool" _ { IrAstTy::Bool }
                 / "u64" _ { IrAstTy::U64 }
+                / "u128" _ { IrAstTy::U128 }
+                / "u256" _ { IrAstTy::U256 }
                 / "b256" _ { IrAstTy::B256 }
                 / "string" _ "<" _ sz:decimal() ">" _ { IrAstTy::String(sz) }
                 / array_ty()
@@ -294,6 +411,12 @@ mod ir_builder {
                     id.to_owned()
                 }
 
+            // Function names may be qualified with `::`, e.g., methods are named by their type.
+            rule fn_id() -> String
+                = !ast_ty() id:$(id_char0() id_char()* ("::" id_char0() id_char()*)*) _ {
+                    id.to_owned()
+                }
+
             rule id_id() -> Ident
                 = !ast_ty() id:$(id_char0() id_char()*) _ {
                     Ident::new(Span {
@@ -341,8 +464,10 @@ mod ir_builder {
         block::Block,
         constant::Constant,
         context::Context,
-        function::Function,
+        function::{Function, Inline},
+        instruction::{BinaryOpKind, Predicate},
         irtype::{Aggregate, Type},
+        metadata::MetadataIndex,
         module::{Kind, Module},
         pointer::Pointer,
         value::Value,
@@ -353,6 +478,7 @@ mod ir_builder {
         name: String,
         kind: Kind,
         fn_decls: Vec<IrAstFnDecl>,
+        metadata: Vec<(MdIdxRef, IrMetadatum)>,
     }
 
     #[derive(Debug)]
@@ -362,6 +488,9 @@ mod ir_builder {
         ret_type: IrAstTy,
         locals: Vec<(IrAstTy, String, bool, Option<IrAstOperation>)>,
         blocks: Vec<IrAstBlock>,
+        selector: Option<[u8; 4]>,
+        is_public: bool,
+        inline: Option<Inline>,
     }
 
     #[derive(Debug)]
@@ -374,6 +503,7 @@ mod ir_builder {
     struct IrAstInstruction {
         value_name: Option<String>,
         op: IrAstOperation,
+        md_idx: Option<MdIdxRef>,
     }
 
     #[derive(Debug)]
@@ -383,9 +513,11 @@ mod ir_builder {
             Option<Ident>,
             Vec<IrAstAsmOp>,
         ),
+        BinaryOp(BinaryOpKind, String, String),
         Br(String),
         Call(String, Vec<String>),
         Cbr(String, String, String),
+        Cmp(Predicate, String, String),
         Const(IrAstConstValue),
         ExtractElement(String, IrAstTy, String),
         ExtractValue(String, IrAstTy, Vec<u64>),
@@ -395,6 +527,8 @@ mod ir_builder {
         Load(String),
         Phi(Vec<(String, String)>),
         Ret(IrAstTy, String),
+        StateLoad(IrAstTy, String),
+        StateStore(String, String),
         Store(String, String),
     }
 
@@ -405,6 +539,7 @@ mod ir_builder {
         Bool(bool),
         B256([u8; 32]),
         Number(u64),
+        WideUint(Vec<u8>),
         String(String),
         Array(IrAstTy, Vec<IrAstConstValue>),
         Struct(Vec<(IrAstTy, IrAstConstValue)>),
@@ -416,6 +551,19 @@ mod ir_builder {
         Imm(IrAstConstValue),
     }
 
+    // The index of some metadata as it's printed, e.g., `!3`.
+    type MdIdxRef = u64;
+
+    #[derive(Debug)]
+    enum IrMetadatum {
+        FileLocation(String),
+        Span {
+            loc_idx: MdIdxRef,
+            start: usize,
+            end: usize,
+        },
+    }
+
     #[derive(Debug)]
     struct IrAstAsmOp {
         name: Ident,
@@ -424,6 +572,25 @@ mod ir_builder {
     }
 
     impl IrAstConstValue {
+        // A `u256` can't be told apart from a `b256`, nor a small wide integer from a `u64`,
+        // until its type is known.
+        fn with_type(self, ty: &IrAstTy) -> Self {
+            let nbytes = match ty {
+                IrAstTy::U128 => 16,
+                IrAstTy::U256 => 32,
+                _otherwise => return self,
+            };
+            match self {
+                IrAstConstValue::B256(bs) => IrAstConstValue::WideUint(bs.to_vec()),
+                IrAstConstValue::Number(n) => {
+                    let mut bytes = vec![0; nbytes - 8];
+                    bytes.extend_from_slice(&n.to_be_bytes());
+                    IrAstConstValue::WideUint(bytes)
+                }
+                otherwise => otherwise,
+            }
+        }
+
         fn as_constant(&self, context: &mut Context) -> Constant {
             match self {
                 IrAstConstValue::Undef(ty) => {
@@ -434,6 +601,9 @@ mod ir_builder {
                 IrAstConstValue::Bool(b) => Constant::new_bool(*b),
                 IrAstConstValue::B256(bs) => Constant::new_b256(*bs),
                 IrAstConstValue::Number(n) => Constant::new_uint(64, *n),
+                IrAstConstValue::WideUint(bs) => {
+                    Constant::new_wide_uint(bs.len() as u16 * 8, bs.clone())
+                }
                 IrAstConstValue::String(s) => Constant::new_string(s.clone()),
                 IrAstConstValue::Array(el_ty, els) => {
                     let els: Vec<_> = els.iter().map(|cv| cv.as_constant(context)).collect();
@@ -460,6 +630,9 @@ mod ir_builder {
                 IrAstConstValue::Bool(b) => Constant::get_bool(context, *b),
                 IrAstConstValue::B256(bs) => Constant::get_b256(context, *bs),
                 IrAstConstValue::Number(n) => Constant::get_uint(context, 64, *n),
+                IrAstConstValue::WideUint(bs) => {
+                    Constant::get_wide_uint(context, bs.len() as u16 * 8, bs.clone())
+                }
                 IrAstConstValue::String(s) => Constant::get_string(context, s.clone()),
                 IrAstConstValue::Array(..) => {
                     let array_const = self.as_constant(context);
@@ -478,6 +651,8 @@ mod ir_builder {
         Unit,
         Bool,
         U64,
+        U128,
+        U256,
         B256,
         String(u64),
         Array(Box<IrAstTy>, u64),
@@ -491,6 +666,8 @@ mod ir_builder {
                 IrAstTy::Unit => Type::Unit,
                 IrAstTy::Bool => Type::Bool,
                 IrAstTy::U64 => Type::Uint(64),
+                IrAstTy::U128 => Type::Uint(128),
+                IrAstTy::U256 => Type::Uint(256),
                 IrAstTy::B256 => Type::B256,
                 IrAstTy::String(n) => Type::String(*n),
                 IrAstTy::Array(..) => Type::Array(self.to_ir_aggregate_type(context)),
@@ -520,21 +697,63 @@ mod ir_builder {
 
     use std::collections::HashMap;
     use std::iter::FromIterator;
+    use std::{path::PathBuf, sync::Arc};
 
     pub(super) fn build_context(ir_ast_mod: IrAstModule) -> Result<Context, String> {
         let mut ctx = Context::default();
+        let md_map = build_metadata_map(&mut ctx, ir_ast_mod.metadata)?;
         let module = Module::new(&mut ctx, ir_ast_mod.kind, &ir_ast_mod.name);
-        for fn_decl in ir_ast_mod.fn_decls {
-            build_add_fn_decl(&mut ctx, module, fn_decl)?;
+        // All the functions are declared before any of their bodies are built, so that calls may
+        // refer to functions which are defined later in the module.
+        let funcs = ir_ast_mod
+            .fn_decls
+            .into_iter()
+            .map(|fn_decl| (build_add_fn_decl(&mut ctx, module, &fn_decl), fn_decl))
+            .collect::<Vec<_>>();
+        for (func, fn_decl) in funcs {
+            build_fn_body(&mut ctx, func, fn_decl, &md_map)?;
         }
         Ok(ctx)
     }
 
-    fn build_add_fn_decl(
+    fn build_metadata_map(
         context: &mut Context,
-        module: Module,
-        fn_decl: IrAstFnDecl,
-    ) -> Result<(), String> {
+        ir_metadata: Vec<(MdIdxRef, IrMetadatum)>,
+    ) -> Result<HashMap<MdIdxRef, MetadataIndex>, String> {
+        // Spans refer to file locations, so those must be added first.  The file contents are only
+        // needed to make source spans from the metadata, so if the file can't be read we carry on
+        // without them.
+        let (locs, spans): (Vec<_>, Vec<_>) = ir_metadata
+            .into_iter()
+            .partition(|(_, md)| matches!(md, IrMetadatum::FileLocation(_)));
+        let mut md_map = HashMap::new();
+        for (idx_ref, md) in locs.into_iter().chain(spans) {
+            let md_idx = match md {
+                IrMetadatum::FileLocation(path) => {
+                    let src = std::fs::read_to_string(&path).unwrap_or_default();
+                    MetadataIndex::from_file_location(
+                        context,
+                        Arc::new(PathBuf::from(path)),
+                        Arc::from(src),
+                    )
+                }
+                IrMetadatum::Span {
+                    loc_idx,
+                    start,
+                    end,
+                } => {
+                    let loc_idx = md_map.get(&loc_idx).copied().ok_or_else(|| {
+                        format!("Span refers to unknown file location !{}.", loc_idx)
+                    })?;
+                    MetadataIndex::new_span(context, loc_idx, start, end)
+                }
+            };
+            md_map.insert(idx_ref, md_idx);
+        }
+        Ok(md_map)
+    }
+
+    fn build_add_fn_decl(context: &mut Context, module: Module, fn_decl: &IrAstFnDecl) -> Function {
         let args: Vec<(String, Type)> = fn_decl
             .args
             .iter()
@@ -544,16 +763,25 @@ mod ir_builder {
         let func = Function::new(
             context,
             module,
-            fn_decl.name,
-            args.clone(),
+            fn_decl.name.clone(),
+            args,
             ret_type,
-            None,
-            false,
+            fn_decl.selector,
+            fn_decl.is_public,
         );
+        func.set_inline(context, fn_decl.inline);
+        func
+    }
 
+    fn build_fn_body(
+        context: &mut Context,
+        func: Function,
+        fn_decl: IrAstFnDecl,
+        md_map: &HashMap<MdIdxRef, MetadataIndex>,
+    ) -> Result<(), String> {
         // Gather all the (new) arg values by name into a map.
         let mut arg_map: HashMap<String, Value> =
-            HashMap::from_iter(args.into_iter().map(|(name, _)| {
+            HashMap::from_iter(fn_decl.args.into_iter().map(|(_, name)| {
                 let arg_val = func.get_arg(context, &name).unwrap();
                 (name, arg_val)
             }));
@@ -585,20 +813,47 @@ mod ir_builder {
             )
         }));
 
+        // The phi values may refer to values in blocks which haven't been added yet, i.e., in a
+        // loop, so their values are only added once all the blocks have been.
+        let mut phi_pairs = Vec::new();
         for block in fn_decl.blocks {
-            build_add_block_instructions(context, block, &named_blocks, &ptr_map, &mut arg_map);
+            build_add_block_instructions(
+                context,
+                block,
+                &named_blocks,
+                &ptr_map,
+                &mut arg_map,
+                &mut phi_pairs,
+                md_map,
+            )?;
+        }
+        for (block, phi_val, pairs) in phi_pairs {
+            for (block_name, val_name) in pairs {
+                block.add_phi_entry(
+                    context,
+                    phi_val,
+                    *named_blocks.get(&block_name).unwrap(),
+                    *arg_map.get(&val_name).unwrap(),
+                );
+            }
         }
         Ok(())
     }
 
+    // A phi instruction in a block and the names of its incoming blocks and values.
+    type PhiPairs = (Block, Value, Vec<(String, String)>);
+
     fn build_add_block_instructions(
         context: &mut Context,
         ir_block: IrAstBlock,
         named_blocks: &HashMap<String, Block>,
         ptr_map: &HashMap<String, Pointer>,
         val_map: &mut HashMap<String, Value>,
-    ) {
+        phi_pairs: &mut Vec<PhiPairs>,
+        md_map: &HashMap<MdIdxRef, MetadataIndex>,
+    ) -> Result<(), String> {
         let block = named_blocks.get(&ir_block.label).unwrap();
+        let mut first_phi = true;
         for ins in ir_block.instructions {
             let ins_val = match ins.op {
                 IrAstOperation::Asm(args, return_name, ops) => {
@@ -622,6 +877,11 @@ mod ir_builder {
                         .collect();
                     block.ins(context).asm_block(args, body, return_name)
                 }
+                IrAstOperation::BinaryOp(op, arg1, arg2) => block.ins(context).binary_op(
+                    op,
+                    *val_map.get(&arg1).unwrap(),
+                    *val_map.get(&arg2).unwrap(),
+                ),
                 IrAstOperation::Br(to_block_name) => {
                     let to_block = named_blocks.get(&to_block_name).unwrap();
                     block.ins(context).branch(*to_block, None)
@@ -655,6 +915,11 @@ mod ir_builder {
                         None,
                     )
                 }
+                IrAstOperation::Cmp(pred, lhs, rhs) => block.ins(context).cmp(
+                    pred,
+                    *val_map.get(&lhs).unwrap(),
+                    *val_map.get(&rhs).unwrap(),
+                ),
                 IrAstOperation::Const(val) => val.as_value(context),
                 IrAstOperation::ExtractElement(aval, ty, idx) => {
                     let ir_ty = ty.to_ir_aggregate_type(context);
@@ -695,14 +960,15 @@ mod ir_builder {
                     block.ins(context).load(*ptr_map.get(&src_name).unwrap())
                 }
                 IrAstOperation::Phi(pairs) => {
-                    for (block_name, val_name) in pairs {
-                        block.add_phi(
-                            context,
-                            *named_blocks.get(&block_name).unwrap(),
-                            *val_map.get(&val_name).unwrap(),
-                        );
-                    }
-                    block.get_phi(context)
+                    // The first phi is created with the block, any others are new.
+                    let phi_val = if first_phi {
+                        first_phi = false;
+                        block.get_phi(context)
+                    } else {
+                        block.new_phi(context)
+                    };
+                    phi_pairs.push((*block, phi_val, pairs));
+                    phi_val
                 }
                 IrAstOperation::Ret(ty, ret_val_name) => {
                     let ty = ty.to_ir_type(context);
@@ -710,13 +976,36 @@ mod ir_builder {
                         .ins(context)
                         .ret(*val_map.get(&ret_val_name).unwrap(), ty)
                 }
+                IrAstOperation::StateLoad(ty, key_name) => {
+                    let ty = ty.to_ir_type(context);
+                    block
+                        .ins(context)
+                        .state_load(*val_map.get(&key_name).unwrap(), ty)
+                }
+                IrAstOperation::StateStore(stored_val_name, key_name) => {
+                    block.ins(context).state_store(
+                        *val_map.get(&key_name).unwrap(),
+                        *val_map.get(&stored_val_name).unwrap(),
+                    )
+                }
                 IrAstOperation::Store(stored_val_name, ptr_name) => block.ins(context).store(
                     *ptr_map.get(&ptr_name).unwrap(),
                     *val_map.get(&stored_val_name).unwrap(),
                 ),
             };
+            let md_idx = ins
+                .md_idx
+                .map(|idx_ref| {
+                    md_map
+                        .get(&idx_ref)
+                        .copied()
+                        .ok_or_else(|| format!("Unknown metadata !{}.", idx_ref))
+                })
+                .transpose()?;
+            let ins_val = ins_val.add_metadatum(context, md_idx);
             ins.value_name.map(|vn| val_map.insert(vn, ins_val));
         }
+        Ok(())
     }
 }
 
@@ -0,0 +1,288 @@
+//! A manager for running optimization passes over a [`Context`].
+//!
+//! Passes are registered with a [`PassManager`] under a unique name, and a [`PassPipeline`] lists
+//! the names of the passes to run, in order.  Each pass returns whether it modified the IR, and
+//! declares which passes may have more work to do when it has.  If the pipeline is run to a
+//! fixpoint then those passes are run again until none of them modify the IR.
+//!
+//! The pipeline may also verify the IR with [`Context::verify`] after every pass which modifies
+//! it, which is useful for tracking down a pass which leaves the IR in an invalid state.
+
+use crate::{context::Context, function::Function, optimize};
+
+/// The passes which may have more work to do after a pass has modified the IR.
+pub enum Invalidates {
+    /// Any pass, including the modifying pass itself.
+    All,
+    /// Only the named passes.
+    Passes(&'static [&'static str]),
+}
+
+/// The function run by a [`Pass`], which returns whether it modified the IR.
+pub type PassRunFn = Box<dyn Fn(&mut Context) -> Result<bool, String>>;
+
+/// An optimization pass.
+pub struct Pass {
+    /// The unique name of the pass, used to refer to it in a [`PassPipeline`].
+    pub name: &'static str,
+    /// A short description of what the pass does.
+    pub descr: &'static str,
+    /// Which passes to run again when this pass modifies the IR.
+    pub invalidates: Invalidates,
+    /// Runs the pass.
+    pub run: PassRunFn,
+}
+
+impl Pass {
+    /// Create a new pass which runs `run` over the whole context.
+    pub fn new<F>(name: &'static str, descr: &'static str, invalidates: Invalidates, run: F) -> Self
+    where
+        F: Fn(&mut Context) -> Result<bool, String> + 'static,
+    {
+        Pass {
+            name,
+            descr,
+            invalidates,
+            run: Box::new(run),
+        }
+    }
+
+    /// Create a new pass which runs `run` over every function in the context in turn.
+    pub fn for_each_function<F>(
+        name: &'static str,
+        descr: &'static str,
+        invalidates: Invalidates,
+        run: F,
+    ) -> Self
+    where
+        F: Fn(&mut Context, &Function) -> Result<bool, String> + 'static,
+    {
+        Pass::new(name, descr, invalidates, move |context| {
+            let functions = context
+                .functions
+                .iter()
+                .map(|(idx, _)| Function(idx))
+                .collect::<Vec<_>>();
+            let mut modified = false;
+            for function in functions {
+                modified |= run(context, &function)?;
+            }
+            Ok(modified)
+        })
+    }
+}
+
+/// Functions with up to this many instructions are always inlined by the `inline-small` pass,
+/// unless they're recursive or hinted not to be.
+pub const INLINE_MAX_INSTR_COUNT: usize = 12;
+
+/// The most times a [`PassPipeline`] will be run when trying to reach a fixpoint.
+const MAX_FIXPOINT_ITERATIONS: usize = 16;
+
+/// A collection of registered passes, which may be run in a [`PassPipeline`].
+#[derive(Default)]
+pub struct PassManager {
+    passes: Vec<Pass>,
+}
+
+impl PassManager {
+    /// Create a new pass manager with no passes registered.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new pass manager with all the passes from the [`optimize`] module registered.
+    pub fn with_standard_passes() -> Self {
+        let mut pass_mgr = Self::new();
+        for pass in standard_passes() {
+            pass_mgr
+                .register(pass)
+                .expect("Standard pass names are unique.");
+        }
+        pass_mgr
+    }
+
+    /// Register a pass.  It is an error to register more than one pass with the same name.
+    pub fn register(&mut self, pass: Pass) -> Result<(), String> {
+        if self.lookup(pass.name).is_some() {
+            return Err(format!("Pass '{}' is already registered.", pass.name));
+        }
+        self.passes.push(pass);
+        Ok(())
+    }
+
+    /// Find the pass registered with `name`.
+    pub fn lookup(&self, name: &str) -> Option<&Pass> {
+        self.passes.iter().find(|pass| pass.name == name)
+    }
+
+    /// Return an iterator over the registered passes, in the order they were registered.
+    pub fn passes(&self) -> impl Iterator<Item = &Pass> {
+        self.passes.iter()
+    }
+
+    /// Run the passes in `pipeline` over `context`, returning whether the IR was modified.
+    pub fn run(&self, context: &mut Context, pipeline: &PassPipeline) -> Result<bool, String> {
+        let passes = pipeline
+            .passes
+            .iter()
+            .map(|name| {
+                self.lookup(name)
+                    .ok_or_else(|| format!("Unknown pass name '{}'.", name))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Every pass is run at least once.  After that only those invalidated by a modification
+        // are, if we're running to a fixpoint.
+        let mut pending = vec![true; passes.len()];
+        let mut modified = false;
+        for _ in 0..MAX_FIXPOINT_ITERATIONS {
+            for (idx, pass) in passes.iter().enumerate() {
+                if !pending[idx] {
+                    continue;
+                }
+                pending[idx] = false;
+                if !(pass.run)(context)? {
+                    continue;
+                }
+                modified = true;
+                for (other_idx, other_pass) in passes.iter().enumerate() {
+                    if match pass.invalidates {
+                        Invalidates::All => true,
+                        Invalidates::Passes(names) => names.contains(&other_pass.name),
+                    } {
+                        pending[other_idx] = true;
+                    }
+                }
+                if pipeline.verify_each {
+                    context.verify().map_err(|msg| {
+                        format!("IR verification failed after pass '{}': {}", pass.name, msg)
+                    })?;
+                }
+            }
+            if !pipeline.fixpoint || !pending.contains(&true) {
+                break;
+            }
+        }
+        Ok(modified)
+    }
+}
+
+fn standard_passes() -> Vec<Pass> {
+    vec![
+        Pass::new(
+            "inline",
+            "Inline all function calls made from `main()`.",
+            Invalidates::All,
+            |context| {
+                let main_fn = context.functions.iter().find_map(|(idx, fc)| {
+                    if fc.name == "main" {
+                        Some(Function(idx))
+                    } else {
+                        None
+                    }
+                });
+                match main_fn {
+                    Some(main_fn) => optimize::inline_all_function_calls(context, &main_fn),
+                    None => Ok(false),
+                }
+            },
+        ),
+        Pass::for_each_function(
+            "inline-small",
+            "Inline calls to functions which are small or called only once, or are hinted to be.",
+            Invalidates::All,
+            |context, function| {
+                optimize::inline_some_function_calls(context, function, |context, inlined_fn| {
+                    optimize::should_inline(context, inlined_fn, INLINE_MAX_INSTR_COUNT)
+                })
+            },
+        ),
+        Pass::for_each_function(
+            "mem2reg",
+            "Promote local variables which fit in a register to SSA values.",
+            Invalidates::Passes(&["inline-small", "constcombine", "simplifycfg", "dce"]),
+            optimize::mem2reg,
+        ),
+        Pass::for_each_function(
+            "constcombine",
+            "Evaluate constant expressions at compile time.",
+            Invalidates::Passes(&["inline-small"]),
+            optimize::combine_constants,
+        ),
+        Pass::for_each_function(
+            "simplifycfg",
+            "Fold constant branches and merge or remove redundant blocks.",
+            Invalidates::All,
+            optimize::simplify_cfg,
+        ),
+        Pass::for_each_function(
+            "dce",
+            "Remove unused instructions which have no side effects.",
+            Invalidates::Passes(&["inline-small", "simplifycfg"]),
+            optimize::dce,
+        ),
+    ]
+}
+
+/// An ordered list of pass names to be run by a [`PassManager`].
+#[derive(Clone, Debug, Default)]
+pub struct PassPipeline {
+    passes: Vec<String>,
+    fixpoint: bool,
+    verify_each: bool,
+}
+
+impl PassPipeline {
+    /// Create a new empty pipeline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create the pipeline for an optimization level.  Level 0 runs no passes, level 1 runs the
+    /// heuristic inliner, local variable promotion and the simplifying passes once each and level
+    /// 2 or above runs them to a fixpoint.
+    pub fn for_opt_level(level: u8) -> Self {
+        match level {
+            0 => Self::new(),
+            1 => Self::new()
+                .add_pass("inline-small")
+                .add_pass("mem2reg")
+                .add_pass("constcombine")
+                .add_pass("simplifycfg")
+                .add_pass("dce"),
+            _ => Self::for_opt_level(1).fixpoint(true),
+        }
+    }
+
+    /// Add the pass named `name` to the end of the pipeline.
+    pub fn add_pass(mut self, name: &str) -> Self {
+        self.passes.push(name.to_owned());
+        self
+    }
+
+    /// Whether to keep re-running passes until none of them modify the IR.
+    pub fn fixpoint(self, a: bool) -> Self {
+        Self {
+            fixpoint: a,
+            ..self
+        }
+    }
+
+    /// Whether to verify the IR after each pass which modifies it.
+    pub fn verify_each(self, a: bool) -> Self {
+        Self {
+            verify_each: a,
+            ..self
+        }
+    }
+
+    /// Return the names of the passes in the pipeline, in order.
+    pub fn pass_names(&self) -> impl Iterator<Item = &str> {
+        self.passes.iter().map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+}
@@ -9,9 +9,10 @@ use crate::{
     block::Block,
     constant::{Constant, ConstantValue},
     context::Context,
-    function::{Function, FunctionContent},
+    function::{Function, FunctionContent, Inline},
     instruction::Instruction,
     irtype::Type,
+    metadata::{MetadataIndex, Metadatum},
     module::{Kind, ModuleContent},
     pointer::{Pointer, PointerContent},
     value::{Value, ValueContent},
@@ -75,23 +76,32 @@ impl Doc {
 /// Pretty-print a whole [`Context`] to a string.
 ///
 /// The ouput from this function must always be suitable for [`crate::parser::parse`].
+///
+/// Any metadata referred to by the instructions is printed after the modules, and each instruction
+/// with metadata refers to it by its index, e.g., `ret u64 v0, !1`.
 pub fn to_string(context: &Context) -> String {
+    let mut md_namer = MetadataNamer::default();
     context
         .modules
         .iter()
         .fold(Doc::Empty, |doc, (_, module)| {
-            doc.append(module_to_doc(context, module))
+            doc.append(module_to_doc(context, &mut md_namer, module))
         })
+        .append(md_namer.to_doc(context))
         .build()
 }
 
-fn module_to_doc<'a>(context: &'a Context, module: &'a ModuleContent) -> Doc {
+fn module_to_doc<'a>(
+    context: &'a Context,
+    md_namer: &mut MetadataNamer,
+    module: &'a ModuleContent,
+) -> Doc {
     Doc::line(Doc::Text(format!(
         "{} {} {{",
         match module.kind {
             Kind::Contract => "contract",
             Kind::Library => "library",
-            Kind::Predicate => "predicate ",
+            Kind::Predicate => "predicate",
             Kind::Script => "script",
         },
         &module.name
@@ -105,6 +115,7 @@ fn module_to_doc<'a>(context: &'a Context, module: &'a ModuleContent) -> Doc {
                 .map(|function| {
                     function_to_doc(
                         context,
+                        md_namer,
                         &mut Namer::new(*function),
                         &context.functions[function.0],
                     )
@@ -118,13 +129,19 @@ fn module_to_doc<'a>(context: &'a Context, module: &'a ModuleContent) -> Doc {
 
 fn function_to_doc<'a>(
     context: &'a Context,
+    md_namer: &mut MetadataNamer,
     namer: &mut Namer,
     function: &'a FunctionContent,
 ) -> Doc {
     Doc::line(
         Doc::text(format!(
-            "{}fn {}{}",
+            "{}{}fn {}{}",
             if function.is_public { "pub " } else { "" },
+            match function.inline {
+                None => "",
+                Some(Inline::Always) => "inline(always) ",
+                Some(Inline::Never) => "inline(never) ",
+            },
             function.name,
             match function.selector {
                 None => "".to_owned(),
@@ -180,7 +197,7 @@ fn function_to_doc<'a>(
                     function
                         .blocks
                         .iter()
-                        .map(|block| block_to_doc(context, namer, block))
+                        .map(|block| block_to_doc(context, md_namer, namer, block))
                         .collect(),
                     Doc::line(Doc::Empty),
                 ),
@@ -191,13 +208,18 @@ fn function_to_doc<'a>(
     .append(Doc::line(Doc::text("}")))
 }
 
-fn block_to_doc<'a>(context: &'a Context, namer: &mut Namer, block: &Block) -> Doc {
+fn block_to_doc<'a>(
+    context: &'a Context,
+    md_namer: &mut MetadataNamer,
+    namer: &mut Namer,
+    block: &Block,
+) -> Doc {
     let block_content = &context.blocks[block.0];
     Doc::line(Doc::text(format!("{}:", block_content.label))).append(Doc::List(
         block_content
             .instructions
             .iter()
-            .map(|ins| instruction_to_doc(context, namer, block, ins))
+            .map(|ins| instruction_to_doc(context, md_namer, namer, block, ins))
             .collect(),
     ))
 }
@@ -224,33 +246,43 @@ fn maybe_constant_phi_to_doc(
     caller: &Block,
     callee: &Block,
 ) -> Doc {
-    if let ValueContent::Instruction(Instruction::Phi(pairs)) =
-        &context.values[callee.get_phi(context).0]
-    {
-        pairs
-            .iter()
-            .find(|(block, _)| block == caller)
-            .map(|(_, phi_val)| maybe_constant_to_doc(context, namer, phi_val))
-            .unwrap_or(Doc::Empty)
-    } else {
-        unreachable!("Phi must be an instruction.")
-    }
+    callee
+        .get_phi_vals_coming_from(context, caller)
+        .iter()
+        .fold(Doc::Empty, |doc, (_, phi_val)| {
+            doc.append(maybe_constant_to_doc(context, namer, phi_val))
+        })
 }
 
 fn instruction_to_doc<'a>(
     context: &'a Context,
+    md_namer: &mut MetadataNamer,
     namer: &mut Namer,
     block: &Block,
     ins_value: &'a Value,
 ) -> Doc {
+    // The metadata reference, if any, goes at the end of the instruction's line.
+    let md_doc = md_namer.md_idx_to_doc(context, ins_value.get_metadata(context));
     match &context.values[ins_value.0] {
         ValueContent::Instruction(instruction) => match instruction {
             Instruction::AsmBlock(asm, args) => {
-                asm_block_to_doc(context, namer, ins_value, asm, args)
+                asm_block_to_doc(context, namer, ins_value, asm, args, md_doc)
             }
+            Instruction::BinaryOp { op, arg1, arg2 } => maybe_constant_to_doc(context, namer, arg1)
+                .append(maybe_constant_to_doc(context, namer, arg2))
+                .append(Doc::line(
+                    Doc::text(format!(
+                        "{} = {} {}, {}",
+                        namer.name(context, ins_value),
+                        op.as_str(),
+                        namer.name(context, arg1),
+                        namer.name(context, arg2),
+                    ))
+                    .append(md_doc),
+                )),
             Instruction::Branch(to_block) => {
-                maybe_constant_phi_to_doc(context, namer, block, to_block).append(Doc::text_line(
-                    format!("br {}", context.blocks[to_block.0].label),
+                maybe_constant_phi_to_doc(context, namer, block, to_block).append(Doc::line(
+                    Doc::text(format!("br {}", context.blocks[to_block.0].label)).append(md_doc),
                 ))
             }
             Instruction::Call(func, args) => args
@@ -272,7 +304,20 @@ fn instruction_to_doc<'a>(
                         args.iter()
                             .map(|arg_val| Doc::text(namer.name(context, arg_val)))
                             .collect(),
-                    )),
+                    ))
+                    .append(md_doc),
+                )),
+            Instruction::Cmp(pred, lhs, rhs) => maybe_constant_to_doc(context, namer, lhs)
+                .append(maybe_constant_to_doc(context, namer, rhs))
+                .append(Doc::line(
+                    Doc::text(format!(
+                        "{} = cmp {} {}, {}",
+                        namer.name(context, ins_value),
+                        pred.as_str(),
+                        namer.name(context, lhs),
+                        namer.name(context, rhs),
+                    ))
+                    .append(md_doc),
                 )),
             Instruction::ConditionalBranch {
                 cond_value,
@@ -281,28 +326,48 @@ fn instruction_to_doc<'a>(
             } => {
                 let true_label = &context.blocks[true_block.0].label;
                 let false_label = &context.blocks[false_block.0].label;
+                // The false block's phi values only need printing if they haven't been already.
+                let mut printed_vals = true_block
+                    .get_phi_vals_coming_from(context, block)
+                    .into_iter()
+                    .map(|(_, phi_val)| phi_val)
+                    .collect::<Vec<_>>();
+                printed_vals.push(*cond_value);
                 maybe_constant_phi_to_doc(context, namer, block, true_block)
+                    .append(
+                        false_block
+                            .get_phi_vals_coming_from(context, block)
+                            .iter()
+                            .filter(|(_, phi_val)| !printed_vals.contains(phi_val))
+                            .fold(Doc::Empty, |doc, (_, phi_val)| {
+                                doc.append(maybe_constant_to_doc(context, namer, phi_val))
+                            }),
+                    )
                     .append(maybe_constant_to_doc(context, namer, cond_value))
-                    .append(Doc::text_line(format!(
-                        "cbr {}, {}, {}",
-                        namer.name(context, cond_value),
-                        true_label,
-                        false_label
-                    )))
+                    .append(Doc::line(
+                        Doc::text(format!(
+                            "cbr {}, {}, {}",
+                            namer.name(context, cond_value),
+                            true_label,
+                            false_label
+                        ))
+                        .append(md_doc),
+                    ))
             }
             Instruction::ExtractElement {
                 array,
                 ty,
                 index_val,
-            } => maybe_constant_to_doc(context, namer, index_val).append(Doc::line(Doc::text(
-                format!(
+            } => maybe_constant_to_doc(context, namer, index_val).append(Doc::line(
+                Doc::text(format!(
                     "{} = extract_element {}, {}, {}",
                     namer.name(context, ins_value),
                     namer.name(context, array),
                     Type::Array(*ty).as_string(context),
                     namer.name(context, index_val),
-                ),
-            ))),
+                ))
+                .append(md_doc),
+            )),
             Instruction::ExtractValue {
                 aggregate,
                 ty,
@@ -320,18 +385,22 @@ fn instruction_to_doc<'a>(
                         .map(|idx| Doc::text(format!("{}", idx)))
                         .collect(),
                     Doc::Comma,
-                )),
+                ))
+                .append(md_doc),
             ),
             Instruction::GetPointer(ptr) => {
                 let name = block
                     .get_function(context)
                     .lookup_local_name(context, ptr)
                     .unwrap();
-                Doc::text_line(format!(
-                    "{} = get_ptr {}",
-                    namer.name(context, ins_value),
-                    ptr.as_string(context, name)
-                ))
+                Doc::line(
+                    Doc::text(format!(
+                        "{} = get_ptr {}",
+                        namer.name(context, ins_value),
+                        ptr.as_string(context, name)
+                    ))
+                    .append(md_doc),
+                )
             }
             Instruction::InsertElement {
                 array,
@@ -341,14 +410,17 @@ fn instruction_to_doc<'a>(
             } => maybe_constant_to_doc(context, namer, array)
                 .append(maybe_constant_to_doc(context, namer, value))
                 .append(maybe_constant_to_doc(context, namer, index_val))
-                .append(Doc::line(Doc::text(format!(
-                    "{} = insert_element {}, {}, {}, {}",
-                    namer.name(context, ins_value),
-                    namer.name(context, array),
-                    Type::Array(*ty).as_string(context),
-                    namer.name(context, value),
-                    namer.name(context, index_val),
-                )))),
+                .append(Doc::line(
+                    Doc::text(format!(
+                        "{} = insert_element {}, {}, {}, {}",
+                        namer.name(context, ins_value),
+                        namer.name(context, array),
+                        Type::Array(*ty).as_string(context),
+                        namer.name(context, value),
+                        namer.name(context, index_val),
+                    ))
+                    .append(md_doc),
+                )),
             Instruction::InsertValue {
                 aggregate,
                 ty,
@@ -370,18 +442,22 @@ fn instruction_to_doc<'a>(
                             .map(|idx| Doc::text(format!("{}", idx)))
                             .collect(),
                         Doc::Comma,
-                    )),
+                    ))
+                    .append(md_doc),
                 )),
             Instruction::Load(ptr) => {
                 let name = block
                     .get_function(context)
                     .lookup_local_name(context, ptr)
                     .unwrap();
-                Doc::text_line(format!(
-                    "{} = load {}",
-                    namer.name(context, ins_value),
-                    ptr.as_string(context, name)
-                ))
+                Doc::line(
+                    Doc::text(format!(
+                        "{} = load {}",
+                        namer.name(context, ins_value),
+                        ptr.as_string(context, name)
+                    ))
+                    .append(md_doc),
+                )
             }
             Instruction::Phi(pairs) => {
                 if pairs.is_empty() {
@@ -402,27 +478,54 @@ fn instruction_to_doc<'a>(
                     );
                     Doc::line(
                         Doc::text(format!("{} = phi", namer.name(context, ins_value)))
-                            .append(pairs_doc),
+                            .append(pairs_doc)
+                            .append(md_doc),
                     )
                 }
             }
-            Instruction::Ret(v, t) => {
-                maybe_constant_to_doc(context, namer, v).append(Doc::text_line(format!(
+            Instruction::Ret(v, t) => maybe_constant_to_doc(context, namer, v).append(Doc::line(
+                Doc::text(format!(
                     "ret {} {}",
                     t.as_string(context),
                     namer.name(context, v)
-                )))
+                ))
+                .append(md_doc),
+            )),
+            Instruction::StateLoad { key, ty } => maybe_constant_to_doc(context, namer, key)
+                .append(Doc::line(
+                    Doc::text(format!(
+                        "{} = state_load {} key {}",
+                        namer.name(context, ins_value),
+                        ty.as_string(context),
+                        namer.name(context, key),
+                    ))
+                    .append(md_doc),
+                )),
+            Instruction::StateStore { key, stored_val } => {
+                maybe_constant_to_doc(context, namer, key)
+                    .append(maybe_constant_to_doc(context, namer, stored_val))
+                    .append(Doc::line(
+                        Doc::text(format!(
+                            "state_store {}, key {}",
+                            namer.name(context, stored_val),
+                            namer.name(context, key),
+                        ))
+                        .append(md_doc),
+                    ))
             }
             Instruction::Store { ptr, stored_val } => {
                 let name = block
                     .get_function(context)
                     .lookup_local_name(context, ptr)
                     .unwrap();
-                maybe_constant_to_doc(context, namer, stored_val).append(Doc::text_line(format!(
-                    "store {}, {}",
-                    namer.name(context, stored_val),
-                    ptr.as_string(context, name),
-                )))
+                maybe_constant_to_doc(context, namer, stored_val).append(Doc::line(
+                    Doc::text(format!(
+                        "store {}, {}",
+                        namer.name(context, stored_val),
+                        ptr.as_string(context, name),
+                    ))
+                    .append(md_doc),
+                ))
             }
         },
         _ => unreachable!("Unexpected non instruction for block contents."),
@@ -435,6 +538,7 @@ fn asm_block_to_doc(
     ins_value: &Value,
     asm: &AsmBlock,
     args: &[AsmArg],
+    md_doc: Doc,
 ) -> Doc {
     let AsmBlockContent {
         body, return_name, ..
@@ -495,7 +599,7 @@ fn asm_block_to_doc(
                     .collect(),
             ),
         ))
-        .append(Doc::text_line("}"))
+        .append(Doc::line(Doc::text("}").append(md_doc)))
 }
 
 impl Value {
@@ -515,6 +619,14 @@ impl Constant {
             ConstantValue::Unit => "unit ()".into(),
             ConstantValue::Bool(b) => format!("bool {}", if *b { "true" } else { "false" }),
             ConstantValue::Uint(v) => format!("{} {}", self.ty.as_string(context), v),
+            ConstantValue::WideUint(bs) => format!(
+                "{} 0x{}",
+                self.ty.as_string(context),
+                bs.iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect::<Vec<String>>()
+                    .concat()
+            ),
             ConstantValue::B256(bs) => format!(
                 "b256 0x{}",
                 bs.iter()
@@ -596,6 +708,70 @@ impl Namer {
     }
 }
 
+// Metadata is numbered in the order it's first referred to, and a span's file is numbered
+// immediately after it.
+#[derive(Default)]
+struct MetadataNamer {
+    next_idx: u64,
+    names: std::collections::HashMap<MetadataIndex, u64>,
+}
+
+impl MetadataNamer {
+    fn name(&mut self, context: &Context, md_idx: &MetadataIndex) -> u64 {
+        match self.names.get(md_idx) {
+            Some(name) => *name,
+            None => {
+                let name = self.next_idx;
+                self.next_idx += 1;
+                self.names.insert(*md_idx, name);
+                if let Metadatum::Span { loc_idx, .. } = &context.metadata[md_idx.0] {
+                    self.name(context, loc_idx);
+                }
+                name
+            }
+        }
+    }
+
+    fn md_idx_to_doc(&mut self, context: &Context, md_idx: Option<MetadataIndex>) -> Doc {
+        match md_idx {
+            Some(md_idx) => Doc::text(format!(", !{}", self.name(context, &md_idx))),
+            None => Doc::Empty,
+        }
+    }
+
+    fn to_doc(&self, context: &Context) -> Doc {
+        if self.names.is_empty() {
+            return Doc::Empty;
+        }
+        let mut md_idcs = self.names.iter().collect::<Vec<_>>();
+        md_idcs.sort_by_key(|(_, name)| **name);
+        Doc::line(Doc::Empty).append(Doc::List(
+            md_idcs
+                .into_iter()
+                .map(|(md_idx, name)| {
+                    Doc::text_line(match &context.metadata[md_idx.0] {
+                        Metadatum::FileLocation(path, _) => format!(
+                            "!{} = filepath \"{}\"",
+                            name,
+                            path.to_string_lossy()
+                                .replace('\\', "\\\\")
+                                .replace('"', "\\\"")
+                        ),
+                        Metadatum::Span {
+                            loc_idx,
+                            start,
+                            end,
+                        } => format!(
+                            "!{} = span !{} {} {}",
+                            name, self.names[loc_idx], start, end
+                        ),
+                    })
+                })
+                .collect(),
+        ))
+    }
+}
+
 /// There will be a much more efficient way to do this, but for now this will do.
 fn build_doc(doc: Doc, indent: i64) -> String {
     match doc {
@@ -6,7 +6,10 @@
 //! Like most IR data structures they are `Copy` and cheap to pass around by value.  They are
 //! therefore also easy to replace, a common practise for optimization passes.
 
-use crate::{constant::Constant, context::Context, instruction::Instruction, irtype::Type};
+use crate::{
+    constant::Constant, context::Context, instruction::Instruction, irtype::Type,
+    metadata::MetadataIndex,
+};
 
 /// A wrapper around an [ECS](https://github.com/fitzgen/generational-arena) handle into the
 /// [`Context`].
@@ -40,6 +43,23 @@ impl Value {
         Value(context.values.insert(content))
     }
 
+    /// Attach the metadata at `md_idx` to this value, replacing any it already has, and return the
+    /// value.  If `md_idx` is `None` then the value is left as it is.
+    ///
+    /// This is convenient for tagging instructions as they're created, e.g.,
+    /// `block.ins(context).load(ptr).add_metadatum(context, span_md_idx)`.
+    pub fn add_metadatum(self, context: &mut Context, md_idx: Option<MetadataIndex>) -> Self {
+        if let Some(md_idx) = md_idx {
+            context.value_metadata.insert(self, md_idx);
+        }
+        self
+    }
+
+    /// Get the index of the metadata attached to this value, if any.
+    pub fn get_metadata(&self, context: &Context) -> Option<MetadataIndex> {
+        context.value_metadata.get(self).copied()
+    }
+
     /// Return whether this is a constant value.
     pub fn is_constant(&self, context: &Context) -> bool {
         matches!(context.values[self.0], ValueContent::Constant(_))
@@ -14,7 +14,7 @@ use crate::{
     block::{Block, BlockContent},
     context::Context,
     function::{Function, FunctionContent},
-    instruction::Instruction,
+    instruction::{BinaryOpKind, Instruction},
     irtype::{Aggregate, Type},
     module::ModuleContent,
     pointer::Pointer,
@@ -48,6 +48,23 @@ impl Context {
         for ins in &block.instructions {
             self.verify_instruction(function, &self.values[ins.0])?;
         }
+        let is_phi = |ins: &Value| {
+            matches!(
+                self.values[ins.0],
+                ValueContent::Instruction(Instruction::Phi(_))
+            )
+        };
+        let num_phis = block
+            .instructions
+            .iter()
+            .take_while(|ins| is_phi(ins))
+            .count();
+        if num_phis == 0 || block.instructions[num_phis..].iter().any(is_phi) {
+            return Err(format!(
+                "Block {} must have its phi instructions at its start.\n\n{}",
+                block.label, self
+            ));
+        }
         let (last_is_term, num_terms) =
             block.instructions.iter().fold((false, 0), |(_, n), ins| {
                 if ins.is_terminator(self) {
@@ -74,8 +91,12 @@ impl Context {
         if let ValueContent::Instruction(instruction) = instruction {
             match instruction {
                 Instruction::AsmBlock(asm, args) => self.verify_asm_block(asm, args)?,
+                Instruction::BinaryOp { op, arg1, arg2 } => {
+                    self.verify_binary_op(op, arg1, arg2)?
+                }
                 Instruction::Branch(block) => self.verify_br(block)?,
                 Instruction::Call(func, args) => self.verify_call(func, args)?,
+                Instruction::Cmp(_, lhs, rhs) => self.verify_cmp(lhs, rhs)?,
                 Instruction::ConditionalBranch {
                     cond_value,
                     true_block,
@@ -107,6 +128,10 @@ impl Context {
                 Instruction::Load(ptr) => self.verify_load(ptr)?,
                 Instruction::Phi(pairs) => self.verify_phi(&pairs[..])?,
                 Instruction::Ret(val, ty) => self.verify_ret(function, val, ty)?,
+                Instruction::StateLoad { key, ty } => self.verify_state_load(key, ty)?,
+                Instruction::StateStore { key, stored_val } => {
+                    self.verify_state_store(key, stored_val)?
+                }
                 Instruction::Store { ptr, stored_val } => self.verify_store(ptr, stored_val)?,
             }
         } else {
@@ -119,6 +144,23 @@ impl Context {
         Ok(())
     }
 
+    fn verify_binary_op(
+        &self,
+        op: &BinaryOpKind,
+        arg1: &Value,
+        arg2: &Value,
+    ) -> Result<(), String> {
+        if op.is_shift() {
+            match (arg1.get_type(self), arg2.get_type(self)) {
+                (Some(Type::Uint(_)), Some(Type::Uint(_))) => Ok(()),
+                _otherwise => Err("Shift args must be integers.".into()),
+            }
+        } else {
+            self.verify_integer_operands(arg1, arg2)
+                .map_err(|_| "Binary op args must be integers of the same type.".into())
+        }
+    }
+
     fn verify_br(&self, _block: &Block) -> Result<(), String> {
         Ok(())
     }
@@ -144,6 +186,20 @@ impl Context {
         //}
     }
 
+    fn verify_cmp(&self, lhs: &Value, rhs: &Value) -> Result<(), String> {
+        self.verify_integer_operands(lhs, rhs)
+            .map_err(|_| "Cmp args must be integers of the same type.".into())
+    }
+
+    fn verify_integer_operands(&self, lhs: &Value, rhs: &Value) -> Result<(), ()> {
+        match (lhs.get_type(self), rhs.get_type(self)) {
+            (Some(Type::Uint(lhs_bits)), Some(Type::Uint(rhs_bits))) if lhs_bits == rhs_bits => {
+                Ok(())
+            }
+            _otherwise => Err(()),
+        }
+    }
+
     fn verify_extract_element(
         &self,
         _array: &Value,
@@ -226,6 +282,15 @@ impl Context {
         }
     }
 
+    fn verify_state_load(&self, _key: &Value, _ty: &Type) -> Result<(), String> {
+        // XXX The key should be a b256, once we have type info available from instructions.
+        Ok(())
+    }
+
+    fn verify_state_store(&self, _key: &Value, _stored_val: &Value) -> Result<(), String> {
+        Ok(())
+    }
+
     fn verify_store(&self, _ptr: &Pointer, _stored_val: &Value) -> Result<(), String> {
         // XXX When we have some type info available from instructions...
         //if ptr_val.get_type(self) != stored_val.get_type(self) {
@@ -18,10 +18,18 @@ fn ir_to_ir_tests() {
                 // should use a comment within the test file to invoke `lit`.
 
                 let path_str = path.file_name().unwrap().to_string_lossy();
-                if path_str.starts_with("inline") {
-                    test_inline(path);
+                if path_str.starts_with("inline_heuristic") {
+                    test_inline_heuristic(path);
+                } else if path_str.starts_with("inline") {
+                    test_pass(path, "inline");
                 } else if path_str.starts_with("constants") {
-                    test_constants(path);
+                    test_pass(path, "constcombine");
+                } else if path_str.starts_with("dce") {
+                    test_pass(path, "dce");
+                } else if path_str.starts_with("mem2reg") {
+                    test_pass(path, "mem2reg");
+                } else if path_str.starts_with("simplifycfg") {
+                    test_pass(path, "simplifycfg");
                 } else {
                     panic!(
                         "File which doesn't match valid passes: {:?}",
@@ -40,7 +48,7 @@ fn ir_to_ir_tests() {
 
 // -------------------------------------------------------------------------------------------------
 
-fn test_inline(mut path: PathBuf) {
+fn test_pass(mut path: PathBuf, pass_name: &str) {
     let input_bytes = std::fs::read(&path).unwrap();
     let input = String::from_utf8_lossy(&input_bytes);
 
@@ -50,12 +58,11 @@ fn test_inline(mut path: PathBuf) {
     let expected = String::from_utf8_lossy(&expected_bytes);
 
     let mut ir = sway_ir::parser::parse(&input).unwrap();
-    let main_fn = ir
-        .functions
-        .iter()
-        .find_map(|(idx, fc)| if fc.name == "main" { Some(idx) } else { None })
-        .unwrap();
-    sway_ir::optimize::inline_all_function_calls(&mut ir, &sway_ir::function::Function(main_fn))
+    let pipeline = sway_ir::PassPipeline::new()
+        .add_pass(pass_name)
+        .verify_each(true);
+    sway_ir::PassManager::with_standard_passes()
+        .run(&mut ir, &pipeline)
         .unwrap();
     let output = sway_ir::printer::to_string(&ir);
 
@@ -67,7 +74,7 @@ fn test_inline(mut path: PathBuf) {
 
 // -------------------------------------------------------------------------------------------------
 
-fn test_constants(mut path: PathBuf) {
+fn test_inline_heuristic(mut path: PathBuf) {
     let input_bytes = std::fs::read(&path).unwrap();
     let input = String::from_utf8_lossy(&input_bytes);
 
@@ -77,11 +84,16 @@ fn test_constants(mut path: PathBuf) {
     let expected = String::from_utf8_lossy(&expected_bytes);
 
     let mut ir = sway_ir::parser::parse(&input).unwrap();
-
-    let fn_idcs: Vec<_> = ir.functions.iter().map(|func| func.0).collect();
-    for fn_idx in fn_idcs {
-        sway_ir::optimize::combine_constants(&mut ir, &sway_ir::function::Function(fn_idx))
-            .unwrap();
+    let funcs = ir
+        .functions
+        .iter()
+        .map(|(idx, _)| sway_ir::function::Function(idx))
+        .collect::<Vec<_>>();
+    for func in funcs {
+        sway_ir::optimize::inline_some_function_calls(&mut ir, &func, |context, inlined_func| {
+            sway_ir::optimize::should_inline(context, inlined_func, 4)
+        })
+        .unwrap();
     }
     let output = sway_ir::printer::to_string(&ir);
 
@@ -92,3 +104,191 @@ fn test_constants(mut path: PathBuf) {
 }
 
 // -------------------------------------------------------------------------------------------------
+
+#[test]
+fn pass_manager_fixpoint() {
+    use std::{cell::Cell, rc::Rc};
+    use sway_ir::{Context, Invalidates, Pass, PassManager, PassPipeline};
+
+    // `countdown` modifies the IR until it reaches zero, and each time `counter` has more to do.
+    let remaining = Rc::new(Cell::new(3u32));
+    let countdown_runs = Rc::new(Cell::new(0));
+    let counter_runs = Rc::new(Cell::new(0));
+
+    let mut pass_mgr = PassManager::new();
+    let (rem, runs) = (remaining.clone(), countdown_runs.clone());
+    pass_mgr
+        .register(Pass::new(
+            "countdown",
+            "Count down to zero.",
+            Invalidates::Passes(&["countdown", "counter"]),
+            move |_| {
+                runs.set(runs.get() + 1);
+                let modified = rem.get() > 0;
+                rem.set(rem.get().saturating_sub(1));
+                Ok(modified)
+            },
+        ))
+        .unwrap();
+    let runs = counter_runs.clone();
+    pass_mgr
+        .register(Pass::new(
+            "counter",
+            "Count the runs.",
+            Invalidates::All,
+            move |_| {
+                runs.set(runs.get() + 1);
+                Ok(false)
+            },
+        ))
+        .unwrap();
+    assert!(pass_mgr
+        .register(Pass::new("counter", "", Invalidates::All, |_| Ok(false)))
+        .is_err());
+
+    let mut ir = Context::default();
+    let pipeline = PassPipeline::new()
+        .add_pass("countdown")
+        .add_pass("counter");
+
+    assert!(pass_mgr.run(&mut ir, &pipeline).unwrap());
+    assert_eq!((countdown_runs.get(), counter_runs.get()), (1, 1));
+
+    assert!(pass_mgr
+        .run(&mut ir, &pipeline.clone().fixpoint(true))
+        .unwrap());
+    assert_eq!((countdown_runs.get(), counter_runs.get()), (4, 3));
+
+    assert!(!pass_mgr.run(&mut ir, &pipeline).unwrap());
+    assert!(pass_mgr
+        .run(&mut ir, &PassPipeline::new().add_pass("nothing"))
+        .is_err());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[test]
+fn dominator_tree() {
+    use sway_ir::{Block, DominatorTree, Function};
+
+    // A diamond inside a loop, followed by a block which is unreachable.
+    let ir = sway_ir::parser::parse(
+        r#"
+script script {
+    fn main(c: bool) -> () {
+        entry:
+        br header
+
+        header:
+        cbr c, left, right
+
+        left:
+        br join
+
+        right:
+        br join
+
+        join:
+        cbr c, header, exit
+
+        exit:
+        v0 = const unit ()
+        ret () v0
+
+        dead:
+        br exit
+    }
+}
+"#,
+    )
+    .unwrap();
+    let (func_idx, _) = ir.functions.iter().next().unwrap();
+    let function = Function(func_idx);
+    let block = |label: &str| {
+        function
+            .block_iter(&ir)
+            .find(|block| block.get_label(&ir) == label)
+            .unwrap()
+    };
+    let [entry, header, left, right, join, exit, dead]: [Block; 7] =
+        ["entry", "header", "left", "right", "join", "exit", "dead"].map(block);
+
+    let dom_tree = DominatorTree::new(&ir, &function);
+    assert_eq!(
+        dom_tree.reverse_postorder(),
+        &[entry, header, right, left, join, exit]
+    );
+    assert!(!dom_tree.is_reachable(&dead));
+
+    assert_eq!(dom_tree.immediate_dominator(&entry), None);
+    assert_eq!(dom_tree.immediate_dominator(&header), Some(entry));
+    assert_eq!(dom_tree.immediate_dominator(&left), Some(header));
+    assert_eq!(dom_tree.immediate_dominator(&join), Some(header));
+    assert_eq!(dom_tree.immediate_dominator(&exit), Some(join));
+    assert_eq!(dom_tree.immediate_dominator(&dead), None);
+    assert_eq!(dom_tree.children(&header), &[right, left, join]);
+
+    assert!(dom_tree.dominates(&header, &exit));
+    assert!(dom_tree.dominates(&join, &join));
+    assert!(!dom_tree.dominates(&left, &join));
+    assert!(!dom_tree.dominates(&dead, &exit));
+
+    assert_eq!(dom_tree.dominance_frontier(&left), &[join]);
+    assert_eq!(dom_tree.dominance_frontier(&join), &[header]);
+    assert_eq!(dom_tree.dominance_frontier(&header), &[header]);
+    assert!(dom_tree.dominance_frontier(&exit).is_empty());
+    assert_eq!(
+        dom_tree.iterated_dominance_frontier([left]),
+        vec![header, join]
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[test]
+fn metadata_spans() {
+    use std::{path::PathBuf, sync::Arc};
+    use sway_ir::{Function, MetadataIndex};
+    use sway_types::span::Span;
+
+    let src: Arc<str> = Arc::from("script;\n\nfn main() -> u64 {\n    42\n}\n");
+    let span = Span {
+        span: pest::Span::new(src.clone(), 32, 34).unwrap(),
+        path: Some(Arc::new(PathBuf::from("/path/to/main.sw"))),
+    };
+
+    let mut ir = sway_ir::parser::parse(
+        r#"
+script script {
+    fn main() -> u64 {
+        entry:
+        v0 = const u64 42
+        ret u64 v0
+    }
+}
+"#,
+    )
+    .unwrap();
+    let (func_idx, _) = ir.functions.iter().next().unwrap();
+    let (_, ret_val) = Function(func_idx).instruction_iter(&ir).last().unwrap();
+    let span_md_idx = MetadataIndex::from_span(&mut ir, &span);
+    ret_val.add_metadatum(&mut ir, span_md_idx);
+
+    let to_span = ret_val.get_metadata(&ir).unwrap().to_span(&ir).unwrap();
+    assert_eq!(to_span.as_str(), "42");
+    assert_eq!(to_span.path, span.path);
+
+    // Spans which don't refer to a file are ignored.
+    let no_path_span = Span { path: None, ..span };
+    assert!(MetadataIndex::from_span(&mut ir, &no_path_span).is_none());
+
+    let output = sway_ir::printer::to_string(&ir);
+    assert!(output.contains("ret u64 v0, !0\n"));
+    assert!(output.ends_with("\n!0 = span !1 32 34\n!1 = filepath \"/path/to/main.sw\"\n"));
+    assert_eq!(
+        sway_ir::printer::to_string(&sway_ir::parser::parse(&output).unwrap()),
+        output
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -67,6 +67,11 @@ fn get_hover_format(token: &Token, documents: &Documents) -> Hover {
             &token.name
         ),
         TokenType::Enum => format!("enum {}", &token.name),
+        TokenType::TypeAlias(type_alias_details) => format!(
+            "{}type {}",
+            extract_visibility(&type_alias_details.visibility),
+            &token.name
+        ),
         _ => token.name.clone(),
     };
 
@@ -1,6 +1,6 @@
 use super::token_type::{get_trait_details, TokenType, VariableDetails};
 use crate::{
-    core::token_type::{get_function_details, get_struct_details},
+    core::token_type::{get_function_details, get_struct_details, get_type_alias_details},
     utils::common::extract_var_body,
 };
 use lspower::lsp::{Position, Range};
@@ -149,6 +149,14 @@ fn handle_declaration(declaration: Declaration, tokens: &mut Vec<Token>) {
             let token = Token::from_ident(&ident, TokenType::Enum);
             tokens.push(token);
         }
+        Declaration::TypeAliasDeclaration(type_alias_dec) => {
+            let ident = &type_alias_dec.name;
+            let token = Token::from_ident(
+                ident,
+                TokenType::TypeAlias(get_type_alias_details(&type_alias_dec)),
+            );
+            tokens.push(token);
+        }
         _ => {}
     };
 }
@@ -1,4 +1,4 @@
-use sway_core::{Literal, VariableDeclaration, Visibility};
+use sway_core::{Literal, TypeInfo, VariableDeclaration, Visibility};
 
 use crate::core::token_type::VarBody;
 use sway_core::Expression;
@@ -11,6 +11,13 @@ pub(crate) fn extract_visibility(visibility: &Visibility) -> String {
 }
 
 pub(crate) fn extract_var_body(var_dec: &VariableDeclaration) -> VarBody {
+    // a variable annotated with a named type, which may be a type alias, is shown with its type
+    // as written
+    if let (TypeInfo::Custom { .. }, Some(type_span)) =
+        (&var_dec.type_ascription, &var_dec.type_ascription_span)
+    {
+        return VarBody::Type(type_span.as_str().into());
+    }
     match &var_dec.body {
         Expression::FunctionApplication { name, .. } => {
             VarBody::FunctionCall(name.suffix.as_str().into())
@@ -23,6 +30,12 @@ pub(crate) fn extract_var_body(var_dec: &VariableDeclaration) -> VarBody {
             Literal::U16(_) => VarBody::Type("u16".into()),
             Literal::U32(_) => VarBody::Type("u32".into()),
             Literal::U64(_) => VarBody::Type("u64".into()),
+            Literal::U128(_) => VarBody::Type("u128".into()),
+            Literal::U256(_) => VarBody::Type("u256".into()),
+            Literal::I8(_) => VarBody::Type("i8".into()),
+            Literal::I16(_) => VarBody::Type("i16".into()),
+            Literal::I32(_) => VarBody::Type("i32".into()),
+            Literal::I64(_) => VarBody::Type("i64".into()),
             Literal::Numeric(_) => VarBody::Type("u64".into()),
             Literal::String(len) => VarBody::Type(format!("str[{}]", len.as_str().len())),
             Literal::Boolean(_) => VarBody::Type("bool".into()),
@@ -361,3 +361,34 @@ pub struct Property {
     pub type_field: String,
     pub components: Option<Vec<Property>>, // Used for custom types
 }
+
+/// The types of the values which the entry points of a program may log through the `log`
+/// intrinsic, emitted alongside the [JsonABI] rather than as a section of it, so that the
+/// [JsonABI] remains an array of functions.
+pub type LoggedTypes = Vec<LoggedType>;
+
+/// A type of value which is logged through the `log` intrinsic. Each log receipt carries the
+/// `log_id` of the logged value's type, from which a decoder can find how to decode it.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoggedType {
+    pub log_id: u64,
+    #[serde(rename = "type")]
+    pub type_field: String,
+    pub components: Option<Vec<Property>>,
+}
+
+/// The revert codes which each entry point of a program may revert with through the `revert` and
+/// `require` intrinsics, emitted alongside the [JsonABI].
+pub type RevertManifest = Vec<RevertCodes>;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevertCodes {
+    pub name: String,
+    /// The codes which are known at compile time, in ascending order.
+    pub codes: Vec<u64>,
+    /// Whether the function may also revert with a code which is only known at run time, such as
+    /// one computed from its arguments or a revert within inline asm.
+    pub dynamic: bool,
+}
@@ -27,6 +27,9 @@ pub(crate) fn deploy_contract(file_name: &str) -> ContractId {
             print_finalized_asm: false,
             print_intermediate_asm: false,
             print_ir: false,
+            print_peephole_savings: false,
+            disable_asm_optimizations: false,
+            opt_level: 1,
             binary_outfile: None,
             debug_outfile: None,
             offline_mode: false,
@@ -63,6 +66,9 @@ pub(crate) fn runs_on_node(file_name: &str, contract_ids: &[fuel_tx::ContractId]
         print_finalized_asm: false,
         print_intermediate_asm: false,
         print_ir: false,
+        print_peephole_savings: false,
+        disable_asm_optimizations: false,
+        opt_level: 1,
         silent_mode: !verbose,
         pretty_print: false,
         contract: Some(contracts),
@@ -127,6 +133,9 @@ pub(crate) fn compile_to_bytes(file_name: &str) -> Result<Vec<u8>, String> {
         print_finalized_asm: false,
         print_intermediate_asm: false,
         print_ir: false,
+        print_peephole_savings: false,
+        disable_asm_optimizations: false,
+        opt_level: 1,
         binary_outfile: None,
         debug_outfile: None,
         offline_mode: false,
@@ -161,7 +170,55 @@ pub(crate) fn test_json_abi(file_name: &str) -> Result<(), String> {
     Ok(())
 }
 
+pub(crate) fn test_revert_codes(file_name: &str) -> Result<(), String> {
+    test_json_abi_extra(file_name, "revert_codes", "Revert codes")
+}
+
+pub(crate) fn test_logged_types(file_name: &str) -> Result<(), String> {
+    test_json_abi_extra(file_name, "logged_types", "Logged types")
+}
+
+/// Compares one of the optional outputs of `forc json-abi`, named `kind`, with its oracle.
+fn test_json_abi_extra(file_name: &str, kind: &str, description: &str) -> Result<(), String> {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let oracle_path = format!(
+        "{}/src/e2e_vm_tests/test_programs/{}/{}_oracle.json",
+        manifest_dir, file_name, kind
+    );
+    let output_path = format!(
+        "{}/src/e2e_vm_tests/test_programs/{}/{}_output.json",
+        manifest_dir, file_name, kind
+    );
+    let outfile = Some(output_path.clone());
+    let _script = match kind {
+        "revert_codes" => compile_to_json_abi_with(file_name, outfile, None)?,
+        _ => compile_to_json_abi_with(file_name, None, outfile)?,
+    };
+    if fs::metadata(oracle_path.clone()).is_err() {
+        return Err(format!(
+            "{} oracle file does not exist for this test.",
+            description
+        ));
+    }
+    let oracle_contents =
+        fs::read_to_string(oracle_path).expect("Something went wrong reading the file.");
+    let output_contents =
+        fs::read_to_string(output_path).expect("Something went wrong reading the file.");
+    if oracle_contents != output_contents {
+        return Err(format!("Mismatched {} output.", description.to_lowercase()));
+    }
+    Ok(())
+}
+
 fn compile_to_json_abi(file_name: &str) -> Result<Value, String> {
+    compile_to_json_abi_with(file_name, None, None)
+}
+
+fn compile_to_json_abi_with(
+    file_name: &str,
+    revert_codes_outfile: Option<String>,
+    logged_types_outfile: Option<String>,
+) -> Result<Value, String> {
     println!("   ABI gen {}", file_name);
     let manifest_dir = env!("CARGO_MANIFEST_DIR");
     forc_abi_json::build(JsonAbiCommand {
@@ -173,11 +230,18 @@ fn compile_to_json_abi(file_name: &str) -> Result<Value, String> {
             "{}/src/e2e_vm_tests/test_programs/{}/{}",
             manifest_dir, file_name, "json_abi_output.json"
         )),
+        revert_codes_outfile,
+        logged_types_outfile,
         offline_mode: false,
         silent_mode: true,
     })
 }
 
+/// Returns `true` if the tests are compiling via the IR.
+pub(crate) fn use_ir() -> bool {
+    get_test_config_from_env().1
+}
+
 fn get_test_config_from_env() -> (bool, bool) {
     let var_exists = |key| std::env::var(key).map(|_| true).unwrap_or(false);
 
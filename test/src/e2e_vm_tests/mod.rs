@@ -73,6 +73,27 @@ pub fn run(filter_regex: Option<regex::Regex>) {
         // Disabled, pending decision on runtime OOB checks. ("array_dynamic_oob", ProgramState::Revert(1)),
         ("array_generics", ProgramState::Return(1)), // true
         ("match_expressions_structs", ProgramState::Return(4)),
+        ("match_expressions_exhaustive", ProgramState::Return(42)),
+        ("match_expressions_enum_variants", ProgramState::Return(37)),
+        ("for_loops", ProgramState::Return(44)),
+        ("let_destructuring", ProgramState::Return(367)),
+        ("generic_trait_constraints", ProgramState::Return(86)),
+        ("wide_int_ops", ProgramState::Return(255)),
+        ("wide_int_abi", ProgramState::Revert(0)),
+        ("wide_int_loop", ProgramState::Return(7)),
+        ("signed_int_ops", ProgramState::Return(255)),
+        ("signed_int_abi", ProgramState::Revert(0)),
+        ("const_eval", ProgramState::Return(63)),
+        ("attributes", ProgramState::Return(33)),
+        ("revert_intrinsics", ProgramState::Revert(42)),
+        ("log_intrinsic", ProgramState::Return(3)),
+        ("postfix_exprs", ProgramState::Return(1)),
+        ("vec_basics", ProgramState::Return(1)),
+        ("vec_index_oob", ProgramState::Revert(1)),
+        ("type_aliases", ProgramState::Return(1)),
+        ("generic_traits", ProgramState::Return(1)),
+        ("ref_mut_params", ProgramState::Return(1)),
+        ("function_values", ProgramState::Return(1)),
         ("block_height", ProgramState::Return(1)),   // true
         ("b512_test", ProgramState::Return(1)),      // true
         ("block_height", ProgramState::Return(1)),   // true
@@ -83,6 +104,7 @@ pub fn run(filter_regex: Option<regex::Regex>) {
         ("trait_import_with_star", ProgramState::Return(0)),
         ("tuple_desugaring", ProgramState::Return(9)),
         ("multi_item_import", ProgramState::Return(0)), // false
+        ("storage_declaration", ProgramState::Revert(0)),
     ];
 
     let mut number_of_tests_run = positive_project_names.iter().fold(0, |acc, (name, res)| {
@@ -95,6 +117,55 @@ pub fn run(filter_regex: Option<regex::Regex>) {
         }
     });
 
+    // programs which need real function calls, which only the IR code generation supports
+    let ir_positive_project_names = vec![
+        ("recursive_fn_call", ProgramState::Return(55)),
+        ("recursive_fn_call_spills", ProgramState::Return(5670)),
+        ("fn_call_loop", ProgramState::Return(3)),
+    ];
+
+    if crate::e2e_vm_tests::harness::use_ir() {
+        number_of_tests_run += ir_positive_project_names
+            .iter()
+            .fold(0, |acc, (name, res)| {
+                if filter(name) {
+                    assert_eq!(crate::e2e_vm_tests::harness::runs_in_vm(name), *res);
+                    assert_eq!(crate::e2e_vm_tests::harness::test_json_abi(name), Ok(()));
+                    acc + 1
+                } else {
+                    acc
+                }
+            });
+    }
+
+    // programs whose revert code manifest is checked against an oracle
+    let revert_codes_project_names = vec!["revert_intrinsics"];
+    number_of_tests_run += revert_codes_project_names.iter().fold(0, |acc, name| {
+        if filter(name) {
+            assert_eq!(
+                crate::e2e_vm_tests::harness::test_revert_codes(name),
+                Ok(())
+            );
+            acc + 1
+        } else {
+            acc
+        }
+    });
+
+    // programs whose logged types are checked against an oracle
+    let logged_types_project_names = vec!["log_intrinsic"];
+    number_of_tests_run += logged_types_project_names.iter().fold(0, |acc, name| {
+        if filter(name) {
+            assert_eq!(
+                crate::e2e_vm_tests::harness::test_logged_types(name),
+                Ok(())
+            );
+            acc + 1
+        } else {
+            acc
+        }
+    });
+
     // source code that should _not_ compile
     let negative_project_names = vec![
         "recursive_calls",
@@ -116,12 +187,33 @@ pub fn run(filter_regex: Option<regex::Regex>) {
         "name_shadowing",
         "match_expressions_wrong_struct",
         "match_expressions_enums",
+        "match_expressions_non_exhaustive",
+        "break_outside_loop",
+        "refutable_let_pattern",
+        "trait_constraint_not_satisfied",
         "pure_calls_impure",
         "nested_impure",
         "predicate_calls_impure",
         "script_calls_impure",
         "contract_pure_calls_impure",
         "literal_too_large_for_type",
+        "pure_accesses_storage",
+        "storage_in_script",
+        "wide_int_mismatch",
+        "signed_int_mismatch",
+        "const_non_constant",
+        "attribute_misplaced",
+        "revert_bad_code",
+        "tuple_index_out_of_bounds",
+        "missing_associated_type",
+        "ambiguous_trait_method",
+        "no_trait_method_accepts_arguments",
+        "ref_mut_immutable_arg",
+        "function_value_in_mut_let",
+        "function_value_returned",
+        "closure_with_captures_in_let",
+        "function_value_in_struct",
+        "vec_push_immutable",
     ];
     number_of_tests_run += negative_project_names.iter().fold(0, |acc, name| {
         if filter(name) {
@@ -142,9 +234,13 @@ pub fn run(filter_regex: Option<regex::Regex>) {
         ("contract_abi_impl", "contract_call"),
         ("balance_test_contract", "bal_opcode"),
         ("test_fuel_coin_contract", "token_ops_test"),
+        ("storage_access_contract", "call_storage_access"),
     ];
 
     let total_number_of_tests = positive_project_names.len()
+        + ir_positive_project_names.len()
+        + revert_codes_project_names.len()
+        + logged_types_project_names.len()
         + negative_project_names.len()
         + contract_and_project_names.len();
 
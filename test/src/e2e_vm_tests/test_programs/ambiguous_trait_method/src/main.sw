@@ -0,0 +1,27 @@
+script;
+
+struct Amount {
+    value: u64,
+}
+
+trait Zero<T> {
+    fn zero() -> Self;
+}
+
+impl Zero<u64> for Amount {
+    fn zero() -> Amount {
+        Amount { value: 0 }
+    }
+}
+
+impl Zero<bool> for Amount {
+    fn zero() -> Amount {
+        Amount { value: 0 }
+    }
+}
+
+fn main() -> u64 {
+    // both implementations accept no arguments
+    let a = ~Amount::zero();
+    a.value
+}
@@ -0,0 +1,17 @@
+script;
+
+#[test]
+struct Point {
+    x: u64,
+    y: u64,
+}
+
+#[inline(sometimes)]
+fn origin() -> Point {
+    Point { x: 0, y: 0 }
+}
+
+fn main() -> u64 {
+    let p = origin();
+    p.x
+}
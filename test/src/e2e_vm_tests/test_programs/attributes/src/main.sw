@@ -0,0 +1,47 @@
+script;
+
+struct Counter {
+    count: u64,
+}
+
+#[inline(never)]
+impl Counter {
+    fn bumped(self) -> Counter {
+        Counter {
+            count: self.count + 1,
+        }
+    }
+}
+
+#[inline(never)]
+fn sum_of_squares(a: u64, b: u64, c: u64) -> u64 {
+    a * a + b * b + c * c
+}
+
+#[inline(always)]
+fn double(x: u64) -> u64 {
+    x + x
+}
+
+#[deprecated]
+fn legacy_offset() -> u64 {
+    3
+}
+
+#[test]
+fn sum_of_squares_is_positive() {
+    let x = sum_of_squares(1, 2, 3);
+}
+
+#[doc_hidden]
+fn seven() -> u64 {
+    7
+}
+
+fn main() -> u64 {
+    let counter = Counter { count: 1 };
+    let counter = counter.bumped();
+    let counter = counter.bumped();
+    // 14 + 6 + 3 + 7 + 3
+    sum_of_squares(1, 2, 3) + double(counter.count) + legacy_offset() + seven() + counter.count
+}
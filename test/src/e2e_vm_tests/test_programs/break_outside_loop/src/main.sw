@@ -0,0 +1,13 @@
+script;
+
+fn main() -> u64 {
+    let mut total = 0;
+    for i in 0..4 {
+        total = total + i;
+    }
+    if total == 6 {
+        break;
+    };
+    continue;
+    total
+}
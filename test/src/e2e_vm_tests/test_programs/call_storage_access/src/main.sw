@@ -0,0 +1,41 @@
+script;
+use std::{constants::ETH_ID, chain::assert};
+use storage_access_abi::*;
+
+fn main() -> bool {
+  let gas: u64 = 10000;
+  let contract = abi(StorageAccess, 0xde8e2dc32dcbcf6cfc354f2e2ef5a159ad078c3eee1b11de952fcd5bdd0e05ea);
+
+  // A single word.
+  contract.set_word(gas, 0, ETH_ID, 42);
+  assert(contract.get_word(gas, 0, ETH_ID, ()) == 42);
+
+  // A struct which fits in a single storage slot.
+  contract.set_pair(gas, 0, ETH_ID, Pair {
+    a: 7,
+    b: true
+  });
+  let pair = contract.get_pair(gas, 0, ETH_ID, ());
+  assert(pair.a == 7);
+  assert(pair.b);
+
+  // A struct wider than 32 bytes, which is split across storage slots.
+  contract.set_big(gas, 0, ETH_ID, Big {
+    a: 1,
+    b: 2,
+    c: 3,
+    d: 4,
+    e: 0x0000000000000000000000000000000000000000000000000000000000000005
+  });
+  let big = contract.get_big(gas, 0, ETH_ID, ());
+  assert(big.a == 1);
+  assert(big.b == 2);
+  assert(big.c == 3);
+  assert(big.d == 4);
+  assert(big.e == 0x0000000000000000000000000000000000000000000000000000000000000005);
+
+  // The other fields are untouched.
+  assert(contract.get_word(gas, 0, ETH_ID, ()) == 42);
+
+  true
+}
@@ -0,0 +1,9 @@
+script;
+
+use core::ops::*;
+
+fn main() -> u64 {
+    let step = 10;
+    let add_step = |x: u64| -> u64 { x + step };
+    0
+}
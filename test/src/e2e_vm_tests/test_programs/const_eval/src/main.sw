@@ -0,0 +1,45 @@
+script;
+
+struct Limits {
+    low: u64,
+    high: u64,
+}
+
+const SCALE: u64 = 1000;
+const MAX: u64 = 10 * SCALE;
+const HALF: u64 = MAX / 2 - 1;
+const LIMITS: Limits = Limits {
+    low: HALF % 7,
+    high: MAX + 1,
+};
+const STEPS: [u64; 3] = [1, SCALE, LIMITS.high];
+const PAIR: (u64, bool) = (STEPS[1] * 2, MAX > HALF && SCALE != 0);
+const WIDE: u128 = (1u128 << 100) / 3u128;
+const OFFSET: i32 = -7i32 * 3i32;
+
+fn main() -> u64 {
+    const LOCAL: u64 = MAX << 1;
+    let mut result = 0;
+
+    if HALF == 4999 && LOCAL == 20000 {
+        result = result + 1;
+    };
+    if LIMITS.low == 1 && LIMITS.high == 10001 {
+        result = result + 2;
+    };
+    if STEPS[0] + STEPS[2] == 10002 {
+        result = result + 4;
+    };
+    let (doubled, ordered) = PAIR;
+    if doubled == 2000 && ordered {
+        result = result + 8;
+    };
+    if WIDE == 422550200076076467165567735125u128 {
+        result = result + 16;
+    };
+    if OFFSET == -21i32 {
+        result = result + 32;
+    };
+
+    result
+}
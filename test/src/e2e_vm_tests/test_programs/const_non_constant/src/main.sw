@@ -0,0 +1,11 @@
+script;
+
+fn limit() -> u64 {
+    10
+}
+
+const LIMIT: u64 = limit() * 2;
+
+fn main() -> u64 {
+    LIMIT
+}
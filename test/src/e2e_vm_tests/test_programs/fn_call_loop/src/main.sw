@@ -0,0 +1,83 @@
+script;
+
+struct Pair {
+    a: u64,
+    b: u64,
+}
+
+fn stack_pointer() -> u64 {
+    asm(r1) {
+        move r1 sp;
+        r1: u64
+    }
+}
+
+fn less_than(a: u64, b: u64) -> bool {
+    asm(r1: a, r2: b, r3) {
+        lt r3 r1 r2;
+        r3: bool
+    }
+}
+
+fn plus(a: u64, b: u64) -> u64 {
+    asm(r1: a, r2: b, r3) {
+        add r3 r1 r2;
+        r3: u64
+    }
+}
+
+fn minus(a: u64, b: u64) -> u64 {
+    asm(r1: a, r2: b, r3) {
+        sub r3 r1 r2;
+        r3: u64
+    }
+}
+
+// These are recursive so they are called rather than inlined.
+fn fib(n: u64) -> u64 {
+    if less_than(n, 2) {
+        n
+    } else {
+        plus(fib(minus(n, 1)), fib(minus(n, 2)))
+    }
+}
+
+fn fib_pair(n: u64) -> Pair {
+    if less_than(n, 1) {
+        Pair {
+            a: 0,
+            b: 1,
+        }
+    } else {
+        let prev = fib_pair(minus(n, 1));
+        Pair {
+            a: prev.b,
+            b: plus(prev.a, prev.b),
+        }
+    }
+}
+
+fn main() -> u64 {
+    let mut result = 0;
+
+    let sp_before = stack_pointer();
+    let mut total = 0;
+    let mut i = 0;
+    while less_than(i, 100) {
+        total = plus(total, fib(10));
+        let pair = fib_pair(10);
+        total = plus(total, pair.a);
+        i = plus(i, 1);
+    }
+
+    if total == 11000 {
+        result = plus(result, 1);
+    };
+
+    // every call frame is freed when the call returns
+    if stack_pointer() == sp_before {
+        result = plus(result, 2);
+    };
+
+    result
+}
@@ -0,0 +1,47 @@
+script;
+
+struct Point {
+    x: u64,
+    y: u64
+}
+
+fn main() -> u64 {
+    let mut total = 0;
+
+    // a range counts up to but not including its end
+    for i in 0..4 {
+        total = total + i;
+    }
+
+    // `continue` skips the rest of the body and `break` leaves the loop
+    let values = [1, 2, 3, 4, 5, 6];
+    for v in values {
+        if v == 2 {
+            continue;
+        };
+        if v == 5 {
+            break;
+        };
+        total = total + v;
+    }
+
+    // elements too large for a register
+    let points = [Point { x: 10, y: 1 }, Point { x: 20, y: 2 }];
+    for p in points {
+        total = total + p.x - p.y;
+    }
+
+    // `break` only leaves the innermost loop
+    let mut i = 0;
+    while i < 3 {
+        i = i + 1;
+        for j in 0..10 {
+            if j == 1 {
+                break;
+            };
+            total = total + 1;
+        }
+    }
+
+    total
+}
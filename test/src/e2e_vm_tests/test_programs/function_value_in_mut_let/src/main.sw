@@ -0,0 +1,12 @@
+script;
+
+use core::ops::*;
+
+fn double(x: u64) -> u64 {
+    x + x
+}
+
+fn main() -> u64 {
+    let mut f = double;
+    0
+}
@@ -0,0 +1,18 @@
+script;
+
+use core::ops::*;
+
+struct Wrapper<T> {
+    value: T,
+}
+
+fn double(x: u64) -> u64 {
+    x + x
+}
+
+fn main() -> u64 {
+    let wrapped = Wrapper {
+        value: double
+    };
+    0
+}
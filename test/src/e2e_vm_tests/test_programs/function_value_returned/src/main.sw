@@ -0,0 +1,15 @@
+script;
+
+use core::ops::*;
+
+fn double(x: u64) -> u64 {
+    x + x
+}
+
+fn doubler() -> fn(u64) -> u64 {
+    double
+}
+
+fn main() -> u64 {
+    0
+}
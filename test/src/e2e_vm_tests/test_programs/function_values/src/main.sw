@@ -0,0 +1,77 @@
+script;
+
+use core::ops::*;
+
+struct Counter {
+    count: u64,
+}
+
+impl Counter {
+    fn map(self, f: fn(u64) -> u64) -> Counter {
+        Counter {
+            count: f(self.count),
+        }
+    }
+}
+
+fn double(x: u64) -> u64 {
+    x + x
+}
+
+fn apply(f: fn(u64) -> u64, x: u64) -> u64 {
+    f(x)
+}
+
+fn twice(f: fn(u64) -> u64, x: u64) -> u64 {
+    apply(f, apply(f, x))
+}
+
+fn map<T, U>(x: T, f: fn(T) -> U) -> U {
+    f(x)
+}
+
+fn fold(xs: [u64; 4], init: u64, f: fn(u64, u64) -> u64) -> u64 {
+    let mut acc = init;
+    let mut i = 0;
+    while i < 4 {
+        acc = f(acc, xs[i]);
+        i = i + 1;
+    }
+    acc
+}
+
+fn count_if(xs: [u64; 5], pred: fn(u64) -> bool) -> u64 {
+    let mut n = 0;
+    let mut i = 0;
+    while i < 5 {
+        n = if pred(xs[i]) { n + 1 } else { n };
+        i = i + 1;
+    }
+    n
+}
+
+fn is_even(x: u64) -> bool {
+    x % 2 == 0
+}
+
+fn main() -> bool {
+    let doubled = apply(double, 21);
+    let quadrupled = twice(double, 5);
+    let even = map(doubled, is_even);
+    let sum = fold([10, 11, 12, 9], 0, |acc, x| acc + x);
+
+    let limit = 3;
+    let big = count_if([1, 5, 2, 8, 9], |x| limit < x);
+
+    let step = 10;
+    let counter = Counter { count: 2 }.map(|x: u64| -> u64 { x + step }).map(double);
+
+    // a variable bound to a function value is another name for it
+    let times_two = double;
+    let also_times_two = times_two;
+    let increment = |x: u64| -> u64 { x + 1 };
+    let aliased = times_two(4) + apply(also_times_two, 3) + twice(increment, 1);
+
+    doubled == 42 && quadrupled == 20 && even && sum == 42 && big == 3 && counter.count == 24
+        && aliased == 17
+}
@@ -0,0 +1,114 @@
+script;
+
+trait Area {
+    fn area(self) -> u64;
+}
+
+trait Scale {
+    fn scale(self, factor: u64) -> Self;
+}
+
+// the surface area of a cube, which has a method of the same name as `Area`
+trait Cube {
+    fn area(self) -> u64;
+}
+
+struct Square {
+    side: u64
+}
+
+struct Rectangle {
+    width: u64,
+    height: u64
+}
+
+// a type parameter of a struct can be constrained too
+struct Pair<T> where T: Area {
+    first: T,
+    second: T
+}
+
+fn sum(a: u64, b: u64) -> u64 {
+    asm(r1: a, r2: b, r3) {
+        add r3 r1 r2;
+        r3: u64
+    }
+}
+
+fn product(a: u64, b: u64) -> u64 {
+    asm(r1: a, r2: b, r3) {
+        mul r3 r1 r2;
+        r3: u64
+    }
+}
+
+impl Area for Square {
+    fn area(self) -> u64 {
+        product(self.side, self.side)
+    }
+}
+
+impl Area for Rectangle {
+    fn area(self) -> u64 {
+        product(self.width, self.height)
+    }
+}
+
+impl Area for u64 {
+    fn area(self) -> u64 {
+        self
+    }
+}
+
+impl Scale for Square {
+    fn scale(self, by: u64) -> Self {
+        Square {
+            side: product(self.side, by)
+        }
+    }
+}
+
+impl Cube for Square {
+    fn area(self) -> u64 {
+        product(product(self.side, self.side), 6)
+    }
+}
+
+fn double_area<T>(shape: T) -> u64 where T: Area {
+    product(shape.area(), 2)
+}
+
+fn scaled_area<T>(shape: T, factor: u64) -> u64 where T: Area, T: Scale {
+    let scaled = shape.scale(factor);
+    scaled.area()
+}
+
+// `area` is the method of `Cube`, not of `Area`
+fn cube_area<T>(shape: T) -> u64 where T: Cube {
+    shape.area()
+}
+
+// calls a constrained generic function with its own constrained type parameters
+fn total_area<A, B>(a: A, b: B) -> u64 where A: Area, B: Area {
+    sum(double_area(a), double_area(b))
+}
+
+fn main() -> u64 {
+    let a = double_area(Square {
+        side: 3
+    });
+    let b = scaled_area(Square {
+        side: 2
+    }, 3);
+    let c = total_area(Rectangle {
+        width: 2, height: 5
+    }, 1);
+    let pair = Pair {
+        first: 3, second: 4
+    };
+    let d = cube_area(Square {
+        side: 1
+    });
+    // 18 + 36 + 22 + 4 + 6
+    sum(sum(sum(a, b), sum(c, pair.second.area())), d)
+}
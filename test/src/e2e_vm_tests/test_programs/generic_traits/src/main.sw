@@ -0,0 +1,71 @@
+script;
+
+use core::ops::*;
+
+struct Amount {
+    value: u64,
+}
+
+struct Doubled {
+    value: u64,
+}
+
+trait From<T> {
+    fn from(v: T) -> Self;
+}
+
+impl From<u64> for Amount {
+    fn from(v: u64) -> Amount {
+        Amount { value: v }
+    }
+}
+
+impl From<bool> for Amount {
+    fn from(v: bool) -> Amount {
+        if v {
+            Amount { value: 1 }
+        } else {
+            Amount { value: 0 }
+        }
+    }
+}
+
+trait Combine<Rhs> {
+    type Output;
+    fn combine(self, rhs: Rhs) -> Self::Output;
+} {
+    fn combine_twice(self, rhs: Rhs, other: Rhs) -> u64 {
+        2
+    }
+}
+
+impl Combine<u64> for Amount {
+    type Output = Doubled;
+    fn combine(self, rhs: u64) -> Doubled {
+        Doubled { value: (self.value + rhs) * 2 }
+    }
+}
+
+impl Combine<bool> for Amount {
+    type Output = u64;
+    fn combine(self, rhs: bool) -> u64 {
+        if rhs {
+            self.value
+        } else {
+            0
+        }
+    }
+}
+
+fn main() -> bool {
+    let a = ~Amount::from(20);
+    let b = ~Amount::from(true);
+    let doubled = a.combine(1);
+
+    a.value == 20 &&
+    b.value == 1 &&
+    doubled.value == 42 &&
+    a.combine(true) == 20 &&
+    a.combine(false) == 0 &&
+    b.combine_twice(1, 2) == 2
+}
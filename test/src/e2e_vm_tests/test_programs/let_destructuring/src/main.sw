@@ -0,0 +1,38 @@
+script;
+
+struct Point {
+    x: u64,
+    y: u64
+}
+
+struct Line {
+    start: Point,
+    end: Point
+}
+
+fn swap(pair: (u64, u64)) -> (u64, u64) {
+    let (a, b) = pair;
+    (b, a)
+}
+
+fn main() -> u64 {
+    let (a, b) = swap((1, 2));
+
+    // a struct field can be bound to its own name or to another one
+    let Point { x, y: height } = Point { x: 10, y: 20 };
+
+    // patterns nest, and each binding can be made mutable on its own
+    let (mut count, Line { start: Point { x: x0, y: y0 }, end }) = (
+        3,
+        Line {
+            start: Point { x: 100, y: 200 },
+            end: Point { x: 4, y: 5 }
+        }
+    );
+    count = count + 1;
+
+    let (p, (q, r)): (u64, (u64, u64)) = (6, (7, 8));
+
+    // 2 + 1 + 10 + 20 + 4 + 100 + 200 + 4 + 5 + 6 + 7 + 8
+    a + b + x + height + count + x0 + y0 + end.x + end.y + p + q + r
+}
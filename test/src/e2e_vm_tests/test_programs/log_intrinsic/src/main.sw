@@ -0,0 +1,37 @@
+script;
+
+use core::ops::*;
+
+struct Transfer {
+    amount: u64,
+    recipient: b256,
+}
+
+enum Outcome {
+    Accepted: u64,
+    Rejected: bool,
+}
+
+fn log_both<T>(value: T, code: u64) {
+    log(value);
+    log(code);
+}
+
+fn main() -> u64 {
+    let transfer = Transfer {
+        amount: 40,
+        recipient: 0x0000000000000000000000000000000000000000000000000000000000000001,
+    };
+    log(transfer);
+    log(Outcome::Accepted(transfer.amount));
+    log_both((1, true), 2);
+    let mut total = 0;
+    let mut i = 0;
+    while i < 3 {
+        log(i);
+        total = total + i;
+        i = i + 1;
+    }
+    log(total == 3);
+    total
+}
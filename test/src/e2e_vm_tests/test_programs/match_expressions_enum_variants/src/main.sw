@@ -0,0 +1,37 @@
+script;
+
+enum Color {
+    Red: (),
+    Green: (),
+    Blue: (),
+}
+
+enum Switch {
+    On: (),
+    Off: (),
+}
+
+fn color_value(c: Color) -> u64 {
+    // no catch-all arm, as every variant is matched
+    match c {
+        Color::Red => { 1 },
+        Color::Green => { 2 },
+        Color::Blue => { 3 },
+    }
+}
+
+fn switch_value(s: Switch) -> u64 {
+    match s {
+        Switch::On => { 1 },
+        Switch::Off => { 0 },
+    }
+}
+
+fn main() -> u64 {
+    let nested = match (Color::Blue, Switch::Off) {
+        (Color::Red, s) => { 10 },
+        (c, Switch::On) => { 20 },
+        (c, s) => { 30 },
+    };
+    color_value(Color::Red) + color_value(Color::Green) + color_value(Color::Blue) + switch_value(Switch::On) + switch_value(Switch::Off) + nested
+}
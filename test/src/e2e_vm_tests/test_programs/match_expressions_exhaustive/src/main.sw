@@ -0,0 +1,24 @@
+script;
+
+struct Point {
+    x: u64,
+    y: u64
+}
+
+fn main() -> u64 {
+    let p = Point {
+        x: 3,
+        y: 4,
+    };
+
+    // neither match has a catch-all arm, but every value is matched by some arm
+    let a = match p {
+        Point { x: 3, y } => { y },
+        Point { x, y } => { x },
+    };
+    let b = match (1, 2) {
+        (1, y) => { y },
+        (x, y) => { x },
+    };
+    a * 10 + b
+}
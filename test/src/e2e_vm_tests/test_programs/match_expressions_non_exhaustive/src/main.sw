@@ -0,0 +1,29 @@
+script;
+
+enum Color {
+    Red: (),
+    Green: (),
+    Blue: (),
+}
+
+struct Point {
+    x: u64,
+    y: u64
+}
+
+fn main() -> u64 {
+    let c = Color::Green;
+    let a = match c {
+        Color::Red => { 1 },
+        Color::Blue => { 3 },
+    };
+
+    let p = Point {
+        x: 3,
+        y: 4,
+    };
+    match p {
+        Point { x: 3, y } => { y },
+        Point { x, y: 4 } => { x },
+    }
+}
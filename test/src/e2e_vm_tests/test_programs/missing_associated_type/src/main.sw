@@ -0,0 +1,16 @@
+script;
+
+trait Combine<Rhs> {
+    type Output;
+    fn combine(self, rhs: Rhs) -> Self::Output;
+}
+
+impl Combine<u64> for u64 {
+    fn combine(self, rhs: u64) -> u64 {
+        rhs
+    }
+}
+
+fn main() -> u64 {
+    5.combine(1)
+}
@@ -0,0 +1,27 @@
+script;
+
+struct Amount {
+    value: u64,
+}
+
+trait From<T> {
+    fn from(v: T) -> Self;
+}
+
+impl From<u64> for Amount {
+    fn from(v: u64) -> Amount {
+        Amount { value: v }
+    }
+}
+
+impl From<u32> for Amount {
+    fn from(v: u32) -> Amount {
+        Amount { value: 1 }
+    }
+}
+
+fn main() -> u64 {
+    // neither implementation takes a bool
+    let a = ~Amount::from(true);
+    a.value
+}
@@ -0,0 +1,66 @@
+script;
+
+use core::ops::*;
+
+struct Point {
+    x: u64,
+    y: u64,
+}
+
+struct Line {
+    start: Point,
+    end: Point,
+    weights: [u64; 3],
+}
+
+impl Point {
+    fn shifted(self, by: u64) -> Point {
+        Point {
+            x: self.x + by,
+            y: self.y + by,
+        }
+    }
+
+    fn sum(self) -> u64 {
+        self.x + self.y
+    }
+}
+
+impl Line {
+    fn reversed(self) -> Line {
+        Line {
+            start: self.end,
+            end: self.start,
+            weights: self.weights,
+        }
+    }
+}
+
+fn origin() -> Point {
+    Point {
+        x: 0,
+        y: 0,
+    }
+}
+
+fn nested() -> (u64, (bool, u64)) {
+    (1, (true, 2))
+}
+
+fn main() -> bool {
+    let line = Line {
+        start: origin(),
+        end: origin().shifted(3),
+        weights: [4, 5, 6],
+    };
+    let t = nested();
+
+    origin().shifted(1).sum() == 2 &&
+    Point { x: 2, y: 3 }.sum() == 5 &&
+    line.reversed().start.shifted(1).x == 4 &&
+    line.reversed().weights[2] == 6 &&
+    (line.end).sum() == 6 &&
+    t.1.1 == 2 &&
+    nested().0 == 1 &&
+    nested().1.0
+}
@@ -0,0 +1,16 @@
+contract;
+
+abi StorageTest {
+  fn get_supply(gas: u64, coins: u64, asset_id: b256, input: ()) -> u64;
+}
+
+storage {
+  supply: u64 = 0,
+}
+
+impl StorageTest for Contract {
+  // this should fail because a pure function cannot access storage
+  fn get_supply(gas: u64, coins: u64, asset_id: b256, input: ()) -> u64 {
+    storage.supply
+  }
+}
@@ -0,0 +1,34 @@
+script;
+
+fn less_than(a: u64, b: u64) -> bool {
+    asm(r1: a, r2: b, r3) {
+        lt r3 r1 r2;
+        r3: bool
+    }
+}
+
+fn plus(a: u64, b: u64) -> u64 {
+    asm(r1: a, r2: b, r3) {
+        add r3 r1 r2;
+        r3: u64
+    }
+}
+
+fn minus(a: u64, b: u64) -> u64 {
+    asm(r1: a, r2: b, r3) {
+        sub r3 r1 r2;
+        r3: u64
+    }
+}
+
+fn fib(n: u64) -> u64 {
+    if less_than(n, 2) {
+        n
+    } else {
+        plus(fib(minus(n, 1)), fib(minus(n, 2)))
+    }
+}
+
+fn main() -> u64 {
+    fib(10)
+}
@@ -0,0 +1,157 @@
+script;
+
+fn less_than(a: u64, b: u64) -> bool {
+    asm(r1: a, r2: b, r3) {
+        lt r3 r1 r2;
+        r3: bool
+    }
+}
+
+fn plus(a: u64, b: u64) -> u64 {
+    asm(r1: a, r2: b, r3) {
+        add r3 r1 r2;
+        r3: u64
+    }
+}
+
+fn minus(a: u64, b: u64) -> u64 {
+    asm(r1: a, r2: b, r3) {
+        sub r3 r1 r2;
+        r3: u64
+    }
+}
+
+// Each of the `a` values is live across the recursive call, which is more than there are
+// registers, so some are spilled.  They must survive the recursive call spilling its own.
+fn sum(n: u64) -> u64 {
+    if less_than(n, 1) {
+        0
+    } else {
+        let a0 = plus(n, 0);
+        let a1 = plus(n, 1);
+        let a2 = plus(n, 2);
+        let a3 = plus(n, 3);
+        let a4 = plus(n, 4);
+        let a5 = plus(n, 5);
+        let a6 = plus(n, 6);
+        let a7 = plus(n, 7);
+        let a8 = plus(n, 8);
+        let a9 = plus(n, 9);
+        let a10 = plus(n, 10);
+        let a11 = plus(n, 11);
+        let a12 = plus(n, 12);
+        let a13 = plus(n, 13);
+        let a14 = plus(n, 14);
+        let a15 = plus(n, 15);
+        let a16 = plus(n, 16);
+        let a17 = plus(n, 17);
+        let a18 = plus(n, 18);
+        let a19 = plus(n, 19);
+        let a20 = plus(n, 20);
+        let a21 = plus(n, 21);
+        let a22 = plus(n, 22);
+        let a23 = plus(n, 23);
+        let a24 = plus(n, 24);
+        let a25 = plus(n, 25);
+        let a26 = plus(n, 26);
+        let a27 = plus(n, 27);
+        let a28 = plus(n, 28);
+        let a29 = plus(n, 29);
+        let a30 = plus(n, 30);
+        let a31 = plus(n, 31);
+        let a32 = plus(n, 32);
+        let a33 = plus(n, 33);
+        let a34 = plus(n, 34);
+        let a35 = plus(n, 35);
+        let a36 = plus(n, 36);
+        let a37 = plus(n, 37);
+        let a38 = plus(n, 38);
+        let a39 = plus(n, 39);
+        let a40 = plus(n, 40);
+        let a41 = plus(n, 41);
+        let a42 = plus(n, 42);
+        let a43 = plus(n, 43);
+        let a44 = plus(n, 44);
+        let a45 = plus(n, 45);
+        let a46 = plus(n, 46);
+        let a47 = plus(n, 47);
+        let a48 = plus(n, 48);
+        let a49 = plus(n, 49);
+        let a50 = plus(n, 50);
+        let a51 = plus(n, 51);
+        let a52 = plus(n, 52);
+        let a53 = plus(n, 53);
+        let a54 = plus(n, 54);
+        let a55 = plus(n, 55);
+        let a56 = plus(n, 56);
+        let a57 = plus(n, 57);
+        let a58 = plus(n, 58);
+        let a59 = plus(n, 59);
+        let r = sum(minus(n, 1));
+        let s0 = plus(r, a0);
+        let s1 = plus(s0, a1);
+        let s2 = plus(s1, a2);
+        let s3 = plus(s2, a3);
+        let s4 = plus(s3, a4);
+        let s5 = plus(s4, a5);
+        let s6 = plus(s5, a6);
+        let s7 = plus(s6, a7);
+        let s8 = plus(s7, a8);
+        let s9 = plus(s8, a9);
+        let s10 = plus(s9, a10);
+        let s11 = plus(s10, a11);
+        let s12 = plus(s11, a12);
+        let s13 = plus(s12, a13);
+        let s14 = plus(s13, a14);
+        let s15 = plus(s14, a15);
+        let s16 = plus(s15, a16);
+        let s17 = plus(s16, a17);
+        let s18 = plus(s17, a18);
+        let s19 = plus(s18, a19);
+        let s20 = plus(s19, a20);
+        let s21 = plus(s20, a21);
+        let s22 = plus(s21, a22);
+        let s23 = plus(s22, a23);
+        let s24 = plus(s23, a24);
+        let s25 = plus(s24, a25);
+        let s26 = plus(s25, a26);
+        let s27 = plus(s26, a27);
+        let s28 = plus(s27, a28);
+        let s29 = plus(s28, a29);
+        let s30 = plus(s29, a30);
+        let s31 = plus(s30, a31);
+        let s32 = plus(s31, a32);
+        let s33 = plus(s32, a33);
+        let s34 = plus(s33, a34);
+        let s35 = plus(s34, a35);
+        let s36 = plus(s35, a36);
+        let s37 = plus(s36, a37);
+        let s38 = plus(s37, a38);
+        let s39 = plus(s38, a39);
+        let s40 = plus(s39, a40);
+        let s41 = plus(s40, a41);
+        let s42 = plus(s41, a42);
+        let s43 = plus(s42, a43);
+        let s44 = plus(s43, a44);
+        let s45 = plus(s44, a45);
+        let s46 = plus(s45, a46);
+        let s47 = plus(s46, a47);
+        let s48 = plus(s47, a48);
+        let s49 = plus(s48, a49);
+        let s50 = plus(s49, a50);
+        let s51 = plus(s50, a51);
+        let s52 = plus(s51, a52);
+        let s53 = plus(s52, a53);
+        let s54 = plus(s53, a54);
+        let s55 = plus(s54, a55);
+        let s56 = plus(s55, a56);
+        let s57 = plus(s56, a57);
+        let s58 = plus(s57, a58);
+        let s59 = plus(s58, a59);
+        s59
+    }
+}
+
+fn main() -> u64 {
+    sum(3)
+}
@@ -0,0 +1,13 @@
+script;
+
+use core::ops::*;
+
+fn increment(ref mut x: u64) {
+    x = x + 1;
+}
+
+fn main() -> u64 {
+    let x = 41;
+    increment(x);
+    x
+}
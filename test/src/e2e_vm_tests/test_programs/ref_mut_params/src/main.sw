@@ -0,0 +1,54 @@
+script;
+
+use core::ops::*;
+
+struct Counter {
+    count: u64,
+    step: u64,
+}
+
+impl Counter {
+    fn tick(&mut self) {
+        self.count = self.count + self.step;
+    }
+}
+
+fn increment(ref mut x: u64) {
+    x = x + 1;
+}
+
+fn increment_twice(ref mut x: u64) {
+    increment(x);
+    increment(x);
+}
+
+fn double_step(ref mut counter: Counter) {
+    counter.step = counter.step * 2;
+}
+
+fn reset(ref mut counter: Counter) {
+    counter = Counter {
+        count: 0,
+        step: 1,
+    };
+}
+
+fn main() -> bool {
+    let mut x = 40;
+    increment(x);
+    increment_twice(x);
+
+    let mut counter = Counter {
+        count: 5,
+        step: 3,
+    };
+    counter.tick();
+    double_step(counter);
+    counter.tick();
+    let ticked = counter.count == 14;
+
+    reset(counter);
+    counter.tick();
+
+    x == 43 && ticked && counter.count == 1 && counter.step == 1
+}
@@ -0,0 +1,13 @@
+script;
+
+enum Option {
+    Some: u64,
+    None: ()
+}
+
+fn main() -> u64 {
+    // only patterns which always match can be used in a `let`
+    let (a, 0) = (1, 0);
+    let (b, Option::Some(c)) = (2, Option::Some(3));
+    0
+}
@@ -0,0 +1,6 @@
+script;
+
+fn main() -> u64 {
+    require(true, false);
+    revert()
+}
@@ -0,0 +1,39 @@
+script;
+
+use core::ops::*;
+
+struct Account {
+    balance: u64,
+}
+
+impl Account {
+    fn withdraw(self, amount: u64) -> Account {
+        require(amount < self.balance + 1, 1);
+        Account {
+            balance: self.balance - amount,
+        }
+    }
+}
+
+fn unwrap_or_revert<T>(value: T, present: bool) -> T {
+    if present { value } else { revert(2) }
+}
+
+fn expect_even(n: u64) -> u64 {
+    if n % 2 == 0 { n } else { revert(3) }
+}
+
+fn fail(code: u64) -> u64 {
+    revert(code);
+}
+
+fn main() -> u64 {
+    let account = Account {
+        balance: 10,
+    };
+    let account = account.withdraw(4);
+    let balance = unwrap_or_revert(account.balance, true);
+    let even = expect_even(balance);
+    require(even == 6, 4);
+    fail(even * 7)
+}
@@ -0,0 +1,11 @@
+contract;
+
+abi SignedInts {
+    fn offset(gas: u64, coin: u64, asset_id: b256, input: i32) -> i64;
+}
+
+impl SignedInts for Contract {
+    fn offset(gas: u64, coin: u64, asset_id: b256, input: i32) -> i64 {
+        -1i64
+    }
+}
@@ -0,0 +1,8 @@
+script;
+
+fn main() -> i64 {
+    let a: i64 = 1;
+    let b: u64 = 2;
+    // signed and unsigned integers don't mix
+    a + b
+}
@@ -0,0 +1,59 @@
+script;
+
+struct Reading {
+    sensor: u64,
+    delta: i32,
+}
+
+fn midpoint(a: i64, b: i64) -> i64 {
+    (a + b) / 2
+}
+
+fn adjusted(reading: Reading, offset: i32) -> Reading {
+    Reading {
+        sensor: reading.sensor,
+        delta: reading.delta - offset,
+    }
+}
+
+fn main() -> u64 {
+    let mut result = 0;
+
+    // division rounds towards zero, and the remainder has the sign of the dividend
+    if -7i64 / 2 == -3i64 && -7i64 % 2 == -1i64 {
+        result = result + 1;
+    };
+    if midpoint(-10i64, 4i64) == -3i64 {
+        result = result + 2;
+    };
+
+    // comparisons
+    if -5i32 < 3i32 && 3i32 > -5i32 && -1i8 <= -1i8 {
+        result = result + 4;
+    };
+    if (-1i16 >= 0i16) == false {
+        result = result + 8;
+    };
+
+    // shifts are arithmetic for signed integers and logical for unsigned integers
+    if -16i64 >> 2 == -4i64 && 3i64 << 2 == 12i64 {
+        result = result + 16;
+    };
+    if (1 << 63) >> 63 == 1 {
+        result = result + 32;
+    };
+
+    // numeric literals take the type of the other operand
+    let x: i32 = 5;
+    if x - 10 == -5i32 {
+        result = result + 64;
+    };
+
+    // as struct fields and function arguments
+    let reading = adjusted(Reading { sensor: 1, delta: -3i32 }, 4);
+    if reading.delta == -7i32 {
+        result = result + 128;
+    };
+
+    result
+}
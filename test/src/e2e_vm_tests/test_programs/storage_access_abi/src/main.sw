@@ -0,0 +1,23 @@
+library storage_access_abi;
+
+pub struct Pair {
+  a: u64,
+  b: bool
+}
+
+pub struct Big {
+  a: u64,
+  b: u64,
+  c: u64,
+  d: u64,
+  e: b256
+}
+
+abi StorageAccess {
+  impure fn set_word(gas: u64, coins: u64, asset_id: b256, value: u64);
+  impure fn get_word(gas: u64, coins: u64, asset_id: b256, input: ()) -> u64;
+  impure fn set_pair(gas: u64, coins: u64, asset_id: b256, value: Pair);
+  impure fn get_pair(gas: u64, coins: u64, asset_id: b256, input: ()) -> Pair;
+  impure fn set_big(gas: u64, coins: u64, asset_id: b256, value: Big);
+  impure fn get_big(gas: u64, coins: u64, asset_id: b256, input: ()) -> Big;
+}
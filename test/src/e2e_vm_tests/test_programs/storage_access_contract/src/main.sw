@@ -0,0 +1,43 @@
+contract;
+use storage_access_abi::*;
+
+storage {
+  word: u64 = 0,
+  pair: Pair = Pair {
+    a: 0,
+    b: false
+  },
+  big: Big = Big {
+    a: 0,
+    b: 0,
+    c: 0,
+    d: 0,
+    e: 0x0000000000000000000000000000000000000000000000000000000000000000
+  },
+}
+
+impl StorageAccess for Contract {
+  impure fn set_word(gas: u64, coins: u64, asset_id: b256, value: u64) {
+    storage.word = value;
+  }
+
+  impure fn get_word(gas: u64, coins: u64, asset_id: b256, input: ()) -> u64 {
+    storage.word
+  }
+
+  impure fn set_pair(gas: u64, coins: u64, asset_id: b256, value: Pair) {
+    storage.pair = value;
+  }
+
+  impure fn get_pair(gas: u64, coins: u64, asset_id: b256, input: ()) -> Pair {
+    storage.pair
+  }
+
+  impure fn set_big(gas: u64, coins: u64, asset_id: b256, value: Big) {
+    storage.big = value;
+  }
+
+  impure fn get_big(gas: u64, coins: u64, asset_id: b256, input: ()) -> Big {
+    storage.big
+  }
+}
@@ -1,9 +1,34 @@
 contract;
+
+abi StorageTest {
+  impure fn set_supply(gas: u64, coins: u64, asset_id: b256, supply: u64);
+  impure fn get_supply(gas: u64, coins: u64, asset_id: b256, input: ()) -> u64;
+  impure fn set_owner(gas: u64, coins: u64, asset_id: b256, owner: b256);
+  impure fn get_owner(gas: u64, coins: u64, asset_id: b256, input: ()) -> b256;
+}
+
 storage {
   supply: u64 = 0,
   demand: u64 = 0,
   name: str[4] = "test",
+  owner: b256 = 0x0000000000000000000000000000000000000000000000000000000000000000,
 }
-fn main() {
-    
+
+impl StorageTest for Contract {
+  impure fn set_supply(gas: u64, coins: u64, asset_id: b256, supply: u64) {
+    storage.supply = supply;
+    storage.demand = storage.supply + 1;
+  }
+
+  impure fn get_supply(gas: u64, coins: u64, asset_id: b256, input: ()) -> u64 {
+    storage.supply
+  }
+
+  impure fn set_owner(gas: u64, coins: u64, asset_id: b256, owner: b256) {
+    storage.owner = owner;
+  }
+
+  impure fn get_owner(gas: u64, coins: u64, asset_id: b256, input: ()) -> b256 {
+    storage.owner
+  }
 }
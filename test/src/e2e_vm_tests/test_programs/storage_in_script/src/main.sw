@@ -0,0 +1,9 @@
+script;
+
+// this should fail because only contracts have storage
+storage {
+  supply: u64 = 0,
+}
+
+fn main() {
+}
@@ -0,0 +1,37 @@
+script;
+
+trait Area {
+    fn area(self) -> u64;
+}
+
+struct Square {
+    side: u64
+}
+
+struct Circle {
+    radius: u64
+}
+
+impl Area for Square {
+    fn area(self) -> u64 {
+        asm(r1: self.side, r2) {
+            mul r2 r1 r1;
+            r2: u64
+        }
+    }
+}
+
+fn double_area<T>(shape: T) -> u64 where T: Area {
+    let area = shape.area();
+    asm(r1: area, r2) {
+        add r2 r1 r1;
+        r2: u64
+    }
+}
+
+fn main() -> u64 {
+    // there is no `impl Area for Circle`
+    double_area(Circle {
+        radius: 1
+    })
+}
@@ -0,0 +1,9 @@
+script;
+
+fn pair() -> (u64, bool) {
+    (1, true)
+}
+
+fn main() -> u64 {
+    pair().2
+}
@@ -0,0 +1,39 @@
+script;
+
+use core::ops::*;
+
+struct Point {
+    x: u64,
+    y: u64,
+}
+
+type Amount = u64;
+type Pair<T> = (T, T);
+type Line = Pair<Point>;
+type Amounts = [Amount; 3];
+
+fn total(a: Amount, b: Amount) -> Amount {
+    a + b
+}
+
+fn swap<T>(pair: Pair<T>) -> Pair<T> {
+    (pair.1, pair.0)
+}
+
+fn main() -> bool {
+    let a: Amount = 40;
+    let flags: Pair<bool> = (false, true);
+    let line: Line = (Point { x: 1, y: 2 }, Point { x: 3, y: 4 });
+    let amounts: Amounts = [1, 2, 3];
+
+    type Local = (Amount, bool);
+    let local: Local = (7, true);
+
+    total(a, 2) == 42 &&
+    swap(flags).0 &&
+    swap(line).0.x == 3 &&
+    line.1.y == 4 &&
+    amounts[2] == 3 &&
+    local.0 == 7 &&
+    local.1
+}
@@ -0,0 +1,46 @@
+script;
+
+use core::ops::*;
+
+struct Point {
+    x: u64,
+    y: u64,
+}
+
+fn filled<T>(elem: T, len: u64) -> Vec<T> {
+    let mut v = Vec::new();
+    let mut i = 0;
+    while i < len {
+        v.push(elem);
+        i = i + 1;
+    }
+    v
+}
+
+fn main() -> bool {
+    let mut v = Vec::new();
+    let mut i = 0;
+    while i < 20 {
+        v.push(i * 2);
+        i = i + 1;
+    }
+    v.set(3, 100);
+
+    let mut points = Vec::with_capacity(1);
+    points.push(Point { x: 1, y: 2 });
+    points.push(Point { x: 3, y: 4 });
+    let mut p = points[1];
+    p.x = 10;
+
+    let flags = filled(true, 3);
+
+    v.len() == 20 &&
+    v.capacity() > 19 &&
+    v[19] == 38 &&
+    v.get(3) == 100 &&
+    points[1].x == 3 &&
+    points[0].y == 2 &&
+    p.x == 10 &&
+    flags.len() == 3 &&
+    flags[2]
+}
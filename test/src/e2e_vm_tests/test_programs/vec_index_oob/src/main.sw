@@ -0,0 +1,8 @@
+script;
+
+fn main() -> u64 {
+    let mut v = Vec::new();
+    v.push(1);
+    v.push(2);
+    v[2]
+}
@@ -0,0 +1,10 @@
+script;
+
+fn main() -> u64 {
+    let v = Vec::new();
+    v.push(1);
+    v.set(0, 2);
+    let pair = (Vec::new(), 0);
+    pair.0.push(3);
+    v[0]
+}
@@ -0,0 +1,11 @@
+contract;
+
+abi WideInts {
+    fn scale(gas: u64, coin: u64, asset_id: b256, input: u128) -> u256;
+}
+
+impl WideInts for Contract {
+    fn scale(gas: u64, coin: u64, asset_id: b256, input: u128) -> u256 {
+        1000000000000000000000000000000000000000u256
+    }
+}
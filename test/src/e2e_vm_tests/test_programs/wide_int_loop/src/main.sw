@@ -0,0 +1,41 @@
+script;
+
+fn stack_pointer() -> u64 {
+    asm(r1) {
+        move r1 sp;
+        r1: u64
+    }
+}
+
+fn main() -> u64 {
+    let mut result = 0;
+
+    let sp_before = stack_pointer();
+    let mut power = 1u128;
+    let mut i = 0;
+    while i < 50 {
+        let product = power * 3;
+        power = product / 3 + product % 3 + power * 2;
+        i = i + 1;
+    }
+    let sp_after = stack_pointer();
+
+    if power == 717897987691852588770249u128 {
+        result = result + 1;
+    };
+
+    // the ops in the loop reuse the memory for their results
+    if sp_after == sp_before {
+        result = result + 2;
+    };
+
+    let mut shifted = 1u256;
+    while shifted < 340282366920938463463374607431768211456u256 {
+        shifted = shifted << 1;
+    }
+    if stack_pointer() == sp_before {
+        result = result + 4;
+    };
+
+    result
+}
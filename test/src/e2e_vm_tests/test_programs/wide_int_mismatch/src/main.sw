@@ -0,0 +1,8 @@
+script;
+
+fn main() -> u128 {
+    let a: u128 = 1;
+    let b: u64 = 2;
+    // a u64 is not implicitly widened to a u128
+    a + b
+}
@@ -0,0 +1,60 @@
+script;
+
+struct Balance {
+    owner: u64,
+    amount: u128,
+}
+
+fn max_u64() -> u128 {
+    18446744073709551615u128
+}
+
+fn scaled(balance: Balance, factor: u128) -> Balance {
+    Balance {
+        owner: balance.owner,
+        amount: balance.amount * factor,
+    }
+}
+
+fn main() -> u64 {
+    let mut result = 0;
+
+    // carries and borrows between words
+    let two_64 = max_u64() + 1;
+    if two_64 == 18446744073709551616u128 {
+        result = result + 1;
+    };
+    if two_64 - 1 == max_u64() {
+        result = result + 2;
+    };
+
+    // products which don't fit in 64 bits
+    if max_u64() * max_u64() == 340282366920938463426481119284349108225u128 {
+        result = result + 4;
+    };
+    let big: u256 = 340282366920938463463374607431768211455u256;
+    if big * big == 115792089237316195423570985008687907852589419931798687112530834793049593217025u256 {
+        result = result + 8;
+    };
+
+    // division and remainder
+    if 1000000000000000000000u128 / 7 == 142857142857142857142u128 {
+        result = result + 16;
+    };
+    if 1000000000000000000000u256 % 7 == 6 {
+        result = result + 32;
+    };
+
+    // comparisons
+    if max_u64() < two_64 && two_64 >= max_u64() && big > 1 {
+        result = result + 64;
+    };
+
+    // as struct fields and function arguments
+    let balance = scaled(Balance { owner: 1, amount: two_64 }, 3);
+    if balance.amount == 55340232221128654848u128 {
+        result = result + 128;
+    };
+
+    result
+}